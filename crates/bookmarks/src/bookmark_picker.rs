@@ -0,0 +1,247 @@
+use std::sync::Arc;
+
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{DismissEvent, Entity, EventEmitter, Focusable, Render, Subscription, Task, WeakEntity};
+use picker::{Picker, PickerDelegate};
+use project::bookmark_store::BookmarkEntry;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::paths::PathExt as _;
+use workspace::{ModalView, Workspace};
+
+use crate::Toggle;
+
+pub struct BookmarkPicker {
+    picker: Entity<Picker<BookmarkPickerDelegate>>,
+    _subscription: Subscription,
+}
+
+impl BookmarkPicker {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        _: &Toggle,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let bookmarks = workspace
+            .project()
+            .read(cx)
+            .bookmark_store()
+            .read(cx)
+            .bookmarks(cx);
+        let workspace_handle = workspace.weak_handle();
+        workspace.toggle_modal(window, cx, |window, cx| {
+            Self::new(workspace_handle, bookmarks, window, cx)
+        });
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        bookmarks: Vec<BookmarkEntry>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let candidates = bookmarks
+            .iter()
+            .enumerate()
+            .map(|(id, bookmark)| StringMatchCandidate::new(id, &bookmark_title(bookmark)))
+            .collect();
+        let picker = cx.new(|cx| {
+            Picker::uniform_list(
+                BookmarkPickerDelegate {
+                    workspace,
+                    bookmarks,
+                    candidates,
+                    matches: Vec::new(),
+                    selected_index: 0,
+                },
+                window,
+                cx,
+            )
+            .modal(true)
+        });
+        Self {
+            _subscription: cx.subscribe(&picker, |_, _, _, cx| {
+                cx.emit(DismissEvent);
+            }),
+            picker,
+        }
+    }
+}
+
+impl Render for BookmarkPicker {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("BookmarkPicker")
+            .track_focus(&self.focus_handle(cx))
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarkPicker {}
+
+impl Focusable for BookmarkPicker {
+    fn focus_handle(&self, cx: &App) -> gpui::FocusHandle {
+        self.picker.read(cx).focus_handle(cx)
+    }
+}
+
+impl ModalView for BookmarkPicker {}
+
+/// The text bookmarks are searched by: their label, or the text of their line, followed by their location.
+fn bookmark_title(bookmark: &BookmarkEntry) -> String {
+    let text = bookmark
+        .label
+        .as_deref()
+        .unwrap_or_else(|| bookmark.line_text.trim());
+    let file_name = bookmark
+        .path
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy())
+        .unwrap_or_default();
+    format!("{text} {file_name}:{}", bookmark.row + 1)
+}
+
+pub struct BookmarkPickerDelegate {
+    workspace: WeakEntity<Workspace>,
+    bookmarks: Vec<BookmarkEntry>,
+    candidates: Arc<[StringMatchCandidate]>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for BookmarkPickerDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Find a bookmark".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        if self.bookmarks.is_empty() {
+            Some("No bookmarks have been added in this project yet".into())
+        } else {
+            Some("No matches".into())
+        }
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let candidates = self.candidates.clone();
+        cx.spawn(async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        score: 0.,
+                        positions: Vec::new(),
+                        string: candidate.string.clone(),
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates[..],
+                    &query,
+                    true,
+                    true,
+                    1000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+
+            this.update(cx, |this, _| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                if delegate.matches.is_empty() {
+                    delegate.selected_index = 0;
+                } else {
+                    delegate.selected_index =
+                        delegate.selected_index.min(delegate.matches.len() - 1);
+                }
+            })
+            .ok();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(bookmark) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|hit| self.bookmarks.get(hit.candidate_id))
+        else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                crate::open_bookmark(workspace, bookmark, window, cx);
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let bookmark = self.bookmarks.get(hit.candidate_id)?;
+
+        let location = format!("{}:{}", bookmark.path.compact().display(), bookmark.row + 1);
+        let subtitle = match &bookmark.list {
+            Some(list) => format!("{list} · {location}"),
+            None => location,
+        };
+
+        Some(
+            ListItem::new(SharedString::from(format!("bookmark-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(IconName::Bookmark).color(Color::Muted))
+                .child(
+                    v_flex()
+                        .items_start()
+                        .child(HighlightedLabel::new(
+                            hit.string.clone(),
+                            hit.positions.clone(),
+                        ))
+                        .child(
+                            Label::new(subtitle)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use editor::{Editor, actions::SelectAll};
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render};
+use itertools::Itertools as _;
+use project::bookmark_store::{BookmarkId, BookmarkStore};
+use ui::prelude::*;
+use workspace::{ModalView, Workspace};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum BookmarkField {
+    Label,
+    List,
+}
+
+/// A modal for editing the label of a bookmark, or the list it belongs to.
+pub(crate) struct BookmarkPrompt {
+    editor: Entity<Editor>,
+    bookmark_store: Entity<BookmarkStore>,
+    bookmark_id: BookmarkId,
+    field: BookmarkField,
+    existing_lists: Vec<Arc<str>>,
+}
+
+impl ModalView for BookmarkPrompt {}
+
+impl Focusable for BookmarkPrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarkPrompt {}
+
+impl BookmarkPrompt {
+    pub(crate) fn show(
+        workspace: &mut Workspace,
+        bookmark_id: BookmarkId,
+        field: BookmarkField,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let bookmark_store = workspace.project().read(cx).bookmark_store();
+        workspace.toggle_modal(window, cx, |window, cx| {
+            Self::new(bookmark_store, bookmark_id, field, window, cx)
+        });
+    }
+
+    fn new(
+        bookmark_store: Entity<BookmarkStore>,
+        bookmark_id: BookmarkId,
+        field: BookmarkField,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let (current_text, existing_lists) = {
+            let bookmark_store = bookmark_store.read(cx);
+            let current_text = bookmark_store
+                .bookmarks(cx)
+                .into_iter()
+                .find(|bookmark| bookmark.id == bookmark_id)
+                .and_then(|bookmark| match field {
+                    BookmarkField::Label => bookmark.label,
+                    BookmarkField::List => bookmark.list,
+                });
+            (current_text, bookmark_store.lists())
+        };
+
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(
+                match field {
+                    BookmarkField::Label => "Bookmark label",
+                    BookmarkField::List => "Bookmark list",
+                },
+                window,
+                cx,
+            );
+            if let Some(current_text) = current_text {
+                editor.set_text(current_text, window, cx);
+                editor.select_all(&SelectAll, window, cx);
+            }
+            editor
+        });
+
+        Self {
+            editor,
+            bookmark_store,
+            bookmark_id,
+            field,
+            existing_lists,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let text: Arc<str> = self.editor.read(cx).text(cx).trim().into();
+        let text = (!text.is_empty()).then_some(text);
+        let bookmark_id = self.bookmark_id;
+        let field = self.field;
+        self.bookmark_store
+            .update(cx, |bookmark_store, cx| match field {
+                BookmarkField::Label => bookmark_store.set_label(bookmark_id, text, cx),
+                BookmarkField::List => bookmark_store.set_list(bookmark_id, text, cx),
+            });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for BookmarkPrompt {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let help_text: SharedString = match self.field {
+            BookmarkField::Label => "Leave empty to show the line's text instead".into(),
+            BookmarkField::List if self.existing_lists.is_empty() => {
+                "Leave empty to remove the bookmark from its list".into()
+            }
+            BookmarkField::List => {
+                format!("Existing lists: {}", self.existing_lists.iter().join(", ")).into()
+            }
+        };
+
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("BookmarkPrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .child(Label::new(help_text).color(Color::Muted)),
+            )
+    }
+}
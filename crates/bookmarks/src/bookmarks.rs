@@ -0,0 +1,310 @@
+mod bookmark_picker;
+mod bookmark_prompt;
+pub mod bookmarks_panel;
+mod bookmarks_panel_settings;
+mod persistence;
+
+use std::path::Path;
+
+use editor::{Editor, SelectionEffects, ToPoint as _, scroll::Autoscroll};
+use gpui::{App, AppContext as _, Context, Entity, Window, actions};
+use language::{Buffer, Point};
+use project::bookmark_store::{BookmarkEntry, BookmarkStore, BookmarkStoreEvent};
+use util::ResultExt as _;
+use workspace::{OpenOptions, OpenVisible, Workspace};
+
+pub use bookmark_picker::BookmarkPicker;
+pub use bookmarks_panel::BookmarksPanel;
+pub use bookmarks_panel_settings::BookmarksPanelSettings;
+
+use crate::{bookmark_prompt::BookmarkField, persistence::DB};
+
+actions!(
+    bookmarks,
+    [
+        /// Opens a picker listing all bookmarks of the project.
+        Toggle,
+        /// Moves to the next bookmark, continuing into the following files.
+        GoToNextBookmark,
+        /// Moves to the previous bookmark, continuing into the preceding files.
+        GoToPreviousBookmark,
+        /// Edits the label of the bookmark on the current line, adding one if needed.
+        EditBookmarkLabel,
+        /// Moves the bookmark on the current line to another list, adding one if needed.
+        MoveBookmarkToList,
+        /// Removes all bookmarks of the project.
+        ClearBookmarks,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, cx| {
+        workspace
+            .register_action(BookmarkPicker::toggle)
+            .register_action(|workspace, _: &GoToNextBookmark, window, cx| {
+                go_to_adjacent_bookmark(workspace, Direction::Next, window, cx);
+            })
+            .register_action(|workspace, _: &GoToPreviousBookmark, window, cx| {
+                go_to_adjacent_bookmark(workspace, Direction::Previous, window, cx);
+            })
+            .register_action(|workspace, _: &EditBookmarkLabel, window, cx| {
+                edit_bookmark_at_cursor(workspace, BookmarkField::Label, window, cx);
+            })
+            .register_action(|workspace, _: &MoveBookmarkToList, window, cx| {
+                edit_bookmark_at_cursor(workspace, BookmarkField::List, window, cx);
+            })
+            .register_action(|workspace, _: &ClearBookmarks, _, cx| {
+                workspace
+                    .project()
+                    .read(cx)
+                    .bookmark_store()
+                    .update(cx, |bookmark_store, cx| bookmark_store.clear_bookmarks(cx));
+            })
+            .register_action(|workspace, _: &bookmarks_panel::ToggleFocus, window, cx| {
+                workspace.toggle_panel_focus::<BookmarksPanel>(window, cx);
+            });
+        persist_bookmarks(workspace, cx);
+    })
+    .detach();
+}
+
+/// Restores the bookmarks saved for the workspace, and saves them again whenever they change.
+///
+/// Saving only starts once the restore has completed, so that changes made in the meantime don't
+/// overwrite the saved bookmarks before they've been read.
+fn persist_bookmarks(workspace: &mut Workspace, cx: &mut Context<Workspace>) {
+    let bookmark_store = workspace.project().read(cx).bookmark_store();
+    let Some(workspace_id) = workspace.database_id() else {
+        save_bookmarks_on_change(&bookmark_store, cx);
+        return;
+    };
+    cx.spawn(async move |workspace, cx| {
+        let bookmarks = cx
+            .background_spawn(async move { DB.bookmarks(workspace_id) })
+            .await?;
+        bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.with_serialized_bookmarks(bookmarks, cx)
+        })?;
+        workspace.update(cx, |workspace, cx| {
+            // Bookmarks added while restoring were merged with the restored ones.
+            save_bookmarks(workspace, &bookmark_store, cx);
+            save_bookmarks_on_change(&bookmark_store, cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn save_bookmarks_on_change(bookmark_store: &Entity<BookmarkStore>, cx: &mut Context<Workspace>) {
+    cx.subscribe(
+        bookmark_store,
+        |workspace, bookmark_store, _: &BookmarkStoreEvent, cx| {
+            save_bookmarks(workspace, &bookmark_store, cx);
+        },
+    )
+    .detach();
+}
+
+fn save_bookmarks(
+    workspace: &Workspace,
+    bookmark_store: &Entity<BookmarkStore>,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(workspace_id) = workspace.database_id() else {
+        return;
+    };
+    let bookmarks = bookmark_store.read(cx).serialized_bookmarks(cx);
+    cx.background_spawn(async move { DB.save_bookmarks(workspace_id, bookmarks).await })
+        .detach_and_log_err(cx);
+}
+
+/// Returns the buffer and row of the newest cursor in the active editor.
+fn cursor_position(workspace: &Workspace, cx: &App) -> Option<(Entity<Buffer>, u32)> {
+    let editor = workspace.active_item_as::<Editor>(cx)?;
+    let editor = editor.read(cx);
+    let multi_buffer = editor.buffer().read(cx);
+    let head = editor.selections.newest_anchor().head();
+    let (buffer, point, _) =
+        multi_buffer.point_to_buffer_point(head.to_point(&multi_buffer.snapshot(cx)), cx)?;
+    Some((buffer, point.row))
+}
+
+fn edit_bookmark_at_cursor(
+    workspace: &mut Workspace,
+    field: BookmarkField,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some((buffer, row)) = cursor_position(workspace, cx) else {
+        return;
+    };
+    let bookmark_store = workspace.project().read(cx).bookmark_store();
+    if bookmark_store
+        .read(cx)
+        .bookmark_at_row(&buffer, row, cx)
+        .is_none()
+    {
+        bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.toggle_bookmark(&buffer, row, cx)
+        });
+    }
+    if let Some(id) = bookmark_store.read(cx).bookmark_at_row(&buffer, row, cx) {
+        bookmark_prompt::BookmarkPrompt::show(workspace, id, field, window, cx);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Direction {
+    Next,
+    Previous,
+}
+
+fn go_to_adjacent_bookmark(
+    workspace: &mut Workspace,
+    direction: Direction,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let bookmark_store = workspace.project().read(cx).bookmark_store();
+    let current = cursor_position(workspace, cx)
+        .and_then(|(buffer, row)| Some((BookmarkStore::abs_path_from_buffer(&buffer, cx)?, row)));
+    let bookmarks = bookmark_store.read(cx).bookmarks(cx);
+    let positions = bookmarks
+        .iter()
+        .map(|bookmark| (bookmark.path.as_ref(), bookmark.row))
+        .collect::<Vec<_>>();
+    let current = current.as_ref().map(|(path, row)| (path.as_ref(), *row));
+    if let Some(bookmark) = adjacent_bookmark_index(&positions, current, direction)
+        .and_then(|index| bookmarks.get(index))
+    {
+        open_bookmark(workspace, bookmark, window, cx);
+    }
+}
+
+/// Returns the index of the bookmark following (or preceding) the given position, wrapping around at the
+/// ends of the project. `positions` must be sorted by path and row.
+fn adjacent_bookmark_index(
+    positions: &[(&Path, u32)],
+    current: Option<(&Path, u32)>,
+    direction: Direction,
+) -> Option<usize> {
+    if positions.is_empty() {
+        return None;
+    }
+    let last = positions.len() - 1;
+    let Some(current) = current else {
+        return Some(match direction {
+            Direction::Next => 0,
+            Direction::Previous => last,
+        });
+    };
+    match direction {
+        Direction::Next => Some(
+            positions
+                .iter()
+                .position(|&position| position > current)
+                .unwrap_or(0),
+        ),
+        Direction::Previous => Some(
+            positions
+                .iter()
+                .rposition(|&position| position < current)
+                .unwrap_or(last),
+        ),
+    }
+}
+
+/// Opens the file of a bookmark and moves the cursor to the bookmarked line.
+pub(crate) fn open_bookmark(
+    workspace: &mut Workspace,
+    bookmark: &BookmarkEntry,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let bookmark_store = workspace.project().read(cx).bookmark_store();
+    let id = bookmark.id;
+    let row = bookmark.row;
+    let open_task = workspace.open_abs_path(
+        bookmark.path.to_path_buf(),
+        OpenOptions {
+            visible: Some(OpenVisible::All),
+            focus: Some(true),
+            ..Default::default()
+        },
+        window,
+        cx,
+    );
+    cx.spawn_in(window, async move |_, cx| {
+        let item = open_task.await?;
+        cx.update(|window, cx| {
+            let Some(editor) = item.act_as::<Editor>(cx) else {
+                return;
+            };
+            // Opening the file may have re-anchored the bookmark to another line.
+            let row = bookmark_store
+                .read(cx)
+                .bookmarks(cx)
+                .into_iter()
+                .find(|bookmark| bookmark.id == id)
+                .map_or(row, |bookmark| bookmark.row);
+            editor.update(cx, |editor, cx| {
+                let point = Point::new(row, 0);
+                editor.change_selections(
+                    SelectionEffects::scroll(Autoscroll::center()),
+                    window,
+                    cx,
+                    |selections| selections.select_ranges([point..point]),
+                );
+            });
+        })
+        .log_err();
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{Direction, adjacent_bookmark_index};
+
+    #[test]
+    fn test_adjacent_bookmark_index() {
+        let a = Path::new("/project/a.rs");
+        let b = Path::new("/project/b.rs");
+        let c = Path::new("/project/c.rs");
+        let positions = [(a, 3), (a, 10), (b, 0)];
+
+        assert_eq!(
+            adjacent_bookmark_index(&positions, Some((a, 3)), Direction::Next),
+            Some(1)
+        );
+        assert_eq!(
+            adjacent_bookmark_index(&positions, Some((a, 5)), Direction::Previous),
+            Some(0)
+        );
+        // Navigation continues into the following and preceding files.
+        assert_eq!(
+            adjacent_bookmark_index(&positions, Some((a, 12)), Direction::Next),
+            Some(2)
+        );
+        assert_eq!(
+            adjacent_bookmark_index(&positions, Some((b, 0)), Direction::Previous),
+            Some(1)
+        );
+        // And wraps around at the ends of the project, including from files without bookmarks.
+        assert_eq!(
+            adjacent_bookmark_index(&positions, Some((c, 0)), Direction::Next),
+            Some(0)
+        );
+        assert_eq!(
+            adjacent_bookmark_index(&positions, Some((a, 0)), Direction::Previous),
+            Some(2)
+        );
+        assert_eq!(
+            adjacent_bookmark_index(&positions, None, Direction::Previous),
+            Some(2)
+        );
+        assert_eq!(adjacent_bookmark_index(&[], None, Direction::Next), None);
+    }
+}
@@ -0,0 +1,267 @@
+use std::sync::Arc;
+
+use collections::BTreeMap;
+use fs::Fs;
+use gpui::{
+    Action, App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable, Pixels,
+    Render, Subscription, WeakEntity, Window, actions,
+};
+use project::bookmark_store::{BookmarkEntry, BookmarkStore};
+use settings::Settings as _;
+use ui::{ListHeader, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::paths::PathExt as _;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+use crate::{
+    BookmarksPanelSettings,
+    bookmark_prompt::{BookmarkField, BookmarkPrompt},
+};
+
+const BOOKMARKS_PANEL_KEY: &str = "BookmarksPanel";
+
+actions!(
+    bookmarks_panel,
+    [
+        /// Toggles focus on the bookmarks panel.
+        ToggleFocus
+    ]
+);
+
+/// Lists the bookmarks of the project, grouped by list.
+pub struct BookmarksPanel {
+    workspace: WeakEntity<Workspace>,
+    bookmark_store: Entity<BookmarkStore>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    _subscription: Subscription,
+}
+
+impl BookmarksPanel {
+    pub async fn load(
+        workspace: WeakEntity<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<Entity<Self>> {
+        workspace.update_in(&mut cx, |workspace, _, cx| {
+            let bookmark_store = workspace.project().read(cx).bookmark_store();
+            let fs = workspace.app_state().fs.clone();
+            let workspace = workspace.weak_handle();
+            cx.new(|cx| Self {
+                _subscription: cx.observe(&bookmark_store, |_, _, cx| cx.notify()),
+                workspace,
+                bookmark_store,
+                fs,
+                focus_handle: cx.focus_handle(),
+                width: None,
+            })
+        })
+    }
+
+    fn open_bookmark(&self, bookmark: &BookmarkEntry, window: &mut Window, cx: &mut App) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                crate::open_bookmark(workspace, bookmark, window, cx);
+            })
+            .ok();
+    }
+
+    fn edit_bookmark(
+        &self,
+        bookmark: &BookmarkEntry,
+        field: BookmarkField,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                BookmarkPrompt::show(workspace, bookmark.id, field, window, cx);
+            })
+            .ok();
+    }
+
+    fn render_bookmark(
+        &self,
+        ix: usize,
+        bookmark: BookmarkEntry,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let title: SharedString = match &bookmark.label {
+            Some(label) => label.to_string().into(),
+            None => bookmark.line_text.trim().to_string().into(),
+        };
+        let location = format!("{}:{}", bookmark.path.compact().display(), bookmark.row + 1);
+        let bookmark = Arc::new(bookmark);
+
+        ListItem::new(("bookmark", ix))
+            .spacing(ListItemSpacing::Sparse)
+            .start_slot(Icon::new(IconName::Bookmark).color(Color::Muted))
+            .child(
+                v_flex()
+                    .overflow_hidden()
+                    .child(Label::new(title).truncate())
+                    .child(
+                        Label::new(location)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .truncate(),
+                    ),
+            )
+            .on_click(cx.listener({
+                let bookmark = bookmark.clone();
+                move |this, _, window, cx| this.open_bookmark(&bookmark, window, cx)
+            }))
+            .end_hover_slot(
+                h_flex()
+                    .gap_0p5()
+                    .child(
+                        IconButton::new(("rename-bookmark", ix), IconName::Pencil)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Edit Label"))
+                            .on_click(cx.listener({
+                                let bookmark = bookmark.clone();
+                                move |this, _, window, cx| {
+                                    this.edit_bookmark(&bookmark, BookmarkField::Label, window, cx)
+                                }
+                            })),
+                    )
+                    .child(
+                        IconButton::new(("move-bookmark", ix), IconName::ListTree)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Move to List"))
+                            .on_click(cx.listener({
+                                let bookmark = bookmark.clone();
+                                move |this, _, window, cx| {
+                                    this.edit_bookmark(&bookmark, BookmarkField::List, window, cx)
+                                }
+                            })),
+                    )
+                    .child(
+                        IconButton::new(("remove-bookmark", ix), IconName::Trash)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Remove Bookmark"))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.bookmark_store.update(cx, |bookmark_store, cx| {
+                                    bookmark_store.remove_bookmark(bookmark.id, cx)
+                                });
+                            })),
+                    ),
+            )
+    }
+}
+
+impl Render for BookmarksPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut lists = BTreeMap::<Option<Arc<str>>, Vec<BookmarkEntry>>::default();
+        for bookmark in self.bookmark_store.read(cx).bookmarks(cx) {
+            lists
+                .entry(bookmark.list.clone())
+                .or_default()
+                .push(bookmark);
+        }
+
+        let mut panel = v_flex()
+            .id("bookmarks-panel")
+            .key_context("BookmarksPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_1()
+            .overflow_y_scroll();
+        if lists.is_empty() {
+            return panel.child(
+                v_flex()
+                    .size_full()
+                    .items_center()
+                    .justify_center()
+                    .gap_1()
+                    .child(Label::new("No bookmarks yet"))
+                    .child(
+                        Label::new("Add one from the gutter's context menu")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            );
+        }
+
+        let mut ix = 0;
+        for (list, bookmarks) in lists {
+            let header = match list {
+                Some(list) => SharedString::from(list.to_string()),
+                None => SharedString::from("Unsorted"),
+            };
+            panel = panel.child(ListHeader::new(header));
+            for bookmark in bookmarks {
+                panel = panel.child(self.render_bookmark(ix, bookmark, cx));
+                ix += 1;
+            }
+        }
+        panel
+    }
+}
+
+impl Focusable for BookmarksPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for BookmarksPanel {}
+
+impl Panel for BookmarksPanel {
+    fn persistent_name() -> &'static str {
+        "BookmarksPanel"
+    }
+
+    fn panel_key() -> &'static str {
+        BOOKMARKS_PANEL_KEY
+    }
+
+    fn position(&self, _window: &Window, cx: &App) -> DockPosition {
+        BookmarksPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(
+        &mut self,
+        position: DockPosition,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings.bookmarks_panel.get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn size(&self, _window: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| BookmarksPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _window: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _window: &Window, cx: &App) -> Option<IconName> {
+        BookmarksPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::Bookmark)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _cx: &App) -> Option<&'static str> {
+        Some("Bookmarks Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        7
+    }
+}
@@ -0,0 +1,131 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::Result;
+use collections::BTreeMap;
+use db::{
+    sqlez::{domain::Domain, thread_safe_connection::ThreadSafeConnection},
+    sqlez_macros::sql,
+};
+use project::bookmark_store::SerializedBookmark;
+use workspace::{WorkspaceDb, WorkspaceId};
+
+pub struct BookmarksDb(ThreadSafeConnection);
+
+impl Domain for BookmarksDb {
+    const NAME: &str = stringify!(BookmarksDb);
+
+    const MIGRATIONS: &[&str] = &[sql!(
+        CREATE TABLE bookmarks (
+            workspace_id INTEGER NOT NULL,
+            path BLOB NOT NULL,
+            row_index INTEGER NOT NULL,
+            label TEXT,
+            list TEXT,
+            line_text TEXT NOT NULL,
+            PRIMARY KEY(workspace_id, path, row_index),
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+        ) STRICT;
+    )];
+}
+
+db::static_connection!(DB, BookmarksDb, [WorkspaceDb]);
+
+impl BookmarksDb {
+    /// Replaces all bookmarks stored for the workspace.
+    pub(crate) async fn save_bookmarks(
+        &self,
+        workspace_id: WorkspaceId,
+        bookmarks: BTreeMap<Arc<Path>, Vec<SerializedBookmark>>,
+    ) -> Result<()> {
+        log::debug!("Saving bookmarks of {} files", bookmarks.len());
+
+        self.write(move |conn| {
+            conn.with_savepoint("save_bookmarks", || {
+                conn.exec_bound(sql!(
+                    DELETE FROM bookmarks WHERE workspace_id = ?
+                ))?(workspace_id)?;
+                let mut insert = conn.exec_bound(sql!(
+                    INSERT OR REPLACE INTO bookmarks
+                        (workspace_id, path, row_index, label, list, line_text)
+                    VALUES
+                        (?, ?, ?, ?, ?, ?)
+                ))?;
+                for (path, bookmarks) in bookmarks {
+                    for bookmark in bookmarks {
+                        insert((
+                            workspace_id,
+                            path.clone(),
+                            bookmark.row,
+                            bookmark.label,
+                            bookmark.list,
+                            bookmark.line_text,
+                        ))?;
+                    }
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+
+    pub(crate) fn bookmarks(
+        &self,
+        workspace_id: WorkspaceId,
+    ) -> Result<BTreeMap<Arc<Path>, Vec<SerializedBookmark>>> {
+        let rows: Vec<(Arc<Path>, u32, Option<Arc<str>>, Option<Arc<str>>, Arc<str>)> =
+            self.select_bound(sql!(
+                SELECT path, row_index, label, list, line_text FROM bookmarks
+                    WHERE workspace_id = ?
+                    ORDER BY path, row_index
+            ))?(workspace_id)?;
+
+        let mut bookmarks = BTreeMap::<Arc<Path>, Vec<SerializedBookmark>>::default();
+        for (path, row, label, list, line_text) in rows {
+            bookmarks.entry(path).or_default().push(SerializedBookmark {
+                row,
+                label,
+                list,
+                line_text,
+            });
+        }
+        Ok(bookmarks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_save_and_load_bookmarks() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        let path: Arc<Path> = Path::new("/root/src/main.rs").into();
+        let bookmark =
+            |row, label: Option<&str>, list: Option<&str>, line_text: &str| SerializedBookmark {
+                row,
+                label: label.map(Into::into),
+                list: list.map(Into::into),
+                line_text: line_text.into(),
+            };
+
+        let bookmarks = BTreeMap::from_iter([(
+            path.clone(),
+            vec![
+                bookmark(0, None, None, "fn main() {"),
+                bookmark(4, Some("entry point"), Some("startup"), "    run();"),
+            ],
+        )]);
+        DB.save_bookmarks(workspace_id, bookmarks.clone())
+            .await
+            .unwrap();
+        assert_eq!(DB.bookmarks(workspace_id).unwrap(), bookmarks);
+
+        // Saving again replaces the previous bookmarks, rather than merging with them.
+        let bookmarks = BTreeMap::from_iter([(path, vec![bookmark(2, None, None, "}")])]);
+        DB.save_bookmarks(workspace_id, bookmarks.clone())
+            .await
+            .unwrap();
+        assert_eq!(DB.bookmarks(workspace_id).unwrap(), bookmarks);
+    }
+}
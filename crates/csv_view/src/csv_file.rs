@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use gpui::{App, AppContext as _, Context, Entity, Subscription, Task};
+use language::{Buffer, BufferEvent};
+use project::{File, Project, ProjectEntryId, ProjectPath};
+
+/// Extensions of files that are opened as tables rather than as text.
+const TABLE_FILE_EXTENSIONS: &[&str] = &["csv", "psv", "tsv"];
+
+/// A delimited file, opened as a buffer so that edits made in the table view go through the
+/// buffer's undo history, collaboration and saving.
+pub struct CsvFile {
+    buffer: Entity<Buffer>,
+    /// Mirrors the buffer's dirty state, as [`project::ProjectItem::is_dirty`] can't read it.
+    is_dirty: bool,
+    _subscription: Subscription,
+}
+
+impl CsvFile {
+    pub fn open(
+        project: &Entity<Project>,
+        project_path: &ProjectPath,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let open_buffer = project.update(cx, |project, cx| {
+            project.open_buffer(project_path.clone(), cx)
+        });
+        cx.spawn(async move |cx| {
+            let buffer = open_buffer.await?;
+            cx.new(|cx| Self::new(buffer, cx))
+        })
+    }
+
+    pub fn new(buffer: Entity<Buffer>, cx: &mut Context<Self>) -> Self {
+        let subscription = cx.subscribe(&buffer, |this, buffer, event, cx| {
+            if let BufferEvent::DirtyChanged | BufferEvent::Saved | BufferEvent::Reloaded = event {
+                this.is_dirty = buffer.read(cx).is_dirty();
+            }
+        });
+        Self {
+            is_dirty: buffer.read(cx).is_dirty(),
+            buffer,
+            _subscription: subscription,
+        }
+    }
+
+    pub fn buffer(&self) -> &Entity<Buffer> {
+        &self.buffer
+    }
+
+    pub fn file<'a>(&self, cx: &'a App) -> Option<&'a File> {
+        File::from_dyn(self.buffer.read(cx).file())
+    }
+
+    pub fn abs_path(&self, cx: &App) -> Option<PathBuf> {
+        let file = self.file(cx)?;
+        Some(file.worktree.read(cx).absolutize(&file.path))
+    }
+
+    /// The lowercase extension of the file, which determines its delimiter.
+    pub fn extension(&self, cx: &App) -> Option<String> {
+        let file = self.file(cx)?;
+        Some(file.path.extension()?.to_ascii_lowercase())
+    }
+}
+
+impl project::ProjectItem for CsvFile {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        let extension = path.path.extension()?.to_ascii_lowercase();
+        if !TABLE_FILE_EXTENSIONS.contains(&extension.as_str()) {
+            return None;
+        }
+        Some(Self::open(project, path, cx))
+    }
+
+    fn entry_id(&self, cx: &App) -> Option<ProjectEntryId> {
+        project::ProjectItem::entry_id(self.buffer.read(cx), cx)
+    }
+
+    fn project_path(&self, cx: &App) -> Option<ProjectPath> {
+        project::ProjectItem::project_path(self.buffer.read(cx), cx)
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.is_dirty
+    }
+}
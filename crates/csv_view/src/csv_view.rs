@@ -0,0 +1,1105 @@
+mod csv_file;
+mod table;
+
+use std::{ops::Range, path::Path, sync::Arc};
+
+use anyhow::Result;
+use collections::HashSet;
+use editor::{Editor, EditorElement, EditorEvent, EditorStyle};
+use file_icons::FileIcons;
+use gpui::{
+    AnyElement, App, Bounds, Context, Entity, EventEmitter, FocusHandle, Focusable, FontWeight,
+    ListHorizontalSizingBehavior, MouseButton, MouseDownEvent, MouseMoveEvent, Pixels, Point,
+    ScrollStrategy, Subscription, Task, TextStyle, UniformListDecoration, UniformListScrollHandle,
+    WeakEntity, Window, actions, px, transparent_black, uniform_list,
+};
+use language::{BufferEvent, DiskState};
+use project::{Project, ProjectPath};
+use settings::Settings as _;
+use theme::ThemeSettings;
+use ui::{Divider, ScrollAxes, Scrollbars, Tooltip, WithScrollbar as _, prelude::*};
+use util::{ResultExt as _, paths::PathExt as _};
+use workspace::{
+    ItemSettings, Pane, SaveIntent, Workspace, WorkspaceId,
+    invalid_item_view::InvalidItemView,
+    item::{Item, ItemEvent, ItemHandle, ProjectItem, SaveOptions},
+};
+use zed_actions::csv_view::OpenAsTable;
+
+use crate::table::{
+    ColumnSort, Dialect, SortDirection, Table, column_character_counts, column_name,
+    detect_dialect, display_rows, parse_table, range_after_edits,
+};
+
+pub use crate::csv_file::CsvFile;
+
+actions!(
+    csv_view,
+    [
+        /// Selects the cell above the selected one.
+        MoveUp,
+        /// Selects the cell below the selected one.
+        MoveDown,
+        /// Selects the cell left of the selected one.
+        MoveLeft,
+        /// Selects the cell right of the selected one.
+        MoveRight,
+        /// Focuses the editor of the selected cell.
+        EditCell,
+        /// Focuses the filter bar.
+        Filter,
+        /// Reopens the table as text.
+        OpenAsText,
+    ]
+);
+
+/// How many rows are looked at to choose the initial width of each column.
+const COLUMN_WIDTH_SAMPLE_ROWS: usize = 100;
+const MIN_COLUMN_CHARACTERS: usize = 3;
+const MAX_COLUMN_CHARACTERS: usize = 40;
+const MIN_COLUMN_WIDTH: Pixels = px(24.);
+/// The horizontal padding and border of a cell.
+const CELL_PADDING: Pixels = px(9.);
+
+pub fn init(cx: &mut App) {
+    workspace::register_project_item::<CsvView>(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(open_as_table);
+        workspace.register_action(open_as_text);
+    })
+    .detach();
+}
+
+/// Reopens the file of the active item as a table, in place of that item.
+fn open_as_table(
+    workspace: &mut Workspace,
+    _: &OpenAsTable,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let pane = workspace.active_pane().clone();
+    let Some(active_item) = pane.read(cx).active_item() else {
+        return;
+    };
+    if active_item.downcast::<CsvView>().is_some() {
+        return;
+    }
+    let Some(project_path) = active_item.project_path(cx) else {
+        return;
+    };
+
+    let open_csv_file = CsvFile::open(&project, &project_path, cx);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let csv_file = open_csv_file.await?;
+        replace_item(workspace, pane, active_item, cx, |window, cx| {
+            Box::new(cx.new(|cx| CsvView::new(csv_file, project, window, cx)))
+        })
+        .await
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Reopens the file of the active table as text, in place of the table.
+fn open_as_text(
+    workspace: &mut Workspace,
+    _: &OpenAsText,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let pane = workspace.active_pane().clone();
+    let Some(active_item) = pane.read(cx).active_item() else {
+        return;
+    };
+    let Some(csv_view) = active_item.downcast::<CsvView>() else {
+        return;
+    };
+    let buffer = csv_view.read(cx).csv_file.read(cx).buffer().clone();
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        replace_item(workspace, pane, active_item, cx, |window, cx| {
+            Box::new(cx.new(|cx| Editor::for_buffer(buffer, Some(project), window, cx)))
+        })
+        .await
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Replaces an item with another one showing the same file, at the same index of the pane.
+async fn replace_item(
+    workspace: WeakEntity<Workspace>,
+    pane: Entity<Pane>,
+    item: Box<dyn ItemHandle>,
+    cx: &mut gpui::AsyncWindowContext,
+    build_item: impl FnOnce(&mut Window, &mut Context<Workspace>) -> Box<dyn ItemHandle>,
+) -> Result<()> {
+    // The pane deduplicates items by their project entry, so the item showing the same file has
+    // to be closed first. Both items share the file's buffer, so its unsaved edits are kept.
+    let (item_index, close_item) = pane.update_in(cx, |pane, window, cx| {
+        let item_index = pane.index_for_item(item.as_ref());
+        let close_item = pane.close_item_by_id(item.item_id(), SaveIntent::Skip, window, cx);
+        (item_index, close_item)
+    })?;
+    close_item.await?;
+    workspace.update_in(cx, |workspace, window, cx| {
+        if pane.read(cx).index_for_item(item.as_ref()).is_some() {
+            return;
+        }
+        let new_item = build_item(window, cx);
+        workspace.add_item(pane, new_item, item_index, true, true, window, cx);
+    })
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct CellPosition {
+    /// The index of the cell's row in the table, where the header row is 0.
+    row: usize,
+    column: usize,
+}
+
+/// A column whose width is being dragged.
+#[derive(Clone, Copy, Debug)]
+struct ColumnResize {
+    column: usize,
+    start_position: Pixels,
+    start_width: Pixels,
+}
+
+/// Shows a delimited file as a grid of cells below a sticky header row, and writes edited cells
+/// back to the file's buffer.
+///
+/// Sorting and filtering only change which rows are shown and in what order, leaving the file as
+/// it is.
+pub struct CsvView {
+    csv_file: Entity<CsvFile>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    cell_editor: Entity<Editor>,
+    filter_editor: Entity<Editor>,
+    table: Arc<Table>,
+    /// The version of the buffer that the table was parsed from, or `None` before the first parse.
+    table_version: Option<clock::Global>,
+    /// The indices of the shown rows, starting with the header row.
+    display_rows: Vec<usize>,
+    sort: Option<ColumnSort>,
+    column_widths: Vec<Pixels>,
+    column_resize: Option<ColumnResize>,
+    /// The width of a character in the buffer font, measured when the view is rendered.
+    character_width: Pixels,
+    selected_cell: CellPosition,
+    status_message: Option<SharedString>,
+    _parse_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl CsvView {
+    pub fn new(
+        csv_file: Entity<CsvFile>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let cell_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Cell Value", window, cx);
+            editor
+        });
+        let filter_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Filter Rows", window, cx);
+            editor
+        });
+        let buffer = csv_file.read(cx).buffer().clone();
+        let subscriptions = vec![
+            cx.subscribe_in(&buffer, window, Self::on_buffer_event),
+            cx.subscribe(&filter_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    this.update_display_rows(cx);
+                }
+            }),
+        ];
+
+        let mut this = Self {
+            csv_file,
+            project,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            cell_editor,
+            filter_editor,
+            table: Arc::default(),
+            table_version: None,
+            display_rows: Vec::new(),
+            sort: None,
+            column_widths: Vec::new(),
+            column_resize: None,
+            character_width: px(0.),
+            selected_cell: CellPosition::default(),
+            status_message: None,
+            _parse_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.parse_in_background(window, cx);
+        this
+    }
+
+    pub fn csv_file(&self) -> &Entity<CsvFile> {
+        &self.csv_file
+    }
+
+    fn on_buffer_event(
+        &mut self,
+        _: &Entity<language::Buffer>,
+        event: &BufferEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            BufferEvent::Edited => {
+                self.parse_in_background(window, cx);
+                cx.emit(ItemEvent::Edit);
+            }
+            BufferEvent::Reloaded => {
+                self.parse_in_background(window, cx);
+                cx.emit(ItemEvent::UpdateTab);
+            }
+            BufferEvent::DirtyChanged
+            | BufferEvent::Saved
+            | BufferEvent::FileHandleChanged
+            | BufferEvent::CapabilityChanged => cx.emit(ItemEvent::UpdateTab),
+            _ => {}
+        }
+    }
+
+    /// The dialect of the file, detected when it's first parsed.
+    fn dialect(&self) -> Option<Dialect> {
+        self.table_version.is_some().then_some(self.table.dialect)
+    }
+
+    fn is_table_outdated(&self, cx: &App) -> bool {
+        let buffer = self.csv_file.read(cx).buffer().read(cx);
+        self.table_version.as_ref() != Some(&buffer.version())
+    }
+
+    fn parse_in_background(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.is_table_outdated(cx) {
+            return;
+        }
+        let snapshot = self.csv_file.read(cx).buffer().read(cx).text_snapshot();
+        let dialect = self.dialect();
+        let extension = self.csv_file.read(cx).extension(cx);
+        self._parse_task = cx.spawn_in(window, async move |this, cx| {
+            let version = snapshot.version().clone();
+            let table = cx
+                .background_spawn(async move {
+                    let text = snapshot.text();
+                    let dialect =
+                        dialect.unwrap_or_else(|| detect_dialect(&text, extension.as_deref()));
+                    parse_table(&text, dialect)
+                })
+                .await;
+            this.update_in(cx, |this, window, cx| {
+                this.set_table(table, version, window, cx)
+            })
+            .log_err();
+        });
+    }
+
+    fn set_table(
+        &mut self,
+        table: Table,
+        version: clock::Global,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.table = Arc::new(table);
+        self.table_version = Some(version);
+        self.selected_cell.row = self
+            .selected_cell
+            .row
+            .min(self.table.rows.len().saturating_sub(1));
+        self.selected_cell.column = self
+            .selected_cell
+            .column
+            .min(self.table.column_count.saturating_sub(1));
+        if self
+            .sort
+            .is_some_and(|sort| sort.column >= self.table.column_count)
+        {
+            self.sort = None;
+        }
+        self.update_display_rows(cx);
+        if !self.cell_editor.focus_handle(cx).is_focused(window) {
+            self.reset_cell_editor(window, cx);
+        }
+    }
+
+    fn update_display_rows(&mut self, cx: &mut Context<Self>) {
+        let filter = self.filter_editor.read(cx).text(cx);
+        self.display_rows = if self.table.rows.is_empty() {
+            Vec::new()
+        } else {
+            std::iter::once(0)
+                .chain(display_rows(&self.table, filter.trim(), self.sort))
+                .collect()
+        };
+        cx.notify();
+    }
+
+    fn reset_cell_editor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let value = self
+            .table
+            .value(self.selected_cell.row, self.selected_cell.column)
+            .to_string();
+        let read_only = self.csv_file.read(cx).buffer().read(cx).read_only();
+        self.cell_editor.update(cx, |editor, cx| {
+            if editor.text(cx) != value {
+                editor.set_text(value, window, cx);
+            }
+            editor.set_read_only(read_only);
+        });
+    }
+
+    fn select_cell(&mut self, cell: CellPosition, window: &mut Window, cx: &mut Context<Self>) {
+        self.selected_cell = cell;
+        self.status_message = None;
+        self.reset_cell_editor(window, cx);
+        if let Some(index) = self.display_rows.iter().position(|row| *row == cell.row) {
+            self.scroll_handle
+                .scroll_to_item(index, ScrollStrategy::Nearest);
+        }
+        self.scroll_to_column(cell.column);
+        cx.notify();
+    }
+
+    fn scroll_to_column(&self, column: usize) {
+        let Some(width) = self.column_widths.get(column) else {
+            return;
+        };
+        let left = self.column_widths[..column]
+            .iter()
+            .fold(self.gutter_width(), |left, width| left + *width);
+        let right = left + *width;
+        let base_handle = self.scroll_handle.0.borrow().base_handle.clone();
+        let viewport_width = base_handle.bounds().size.width;
+        let mut offset = base_handle.offset();
+        if left < -offset.x {
+            offset.x = -left;
+        } else if right > viewport_width - offset.x {
+            offset.x = (viewport_width - right).min(px(0.));
+        }
+        base_handle.set_offset(offset);
+    }
+
+    fn move_selection(
+        &mut self,
+        row_delta: isize,
+        column_delta: isize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.display_rows.is_empty() {
+            return;
+        }
+        let index = self
+            .display_rows
+            .iter()
+            .position(|row| *row == self.selected_cell.row)
+            .unwrap_or(0);
+        let Some(row) = index
+            .checked_add_signed(row_delta)
+            .and_then(|index| self.display_rows.get(index))
+        else {
+            return;
+        };
+        let Some(column) = self
+            .selected_cell
+            .column
+            .checked_add_signed(column_delta)
+            .filter(|column| *column < self.table.column_count)
+        else {
+            return;
+        };
+        self.select_cell(CellPosition { row: *row, column }, window, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, window: &mut Window, cx: &mut Context<Self>) {
+        self.move_selection(-1, 0, window, cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, window: &mut Window, cx: &mut Context<Self>) {
+        self.move_selection(1, 0, window, cx);
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, window: &mut Window, cx: &mut Context<Self>) {
+        self.move_selection(0, -1, window, cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, window: &mut Window, cx: &mut Context<Self>) {
+        self.move_selection(0, 1, window, cx);
+    }
+
+    fn edit_cell(&mut self, _: &EditCell, window: &mut Window, cx: &mut Context<Self>) {
+        if self.table.column_count == 0 {
+            return;
+        }
+        self.reset_cell_editor(window, cx);
+        self.cell_editor.update(cx, |editor, cx| {
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+        });
+        self.cell_editor.focus_handle(cx).focus(window);
+        cx.notify();
+    }
+
+    fn filter(&mut self, _: &Filter, window: &mut Window, cx: &mut Context<Self>) {
+        self.filter_editor.focus_handle(cx).focus(window);
+        cx.notify();
+    }
+
+    fn undo(&mut self, _: &editor::actions::Undo, _: &mut Window, cx: &mut Context<Self>) {
+        let buffer = self.csv_file.read(cx).buffer().clone();
+        buffer.update(cx, |buffer, cx| buffer.undo(cx));
+    }
+
+    fn redo(&mut self, _: &editor::actions::Redo, _: &mut Window, cx: &mut Context<Self>) {
+        let buffer = self.csv_file.read(cx).buffer().clone();
+        buffer.update(cx, |buffer, cx| buffer.redo(cx));
+    }
+
+    /// Writes the value in the cell editor to the selected cell of the buffer.
+    ///
+    /// The buffer is re-parsed in the background after the edit, so if it was edited since the
+    /// table was last parsed, the cell's range is moved past those edits instead.
+    fn write_cell_value(&mut self, cx: &mut Context<Self>) {
+        let buffer = self.csv_file.read(cx).buffer().clone();
+        if buffer.read(cx).read_only() {
+            self.status_message = Some("The file is read-only".into());
+            cx.notify();
+            return;
+        }
+        let Some(version) = self.table_version.clone() else {
+            return;
+        };
+        let value = self.cell_editor.read(cx).text(cx);
+        let cell = self.selected_cell;
+        let Some((range, text)) = self.table.edit_for_cell(cell.row, cell.column, &value) else {
+            return;
+        };
+        let range = range_after_edits(
+            range,
+            buffer
+                .read(cx)
+                .edits_since::<usize>(&version)
+                .map(|edit| (edit.old, edit.new)),
+        );
+        match range {
+            Some(range) => buffer.update(cx, |buffer, cx| {
+                buffer.edit([(range, text)], None, cx);
+            }),
+            None => {
+                self.status_message = Some("The cell was changed while it was being edited".into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn cycle_sort(&mut self, column: usize, cx: &mut Context<Self>) {
+        self.sort = match self.sort {
+            Some(sort) if sort.column == column => match sort.direction {
+                SortDirection::Ascending => Some(ColumnSort {
+                    column,
+                    direction: SortDirection::Descending,
+                }),
+                SortDirection::Descending => None,
+            },
+            _ => Some(ColumnSort {
+                column,
+                direction: SortDirection::Ascending,
+            }),
+        };
+        self.update_display_rows(cx);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.cell_editor.focus_handle(cx).is_focused(window) {
+            self.write_cell_value(cx);
+            window.focus(&self.focus_handle);
+            self.move_selection(1, 0, window, cx);
+        } else if self.filter_editor.focus_handle(cx).is_focused(window) {
+            window.focus(&self.focus_handle);
+            cx.notify();
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.cell_editor.focus_handle(cx).is_focused(window) {
+            self.reset_cell_editor(window, cx);
+            window.focus(&self.focus_handle);
+        } else if self.filter_editor.focus_handle(cx).is_focused(window) {
+            window.focus(&self.focus_handle);
+        }
+        self.status_message = None;
+        cx.notify();
+    }
+
+    fn measure_columns(&mut self, window: &mut Window, cx: &App) {
+        let settings = ThemeSettings::get_global(cx);
+        let font_size = TextSize::Small.rems(cx).to_pixels(window.rem_size());
+        let font_id = window.text_system().resolve_font(&settings.buffer_font);
+        self.character_width = window
+            .text_system()
+            .em_advance(font_id, font_size)
+            .log_err()
+            .unwrap_or(font_size);
+        if self.column_widths.len() < self.table.column_count {
+            let character_counts = column_character_counts(&self.table, COLUMN_WIDTH_SAMPLE_ROWS);
+            let new_widths = character_counts[self.column_widths.len()..]
+                .iter()
+                .map(|count| {
+                    let characters = (*count).clamp(MIN_COLUMN_CHARACTERS, MAX_COLUMN_CHARACTERS);
+                    self.character_width * characters as f32 + CELL_PADDING
+                })
+                .collect::<Vec<_>>();
+            self.column_widths.extend(new_widths);
+        }
+    }
+
+    /// The width of the row numbers left of the cells.
+    fn gutter_width(&self) -> Pixels {
+        let digits = self.table.rows.len().max(1).ilog10() as f32 + 1.;
+        self.character_width * digits + CELL_PADDING
+    }
+
+    fn editor_style(editor: &Entity<Editor>, cx: &Context<Self>) -> EditorStyle {
+        let settings = ThemeSettings::get_global(cx);
+        let theme = cx.theme();
+        let text_style = TextStyle {
+            color: if editor.read(cx).read_only(cx) {
+                theme.colors().text_muted
+            } else {
+                theme.colors().text
+            },
+            font_family: settings.buffer_font.family.clone(),
+            font_features: settings.buffer_font.features.clone(),
+            font_size: TextSize::Small.rems(cx).into(),
+            font_weight: settings.buffer_font.weight,
+            ..Default::default()
+        };
+        EditorStyle {
+            background: theme.colors().editor_background,
+            local_player: theme.players().local(),
+            text: text_style,
+            ..Default::default()
+        }
+    }
+
+    fn render_editor_box(
+        &self,
+        editor: &Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        h_flex()
+            .rounded_md()
+            .border_1()
+            .px_2()
+            .py_0p5()
+            .bg(cx.theme().colors().editor_background)
+            .when_else(
+                editor.focus_handle(cx).contains_focused(window, cx),
+                |this| this.border_color(cx.theme().colors().border_focused),
+                |this| this.border_color(cx.theme().colors().border_transparent),
+            )
+            .child(EditorElement::new(editor, Self::editor_style(editor, cx)))
+    }
+
+    fn render_toolbar(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let cell_name = format!(
+            "{}{}",
+            column_name(self.selected_cell.column),
+            self.selected_cell.row + 1
+        );
+
+        h_flex()
+            .w_full()
+            .gap_1()
+            .child(
+                div().min_w_12().px_1().child(
+                    Label::new(cell_name)
+                        .buffer_font(cx)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+            )
+            .child(
+                self.render_editor_box(&self.cell_editor, window, cx)
+                    .flex_1(),
+            )
+            .child(
+                self.render_editor_box(&self.filter_editor, window, cx)
+                    .w_1_3()
+                    .gap_x_2()
+                    .child(
+                        Icon::new(IconName::ListFilter)
+                            .size(IconSize::XSmall)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(
+                IconButton::new("csv-view-open-as-text", IconName::FileDoc)
+                    .icon_size(IconSize::XSmall)
+                    .tooltip(Tooltip::text("Open as Text"))
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(Box::new(OpenAsText), cx);
+                    }),
+            )
+    }
+
+    fn render_row(&self, row: usize, is_header: bool, cx: &Context<Self>) -> AnyElement {
+        let header_background = cx.theme().colors().editor_background;
+        let header_border_color = cx.theme().colors().border;
+        let border_color = cx.theme().colors().border_variant;
+        let selection_color = Color::Selected.color(cx).opacity(0.2);
+        let cursor_color = cx.theme().players().local().cursor;
+        let cells = self.table.rows.get(row).map_or(&[][..], |row| &row.cells);
+
+        h_flex()
+            .when(is_header, |this| {
+                this.bg(header_background)
+                    .border_b_1()
+                    .border_color(header_border_color)
+            })
+            .child(
+                div()
+                    .w(self.gutter_width())
+                    .flex_none()
+                    .px_1()
+                    .border_r_1()
+                    .border_color(border_color)
+                    .when(!is_header, |this| {
+                        this.child(
+                            Label::new((row + 1).to_string())
+                                .buffer_font(cx)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .children((0..self.table.column_count).map(|column| {
+                let value = cells.get(column).map_or("", |cell| cell.value.as_str());
+                let is_selected = self.selected_cell == CellPosition { row, column };
+                let cell = CellPosition { row, column };
+                h_flex()
+                    .w(self
+                        .column_widths
+                        .get(column)
+                        .copied()
+                        .unwrap_or(MIN_COLUMN_WIDTH))
+                    .flex_none()
+                    .overflow_hidden()
+                    .border_1()
+                    .border_color(if is_selected {
+                        cursor_color
+                    } else {
+                        transparent_black()
+                    })
+                    .when(is_selected, |this| this.bg(selection_color))
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .px_1()
+                            .border_r_1()
+                            .border_color(border_color)
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                                    if this.cell_editor.focus_handle(cx).is_focused(window) {
+                                        this.write_cell_value(cx);
+                                    }
+                                    window.focus(&this.focus_handle);
+                                    this.select_cell(cell, window, cx);
+                                    if event.click_count > 1 {
+                                        this.edit_cell(&EditCell, window, cx);
+                                    }
+                                }),
+                            )
+                            .child(
+                                Label::new(value.replace(['\r', '\n'], "⏎"))
+                                    .buffer_font(cx)
+                                    .size(LabelSize::Small)
+                                    .single_line()
+                                    .truncate()
+                                    .when(is_header, |this| this.weight(FontWeight::BOLD)),
+                            ),
+                    )
+                    .when(is_header, |this| {
+                        this.child(self.render_sort_button(column, cx))
+                            .child(self.render_resize_handle(column, cx))
+                    })
+            }))
+            .into_any_element()
+    }
+
+    fn render_sort_button(&self, column: usize, cx: &Context<Self>) -> impl IntoElement {
+        let sort_direction = self
+            .sort
+            .filter(|sort| sort.column == column)
+            .map(|sort| sort.direction);
+        let (icon, tooltip) = match sort_direction {
+            Some(SortDirection::Ascending) => (IconName::ArrowUp, "Sorted Ascending"),
+            Some(SortDirection::Descending) => (IconName::ArrowDown, "Sorted Descending"),
+            None => (IconName::ChevronUpDown, "Sort"),
+        };
+        IconButton::new(("csv-view-sort", column), icon)
+            .icon_size(IconSize::XSmall)
+            .icon_color(if sort_direction.is_some() {
+                Color::Accent
+            } else {
+                Color::Muted
+            })
+            .tooltip(Tooltip::text(tooltip))
+            .on_click(cx.listener(move |this, _, _, cx| this.cycle_sort(column, cx)))
+    }
+
+    fn render_resize_handle(&self, column: usize, cx: &Context<Self>) -> impl IntoElement {
+        let start_width = self
+            .column_widths
+            .get(column)
+            .copied()
+            .unwrap_or(MIN_COLUMN_WIDTH);
+        div()
+            .id(("csv-view-resize-column", column))
+            .w(px(4.))
+            .h_full()
+            .flex_none()
+            .cursor_col_resize()
+            .hover(|this| this.bg(cx.theme().colors().border_focused))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, event: &MouseDownEvent, _, cx| {
+                    this.column_resize = Some(ColumnResize {
+                        column,
+                        start_position: event.position.x,
+                        start_width,
+                    });
+                    cx.stop_propagation();
+                }),
+            )
+    }
+
+    fn render_rows(&self, rows: Range<usize>, cx: &mut Context<Self>) -> Vec<AnyElement> {
+        rows.filter_map(|index| {
+            let row = *self.display_rows.get(index)?;
+            if index == 0 {
+                // The header row scrolls away under the sticky header, which is the one that
+                // takes clicks, so it gets its own id to keep their element state apart.
+                return Some(
+                    div()
+                        .id("csv-view-header-row")
+                        .child(self.render_row(row, true, cx))
+                        .into_any_element(),
+                );
+            }
+            Some(self.render_row(row, false, cx))
+        })
+        .collect()
+    }
+
+    fn render_status(&self, cx: &Context<Self>) -> impl IntoElement {
+        let data_rows = self.table.rows.len().saturating_sub(1);
+        let shown_rows = self.display_rows.len().saturating_sub(1);
+        let mut summary = format!("{data_rows} rows, {} columns", self.table.column_count);
+        if shown_rows != data_rows {
+            summary.push_str(&format!(" ({shown_rows} shown)"));
+        }
+
+        h_flex()
+            .w_full()
+            .gap_2()
+            .px_2()
+            .child(Label::new(summary).size(LabelSize::Small))
+            .children(self.status_message.clone().map(|message| {
+                Label::new(message)
+                    .size(LabelSize::Small)
+                    .color(Color::Warning)
+            }))
+            .child(div().flex_1())
+            .children(self.dialect().map(|dialect| {
+                div()
+                    .id("csv-view-delimiter")
+                    .child(
+                        Label::new(dialect.delimiter_name())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .tooltip(Tooltip::text("The delimiter between cells"))
+            }))
+    }
+}
+
+/// Draws the header row over the top of the list, so it stays in place as the rows scroll.
+struct StickyHeader(WeakEntity<CsvView>);
+
+impl UniformListDecoration for StickyHeader {
+    fn compute(
+        &self,
+        _: Range<usize>,
+        _: Bounds<Pixels>,
+        scroll_offset: Point<Pixels>,
+        _: Pixels,
+        item_count: usize,
+        _: &mut Window,
+        cx: &mut App,
+    ) -> AnyElement {
+        self.0
+            .update(cx, |csv_view, cx| {
+                div()
+                    .id("csv-view-sticky-header")
+                    .relative()
+                    .top(-scroll_offset.y)
+                    .occlude()
+                    .when(item_count > 0, |this| {
+                        this.child(csv_view.render_row(0, true, cx))
+                    })
+                    .into_any_element()
+            })
+            .unwrap_or_else(|_| div().into_any_element())
+    }
+}
+
+impl Render for CsvView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.measure_columns(window, cx);
+        let row_count = self.display_rows.len();
+
+        v_flex()
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::edit_cell))
+            .on_action(cx.listener(Self::filter))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .size_full()
+            .p_1()
+            .gap_1()
+            .bg(cx.theme().colors().editor_background)
+            .on_mouse_move(cx.listener(|this, event: &MouseMoveEvent, _, cx| {
+                if let Some(resize) = this.column_resize
+                    && event.dragging()
+                {
+                    let width = (resize.start_width + event.position.x - resize.start_position)
+                        .max(MIN_COLUMN_WIDTH);
+                    if let Some(column_width) = this.column_widths.get_mut(resize.column) {
+                        *column_width = width;
+                        cx.notify();
+                    }
+                }
+            }))
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _, _, _| this.column_resize = None),
+            )
+            .on_mouse_up_out(
+                MouseButton::Left,
+                cx.listener(|this, _, _, _| this.column_resize = None),
+            )
+            .child(self.render_toolbar(window, cx))
+            .child(Divider::horizontal())
+            .child(
+                v_flex()
+                    .key_context("CsvView")
+                    .size_full()
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::undo))
+                    .on_action(cx.listener(Self::redo))
+                    .child(
+                        uniform_list(
+                            "csv-view-rows",
+                            row_count,
+                            cx.processor(|this, rows, _, cx| this.render_rows(rows, cx)),
+                        )
+                        .with_horizontal_sizing_behavior(
+                            ListHorizontalSizingBehavior::Unconstrained,
+                        )
+                        .with_width_from_item(Some(0))
+                        .with_decoration(StickyHeader(cx.weak_entity()))
+                        .track_scroll(&self.scroll_handle)
+                        .size_full(),
+                    )
+                    .custom_scrollbars(
+                        Scrollbars::new(ScrollAxes::Both)
+                            .tracked_scroll_handle(&self.scroll_handle)
+                            .tracked_entity(cx.entity_id()),
+                        window,
+                        cx,
+                    ),
+            )
+            .child(Divider::horizontal())
+            .child(self.render_status(cx))
+    }
+}
+
+impl Focusable for CsvView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<ItemEvent> for CsvView {}
+
+impl Item for CsvView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.csv_file.entity_id(), self.csv_file.read(cx))
+    }
+
+    fn buffer_kind(&self, _: &App) -> workspace::item::ItemBufferKind {
+        workspace::item::ItemBufferKind::Singleton
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.csv_file.read(cx).buffer().read(cx).file().map_or_else(
+            || "untitled".into(),
+            |file| file.file_name(cx).to_string().into(),
+        )
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = self.csv_file.read(cx).abs_path(cx)?;
+        Some(abs_path.compact().to_string_lossy().into_owned().into())
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let abs_path = self.csv_file.read(cx).abs_path(cx)?;
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(&abs_path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("CSV View Opened")
+    }
+
+    fn can_split(&self) -> bool {
+        true
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Entity<Self>>>
+    where
+        Self: Sized,
+    {
+        Task::ready(Some(cx.new(|cx| {
+            Self::new(self.csv_file.clone(), self.project.clone(), window, cx)
+        })))
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.csv_file.read(cx).buffer().read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.csv_file.read(cx).buffer().read(cx).has_conflict()
+    }
+
+    fn has_deleted_file(&self, cx: &App) -> bool {
+        self.csv_file
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .file()
+            .is_some_and(|file| file.disk_state() == DiskState::Deleted)
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.csv_file.read(cx).buffer().read(cx).file().is_some()
+    }
+
+    fn save(
+        &mut self,
+        _: SaveOptions,
+        project: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let buffer = self.csv_file.read(cx).buffer().clone();
+        project.update(cx, |project, cx| project.save_buffer(buffer, cx))
+    }
+
+    fn save_as(
+        &mut self,
+        project: Entity<Project>,
+        path: ProjectPath,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let buffer = self.csv_file.read(cx).buffer().clone();
+        project.update(cx, |project, cx| project.save_buffer_as(buffer, path, cx))
+    }
+
+    fn reload(
+        &mut self,
+        project: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let buffer = self.csv_file.read(cx).buffer().clone();
+        let reload_buffers = project.update(cx, |project, cx| {
+            project.reload_buffers(HashSet::from_iter([buffer]), true, cx)
+        });
+        cx.background_spawn(async move {
+            reload_buffers.await?;
+            Ok(())
+        })
+    }
+}
+
+impl ProjectItem for CsvView {
+    type Item = CsvFile;
+
+    fn for_project_item(
+        project: Entity<Project>,
+        _: Option<&Pane>,
+        item: Entity<Self::Item>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, project, window, cx)
+    }
+
+    fn for_broken_project_item(
+        abs_path: &Path,
+        is_local: bool,
+        e: &anyhow::Error,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<InvalidItemView>
+    where
+        Self: Sized,
+    {
+        Some(InvalidItemView::new(abs_path, is_local, e, window, cx))
+    }
+}
@@ -0,0 +1,507 @@
+use std::{cmp::Ordering, ops::Range};
+
+/// Delimiters tried when a file's extension doesn't determine one.
+const DELIMITER_CANDIDATES: &[char] = &[',', '\t', ';', '|'];
+
+/// How many lines of a file are looked at to detect its dialect.
+const DIALECT_SAMPLE_LINES: usize = 50;
+
+/// The characters separating and quoting the cells of a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Dialect {
+    pub delimiter: char,
+    pub quote: char,
+}
+
+impl Default for Dialect {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote: '"',
+        }
+    }
+}
+
+impl Dialect {
+    pub fn delimiter_name(&self) -> &'static str {
+        match self.delimiter {
+            ',' => "Comma",
+            '\t' => "Tab",
+            ';' => "Semicolon",
+            '|' => "Pipe",
+            _ => "Custom",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cell {
+    /// The range of the cell in the text, including its quotes.
+    pub range: Range<usize>,
+    /// The contents of the cell, with quotes removed and escaped quotes unescaped.
+    pub value: String,
+    pub is_quoted: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Row {
+    /// The range of the row in the text, excluding its line ending.
+    pub range: Range<usize>,
+    pub cells: Vec<Cell>,
+}
+
+/// The rows of a delimited file, with the position of every cell in its text.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Table {
+    pub dialect: Dialect,
+    pub rows: Vec<Row>,
+    /// The number of cells in the longest row.
+    pub column_count: usize,
+}
+
+impl Table {
+    pub fn cell(&self, row: usize, column: usize) -> Option<&Cell> {
+        self.rows.get(row)?.cells.get(column)
+    }
+
+    pub fn value(&self, row: usize, column: usize) -> &str {
+        self.cell(row, column)
+            .map_or("", |cell| cell.value.as_str())
+    }
+
+    /// The edit that sets the value of a cell, as a range of the text and its replacement.
+    ///
+    /// Cells missing from the end of a short row are added along with the delimiters before them.
+    pub fn edit_for_cell(
+        &self,
+        row: usize,
+        column: usize,
+        value: &str,
+    ) -> Option<(Range<usize>, String)> {
+        let row = self.rows.get(row)?;
+        match row.cells.get(column) {
+            Some(cell) => {
+                if cell.value == value {
+                    return None;
+                }
+                Some((
+                    cell.range.clone(),
+                    encode_value(value, self.dialect, cell.is_quoted),
+                ))
+            }
+            None => {
+                if value.is_empty() {
+                    return None;
+                }
+                let delimiters = self
+                    .dialect
+                    .delimiter
+                    .to_string()
+                    .repeat(column + 1 - row.cells.len());
+                Some((
+                    row.range.end..row.range.end,
+                    delimiters + &encode_value(value, self.dialect, false),
+                ))
+            }
+        }
+    }
+}
+
+/// Moves a range of an earlier version of the text past the edits made since, given in order as
+/// their old and new ranges. Returns `None` if an edit touches the range, as its contents may
+/// have changed.
+pub fn range_after_edits(
+    range: Range<usize>,
+    edits: impl IntoIterator<Item = (Range<usize>, Range<usize>)>,
+) -> Option<Range<usize>> {
+    let mut start = range.start;
+    let mut end = range.end;
+    for (old_range, new_range) in edits {
+        if old_range.start > range.end {
+            break;
+        }
+        if old_range.end >= range.start {
+            return None;
+        }
+        start = start + new_range.len() - old_range.len();
+        end = end + new_range.len() - old_range.len();
+    }
+    Some(start..end)
+}
+
+/// Detects the delimiter and quote character of a file from its first lines.
+///
+/// `.tsv` and `.psv` files always use tabs and pipes, while other files use whichever candidate
+/// splits the sampled lines into the same, largest number of cells.
+pub fn detect_dialect(text: &str, extension: Option<&str>) -> Dialect {
+    let sample_end = text
+        .match_indices('\n')
+        .nth(DIALECT_SAMPLE_LINES - 1)
+        .map_or(text.len(), |(index, _)| index);
+    let sample = &text[..sample_end];
+    let quote = detect_quote(sample);
+    let delimiter = match extension
+        .map(|extension| extension.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("tsv") => '\t',
+        Some("psv") => '|',
+        _ => detect_delimiter(sample, quote),
+    };
+    Dialect { delimiter, quote }
+}
+
+fn detect_quote(sample: &str) -> char {
+    let count_cell_starts = |quote: char| {
+        let mut previous = None;
+        sample
+            .chars()
+            .filter(|character| {
+                let is_cell_start = *character == quote
+                    && previous.is_none_or(|previous: char| {
+                        previous == '\n' || DELIMITER_CANDIDATES.contains(&previous)
+                    });
+                previous = Some(*character);
+                is_cell_start
+            })
+            .count()
+    };
+    let single_quotes = count_cell_starts('\'');
+    if single_quotes > count_cell_starts('"') {
+        '\''
+    } else {
+        '"'
+    }
+}
+
+fn detect_delimiter(sample: &str, quote: char) -> char {
+    DELIMITER_CANDIDATES
+        .iter()
+        .filter_map(|&delimiter| {
+            let table = parse_table(sample, Dialect { delimiter, quote });
+            let first_row_cells = table.rows.first()?.cells.len();
+            if first_row_cells < 2 {
+                return None;
+            }
+            let consistent_rows = table
+                .rows
+                .iter()
+                .filter(|row| row.cells.len() == first_row_cells)
+                .count();
+            Some(((consistent_rows, first_row_cells), delimiter))
+        })
+        .max_by_key(|(score, _)| *score)
+        .map_or(',', |(_, delimiter)| delimiter)
+}
+
+/// Splits text into rows and cells.
+///
+/// Quoted cells may contain delimiters, line breaks and doubled quotes. Unterminated quotes and
+/// characters after a closing quote are kept rather than rejected, so every file can be shown.
+pub fn parse_table(text: &str, dialect: Dialect) -> Table {
+    let bytes = text.as_bytes();
+    let delimiter = dialect.delimiter as u8;
+    let quote = dialect.quote as u8;
+    let mut rows = Vec::new();
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let row_start = offset;
+        let mut cells = Vec::new();
+        loop {
+            let cell = parse_cell(text, offset, delimiter, quote);
+            offset = cell.range.end;
+            cells.push(cell);
+            if bytes.get(offset) == Some(&delimiter) {
+                offset += 1;
+            } else {
+                break;
+            }
+        }
+        rows.push(Row {
+            range: row_start..offset,
+            cells,
+        });
+        if bytes.get(offset) == Some(&b'\r') {
+            offset += 1;
+        }
+        if bytes.get(offset) == Some(&b'\n') {
+            offset += 1;
+        }
+    }
+    let column_count = rows.iter().map(|row| row.cells.len()).max().unwrap_or(0);
+    Table {
+        dialect,
+        rows,
+        column_count,
+    }
+}
+
+fn parse_cell(text: &str, start: usize, delimiter: u8, quote: u8) -> Cell {
+    let bytes = text.as_bytes();
+    let find_cell_end = |offset: usize| {
+        bytes[offset..]
+            .iter()
+            .position(|&byte| byte == delimiter || byte == b'\n' || byte == b'\r')
+            .map_or(bytes.len(), |position| offset + position)
+    };
+
+    if bytes.get(start) != Some(&quote) {
+        let end = find_cell_end(start);
+        return Cell {
+            range: start..end,
+            value: text[start..end].to_string(),
+            is_quoted: false,
+        };
+    }
+
+    let mut value = String::new();
+    let mut offset = start + 1;
+    loop {
+        let Some(position) = bytes[offset..].iter().position(|&byte| byte == quote) else {
+            value.push_str(&text[offset..]);
+            offset = bytes.len();
+            break;
+        };
+        let quote_offset = offset + position;
+        value.push_str(&text[offset..quote_offset]);
+        offset = quote_offset + 1;
+        if bytes.get(offset) == Some(&quote) {
+            value.push(quote as char);
+            offset += 1;
+        } else {
+            break;
+        }
+    }
+    let end = find_cell_end(offset);
+    value.push_str(&text[offset..end]);
+    Cell {
+        range: start..end,
+        value,
+        is_quoted: true,
+    }
+}
+
+/// Writes a value as cell text, quoting it when it contains characters that would otherwise
+/// split or end the cell.
+pub fn encode_value(value: &str, dialect: Dialect, force_quote: bool) -> String {
+    let needs_quotes = force_quote
+        || value.starts_with(dialect.quote)
+        || value.contains([dialect.delimiter, '\n', '\r']);
+    if !needs_quotes {
+        return value.to_string();
+    }
+    let quote = dialect.quote.to_string();
+    let escaped_value = value.replace(&quote, &quote.repeat(2));
+    format!("{quote}{escaped_value}{quote}")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColumnSort {
+    pub column: usize,
+    pub direction: SortDirection,
+}
+
+/// The indices of the rows below the header that contain the filter text, in the order they're
+/// shown in.
+pub fn display_rows(table: &Table, filter: &str, sort: Option<ColumnSort>) -> Vec<usize> {
+    let filter = filter.to_lowercase();
+    let mut rows = (1..table.rows.len())
+        .filter(|&row| {
+            filter.is_empty()
+                || table.rows[row]
+                    .cells
+                    .iter()
+                    .any(|cell| cell.value.to_lowercase().contains(&filter))
+        })
+        .collect::<Vec<_>>();
+    if let Some(sort) = sort {
+        rows.sort_by(|&left, &right| {
+            let ordering = compare_values(
+                table.value(left, sort.column),
+                table.value(right, sort.column),
+            );
+            match sort.direction {
+                SortDirection::Ascending => ordering,
+                SortDirection::Descending => ordering.reverse(),
+            }
+        });
+    }
+    rows
+}
+
+/// Orders numbers by value and before other text, which is ordered case-insensitively.
+pub fn compare_values(left: &str, right: &str) -> Ordering {
+    let parse_number = |value: &str| value.trim().parse::<f64>().ok();
+    match (parse_number(left), parse_number(right)) {
+        (Some(left), Some(right)) => left.partial_cmp(&right).unwrap_or(Ordering::Equal),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => left
+            .to_lowercase()
+            .cmp(&right.to_lowercase())
+            .then_with(|| left.cmp(right)),
+    }
+}
+
+/// The spreadsheet-style name of a column, such as `A`, `Z` or `AA`.
+pub fn column_name(column: usize) -> String {
+    let mut name = Vec::new();
+    let mut remaining = column + 1;
+    while remaining > 0 {
+        remaining -= 1;
+        name.push(b'A' + (remaining % 26) as u8);
+        remaining /= 26;
+    }
+    name.reverse();
+    String::from_utf8_lossy(&name).into_owned()
+}
+
+/// The number of characters in the longest value of each column, among the first rows.
+pub fn column_character_counts(table: &Table, row_limit: usize) -> Vec<usize> {
+    let mut counts = vec![0; table.column_count];
+    for row in table.rows.iter().take(row_limit) {
+        for (count, cell) in counts.iter_mut().zip(&row.cells) {
+            *count = (*count).max(cell.value.chars().count());
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(table: &Table) -> Vec<Vec<&str>> {
+        table
+            .rows
+            .iter()
+            .map(|row| row.cells.iter().map(|cell| cell.value.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_table() {
+        let text = "name,notes\r\n\"Smith, J\",\"said \"\"hi\"\"\nand left\"\nDoe,\n";
+        let table = parse_table(text, Dialect::default());
+        assert_eq!(
+            values(&table),
+            vec![
+                vec!["name", "notes"],
+                vec!["Smith, J", "said \"hi\"\nand left"],
+                vec!["Doe", ""],
+            ]
+        );
+        assert_eq!(table.column_count, 2);
+        assert_eq!(table.rows[0].range, 0..10);
+        assert_eq!(&text[table.rows[1].cells[0].range.clone()], "\"Smith, J\"");
+        assert!(table.rows[1].cells[1].is_quoted);
+        assert_eq!(table.rows[2].cells[1].range, 50..50);
+
+        let table = parse_table("a,\"unterminated\nb", Dialect::default());
+        assert_eq!(values(&table), vec![vec!["a", "unterminated\nb"]]);
+
+        let table = parse_table("\"quoted\"tail,x\n\n", Dialect::default());
+        assert_eq!(values(&table), vec![vec!["quotedtail", "x"], vec![""]]);
+    }
+
+    #[test]
+    fn test_detect_dialect() {
+        assert_eq!(
+            detect_dialect("a;b;c\n1,5;2;3\n", Some("csv")),
+            Dialect {
+                delimiter: ';',
+                quote: '"',
+            }
+        );
+        assert_eq!(detect_dialect("a\tb\n1\t2\n", None).delimiter, '\t');
+        assert_eq!(detect_dialect("a,b|c\n", Some("PSV")).delimiter, '|');
+        assert_eq!(
+            detect_dialect("single column\n", Some("csv")).delimiter,
+            ','
+        );
+        assert_eq!(
+            detect_dialect("'a, b',c\n'd',e\n", Some("csv")),
+            Dialect {
+                delimiter: ',',
+                quote: '\'',
+            }
+        );
+    }
+
+    #[test]
+    fn test_edit_for_cell() {
+        let text = "a,\"b\"\nc\n";
+        let table = parse_table(text, Dialect::default());
+        assert_eq!(
+            table.edit_for_cell(0, 0, "x,y"),
+            Some((0..1, "\"x,y\"".into()))
+        );
+        assert_eq!(table.edit_for_cell(0, 1, "b"), None);
+        assert_eq!(
+            table.edit_for_cell(0, 1, "say \"b\""),
+            Some((2..5, "\"say \"\"b\"\"\"".into()))
+        );
+        assert_eq!(table.edit_for_cell(1, 2, "z"), Some((7..7, ",,z".into())));
+        assert_eq!(table.edit_for_cell(1, 2, ""), None);
+        assert_eq!(table.edit_for_cell(2, 0, "z"), None);
+    }
+
+    #[test]
+    fn test_range_after_edits() {
+        assert_eq!(range_after_edits(4..6, []), Some(4..6));
+        assert_eq!(
+            range_after_edits(4..6, [(0..1, 0..3), (2..3, 4..4), (8..9, 9..9)]),
+            Some(5..7)
+        );
+        assert_eq!(range_after_edits(4..6, [(3..4, 3..5)]), None);
+        assert_eq!(range_after_edits(4..6, [(5..5, 5..6)]), None);
+        assert_eq!(range_after_edits(4..6, [(6..7, 6..6)]), None);
+    }
+
+    #[test]
+    fn test_column_name() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(701), "ZZ");
+        assert_eq!(column_name(702), "AAA");
+    }
+
+    #[test]
+    fn test_display_rows() {
+        let table = parse_table(
+            "name,size\nbeta,10\nAlpha,9\ngamma,n/a\ndelta,-1.5\n",
+            Dialect::default(),
+        );
+        assert_eq!(display_rows(&table, "", None), vec![1, 2, 3, 4]);
+        assert_eq!(display_rows(&table, "ALPHA", None), vec![2]);
+        assert_eq!(
+            display_rows(
+                &table,
+                "",
+                Some(ColumnSort {
+                    column: 1,
+                    direction: SortDirection::Ascending,
+                })
+            ),
+            vec![4, 2, 1, 3]
+        );
+        assert_eq!(
+            display_rows(
+                &table,
+                "a",
+                Some(ColumnSort {
+                    column: 0,
+                    direction: SortDirection::Descending,
+                })
+            ),
+            vec![3, 4, 1, 2]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::persistence::DebuggerPaneItem;
+use crate::persistence::{DebuggerPaneItem, SerializedDebugSession};
 use crate::session::DebugSession;
 use crate::session::running::RunningState;
 use crate::session::running::breakpoint_list::BreakpointList;
@@ -6,8 +6,9 @@ use crate::session::running::breakpoint_list::BreakpointList;
 use crate::{
     ClearAllBreakpoints, Continue, CopyDebugAdapterArguments, Detach, FocusBreakpointList,
     FocusConsole, FocusFrames, FocusLoadedSources, FocusModules, FocusTerminal, FocusVariables,
-    NewProcessModal, NewProcessMode, Pause, RerunSession, StepInto, StepOut, StepOver, Stop,
-    ToggleExpandItem, ToggleSessionPicker, ToggleThreadPicker, persistence, spawn_task_or_modal,
+    NewProcessModal, NewProcessMode, Pause, RerunSession, ReverseContinue, StepBack, StepInto,
+    StepOut, StepOver, Stop, ToggleExpandItem, ToggleSessionPicker, ToggleThreadPicker,
+    persistence, spawn_task_or_modal,
 };
 use anyhow::{Context as _, Result, anyhow};
 use collections::IndexMap;
@@ -215,6 +216,8 @@ impl DebugPanel {
             return;
         };
 
+        let worktree_path = worktree.read(cx).abs_path().to_path_buf();
+
         self.debug_scenario_scheduled_last = true;
         if let Some(inventory) = self
             .project
@@ -265,6 +268,16 @@ impl DebugPanel {
                         })
                     })?
                     .await?;
+                let history_entry = SerializedDebugSession {
+                    scenario: definition.to_scenario(),
+                    worktree_path: Some(worktree_path),
+                };
+                cx.background_spawn(async move {
+                    persistence::record_debug_session(history_entry)
+                        .await
+                        .log_err();
+                })
+                .detach();
                 dap_store
                     .update(cx, |dap_store, cx| {
                         dap_store.boot_session(session.clone(), definition, worktree, cx)
@@ -816,6 +829,66 @@ impl DebugPanel {
                                                 }
                                             }),
                                     )
+                                    .when(
+                                        capabilities.supports_step_back.unwrap_or_default(),
+                                        |this| {
+                                            this.child(Divider::vertical())
+                                                .child(
+                                                    IconButton::new(
+                                                        "step-back",
+                                                        IconName::DebugStepBack,
+                                                    )
+                                                    .icon_size(IconSize::Small)
+                                                    .on_click(window.listener_for(
+                                                        running_state,
+                                                        |this, _, _window, cx| {
+                                                            this.step_back(cx);
+                                                        },
+                                                    ))
+                                                    .disabled(
+                                                        thread_status != ThreadStatus::Stopped,
+                                                    )
+                                                    .tooltip({
+                                                        let focus_handle = focus_handle.clone();
+                                                        move |_window, cx| {
+                                                            Tooltip::for_action_in(
+                                                                "Step Back",
+                                                                &StepBack,
+                                                                &focus_handle,
+                                                                cx,
+                                                            )
+                                                        }
+                                                    }),
+                                                )
+                                                .child(
+                                                    IconButton::new(
+                                                        "debug-reverse-continue",
+                                                        IconName::DebugReverseContinue,
+                                                    )
+                                                    .icon_size(IconSize::Small)
+                                                    .on_click(window.listener_for(
+                                                        running_state,
+                                                        |this, _, _window, cx| {
+                                                            this.reverse_continue(cx);
+                                                        },
+                                                    ))
+                                                    .disabled(
+                                                        thread_status != ThreadStatus::Stopped,
+                                                    )
+                                                    .tooltip({
+                                                        let focus_handle = focus_handle.clone();
+                                                        move |_window, cx| {
+                                                            Tooltip::for_action_in(
+                                                                "Reverse Continue",
+                                                                &ReverseContinue,
+                                                                &focus_handle,
+                                                                cx,
+                                                            )
+                                                        }
+                                                    }),
+                                                )
+                                        },
+                                    )
                                     .child(Divider::vertical())
                                     .child(
                                         IconButton::new("debug-restart", IconName::RotateCcw)
@@ -6,6 +6,7 @@ use gpui::{Action, App, DispatchPhase, EntityInputHandler, actions};
 use new_process_modal::{NewProcessModal, NewProcessMode};
 use onboarding_modal::DebuggerOnboardingModal;
 use project::debugger::{self, breakpoint_store::SourceBreakpoint, session::ThreadStatus};
+use recent_sessions_modal::RecentSessionsModal;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use session::DebugSession;
@@ -20,9 +21,11 @@ use zed_actions::debugger::OpenOnboardingModal;
 pub mod attach_modal;
 pub mod debugger_panel;
 mod dropdown_menus;
+mod edit_session_modal;
 mod new_process_modal;
 mod onboarding_modal;
 mod persistence;
+mod recent_sessions_modal;
 pub(crate) mod session;
 mod stack_trace_view;
 
@@ -36,6 +39,8 @@ actions!(
         Start,
         /// Continues execution until the next breakpoint.
         Continue,
+        /// Continues execution backwards until the previous breakpoint.
+        ReverseContinue,
         /// Detaches the debugger from the running process.
         Detach,
         /// Pauses the currently running program.
@@ -44,6 +49,11 @@ actions!(
         Restart,
         /// Reruns the current debugging session with the same configuration.
         RerunSession,
+        /// Opens a picker of previously launched debug sessions to relaunch, edit or save.
+        OpenRecentSessions,
+        /// Opens the configuration of the selected recent debug session for editing before
+        /// relaunching it.
+        EditRecentSession,
         /// Steps into the next function call.
         StepInto,
         /// Steps over the current line.
@@ -133,6 +143,11 @@ pub fn init(cx: &mut App) {
                     debug_panel.rerun_last_session(workspace, window, cx);
                 })
             })
+            .register_action(
+                |workspace: &mut Workspace, _: &OpenRecentSessions, window, cx| {
+                    RecentSessionsModal::show(workspace, window, cx);
+                },
+            )
             .register_action(
                 |workspace: &mut Workspace, _: &ShutdownDebugAdapters, _window, cx| {
                     workspace.project().update(cx, |project, cx| {
@@ -195,9 +210,19 @@ pub fn init(cx: &mut App) {
                         }
                     })
                     .when(supports_step_back, |div| {
-                        let active_item = active_item.clone();
-                        div.on_action(move |_: &StepBack, _, cx| {
-                            active_item.update(cx, |item, cx| item.step_back(cx)).ok();
+                        div.on_action({
+                            let active_item = active_item.clone();
+                            move |_: &StepBack, _, cx| {
+                                active_item.update(cx, |item, cx| item.step_back(cx)).ok();
+                            }
+                        })
+                        .on_action({
+                            let active_item = active_item.clone();
+                            move |_: &ReverseContinue, _, cx| {
+                                active_item
+                                    .update(cx, |item, cx| item.reverse_continue(cx))
+                                    .ok();
+                            }
                         })
                     })
                     .on_action({
@@ -0,0 +1,178 @@
+use editor::Editor;
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, WeakEntity};
+use project::WorktreeId;
+use task::{DebugScenario, TaskContext};
+use ui::{KeyBinding, Modal, ModalFooter, ModalHeader, Section, prelude::*};
+use workspace::{ModalView, Workspace};
+
+use crate::debugger_panel::DebugPanel;
+
+/// Edits the configuration of a previously launched debug session as JSON, and launches the
+/// edited configuration.
+pub(crate) struct EditSessionModal {
+    debug_panel: WeakEntity<DebugPanel>,
+    worktree_id: Option<WorktreeId>,
+    editor: Entity<Editor>,
+    error: Option<SharedString>,
+}
+
+impl EditSessionModal {
+    pub(crate) fn show(
+        workspace: &mut Workspace,
+        scenario: DebugScenario,
+        worktree_id: Option<WorktreeId>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) else {
+            return;
+        };
+        let debug_panel = debug_panel.downgrade();
+        let json = match serde_json::to_string_pretty(&scenario) {
+            Ok(json) => json,
+            Err(error) => {
+                log::error!("Failed to serialize debug scenario: {error}");
+                return;
+            }
+        };
+        let languages = workspace.app_state().languages.clone();
+
+        cx.spawn_in(window, async move |workspace, cx| {
+            let jsonc_language = languages.language_for_name("jsonc").await.ok();
+            workspace.update_in(cx, |workspace, window, cx| {
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    let editor = cx.new(|cx| {
+                        let mut editor = Editor::auto_height(8, 24, window, cx);
+                        editor.set_text(json, window, cx);
+                        editor.set_show_gutter(false, cx);
+                        if let Some(buffer) = editor.buffer().read(cx).as_singleton() {
+                            buffer.update(cx, |buffer, cx| buffer.set_language(jsonc_language, cx));
+                        }
+                        editor
+                    });
+                    Self {
+                        debug_panel,
+                        worktree_id,
+                        editor,
+                        error: None,
+                    }
+                });
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.editor.read(cx).text(cx);
+        let scenario = match serde_json_lenient::from_str::<DebugScenario>(&text) {
+            Ok(scenario) => scenario,
+            Err(error) => {
+                self.error = Some(format!("Invalid debug configuration: {error}").into());
+                cx.notify();
+                return;
+            }
+        };
+
+        let worktree_id = self.worktree_id;
+        self.debug_panel
+            .update(cx, |debug_panel, cx| {
+                debug_panel.start_session(
+                    scenario,
+                    TaskContext::default(),
+                    None,
+                    worktree_id,
+                    window,
+                    cx,
+                );
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for EditSessionModal {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle(cx);
+        v_flex()
+            .elevation_3(cx)
+            .w(rems(34.))
+            .key_context("EditDebugSessionModal")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .child(
+                Modal::new("edit-debug-session", None)
+                    .header(ModalHeader::new().headline("Edit and Relaunch Debug Session"))
+                    .section(
+                        Section::new()
+                            .child(
+                                div()
+                                    .p_2()
+                                    .rounded_md()
+                                    .border_1()
+                                    .border_color(cx.theme().colors().border_variant)
+                                    .bg(cx.theme().colors().editor_background)
+                                    .child(self.editor.clone()),
+                            )
+                            .when_some(self.error.clone(), |section, error| {
+                                section.child(
+                                    h_flex()
+                                        .pt_2()
+                                        .gap_2()
+                                        .child(
+                                            Icon::new(IconName::Warning)
+                                                .size(IconSize::XSmall)
+                                                .color(Color::Warning),
+                                        )
+                                        .child(
+                                            Label::new(error)
+                                                .size(LabelSize::Small)
+                                                .color(Color::Muted),
+                                        ),
+                                )
+                            }),
+                    )
+                    .footer(
+                        ModalFooter::new().end_slot(
+                            h_flex()
+                                .gap_2()
+                                .child(
+                                    Button::new("cancel", "Cancel")
+                                        .key_binding(KeyBinding::for_action_in(
+                                            &menu::Cancel,
+                                            &focus_handle,
+                                            cx,
+                                        ))
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.cancel(&menu::Cancel, window, cx)
+                                        })),
+                                )
+                                .child(
+                                    Button::new("launch", "Launch")
+                                        .key_binding(KeyBinding::for_action_in(
+                                            &menu::Confirm,
+                                            &focus_handle,
+                                            cx,
+                                        ))
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.confirm(&menu::Confirm, window, cx)
+                                        })),
+                                ),
+                        ),
+                    ),
+            )
+    }
+}
+
+impl EventEmitter<DismissEvent> for EditSessionModal {}
+
+impl Focusable for EditSessionModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl ModalView for EditSessionModal {}
@@ -5,6 +5,8 @@ use db::kvp::KEY_VALUE_STORE;
 use gpui::{Axis, Context, Entity, EntityId, Focusable, Subscription, WeakEntity, Window};
 use project::Project;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use task::DebugScenario;
 use ui::{App, SharedString};
 use util::ResultExt;
 use workspace::{Member, Pane, PaneAxis, Workspace};
@@ -136,6 +138,43 @@ pub(crate) async fn serialize_pane_layout(
         .await
 }
 
+const DEBUG_SESSION_HISTORY_KEY: &str = "debugger_session_history";
+const MAX_DEBUG_SESSION_HISTORY: usize = 50;
+
+/// A debug session launched at some point, stored with the configuration that
+/// was sent to the adapter so it can be relaunched verbatim.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SerializedDebugSession {
+    pub scenario: DebugScenario,
+    pub worktree_path: Option<PathBuf>,
+}
+
+pub(crate) fn debug_session_history() -> Vec<SerializedDebugSession> {
+    KEY_VALUE_STORE
+        .read_kvp(DEBUG_SESSION_HISTORY_KEY)
+        .log_err()
+        .flatten()
+        .and_then(|value| serde_json::from_str::<Vec<SerializedDebugSession>>(&value).log_err())
+        .unwrap_or_default()
+}
+
+pub(crate) async fn record_debug_session(session: SerializedDebugSession) -> anyhow::Result<()> {
+    let mut history = debug_session_history();
+    history.retain(|entry| {
+        entry.scenario.label != session.scenario.label
+            || entry.scenario.adapter != session.scenario.adapter
+            || entry.worktree_path != session.worktree_path
+    });
+    history.insert(0, session);
+    history.truncate(MAX_DEBUG_SESSION_HISTORY);
+
+    let serialized_history = serde_json::to_string(&history)
+        .context("Serializing debug session history with serde_json as a string")?;
+    KEY_VALUE_STORE
+        .write_kvp(DEBUG_SESSION_HISTORY_KEY.to_string(), serialized_history)
+        .await
+}
+
 pub(crate) fn build_serialized_layout(
     pane_group: &Member,
     dock_axis: Axis,
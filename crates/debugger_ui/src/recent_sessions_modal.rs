@@ -0,0 +1,362 @@
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    Action as _, DismissEvent, Entity, EventEmitter, Focusable, Render, Subscription, Task,
+    WeakEntity,
+};
+use picker::{Picker, PickerDelegate};
+use project::WorktreeId;
+use std::sync::Arc;
+use task::TaskContext;
+use ui::{HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, prelude::*};
+use workspace::{ModalView, Workspace};
+
+use crate::EditRecentSession;
+use crate::debugger_panel::DebugPanel;
+use crate::edit_session_modal::EditSessionModal;
+use crate::persistence::{self, SerializedDebugSession};
+
+#[derive(Clone, Debug)]
+pub(crate) struct RecentSessionCandidate {
+    pub(crate) session: SerializedDebugSession,
+    pub(crate) worktree_id: Option<WorktreeId>,
+}
+
+pub(crate) struct RecentSessionsDelegate {
+    workspace: WeakEntity<Workspace>,
+    debug_panel: WeakEntity<DebugPanel>,
+    candidates: Arc<[RecentSessionCandidate]>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+pub(crate) struct RecentSessionsModal {
+    picker: Entity<Picker<RecentSessionsDelegate>>,
+    _subscription: Subscription,
+}
+
+impl RecentSessionsModal {
+    pub(crate) fn show(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) else {
+            return;
+        };
+
+        let project = workspace.project().read(cx);
+        let candidates = persistence::debug_session_history()
+            .into_iter()
+            .filter_map(|session| {
+                let worktree_id = match &session.worktree_path {
+                    Some(worktree_path) => Some(
+                        project
+                            .visible_worktrees(cx)
+                            .find(|worktree| {
+                                worktree.read(cx).abs_path().as_ref() == worktree_path.as_path()
+                            })?
+                            .read(cx)
+                            .id(),
+                    ),
+                    None => None,
+                };
+                Some(RecentSessionCandidate {
+                    session,
+                    worktree_id,
+                })
+            })
+            .collect::<Arc<[_]>>();
+
+        let workspace_handle = workspace.weak_handle();
+        let debug_panel = debug_panel.downgrade();
+        workspace.toggle_modal(window, cx, |window, cx| {
+            Self::new(workspace_handle, debug_panel, candidates, window, cx)
+        });
+    }
+
+    pub(crate) fn new(
+        workspace: WeakEntity<Workspace>,
+        debug_panel: WeakEntity<DebugPanel>,
+        candidates: Arc<[RecentSessionCandidate]>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let picker = cx.new(|cx| {
+            Picker::uniform_list(
+                RecentSessionsDelegate {
+                    workspace,
+                    debug_panel,
+                    candidates,
+                    matches: Vec::new(),
+                    selected_index: 0,
+                },
+                window,
+                cx,
+            )
+            .modal(true)
+        });
+        Self {
+            _subscription: cx.subscribe(&picker, |_, _, _, cx| {
+                cx.emit(DismissEvent);
+            }),
+            picker,
+        }
+    }
+
+    fn edit_selected_session(
+        &mut self,
+        _: &EditRecentSession,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker.update(cx, |picker, cx| {
+            picker.delegate.edit_selected_session(window, cx)
+        });
+    }
+}
+
+impl Render for RecentSessionsModal {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("RecentDebugSessionsModal")
+            .track_focus(&self.focus_handle(cx))
+            .on_action(cx.listener(Self::edit_selected_session))
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl EventEmitter<DismissEvent> for RecentSessionsModal {}
+
+impl Focusable for RecentSessionsModal {
+    fn focus_handle(&self, cx: &App) -> gpui::FocusHandle {
+        self.picker.read(cx).focus_handle(cx)
+    }
+}
+
+impl ModalView for RecentSessionsModal {}
+
+impl RecentSessionsDelegate {
+    fn selected_candidate(&self) -> Option<&RecentSessionCandidate> {
+        self.matches
+            .get(self.selected_index)
+            .and_then(|hit| self.candidates.get(hit.candidate_id))
+    }
+
+    fn edit_selected_session(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(candidate) = self.selected_candidate().cloned() else {
+            return;
+        };
+        let workspace = self.workspace.clone();
+        // The edit modal replaces this one, which can't happen while it's being updated.
+        window.defer(cx, move |window, cx| {
+            workspace
+                .update(cx, |workspace, cx| {
+                    EditSessionModal::show(
+                        workspace,
+                        candidate.session.scenario,
+                        candidate.worktree_id,
+                        window,
+                        cx,
+                    );
+                })
+                .ok();
+        });
+    }
+}
+
+impl PickerDelegate for RecentSessionsDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Find a recent debug session".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        if self.candidates.is_empty() {
+            Some("No debug sessions have been launched yet".into())
+        } else {
+            Some("No matches".into())
+        }
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let candidates = self.candidates.clone();
+        cx.spawn(async move |this, cx| {
+            let matches = fuzzy::match_strings(
+                &candidates
+                    .iter()
+                    .enumerate()
+                    .map(|(id, candidate)| {
+                        StringMatchCandidate::new(id, candidate.session.scenario.label.as_ref())
+                    })
+                    .collect::<Vec<_>>(),
+                &query,
+                true,
+                true,
+                1000,
+                &Default::default(),
+                cx.background_executor().clone(),
+            )
+            .await;
+
+            this.update(cx, |this, _| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                if delegate.matches.is_empty() {
+                    delegate.selected_index = 0;
+                } else {
+                    delegate.selected_index =
+                        delegate.selected_index.min(delegate.matches.len() - 1);
+                }
+            })
+            .ok();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(candidate) = self.selected_candidate().cloned() else {
+            return;
+        };
+        let scenario = candidate.session.scenario;
+
+        if secondary {
+            let Some(worktree_id) = candidate.worktree_id else {
+                return;
+            };
+            self.debug_panel
+                .update(cx, |debug_panel, cx| {
+                    debug_panel
+                        .save_scenario(scenario, worktree_id, window, cx)
+                        .detach_and_log_err(cx);
+                })
+                .ok();
+        } else {
+            self.debug_panel
+                .update(cx, |debug_panel, cx| {
+                    debug_panel.start_session(
+                        scenario,
+                        TaskContext::default(),
+                        None,
+                        candidate.worktree_id,
+                        window,
+                        cx,
+                    );
+                })
+                .ok();
+        }
+
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_footer(
+        &self,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<AnyElement> {
+        if self.matches.is_empty() {
+            return None;
+        }
+
+        let can_save = self
+            .selected_candidate()
+            .is_some_and(|candidate| candidate.worktree_id.is_some());
+
+        Some(
+            h_flex()
+                .w_full()
+                .p_1p5()
+                .gap_1()
+                .justify_end()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Button::new("save-to-debug-json", "Save to debug.json")
+                        .disabled(!can_save)
+                        .key_binding(KeyBinding::for_action(&menu::SecondaryConfirm, cx))
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::SecondaryConfirm.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("edit-and-relaunch", "Edit and Relaunch")
+                        .key_binding(KeyBinding::for_action(&EditRecentSession, cx))
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(EditRecentSession.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("relaunch", "Relaunch")
+                        .key_binding(KeyBinding::for_action(&menu::Confirm, cx))
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                        }),
+                )
+                .into_any_element(),
+        )
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let candidate = self.candidates.get(hit.candidate_id)?;
+        let scenario = &candidate.session.scenario;
+
+        let subtitle = match &candidate.session.worktree_path {
+            Some(worktree_path) => format!("{} · {}", scenario.adapter, worktree_path.display()),
+            None => scenario.adapter.to_string(),
+        };
+
+        Some(
+            ListItem::new(SharedString::from(format!("recent-debug-session-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(IconName::HistoryRerun).color(Color::Muted))
+                .child(
+                    v_flex()
+                        .items_start()
+                        .child(HighlightedLabel::new(
+                            scenario.label.clone(),
+                            hit.positions.clone(),
+                        ))
+                        .child(
+                            Label::new(subtitle)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
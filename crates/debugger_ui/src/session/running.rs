@@ -1636,6 +1636,16 @@ impl RunningState {
         });
     }
 
+    pub(crate) fn reverse_continue(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        self.session().update(cx, |state, cx| {
+            state.reverse_continue(thread_id, cx);
+        });
+    }
+
     pub fn step_over(&mut self, cx: &mut Context<Self>) {
         let Some(thread_id) = self.thread_id else {
             return;
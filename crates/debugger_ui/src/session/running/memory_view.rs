@@ -26,7 +26,14 @@ use workspace::Workspace;
 
 use crate::{ToggleDataBreakpoint, session::running::stack_frame_list::StackFrameList};
 
-actions!(debugger, [GoToSelectedAddress]);
+actions!(
+    debugger,
+    [
+        GoToSelectedAddress,
+        /// Restores the memory contents overwritten by the last memory write.
+        UndoMemoryWrite
+    ]
+);
 
 pub(crate) struct MemoryView {
     workspace: WeakEntity<Workspace>,
@@ -37,9 +44,33 @@ pub(crate) struct MemoryView {
     session: Entity<Session>,
     width_picker_handle: PopoverMenuHandle<ContextMenu>,
     is_writing_memory: bool,
+    edit_mode: MemoryEditMode,
+    last_write: Option<MemoryWrite>,
     open_context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
 }
 
+/// How text typed into the query bar is turned into bytes when writing memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MemoryEditMode {
+    Hex,
+    Ascii,
+}
+
+impl MemoryEditMode {
+    fn placeholder_text(&self) -> &'static str {
+        match self {
+            MemoryEditMode::Hex => "Write Hex Bytes to Selected Memory Range",
+            MemoryEditMode::Ascii => "Write ASCII Text to Selected Memory Range",
+        }
+    }
+}
+
+/// The contents of memory before our last write, kept around so it can be undone.
+struct MemoryWrite {
+    address: u64,
+    previous_content: Vec<u8>,
+}
+
 impl Focusable for MemoryView {
     fn focus_handle(&self, _: &ui::App) -> FocusHandle {
         self.focus_handle.clone()
@@ -183,6 +214,8 @@ impl MemoryView {
             session,
             width_picker_handle: Default::default(),
             is_writing_memory: true,
+            edit_mode: MemoryEditMode::Hex,
+            last_write: None,
             open_context_menu: None,
         };
         this.change_query_bar_mode(false, window, cx);
@@ -398,9 +431,10 @@ impl MemoryView {
             return;
         }
         if !self.is_writing_memory {
+            let placeholder_text = self.edit_mode.placeholder_text();
             self.query_editor.update(cx, |this, cx| {
                 this.clear(window, cx);
-                this.set_placeholder_text("Write to Selected Memory Range", window, cx);
+                this.set_placeholder_text(placeholder_text, window, cx);
             });
             self.is_writing_memory = true;
             self.query_editor.focus_handle(cx).focus(window);
@@ -456,58 +490,120 @@ impl MemoryView {
         })
     }
 
+    fn start_writing_memory(
+        &mut self,
+        edit_mode: MemoryEditMode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let should_return = self.session.update(cx, |session, cx| {
+            if !session
+                .capabilities()
+                .supports_write_memory_request
+                .unwrap_or_default()
+            {
+                let adapter_name = session.adapter();
+                // We cannot write memory with this adapter.
+                _ =
+                    self.workspace.update(cx, |this, cx| {
+                        this.toggle_status_toast(
+                        StatusToast::new(format!(
+                            "Debug Adapter `{adapter_name}` does not support writing to memory"
+                        ), cx, |this, cx| {
+                            cx.spawn(async move |this, cx| {
+                                cx.background_executor().timer(Duration::from_secs(2)).await;
+                                _ = this.update(cx, |_, cx| {
+                                    cx.emit(DismissEvent)
+                                });
+                            }).detach();
+                            this.icon(ToastIcon::new(IconName::XCircle).color(Color::Error))
+                        }),
+                        cx,
+                    );
+                    });
+                true
+            } else {
+                false
+            }
+        });
+        if should_return {
+            return;
+        }
+
+        self.edit_mode = edit_mode;
+        if self.is_writing_memory {
+            self.query_editor.update(cx, |this, cx| {
+                this.set_placeholder_text(edit_mode.placeholder_text(), window, cx);
+            });
+            self.query_editor.focus_handle(cx).focus(window);
+        } else {
+            self.change_query_bar_mode(true, window, cx);
+        }
+    }
+
+    fn edit_memory_at(
+        &mut self,
+        address: u64,
+        edit_mode: MemoryEditMode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        {
+            let mut view_state = self.view_state();
+            let is_selected = matches!(
+                &view_state.selection,
+                Some(SelectedMemoryRange::DragComplete(drag)) if drag.contains(address)
+            );
+            if !is_selected {
+                view_state.selection = Some(SelectedMemoryRange::DragComplete(Drag {
+                    start_address: address,
+                    end_address: address,
+                }));
+            }
+        }
+        self.start_writing_memory(edit_mode, window, cx);
+        cx.notify();
+    }
+
+    fn write_memory(&mut self, address: u64, data: Vec<u8>, cx: &mut Context<Self>) {
+        if data.is_empty() {
+            return;
+        }
+        let range = address..=address.saturating_add(data.len() as u64 - 1);
+        let previous_content: Option<Vec<u8>> = self.session.update(cx, |this, cx| {
+            this.read_memory(range, cx).map(|cell| cell.0).collect()
+        });
+        // Memory we haven't read (or couldn't read) can't be restored, so there's nothing to undo.
+        self.last_write = previous_content.map(|previous_content| MemoryWrite {
+            address,
+            previous_content,
+        });
+        self.session.update(cx, |this, cx| {
+            this.write_memory(address, &data, cx);
+        });
+    }
+
+    fn undo_memory_write(&mut self, _: &UndoMemoryWrite, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(last_write) = self.last_write.take() else {
+            return;
+        };
+        self.session.update(cx, |this, cx| {
+            this.write_memory(last_write.address, &last_write.previous_content, cx);
+        });
+        cx.notify();
+    }
+
     fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
         let selection = self.view_state().selection.clone();
         if let Some(SelectedMemoryRange::DragComplete(drag)) = selection {
             // Go into memory writing mode.
             if !self.is_writing_memory {
-                let should_return = self.session.update(cx, |session, cx| {
-                    if !session
-                        .capabilities()
-                        .supports_write_memory_request
-                        .unwrap_or_default()
-                    {
-                        let adapter_name = session.adapter();
-                        // We cannot write memory with this adapter.
-                        _ = self.workspace.update(cx, |this, cx| {
-                            this.toggle_status_toast(
-                                StatusToast::new(format!(
-                                    "Debug Adapter `{adapter_name}` does not support writing to memory"
-                                ), cx, |this, cx| {
-                                    cx.spawn(async move |this, cx| {
-                                        cx.background_executor().timer(Duration::from_secs(2)).await;
-                                        _ = this.update(cx, |_, cx| {
-                                            cx.emit(DismissEvent)
-                                        });
-                                    }).detach();
-                                    this.icon(ToastIcon::new(IconName::XCircle).color(Color::Error))
-                                }),
-                                cx,
-                            );
-                        });
-                        true
-                    } else {
-                        false
-                    }
-                });
-                if should_return {
-                    return;
-                }
-
-                self.change_query_bar_mode(true, window, cx);
+                self.start_writing_memory(MemoryEditMode::Hex, window, cx);
             } else if self.query_editor.focus_handle(cx).is_focused(window) {
-                let mut text = self.query_editor.read(cx).text(cx);
-                if text.chars().any(|c| !c.is_ascii_hexdigit()) {
-                    // Interpret this text as a string and oh-so-conveniently convert it.
-                    text = text.bytes().map(|byte| format!("{:02x}", byte)).collect();
+                let text = self.query_editor.read(cx).text(cx);
+                if let Some(data) = parse_memory_write(&text, self.edit_mode) {
+                    self.write_memory(*drag.memory_range().start(), data, cx);
                 }
-                self.session.update(cx, |this, cx| {
-                    let range = drag.memory_range();
-
-                    if let Ok(as_hex) = hex::decode(text) {
-                        this.write_memory(*range.start(), &as_hex, cx);
-                    }
-                });
                 self.change_query_bar_mode(false, window, cx);
             }
 
@@ -641,7 +737,13 @@ impl MemoryView {
                     ToggleDataBreakpoint { access_type: None }.boxed_clone(),
                 );
             }
-            menu.context(self.focus_handle.clone())
+            menu.separator()
+                .action_disabled_when(
+                    self.last_write.is_none(),
+                    "Undo Last Memory Write",
+                    UndoMemoryWrite.boxed_clone(),
+                )
+                .context(self.focus_handle.clone())
         });
 
         cx.focus_view(&context_menu, window);
@@ -678,6 +780,24 @@ impl ViewWidth {
     }
 }
 
+fn parse_memory_write(text: &str, edit_mode: MemoryEditMode) -> Option<Vec<u8>> {
+    match edit_mode {
+        MemoryEditMode::Ascii => Some(text.as_bytes().to_vec()),
+        MemoryEditMode::Hex => {
+            let digits = text
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect::<String>();
+            if digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                hex::decode(digits).ok()
+            } else {
+                // Interpret this text as a string and oh-so-conveniently convert it.
+                Some(text.as_bytes().to_vec())
+            }
+        }
+    }
+}
+
 static WIDTHS: [ViewWidth; 7] = [
     ViewWidth::new(1, "1 byte"),
     ViewWidth::new(2, "2 bytes"),
@@ -766,6 +886,22 @@ fn render_single_memory_view_line(
                             .when(cell.0.is_none(), |this| this.color(Color::Muted))
                             .size(ui::LabelSize::Small),
                         )
+                        .on_click({
+                            let weak = weak.clone();
+                            move |click, window, cx| {
+                                if click.click_count() < 2 {
+                                    return;
+                                }
+                                _ = weak.update(cx, |this, cx| {
+                                    this.edit_memory_at(
+                                        base_address + cell_ix as u64,
+                                        MemoryEditMode::Hex,
+                                        window,
+                                        cx,
+                                    );
+                                });
+                            }
+                        })
                         .on_drag(
                             Drag {
                                 start_address: base_address + cell_ix as u64,
@@ -827,7 +963,9 @@ fn render_single_memory_view_line(
                     } else {
                         '·'
                     };
+                    let weak = weak.clone();
                     div()
+                        .id(("memory-view-row-ascii-memory-cell", ix as u64))
                         .px_0p5()
                         .when_some(view_state.selection.as_ref(), |this, selection| {
                             this.when(selection.contains(base_address + ix as u64), |this| {
@@ -840,6 +978,19 @@ fn render_single_memory_view_line(
                                 .when(cell.0.is_none(), |this| this.color(Color::Muted))
                                 .size(ui::LabelSize::Small),
                         )
+                        .on_click(move |click, window, cx| {
+                            if click.click_count() < 2 {
+                                return;
+                            }
+                            _ = weak.update(cx, |this, cx| {
+                                this.edit_memory_at(
+                                    base_address + ix as u64,
+                                    MemoryEditMode::Ascii,
+                                    window,
+                                    cx,
+                                );
+                            });
+                        })
                 })),
         )
         .into_any()
@@ -866,6 +1017,7 @@ impl Render for MemoryView {
             .p_1()
             .on_action(cx.listener(Self::confirm))
             .on_action(cx.listener(Self::toggle_data_breakpoint))
+            .on_action(cx.listener(Self::undo_memory_write))
             .on_action(cx.listener(Self::page_down))
             .on_action(cx.listener(Self::page_up))
             .size_full()
@@ -933,3 +1085,29 @@ impl Render for MemoryView {
             )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_memory_write() {
+        assert_eq!(
+            parse_memory_write("DEADbeef", MemoryEditMode::Hex),
+            Some(vec![0xDE, 0xAD, 0xBE, 0xEF])
+        );
+        assert_eq!(
+            parse_memory_write("de ad be ef", MemoryEditMode::Hex),
+            Some(vec![0xDE, 0xAD, 0xBE, 0xEF])
+        );
+        assert_eq!(parse_memory_write("abc", MemoryEditMode::Hex), None);
+        assert_eq!(
+            parse_memory_write("hi!", MemoryEditMode::Hex),
+            Some(b"hi!".to_vec())
+        );
+        assert_eq!(
+            parse_memory_write("1234", MemoryEditMode::Ascii),
+            Some(b"1234".to_vec())
+        );
+    }
+}
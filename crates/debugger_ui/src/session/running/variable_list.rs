@@ -540,7 +540,7 @@ impl VariableList {
             };
 
             let variables_reference = state.parent_reference;
-            let Some(name) = var_path.leaf_name else {
+            let Some(name) = var_path.leaf_name.clone() else {
                 return;
             };
 
@@ -549,15 +549,23 @@ impl VariableList {
             };
 
             let value = editor.read(cx).text(cx);
+            let is_watcher = self
+                .entries
+                .iter()
+                .any(|entry| entry.path == var_path && entry.as_watcher().is_some());
 
             self.session.update(cx, |session, cx| {
-                session.set_variable_value(
-                    stack_frame_id,
-                    variables_reference,
-                    name.into(),
-                    value,
-                    cx,
-                )
+                if is_watcher {
+                    session.set_expression(stack_frame_id, name.into(), value, cx)
+                } else {
+                    session.set_variable_value(
+                        stack_frame_id,
+                        variables_reference,
+                        name.into(),
+                        value,
+                        cx,
+                    )
+                }
             });
         }
     }
@@ -656,23 +664,31 @@ impl VariableList {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let (supports_set_variable, supports_data_breakpoints, supports_go_to_memory) =
-            self.session.read_with(cx, |session, _| {
-                (
-                    session
-                        .capabilities()
-                        .supports_set_variable
-                        .unwrap_or_default(),
-                    session
-                        .capabilities()
-                        .supports_data_breakpoints
-                        .unwrap_or_default(),
-                    session
-                        .capabilities()
-                        .supports_read_memory_request
-                        .unwrap_or_default(),
-                )
-            });
+        let (
+            supports_set_variable,
+            supports_set_expression,
+            supports_data_breakpoints,
+            supports_go_to_memory,
+        ) = self.session.read_with(cx, |session, _| {
+            (
+                session
+                    .capabilities()
+                    .supports_set_variable
+                    .unwrap_or_default(),
+                session
+                    .capabilities()
+                    .supports_set_expression
+                    .unwrap_or_default(),
+                session
+                    .capabilities()
+                    .supports_data_breakpoints
+                    .unwrap_or_default(),
+                session
+                    .capabilities()
+                    .supports_read_memory_request
+                    .unwrap_or_default(),
+            )
+        });
         let can_toggle_data_breakpoint = entry
             .as_variable()
             .filter(|_| supports_data_breakpoints)
@@ -747,7 +763,7 @@ impl VariableList {
                     .when(entry.as_watcher().is_some(), |menu| {
                         menu.action("Copy Name", CopyVariableName.boxed_clone())
                             .action("Copy Value", CopyVariableValue.boxed_clone())
-                            .when(supports_set_variable, |menu| {
+                            .when(supports_set_expression, |menu| {
                                 menu.action("Edit Value", EditVariable.boxed_clone())
                             })
                             .action("Remove Watch", RemoveWatch.boxed_clone())
@@ -1120,35 +1136,32 @@ impl VariableList {
                     {
                         this.child(div().size_full().px_2().child(editor.clone()))
                     } else {
+                        let capabilities = self.session.read(cx).capabilities();
+                        let is_editable = if entry.as_watcher().is_some() {
+                            capabilities.supports_set_expression.unwrap_or_default()
+                        } else {
+                            capabilities.supports_set_variable.unwrap_or_default()
+                        };
                         this.text_color(cx.theme().colors().text_muted)
-                            .when(
-                                !self.disabled
-                                    && self
-                                        .session
-                                        .read(cx)
-                                        .capabilities()
-                                        .supports_set_variable
-                                        .unwrap_or_default(),
-                                |this| {
-                                    let path = entry.path.clone();
-                                    let variable_value = value.clone();
-                                    this.on_click(cx.listener(
-                                        move |this, click: &ClickEvent, window, cx| {
-                                            if click.click_count() < 2 {
-                                                return;
-                                            }
-                                            let editor = Self::create_variable_editor(
-                                                &variable_value,
-                                                window,
-                                                cx,
-                                            );
-                                            this.edited_path = Some((path.clone(), editor));
-
-                                            cx.notify();
-                                        },
-                                    ))
-                                },
-                            )
+                            .when(!self.disabled && is_editable, |this| {
+                                let path = entry.path.clone();
+                                let variable_value = value.clone();
+                                this.on_click(cx.listener(
+                                    move |this, click: &ClickEvent, window, cx| {
+                                        if click.click_count() < 2 {
+                                            return;
+                                        }
+                                        let editor = Self::create_variable_editor(
+                                            &variable_value,
+                                            window,
+                                            cx,
+                                        );
+                                        this.edited_path = Some((path.clone(), editor));
+
+                                        cx.notify();
+                                    },
+                                ))
+                            })
                             .child(
                                 Label::new(format!("=  {}", &value))
                                     .single_line()
@@ -9,8 +9,8 @@ use dap::{
     adapters::DebugTaskDefinition,
     client::SessionId,
     requests::{
-        Continue, Disconnect, Launch, Next, RunInTerminal, SetBreakpoints, StackTrace,
-        StartDebugging, StepBack, StepIn, StepOut, Threads,
+        Continue, Disconnect, Launch, Next, ReverseContinue, RunInTerminal, SetBreakpoints,
+        StackTrace, StartDebugging, StepBack, StepIn, StepOut, Threads,
     },
 };
 use editor::{
@@ -994,6 +994,20 @@ async fn test_debug_panel_item_thread_status_reset_on_failure(
         })
     });
 
+    client.on_request::<ReverseContinue, _>(move |_, _| {
+        Err(ErrorResponse {
+            error: Some(dap::Message {
+                id: 1,
+                format: "error".into(),
+                variables: None,
+                send_telemetry: None,
+                show_user: None,
+                url: None,
+                url_label: None,
+            }),
+        })
+    });
+
     client.on_request::<Continue, _>(move |_, _| {
         Err(ErrorResponse {
             error: Some(dap::Message {
@@ -1032,6 +1046,7 @@ async fn test_debug_panel_item_thread_status_reset_on_failure(
         "step_over",
         "continue_thread",
         "step_back",
+        "reverse_continue",
         "step_in",
         "step_out",
     ] {
@@ -1039,6 +1054,7 @@ async fn test_debug_panel_item_thread_status_reset_on_failure(
             "step_over" => running_state.step_over(cx),
             "continue_thread" => running_state.continue_thread(cx),
             "step_back" => running_state.step_back(cx),
+            "reverse_continue" => running_state.reverse_continue(cx),
             "step_in" => running_state.step_in(cx),
             "step_out" => running_state.step_out(cx),
             _ => unreachable!(),
@@ -1051,7 +1067,7 @@ async fn test_debug_panel_item_thread_status_reset_on_failure(
                     .thread_status(cx)
                     .expect("There should be an active thread selected"),
                 match *operation {
-                    "continue_thread" => ThreadStatus::Running,
+                    "continue_thread" | "reverse_continue" => ThreadStatus::Running,
                     _ => ThreadStatus::Stepping,
                 },
                 "Thread status was not set to correct intermediate state after {} request",
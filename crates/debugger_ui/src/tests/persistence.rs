@@ -1,11 +1,11 @@
-use std::iter::zip;
+use std::{iter::zip, path::Path};
 
 use crate::{
     debugger_panel::DebugPanel,
-    persistence::SerializedPaneLayout,
-    tests::{init_test, init_test_workspace, start_debug_session},
+    persistence::{self, SerializedPaneLayout},
+    tests::{init_test, init_test_workspace, start_debug_session, start_debug_session_with},
 };
-use dap::{StoppedEvent, StoppedEventReason, messages::Events};
+use dap::{StoppedEvent, StoppedEventReason, adapters::DebugTaskDefinition, messages::Events};
 use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
 use project::{FakeFs, Project};
 use serde_json::json;
@@ -129,3 +129,53 @@ async fn test_invert_axis_on_panel_position_change(
         }
     }
 }
+
+#[gpui::test]
+async fn test_launched_sessions_are_recorded_in_history(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/history-project"),
+        json!({
+            "main.rs": "fn main() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/history-project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    start_debug_session_with(
+        &workspace,
+        cx,
+        DebugTaskDefinition {
+            adapter: "fake-adapter".into(),
+            label: "history test".into(),
+            config: json!({
+                "request": "launch",
+                "program": "main",
+            }),
+            tcp_connection: None,
+        },
+        |_| {},
+    )
+    .unwrap();
+
+    cx.run_until_parked();
+
+    let entry = persistence::debug_session_history()
+        .into_iter()
+        .find(|entry| entry.scenario.label.as_ref() == "history test")
+        .expect("launched session should be recorded");
+    assert_eq!(entry.scenario.adapter.as_ref(), "fake-adapter");
+    assert_eq!(entry.scenario.config["program"], json!("main"));
+    assert_eq!(
+        entry.worktree_path.as_deref(),
+        Some(Path::new(path!("/history-project")))
+    );
+}
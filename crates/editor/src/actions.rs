@@ -389,6 +389,8 @@ actions!(
         ClearFlycheck,
         /// Confirms the rename operation.
         ConfirmRename,
+        /// Confirms the rename operation, reviewing its edits before applying them.
+        ConfirmRenameWithPreview,
         /// Confirms completion by inserting at cursor.
         ConfirmCompletionInsert,
         /// Confirms completion by replacing existing text.
@@ -625,6 +627,8 @@ actions!(
         NewlineBelow,
         /// Navigates to the next edit prediction.
         NextEditPrediction,
+        /// Shows the next window of a file opened in large-file mode.
+        NextLargeFileWindow,
         /// Scrolls to the next screen.
         NextScreen,
         /// Goes to the next snippet tabstop if one exists.
@@ -662,6 +666,8 @@ actions!(
         Paste,
         /// Navigates to the previous edit prediction.
         PreviousEditPrediction,
+        /// Shows the previous window of a file opened in large-file mode.
+        PreviousLargeFileWindow,
         /// Goes to the previous snippet tabstop if one exists.
         PreviousSnippetTabstop,
         /// Redoes the last undone edit.
@@ -772,6 +778,8 @@ actions!(
         Tab,
         /// Removes a tab character or outdents.
         Backtab,
+        /// Adds or removes a bookmark at the current line.
+        ToggleBookmark,
         /// Toggles a breakpoint at the current line.
         ToggleBreakpoint,
         /// Toggles the case of selected text.
@@ -0,0 +1,77 @@
+use collections::HashSet;
+use gpui::{App, Context, Entity, Window};
+use language::{Buffer, Point};
+use multi_buffer::Anchor;
+use theme::ActiveTheme as _;
+
+use crate::{Editor, ToPoint as _, actions::ToggleBookmark};
+
+struct BookmarkGutterHighlight;
+
+impl Editor {
+    pub(super) fn refresh_bookmark_highlights(&mut self, cx: &mut Context<Self>) {
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return;
+        };
+        let multi_buffer = self.buffer.read(cx);
+        let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+        let mut ranges = Vec::new();
+        for buffer in multi_buffer.all_buffers() {
+            for (_, position) in bookmark_store.read(cx).bookmarks_for_buffer(&buffer, cx) {
+                if let Some(anchor) = multi_buffer.buffer_anchor_to_anchor(&buffer, position, cx) {
+                    ranges.push(anchor..anchor);
+                }
+            }
+        }
+        ranges.sort_by(|a, b| a.start.cmp(&b.start, &multi_buffer_snapshot));
+        self.highlight_gutter::<BookmarkGutterHighlight>(ranges, |cx| cx.theme().status().info, cx);
+    }
+
+    pub fn toggle_bookmark(&mut self, _: &ToggleBookmark, _: &mut Window, cx: &mut Context<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let cursors = self
+            .selections
+            .disjoint_anchors_arc()
+            .iter()
+            .map(|selection| selection.head())
+            .collect::<Vec<_>>();
+        let mut toggled_rows = HashSet::default();
+        for cursor in cursors {
+            if toggled_rows.insert(cursor.to_point(&snapshot).row) {
+                self.toggle_bookmark_at_anchor(cursor, cx);
+            }
+        }
+    }
+
+    pub(super) fn toggle_bookmark_at_anchor(&mut self, anchor: Anchor, cx: &mut Context<Self>) {
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return;
+        };
+        let Some((buffer, point)) = self.buffer_point_for_anchor(anchor, cx) else {
+            return;
+        };
+        bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.toggle_bookmark(&buffer, point.row, cx);
+        });
+    }
+
+    pub(super) fn has_bookmark_at_anchor(&self, anchor: Anchor, cx: &App) -> bool {
+        let Some(bookmark_store) = self.bookmark_store.as_ref() else {
+            return false;
+        };
+        self.buffer_point_for_anchor(anchor, cx)
+            .is_some_and(|(buffer, point)| {
+                bookmark_store
+                    .read(cx)
+                    .bookmark_at_row(&buffer, point.row, cx)
+                    .is_some()
+            })
+    }
+
+    fn buffer_point_for_anchor(&self, anchor: Anchor, cx: &App) -> Option<(Entity<Buffer>, Point)> {
+        let multi_buffer = self.buffer.read(cx);
+        let point = anchor.to_point(&multi_buffer.snapshot(cx));
+        let (buffer, point, _) = multi_buffer.point_to_buffer_point(point, cx)?;
+        Some((buffer, point))
+    }
+}
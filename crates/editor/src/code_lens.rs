@@ -0,0 +1,395 @@
+use std::{collections::BTreeMap, ops::Range, sync::Arc, time::Duration};
+
+use collections::{HashMap, HashSet};
+use futures::future::join_all;
+use gpui::{AnyElement, Entity, Task, WeakEntity};
+use language::{Buffer, Point};
+use multi_buffer::Anchor;
+use project::{CodeAction, CodeLensCommand, LspAction};
+use settings::Settings as _;
+use text::{BufferId, ToOffset as _, ToPoint as _};
+use ui::{App, Context, Window, prelude::*};
+use util::ResultExt as _;
+
+use crate::{
+    CodeActionSource, Editor, EditorSettings, FindAllReferences, ToggleCodeActions,
+    display_map::{
+        BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId,
+        ToDisplayPoint as _,
+    },
+    editor_settings::CodeLensDisplayMode,
+};
+
+const FETCH_CODE_LENS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(150);
+
+#[derive(Debug)]
+pub(super) struct CodeLensData {
+    display_mode: CodeLensDisplayMode,
+    buffer_lenses: HashMap<BufferId, BufferCodeLenses>,
+}
+
+#[derive(Debug, Default)]
+struct BufferCodeLenses {
+    /// The buffer row and titles of each displayed line of lenses.
+    lines: Vec<(u32, Vec<String>)>,
+    block_ids: Vec<CustomBlockId>,
+}
+
+impl CodeLensData {
+    pub fn new(cx: &App) -> Self {
+        Self {
+            display_mode: EditorSettings::get_global(cx).code_lens,
+            buffer_lenses: HashMap::default(),
+        }
+    }
+}
+
+fn lens_title(action: &CodeAction) -> Option<&str> {
+    match &action.lsp_action {
+        LspAction::CodeLens(lens) => Some(lens.command.as_ref()?.title.as_str()),
+        _ => None,
+    }
+}
+
+impl Editor {
+    pub(super) fn refresh_code_lens(
+        &mut self,
+        buffer_id: Option<BufferId>,
+        _: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.ignore_lsp_data() {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        if self
+            .code_lens
+            .as_ref()
+            .is_none_or(|code_lens| code_lens.display_mode != CodeLensDisplayMode::Inline)
+        {
+            return;
+        }
+
+        let mut visible_ranges =
+            HashMap::<BufferId, (Entity<Buffer>, Vec<Range<usize>>)>::default();
+        for (buffer, _, visible_range) in self.visible_excerpts(true, cx).into_values() {
+            let editor_buffer_id = buffer.read(cx).remote_id();
+            if buffer_id.is_none_or(|buffer_id| buffer_id == editor_buffer_id)
+                && self.registered_buffers.contains_key(&editor_buffer_id)
+            {
+                visible_ranges
+                    .entry(editor_buffer_id)
+                    .or_insert_with(|| (buffer, Vec::new()))
+                    .1
+                    .push(visible_range);
+            }
+        }
+
+        let lens_tasks = project.read(cx).lsp_store().update(cx, |lsp_store, cx| {
+            visible_ranges
+                .into_values()
+                .map(|(buffer, visible_ranges)| {
+                    let lens_task = lsp_store.code_lens_actions(&buffer, cx);
+                    (buffer, visible_ranges, lens_task)
+                })
+                .collect::<Vec<_>>()
+        });
+        if lens_tasks.is_empty() {
+            self.refresh_code_lens_task = Task::ready(());
+            return;
+        }
+
+        self.refresh_code_lens_task = cx.spawn(async move |editor, cx| {
+            cx.background_executor()
+                .timer(FETCH_CODE_LENS_DEBOUNCE_TIMEOUT)
+                .await;
+
+            for (buffer, visible_ranges, lens_task) in lens_tasks {
+                let lenses = match lens_task.await {
+                    Ok(lenses) => lenses.unwrap_or_default(),
+                    Err(e) => {
+                        log::error!("Failed to fetch code lens: {e:#}");
+                        continue;
+                    }
+                };
+
+                // Only the visible lenses get resolved, as resolving may be expensive for the
+                // language server (e.g. counting references).
+                let Ok(resolve_tasks) = project.update(cx, |project, cx| {
+                    let snapshot = buffer.read(cx).snapshot();
+                    lenses
+                        .into_iter()
+                        .map(|lens| {
+                            let offset = lens.range.start.to_offset(&snapshot);
+                            let visible =
+                                visible_ranges.iter().any(|range| range.contains(&offset));
+                            if visible && lens_title(&lens).is_none() {
+                                project.resolve_code_lens(&buffer, lens, cx)
+                            } else {
+                                Task::ready(Ok(lens))
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                }) else {
+                    return;
+                };
+                let lenses = join_all(resolve_tasks)
+                    .await
+                    .into_iter()
+                    .filter_map(|lens| lens.log_err())
+                    .collect::<Vec<_>>();
+
+                if editor
+                    .update(cx, |editor, cx| {
+                        editor.display_code_lenses(&buffer, lenses, cx)
+                    })
+                    .is_err()
+                {
+                    return;
+                }
+            }
+        });
+    }
+
+    fn display_code_lenses(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        lenses: Vec<CodeAction>,
+        cx: &mut Context<Self>,
+    ) {
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let buffer_id = buffer_snapshot.remote_id();
+
+        let lsp_store = self
+            .project
+            .as_ref()
+            .map(|project| project.read(cx).lsp_store());
+        let mut lines = BTreeMap::<u32, Vec<CodeAction>>::new();
+        for lens in lenses {
+            let can_run = lsp_store
+                .as_ref()
+                .is_some_and(|lsp_store| lsp_store.read(cx).code_lens_command(&lens).is_some());
+            if can_run && lens_title(&lens).is_some() {
+                let row = lens.range.start.to_point(&buffer_snapshot).row;
+                lines.entry(row).or_default().push(lens);
+            }
+        }
+        let line_titles = lines
+            .iter()
+            .map(|(row, lenses)| {
+                let titles = lenses
+                    .iter()
+                    .filter_map(|lens| lens_title(lens).map(ToOwned::to_owned))
+                    .collect();
+                (*row, titles)
+            })
+            .collect::<Vec<_>>();
+
+        let Some(code_lens) = self.code_lens.as_mut() else {
+            return;
+        };
+        let buffer_lenses = code_lens.buffer_lenses.entry(buffer_id).or_default();
+        if buffer_lenses.lines == line_titles {
+            return;
+        }
+        buffer_lenses.lines = line_titles;
+        let old_block_ids = std::mem::take(&mut buffer_lenses.block_ids);
+
+        let multi_buffer_snapshot = self.buffer.read(cx).snapshot(cx);
+        let excerpts = self.buffer.read(cx).excerpts_for_buffer(buffer_id, cx);
+        let editor = cx.weak_entity();
+        let mut blocks = Vec::new();
+        for (row, lenses) in lines {
+            let indent = buffer_snapshot.indent_size_for_line(row);
+            let position = buffer_snapshot.anchor_before(Point::new(row, indent.len));
+            let Some(anchor) = excerpts.iter().find_map(|(excerpt_id, excerpt_range)| {
+                let contains_position = excerpt_range
+                    .context
+                    .start
+                    .cmp(&position, &buffer_snapshot)
+                    .is_le()
+                    && excerpt_range
+                        .context
+                        .end
+                        .cmp(&position, &buffer_snapshot)
+                        .is_ge();
+                contains_position
+                    .then(|| multi_buffer_snapshot.anchor_in_excerpt(*excerpt_id, position))
+                    .flatten()
+            }) else {
+                continue;
+            };
+
+            let buffer = buffer.clone();
+            let editor = editor.clone();
+            blocks.push(BlockProperties {
+                placement: BlockPlacement::Above(anchor),
+                height: Some(1),
+                style: BlockStyle::Flex,
+                render: Arc::new(move |cx| {
+                    render_code_lens_line(&lenses, anchor, &buffer, editor.clone(), cx)
+                }),
+                priority: 0,
+            });
+        }
+        let new_block_ids = self.insert_blocks(blocks, None, cx);
+        if let Some(buffer_lenses) = self
+            .code_lens
+            .as_mut()
+            .and_then(|code_lens| code_lens.buffer_lenses.get_mut(&buffer_id))
+        {
+            buffer_lenses.block_ids = new_block_ids;
+        }
+        self.remove_blocks(old_block_ids.into_iter().collect(), None, cx);
+    }
+
+    pub(super) fn code_lens_display_mode_updated(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let new_display_mode = EditorSettings::get_global(cx).code_lens;
+        let Some(code_lens) = self.code_lens.as_mut() else {
+            return;
+        };
+        if code_lens.display_mode == new_display_mode {
+            return;
+        }
+        code_lens.display_mode = new_display_mode;
+        let block_ids = code_lens
+            .buffer_lenses
+            .drain()
+            .flat_map(|(_, buffer_lenses)| buffer_lenses.block_ids)
+            .collect::<HashSet<_>>();
+        self.remove_blocks(block_ids, None, cx);
+        self.refresh_code_lens(None, window, cx);
+    }
+
+    /// Runs a code lens: commands the language server can execute are applied like a code action,
+    /// while client-side commands are mapped to the editor's own reference search and runnables.
+    fn run_code_lens(
+        &mut self,
+        buffer: Entity<Buffer>,
+        lens: CodeAction,
+        position: Anchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some(title) = lens_title(&lens).map(ToOwned::to_owned) else {
+            return;
+        };
+
+        let Some(command) = project
+            .read(cx)
+            .lsp_store()
+            .read(cx)
+            .code_lens_command(&lens)
+        else {
+            return;
+        };
+        match command {
+            CodeLensCommand::Server => {
+                let Some(workspace) = self.workspace() else {
+                    return;
+                };
+                let apply_code_action = project.update(cx, |project, cx| {
+                    project.apply_code_action(buffer, lens, true, cx)
+                });
+                let workspace = workspace.downgrade();
+                cx.spawn_in(window, async move |editor, cx| {
+                    let project_transaction = apply_code_action.await?;
+                    Self::open_project_transaction(
+                        &editor,
+                        workspace,
+                        project_transaction,
+                        title,
+                        cx,
+                    )
+                    .await
+                })
+                .detach_and_log_err(cx);
+            }
+            CodeLensCommand::References => {
+                self.move_to_code_lens(position, window, cx);
+                if let Some(task) =
+                    self.find_all_references(&FindAllReferences::default(), window, cx)
+                {
+                    task.detach_and_log_err(cx);
+                }
+            }
+            CodeLensCommand::Runnable => {
+                self.move_to_code_lens(position, window, cx);
+                let row = position.to_display_point(&self.snapshot(window, cx)).row();
+                self.toggle_code_actions(
+                    &ToggleCodeActions {
+                        deployed_from: Some(CodeActionSource::RunMenu(row)),
+                        quick_launch: false,
+                    },
+                    window,
+                    cx,
+                );
+            }
+        }
+    }
+
+    fn move_to_code_lens(&mut self, position: Anchor, window: &mut Window, cx: &mut Context<Self>) {
+        window.focus(&self.focus_handle);
+        self.change_selections(Default::default(), window, cx, |selections| {
+            selections.select_anchor_ranges([position..position])
+        });
+    }
+}
+
+fn render_code_lens_line(
+    lenses: &[CodeAction],
+    position: Anchor,
+    buffer: &Entity<Buffer>,
+    editor: WeakEntity<Editor>,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    let mut line = h_flex()
+        .id(cx.block_id)
+        .h(cx.line_height)
+        .pl(cx.anchor_x)
+        .items_center()
+        .gap_0p5();
+    for (ix, lens) in lenses.iter().enumerate() {
+        let Some(title) = lens_title(lens) else {
+            continue;
+        };
+        if ix > 0 {
+            line = line.child(
+                Label::new("|")
+                    .size(LabelSize::Small)
+                    .color(Color::Disabled),
+            );
+        }
+        let lens = lens.clone();
+        let buffer = buffer.clone();
+        let editor = editor.clone();
+        line = line.child(
+            Button::new(("code-lens", ix), title.to_owned())
+                .label_size(LabelSize::Small)
+                .color(Color::Muted)
+                .on_click(move |_, window, cx| {
+                    editor
+                        .update(cx, |editor, cx| {
+                            editor.run_code_lens(
+                                buffer.clone(),
+                                lens.clone(),
+                                position,
+                                window,
+                                cx,
+                            );
+                        })
+                        .ok();
+                }),
+        );
+    }
+    line.into_any_element()
+}
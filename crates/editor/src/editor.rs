@@ -13,9 +13,11 @@
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides its behavior.
 pub mod actions;
 pub mod blink_manager;
+mod bookmarks;
 mod bracket_colorization;
 mod clangd_ext;
 pub mod code_context_menus;
+mod code_lens;
 pub mod display_map;
 mod editor_settings;
 mod element;
@@ -27,12 +29,15 @@ mod indent_guides;
 mod inlays;
 pub mod items;
 mod jsx_tag_auto_close;
+mod large_file;
 mod linked_editing_ranges;
 mod lsp_colors;
 mod lsp_ext;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
+mod persistent_undo;
+mod rename_preview;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
@@ -53,8 +58,8 @@ pub(crate) use actions::*;
 pub use display_map::{ChunkRenderer, ChunkRendererContext, DisplayPoint, FoldPlaceholder};
 pub use edit_prediction_types::Direction;
 pub use editor_settings::{
-    CurrentLineHighlight, DocumentColorsRenderMode, EditorSettings, HideMouseMode,
-    ScrollBeyondLastLine, ScrollbarAxes, SearchSettings, ShowMinimap,
+    CodeLensDisplayMode, CurrentLineHighlight, DocumentColorsRenderMode, EditorSettings,
+    HideMouseMode, ScrollBeyondLastLine, ScrollbarAxes, SearchSettings, ShowMinimap,
 };
 pub use element::{
     CursorLayout, EditorElement, HighlightedRange, HighlightedRangeLine, PointForPosition,
@@ -88,6 +93,7 @@ use code_context_menus::{
     AvailableCodeAction, CodeActionContents, CodeActionsItem, CodeActionsMenu, CodeContextMenu,
     CompletionsMenu, ContextMenuOrigin,
 };
+use code_lens::CodeLensData;
 use collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use convert_case::{Case, Casing};
 use dap::TelemetrySpawnLocation;
@@ -147,7 +153,8 @@ use project::{
     BreakpointWithPosition, CodeAction, Completion, CompletionDisplayOptions, CompletionIntent,
     CompletionResponse, CompletionSource, DisableAiSettings, DocumentHighlight, InlayHint, InlayId,
     InvalidationStrategy, Location, LocationLink, LspAction, PrepareRenameResponse, Project,
-    ProjectItem, ProjectPath, ProjectTransaction, TaskSourceKind,
+    ProjectItem, ProjectPath, ProjectTransaction, TaskSourceKind, WorkspaceEditPreview,
+    bookmark_store::BookmarkStore,
     debugger::{
         breakpoint_store::{
             Breakpoint, BreakpointEditAction, BreakpointSessionState, BreakpointState,
@@ -162,7 +169,7 @@ use project::{
     },
     project_settings::{DiagnosticSeverity, GoToDiagnosticSeverityFilter, ProjectSettings},
 };
-use rand::seq::SliceRandom;
+use rand::{Rng as _, seq::SliceRandom};
 use rpc::{ErrorCode, ErrorExt, proto::PeerId};
 use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager};
 use selections_collection::{MutableSelectionsCollection, SelectionsCollection};
@@ -216,6 +223,7 @@ use crate::{
         InlineValueCache,
         inlay_hints::{LspInlayHintData, inlay_hint_settings},
     },
+    rename_preview::RenamePreview,
     scroll::{ScrollOffset, ScrollPixelOffset},
     selections_collection::resolve_selections_wrapping_blocks,
     signature_help::{SignatureHelpHiddenBy, SignatureHelpState},
@@ -325,6 +333,8 @@ pub fn init(cx: &mut App) {
     workspace::register_project_item::<Editor>(cx);
     workspace::FollowableViewRegistry::register::<Editor>(cx);
     workspace::register_serializable_item::<Editor>(cx);
+    persistent_undo::init(cx);
+    rename_preview::init(cx);
 
     cx.observe_new(
         |workspace: &mut Workspace, _: Option<&mut Window>, _cx: &mut Context<Workspace>| {
@@ -1174,6 +1184,7 @@ pub struct Editor {
     tasks: BTreeMap<(BufferId, BufferRow), RunnableTasks>,
     tasks_update_task: Option<Task<()>>,
     breakpoint_store: Option<Entity<BreakpointStore>>,
+    bookmark_store: Option<Entity<BookmarkStore>>,
     gutter_breakpoint_indicator: (Option<PhantomBreakpointIndicator>, Option<Task<()>>),
     hovered_diff_hunk_row: Option<DisplayRow>,
     pull_diagnostics_task: Task<()>,
@@ -1203,6 +1214,8 @@ pub struct Editor {
     colors: Option<LspColorData>,
     post_scroll_update: Task<()>,
     refresh_colors_task: Task<()>,
+    code_lens: Option<CodeLensData>,
+    refresh_code_lens_task: Task<()>,
     inlay_hints: Option<LspInlayHintData>,
     folding_newlines: Task<()>,
     select_next_is_case_sensitive: Option<bool>,
@@ -1551,6 +1564,15 @@ struct SnippetState {
     ranges: Vec<Vec<Range<Anchor>>>,
     active_index: usize,
     choices: Vec<Option<Vec<String>>>,
+    transformed_mirrors: Vec<TransformedMirror>,
+}
+
+/// A range of a snippet that is kept in sync with a tabstop's text through a transform.
+#[derive(Debug)]
+struct TransformedMirror {
+    source: Range<Anchor>,
+    mirror: Range<Anchor>,
+    transform: snippet::Transform,
 }
 
 #[doc(hidden)]
@@ -1966,7 +1988,7 @@ impl Editor {
                 window,
                 |editor, _, event, window, cx| match event {
                     project::Event::RefreshCodeLens => {
-                        // we always query lens with actions, without storing them, always refreshing them
+                        editor.refresh_code_lens(None, window, cx);
                     }
                     project::Event::RefreshInlayHints {
                         server_id,
@@ -2178,6 +2200,10 @@ impl Editor {
             (EditorMode::Full { .. }, Some(project)) => Some(project.read(cx).breakpoint_store()),
             _ => None,
         };
+        let bookmark_store = match (&mode, project.as_ref()) {
+            (EditorMode::Full { .. }, Some(project)) => Some(project.read(cx).bookmark_store()),
+            _ => None,
+        };
 
         let mut code_action_providers = Vec::new();
         let mut load_uncommitted_diff = None;
@@ -2340,6 +2366,7 @@ impl Editor {
             tasks: BTreeMap::default(),
 
             breakpoint_store,
+            bookmark_store,
             gutter_breakpoint_indicator: (None, None),
             hovered_diff_hunk_row: None,
             _subscriptions: (!is_minimap)
@@ -2372,6 +2399,8 @@ impl Editor {
             pull_diagnostics_background_task: Task::ready(()),
             colors: None,
             refresh_colors_task: Task::ready(()),
+            code_lens: None,
+            refresh_code_lens_task: Task::ready(()),
             inlay_hints: None,
             next_color_inlay_id: 0,
             post_scroll_update: Task::ready(()),
@@ -2422,6 +2451,14 @@ impl Editor {
                     cx.notify();
                 }));
         }
+        if let Some(bookmark_store) = editor.bookmark_store.as_ref() {
+            editor
+                ._subscriptions
+                .push(cx.observe(bookmark_store, |editor, _, cx| {
+                    editor.refresh_bookmark_highlights(cx);
+                }));
+            editor.refresh_bookmark_highlights(cx);
+        }
         editor.tasks_update_task = Some(editor.refresh_runnables(window, cx));
         editor._subscriptions.extend(project_subscriptions);
 
@@ -2541,6 +2578,7 @@ impl Editor {
             editor.minimap =
                 editor.create_minimap(EditorSettings::get_global(cx).minimap, window, cx);
             editor.colors = Some(LspColorData::new(cx));
+            editor.code_lens = Some(CodeLensData::new(cx));
             editor.inlay_hints = Some(LspInlayHintData::new(inlay_hint_settings));
 
             if let Some(buffer) = multi_buffer.read(cx).as_singleton() {
@@ -6072,6 +6110,11 @@ impl Editor {
         cx.stop_propagation();
 
         let buffer_handle = completions_menu.buffer.clone();
+        let snippet_variables = if completion.is_snippet() {
+            self.snippet_variables(cx)
+        } else {
+            HashMap::default()
+        };
 
         let CompletionEdit {
             new_text,
@@ -6082,6 +6125,7 @@ impl Editor {
             intent,
             &buffer_handle,
             &completions_menu.initial_position.text_anchor,
+            &snippet_variables,
             cx,
         );
 
@@ -8428,6 +8472,15 @@ impl Editor {
             BreakpointState::Disabled => Some("Enable"),
         });
 
+        let toggle_bookmark_msg = self.bookmark_store.as_ref().map(|_| {
+            if self.has_bookmark_at_anchor(anchor, cx) {
+                "Remove Bookmark"
+            } else {
+                "Add Bookmark"
+            }
+        });
+        let bookmark_editor = weak_editor.clone();
+
         let (anchor, breakpoint) =
             breakpoint.unwrap_or_else(|| (anchor, Arc::new(Breakpoint::new_standard())));
 
@@ -8533,6 +8586,15 @@ impl Editor {
                         })
                         .log_err();
                 })
+                .when_some(toggle_bookmark_msg, |this, msg| {
+                    this.separator().entry(msg, None, move |_window, cx| {
+                        bookmark_editor
+                            .update(cx, |this, cx| {
+                                this.toggle_bookmark_at_anchor(anchor, cx);
+                            })
+                            .log_err();
+                    })
+                })
         })
     }
 
@@ -10041,7 +10103,7 @@ impl Editor {
             choices: Option<Vec<String>>,
         }
 
-        let tabstops = self.buffer.update(cx, |buffer, cx| {
+        let (tabstops, transformed_mirrors) = self.buffer.update(cx, |buffer, cx| {
             let snippet_text: Arc<str> = snippet.text.clone().into();
             let edits = insertion_ranges
                 .iter()
@@ -10086,7 +10148,36 @@ impl Editor {
                         choices: tabstop.choices.clone(),
                     }
                 })
-                .collect::<Vec<_>>()
+                .collect::<Vec<_>>();
+
+            let transformed_mirrors = snippet
+                .tabstops
+                .iter()
+                .filter_map(|tabstop| Some((tabstop.ranges.first()?, &tabstop.transformed_mirrors)))
+                .flat_map(|(source_range, mirrors)| {
+                    mirrors.iter().flat_map(move |(mirror_range, transform)| {
+                        let mut delta = 0_isize;
+                        insertion_ranges.iter().map(move |insertion_range| {
+                            let insertion_start = insertion_range.start + delta;
+                            delta += snippet.text.len() as isize
+                                - (insertion_range.end - insertion_range.start) as isize;
+
+                            let anchor_range = |range: &Range<isize>| {
+                                let start = (insertion_start + range.start).min(snapshot.len());
+                                let end = (insertion_start + range.end).min(snapshot.len());
+                                snapshot.anchor_before(start)..snapshot.anchor_after(end)
+                            };
+                            TransformedMirror {
+                                source: anchor_range(source_range),
+                                mirror: anchor_range(mirror_range),
+                                transform: transform.clone(),
+                            }
+                        })
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            (tabstops, transformed_mirrors)
         });
         if let Some(tabstop) = tabstops.first() {
             self.change_selections(Default::default(), window, cx, |s| {
@@ -10118,6 +10209,7 @@ impl Editor {
                     active_index: 0,
                     ranges,
                     choices,
+                    transformed_mirrors,
                 });
             }
 
@@ -10183,6 +10275,37 @@ impl Editor {
         Ok(())
     }
 
+    /// Updates the transformed mirrors of active snippets to match their tabstops' text.
+    fn apply_snippet_transforms(&mut self, cx: &mut Context<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let edits = self
+            .snippet_stack
+            .iter()
+            .flat_map(|snippet| &snippet.transformed_mirrors)
+            .filter_map(|mirror| {
+                let source_text = snapshot
+                    .text_for_range(mirror.source.to_offset(&snapshot))
+                    .collect::<String>();
+                let new_text = mirror.transform.apply(&source_text);
+                let mirror_range = mirror.mirror.to_offset(&snapshot);
+                let old_text = snapshot
+                    .text_for_range(mirror_range.clone())
+                    .collect::<String>();
+                (old_text != new_text).then_some((mirror_range, new_text))
+            })
+            .collect::<Vec<_>>();
+        if !edits.is_empty() {
+            self.buffer.update(cx, |buffer, cx| {
+                // The mirrors are updated in the same undo step as the edit to their tabstops.
+                let edit_transaction = buffer.last_transaction_id(cx);
+                buffer.edit(edits, None, cx);
+                if let Some(edit_transaction) = edit_transaction {
+                    buffer.group_until_transaction(edit_transaction, cx);
+                }
+            });
+        }
+    }
+
     pub fn move_to_next_snippet_tabstop(
         &mut self,
         window: &mut Window,
@@ -14833,10 +14956,11 @@ impl Editor {
             .into_iter()
             .map(|selection| selection.range())
             .collect_vec();
+        let variables = self.snippet_variables(cx);
 
         let snippet = if let Some(snippet_body) = &action.snippet {
             if action.language.is_none() && action.name.is_none() {
-                Snippet::parse(snippet_body)?
+                Snippet::parse_with_variables(snippet_body, &|name| variables.get(name).cloned())?
             } else {
                 bail!("`snippet` is mutually exclusive with `language` and `name`")
             }
@@ -14848,7 +14972,7 @@ impl Editor {
                 .into_iter()
                 .find(|snippet| snippet.name == *name)
                 .context("snippet not found")?;
-            Snippet::parse(&snippet.body)?
+            Snippet::parse_with_variables(&snippet.body, &|name| variables.get(name).cloned())?
         } else {
             // todo(andrew): open modal to select snippet
             bail!("`name` or `snippet` is required")
@@ -14857,6 +14981,127 @@ impl Editor {
         self.insert_snippet(&insertion_ranges, snippet, window, cx)
     }
 
+    /// Resolves the values of snippet variables, like `$TM_FILENAME`, at the newest selection.
+    fn snippet_variables(&self, cx: &mut Context<Self>) -> HashMap<&'static str, String> {
+        let mut variables = HashMap::default();
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let selection = self.selections.newest::<Point>(&self.display_snapshot(cx));
+        let head = selection.head();
+
+        variables.insert(
+            "TM_SELECTED_TEXT",
+            snapshot.text_for_range(selection.range()).collect(),
+        );
+        variables.insert(
+            "TM_CURRENT_LINE",
+            snapshot
+                .text_for_range(
+                    Point::new(head.row, 0)
+                        ..Point::new(head.row, snapshot.line_len(MultiBufferRow(head.row))),
+                )
+                .collect(),
+        );
+        let (word_range, _) = snapshot.surrounding_word(head, None);
+        variables.insert(
+            "TM_CURRENT_WORD",
+            snapshot.text_for_range(word_range).collect(),
+        );
+        let buffer_row = snapshot
+            .point_to_buffer_point(head)
+            .map_or(head.row, |(_, point, _)| point.row);
+        variables.insert("TM_LINE_INDEX", buffer_row.to_string());
+        variables.insert("TM_LINE_NUMBER", (buffer_row + 1).to_string());
+        variables.insert("CURSOR_INDEX", "0".to_string());
+        variables.insert("CURSOR_NUMBER", "1".to_string());
+
+        if let Some((_, buffer, _)) = self.active_excerpt(cx)
+            && let Some(file) = buffer.read(cx).file()
+        {
+            let path = file.path();
+            variables.insert("TM_FILENAME", file.file_name(cx).to_string());
+            if let Some(file_stem) = path.file_stem() {
+                variables.insert("TM_FILENAME_BASE", file_stem.to_string());
+            }
+            if let Some(project) = self.project() {
+                let project = project.read(cx);
+                variables.insert(
+                    "RELATIVE_FILEPATH",
+                    path.display(project.path_style(cx)).to_string(),
+                );
+                if let Some(worktree) = project.worktree_for_id(file.worktree_id(cx), cx) {
+                    let worktree = worktree.read(cx);
+                    variables.insert("WORKSPACE_NAME", worktree.root_name_str().to_string());
+                    variables.insert(
+                        "WORKSPACE_FOLDER",
+                        worktree.abs_path().to_string_lossy().into_owned(),
+                    );
+                }
+            }
+        }
+        if let Some(abs_path) = self.target_file_abs_path(cx) {
+            variables.insert("TM_FILEPATH", abs_path.to_string_lossy().into_owned());
+            if let Some(directory) = abs_path.parent() {
+                variables.insert("TM_DIRECTORY", directory.to_string_lossy().into_owned());
+            }
+        }
+
+        if let Some(clipboard_text) = cx.read_from_clipboard().and_then(|item| item.text()) {
+            variables.insert("CLIPBOARD", clipboard_text);
+        }
+
+        let now =
+            time::OffsetDateTime::now_local().unwrap_or_else(|_| time::OffsetDateTime::now_utc());
+        let month = now.month();
+        let weekday = now.weekday();
+        let month_name = month.to_string();
+        let weekday_name = weekday.to_string();
+        variables.insert("CURRENT_YEAR", now.year().to_string());
+        variables.insert("CURRENT_YEAR_SHORT", format!("{:02}", now.year() % 100));
+        variables.insert("CURRENT_MONTH", format!("{:02}", u8::from(month)));
+        variables.insert("CURRENT_MONTH_NAME_SHORT", month_name[..3].to_string());
+        variables.insert("CURRENT_MONTH_NAME", month_name);
+        variables.insert("CURRENT_DATE", format!("{:02}", now.day()));
+        variables.insert("CURRENT_DAY_NAME_SHORT", weekday_name[..3].to_string());
+        variables.insert("CURRENT_DAY_NAME", weekday_name);
+        variables.insert("CURRENT_HOUR", format!("{:02}", now.hour()));
+        variables.insert("CURRENT_MINUTE", format!("{:02}", now.minute()));
+        variables.insert("CURRENT_SECOND", format!("{:02}", now.second()));
+        variables.insert("CURRENT_SECONDS_UNIX", now.unix_timestamp().to_string());
+        let offset = now.offset();
+        variables.insert(
+            "CURRENT_TIMEZONE_OFFSET",
+            format!(
+                "{}{:02}:{:02}",
+                if offset.is_negative() { '-' } else { '+' },
+                offset.whole_hours().abs(),
+                offset.minutes_past_hour().abs()
+            ),
+        );
+
+        let mut rng = rand::rng();
+        variables.insert("RANDOM", format!("{:06}", rng.random_range(0..1_000_000)));
+        variables.insert(
+            "RANDOM_HEX",
+            format!("{:06x}", rng.random_range(0..0x1000000)),
+        );
+        variables.insert("UUID", uuid::Uuid::new_v4().to_string());
+
+        if let Some(scope) = snapshot.language_scope_at(head) {
+            if let Some(block_comment) = scope.block_comment() {
+                variables.insert(
+                    "BLOCK_COMMENT_START",
+                    block_comment.start.trim().to_string(),
+                );
+                variables.insert("BLOCK_COMMENT_END", block_comment.end.trim().to_string());
+            }
+            if let Some(line_comment) = scope.line_comment_prefixes().first() {
+                variables.insert("LINE_COMMENT", line_comment.trim().to_string());
+            }
+        }
+
+        variables
+    }
+
     fn select_match_ranges(
         &mut self,
         range: Range<MultiBufferOffset>,
@@ -17948,6 +18193,27 @@ impl Editor {
         _: &ConfirmRename,
         window: &mut Window,
         cx: &mut Context<Self>,
+    ) -> Option<Task<Result<()>>> {
+        let preview = ProjectSettings::get_global(cx)
+            .global_lsp_settings
+            .preview_renames;
+        self.perform_pending_rename(preview, window, cx)
+    }
+
+    pub fn confirm_rename_with_preview(
+        &mut self,
+        _: &ConfirmRenameWithPreview,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<Result<()>>> {
+        self.perform_pending_rename(true, window, cx)
+    }
+
+    fn perform_pending_rename(
+        &mut self,
+        preview: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Option<Task<Result<()>>> {
         let rename = self.take_rename(false, window, cx)?;
         let workspace = self.workspace()?.downgrade();
@@ -17966,12 +18232,30 @@ impl Editor {
         let old_name = rename.old_name;
         let new_name = rename.editor.read(cx).text(cx);
 
-        let rename = self.semantics_provider.as_ref()?.perform_rename(
-            &buffer,
-            start,
-            new_name.clone(),
-            cx,
-        )?;
+        let semantics_provider = self.semantics_provider.clone()?;
+        if preview
+            && let Some(rename_preview) =
+                semantics_provider.preview_rename(&buffer, start, new_name.clone(), cx)
+        {
+            return Some(cx.spawn_in(window, async move |editor, cx| {
+                let preview = rename_preview.await?;
+                workspace.update_in(cx, |workspace, window, cx| {
+                    RenamePreview::deploy(
+                        workspace,
+                        format!("Rename: {} → {}", old_name, new_name).into(),
+                        preview,
+                        window,
+                        cx,
+                    );
+                })?;
+                editor.update(cx, |editor, cx| {
+                    editor.refresh_document_highlights(cx);
+                })?;
+                Ok(())
+            }));
+        }
+
+        let rename = semantics_provider.perform_rename(&buffer, start, new_name.clone(), cx)?;
 
         Some(cx.spawn_in(window, async move |editor, cx| {
             let project_transaction = rename.await?;
@@ -21740,6 +22024,9 @@ impl Editor {
                 if self.has_active_edit_prediction() {
                     self.update_visible_edit_prediction(window, cx);
                 }
+                if !self.snippet_stack.is_empty() {
+                    self.apply_snippet_transforms(cx);
+                }
 
                 if let Some(buffer) = edited_buffer {
                     if buffer.read(cx).file().is_none() {
@@ -21859,6 +22146,9 @@ impl Editor {
             multi_buffer::Event::DiagnosticsUpdated => {
                 self.update_diagnostics_state(window, cx);
             }
+            multi_buffer::Event::LargeFileWindowFull => {
+                self.follow_large_file_end(window, cx);
+            }
             _ => {}
         };
     }
@@ -22055,6 +22345,7 @@ impl Editor {
                 }
                 self.refresh_colors_for_visible_range(None, window, cx);
             }
+            self.code_lens_display_mode_updated(window, cx);
         }
 
         cx.notify();
@@ -22962,6 +23253,7 @@ impl Editor {
     ) {
         self.pull_diagnostics(for_buffer, window, cx);
         self.refresh_colors_for_visible_range(for_buffer, window, cx);
+        self.refresh_code_lens(for_buffer, window, cx);
     }
 
     fn register_visible_buffers(&mut self, cx: &mut Context<Self>) {
@@ -22982,6 +23274,10 @@ impl Editor {
             && let Some(project) = self.project.as_ref()
         {
             if let Some(buffer) = self.buffer.read(cx).buffer(buffer_id) {
+                // Large files have no language server data, such as inlay hints, to fetch.
+                if buffer.read(cx).is_large_file() {
+                    return;
+                }
                 project.update(cx, |project, cx| {
                     self.registered_buffers.insert(
                         buffer_id,
@@ -23026,6 +23322,7 @@ fn process_completion_for_edit(
     intent: CompletionIntent,
     buffer: &Entity<Buffer>,
     cursor_position: &text::Anchor,
+    snippet_variables: &HashMap<&'static str, String>,
     cx: &mut Context<Editor>,
 ) -> CompletionEdit {
     let buffer = buffer.read(cx);
@@ -23051,7 +23348,11 @@ fn process_completion_for_edit(
         {
             snippet_source = label;
         }
-        match Snippet::parse(&snippet_source).log_err() {
+        match Snippet::parse_with_variables(&snippet_source, &|name| {
+            snippet_variables.get(name).cloned()
+        })
+        .log_err()
+        {
             Some(parsed_snippet) => (Some(parsed_snippet.clone()), parsed_snippet.text),
             None => (None, completion.new_text.clone()),
         }
@@ -23749,6 +24050,16 @@ pub trait SemanticsProvider {
         new_name: String,
         cx: &mut App,
     ) -> Option<Task<Result<ProjectTransaction>>>;
+
+    fn preview_rename(
+        &self,
+        _buffer: &Entity<Buffer>,
+        _position: text::Anchor,
+        _new_name: String,
+        _cx: &mut App,
+    ) -> Option<Task<Result<WorkspaceEditPreview>>> {
+        None
+    }
 }
 
 pub trait CompletionProvider {
@@ -23841,8 +24152,14 @@ impl CodeActionProvider for Entity<Project> {
         _window: &mut Window,
         cx: &mut App,
     ) -> Task<Result<Vec<CodeAction>>> {
+        let show_code_lens_in_menu =
+            EditorSettings::get_global(cx).code_lens == CodeLensDisplayMode::Menu;
         self.update(cx, |project, cx| {
-            let code_lens_actions = project.code_lens_actions(buffer, range.clone(), cx);
+            let code_lens_actions = if show_code_lens_in_menu {
+                project.code_lens_actions(buffer, range.clone(), cx)
+            } else {
+                Task::ready(Ok(None))
+            };
             let code_actions = project.code_actions(buffer, range, None, cx);
             cx.background_spawn(async move {
                 let (code_lens_actions, code_actions) = join(code_lens_actions, code_actions).await;
@@ -24333,6 +24650,21 @@ impl SemanticsProvider for Entity<Project> {
             project.perform_rename(buffer.clone(), position, new_name, cx)
         }))
     }
+
+    fn preview_rename(
+        &self,
+        buffer: &Entity<Buffer>,
+        position: text::Anchor,
+        new_name: String,
+        cx: &mut App,
+    ) -> Option<Task<Result<WorkspaceEditPreview>>> {
+        if !self.read(cx).is_local() {
+            return None;
+        }
+        Some(self.update(cx, |project, cx| {
+            project.preview_rename(buffer.clone(), position, new_name, cx)
+        }))
+    }
 }
 
 fn consume_contiguous_rows(
@@ -27,7 +27,8 @@ use language::{
     DiagnosticSourceKind, FakeLspAdapter, IndentGuideSettings, LanguageConfig,
     LanguageConfigOverride, LanguageMatcher, LanguageName, Override, Point,
     language_settings::{
-        CompletionSettingsContent, FormatterList, LanguageSettingsContent, LspInsertMode,
+        CompletionSettingsContent, FormatOnSave, FormatterList, LanguageSettingsContent,
+        LspInsertMode,
     },
     tree_sitter_python,
 };
@@ -11464,6 +11465,41 @@ async fn test_snippets(cx: &mut TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_snippet_variables_and_transforms(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("«fooˇ»");
+    cx.update_editor(|editor, window, cx| {
+        editor.insert_snippet_at_selections(
+            &InsertSnippet {
+                language: None,
+                name: None,
+                snippet: Some(
+                    "let ${1:$TM_SELECTED_TEXT} = ${1/(.*)/${1:/upcase}/}; // $UNKNOWN$0"
+                        .to_string(),
+                ),
+            },
+            window,
+            cx,
+        );
+    });
+    cx.assert_editor_state("let «fooˇ» = FOO; // UNKNOWN");
+
+    // Transformed mirrors follow the tabstop as it is edited.
+    cx.simulate_input("bar_baz");
+    cx.assert_editor_state("let bar_bazˇ = BAR_BAZ; // UNKNOWN");
+
+    // Unknown variables become placeholders after the snippet's own tabstops.
+    cx.update_editor(|editor, window, cx| assert!(editor.move_to_next_snippet_tabstop(window, cx)));
+    cx.assert_editor_state("let bar_baz = BAR_BAZ; // «UNKNOWNˇ»");
+
+    cx.update_editor(|editor, window, cx| assert!(editor.move_to_next_snippet_tabstop(window, cx)));
+    cx.assert_editor_state("let bar_baz = BAR_BAZ; // UNKNOWNˇ");
+}
+
 #[gpui::test]
 async fn test_snippet_indentation(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -12376,6 +12412,126 @@ async fn test_range_format_respects_language_tab_size_override(cx: &mut TestAppC
     save.await;
 }
 
+#[gpui::test]
+async fn test_format_modified_lines_on_save(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.format_on_save = Some(FormatOnSave::ModifiedLines);
+        settings.defaults.formatter = Some(FormatterList::Single(Formatter::LanguageServer(
+            settings::LanguageServerFormatterSpecifier::Current,
+        )));
+        settings.defaults.code_actions_on_format = Some(HashMap::from_iter([(
+            "source.organizeImports".to_string(),
+            true,
+        )]));
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "file.rs": "one  \ntwo\nthree\n",
+        }),
+    )
+    .await;
+    fs.set_head_for_repo(
+        path!("/dir/.git").as_ref(),
+        &[("file.rs", "one  \ntwo\nthree\n".into())],
+        "deadbeef",
+    );
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                document_range_formatting_provider: Some(lsp::OneOf::Left(true)),
+                code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/file.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        build_editor_with_project(project.clone(), buffer, window, cx)
+    });
+    cx.executor().start_waiting();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.set_text("one  \nTWO  \nthree\n", window, cx)
+    });
+    cx.run_until_parked();
+
+    fake_server.set_request_handler::<lsp::request::CodeActionRequest, _, _>(
+        move |_params, _| async move {
+            let uri = lsp::Uri::from_file_path(path!("/dir/file.rs")).unwrap();
+            Ok(Some(vec![lsp::CodeActionOrCommand::CodeAction(
+                lsp::CodeAction {
+                    kind: Some("source.organizeImports".into()),
+                    edit: Some(lsp::WorkspaceEdit::new(
+                        [(
+                            uri,
+                            vec![lsp::TextEdit::new(
+                                lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 0)),
+                                "organized\n".to_string(),
+                            )],
+                        )]
+                        .into_iter()
+                        .collect(),
+                    )),
+                    ..Default::default()
+                },
+            )]))
+        },
+    );
+
+    let save = editor
+        .update_in(cx, |editor, window, cx| {
+            editor.save(
+                SaveOptions {
+                    format: true,
+                    autosave: false,
+                },
+                project.clone(),
+                window,
+                cx,
+            )
+        })
+        .unwrap();
+    fake_server
+        .set_request_handler::<lsp::request::RangeFormatting, _, _>(move |params, _| async move {
+            assert_eq!(
+                params.range,
+                lsp::Range::new(lsp::Position::new(1, 0), lsp::Position::new(2, 0)),
+                "Only the modified line should be formatted"
+            );
+            Ok(Some(vec![lsp::TextEdit::new(
+                lsp::Range::new(lsp::Position::new(1, 0), lsp::Position::new(1, 3)),
+                "2".to_string(),
+            )]))
+        })
+        .next()
+        .await;
+    cx.executor().start_waiting();
+    save.await;
+    assert_eq!(
+        editor.update(cx, |editor, cx| editor.text(cx)),
+        "one  \n2\nthree\n",
+        "Trailing whitespace should only be removed from the modified line, \
+        and code actions on format should not change the unmodified lines"
+    );
+}
+
 #[gpui::test]
 async fn test_document_format_manual_trigger(cx: &mut TestAppContext) {
     init_test(cx, |settings| {
@@ -24482,6 +24638,142 @@ async fn test_apply_code_lens_actions_with_commands(cx: &mut gpui::TestAppContex
     );
 }
 
+#[gpui::test]
+async fn test_inline_code_lens(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    update_test_editor_settings(cx, |settings| {
+        settings.code_lens = Some(CodeLensDisplayMode::Inline);
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": "function one() {}\n\nfunction two() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let workspace = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*workspace.deref(), cx);
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(Arc::new(Language::new(
+        LanguageConfig {
+            name: "TypeScript".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["ts".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()),
+    )));
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(true),
+                }),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let editor = workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.open_abs_path(
+                PathBuf::from(path!("/dir/a.ts")),
+                OpenOptions::default(),
+                window,
+                cx,
+            )
+        })
+        .unwrap()
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    let fake_server = fake_language_servers.next().await.unwrap();
+
+    let lens_at_line = |line: u32, command: Option<lsp::Command>| lsp::CodeLens {
+        range: lsp::Range {
+            start: lsp::Position { line, character: 9 },
+            end: lsp::Position {
+                line,
+                character: 12,
+            },
+        },
+        command,
+        data: Some(json!(line)),
+    };
+    fake_server.set_request_handler::<lsp::request::CodeLensRequest, _, _>(
+        move |_, _| async move {
+            Ok(Some(vec![
+                lens_at_line(
+                    0,
+                    Some(lsp::Command {
+                        title: "Run".to_owned(),
+                        command: "client.run".to_owned(),
+                        arguments: None,
+                    }),
+                ),
+                lens_at_line(0, None),
+                lens_at_line(2, None),
+            ]))
+        },
+    );
+    let resolve_requests = Arc::new(AtomicUsize::new(0));
+    fake_server.set_request_handler::<lsp::request::CodeLensResolve, _, _>({
+        let resolve_requests = resolve_requests.clone();
+        move |mut lens, _| {
+            resolve_requests.fetch_add(1, atomic::Ordering::Release);
+            async move {
+                lens.command = Some(lsp::Command {
+                    title: "1 reference".to_owned(),
+                    command: "client.showReferences".to_owned(),
+                    arguments: None,
+                });
+                Ok(lens)
+            }
+        }
+    });
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.refresh_code_lens(None, window, cx);
+    });
+    cx.executor().advance_clock(Duration::from_millis(500));
+    cx.executor().run_until_parked();
+
+    assert_eq!(
+        resolve_requests.load(atomic::Ordering::Acquire),
+        2,
+        "Both visible unresolved lenses should be resolved"
+    );
+    editor.update(cx, |editor, cx| {
+        assert_eq!(
+            editor.display_text(cx),
+            "\nfunction one() {}\n\n\nfunction two() {}\n",
+            "Each line with lenses should get a block above it"
+        );
+    });
+
+    update_test_editor_settings(cx, |settings| {
+        settings.code_lens = Some(CodeLensDisplayMode::Off);
+    });
+    cx.executor().run_until_parked();
+    editor.update(cx, |editor, cx| {
+        assert_eq!(
+            editor.display_text(cx),
+            "function one() {}\n\nfunction two() {}\n",
+            "Lenses should be removed when turned off"
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_editor_restore_data_different_in_panes(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -1633,9 +1633,10 @@ impl SearchableItem for Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let text = self.buffer.read(cx);
-        let text = text.snapshot(cx);
-        let text = text.text_for_range(identifier.clone()).collect::<Vec<_>>();
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let text = snapshot
+            .text_for_range(identifier.clone())
+            .collect::<Vec<_>>();
         let text: Cow<_> = if text.len() == 1 {
             text.first().cloned().unwrap().into()
         } else {
@@ -1643,7 +1644,8 @@ impl SearchableItem for Editor {
             joined_chunks.into()
         };
 
-        if let Some(replacement) = query.replacement_for(&text) {
+        let language = snapshot.language_at(identifier.start);
+        if let Some(replacement) = query.replacement_for(&text, language) {
             self.transact(window, cx, |this, _, cx| {
                 this.edit([(identifier.clone(), Arc::from(&*replacement))], cx);
             });
@@ -1661,6 +1663,7 @@ impl SearchableItem for Editor {
         let mut edits = vec![];
 
         for m in matches {
+            let language = text.language_at(m.start);
             let text = text.text_for_range(m.clone()).collect::<Vec<_>>();
 
             let text: Cow<_> = if text.len() == 1 {
@@ -1670,7 +1673,7 @@ impl SearchableItem for Editor {
                 joined_chunks.into()
             };
 
-            if let Some(replacement) = query.replacement_for(&text) {
+            if let Some(replacement) = query.replacement_for(&text, language) {
                 edits.push((m.clone(), Arc::from(&*replacement)));
             }
         }
@@ -0,0 +1,116 @@
+use gpui::{Context, Task, Window};
+use language::Point;
+use multi_buffer::{Anchor, ExcerptRange};
+use workspace::notifications::NotifyTaskExt as _;
+
+use crate::{
+    Editor,
+    actions::{NextLargeFileWindow, PreviousLargeFileWindow},
+};
+
+impl Editor {
+    pub fn next_large_file_window(
+        &mut self,
+        _: &NextLargeFileWindow,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(file_window) = self.large_file_window(cx) else {
+            cx.propagate();
+            return;
+        };
+        if file_window.reaches_end() {
+            return;
+        }
+        self.open_large_file_window(file_window.range.end, Anchor::min(), window, cx)
+            .detach_and_notify_err(window, cx);
+    }
+
+    pub fn previous_large_file_window(
+        &mut self,
+        _: &PreviousLargeFileWindow,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(file_window) = self.large_file_window(cx) else {
+            cx.propagate();
+            return;
+        };
+        if file_window.range.start == 0 {
+            return;
+        }
+        let start = file_window.range.start.saturating_sub(file_window.max_len);
+        self.open_large_file_window(start, Anchor::min(), window, cx)
+            .detach_and_notify_err(window, cx);
+    }
+
+    /// Replaces a window that filled up while following the end of the file with one holding
+    /// the last half of that much, so that appended text keeps being shown. The cursor stays at
+    /// the end if it was there.
+    pub(crate) fn follow_large_file_end(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(file_window) = self.large_file_window(cx) else {
+            return;
+        };
+        let cursor_row = self
+            .selections
+            .newest::<Point>(&self.display_snapshot(cx))
+            .head()
+            .row;
+        let cursor = if cursor_row == self.buffer.read(cx).snapshot(cx).max_point().row {
+            Anchor::max()
+        } else {
+            Anchor::min()
+        };
+        let start = file_window.file_len.saturating_sub(file_window.max_len / 2);
+        self.open_large_file_window(start, cursor, window, cx)
+            .detach_and_notify_err(window, cx);
+    }
+
+    fn large_file_window(&self, cx: &Context<Self>) -> Option<fs::FileWindow> {
+        self.buffer
+            .read(cx)
+            .as_singleton()?
+            .read(cx)
+            .large_file_window()
+            .cloned()
+    }
+
+    /// Replaces the buffer shown in this editor with the window of the same file starting at
+    /// `start`, so that only one window of a large file is held in memory at a time, and moves
+    /// the cursor to `cursor`.
+    fn open_large_file_window(
+        &mut self,
+        start: u64,
+        cursor: Anchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let (Some(project), Some(buffer)) =
+            (self.project.clone(), self.buffer.read(cx).as_singleton())
+        else {
+            return Task::ready(Ok(()));
+        };
+        let open_window = project.update(cx, |project, cx| {
+            project.open_large_file_window(&buffer, start, cx)
+        });
+        cx.spawn_in(window, async move |editor, cx| {
+            let buffer = open_window.await?;
+            editor.update_in(cx, |editor, window, cx| {
+                editor.buffer.update(cx, |multi_buffer, cx| {
+                    let buffer_id = buffer.read(cx).remote_id();
+                    multi_buffer.clear(cx);
+                    multi_buffer.push_excerpts(
+                        buffer,
+                        [ExcerptRange::new(text::Anchor::min_max_range_for_buffer(
+                            buffer_id,
+                        ))],
+                        cx,
+                    );
+                });
+                editor.change_selections(Default::default(), window, cx, |selections| {
+                    selections.select_ranges(vec![cursor..cursor]);
+                });
+            })
+        })
+    }
+}
@@ -197,6 +197,14 @@ impl Domain for EditorDb {
                 ON DELETE CASCADE
             ) STRICT;
         ),
+        sql! (
+            CREATE TABLE undo_histories (
+                path BLOB NOT NULL,
+                content_hash TEXT NOT NULL,
+                history TEXT NOT NULL,
+                PRIMARY KEY(path)
+            ) STRICT;
+        ),
     ];
 }
 
@@ -281,6 +289,31 @@ impl EditorDb {
         }
     }
 
+    // Returns the hash of the content the undo history leads to, and the history itself
+    query! {
+        pub fn get_undo_history(path: PathBuf) -> Result<Option<(String, String)>> {
+            SELECT content_hash, history
+            FROM undo_histories
+            WHERE path = ?
+        }
+    }
+
+    query! {
+        pub async fn save_undo_history(path: PathBuf, content_hash: String, history: String) -> Result<()> {
+            INSERT OR REPLACE INTO undo_histories
+                (path, content_hash, history)
+            VALUES
+                (?1, ?2, ?3)
+        }
+    }
+
+    query! {
+        pub async fn delete_undo_history(path: PathBuf) -> Result<()> {
+            DELETE FROM undo_histories
+            WHERE path = ?
+        }
+    }
+
     pub async fn save_editor_selections(
         &self,
         editor_id: ItemId,
@@ -465,4 +498,30 @@ mod tests {
             .unwrap();
         assert_eq!(have, serialized_editor);
     }
+
+    #[gpui::test]
+    async fn test_save_and_get_undo_history() {
+        let path = PathBuf::from("/undo_history/testing.txt");
+        assert_eq!(DB.get_undo_history(path.clone()).unwrap(), None);
+
+        DB.save_undo_history(path.clone(), "hash-1".into(), "[]".into())
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_undo_history(path.clone()).unwrap(),
+            Some(("hash-1".to_owned(), "[]".to_owned()))
+        );
+
+        // Saving again replaces the previous history
+        DB.save_undo_history(path.clone(), "hash-2".into(), "[{\"edits\":[]}]".into())
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_undo_history(path.clone()).unwrap(),
+            Some(("hash-2".to_owned(), "[{\"edits\":[]}]".to_owned()))
+        );
+
+        DB.delete_undo_history(path.clone()).await.unwrap();
+        assert_eq!(DB.get_undo_history(path).unwrap(), None);
+    }
 }
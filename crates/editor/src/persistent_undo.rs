@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use gpui::{App, AppContext as _, Entity};
+use language::{Buffer, BufferEvent, UndoHistoryTransaction};
+use project::{Project, buffer_store::BufferStoreEvent};
+use rope::Rope;
+use settings::Settings as _;
+use sha2::{Digest as _, Sha256};
+use util::ResultExt as _;
+use workspace::Workspace;
+
+use crate::{EditorSettings, persistence::DB};
+
+/// Saves the undo history of local files when they are saved, and restores it when they are
+/// opened again, as long as their content hasn't changed on disk in the meantime.
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, cx| {
+        let project = workspace.project().clone();
+        if !project.read(cx).is_local() {
+            return;
+        }
+
+        let buffer_store = project.read(cx).buffer_store().clone();
+        cx.subscribe(&buffer_store, move |_, _, event, cx| {
+            if let BufferStoreEvent::BufferAdded(buffer) = event {
+                restore_undo_history(buffer.clone(), project.clone(), cx);
+                cx.subscribe(buffer, |_, buffer, event: &BufferEvent, cx| {
+                    if let BufferEvent::Saved = event {
+                        save_undo_history(&buffer, cx);
+                    }
+                })
+                .detach();
+            }
+        })
+        .detach();
+    })
+    .detach();
+}
+
+fn buffer_abs_path(buffer: &Entity<Buffer>, cx: &App) -> Option<PathBuf> {
+    project::File::from_dyn(buffer.read(cx).file())
+        .map(|file| file.worktree.read(cx).absolutize(&file.path))
+}
+
+fn content_hash(text: &Rope) -> String {
+    let mut hasher = Sha256::new();
+    for chunk in text.chunks() {
+        hasher.update(chunk);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+fn save_undo_history(buffer: &Entity<Buffer>, cx: &App) {
+    let settings = EditorSettings::get_global(cx).persistent_undo;
+    if !settings.enabled {
+        return;
+    }
+    let Some(path) = buffer_abs_path(buffer, cx) else {
+        return;
+    };
+    let text = buffer.read(cx).as_rope().clone();
+    if text.len() > settings.max_file_size {
+        cx.background_spawn(DB.delete_undo_history(path))
+            .detach_and_log_err(cx);
+        return;
+    }
+
+    let history = buffer.read(cx).undo_history(settings.max_transactions, cx);
+    cx.background_spawn(async move {
+        let history = history.await;
+        if history.is_empty() {
+            return DB.delete_undo_history(path).await;
+        }
+        let history = serde_json::to_string(&history)?;
+        DB.save_undo_history(path, content_hash(&text), history)
+            .await
+    })
+    .detach_and_log_err(cx);
+}
+
+fn restore_undo_history(buffer: Entity<Buffer>, project: Entity<Project>, cx: &App) {
+    let settings = EditorSettings::get_global(cx).persistent_undo;
+    if !settings.enabled {
+        return;
+    }
+    let Some(path) = buffer_abs_path(&buffer, cx) else {
+        return;
+    };
+    let text = buffer.read(cx).as_rope().clone();
+    let version = buffer.read(cx).version();
+    if text.len() > settings.max_file_size {
+        return;
+    }
+
+    cx.spawn(async move |cx| {
+        let history = cx
+            .background_spawn(async move { load_undo_history(path, &text).await })
+            .await?;
+        let Some(history) = history else {
+            return Ok(());
+        };
+        // The history is rebuilt without emitting edits, which collaborators would miss.
+        if project.read_with(cx, |project, _| project.is_shared())? {
+            return Ok(());
+        }
+        buffer.update(cx, |buffer, cx| {
+            // The history can only be replayed onto the content it was saved with.
+            if buffer.version() == version {
+                buffer.restore_undo_history(&history, cx).log_err();
+            }
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Loads the undo history saved for the file, discarding it if the file changed since.
+async fn load_undo_history(
+    path: PathBuf,
+    text: &Rope,
+) -> Result<Option<Vec<UndoHistoryTransaction>>> {
+    let Some((saved_content_hash, history)) = DB.get_undo_history(path.clone())? else {
+        return Ok(None);
+    };
+    if saved_content_hash != content_hash(text) {
+        DB.delete_undo_history(path).await?;
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&history)?))
+}
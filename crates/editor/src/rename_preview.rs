@@ -0,0 +1,630 @@
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    path::Path,
+    sync::Arc,
+};
+
+use collections::HashSet;
+use gpui::{
+    AnyElement, AnyEntity, Entity, EventEmitter, FocusHandle, Focusable, MouseButton, WeakEntity,
+};
+use language::{Buffer, Capability, Point};
+use multi_buffer::{ExcerptInfo, MultiBuffer, PathKey};
+use project::{Project, WorkspaceEditOperation, WorkspaceEditPreview};
+use text::{BufferId, ToPoint as _};
+use ui::{Checkbox, ElevationIndex, ToggleState, prelude::*};
+use util::paths::PathExt as _;
+use workspace::{
+    Item, Workspace, item::ItemEvent, notifications::NotifyTaskExt as _,
+    searchable::SearchableItemHandle,
+};
+
+use crate::{
+    Addon, Anchor, Editor,
+    display_map::{BlockContext, BlockPlacement, BlockProperties, BlockStyle},
+    multibuffer_context_lines,
+};
+
+/// Opens a preview whenever the project asks for language server edits to be reviewed, such as
+/// the `workspace/willRenameFiles` edits of a file renamed in the project panel.
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(
+        |workspace: &mut Workspace, window: Option<&mut Window>, cx: &mut Context<Workspace>| {
+            let Some(window) = window else {
+                return;
+            };
+            let project = workspace.project().clone();
+            cx.subscribe_in(&project, window, |workspace, _, event, window, cx| {
+                if let project::Event::WorkspaceEditPreviewRequested { title, preview } = event {
+                    RenamePreview::deploy(workspace, title.clone(), preview.clone(), window, cx);
+                }
+            })
+            .detach();
+        },
+    )
+    .detach();
+}
+
+/// Identifies an edit of the preview by the index of its operation and its index within it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct EditId {
+    operation_index: usize,
+    edit_index: usize,
+}
+
+/// Lists the changes of a rename in a multibuffer, letting each file and each occurrence be
+/// opted out of before the rest is applied as a single project transaction.
+pub struct RenamePreview {
+    title: SharedString,
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    preview: WorkspaceEditPreview,
+    excluded_operations: HashSet<usize>,
+    excluded_edits: HashSet<EditId>,
+    edit_ranges: Vec<(EditId, Range<Anchor>)>,
+    editor: Entity<Editor>,
+    applying: bool,
+}
+
+impl RenamePreview {
+    pub fn deploy(
+        workspace: &mut Workspace,
+        title: SharedString,
+        preview: WorkspaceEditPreview,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        if preview.is_empty() {
+            return;
+        }
+        let project = workspace.project().clone();
+        let workspace_handle = cx.entity().downgrade();
+        let rename_preview =
+            cx.new(|cx| Self::new(title, preview, project, workspace_handle, window, cx));
+        workspace.add_item_to_active_pane(Box::new(rename_preview), None, true, window, cx);
+    }
+
+    fn new(
+        title: SharedString,
+        preview: WorkspaceEditPreview,
+        project: Entity<Project>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let rename_preview = cx.weak_entity();
+        let multibuffer =
+            cx.new(|_| MultiBuffer::new(Capability::ReadOnly).with_title(title.to_string()));
+
+        let mut edits_by_buffer =
+            Vec::<(Entity<Buffer>, Vec<(EditId, Range<Point>, Arc<str>)>)>::new();
+        for (operation_index, operation) in preview.operations.iter().enumerate() {
+            let WorkspaceEditOperation::Edit { buffer, edits } = operation else {
+                continue;
+            };
+            let snapshot = buffer.read(cx).snapshot();
+            let buffer_index = match edits_by_buffer
+                .iter()
+                .position(|(existing_buffer, _)| existing_buffer == buffer)
+            {
+                Some(buffer_index) => buffer_index,
+                None => {
+                    edits_by_buffer.push((buffer.clone(), Vec::new()));
+                    edits_by_buffer.len() - 1
+                }
+            };
+            edits_by_buffer[buffer_index]
+                .1
+                .extend(edits.iter().enumerate().map(|(edit_index, (range, text))| {
+                    (
+                        EditId {
+                            operation_index,
+                            edit_index,
+                        },
+                        range.start.to_point(&snapshot)..range.end.to_point(&snapshot),
+                        text.clone(),
+                    )
+                }));
+        }
+
+        let mut edit_ranges = Vec::new();
+        let mut blocks = Vec::new();
+        multibuffer.update(cx, |multibuffer, cx| {
+            for (buffer, edits) in &mut edits_by_buffer {
+                edits.sort_by_key(|(_, range, _)| range.start);
+                multibuffer.set_excerpts_for_path(
+                    PathKey::for_buffer(buffer, cx),
+                    buffer.clone(),
+                    edits.iter().map(|(_, range, _)| range.clone()),
+                    multibuffer_context_lines(cx),
+                    cx,
+                );
+
+                let snapshot = buffer.read(cx).snapshot();
+                for (edit_id, range, new_text) in edits.iter() {
+                    let Some((start, end)) = multibuffer
+                        .buffer_point_to_anchor(buffer, range.start, cx)
+                        .zip(multibuffer.buffer_point_to_anchor(buffer, range.end, cx))
+                    else {
+                        continue;
+                    };
+                    let old_text = snapshot.text_for_range(range.clone()).collect::<String>();
+                    edit_ranges.push((*edit_id, start..end));
+                    blocks.push(BlockProperties {
+                        placement: BlockPlacement::Above(start),
+                        height: Some(1),
+                        style: BlockStyle::Flex,
+                        render: Arc::new({
+                            let rename_preview = rename_preview.clone();
+                            let edit_id = *edit_id;
+                            let description: SharedString =
+                                format!("{} → {}", old_text.trim(), new_text.trim()).into();
+                            move |cx| render_edit_toggle(&rename_preview, edit_id, &description, cx)
+                        }),
+                        priority: 0,
+                    });
+                }
+            }
+        });
+
+        let editor = cx.new(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(multibuffer.clone(), Some(project.clone()), window, cx);
+            editor.set_read_only(true);
+            editor.insert_blocks(blocks, None, cx);
+            editor.register_addon(RenamePreviewAddon {
+                rename_preview: rename_preview.clone(),
+            });
+            editor
+        });
+
+        let this = Self {
+            title,
+            project,
+            workspace,
+            preview,
+            excluded_operations: HashSet::default(),
+            excluded_edits: HashSet::default(),
+            edit_ranges,
+            editor,
+            applying: false,
+        };
+        this.update_highlights(cx);
+        this
+    }
+
+    fn edit_ids(&self) -> impl Iterator<Item = (EditId, &Entity<Buffer>)> {
+        self.preview
+            .operations
+            .iter()
+            .enumerate()
+            .filter_map(|(operation_index, operation)| match operation {
+                WorkspaceEditOperation::Edit { buffer, edits } => {
+                    Some((operation_index, buffer, edits))
+                }
+                _ => None,
+            })
+            .flat_map(|(operation_index, buffer, edits)| {
+                (0..edits.len()).map(move |edit_index| {
+                    (
+                        EditId {
+                            operation_index,
+                            edit_index,
+                        },
+                        buffer,
+                    )
+                })
+            })
+    }
+
+    fn change_count(&self) -> usize {
+        self.preview
+            .operations
+            .iter()
+            .map(|operation| match operation {
+                WorkspaceEditOperation::Edit { edits, .. } => edits.len(),
+                _ => 1,
+            })
+            .sum()
+    }
+
+    fn selected_change_count(&self) -> usize {
+        let excluded_operations = self
+            .excluded_operations
+            .iter()
+            .filter(|operation_index| {
+                !matches!(
+                    self.preview.operations.get(**operation_index),
+                    Some(WorkspaceEditOperation::Edit { .. })
+                )
+            })
+            .count();
+        self.change_count() - self.excluded_edits.len() - excluded_operations
+    }
+
+    /// Returns the preview without the file operations and edits that were opted out of.
+    fn selected_preview(&self) -> WorkspaceEditPreview {
+        let operations = self
+            .preview
+            .operations
+            .iter()
+            .enumerate()
+            .filter_map(|(operation_index, operation)| match operation {
+                WorkspaceEditOperation::Edit { buffer, edits } => {
+                    let edits = edits
+                        .iter()
+                        .enumerate()
+                        .filter(|(edit_index, _)| {
+                            !self.excluded_edits.contains(&EditId {
+                                operation_index,
+                                edit_index: *edit_index,
+                            })
+                        })
+                        .map(|(_, edit)| edit.clone())
+                        .collect::<Vec<_>>();
+                    (!edits.is_empty()).then(|| WorkspaceEditOperation::Edit {
+                        buffer: buffer.clone(),
+                        edits,
+                    })
+                }
+                operation => (!self.excluded_operations.contains(&operation_index))
+                    .then(|| operation.clone()),
+            })
+            .collect();
+        WorkspaceEditPreview { operations }
+    }
+
+    fn toggle_edit(&mut self, edit_id: EditId, cx: &mut Context<Self>) {
+        if !self.excluded_edits.remove(&edit_id) {
+            self.excluded_edits.insert(edit_id);
+        }
+        self.selection_changed(cx);
+    }
+
+    fn toggle_operation(&mut self, operation_index: usize, cx: &mut Context<Self>) {
+        if !self.excluded_operations.remove(&operation_index) {
+            self.excluded_operations.insert(operation_index);
+        }
+        self.selection_changed(cx);
+    }
+
+    fn set_buffer_selected(&mut self, buffer_id: BufferId, selected: bool, cx: &mut Context<Self>) {
+        let edit_ids = self
+            .edit_ids()
+            .filter(|(_, buffer)| buffer.read(cx).remote_id() == buffer_id)
+            .map(|(edit_id, _)| edit_id)
+            .collect::<Vec<_>>();
+        for edit_id in edit_ids {
+            if selected {
+                self.excluded_edits.remove(&edit_id);
+            } else {
+                self.excluded_edits.insert(edit_id);
+            }
+        }
+        self.selection_changed(cx);
+    }
+
+    fn buffer_toggle_state(&self, buffer_id: BufferId, cx: &App) -> ToggleState {
+        let mut any_selected = false;
+        let mut all_selected = true;
+        for (edit_id, buffer) in self.edit_ids() {
+            if buffer.read(cx).remote_id() == buffer_id {
+                let selected = !self.excluded_edits.contains(&edit_id);
+                any_selected |= selected;
+                all_selected &= selected;
+            }
+        }
+        ToggleState::from_any_and_all(any_selected, all_selected)
+    }
+
+    fn selection_changed(&mut self, cx: &mut Context<Self>) {
+        self.update_highlights(cx);
+        cx.notify();
+    }
+
+    fn update_highlights(&self, cx: &mut Context<Self>) {
+        let selected_ranges = self
+            .edit_ranges
+            .iter()
+            .filter(|(edit_id, _)| !self.excluded_edits.contains(edit_id))
+            .map(|(_, range)| range.clone())
+            .collect::<Vec<_>>();
+        self.editor.update(cx, |editor, cx| {
+            editor.highlight_background::<Self>(
+                &selected_ranges,
+                |_, theme| theme.colors().search_match_background,
+                cx,
+            );
+            cx.notify();
+        });
+    }
+
+    fn apply(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.applying {
+            return;
+        }
+        self.applying = true;
+        cx.notify();
+
+        let apply = self.project.update(cx, |project, cx| {
+            project.apply_workspace_edit_preview(self.selected_preview(), cx)
+        });
+        let editor = self.editor.downgrade();
+        let workspace = self.workspace.clone();
+        let title = self.title.to_string();
+        cx.spawn_in(window, async move |this, cx| {
+            let result = apply.await;
+            this.update(cx, |this, cx| {
+                this.applying = false;
+                if result.is_ok() {
+                    cx.emit(ItemEvent::CloseItem);
+                }
+                cx.notify();
+            })?;
+            Editor::open_project_transaction(&editor, workspace, result?, title, cx).await
+        })
+        .detach_and_notify_err(window, cx);
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let selected_change_count = self.selected_change_count();
+        h_flex()
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(Label::new(self.title.clone()))
+            .child(
+                Label::new(format!(
+                    "{selected_change_count} of {} changes selected",
+                    self.change_count()
+                ))
+                .color(Color::Muted)
+                .size(LabelSize::Small),
+            )
+            .child(div().flex_1())
+            .child(
+                Button::new("cancel-rename-preview", "Cancel").on_click(cx.listener(
+                    |_, _, _, cx| {
+                        cx.emit(ItemEvent::CloseItem);
+                    },
+                )),
+            )
+            .child(
+                Button::new("apply-rename-preview", "Apply")
+                    .style(ButtonStyle::Filled)
+                    .disabled(self.applying || selected_change_count == 0)
+                    .on_click(cx.listener(|this, _, window, cx| this.apply(window, cx))),
+            )
+    }
+
+    fn render_file_operations(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let operations = self
+            .preview
+            .operations
+            .iter()
+            .enumerate()
+            .filter_map(|(operation_index, operation)| {
+                Some((operation_index, file_operation_description(operation)?))
+            })
+            .collect::<Vec<_>>();
+        if operations.is_empty() {
+            return None;
+        }
+
+        Some(
+            v_flex()
+                .w_full()
+                .px_2()
+                .py_1()
+                .gap_1()
+                .border_b_1()
+                .border_color(cx.theme().colors().border_variant)
+                .children(
+                    operations
+                        .into_iter()
+                        .map(|(operation_index, description)| {
+                            h_flex()
+                                .gap_2()
+                                .child(
+                                    Checkbox::new(
+                                        ("rename-preview-operation", operation_index),
+                                        (!self.excluded_operations.contains(&operation_index))
+                                            .into(),
+                                    )
+                                    .on_click(cx.listener(
+                                        move |this, _, _, cx| {
+                                            this.toggle_operation(operation_index, cx)
+                                        },
+                                    )),
+                                )
+                                .child(Label::new(description).size(LabelSize::Small))
+                        }),
+                ),
+        )
+    }
+}
+
+fn file_operation_description(operation: &WorkspaceEditOperation) -> Option<String> {
+    let display = |path: &Path| path.compact().to_string_lossy().into_owned();
+    match operation {
+        WorkspaceEditOperation::Edit { .. } => None,
+        WorkspaceEditOperation::EditCreatedFile { abs_path, .. } => {
+            Some(format!("Edit {}", display(abs_path)))
+        }
+        WorkspaceEditOperation::CreateFile { abs_path, .. } => {
+            Some(format!("Create {}", display(abs_path)))
+        }
+        WorkspaceEditOperation::RenameFile {
+            old_abs_path,
+            new_abs_path,
+            ..
+        } => Some(format!(
+            "Rename {} → {}",
+            display(old_abs_path),
+            display(new_abs_path)
+        )),
+        WorkspaceEditOperation::DeleteFile { abs_path, .. } => {
+            Some(format!("Delete {}", display(abs_path)))
+        }
+    }
+}
+
+fn render_edit_toggle(
+    rename_preview: &WeakEntity<RenamePreview>,
+    edit_id: EditId,
+    description: &SharedString,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    let selected = rename_preview
+        .upgrade()
+        .is_some_and(|rename_preview| !rename_preview.read(cx).excluded_edits.contains(&edit_id));
+    h_flex()
+        .id(cx.block_id)
+        .h(cx.line_height)
+        .ml(cx.margins.gutter.width)
+        .gap_2()
+        .child(
+            Checkbox::new("rename-preview-edit", selected.into()).on_click({
+                let rename_preview = rename_preview.clone();
+                move |_, _, cx| {
+                    rename_preview
+                        .update(cx, |rename_preview, cx| {
+                            rename_preview.toggle_edit(edit_id, cx)
+                        })
+                        .ok();
+                }
+            }),
+        )
+        .child(
+            Label::new(description.clone())
+                .size(LabelSize::Small)
+                .color(if selected {
+                    Color::Default
+                } else {
+                    Color::Muted
+                }),
+        )
+        .into_any_element()
+}
+
+struct RenamePreviewAddon {
+    rename_preview: WeakEntity<RenamePreview>,
+}
+
+impl Addon for RenamePreviewAddon {
+    fn render_buffer_header_controls(
+        &self,
+        excerpt_info: &ExcerptInfo,
+        _: &Window,
+        cx: &App,
+    ) -> Option<AnyElement> {
+        let buffer_id = excerpt_info.buffer_id;
+        let toggle_state = self
+            .rename_preview
+            .upgrade()?
+            .read(cx)
+            .buffer_toggle_state(buffer_id, cx);
+        let rename_preview = self.rename_preview.clone();
+        Some(
+            h_flex()
+                .id("rename-preview-file")
+                .child(
+                    Checkbox::new("rename-preview-file-checkbox", toggle_state)
+                        .fill()
+                        .elevation(ElevationIndex::Surface)
+                        .on_click(move |state, _, cx| {
+                            let selected = *state == ToggleState::Selected;
+                            rename_preview
+                                .update(cx, |rename_preview, cx| {
+                                    rename_preview.set_buffer_selected(buffer_id, selected, cx);
+                                })
+                                .ok();
+                            cx.stop_propagation();
+                        }),
+                )
+                .on_mouse_down(MouseButton::Left, |_, _, cx| {
+                    // Keeps the click from opening the file behind the header.
+                    cx.stop_propagation();
+                })
+                .into_any_element(),
+        )
+    }
+
+    fn to_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Render for RenamePreview {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_header(cx))
+            .children(self.render_file_operations(cx))
+            .child(div().flex_1().min_h_0().child(self.editor.clone()))
+    }
+}
+
+impl EventEmitter<ItemEvent> for RenamePreview {}
+
+impl Focusable for RenamePreview {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Item for RenamePreview {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &ItemEvent, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content_text(&self, _detail: usize, _: &App) -> SharedString {
+        self.title.clone()
+    }
+
+    fn tab_icon(&self, _: &Window, _: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Pencil))
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+}
@@ -1,11 +1,5 @@
 use crate::FeatureFlag;
 
-pub struct NotebookFeatureFlag;
-
-impl FeatureFlag for NotebookFeatureFlag {
-    const NAME: &'static str = "notebooks";
-}
-
 pub struct PanicFeatureFlag;
 
 impl FeatureFlag for PanicFeatureFlag {
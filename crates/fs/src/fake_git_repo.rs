@@ -6,8 +6,8 @@ use git::{
     Oid, RunHook,
     blame::Blame,
     repository::{
-        AskPassDelegate, Branch, CommitDetails, CommitOptions, FetchOptions, GitRepository,
-        GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode, Worktree,
+        AskPassDelegate, Branch, CommitDetails, CommitOptions, FetchOptions, FileRevision,
+        GitRepository, GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode, Worktree,
     },
     status::{
         DiffTreeType, FileStatus, GitStatus, StatusCode, TrackedStatus, TreeDiff, TreeDiffStatus,
@@ -128,6 +128,28 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
+    fn load_file_bytes(
+        &self,
+        revision: FileRevision,
+        path: RepoPath,
+    ) -> BoxFuture<'_, Result<Option<Vec<u8>>>> {
+        self.with_state_async(false, move |state| {
+            let content = match revision {
+                FileRevision::Index => state.index_contents.get(&path),
+                FileRevision::Head => state.head_contents.get(&path),
+                FileRevision::MergeBase(_) => match state.merge_base_contents.get(&path) {
+                    Some(oid) => Some(state.oids.get(oid).context("oid does not exist")?),
+                    None => None,
+                },
+                FileRevision::Commit(_) | FileRevision::ParentOf(_) => {
+                    bail!("loading files from commits is not supported by the fake repository")
+                }
+            };
+            Ok(content.map(|content| content.as_bytes().to_vec()))
+        })
+        .boxed()
+    }
+
     fn load_commit(
         &self,
         _commit: String,
@@ -37,7 +37,8 @@ use rope::Rope;
 use serde::{Deserialize, Serialize};
 use smol::io::AsyncWriteExt;
 use std::{
-    io::{self, Write},
+    io::{self, Read as _, Write},
+    ops::Range,
     path::{Component, Path, PathBuf},
     pin::Pin,
     sync::Arc,
@@ -121,6 +122,14 @@ pub trait Fs: Send + Sync {
         Ok(String::from_utf8(self.load_bytes(path).await?)?)
     }
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
+    /// Loads a window of at most `max_len` bytes of a text file, as described by [`read_window`].
+    async fn load_window(&self, path: &Path, start: u64, max_len: u64) -> Result<LoadedFileWindow> {
+        read_window(
+            io::Cursor::new(self.load_bytes(path).await?),
+            start,
+            max_len,
+        )
+    }
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
     async fn write(&self, path: &Path, content: &[u8]) -> Result<()>;
@@ -750,6 +759,13 @@ impl Fs for RealFs {
         Ok(bytes)
     }
 
+    async fn load_window(&self, path: &Path, start: u64, max_len: u64) -> Result<LoadedFileWindow> {
+        let path = path.to_path_buf();
+        self.executor
+            .spawn(async move { read_window(std::fs::File::open(path)?, start, max_len) })
+            .await
+    }
+
     #[cfg(not(target_os = "windows"))]
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         smol::unblock(move || {
@@ -2773,6 +2789,139 @@ impl Fs for FakeFs {
     }
 }
 
+/// A part of a large text file, which starts at the beginning of a line and, unless it reaches
+/// the end of the file, ends after a newline.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileWindow {
+    /// The byte range of the file that the window holds.
+    pub range: Range<u64>,
+    /// The offset at which the window's last line starts, which is the end of the window when it
+    /// ends with a newline. A last line without a newline may still be growing.
+    pub last_line_start: u64,
+    /// The length of the file when the window was read.
+    pub file_len: u64,
+    /// The maximum number of bytes that the window was read with.
+    pub max_len: u64,
+}
+
+impl FileWindow {
+    /// Whether the window held the end of the file when it was read.
+    pub fn reaches_end(&self) -> bool {
+        self.range.end >= self.file_len
+    }
+}
+
+/// A [`FileWindow`] along with its text.
+pub struct LoadedFileWindow {
+    pub window: FileWindow,
+    pub text: Rope,
+    pub line_ending: LineEnding,
+}
+
+/// Reads a window of at most `max_len` bytes of a text file, starting with the first line that
+/// begins at or after `start`, so that the rest of the file is never held in memory. Line endings
+/// are normalized and invalid UTF-8 is replaced, except at the start of the file, where content
+/// that isn't text is rejected as it would be by [`Fs::load`].
+pub fn read_window(
+    mut reader: impl io::Read + io::Seek,
+    start: u64,
+    max_len: u64,
+) -> Result<LoadedFileWindow> {
+    let file_len = reader.seek(io::SeekFrom::End(0))?;
+    let start = start.min(file_len);
+    // The byte preceding the window tells whether it starts at the beginning of a line.
+    let read_start = start.saturating_sub(1);
+    reader.seek(io::SeekFrom::Start(read_start))?;
+    let mut bytes = Vec::new();
+    reader
+        .take(start - read_start + max_len)
+        .read_to_end(&mut bytes)?;
+    decode_window(&bytes, read_start, start, file_len, max_len)
+}
+
+fn decode_window(
+    bytes: &[u8],
+    read_start: u64,
+    start: u64,
+    file_len: u64,
+    max_len: u64,
+) -> Result<LoadedFileWindow> {
+    const BINARY_CHECK_LEN: usize = 1024 * 1024;
+
+    let mut text_start = (start - read_start) as usize;
+    if text_start > 0 && bytes[0] != b'\n' {
+        text_start += match bytes[text_start..].iter().position(|&byte| byte == b'\n') {
+            Some(newline_ix) => newline_ix + 1,
+            // A line longer than the window is split wherever a character starts.
+            None => bytes[text_start..]
+                .iter()
+                .take_while(|&&byte| byte & 0b1100_0000 == 0b1000_0000)
+                .count(),
+        };
+    }
+
+    let reaches_end = read_start + bytes.len() as u64 >= file_len;
+    let mut text_end = bytes.len();
+    let last_line_start;
+    match bytes[text_start..].iter().rposition(|&byte| byte == b'\n') {
+        Some(newline_ix) => {
+            last_line_start = text_start + newline_ix + 1;
+            if !reaches_end {
+                text_end = last_line_start;
+            }
+        }
+        None => {
+            last_line_start = text_start;
+            if !reaches_end {
+                text_end -= incomplete_char_len(&bytes[text_start..]);
+            }
+        }
+    }
+
+    let text = &bytes[text_start..text_end];
+    if start == 0 {
+        let first_block = &text[..text.len().min(BINARY_CHECK_LEN)];
+        let first_block = &first_block[..first_block.len() - incomplete_char_len(first_block)];
+        anyhow::ensure!(!first_block.contains(&0), "file contains binary data");
+        std::str::from_utf8(first_block)?;
+    }
+
+    let text = String::from_utf8_lossy(text);
+    let line_ending = LineEnding::detect(&text);
+    let text = Rope::from(LineEnding::normalize_cow(text).as_ref());
+    Ok(LoadedFileWindow {
+        window: FileWindow {
+            range: read_start + text_start as u64..read_start + text_end as u64,
+            last_line_start: read_start + last_line_start as u64,
+            file_len,
+            max_len,
+        },
+        text,
+        line_ending,
+    })
+}
+
+/// Returns the length of the incomplete UTF-8 character at the end of the bytes, if any.
+fn incomplete_char_len(bytes: &[u8]) -> usize {
+    for (continuation_len, byte) in bytes.iter().rev().take(4).enumerate() {
+        let is_continuation = byte & 0b1100_0000 == 0b1000_0000;
+        if !is_continuation {
+            let char_len = match byte {
+                0xF0.. => 4,
+                0xE0.. => 3,
+                0xC0.. => 2,
+                _ => 1,
+            };
+            return if char_len > continuation_len + 1 {
+                continuation_len + 1
+            } else {
+                0
+            };
+        }
+    }
+    0
+}
+
 fn chunks(rope: &Rope, line_ending: LineEnding) -> impl Iterator<Item = &str> {
     rope.chunks().flat_map(move |chunk| {
         let mut newline = false;
@@ -3374,6 +3523,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_read_window() {
+        let read = |text: &[u8], start, max_len| {
+            let loaded = read_window(io::Cursor::new(text), start, max_len).unwrap();
+            (loaded.text.to_string(), loaded.window, loaded.line_ending)
+        };
+        let window = |range: Range<u64>, last_line_start, file_len, max_len| FileWindow {
+            range,
+            last_line_start,
+            file_len,
+            max_len,
+        };
+
+        let text = "héllo\r\nwörld 🦀\r\nend".as_bytes();
+        assert_eq!(
+            read(text, 0, 100),
+            (
+                "héllo\nwörld 🦀\nend".to_string(),
+                window(0..24, 21, 24, 100),
+                LineEnding::Windows
+            )
+        );
+
+        // Windows end after the last newline they hold.
+        assert_eq!(
+            read(text, 0, 20),
+            (
+                "héllo\n".to_string(),
+                window(0..8, 8, 24, 20),
+                LineEnding::Windows
+            )
+        );
+
+        // Windows start at the beginning of a line.
+        assert_eq!(
+            read(text, 8, 100).0,
+            "wörld 🦀\nend",
+            "a window starting at a line keeps it"
+        );
+        assert_eq!(
+            read(text, 3, 100).0,
+            "wörld 🦀\nend",
+            "a window starting within a line skips it"
+        );
+
+        // Lines longer than the window are split between characters.
+        let (window_text, file_window, _) = read(text, 8, 2);
+        assert_eq!(window_text, "w");
+        assert_eq!(file_window, window(8..9, 8, 24, 2));
+        assert_eq!(read(text, 9, 2).0, "ö");
+
+        // Invalid UTF-8 is replaced past the start of the file, and rejected at its start.
+        let text = b"one\ntwo \xFF\xFE three\n";
+        assert_eq!(read(text, 1, 100).0, "two \u{FFFD}\u{FFFD} three\n");
+        assert!(read_window(io::Cursor::new(text), 0, 100).is_err());
+        assert!(read_window(io::Cursor::new(b"\x7FELF\0\0\0"), 0, 100).is_err());
+    }
+
     #[gpui::test]
     async fn test_realfs_atomic_write(executor: BackgroundExecutor) {
         // With the file handle still open, the file should be replaced
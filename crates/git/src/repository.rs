@@ -254,6 +254,21 @@ pub struct Remote {
     pub name: SharedString,
 }
 
+/// A version of a file that [`GitRepository::load_file_bytes`] can load.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum FileRevision {
+    /// The version staged in the index.
+    Index,
+    /// The version committed in HEAD.
+    Head,
+    /// The version in the given commit.
+    Commit(Oid),
+    /// The version in the first parent of the given commit.
+    ParentOf(Oid),
+    /// The version in the merge base of HEAD and the given ref.
+    MergeBase(SharedString),
+}
+
 pub enum ResetMode {
     /// Reset the branch pointer, leave index and worktree unchanged (this will make it look like things that were
     /// committed are now staged).
@@ -419,6 +434,16 @@ pub trait GitRepository: Send + Sync {
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>>;
     fn load_blob_content(&self, oid: Oid) -> BoxFuture<'_, Result<String>>;
 
+    /// Returns the raw bytes of a file at the given revision.
+    /// Unlike the text loaders, this doesn't require the contents to be valid UTF-8.
+    ///
+    /// Returns `None` if the revision has no entry for the given path.
+    fn load_file_bytes(
+        &self,
+        revision: FileRevision,
+        path: RepoPath,
+    ) -> BoxFuture<'_, Result<Option<Vec<u8>>>>;
+
     fn set_index_text(
         &self,
         path: RepoPath,
@@ -1032,6 +1057,49 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn load_file_bytes(
+        &self,
+        revision: FileRevision,
+        path: RepoPath,
+    ) -> BoxFuture<'_, Result<Option<Vec<u8>>>> {
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                let repo = repo.lock();
+                let commit = match revision {
+                    FileRevision::Index => {
+                        let mut index = repo.index()?;
+                        index.read(false)?;
+                        const STAGE_NORMAL: i32 = 0;
+                        let Some(entry) = index.get_path(path.as_std_path(), STAGE_NORMAL) else {
+                            return Ok(None);
+                        };
+                        return Ok(Some(repo.find_blob(entry.id)?.content().to_owned()));
+                    }
+                    FileRevision::Head => repo.head()?.peel_to_commit()?,
+                    FileRevision::Commit(oid) => repo.find_commit(oid.0)?,
+                    FileRevision::ParentOf(oid) => repo.find_commit(oid.0)?.parent(0)?,
+                    FileRevision::MergeBase(base_ref) => {
+                        let head = repo.head()?.peel_to_commit()?.id();
+                        let base = repo
+                            .resolve_reference_from_short_name(base_ref.as_ref())?
+                            .peel_to_commit()?
+                            .id();
+                        repo.find_commit(repo.merge_base(head, base)?)?
+                    }
+                };
+                let oid = match commit.tree()?.get_path(path.as_std_path()) {
+                    Ok(entry) => entry.id(),
+                    Err(error) if error.code() == git2::ErrorCode::NotFound => {
+                        return Ok(None);
+                    }
+                    Err(error) => return Err(error.into()),
+                };
+                Ok(Some(repo.find_blob(oid)?.content().to_owned()))
+            })
+            .boxed()
+    }
+
     fn set_index_text(
         &self,
         path: RepoPath,
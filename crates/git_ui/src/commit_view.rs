@@ -4,8 +4,8 @@ use editor::display_map::{BlockPlacement, BlockProperties, BlockStyle};
 use editor::{
     Editor, EditorEvent, ExcerptId, ExcerptRange, MultiBuffer, multibuffer_context_lines,
 };
-use git::repository::{CommitDetails, CommitDiff, RepoPath};
-use git::{GitHostingProviderRegistry, GitRemote, parse_git_remote_url};
+use git::repository::{CommitDetails, CommitDiff, FileRevision, RepoPath};
+use git::{GitHostingProviderRegistry, GitRemote, Oid, parse_git_remote_url};
 use gpui::{
     AnyElement, App, AppContext as _, Asset, AsyncApp, AsyncWindowContext, Context, Element,
     Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement, IntoElement, ParentElement,
@@ -36,6 +36,7 @@ use workspace::{
 };
 
 use crate::git_panel::GitPanel;
+use crate::image_diff_view::{ImageDiffSource, ImageDiffView, is_image_path};
 
 actions!(git, [ApplyCurrentStash, PopCurrentStash, DropCurrentStash,]);
 
@@ -61,6 +62,16 @@ pub struct CommitView {
     multibuffer: Entity<MultiBuffer>,
     repository: Entity<Repository>,
     remote: Option<GitRemote>,
+    workspace: WeakEntity<Workspace>,
+    /// Changed images, which can't be shown in the multibuffer and are listed above it instead.
+    changed_images: Vec<ChangedImage>,
+}
+
+#[derive(Clone)]
+struct ChangedImage {
+    path: RepoPath,
+    is_added: bool,
+    is_deleted: bool,
 }
 
 struct GitBlob {
@@ -105,12 +116,14 @@ impl CommitView {
                 workspace
                     .update_in(cx, |workspace, window, cx| {
                         let project = workspace.project();
+                        let workspace_handle = workspace.weak_handle();
                         let commit_view = cx.new(|cx| {
                             CommitView::new(
                                 commit_details,
                                 commit_diff,
                                 repo,
                                 project.clone(),
+                                workspace_handle,
                                 stash,
                                 window,
                                 cx,
@@ -141,6 +154,7 @@ impl CommitView {
         commit_diff: CommitDiff,
         repository: Entity<Repository>,
         project: Entity<Project>,
+        workspace: WeakEntity<Workspace>,
         stash: Option<usize>,
         window: &mut Window,
         cx: &mut Context<Self>,
@@ -167,8 +181,21 @@ impl CommitView {
         let repository_clone = repository.clone();
         let commit_message = commit.message.clone();
 
+        let (image_files, text_files): (Vec<_>, Vec<_>) = commit_diff
+            .files
+            .into_iter()
+            .partition(|file| is_image_path(&file.path));
+        let changed_images = image_files
+            .into_iter()
+            .map(|file| ChangedImage {
+                is_added: file.old_text.is_none(),
+                is_deleted: file.new_text.is_none(),
+                path: file.path,
+            })
+            .collect();
+
         cx.spawn(async move |this, cx| {
-            for file in commit_diff.files {
+            for file in text_files {
                 let is_deleted = file.new_text.is_none();
                 let new_text = file.new_text.unwrap_or_default();
                 let old_text = file.old_text;
@@ -306,9 +333,96 @@ impl CommitView {
             stash,
             repository,
             remote,
+            workspace,
+            changed_images,
         }
     }
 
+    fn open_image_diff(
+        &self,
+        changed_image: ChangedImage,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let sha = self.commit.sha.to_string();
+        let Some(commit) = sha.parse::<Oid>().log_err() else {
+            return;
+        };
+        let short_sha = sha.get(0..7).unwrap_or(&sha).to_string();
+        let parent_label = format!("{short_sha}^");
+        let old = if changed_image.is_added {
+            ImageDiffSource::missing(parent_label)
+        } else {
+            ImageDiffSource::repository(
+                parent_label,
+                &self.repository,
+                FileRevision::ParentOf(commit),
+                changed_image.path.clone(),
+                cx,
+            )
+        };
+        let new = if changed_image.is_deleted {
+            ImageDiffSource::missing(short_sha)
+        } else {
+            ImageDiffSource::repository(
+                short_sha,
+                &self.repository,
+                FileRevision::Commit(commit),
+                changed_image.path.clone(),
+                cx,
+            )
+        };
+        ImageDiffView::open(
+            changed_image.path,
+            old,
+            new,
+            self.workspace.clone(),
+            window,
+            cx,
+        )
+        .detach_and_notify_err(window, cx);
+    }
+
+    fn render_changed_images(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .flex_wrap()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new("Changed images:")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .children(
+                self.changed_images
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, changed_image)| {
+                        let file_name = changed_image
+                            .path
+                            .file_name()
+                            .map(|name| name.to_string())
+                            .unwrap_or_default();
+                        let tooltip = changed_image.path.display(PathStyle::Posix).to_string();
+                        let changed_image = changed_image.clone();
+                        Button::new(("commit-view-changed-image", ix), file_name)
+                            .icon(IconName::Image)
+                            .icon_size(IconSize::Small)
+                            .icon_color(Color::Muted)
+                            .icon_position(IconPosition::Start)
+                            .label_size(LabelSize::Small)
+                            .tooltip(Tooltip::text(tooltip))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.open_image_diff(changed_image.clone(), window, cx)
+                            }))
+                    }),
+            )
+    }
+
     fn render_commit_avatar(
         &self,
         sha: &SharedString,
@@ -996,6 +1110,8 @@ impl Item for CommitView {
                 stash: self.stash,
                 repository: self.repository.clone(),
                 remote: self.remote.clone(),
+                workspace: self.workspace.clone(),
+                changed_images: self.changed_images.clone(),
             }
         })))
     }
@@ -1010,6 +1126,9 @@ impl Render for CommitView {
             .size_full()
             .bg(cx.theme().colors().editor_background)
             .child(self.render_header(window, cx))
+            .when(!self.changed_images.is_empty(), |this| {
+                this.child(self.render_changed_images(cx))
+            })
             .child(div().flex_grow().child(self.editor.clone()))
     }
 }
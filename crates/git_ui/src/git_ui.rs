@@ -39,6 +39,7 @@ pub mod file_diff_view;
 pub mod file_history_view;
 pub mod git_panel;
 mod git_panel_settings;
+pub mod image_diff_view;
 pub mod onboarding;
 pub mod picker_prompt;
 pub mod project_diff;
@@ -0,0 +1,611 @@
+//! ImageDiffView compares two versions of an image, either next to each other or layered on top
+//! of each other, for changes that a text diff can't show.
+
+use anyhow::Result;
+use git::repository::{FileRevision, RepoPath};
+use gpui::{
+    AnyElement, App, AppContext as _, Context, DragMoveEvent, Empty, Entity, EventEmitter,
+    FocusHandle, Focusable, ObjectFit, Render, Task, WeakEntity, Window, canvas, img,
+};
+use image_viewer::{ImageViewerSettings, format_image_size, paint_checkered_background};
+use project::{
+    ImageItem, Project, ProjectPath,
+    git_store::Repository,
+    image_store::{ImageMetadata, create_gpui_image, is_image_extension},
+};
+use settings::{ImageFileSizeUnit, Settings as _};
+use std::sync::Arc;
+use theme::ActiveTheme;
+use ui::prelude::*;
+use util::{ResultExt as _, paths::PathStyle};
+use workspace::{
+    Item, Workspace,
+    item::{ItemEvent, TabContentParams},
+};
+
+/// Whether the file at the given path is shown as an image rather than as text.
+pub fn is_image_path(path: &RepoPath) -> bool {
+    path.extension()
+        .is_some_and(|extension| is_image_extension(&extension.to_ascii_lowercase()))
+}
+
+/// How the two versions of an image are presented.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageDiffMode {
+    SideBySide,
+    /// The new version is drawn over the old one up to a draggable divider.
+    Swipe,
+    /// The new version is drawn over the old one with adjustable opacity.
+    OnionSkin,
+}
+
+/// One version of the image being compared, loaded from wherever it lives.
+pub struct ImageDiffSource {
+    pub label: SharedString,
+    /// Resolves to the image's bytes, or `None` if the image doesn't exist in this version.
+    pub content: Task<Result<Option<Vec<u8>>>>,
+}
+
+impl ImageDiffSource {
+    /// The image at a revision of the repository.
+    pub fn repository(
+        label: impl Into<SharedString>,
+        repository: &Entity<Repository>,
+        revision: FileRevision,
+        path: RepoPath,
+        cx: &mut App,
+    ) -> Self {
+        let content = repository.update(cx, |repository, _| {
+            repository.load_file_bytes(revision, path)
+        });
+        Self {
+            label: label.into(),
+            content: cx.background_spawn(async move { content.await? }),
+        }
+    }
+
+    /// The image as it currently is in the worktree.
+    pub fn worktree(
+        label: impl Into<SharedString>,
+        project: &Entity<Project>,
+        project_path: ProjectPath,
+        cx: &mut App,
+    ) -> Self {
+        let open_image = project.update(cx, |project, cx| project.open_image(project_path, cx));
+        Self {
+            label: label.into(),
+            content: cx.spawn(async move |cx| {
+                let image = open_image.await?;
+                image.read_with(cx, |image, _| Some(image.image.bytes.clone()))
+            }),
+        }
+    }
+
+    /// A version in which the image doesn't exist, such as the old side of an added image.
+    pub fn missing(label: impl Into<SharedString>) -> Self {
+        Self {
+            label: label.into(),
+            content: Task::ready(Ok(None)),
+        }
+    }
+}
+
+struct LoadedImage {
+    label: SharedString,
+    /// `None` if the image doesn't exist in this version, or is in a format that can't be displayed.
+    image: Option<Arc<gpui::Image>>,
+    metadata: Option<ImageMetadata>,
+    file_size: Option<u64>,
+}
+
+impl LoadedImage {
+    fn decode(label: SharedString, content: Option<Vec<u8>>) -> Self {
+        let Some(content) = content else {
+            return Self {
+                label,
+                image: None,
+                metadata: None,
+                file_size: None,
+            };
+        };
+        let file_size = Some(content.len() as u64);
+        let metadata = ImageItem::compute_metadata_from_bytes(&content).log_err();
+        let image = create_gpui_image(content).log_err();
+        Self {
+            label,
+            image,
+            metadata,
+            file_size,
+        }
+    }
+
+    fn description(&self, unit: ImageFileSizeUnit) -> String {
+        match (self.file_size, self.metadata.as_ref()) {
+            (None, _) => "Not present".to_string(),
+            (Some(file_size), None) => format_image_size(file_size, unit),
+            (Some(file_size), Some(metadata)) => format!(
+                "{}×{} · {}",
+                metadata.width,
+                metadata.height,
+                format_image_size(file_size, unit)
+            ),
+        }
+    }
+}
+
+struct DraggedSwipeDivider;
+
+struct DraggedOpacitySlider;
+
+pub struct ImageDiffView {
+    path: RepoPath,
+    old: LoadedImage,
+    new: LoadedImage,
+    mode: ImageDiffMode,
+    /// How far across the image the swipe divider is, from 0 to 1.
+    swipe_position: f32,
+    /// The opacity of the new version in onion skin mode.
+    overlay_opacity: f32,
+    focus_handle: FocusHandle,
+}
+
+impl ImageDiffView {
+    pub fn open(
+        path: RepoPath,
+        old: ImageDiffSource,
+        new: ImageDiffSource,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        window.spawn(cx, async move |cx| {
+            let (old_content, new_content) = futures::join!(old.content, new.content);
+            let (old_content, new_content) = (old_content?, new_content?);
+            let (old_label, new_label) = (old.label, new.label);
+            let (old, new) = cx
+                .background_spawn(async move {
+                    (
+                        LoadedImage::decode(old_label, old_content),
+                        LoadedImage::decode(new_label, new_content),
+                    )
+                })
+                .await;
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let diff_view = cx.new(|cx| Self::new(path, old, new, cx));
+                workspace.active_pane().update(cx, |pane, cx| {
+                    pane.add_item(Box::new(diff_view.clone()), true, true, None, window, cx);
+                });
+                diff_view
+            })
+        })
+    }
+
+    fn new(path: RepoPath, old: LoadedImage, new: LoadedImage, cx: &mut Context<Self>) -> Self {
+        Self {
+            path,
+            old,
+            new,
+            mode: ImageDiffMode::SideBySide,
+            swipe_position: 0.5,
+            overlay_opacity: 0.5,
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    /// Swipe and onion skin need both versions, so without them the images are shown side by side.
+    fn can_overlay(&self) -> bool {
+        self.old.image.is_some() && self.new.image.is_some()
+    }
+
+    fn effective_mode(&self) -> ImageDiffMode {
+        if self.can_overlay() {
+            self.mode
+        } else {
+            ImageDiffMode::SideBySide
+        }
+    }
+
+    fn set_mode(&mut self, mode: ImageDiffMode, cx: &mut Context<Self>) {
+        self.mode = mode;
+        cx.notify();
+    }
+
+    fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| self.path.display(PathStyle::Posix).to_string())
+    }
+
+    fn render_mode_button(
+        &self,
+        mode: ImageDiffMode,
+        label: &'static str,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let id = match mode {
+            ImageDiffMode::SideBySide => "image-diff-side-by-side",
+            ImageDiffMode::Swipe => "image-diff-swipe",
+            ImageDiffMode::OnionSkin => "image-diff-onion-skin",
+        };
+        Button::new(id, label)
+            .style(ButtonStyle::Subtle)
+            .label_size(LabelSize::Small)
+            .toggle_state(self.effective_mode() == mode)
+            .disabled(mode != ImageDiffMode::SideBySide && !self.can_overlay())
+            .on_click(cx.listener(move |this, _, _, cx| this.set_mode(mode, cx)))
+    }
+
+    fn render_opacity_slider(&self, cx: &Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        h_flex()
+            .gap_2()
+            .child(
+                Label::new("Opacity")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                div()
+                    .id("image-diff-opacity-slider")
+                    .relative()
+                    .w(px(120.))
+                    .h(px(12.))
+                    .cursor_pointer()
+                    .on_drag(DraggedOpacitySlider, |_, _, _, cx| cx.new(|_| Empty))
+                    .on_drag_move(cx.listener(
+                        |this, event: &DragMoveEvent<DraggedOpacitySlider>, _, cx| {
+                            this.overlay_opacity = drag_fraction(event);
+                            cx.notify();
+                        },
+                    ))
+                    .child(
+                        div()
+                            .absolute()
+                            .top(px(5.))
+                            .left_0()
+                            .w_full()
+                            .h(px(2.))
+                            .rounded_sm()
+                            .bg(colors.border),
+                    )
+                    .child(
+                        div()
+                            .absolute()
+                            .top(px(2.))
+                            .left(relative(self.overlay_opacity))
+                            .ml(px(-4.))
+                            .size(px(8.))
+                            .rounded_full()
+                            .bg(colors.text_accent),
+                    ),
+            )
+            .child(
+                Label::new(format!("{:.0}%", self.overlay_opacity * 100.))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+    }
+
+    fn render_toolbar(&self, cx: &Context<Self>) -> impl IntoElement {
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(self.render_mode_button(ImageDiffMode::SideBySide, "Side by Side", cx))
+                    .child(self.render_mode_button(ImageDiffMode::Swipe, "Swipe", cx))
+                    .child(self.render_mode_button(ImageDiffMode::OnionSkin, "Onion Skin", cx)),
+            )
+            .when(self.effective_mode() == ImageDiffMode::OnionSkin, |this| {
+                this.child(self.render_opacity_slider(cx))
+            })
+    }
+
+    fn render_side_by_side(&self, cx: &Context<Self>) -> impl IntoElement {
+        let unit = ImageViewerSettings::get_global(cx).unit;
+        let panel = |image: &LoadedImage| {
+            v_flex()
+                .flex_1()
+                .min_w_0()
+                .h_full()
+                .gap_1()
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Label::new(image.label.clone()).size(LabelSize::Small))
+                        .child(
+                            Label::new(image.description(unit))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                )
+                .child(
+                    render_stage(cx)
+                        .flex_1()
+                        .min_h_0()
+                        .child(render_layer(image, cx)),
+                )
+        };
+        h_flex()
+            .size_full()
+            .gap_2()
+            .child(panel(&self.old))
+            .child(panel(&self.new))
+    }
+
+    fn render_swipe(&self, cx: &Context<Self>) -> impl IntoElement {
+        let position = self.swipe_position;
+        render_stage(cx)
+            .id("image-diff-swipe-stage")
+            .size_full()
+            .cursor_col_resize()
+            .on_drag(DraggedSwipeDivider, |_, _, _, cx| cx.new(|_| Empty))
+            .on_drag_move(
+                cx.listener(|this, event: &DragMoveEvent<DraggedSwipeDivider>, _, cx| {
+                    this.swipe_position = drag_fraction(event);
+                    cx.notify();
+                }),
+            )
+            .child(render_layer(&self.old, cx))
+            .when(position > 0., |this| {
+                // The clipped layer is narrower than the stage, so its child is widened back to the
+                // stage's width to keep the new version aligned with the old one.
+                this.child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .h_full()
+                        .w(relative(position))
+                        .overflow_hidden()
+                        .child(
+                            div()
+                                .relative()
+                                .h_full()
+                                .w(relative(1. / position))
+                                .child(render_layer(&self.new, cx)),
+                        ),
+                )
+            })
+            .child(
+                div()
+                    .absolute()
+                    .top_0()
+                    .left(relative(position))
+                    .ml(px(-1.))
+                    .h_full()
+                    .w(px(2.))
+                    .bg(cx.theme().colors().border_focused),
+            )
+    }
+
+    fn render_onion_skin(&self, cx: &Context<Self>) -> impl IntoElement {
+        render_stage(cx)
+            .size_full()
+            .child(render_layer(&self.old, cx))
+            .child(
+                div()
+                    .absolute()
+                    .top_0()
+                    .left_0()
+                    .size_full()
+                    .opacity(self.overlay_opacity)
+                    .child(render_layer(&self.new, cx)),
+            )
+    }
+
+    fn render_summary(&self, cx: &Context<Self>) -> impl IntoElement {
+        let unit = ImageViewerSettings::get_global(cx).unit;
+        let dimensions = match (self.old.metadata.as_ref(), self.new.metadata.as_ref()) {
+            (Some(old), Some(new)) => Some(format!(
+                "{}×{} → {}×{}{}",
+                old.width,
+                old.height,
+                new.width,
+                new.height,
+                dimensions_delta((old.width, old.height), (new.width, new.height))
+                    .map(|delta| format!(" ({delta})"))
+                    .unwrap_or_default()
+            )),
+            (Some(metadata), None) | (None, Some(metadata)) => {
+                Some(format!("{}×{}", metadata.width, metadata.height))
+            }
+            (None, None) => None,
+        };
+        let file_size = match (self.old.file_size, self.new.file_size) {
+            (Some(old), Some(new)) => format!(
+                "{} → {} ({})",
+                format_image_size(old, unit),
+                format_image_size(new, unit),
+                size_delta(old, new, unit)
+            ),
+            (None, Some(new)) => format!("Added · {}", format_image_size(new, unit)),
+            (Some(old), None) => format!("Deleted · {}", format_image_size(old, unit)),
+            (None, None) => "Not present in either version".to_string(),
+        };
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_3()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new(format!("{} → {}", self.old.label, self.new.label))
+                    .size(LabelSize::Small),
+            )
+            .children(dimensions.map(|dimensions| {
+                Label::new(dimensions)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+            }))
+            .child(
+                Label::new(file_size)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+    }
+}
+
+/// A frame with the checkerboard behind it, in which image layers are stacked.
+fn render_stage(cx: &App) -> Div {
+    div()
+        .relative()
+        .overflow_hidden()
+        .border_1()
+        .border_color(cx.theme().colors().border)
+        .child(
+            canvas(
+                |_, _, _| (),
+                |bounds, _, window, _| paint_checkered_background(bounds, window),
+            )
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full(),
+        )
+}
+
+fn render_layer(image: &LoadedImage, cx: &App) -> AnyElement {
+    let layer = div().absolute().top_0().left_0().size_full();
+    match &image.image {
+        Some(gpui_image) => layer
+            .child(
+                img(gpui_image.clone())
+                    .object_fit(ObjectFit::ScaleDown)
+                    .size_full(),
+            )
+            .into_any_element(),
+        None => {
+            let message = if image.file_size.is_some() {
+                "This image format can't be displayed"
+            } else {
+                "Not present"
+            };
+            layer
+                .flex()
+                .items_center()
+                .justify_center()
+                .bg(cx.theme().colors().editor_background)
+                .child(Label::new(message).color(Color::Muted))
+                .into_any_element()
+        }
+    }
+}
+
+fn drag_fraction<T>(event: &DragMoveEvent<T>) -> f32 {
+    let bounds = event.bounds;
+    ((event.event.position.x - bounds.left()) / bounds.size.width).clamp(0., 1.)
+}
+
+fn dimensions_delta(old: (u32, u32), new: (u32, u32)) -> Option<String> {
+    if old == new {
+        return None;
+    }
+    let width = i64::from(new.0) - i64::from(old.0);
+    let height = i64::from(new.1) - i64::from(old.1);
+    Some(format!("{width:+}×{height:+}"))
+}
+
+fn size_delta(old: u64, new: u64, unit: ImageFileSizeUnit) -> String {
+    if new >= old {
+        format!("+{}", format_image_size(new - old, unit))
+    } else {
+        format!("-{}", format_image_size(old - new, unit))
+    }
+}
+
+impl EventEmitter<ItemEvent> for ImageDiffView {}
+
+impl Focusable for ImageDiffView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for ImageDiffView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Image).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        format!(
+            "{} ({} ↔ {})",
+            self.file_name(),
+            self.old.label,
+            self.new.label
+        )
+        .into()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(self.path.display(PathStyle::Posix).to_string().into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Image Diff View Opened")
+    }
+}
+
+impl Render for ImageDiffView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("ImageDiffView")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_toolbar(cx))
+            .child(
+                div()
+                    .flex_1()
+                    .min_h_0()
+                    .p_2()
+                    .map(|this| match self.effective_mode() {
+                        ImageDiffMode::SideBySide => this.child(self.render_side_by_side(cx)),
+                        ImageDiffMode::Swipe => this.child(self.render_swipe(cx)),
+                        ImageDiffMode::OnionSkin => this.child(self.render_onion_skin(cx)),
+                    }),
+            )
+            .child(self.render_summary(cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deltas() {
+        assert_eq!(dimensions_delta((16, 16), (16, 16)), None);
+        assert_eq!(
+            dimensions_delta((16, 32), (32, 24)),
+            Some("+16×-8".to_string())
+        );
+        assert_eq!(size_delta(1024, 3072, ImageFileSizeUnit::Binary), "+2.0KiB");
+        assert_eq!(size_delta(3000, 1000, ImageFileSizeUnit::Decimal), "-2.0KB");
+        assert_eq!(size_delta(10, 10, ImageFileSizeUnit::Binary), "+0B");
+    }
+}
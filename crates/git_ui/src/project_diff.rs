@@ -2,6 +2,7 @@ use crate::{
     conflict_view::ConflictAddon,
     git_panel::{GitPanel, GitPanelAddon, GitStatusEntry},
     git_panel_settings::GitPanelSettings,
+    image_diff_view::{ImageDiffSource, ImageDiffView, is_image_path},
     remote_button::{render_publish_button, render_push_button},
 };
 use anyhow::{Context as _, Result, anyhow};
@@ -15,8 +16,10 @@ use editor::{
 };
 use git::{
     Commit, StageAll, StageAndNext, ToggleStaged, UnstageAll, UnstageAndNext,
-    repository::{Branch, RepoPath, Upstream, UpstreamTracking, UpstreamTrackingStatus},
-    status::FileStatus,
+    repository::{
+        Branch, FileRevision, RepoPath, Upstream, UpstreamTracking, UpstreamTrackingStatus,
+    },
+    status::{FileStatus, TreeDiffStatus},
 };
 use gpui::{
     Action, AnyElement, App, AppContext as _, AsyncWindowContext, Entity, EventEmitter,
@@ -37,7 +40,7 @@ use std::any::{Any, TypeId};
 use std::sync::Arc;
 use theme::ActiveTheme;
 use ui::{KeyBinding, Tooltip, prelude::*, vertical_divider};
-use util::{ResultExt as _, rel_path::RelPath};
+use util::{ResultExt as _, paths::PathStyle, rel_path::RelPath};
 use workspace::{
     CloseActiveItem, ItemNavHistory, SerializableItem, ToolbarItemEvent, ToolbarItemLocation,
     ToolbarItemView, Workspace,
@@ -70,6 +73,8 @@ pub struct ProjectDiff {
     workspace: WeakEntity<Workspace>,
     focus_handle: FocusHandle,
     pending_scroll: Option<PathKey>,
+    /// Changed images, which can't be shown in the multibuffer and are listed above it instead.
+    changed_images: Vec<(RepoPath, FileStatus)>,
     _task: Task<Result<()>>,
     _subscription: Subscription,
 }
@@ -320,6 +325,7 @@ impl ProjectDiff {
             multibuffer,
             buffer_diff_subscriptions: Default::default(),
             pending_scroll: None,
+            changed_images: Vec::new(),
             _task: task,
             _subscription: branch_diff_subscription,
         }
@@ -335,6 +341,10 @@ impl ProjectDiff {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if is_image_path(&entry.repo_path) {
+            self.open_image_diff(entry.repo_path, entry.status, window, cx);
+            return;
+        }
         let Some(git_repo) = self.branch_diff.read(cx).repo() else {
             return;
         };
@@ -345,6 +355,54 @@ impl ProjectDiff {
         self.move_to_path(path_key, window, cx)
     }
 
+    fn open_image_diff(
+        &mut self,
+        repo_path: RepoPath,
+        file_status: FileStatus,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let branch_diff = self.branch_diff.read(cx);
+        let Some(repository) = branch_diff.repo().cloned() else {
+            return;
+        };
+        let (base_label, base_revision) = match branch_diff.diff_base() {
+            DiffBase::Head => (SharedString::from("HEAD"), Some(FileRevision::Head)),
+            DiffBase::Merge { base_ref } => {
+                let revision = match branch_diff.tree_diff_status(&repo_path) {
+                    Some(TreeDiffStatus::Added) => None,
+                    Some(TreeDiffStatus::Modified { .. } | TreeDiffStatus::Deleted { .. }) => {
+                        Some(FileRevision::MergeBase(base_ref.clone()))
+                    }
+                    // Unchanged since the merge base, so the base version is the one in HEAD.
+                    None => Some(FileRevision::Head),
+                };
+                (base_ref.clone(), revision)
+            }
+        };
+        let old = match base_revision {
+            Some(revision) => ImageDiffSource::repository(
+                base_label,
+                &repository,
+                Some(revision),
+                repo_path.clone(),
+                cx,
+            ),
+            None => ImageDiffSource::missing(base_label),
+        };
+        let project_path = repository
+            .read(cx)
+            .repo_path_to_project_path(&repo_path, cx);
+        let new = match project_path {
+            Some(project_path) if !file_status.is_deleted() => {
+                ImageDiffSource::worktree("Working Copy", &self.project, project_path, cx)
+            }
+            _ => ImageDiffSource::missing("Working Copy"),
+        };
+        ImageDiffView::open(repo_path, old, new, self.workspace.clone(), window, cx)
+            .detach_and_notify_err(window, cx);
+    }
+
     pub fn active_path(&self, cx: &App) -> Option<ProjectPath> {
         let editor = self.editor.read(cx).last_selected_editor().read(cx);
         let position = editor.selections.newest_anchor().head();
@@ -588,6 +646,13 @@ impl ProjectDiff {
                 let load_buffers = branch_diff.load_buffers(cx);
                 (branch_diff.repo().cloned(), load_buffers)
             });
+            let (changed_images, buffers_to_load): (Vec<_>, Vec<_>) = buffers_to_load
+                .into_iter()
+                .partition(|entry| is_image_path(&entry.repo_path));
+            this.changed_images = changed_images
+                .into_iter()
+                .map(|entry| (entry.repo_path, entry.file_status))
+                .collect();
             let mut previous_paths = this.multibuffer.read(cx).paths().collect::<HashSet<_>>();
 
             if let Some(repo) = repo {
@@ -863,9 +928,48 @@ impl Item for ProjectDiff {
     }
 }
 
+impl ProjectDiff {
+    fn render_changed_images(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .flex_wrap()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new("Changed images:")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .children(self.changed_images.iter().enumerate().map(
+                |(ix, (repo_path, file_status))| {
+                    let file_name = repo_path
+                        .file_name()
+                        .map(|name| name.to_string())
+                        .unwrap_or_default();
+                    let tooltip = repo_path.display(PathStyle::Posix).to_string();
+                    let repo_path = repo_path.clone();
+                    let file_status = *file_status;
+                    Button::new(("project-diff-changed-image", ix), file_name)
+                        .icon(IconName::Image)
+                        .icon_size(IconSize::Small)
+                        .icon_color(Color::Muted)
+                        .icon_position(IconPosition::Start)
+                        .label_size(LabelSize::Small)
+                        .tooltip(Tooltip::text(tooltip))
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.open_image_diff(repo_path.clone(), file_status, window, cx)
+                        }))
+                },
+            ))
+    }
+}
+
 impl Render for ProjectDiff {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let is_empty = self.multibuffer.read(cx).is_empty();
+        let is_empty = self.multibuffer.read(cx).is_empty() && self.changed_images.is_empty();
 
         div()
             .track_focus(&self.focus_handle)
@@ -922,7 +1026,18 @@ impl Render for ProjectDiff {
                         ),
                 )
             })
-            .when(!is_empty, |el| el.child(self.editor.clone()))
+            .when(!is_empty, |el| {
+                if self.changed_images.is_empty() {
+                    el.child(self.editor.clone())
+                } else {
+                    el.child(
+                        v_flex()
+                            .size_full()
+                            .child(self.render_changed_images(cx))
+                            .child(div().flex_1().min_h_0().child(self.editor.clone())),
+                    )
+                }
+            })
     }
 }
 
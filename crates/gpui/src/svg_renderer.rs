@@ -64,7 +64,8 @@ impl SvgRenderer {
         }
     }
 
-    /// Renders the given bytes into an image buffer.
+    /// Renders the given bytes into an image buffer, with `scale_factor` device pixels per
+    /// pixel of the SVG's own size, which remains the image's size when displayed.
     pub fn render_single_frame(
         &self,
         bytes: &[u8],
@@ -87,7 +88,7 @@ impl SvgRenderer {
             }
 
             let mut image = RenderImage::new(SmallVec::from_const([Frame::new(buffer)]));
-            image.scale_factor = SMOOTH_SVG_SCALE_FACTOR;
+            image.scale_factor = scale_factor * SMOOTH_SVG_SCALE_FACTOR;
             Arc::new(image)
         })
     }
@@ -0,0 +1,966 @@
+mod hex_file;
+mod search;
+
+use std::{ops::Range, path::Path, sync::LazyLock};
+
+use anyhow::Result;
+use editor::{Editor, EditorElement, EditorStyle};
+use file_icons::FileIcons;
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, KeyDownEvent,
+    MouseButton, MouseDownEvent, MouseMoveEvent, ScrollStrategy, Subscription, Task, TextStyle,
+    UniformListScrollHandle, Window, actions, transparent_black, uniform_list,
+};
+use language::{DiskState, File as _};
+use project::{Project, ProjectPath};
+use settings::Settings as _;
+use theme::ThemeSettings;
+use ui::{Divider, Tooltip, WithScrollbar as _, prelude::*};
+use util::paths::PathExt as _;
+use workspace::{
+    ItemSettings, Pane, SaveIntent, Workspace, WorkspaceId,
+    invalid_item_view::InvalidItemView,
+    item::{Item, ItemEvent, ProjectItem, SaveOptions},
+};
+use zed_actions::hex_editor::OpenInHexEditor;
+
+use crate::search::{find_next, find_previous, parse_search_pattern};
+
+pub use crate::hex_file::{HexFile, HexFileEvent};
+
+actions!(
+    hex_editor,
+    [
+        /// Moves the cursor to the previous byte.
+        MoveLeft,
+        /// Moves the cursor to the next byte.
+        MoveRight,
+        /// Moves the cursor to the byte in the previous row.
+        MoveUp,
+        /// Moves the cursor to the byte in the next row.
+        MoveDown,
+        /// Moves the cursor to the first byte of the file.
+        MoveToBeginning,
+        /// Moves the cursor to the last byte of the file.
+        MoveToEnd,
+        /// Extends the selection to the previous byte.
+        SelectLeft,
+        /// Extends the selection to the next byte.
+        SelectRight,
+        /// Extends the selection to the byte in the previous row.
+        SelectUp,
+        /// Extends the selection to the byte in the next row.
+        SelectDown,
+        /// Switches typing between the hex and the ASCII column.
+        ToggleColumn,
+        /// Focuses the query bar to search for hex bytes or text.
+        Find,
+        /// Selects the next match of the search query.
+        SelectNextMatch,
+        /// Selects the previous match of the search query.
+        SelectPreviousMatch,
+        /// Focuses the query bar to jump to an offset.
+        GoToOffset,
+        /// Restores the bytes overwritten by the last edit.
+        Undo,
+    ]
+);
+
+const BYTES_PER_ROW: usize = 16;
+
+static HEX_BYTES_MEMOIZED: LazyLock<[SharedString; 256]> =
+    LazyLock::new(|| std::array::from_fn(|byte| SharedString::from(format!("{byte:02X}"))));
+
+pub fn init(cx: &mut App) {
+    workspace::register_project_item::<HexEditor>(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(open_in_hex_editor);
+    })
+    .detach();
+}
+
+/// Reopens the file of the active item in the hex editor, in place of that item.
+fn open_in_hex_editor(
+    workspace: &mut Workspace,
+    _: &OpenInHexEditor,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let pane = workspace.active_pane().clone();
+    let Some(active_item) = pane.read(cx).active_item() else {
+        return;
+    };
+    if active_item.downcast::<HexEditor>().is_some() {
+        return;
+    }
+    let project_path = match active_item.downcast::<InvalidItemView>() {
+        Some(invalid_item_view) => project
+            .read(cx)
+            .find_project_path(&invalid_item_view.read(cx).abs_path, cx),
+        None => active_item.project_path(cx),
+    };
+    let Some(project_path) = project_path else {
+        return;
+    };
+
+    let open_hex_file = HexFile::open(&project, &project_path, cx);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let hex_file = open_hex_file.await?;
+        // The pane deduplicates items by their project entry, so the item showing the same file
+        // has to be closed before the hex editor is added.
+        let (item_index, close_item) = pane.update_in(cx, |pane, window, cx| {
+            let item_index = pane.index_for_item(active_item.as_ref());
+            let close_item =
+                pane.close_item_by_id(active_item.item_id(), SaveIntent::Close, window, cx);
+            (item_index, close_item)
+        })?;
+        close_item.await?;
+        workspace.update_in(cx, |workspace, window, cx| {
+            if pane.read(cx).index_for_item(active_item.as_ref()).is_some() {
+                return;
+            }
+            let hex_editor = cx.new(|cx| HexEditor::new(hex_file, project, window, cx));
+            workspace.add_item(
+                pane,
+                Box::new(hex_editor),
+                item_index,
+                true,
+                true,
+                window,
+                cx,
+            );
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// The column that typed characters are written to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Column {
+    Hex,
+    Ascii,
+}
+
+/// What text typed into the query bar is used for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum QueryMode {
+    Find,
+    GoToOffset,
+}
+
+impl QueryMode {
+    fn placeholder_text(&self) -> &'static str {
+        match self {
+            QueryMode::Find => "Find Hex Bytes or \"Text\"",
+            QueryMode::GoToOffset => "Go to Offset",
+        }
+    }
+}
+
+/// A range of selected bytes, both ends included.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct ByteSelection {
+    anchor: usize,
+    head: usize,
+}
+
+impl ByteSelection {
+    fn range(&self) -> Range<usize> {
+        self.anchor.min(self.head)..self.anchor.max(self.head) + 1
+    }
+}
+
+/// Shows a file as rows of offsets, hex bytes and their ASCII characters, and overwrites its
+/// bytes as hex digits or characters are typed.
+pub struct HexEditor {
+    hex_file: Entity<HexFile>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    query_editor: Entity<Editor>,
+    query_mode: QueryMode,
+    selection: ByteSelection,
+    column: Column,
+    /// Whether the high nibble of the byte under the cursor has been typed, so the next hex
+    /// digit goes to its low nibble.
+    is_editing_low_nibble: bool,
+    is_selecting: bool,
+    status_message: Option<SharedString>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl HexEditor {
+    pub fn new(
+        hex_file: Entity<HexFile>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let query_mode = QueryMode::Find;
+        let query_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(query_mode.placeholder_text(), window, cx);
+            editor
+        });
+        let subscriptions = vec![cx.subscribe(&hex_file, Self::on_hex_file_event)];
+
+        Self {
+            hex_file,
+            project,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            query_editor,
+            query_mode,
+            selection: ByteSelection::default(),
+            column: Column::Hex,
+            is_editing_low_nibble: false,
+            is_selecting: false,
+            status_message: None,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    pub fn hex_file(&self) -> &Entity<HexFile> {
+        &self.hex_file
+    }
+
+    /// The range of selected bytes.
+    pub fn selected_range(&self, cx: &App) -> Range<usize> {
+        if self.hex_file.read(cx).is_empty() {
+            0..0
+        } else {
+            self.selection.range()
+        }
+    }
+
+    fn on_hex_file_event(
+        &mut self,
+        hex_file: Entity<HexFile>,
+        event: &HexFileEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            HexFileEvent::Edited => cx.emit(ItemEvent::Edit),
+            HexFileEvent::DirtyChanged | HexFileEvent::Saved => cx.emit(ItemEvent::UpdateTab),
+            HexFileEvent::Reloaded => {
+                let last_offset = hex_file.read(cx).len().saturating_sub(1);
+                self.selection.anchor = self.selection.anchor.min(last_offset);
+                self.selection.head = self.selection.head.min(last_offset);
+                self.is_editing_low_nibble = false;
+                cx.emit(ItemEvent::UpdateTab);
+            }
+        }
+        cx.notify();
+    }
+
+    fn row_count(&self, cx: &App) -> usize {
+        self.hex_file.read(cx).len().div_ceil(BYTES_PER_ROW).max(1)
+    }
+
+    fn select_range(&mut self, range: Range<usize>, cx: &mut Context<Self>) {
+        let last_offset = self.hex_file.read(cx).len().saturating_sub(1);
+        self.selection = ByteSelection {
+            anchor: range.start.min(last_offset),
+            head: range
+                .end
+                .saturating_sub(1)
+                .max(range.start)
+                .min(last_offset),
+        };
+        self.is_editing_low_nibble = false;
+        self.scroll_to_offset(self.selection.head);
+        cx.notify();
+    }
+
+    fn move_head(&mut self, delta: isize, extend: bool, cx: &mut Context<Self>) {
+        let last_offset = self.hex_file.read(cx).len().saturating_sub(1);
+        let Some(head) = self
+            .selection
+            .head
+            .checked_add_signed(delta)
+            .filter(|head| *head <= last_offset)
+        else {
+            return;
+        };
+        self.selection.head = head;
+        if !extend {
+            self.selection.anchor = head;
+        }
+        self.is_editing_low_nibble = false;
+        self.status_message = None;
+        self.scroll_to_offset(head);
+        cx.notify();
+    }
+
+    fn scroll_to_offset(&self, offset: usize) {
+        self.scroll_handle
+            .scroll_to_item(offset / BYTES_PER_ROW, ScrollStrategy::Nearest);
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_head(-1, false, cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_head(1, false, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_head(-(BYTES_PER_ROW as isize), false, cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_head(BYTES_PER_ROW as isize, false, cx);
+    }
+
+    fn move_to_beginning(&mut self, _: &MoveToBeginning, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_range(0..1, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        let len = self.hex_file.read(cx).len();
+        self.select_range(len.saturating_sub(1)..len, cx);
+    }
+
+    fn select_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_head(-1, true, cx);
+    }
+
+    fn select_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_head(1, true, cx);
+    }
+
+    fn select_up(&mut self, _: &SelectUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_head(-(BYTES_PER_ROW as isize), true, cx);
+    }
+
+    fn select_down(&mut self, _: &SelectDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_head(BYTES_PER_ROW as isize, true, cx);
+    }
+
+    fn toggle_column(&mut self, _: &ToggleColumn, _: &mut Window, cx: &mut Context<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Ascii,
+            Column::Ascii => Column::Hex,
+        };
+        self.is_editing_low_nibble = false;
+        cx.notify();
+    }
+
+    fn select_byte(
+        &mut self,
+        offset: usize,
+        column: Column,
+        extend: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.selection.head = offset;
+        if !extend {
+            self.selection.anchor = offset;
+        }
+        self.column = column;
+        self.is_editing_low_nibble = false;
+        self.is_selecting = true;
+        self.status_message = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let modifiers = &event.keystroke.modifiers;
+        if modifiers.control || modifiers.alt || modifiers.platform || modifiers.function {
+            return;
+        }
+        let Some(key_char) = event.keystroke.key_char.as_deref() else {
+            return;
+        };
+        let mut characters = key_char.chars();
+        let (Some(character), None) = (characters.next(), characters.next()) else {
+            return;
+        };
+        match self.column {
+            Column::Hex => {
+                let Some(digit) = character.to_digit(16) else {
+                    return;
+                };
+                self.type_hex_digit(digit as u8, cx);
+            }
+            Column::Ascii => {
+                if !character.is_ascii() || character.is_ascii_control() {
+                    return;
+                }
+                self.overwrite_at_cursor(character as u8, true, cx);
+            }
+        }
+        cx.stop_propagation();
+    }
+
+    fn type_hex_digit(&mut self, digit: u8, cx: &mut Context<Self>) {
+        let Some(&byte) = self.hex_file.read(cx).bytes().get(self.cursor()) else {
+            return;
+        };
+        if self.is_editing_low_nibble {
+            self.is_editing_low_nibble = false;
+            self.overwrite_at_cursor((byte & 0xF0) | digit, true, cx);
+        } else {
+            self.overwrite_at_cursor((digit << 4) | (byte & 0x0F), false, cx);
+            self.is_editing_low_nibble = true;
+        }
+    }
+
+    /// The offset of the byte that typed characters overwrite.
+    fn cursor(&self) -> usize {
+        self.selection.range().start
+    }
+
+    fn overwrite_at_cursor(&mut self, byte: u8, advance: bool, cx: &mut Context<Self>) {
+        let offset = self.cursor();
+        self.hex_file.update(cx, |hex_file, cx| {
+            hex_file.overwrite(offset, &[byte], cx);
+        });
+        self.selection = ByteSelection {
+            anchor: offset,
+            head: offset,
+        };
+        self.status_message = None;
+        if advance {
+            self.move_head(1, false, cx);
+        }
+    }
+
+    fn undo(&mut self, _: &Undo, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(range) = self.hex_file.update(cx, |hex_file, cx| hex_file.undo(cx)) {
+            self.select_range(range, cx);
+        }
+    }
+
+    fn set_query_mode(
+        &mut self,
+        query_mode: QueryMode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.query_mode != query_mode {
+            self.query_mode = query_mode;
+            self.query_editor.update(cx, |editor, cx| {
+                editor.clear(window, cx);
+                editor.set_placeholder_text(query_mode.placeholder_text(), window, cx);
+            });
+        }
+        self.query_editor.focus_handle(cx).focus(window);
+        cx.notify();
+    }
+
+    fn find(&mut self, _: &Find, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_query_mode(QueryMode::Find, window, cx);
+    }
+
+    fn go_to_offset(&mut self, _: &GoToOffset, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_query_mode(QueryMode::GoToOffset, window, cx);
+    }
+
+    fn select_next_match(&mut self, _: &SelectNextMatch, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_match(true, cx);
+    }
+
+    fn select_previous_match(
+        &mut self,
+        _: &SelectPreviousMatch,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.select_match(false, cx);
+    }
+
+    fn select_match(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let query = self.query_editor.read(cx).text(cx);
+        let Some(pattern) = parse_search_pattern(&query) else {
+            return;
+        };
+        let selected_range = self.selected_range(cx);
+        let bytes = self.hex_file.read(cx).bytes();
+        let found = if forward {
+            // Skip the current match, so repeated searches move through the matches.
+            let is_on_match = bytes.get(selected_range.clone()) == Some(pattern.as_slice());
+            let start = selected_range.start + usize::from(is_on_match);
+            find_next(bytes, &pattern, start)
+        } else {
+            find_previous(bytes, &pattern, selected_range.start)
+        };
+        match found {
+            Some(offset) => {
+                self.status_message = None;
+                self.select_range(offset..offset + pattern.len(), cx);
+            }
+            None => {
+                self.status_message = Some("No matches".into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.query_editor.focus_handle(cx).is_focused(window) {
+            return;
+        }
+        match self.query_mode {
+            QueryMode::Find => self.select_match(true, cx),
+            QueryMode::GoToOffset => {
+                let text = self.query_editor.read(cx).text(cx);
+                let len = self.hex_file.read(cx).len();
+                match parse_int::parse::<usize>(text.trim()) {
+                    Ok(offset) if offset < len => {
+                        self.status_message = None;
+                        self.select_range(offset..offset + 1, cx);
+                        window.focus(&self.focus_handle);
+                    }
+                    Ok(_) => {
+                        self.status_message =
+                            Some(format!("The file is only {len:#X} bytes long").into());
+                        cx.notify();
+                    }
+                    Err(_) => {
+                        self.status_message = Some("Invalid offset".into());
+                        cx.notify();
+                    }
+                }
+            }
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.query_editor.focus_handle(cx).is_focused(window) {
+            window.focus(&self.focus_handle);
+        } else {
+            self.selection.anchor = self.selection.head;
+        }
+        self.status_message = None;
+        cx.notify();
+    }
+
+    fn editor_style(editor: &Entity<Editor>, cx: &Context<Self>) -> EditorStyle {
+        let settings = ThemeSettings::get_global(cx);
+        let theme = cx.theme();
+        let text_style = TextStyle {
+            color: if editor.read(cx).read_only(cx) {
+                theme.colors().text_muted
+            } else {
+                theme.colors().text
+            },
+            font_family: settings.buffer_font.family.clone(),
+            font_features: settings.buffer_font.features.clone(),
+            font_size: TextSize::Small.rems(cx).into(),
+            font_weight: settings.buffer_font.weight,
+            ..Default::default()
+        };
+        EditorStyle {
+            background: theme.colors().editor_background,
+            local_player: theme.players().local(),
+            text: text_style,
+            ..Default::default()
+        }
+    }
+
+    fn render_query_bar(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let query_mode_button = |id, icon, query_mode: QueryMode, tooltip| {
+            IconButton::new(id, icon)
+                .icon_size(IconSize::XSmall)
+                .toggle_state(self.query_mode == query_mode)
+                .tooltip(Tooltip::text(tooltip))
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    this.set_query_mode(query_mode, window, cx)
+                }))
+        };
+
+        h_flex()
+            .w_full()
+            .gap_1()
+            .child(
+                h_flex()
+                    .w_full()
+                    .rounded_md()
+                    .border_1()
+                    .gap_x_2()
+                    .px_2()
+                    .py_0p5()
+                    .bg(cx.theme().colors().editor_background)
+                    .when_else(
+                        self.query_editor
+                            .focus_handle(cx)
+                            .contains_focused(window, cx),
+                        |this| this.border_color(cx.theme().colors().border_focused),
+                        |this| this.border_color(cx.theme().colors().border_transparent),
+                    )
+                    .child(EditorElement::new(
+                        &self.query_editor,
+                        Self::editor_style(&self.query_editor, cx),
+                    )),
+            )
+            .child(query_mode_button(
+                "hex-editor-find",
+                IconName::MagnifyingGlass,
+                QueryMode::Find,
+                "Find Hex Bytes or Text",
+            ))
+            .child(query_mode_button(
+                "hex-editor-go-to-offset",
+                IconName::Hash,
+                QueryMode::GoToOffset,
+                "Go to Offset",
+            ))
+    }
+
+    fn render_rows(&self, rows: Range<usize>, cx: &mut Context<Self>) -> Vec<AnyElement> {
+        let bytes = self.hex_file.read(cx).bytes();
+        let offset_digits = if bytes.len() > u32::MAX as usize {
+            16
+        } else {
+            8
+        };
+        let selected_range = self.selected_range(cx);
+        let cursor = selected_range.start;
+        let selection_color = Color::Selected.color(cx).opacity(0.2);
+        let cursor_color = cx.theme().players().local().cursor;
+        let muted_color = Color::Muted.color(cx);
+
+        rows.map(|row| {
+            let row_start = row * BYTES_PER_ROW;
+            let row_end = (row_start + BYTES_PER_ROW).min(bytes.len());
+            let row_bytes = &bytes[row_start.min(row_end)..row_end];
+            let cell = |offset: usize, column: Column| {
+                let is_cursor = offset == cursor && column == self.column;
+                div()
+                    .px_0p5()
+                    .border_1()
+                    .border_color(if is_cursor {
+                        cursor_color
+                    } else {
+                        transparent_black()
+                    })
+                    .when(selected_range.contains(&offset), |this| {
+                        this.bg(selection_color)
+                    })
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                            this.select_byte(offset, column, event.modifiers.shift, window, cx)
+                        }),
+                    )
+                    .on_mouse_move(cx.listener(move |this, event: &MouseMoveEvent, _, cx| {
+                        if this.is_selecting && event.dragging() && this.selection.head != offset {
+                            this.selection.head = offset;
+                            cx.notify();
+                        }
+                    }))
+            };
+
+            h_flex()
+                .gap_x_2()
+                .child(
+                    div()
+                        .child(
+                            Label::new(format!("{row_start:0offset_digits$X}"))
+                                .buffer_font(cx)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .px_1()
+                        .border_r_1()
+                        .border_color(muted_color),
+                )
+                .child(h_flex().px_1().children(row_bytes.iter().enumerate().map(
+                    |(index, byte)| {
+                        cell(row_start + index, Column::Hex).child(
+                            Label::new(HEX_BYTES_MEMOIZED[*byte as usize].clone())
+                                .buffer_font(cx)
+                                .size(LabelSize::Small),
+                        )
+                    },
+                )))
+                .child(
+                    h_flex()
+                        .px_1()
+                        .border_l_1()
+                        .border_color(muted_color)
+                        .children(row_bytes.iter().enumerate().map(|(index, byte)| {
+                            let character = char::from(*byte);
+                            let visible_character = if character.is_ascii_graphic() {
+                                character
+                            } else {
+                                '·'
+                            };
+                            cell(row_start + index, Column::Ascii).child(
+                                Label::new(visible_character.to_string())
+                                    .buffer_font(cx)
+                                    .size(LabelSize::Small)
+                                    .when(!character.is_ascii_graphic(), |this| {
+                                        this.color(Color::Muted)
+                                    }),
+                            )
+                        })),
+                )
+                .into_any_element()
+        })
+        .collect()
+    }
+
+    fn render_status(&self, cx: &Context<Self>) -> impl IntoElement {
+        let len = self.hex_file.read(cx).len();
+        let selected_range = self.selected_range(cx);
+        let selection_text = if selected_range.len() > 1 {
+            format!(
+                "{:#X}–{:#X} ({} bytes)",
+                selected_range.start,
+                selected_range.end - 1,
+                selected_range.len()
+            )
+        } else {
+            format!("{:#X} ({})", selected_range.start, selected_range.start)
+        };
+        let column_text = match self.column {
+            Column::Hex => "Hex",
+            Column::Ascii => "ASCII",
+        };
+
+        h_flex()
+            .w_full()
+            .gap_2()
+            .px_2()
+            .child(
+                Label::new(selection_text)
+                    .buffer_font(cx)
+                    .size(LabelSize::Small),
+            )
+            .child(
+                Label::new(format!("{len} bytes"))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .children(self.status_message.clone().map(|message| {
+                Label::new(message)
+                    .size(LabelSize::Small)
+                    .color(Color::Warning)
+            }))
+            .child(div().flex_1())
+            .child(
+                div()
+                    .id("hex-editor-column")
+                    .child(
+                        Label::new(column_text)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .tooltip(Tooltip::text("The column typed characters are written to")),
+            )
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let row_count = self.row_count(cx);
+        v_flex()
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::select_left))
+            .on_action(cx.listener(Self::select_right))
+            .on_action(cx.listener(Self::select_up))
+            .on_action(cx.listener(Self::select_down))
+            .on_action(cx.listener(Self::toggle_column))
+            .on_action(cx.listener(Self::find))
+            .on_action(cx.listener(Self::go_to_offset))
+            .on_action(cx.listener(Self::select_next_match))
+            .on_action(cx.listener(Self::select_previous_match))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .size_full()
+            .p_1()
+            .gap_1()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_query_bar(window, cx))
+            .child(Divider::horizontal())
+            .child(
+                v_flex()
+                    .key_context("HexEditor")
+                    .size_full()
+                    .track_focus(&self.focus_handle)
+                    .on_key_down(cx.listener(Self::key_down))
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _, _, _| this.is_selecting = false),
+                    )
+                    .on_mouse_up_out(
+                        MouseButton::Left,
+                        cx.listener(|this, _, _, _| this.is_selecting = false),
+                    )
+                    .child(
+                        uniform_list(
+                            "hex-editor-rows",
+                            row_count,
+                            cx.processor(|this, rows, _, cx| this.render_rows(rows, cx)),
+                        )
+                        .track_scroll(&self.scroll_handle)
+                        .size_full(),
+                    )
+                    .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+            )
+            .child(Divider::horizontal())
+            .child(self.render_status(cx))
+    }
+}
+
+impl Focusable for HexEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<ItemEvent> for HexEditor {}
+
+impl Item for HexEditor {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.hex_file.entity_id(), self.hex_file.read(cx))
+    }
+
+    fn buffer_kind(&self, _: &App) -> workspace::item::ItemBufferKind {
+        workspace::item::ItemBufferKind::Singleton
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.hex_file
+            .read(cx)
+            .file()
+            .file_name(cx)
+            .to_string()
+            .into()
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = self.hex_file.read(cx).abs_path(cx);
+        Some(abs_path.compact().to_string_lossy().into_owned().into())
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let abs_path = self.hex_file.read(cx).abs_path(cx);
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(&abs_path, cx))
+            .flatten()
+            .map(Icon::from_path)
+            .or_else(|| Some(Icon::new(IconName::Binary)))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Hex Editor Opened")
+    }
+
+    fn can_split(&self) -> bool {
+        true
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Entity<Self>>>
+    where
+        Self: Sized,
+    {
+        Task::ready(Some(cx.new(|cx| {
+            Self::new(self.hex_file.clone(), self.project.clone(), window, cx)
+        })))
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        project::ProjectItem::is_dirty(self.hex_file.read(cx))
+    }
+
+    fn has_deleted_file(&self, cx: &App) -> bool {
+        self.hex_file.read(cx).file().disk_state() == DiskState::Deleted
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.hex_file.read(cx).file().is_local
+    }
+
+    fn save(
+        &mut self,
+        _: SaveOptions,
+        project: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let fs = project.read(cx).fs().clone();
+        self.hex_file
+            .update(cx, |hex_file, cx| hex_file.save(fs, cx))
+    }
+
+    fn save_as(
+        &mut self,
+        _: Entity<Project>,
+        _: ProjectPath,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        Task::ready(Err(anyhow::anyhow!(
+            "the hex editor can only save files in place"
+        )))
+    }
+
+    fn reload(
+        &mut self,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.hex_file.update(cx, |hex_file, cx| hex_file.reload(cx))
+    }
+}
+
+impl ProjectItem for HexEditor {
+    type Item = HexFile;
+
+    fn for_project_item(
+        project: Entity<Project>,
+        _: Option<&Pane>,
+        item: Entity<Self::Item>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, project, window, cx)
+    }
+
+    fn for_broken_project_item(
+        abs_path: &Path,
+        is_local: bool,
+        e: &anyhow::Error,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<InvalidItemView>
+    where
+        Self: Sized,
+    {
+        Some(InvalidItemView::new(abs_path, is_local, e, window, cx))
+    }
+}
@@ -0,0 +1,288 @@
+use std::{ops::Range, path::PathBuf, sync::Arc};
+
+use anyhow::{Context as _, Result, anyhow};
+use fs::Fs;
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Task};
+use project::{File, Project, ProjectEntryId, ProjectPath};
+use worktree::LoadedBinaryFile;
+
+/// Extensions of files that are always opened in the hex editor, as no other view can show them.
+const BINARY_FILE_EXTENSIONS: &[&str] = &[
+    "a", "bin", "class", "dll", "dylib", "elf", "exe", "img", "iso", "o", "pyc", "rom", "so",
+    "wasm",
+];
+
+/// The size of the largest file the hex editor opens, as it holds the whole file in memory.
+const MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+
+pub enum HexFileEvent {
+    Edited,
+    DirtyChanged,
+    Saved,
+    Reloaded,
+}
+
+/// The contents of an overwritten range before the edit, kept around so it can be undone.
+struct ByteEdit {
+    offset: usize,
+    previous_bytes: Vec<u8>,
+}
+
+/// A file loaded as raw bytes, edited in place without changing its length.
+pub struct HexFile {
+    file: Arc<File>,
+    /// Shared with any save in progress, so that saving doesn't copy the file.
+    bytes: Arc<Vec<u8>>,
+    undo_stack: Vec<ByteEdit>,
+    /// The depth of the undo stack when the file was last saved, or `None` if that state can't be
+    /// reached by undoing anymore.
+    saved_undo_depth: Option<usize>,
+}
+
+impl project::ProjectItem for HexFile {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        let extension = path.path.extension()?.to_ascii_lowercase();
+        if !BINARY_FILE_EXTENSIONS.contains(&extension.as_str()) {
+            return None;
+        }
+        Some(Self::open(project, path, cx))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.file.entry_id
+    }
+
+    fn project_path(&self, cx: &App) -> Option<ProjectPath> {
+        Some(ProjectPath {
+            worktree_id: self.file.worktree_id(cx),
+            path: self.file.path.clone(),
+        })
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.saved_undo_depth != Some(self.undo_stack.len())
+    }
+}
+
+impl HexFile {
+    pub fn open(
+        project: &Entity<Project>,
+        project_path: &ProjectPath,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let Some(worktree) = project
+            .read(cx)
+            .worktree_for_id(project_path.worktree_id, cx)
+        else {
+            return Task::ready(Err(anyhow!("no worktree found for {project_path:?}")));
+        };
+        if let Some(entry) = worktree.read(cx).entry_for_path(&project_path.path)
+            && entry.size > MAX_FILE_SIZE
+        {
+            return Task::ready(Err(anyhow!(
+                "file is too large for the hex editor ({} bytes, the limit is {MAX_FILE_SIZE})",
+                entry.size
+            )));
+        }
+        let load_file = worktree.update(cx, |worktree, cx| {
+            worktree.load_binary_file(&project_path.path, cx)
+        });
+        cx.spawn(async move |cx| {
+            let LoadedBinaryFile { file, content } = load_file.await?;
+            cx.new(|_| Self {
+                file,
+                bytes: Arc::new(content),
+                undo_stack: Vec::new(),
+                saved_undo_depth: Some(0),
+            })
+        })
+    }
+
+    pub fn file(&self) -> &Arc<File> {
+        &self.file
+    }
+
+    pub fn abs_path(&self, cx: &App) -> PathBuf {
+        self.file.worktree.read(cx).absolutize(&self.file.path)
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Replaces the bytes starting at `offset`, dropping those that would go past the end of the
+    /// file.
+    pub fn overwrite(&mut self, offset: usize, bytes: &[u8], cx: &mut Context<Self>) {
+        let range =
+            offset.min(self.bytes.len())..offset.saturating_add(bytes.len()).min(self.bytes.len());
+        if range.is_empty() || self.bytes[range.clone()] == bytes[..range.len()] {
+            return;
+        }
+        let was_dirty = project::ProjectItem::is_dirty(self);
+        self.undo_stack.push(ByteEdit {
+            offset: range.start,
+            previous_bytes: self.bytes[range.clone()].to_vec(),
+        });
+        Arc::make_mut(&mut self.bytes)[range.clone()].copy_from_slice(&bytes[..range.len()]);
+        self.edited(was_dirty, cx);
+    }
+
+    /// Restores the bytes overwritten by the last edit, returning the range they occupy.
+    pub fn undo(&mut self, cx: &mut Context<Self>) -> Option<Range<usize>> {
+        let was_dirty = project::ProjectItem::is_dirty(self);
+        let edit = self.undo_stack.pop()?;
+        if self
+            .saved_undo_depth
+            .is_some_and(|depth| depth > self.undo_stack.len())
+        {
+            self.saved_undo_depth = None;
+        }
+        let range = edit.offset..edit.offset + edit.previous_bytes.len();
+        Arc::make_mut(&mut self.bytes)[range.clone()].copy_from_slice(&edit.previous_bytes);
+        self.edited(was_dirty, cx);
+        Some(range)
+    }
+
+    fn edited(&mut self, was_dirty: bool, cx: &mut Context<Self>) {
+        cx.emit(HexFileEvent::Edited);
+        if was_dirty != project::ProjectItem::is_dirty(self) {
+            cx.emit(HexFileEvent::DirtyChanged);
+        }
+        cx.notify();
+    }
+
+    pub fn save(&mut self, fs: Arc<dyn Fs>, cx: &mut Context<Self>) -> Task<Result<()>> {
+        if !self.file.is_local {
+            return Task::ready(Err(anyhow!(
+                "remote files can't be saved in the hex editor"
+            )));
+        }
+        let abs_path = self.abs_path(cx);
+        let bytes = self.bytes.clone();
+        let saved_undo_depth = self.undo_stack.len();
+        cx.spawn(async move |this, cx| {
+            fs.write(&abs_path, &bytes)
+                .await
+                .with_context(|| format!("saving {abs_path:?}"))?;
+            this.update(cx, |this, cx| {
+                this.saved_undo_depth = Some(saved_undo_depth);
+                cx.emit(HexFileEvent::Saved);
+                cx.emit(HexFileEvent::DirtyChanged);
+                cx.notify();
+            })
+        })
+    }
+
+    /// Replaces the contents with those on disk, discarding the edits and their history.
+    pub fn reload(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let worktree = self.file.worktree.clone();
+        let load_file = worktree.update(cx, |worktree, cx| {
+            worktree.load_binary_file(&self.file.path, cx)
+        });
+        cx.spawn(async move |this, cx| {
+            let LoadedBinaryFile { file, content } = load_file.await?;
+            this.update(cx, |this, cx| {
+                this.file = file;
+                this.bytes = Arc::new(content);
+                this.undo_stack.clear();
+                this.saved_undo_depth = Some(0);
+                cx.emit(HexFileEvent::Reloaded);
+                cx.emit(HexFileEvent::DirtyChanged);
+                cx.notify();
+            })
+        })
+    }
+}
+
+impl EventEmitter<HexFileEvent> for HexFile {}
+
+#[cfg(test)]
+mod tests {
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use project::ProjectItem as _;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::{path, rel_path::rel_path};
+
+    use super::*;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_overwrite_undo_and_save(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "data.bin": "abcdef" }))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let project_path = project.read_with(cx, |project, cx| {
+            let worktree_id = project.worktrees(cx).next().unwrap().read(cx).id();
+            ProjectPath {
+                worktree_id,
+                path: rel_path("data.bin").into(),
+            }
+        });
+        let hex_file = cx
+            .update(|cx| HexFile::open(&project, &project_path, cx))
+            .await
+            .unwrap();
+
+        hex_file.update(cx, |hex_file, cx| {
+            assert!(!hex_file.is_dirty());
+            hex_file.overwrite(1, b"XY", cx);
+            assert_eq!(hex_file.bytes(), b"aXYdef");
+            assert!(hex_file.is_dirty());
+
+            // Bytes past the end of the file are dropped, and unchanged bytes aren't an edit.
+            hex_file.overwrite(4, b"ZZZ", cx);
+            assert_eq!(hex_file.bytes(), b"aXYdZZ");
+            hex_file.overwrite(0, b"a", cx);
+            assert_eq!(hex_file.undo(cx), Some(4..6));
+            assert_eq!(hex_file.bytes(), b"aXYdef");
+        });
+
+        let save = hex_file.update(cx, |hex_file, cx| hex_file.save(fs.clone(), cx));
+        // Edits made while saving don't change what is written.
+        hex_file.update(cx, |hex_file, cx| hex_file.overwrite(0, b"0", cx));
+        save.await.unwrap();
+        assert_eq!(
+            fs.load_bytes(path!("/dir/data.bin").as_ref())
+                .await
+                .unwrap(),
+            b"aXYdef"
+        );
+
+        hex_file.update(cx, |hex_file, cx| {
+            assert!(hex_file.is_dirty());
+            assert_eq!(hex_file.undo(cx), Some(0..1));
+            assert!(!hex_file.is_dirty());
+            // Undoing past the saved state makes it unreachable again.
+            assert_eq!(hex_file.undo(cx), Some(1..3));
+            assert_eq!(hex_file.bytes(), b"abcdef");
+            assert!(hex_file.is_dirty());
+            assert!(!hex_file.can_undo());
+        });
+    }
+}
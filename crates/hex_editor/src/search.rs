@@ -0,0 +1,113 @@
+/// Turns the text typed into the search bar into the bytes to look for.
+///
+/// Text wrapped in double quotes is searched for literally. Otherwise, text made of hex digit
+/// pairs (optionally separated by whitespace) is read as bytes, and anything else as a string.
+pub fn parse_search_pattern(text: &str) -> Option<Vec<u8>> {
+    if text.is_empty() {
+        return None;
+    }
+    if let Some(quoted) = text
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+    {
+        return (!quoted.is_empty()).then(|| quoted.as_bytes().to_vec());
+    }
+    parse_hex_bytes(text).or_else(|| Some(text.as_bytes().to_vec()))
+}
+
+/// Parses hex digit pairs, ignoring whitespace, e.g. `"DE ad BEEF"`.
+pub fn parse_hex_bytes(text: &str) -> Option<Vec<u8>> {
+    let digits = text
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .collect::<String>();
+    if digits.is_empty()
+        || !digits
+            .chars()
+            .all(|character| character.is_ascii_hexdigit())
+    {
+        return None;
+    }
+    hex::decode(digits).ok()
+}
+
+/// Returns the offset of the first occurrence of `pattern` starting at or after `start`,
+/// wrapping around to the beginning of `bytes`.
+pub fn find_next(bytes: &[u8], pattern: &[u8], start: usize) -> Option<usize> {
+    if pattern.is_empty() || pattern.len() > bytes.len() {
+        return None;
+    }
+    let start = start.min(bytes.len());
+    find_in(&bytes[start..], pattern)
+        .map(|offset| start + offset)
+        .or_else(|| {
+            let wrapped_end = (start + pattern.len() - 1).min(bytes.len());
+            find_in(&bytes[..wrapped_end], pattern)
+        })
+}
+
+/// Returns the offset of the last occurrence of `pattern` starting before `end`, wrapping
+/// around to the end of `bytes`.
+pub fn find_previous(bytes: &[u8], pattern: &[u8], end: usize) -> Option<usize> {
+    if pattern.is_empty() || pattern.len() > bytes.len() {
+        return None;
+    }
+    let end = end.min(bytes.len());
+    let search_end = (end + pattern.len() - 1).min(bytes.len());
+    rfind_in(&bytes[..search_end], pattern)
+        .or_else(|| rfind_in(&bytes[end..], pattern).map(|offset| end + offset))
+}
+
+fn find_in(haystack: &[u8], pattern: &[u8]) -> Option<usize> {
+    haystack
+        .windows(pattern.len())
+        .position(|window| window == pattern)
+}
+
+fn rfind_in(haystack: &[u8], pattern: &[u8]) -> Option<usize> {
+    if haystack.len() < pattern.len() {
+        return None;
+    }
+    haystack
+        .windows(pattern.len())
+        .rposition(|window| window == pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_search_pattern() {
+        assert_eq!(
+            parse_search_pattern("DEADbeef"),
+            Some(vec![0xDE, 0xAD, 0xBE, 0xEF])
+        );
+        assert_eq!(
+            parse_search_pattern("de ad be ef"),
+            Some(vec![0xDE, 0xAD, 0xBE, 0xEF])
+        );
+        assert_eq!(parse_search_pattern("abc"), Some(b"abc".to_vec()));
+        assert_eq!(parse_search_pattern("ELF"), Some(b"ELF".to_vec()));
+        assert_eq!(parse_search_pattern("\"cafe\""), Some(b"cafe".to_vec()));
+        assert_eq!(parse_search_pattern("\"\""), None);
+        assert_eq!(parse_search_pattern(""), None);
+    }
+
+    #[test]
+    fn test_find() {
+        let bytes = b"abcabcab";
+        assert_eq!(find_next(bytes, b"abc", 0), Some(0));
+        assert_eq!(find_next(bytes, b"abc", 1), Some(3));
+        assert_eq!(find_next(bytes, b"abc", 4), Some(0));
+        assert_eq!(find_next(bytes, b"abc", 8), Some(0));
+        assert_eq!(find_next(bytes, b"xyz", 0), None);
+        assert_eq!(find_next(bytes, b"", 0), None);
+
+        assert_eq!(find_previous(bytes, b"abc", 8), Some(3));
+        assert_eq!(find_previous(bytes, b"abc", 3), Some(0));
+        assert_eq!(find_previous(bytes, b"abc", 0), Some(3));
+        assert_eq!(find_previous(bytes, b"ab", 1), Some(0));
+        assert_eq!(find_previous(bytes, b"xyz", 8), None);
+    }
+}
@@ -49,6 +49,7 @@ pub enum IconName {
     BoltOutlined,
     Book,
     BookCopy,
+    Bookmark,
     CaseSensitive,
     Chat,
     Check,
@@ -86,6 +87,8 @@ pub enum IconName {
     DebugIgnoreBreakpoints,
     DebugLogBreakpoint,
     DebugPause,
+    DebugReverseContinue,
+    DebugStepBack,
     DebugStepInto,
     DebugStepOut,
     DebugStepOver,
@@ -0,0 +1,251 @@
+use std::io::Cursor;
+
+use anyhow::Result;
+use image::{DynamicImage, ImageFormat, imageops::FilterType};
+
+pub const MIN_ZOOM: f32 = 0.1;
+pub const MAX_ZOOM: f32 = 64.;
+
+/// From this zoom on, each pixel of the image is drawn as a sharp square rather than being
+/// interpolated, so that individual pixels can be told apart.
+pub const PIXEL_VIEW_MIN_ZOOM: f32 = 4.;
+
+/// The largest width or height an image can be resized to, which keeps the resized image's
+/// pixels within a few gigabytes of memory.
+pub const MAX_RESIZE_DIMENSION: u32 = 16_384;
+
+const ZOOM_STEPS: &[f32] = &[
+    0.1, 0.25, 0.5, 0.75, 1., 1.5, 2., 3., 4., 6., 8., 12., 16., 24., 32., 48., 64.,
+];
+
+pub fn zoom_in(zoom: f32) -> f32 {
+    ZOOM_STEPS
+        .iter()
+        .copied()
+        .find(|step| *step > zoom * 1.001)
+        .unwrap_or(MAX_ZOOM)
+}
+
+pub fn zoom_out(zoom: f32) -> f32 {
+    ZOOM_STEPS
+        .iter()
+        .rev()
+        .copied()
+        .find(|step| *step < zoom * 0.999)
+        .unwrap_or(MIN_ZOOM)
+}
+
+/// Parses a zoom percentage such as "150%" or "150" into a zoom factor.
+pub fn parse_zoom(text: &str) -> Option<f32> {
+    let percent = text
+        .trim()
+        .trim_end_matches('%')
+        .trim()
+        .parse::<f32>()
+        .ok()?;
+    (percent.is_finite() && percent > 0.).then(|| (percent / 100.).clamp(MIN_ZOOM, MAX_ZOOM))
+}
+
+pub fn format_zoom(zoom: f32) -> String {
+    format!("{}%", (zoom * 100.).round())
+}
+
+/// A rectangle of pixels within an image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PixelRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PixelRect {
+    /// The rectangle spanning two pixels, both of which are included in it.
+    pub fn from_corners(start: (u32, u32), end: (u32, u32)) -> Self {
+        let (left, right) = (start.0.min(end.0), start.0.max(end.0));
+        let (top, bottom) = (start.1.min(end.1), start.1.max(end.1));
+        Self {
+            x: left,
+            y: top,
+            width: right - left + 1,
+            height: bottom - top + 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageEdit {
+    Crop(PixelRect),
+    Resize { width: u32, height: u32 },
+    RotateClockwise,
+    RotateCounterclockwise,
+}
+
+impl ImageEdit {
+    pub fn apply(self, image: &DynamicImage) -> DynamicImage {
+        match self {
+            ImageEdit::Crop(rect) => image.crop_imm(rect.x, rect.y, rect.width, rect.height),
+            ImageEdit::Resize { width, height } => {
+                image.resize_exact(width, height, FilterType::Lanczos3)
+            }
+            ImageEdit::RotateClockwise => image.rotate90(),
+            ImageEdit::RotateCounterclockwise => image.rotate270(),
+        }
+    }
+}
+
+/// The size to resize an image to, given a new width, height or both. When only one of them is
+/// given, the other one follows the image's aspect ratio.
+pub fn resize_dimensions(
+    (original_width, original_height): (u32, u32),
+    width: Option<u32>,
+    height: Option<u32>,
+) -> Option<(u32, u32)> {
+    let scaled = |size: u32, from: u32, to: u32| {
+        ((size as f64 * to as f64 / from as f64).round() as u32).max(1)
+    };
+    match (width, height) {
+        (Some(0), _) | (_, Some(0)) | (None, None) => None,
+        (Some(width), Some(height)) => Some((width, height)),
+        (Some(width), None) => Some((width, scaled(original_height, original_width, width))),
+        (None, Some(height)) => Some((scaled(original_width, original_height, height), height)),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Png,
+    Jpeg,
+    WebP,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Png => "png",
+            ExportFormat::Jpeg => "jpg",
+            ExportFormat::WebP => "webp",
+        }
+    }
+
+    pub fn encode(self, image: &DynamicImage) -> Result<Vec<u8>> {
+        let mut bytes = Cursor::new(Vec::new());
+        match self {
+            ExportFormat::Png => image.write_to(&mut bytes, ImageFormat::Png)?,
+            // JPEG has no alpha channel, and the WebP encoder only takes 8-bit images.
+            ExportFormat::Jpeg => {
+                DynamicImage::ImageRgb8(image.to_rgb8()).write_to(&mut bytes, ImageFormat::Jpeg)?
+            }
+            ExportFormat::WebP => DynamicImage::ImageRgba8(image.to_rgba8())
+                .write_to(&mut bytes, ImageFormat::WebP)?,
+        }
+        Ok(bytes.into_inner())
+    }
+}
+
+/// Formats a color as `#RRGGBB`, or as `#RRGGBBAA` when it's not fully opaque.
+pub fn format_hex([red, green, blue, alpha]: [u8; 4]) -> String {
+    if alpha == u8::MAX {
+        format!("#{red:02X}{green:02X}{blue:02X}")
+    } else {
+        format!("#{red:02X}{green:02X}{blue:02X}{alpha:02X}")
+    }
+}
+
+/// Formats a color as CSS does, with the alpha channel between 0 and 1.
+pub fn format_rgba([red, green, blue, alpha]: [u8; 4]) -> String {
+    let alpha = (alpha as f32 / u8::MAX as f32 * 1000.).round() / 1000.;
+    format!("rgba({red}, {green}, {blue}, {alpha})")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zoom_steps() {
+        assert_eq!(zoom_in(1.), 1.5);
+        assert_eq!(zoom_in(1.2), 1.5);
+        assert_eq!(zoom_in(MAX_ZOOM), MAX_ZOOM);
+        assert_eq!(zoom_out(1.), 0.75);
+        assert_eq!(zoom_out(1.2), 1.);
+        assert_eq!(zoom_out(MIN_ZOOM), MIN_ZOOM);
+
+        assert_eq!(parse_zoom("150%"), Some(1.5));
+        assert_eq!(parse_zoom(" 800 "), Some(8.));
+        assert_eq!(parse_zoom("100000"), Some(MAX_ZOOM));
+        assert_eq!(parse_zoom("0"), None);
+        assert_eq!(parse_zoom("big"), None);
+        assert_eq!(format_zoom(1.5), "150%");
+    }
+
+    #[test]
+    fn test_pixel_rect_from_corners() {
+        assert_eq!(
+            PixelRect::from_corners((5, 2), (1, 8)),
+            PixelRect {
+                x: 1,
+                y: 2,
+                width: 5,
+                height: 7
+            }
+        );
+        assert_eq!(
+            PixelRect::from_corners((3, 3), (3, 3)),
+            PixelRect {
+                x: 3,
+                y: 3,
+                width: 1,
+                height: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_resize_dimensions() {
+        assert_eq!(
+            resize_dimensions((200, 100), Some(50), None),
+            Some((50, 25))
+        );
+        assert_eq!(
+            resize_dimensions((200, 100), None, Some(50)),
+            Some((100, 50))
+        );
+        assert_eq!(
+            resize_dimensions((200, 100), Some(10), Some(10)),
+            Some((10, 10))
+        );
+        assert_eq!(resize_dimensions((200, 1), Some(2), None), Some((2, 1)));
+        assert_eq!(resize_dimensions((200, 100), None, None), None);
+        assert_eq!(resize_dimensions((200, 100), Some(0), None), None);
+    }
+
+    #[test]
+    fn test_edits() {
+        let image = DynamicImage::new_rgba8(4, 2);
+        let rotated = ImageEdit::RotateClockwise.apply(&image);
+        assert_eq!((rotated.width(), rotated.height()), (2, 4));
+        let cropped = ImageEdit::Crop(PixelRect {
+            x: 1,
+            y: 0,
+            width: 2,
+            height: 2,
+        })
+        .apply(&image);
+        assert_eq!((cropped.width(), cropped.height()), (2, 2));
+
+        for format in [ExportFormat::Png, ExportFormat::Jpeg, ExportFormat::WebP] {
+            let bytes = format.encode(&image).unwrap();
+            let decoded = image::load_from_memory(&bytes).unwrap();
+            assert_eq!((decoded.width(), decoded.height()), (4, 2));
+        }
+    }
+
+    #[test]
+    fn test_format_colors() {
+        assert_eq!(format_hex([255, 128, 0, 255]), "#FF8000");
+        assert_eq!(format_hex([255, 128, 0, 128]), "#FF800080");
+        assert_eq!(format_rgba([255, 128, 0, 255]), "rgba(255, 128, 0, 1)");
+        assert_eq!(format_rgba([255, 128, 0, 128]), "rgba(255, 128, 0, 0.502)");
+    }
+}
@@ -37,7 +37,7 @@ impl ImageInfo {
     }
 }
 
-fn format_image_size(size: u64, image_unit_type: ImageFileSizeUnit) -> String {
+pub fn format_image_size(size: u64, image_unit_type: ImageFileSizeUnit) -> String {
     let use_decimal = matches!(image_unit_type, ImageFileSizeUnit::Decimal);
     format_file_size(size, use_decimal)
 }
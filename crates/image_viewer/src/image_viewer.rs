@@ -1,36 +1,131 @@
+mod image_editing;
 mod image_info;
 mod image_viewer_settings;
 
-use std::path::Path;
+use std::{cell::Cell, ops::Range, path::Path, rc::Rc, sync::Arc};
 
 use anyhow::Context as _;
-use editor::{EditorSettings, items::entry_git_aware_label_color};
+use editor::{
+    Editor, EditorElement, EditorSettings, EditorStyle, items::entry_git_aware_label_color,
+};
 use file_icons::FileIcons;
 use gpui::{
-    AnyElement, App, Bounds, Context, Entity, EventEmitter, FocusHandle, Focusable,
-    InteractiveElement, IntoElement, ObjectFit, ParentElement, Render, Styled, Task, WeakEntity,
-    Window, canvas, div, fill, img, opaque_grey, point, size,
+    AnyElement, App, Bounds, ClipboardItem, Context, CursorStyle, Entity, EventEmitter,
+    FocusHandle, Focusable, Hsla, InteractiveElement, IntoElement, MouseButton, MouseDownEvent,
+    MouseMoveEvent, ObjectFit, ParentElement, Point, Render, Rgba, ScrollHandle, Styled, Task,
+    TextStyle, WeakEntity, Window, actions, canvas, div, fill, img, opaque_grey, point, relative,
+    size,
 };
+use image::{DynamicImage, RgbaImage};
 use language::{DiskState, File as _};
 use persistence::IMAGE_VIEWER;
-use project::{ImageItem, Project, ProjectPath, image_store::ImageItemEvent};
+use project::{DirectoryLister, ImageItem, Project, ProjectPath, image_store::ImageItemEvent};
 use settings::Settings;
 use theme::{Theme, ThemeSettings};
-use ui::prelude::*;
-use util::paths::PathExt;
+use ui::{ContextMenu, Divider, PopoverMenu, Tooltip, prelude::*};
+use util::{ResultExt as _, paths::PathExt};
 use workspace::{
     ItemId, ItemSettings, Pane, ToolbarItemLocation, Workspace, WorkspaceId, delete_unloaded_items,
     invalid_item_view::InvalidItemView,
     item::{BreadcrumbText, Item, ProjectItem, SerializableItem, TabContentParams},
+    notifications::NotifyTaskExt as _,
+};
+
+use crate::image_editing::{
+    ExportFormat, ImageEdit, MAX_RESIZE_DIMENSION, MAX_ZOOM, MIN_ZOOM, PIXEL_VIEW_MIN_ZOOM,
+    PixelRect, format_hex, format_rgba, format_zoom, parse_zoom, resize_dimensions,
 };
 
 pub use crate::image_info::*;
 pub use crate::image_viewer_settings::*;
 
+actions!(
+    image_viewer,
+    [
+        /// Zooms into the image.
+        ZoomIn,
+        /// Zooms out of the image.
+        ZoomOut,
+        /// Shows the image at its actual size, with one image pixel per screen pixel.
+        ActualSize,
+        /// Scales the image down to fit the view.
+        ZoomToFit,
+        /// Rotates the image by 90 degrees clockwise.
+        RotateClockwise,
+        /// Rotates the image by 90 degrees counterclockwise.
+        RotateCounterclockwise,
+        /// Toggles selecting a region of the image to crop it to.
+        ToggleCropMode,
+        /// Crops the image to the selected region.
+        Crop,
+        /// Resizes the image to the width and height entered in the toolbar.
+        Resize,
+        /// Discards the crops, resizes and rotations made to the image.
+        DiscardEdits,
+        /// Copies the color of the picked pixel as a hex code.
+        CopyColorHex,
+        /// Copies the color of the picked pixel as a CSS `rgba()` color.
+        CopyColorRgba,
+        /// Saves a copy of the image as a PNG file.
+        ExportAsPng,
+        /// Saves a copy of the image as a JPEG file.
+        ExportAsJpeg,
+        /// Saves a copy of the image as a WebP file.
+        ExportAsWebp,
+    ]
+);
+
 pub struct ImageView {
     image_item: Entity<ImageItem>,
     project: Entity<Project>,
     focus_handle: FocusHandle,
+    /// `None` while the image is being decoded, or when it can't be.
+    decoded: Option<DecodedImage>,
+    /// The image once it has been cropped, resized or rotated, encoded so that it can be displayed.
+    /// Edits never change the file itself, and are only kept by exporting the image.
+    edited_image: Option<Arc<gpui::Image>>,
+    /// `None` when the image is scaled down to fit the view.
+    zoom: Option<f32>,
+    zoom_editor: Entity<Editor>,
+    zoom_placeholder: String,
+    width_editor: Entity<Editor>,
+    height_editor: Entity<Editor>,
+    is_cropping: bool,
+    crop_start: Option<(u32, u32)>,
+    crop_selection: Option<PixelRect>,
+    hovered_pixel: Option<(u32, u32)>,
+    picked_pixel: Option<(u32, u32)>,
+    status_message: Option<SharedString>,
+    /// Where the image and the area around it were painted in the last frame, which is used to map
+    /// mouse positions to pixels and to scale the image to fit.
+    image_bounds: Rc<Cell<Option<Bounds<Pixels>>>>,
+    viewport_bounds: Rc<Cell<Option<Bounds<Pixels>>>>,
+    scroll_handle: ScrollHandle,
+    _decode_task: Task<()>,
+    edit_task: Option<Task<()>>,
+}
+
+#[derive(Clone)]
+struct DecodedImage {
+    image: Arc<DynamicImage>,
+    pixels: Arc<RgbaImage>,
+}
+
+impl DecodedImage {
+    fn new(image: DynamicImage) -> Self {
+        Self {
+            pixels: Arc::new(image.to_rgba8()),
+            image: Arc::new(image),
+        }
+    }
+
+    fn dimensions(&self) -> (u32, u32) {
+        self.pixels.dimensions()
+    }
+
+    fn color_at(&self, (x, y): (u32, u32)) -> Option<[u8; 4]> {
+        self.pixels.get_pixel_checked(x, y).map(|pixel| pixel.0)
+    }
 }
 
 impl ImageView {
@@ -47,14 +142,49 @@ impl ImageView {
                 cx.drop_image(image, None);
             }
             image_data.remove_asset(cx);
+            if let Some(edited_image) = this.edited_image.take() {
+                release_image(edited_image, window, cx);
+            }
         })
         .detach();
 
-        Self {
+        let zoom_editor = cx.new(|cx| Editor::single_line(window, cx));
+        let width_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Width", window, cx);
+            editor
+        });
+        let height_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Height", window, cx);
+            editor
+        });
+
+        let mut this = Self {
             image_item,
             project,
             focus_handle: cx.focus_handle(),
-        }
+            decoded: None,
+            edited_image: None,
+            zoom: None,
+            zoom_editor,
+            zoom_placeholder: String::new(),
+            width_editor,
+            height_editor,
+            is_cropping: false,
+            crop_start: None,
+            crop_selection: None,
+            hovered_pixel: None,
+            picked_pixel: None,
+            status_message: None,
+            image_bounds: Rc::default(),
+            viewport_bounds: Rc::default(),
+            scroll_handle: ScrollHandle::new(),
+            _decode_task: Task::ready(()),
+            edit_task: None,
+        };
+        this.decode_image(window, cx);
+        this
     }
 
     fn on_image_event(
@@ -64,15 +194,825 @@ impl ImageView {
         cx: &mut Context<Self>,
     ) {
         match event {
-            ImageItemEvent::MetadataUpdated
-            | ImageItemEvent::FileHandleChanged
-            | ImageItemEvent::Reloaded => {
+            ImageItemEvent::MetadataUpdated | ImageItemEvent::FileHandleChanged => {
+                cx.emit(ImageViewEvent::TitleChanged);
+                cx.notify();
+            }
+            ImageItemEvent::Reloaded => {
+                // Edits made to the previous contents of the file are kept until they're discarded.
+                if self.edited_image.is_none() {
+                    self.decoded = None;
+                    self.decode_image_in_background(cx);
+                }
                 cx.emit(ImageViewEvent::TitleChanged);
                 cx.notify();
             }
             ImageItemEvent::ReloadNeeded => {}
         }
     }
+
+    fn decode_image(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.decode_image_in_background(cx);
+        self.reset_resize_editors(window, cx);
+    }
+
+    fn decode_image_in_background(&mut self, cx: &mut Context<Self>) {
+        let image = self.image_item.read(cx).image.clone();
+        self._decode_task = cx.spawn(async move |this, cx| {
+            let decoded = cx
+                .background_spawn(async move {
+                    image::load_from_memory(&image.bytes)
+                        .map(DecodedImage::new)
+                        .context("decoding image")
+                })
+                .await
+                .log_err();
+            this.update(cx, |this, cx| {
+                this.decoded = decoded;
+                this.hovered_pixel = None;
+                this.picked_pixel = None;
+                this.crop_selection = None;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn display_image(&self, cx: &App) -> Arc<gpui::Image> {
+        self.edited_image
+            .clone()
+            .unwrap_or_else(|| self.image_item.read(cx).image.clone())
+    }
+
+    /// The zoom the image is shown at, relative to the device's pixels rather than to logical
+    /// pixels, so that 100% always shows one image pixel per screen pixel.
+    fn current_zoom(&self, window: &Window) -> Option<f32> {
+        if let Some(zoom) = self.zoom {
+            return Some(zoom);
+        }
+        let (width, height) = self.decoded.as_ref()?.dimensions();
+        let viewport = self.viewport_bounds.get()?;
+        if width == 0 || height == 0 {
+            return Some(1.);
+        }
+        let scale_factor = window.scale_factor();
+        let fit = (viewport.size.width / px(1.) * scale_factor / width as f32)
+            .min(viewport.size.height / px(1.) * scale_factor / height as f32)
+            .min(1.);
+        Some(fit.max(f32::MIN_POSITIVE))
+    }
+
+    fn set_zoom(&mut self, zoom: Option<f32>, cx: &mut Context<Self>) {
+        self.zoom = zoom.map(|zoom| zoom.clamp(MIN_ZOOM, MAX_ZOOM));
+        cx.notify();
+    }
+
+    fn zoom_in(&mut self, _: &ZoomIn, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(zoom) = self.current_zoom(window) {
+            self.set_zoom(Some(image_editing::zoom_in(zoom)), cx);
+        }
+    }
+
+    fn zoom_out(&mut self, _: &ZoomOut, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(zoom) = self.current_zoom(window) {
+            self.set_zoom(Some(image_editing::zoom_out(zoom)), cx);
+        }
+    }
+
+    fn actual_size(&mut self, _: &ActualSize, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_zoom(Some(1.), cx);
+    }
+
+    fn zoom_to_fit(&mut self, _: &ZoomToFit, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_zoom(None, cx);
+    }
+
+    fn rotate_clockwise(
+        &mut self,
+        _: &RotateClockwise,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.apply_edit(ImageEdit::RotateClockwise, window, cx);
+    }
+
+    fn rotate_counterclockwise(
+        &mut self,
+        _: &RotateCounterclockwise,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.apply_edit(ImageEdit::RotateCounterclockwise, window, cx);
+    }
+
+    fn toggle_crop_mode(&mut self, _: &ToggleCropMode, _: &mut Window, cx: &mut Context<Self>) {
+        self.is_cropping = !self.is_cropping;
+        self.crop_start = None;
+        self.crop_selection = None;
+        cx.notify();
+    }
+
+    fn crop(&mut self, _: &Crop, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(selection) = self.crop_selection {
+            self.apply_edit(ImageEdit::Crop(selection), window, cx);
+        }
+    }
+
+    fn resize(&mut self, _: &Resize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(dimensions) = self.decoded.as_ref().map(DecodedImage::dimensions) else {
+            return;
+        };
+        let parse = |editor: &Entity<Editor>, cx: &App| {
+            let text = editor.read(cx).text(cx);
+            let text = text.trim();
+            if text.is_empty() {
+                Ok(None)
+            } else {
+                text.parse::<u32>().map(Some)
+            }
+        };
+        let (Ok(width), Ok(height)) = (
+            parse(&self.width_editor, cx),
+            parse(&self.height_editor, cx),
+        ) else {
+            self.status_message = Some("The width and height must be whole numbers".into());
+            cx.notify();
+            return;
+        };
+        let Some((width, height)) = resize_dimensions(dimensions, width, height) else {
+            return;
+        };
+        if width > MAX_RESIZE_DIMENSION || height > MAX_RESIZE_DIMENSION {
+            self.status_message = Some(
+                format!("Images can be resized to at most {MAX_RESIZE_DIMENSION} pixels per side")
+                    .into(),
+            );
+            cx.notify();
+            return;
+        }
+        self.apply_edit(ImageEdit::Resize { width, height }, window, cx);
+    }
+
+    fn discard_edits(&mut self, _: &DiscardEdits, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(edited_image) = self.edited_image.take() {
+            release_image(edited_image, window, cx);
+            self.edit_task = None;
+            self.status_message = None;
+            self.decode_image(window, cx);
+        }
+    }
+
+    fn apply_edit(&mut self, edit: ImageEdit, window: &mut Window, cx: &mut Context<Self>) {
+        // Edits are applied one at a time, each to the result of the previous one.
+        if self.edit_task.is_some() {
+            return;
+        }
+        let Some(decoded) = self.decoded.clone() else {
+            return;
+        };
+        self.edit_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let result = cx
+                .background_spawn(async move {
+                    let image = edit.apply(&decoded.image);
+                    let bytes = ExportFormat::Png.encode(&image)?;
+                    anyhow::Ok((
+                        DecodedImage::new(image),
+                        Arc::new(gpui::Image::from_bytes(gpui::ImageFormat::Png, bytes)),
+                    ))
+                })
+                .await;
+            this.update_in(cx, |this, window, cx| {
+                this.edit_task = None;
+                match result {
+                    Ok((decoded, display_image)) => {
+                        if let Some(previous_image) = this.edited_image.replace(display_image) {
+                            release_image(previous_image, window, cx);
+                        }
+                        this.decoded = Some(decoded);
+                        this.hovered_pixel = None;
+                        this.picked_pixel = None;
+                        this.crop_start = None;
+                        this.crop_selection = None;
+                        this.status_message = None;
+                        this.reset_resize_editors(window, cx);
+                    }
+                    Err(error) => {
+                        this.status_message = Some(format!("Failed to edit image: {error}").into());
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn reset_resize_editors(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        for editor in [&self.width_editor, &self.height_editor] {
+            editor.update(cx, |editor, cx| editor.clear(window, cx));
+        }
+    }
+
+    fn export_as_png(&mut self, _: &ExportAsPng, window: &mut Window, cx: &mut Context<Self>) {
+        self.export(ExportFormat::Png, window, cx);
+    }
+
+    fn export_as_jpeg(&mut self, _: &ExportAsJpeg, window: &mut Window, cx: &mut Context<Self>) {
+        self.export(ExportFormat::Jpeg, window, cx);
+    }
+
+    fn export_as_webp(&mut self, _: &ExportAsWebp, window: &mut Window, cx: &mut Context<Self>) {
+        self.export(ExportFormat::WebP, window, cx);
+    }
+
+    fn export(&mut self, format: ExportFormat, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(decoded) = self.decoded.clone() else {
+            return;
+        };
+        let Some(workspace) = window.root::<Workspace>().flatten() else {
+            return;
+        };
+        if !self.project.read(cx).is_local() {
+            self.status_message = Some("Images can only be exported in local projects".into());
+            cx.notify();
+            return;
+        }
+
+        let file_name = self.image_item.read(cx).file.file_name(cx).to_string();
+        let file_stem = Path::new(&file_name)
+            .file_stem()
+            .map_or(file_name.clone(), |stem| {
+                stem.to_string_lossy().into_owned()
+            });
+        let suggested_name = format!("{file_stem}.{}", format.extension());
+        let fs = self.project.read(cx).fs().clone();
+        let lister = DirectoryLister::Local(self.project.clone(), fs.clone());
+        let new_path = workspace.update(cx, |workspace, cx| {
+            workspace.prompt_for_new_path(lister, Some(suggested_name), window, cx)
+        });
+
+        cx.spawn_in(window, async move |this, cx| {
+            let Some(path) = new_path.await.ok().flatten().into_iter().flatten().next() else {
+                return Ok(());
+            };
+            let bytes = cx
+                .background_spawn(async move { format.encode(&decoded.image) })
+                .await?;
+            fs.write(&path, &bytes)
+                .await
+                .with_context(|| format!("writing {}", path.display()))?;
+            this.update(cx, |this, cx| {
+                this.status_message =
+                    Some(format!("Exported to {}", path.compact().display()).into());
+                cx.notify();
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_notify_err(window, cx);
+    }
+
+    fn copy_color_hex(&mut self, _: &CopyColorHex, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(color) = self.inspected_color() {
+            cx.write_to_clipboard(ClipboardItem::new_string(format_hex(color)));
+        }
+    }
+
+    fn copy_color_rgba(&mut self, _: &CopyColorRgba, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(color) = self.inspected_color() {
+            cx.write_to_clipboard(ClipboardItem::new_string(format_rgba(color)));
+        }
+    }
+
+    /// The color of the picked pixel, or of the hovered one when no pixel has been picked.
+    fn inspected_color(&self) -> Option<[u8; 4]> {
+        let decoded = self.decoded.as_ref()?;
+        decoded.color_at(self.picked_pixel.or(self.hovered_pixel)?)
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.zoom_editor.focus_handle(cx).is_focused(window) {
+            let text = self.zoom_editor.read(cx).text(cx);
+            if let Some(zoom) = parse_zoom(&text) {
+                self.set_zoom(Some(zoom), cx);
+            }
+            self.zoom_editor
+                .update(cx, |editor, cx| editor.clear(window, cx));
+            window.focus(&self.focus_handle);
+        } else if self.width_editor.focus_handle(cx).is_focused(window)
+            || self.height_editor.focus_handle(cx).is_focused(window)
+        {
+            self.resize(&Resize, window, cx);
+            window.focus(&self.focus_handle);
+        } else if self.is_cropping {
+            self.crop(&Crop, window, cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.zoom_editor.focus_handle(cx).is_focused(window) {
+            self.zoom_editor
+                .update(cx, |editor, cx| editor.clear(window, cx));
+            window.focus(&self.focus_handle);
+        } else if self.width_editor.focus_handle(cx).is_focused(window)
+            || self.height_editor.focus_handle(cx).is_focused(window)
+        {
+            self.reset_resize_editors(window, cx);
+            window.focus(&self.focus_handle);
+        } else if self.is_cropping {
+            self.is_cropping = false;
+            self.crop_start = None;
+            self.crop_selection = None;
+        } else {
+            self.picked_pixel = None;
+        }
+        self.status_message = None;
+        cx.notify();
+    }
+
+    /// The pixel under the given position, if the position is over the image.
+    fn pixel_at(&self, position: Point<Pixels>) -> Option<(u32, u32)> {
+        let bounds = self.image_bounds.get()?;
+        bounds
+            .contains(&position)
+            .then(|| self.nearest_pixel(position))
+            .flatten()
+    }
+
+    /// The pixel closest to the given position, which may be outside of the image.
+    fn nearest_pixel(&self, position: Point<Pixels>) -> Option<(u32, u32)> {
+        let bounds = self.image_bounds.get()?;
+        let (width, height) = self.decoded.as_ref()?.dimensions();
+        if width == 0 || height == 0 || bounds.size.width <= px(0.) || bounds.size.height <= px(0.)
+        {
+            return None;
+        }
+        let pixel = |offset: Pixels, extent: Pixels, count: u32| {
+            ((offset / extent * count as f32).max(0.) as u32).min(count - 1)
+        };
+        Some((
+            pixel(position.x - bounds.left(), bounds.size.width, width),
+            pixel(position.y - bounds.top(), bounds.size.height, height),
+        ))
+    }
+
+    fn on_image_mouse_down(
+        &mut self,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        window.focus(&self.focus_handle);
+        let pixel = self.pixel_at(event.position);
+        if self.is_cropping {
+            self.crop_start = pixel;
+            self.crop_selection = pixel.map(|pixel| PixelRect::from_corners(pixel, pixel));
+        } else {
+            self.picked_pixel = pixel;
+        }
+        cx.notify();
+    }
+
+    fn on_mouse_move(&mut self, event: &MouseMoveEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let hovered_pixel = self.pixel_at(event.position);
+        let mut changed = hovered_pixel != self.hovered_pixel;
+        self.hovered_pixel = hovered_pixel;
+        if let Some(start) = self.crop_start
+            && event.dragging()
+        {
+            let selection = self
+                .nearest_pixel(event.position)
+                .map(|end| PixelRect::from_corners(start, end));
+            changed |= selection != self.crop_selection;
+            self.crop_selection = selection;
+        }
+        if changed {
+            cx.notify();
+        }
+    }
+
+    fn render_editor_box(
+        &self,
+        editor: &Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Div {
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.buffer_font.family.clone(),
+            font_features: settings.buffer_font.features.clone(),
+            font_size: TextSize::Small.rems(cx).into(),
+            font_weight: settings.buffer_font.weight,
+            ..Default::default()
+        };
+        let style = EditorStyle {
+            background: cx.theme().colors().editor_background,
+            local_player: cx.theme().players().local(),
+            text: text_style,
+            ..Default::default()
+        };
+        h_flex()
+            .rounded_md()
+            .border_1()
+            .px_2()
+            .py_0p5()
+            .bg(cx.theme().colors().editor_background)
+            .when_else(
+                editor.focus_handle(cx).contains_focused(window, cx),
+                |this| this.border_color(cx.theme().colors().border_focused),
+                |this| this.border_color(cx.theme().colors().border_variant),
+            )
+            .child(EditorElement::new(editor, style))
+    }
+
+    fn render_toolbar(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let zoom_placeholder = self
+            .current_zoom(window)
+            .map(format_zoom)
+            .unwrap_or_default();
+        if zoom_placeholder != self.zoom_placeholder {
+            self.zoom_editor.update(cx, |editor, cx| {
+                editor.set_placeholder_text(&zoom_placeholder, window, cx)
+            });
+            self.zoom_placeholder = zoom_placeholder;
+        }
+        let is_editing = self.edit_task.is_some();
+        let focus_handle = self.focus_handle.clone();
+
+        h_flex()
+            .w_full()
+            .px_1()
+            .gap_1()
+            .flex_wrap()
+            .child(
+                IconButton::new("image-viewer-zoom-out", IconName::Dash)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text("Zoom Out"))
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.zoom_out(&ZoomOut, window, cx)),
+                    ),
+            )
+            .child(self.render_editor_box(&self.zoom_editor, window, cx).w_20())
+            .child(
+                IconButton::new("image-viewer-zoom-in", IconName::Plus)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text("Zoom In"))
+                    .on_click(cx.listener(|this, _, window, cx| this.zoom_in(&ZoomIn, window, cx))),
+            )
+            .child(
+                Button::new("image-viewer-zoom-to-fit", "Fit")
+                    .label_size(LabelSize::Small)
+                    .toggle_state(self.zoom.is_none())
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.zoom_to_fit(&ZoomToFit, window, cx)),
+                    ),
+            )
+            .child(
+                Button::new("image-viewer-actual-size", "100%")
+                    .label_size(LabelSize::Small)
+                    .toggle_state(self.zoom == Some(1.))
+                    .on_click(
+                        cx.listener(|this, _, window, cx| {
+                            this.actual_size(&ActualSize, window, cx)
+                        }),
+                    ),
+            )
+            .child(Divider::vertical())
+            .child(
+                IconButton::new("image-viewer-rotate-counterclockwise", IconName::RotateCcw)
+                    .icon_size(IconSize::Small)
+                    .disabled(is_editing)
+                    .tooltip(Tooltip::text("Rotate Counterclockwise"))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.rotate_counterclockwise(&RotateCounterclockwise, window, cx)
+                    })),
+            )
+            .child(
+                IconButton::new("image-viewer-rotate-clockwise", IconName::RotateCw)
+                    .icon_size(IconSize::Small)
+                    .disabled(is_editing)
+                    .tooltip(Tooltip::text("Rotate Clockwise"))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.rotate_clockwise(&RotateClockwise, window, cx)
+                    })),
+            )
+            .child(
+                IconButton::new("image-viewer-crop-mode", IconName::Scissors)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(self.is_cropping)
+                    .tooltip(Tooltip::text("Select a Region to Crop"))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.toggle_crop_mode(&ToggleCropMode, window, cx)
+                    })),
+            )
+            .when(self.is_cropping, |this| {
+                this.child(
+                    Button::new("image-viewer-crop", "Crop")
+                        .label_size(LabelSize::Small)
+                        .disabled(is_editing || self.crop_selection.is_none())
+                        .on_click(cx.listener(|this, _, window, cx| this.crop(&Crop, window, cx))),
+                )
+            })
+            .child(Divider::vertical())
+            .child(
+                self.render_editor_box(&self.width_editor, window, cx)
+                    .w_20(),
+            )
+            .child(Label::new("×").size(LabelSize::Small).color(Color::Muted))
+            .child(
+                self.render_editor_box(&self.height_editor, window, cx)
+                    .w_20(),
+            )
+            .child(
+                Button::new("image-viewer-resize", "Resize")
+                    .label_size(LabelSize::Small)
+                    .disabled(is_editing)
+                    .on_click(cx.listener(|this, _, window, cx| this.resize(&Resize, window, cx))),
+            )
+            .child(div().flex_1())
+            .when(self.edited_image.is_some(), |this| {
+                this.child(
+                    Button::new("image-viewer-discard-edits", "Discard Edits")
+                        .label_size(LabelSize::Small)
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.discard_edits(&DiscardEdits, window, cx)
+                        })),
+                )
+            })
+            .child(
+                PopoverMenu::new("image-viewer-export")
+                    .trigger(
+                        IconButton::new("image-viewer-export-trigger", IconName::Download)
+                            .icon_size(IconSize::Small)
+                            .disabled(self.decoded.is_none()),
+                    )
+                    .menu(move |window, cx| {
+                        Some(ContextMenu::build(window, cx, |menu, _, _| {
+                            menu.context(focus_handle.clone())
+                                .action("Export as PNG", Box::new(ExportAsPng))
+                                .action("Export as JPEG", Box::new(ExportAsJpeg))
+                                .action("Export as WebP", Box::new(ExportAsWebp))
+                        }))
+                    }),
+            )
+    }
+
+    fn render_image(
+        &self,
+        decoded: &DecodedImage,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let (width, height) = decoded.dimensions();
+        let zoom = self.current_zoom(window).unwrap_or(1.);
+        let scale_factor = window.scale_factor();
+        let image_bounds = self.image_bounds.clone();
+        let pixels = decoded.pixels.clone();
+        let display_image = self.display_image(cx);
+        let is_pixel_view = zoom >= PIXEL_VIEW_MIN_ZOOM;
+        let accent = cx.theme().colors().text_accent;
+
+        div()
+            .relative()
+            .flex_none()
+            .w(px((width as f32 * zoom / scale_factor).floor()))
+            .h(px((height as f32 * zoom / scale_factor).floor()))
+            .when(self.is_cropping, |this| this.cursor(CursorStyle::Crosshair))
+            .on_mouse_down(MouseButton::Left, cx.listener(Self::on_image_mouse_down))
+            .child(
+                canvas(
+                    move |bounds, _, _| image_bounds.set(Some(bounds)),
+                    |bounds, _, window, _| paint_checkered_background(bounds, window),
+                )
+                .absolute()
+                .top_0()
+                .left_0()
+                .size_full(),
+            )
+            .map(|this| {
+                // Past some zoom, the pixels are painted one by one so that they stay sharp.
+                if is_pixel_view {
+                    this.child(
+                        canvas(
+                            |_, _, _| (),
+                            move |bounds, _, window, _| paint_pixels(bounds, &pixels, window),
+                        )
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .size_full(),
+                    )
+                } else {
+                    this.child(
+                        img(display_image)
+                            .absolute()
+                            .top_0()
+                            .left_0()
+                            .size_full()
+                            .object_fit(ObjectFit::Fill),
+                    )
+                }
+            })
+            .when_some(
+                self.picked_pixel
+                    .filter(|_| is_pixel_view && !self.is_cropping),
+                |this, (x, y)| {
+                    this.child(
+                        pixel_rect_overlay(
+                            PixelRect::from_corners((x, y), (x, y)),
+                            (width, height),
+                        )
+                        .border_1()
+                        .border_color(accent),
+                    )
+                },
+            )
+            .when_some(
+                self.crop_selection.filter(|_| self.is_cropping),
+                |this, selection| {
+                    this.child(
+                        pixel_rect_overlay(selection, (width, height))
+                            .border_1()
+                            .border_color(accent)
+                            .bg(accent.opacity(0.15)),
+                    )
+                },
+            )
+    }
+
+    fn render_status_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let decoded = self.decoded.as_ref();
+        let dimensions = decoded.map(|decoded| {
+            let (width, height) = decoded.dimensions();
+            format!("{width} × {height}")
+        });
+        let pixel_label = |label: &str, (x, y): (u32, u32)| {
+            Label::new(format!("{label} {x}, {y}"))
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+        };
+        let color_swatch = |color: [u8; 4]| {
+            div()
+                .size_3()
+                .rounded_xs()
+                .border_1()
+                .border_color(cx.theme().colors().border)
+                .bg(pixel_color(color))
+        };
+        let hovered = self
+            .hovered_pixel
+            .and_then(|pixel| Some((pixel, decoded?.color_at(pixel)?)));
+        let picked = self
+            .picked_pixel
+            .and_then(|pixel| Some((pixel, decoded?.color_at(pixel)?)));
+
+        h_flex()
+            .w_full()
+            .px_2()
+            .gap_3()
+            .children(dimensions.map(|dimensions| {
+                Label::new(dimensions)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+            }))
+            .when(self.edited_image.is_some(), |this| {
+                this.child(
+                    Label::new("Edited")
+                        .size(LabelSize::Small)
+                        .color(Color::Modified),
+                )
+            })
+            .children(hovered.map(|(pixel, color)| {
+                h_flex()
+                    .gap_1()
+                    .child(pixel_label("Pointer", pixel))
+                    .child(color_swatch(color))
+                    .child(
+                        Label::new(format_hex(color))
+                            .buffer_font(cx)
+                            .size(LabelSize::Small),
+                    )
+            }))
+            .children(picked.map(|(pixel, color)| {
+                h_flex()
+                    .gap_1()
+                    .child(pixel_label("Picked", pixel))
+                    .child(color_swatch(color))
+                    .child(
+                        Label::new(format_hex(color))
+                            .buffer_font(cx)
+                            .size(LabelSize::Small),
+                    )
+                    .child(
+                        IconButton::new("image-viewer-copy-hex", IconName::Copy)
+                            .icon_size(IconSize::XSmall)
+                            .tooltip(Tooltip::text("Copy Hex Color"))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.copy_color_hex(&CopyColorHex, window, cx)
+                            })),
+                    )
+                    .child(
+                        Label::new(format_rgba(color))
+                            .buffer_font(cx)
+                            .size(LabelSize::Small),
+                    )
+                    .child(
+                        IconButton::new("image-viewer-copy-rgba", IconName::Copy)
+                            .icon_size(IconSize::XSmall)
+                            .tooltip(Tooltip::text("Copy RGBA Color"))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.copy_color_rgba(&CopyColorRgba, window, cx)
+                            })),
+                    )
+            }))
+            .child(div().flex_1())
+            .children(self.status_message.clone().map(|message| {
+                Label::new(message)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .single_line()
+            }))
+    }
+}
+
+/// A box covering the given pixels of an image, which is positioned relative to the image.
+fn pixel_rect_overlay(rect: PixelRect, (width, height): (u32, u32)) -> Div {
+    let width = width.max(1) as f32;
+    let height = height.max(1) as f32;
+    div()
+        .absolute()
+        .left(relative(rect.x as f32 / width))
+        .top(relative(rect.y as f32 / height))
+        .w(relative(rect.width as f32 / width))
+        .h(relative(rect.height as f32 / height))
+}
+
+/// Paints each pixel of an image as a square, skipping the ones that are scrolled out of view and
+/// merging runs of pixels of the same color within a row.
+fn paint_pixels(bounds: Bounds<Pixels>, pixels: &RgbaImage, window: &mut Window) {
+    let (width, height) = pixels.dimensions();
+    if width == 0 || height == 0 {
+        return;
+    }
+    let visible_bounds = bounds.intersect(&window.content_mask().bounds);
+    if visible_bounds.is_empty() {
+        return;
+    }
+    let pixel_width = bounds.size.width / width as f32;
+    let pixel_height = bounds.size.height / height as f32;
+    let columns = visible_range(
+        visible_bounds.left() - bounds.left(),
+        visible_bounds.right() - bounds.left(),
+        pixel_width,
+        width,
+    );
+    let rows = visible_range(
+        visible_bounds.top() - bounds.top(),
+        visible_bounds.bottom() - bounds.top(),
+        pixel_height,
+        height,
+    );
+
+    for y in rows {
+        let top = bounds.top() + pixel_height * y as f32;
+        let mut x = columns.start;
+        while x < columns.end {
+            let color = pixels.get_pixel(x, y).0;
+            let run_start = x;
+            while x < columns.end && pixels.get_pixel(x, y).0 == color {
+                x += 1;
+            }
+            if color[3] == 0 {
+                continue;
+            }
+            let origin = point(bounds.left() + pixel_width * run_start as f32, top);
+            let run_size = size(pixel_width * (x - run_start) as f32, pixel_height);
+            window.paint_quad(fill(Bounds::new(origin, run_size), pixel_color(color)));
+        }
+    }
+}
+
+fn visible_range(start: Pixels, end: Pixels, pixel_size: Pixels, count: u32) -> Range<u32> {
+    let first = ((start / pixel_size).floor().max(0.) as u32).min(count);
+    let last = ((end / pixel_size).ceil().max(0.) as u32).min(count);
+    first..last
+}
+
+fn pixel_color([red, green, blue, alpha]: [u8; 4]) -> Hsla {
+    Rgba {
+        r: red as f32 / 255.,
+        g: green as f32 / 255.,
+        b: blue as f32 / 255.,
+        a: alpha as f32 / 255.,
+    }
+    .into()
+}
+
+fn release_image(image: Arc<gpui::Image>, window: &mut Window, cx: &mut App) {
+    if let Some(render_image) = image.clone().get_render_image(window, cx) {
+        cx.drop_image(render_image, Some(window));
+    }
+    image.remove_asset(cx);
 }
 
 pub enum ImageViewEvent {
@@ -181,17 +1121,17 @@ impl Item for ImageView {
     fn clone_on_split(
         &self,
         _workspace_id: Option<WorkspaceId>,
-        _: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Task<Option<Entity<Self>>>
     where
         Self: Sized,
     {
-        Task::ready(Some(cx.new(|cx| Self {
-            image_item: self.image_item.clone(),
-            project: self.project.clone(),
-            focus_handle: cx.focus_handle(),
-        })))
+        let image_item = self.image_item.clone();
+        let project = self.project.clone();
+        Task::ready(Some(
+            cx.new(|cx| Self::new(image_item, project, window, cx)),
+        ))
     }
 
     fn has_deleted_file(&self, cx: &App) -> bool {
@@ -302,82 +1242,133 @@ impl Focusable for ImageView {
     }
 }
 
-impl Render for ImageView {
-    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let image = self.image_item.read(cx).image.clone();
-        let checkered_background =
-            |bounds: Bounds<Pixels>, _, window: &mut Window, _cx: &mut App| {
-                let square_size: f32 = 32.0;
-
-                let start_y = bounds.origin.y.into();
-                let height: f32 = bounds.size.height.into();
-                let start_x = bounds.origin.x.into();
-                let width: f32 = bounds.size.width.into();
-
-                let mut y = start_y;
-                let mut x = start_x;
-                let mut color_swapper = true;
-                // draw checkerboard pattern
-                while y < start_y + height {
-                    // Keeping track of the grid in order to be resilient to resizing
-                    let start_swap = color_swapper;
-                    while x < start_x + width {
-                        // Clamp square dimensions to not exceed bounds
-                        let square_width = square_size.min(start_x + width - x);
-                        let square_height = square_size.min(start_y + height - y);
-
-                        let rect = Bounds::new(
-                            point(px(x), px(y)),
-                            size(px(square_width), px(square_height)),
-                        );
-
-                        let color = if color_swapper {
-                            opaque_grey(0.6, 0.4)
-                        } else {
-                            opaque_grey(0.7, 0.4)
-                        };
-
-                        window.paint_quad(fill(rect, color));
-                        color_swapper = !color_swapper;
-                        x += square_size;
-                    }
-                    x = start_x;
-                    color_swapper = !start_swap;
-                    y += square_size;
-                }
+/// Paints the checkerboard shown behind images, which makes transparent regions visible.
+pub fn paint_checkered_background(bounds: Bounds<Pixels>, window: &mut Window) {
+    let square_size: f32 = 32.0;
+
+    let start_y = bounds.origin.y.into();
+    let height: f32 = bounds.size.height.into();
+    let start_x = bounds.origin.x.into();
+    let width: f32 = bounds.size.width.into();
+
+    let mut y = start_y;
+    let mut x = start_x;
+    let mut color_swapper = true;
+    // draw checkerboard pattern
+    while y < start_y + height {
+        // Keeping track of the grid in order to be resilient to resizing
+        let start_swap = color_swapper;
+        while x < start_x + width {
+            // Clamp square dimensions to not exceed bounds
+            let square_width = square_size.min(start_x + width - x);
+            let square_height = square_size.min(start_y + height - y);
+
+            let rect = Bounds::new(
+                point(px(x), px(y)),
+                size(px(square_width), px(square_height)),
+            );
+
+            let color = if color_swapper {
+                opaque_grey(0.6, 0.4)
+            } else {
+                opaque_grey(0.7, 0.4)
             };
 
-        div().track_focus(&self.focus_handle(cx)).size_full().child(
-            div()
-                .flex()
-                .justify_center()
-                .items_center()
-                .w_full()
-                // TODO: In browser based Tailwind & Flex this would be h-screen and we'd use w-full
-                .h_full()
-                .child(
-                    div()
-                        .relative()
-                        .max_w_full()
-                        .max_h_full()
-                        .child(
-                            canvas(|_, _, _| (), checkered_background)
-                                .border_2()
-                                .border_color(cx.theme().styles.colors.border)
-                                .size_full()
-                                .absolute()
-                                .top_0()
-                                .left_0(),
+            window.paint_quad(fill(rect, color));
+            color_swapper = !color_swapper;
+            x += square_size;
+        }
+        x = start_x;
+        color_swapper = !start_swap;
+        y += square_size;
+    }
+}
+
+impl Render for ImageView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let viewport_bounds = self.viewport_bounds.clone();
+        let entity_id = cx.entity_id();
+        let decoded = self.decoded.clone();
+
+        v_flex()
+            .key_context("ImageViewer")
+            .track_focus(&self.focus_handle(cx))
+            .on_action(cx.listener(Self::zoom_in))
+            .on_action(cx.listener(Self::zoom_out))
+            .on_action(cx.listener(Self::actual_size))
+            .on_action(cx.listener(Self::zoom_to_fit))
+            .on_action(cx.listener(Self::rotate_clockwise))
+            .on_action(cx.listener(Self::rotate_counterclockwise))
+            .on_action(cx.listener(Self::toggle_crop_mode))
+            .on_action(cx.listener(Self::crop))
+            .on_action(cx.listener(Self::resize))
+            .on_action(cx.listener(Self::discard_edits))
+            .on_action(cx.listener(Self::copy_color_hex))
+            .on_action(cx.listener(Self::copy_color_rgba))
+            .on_action(cx.listener(Self::export_as_png))
+            .on_action(cx.listener(Self::export_as_jpeg))
+            .on_action(cx.listener(Self::export_as_webp))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_mouse_move(cx.listener(Self::on_mouse_move))
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _, _, _| this.crop_start = None),
+            )
+            .on_mouse_up_out(
+                MouseButton::Left,
+                cx.listener(|this, _, _, _| this.crop_start = None),
+            )
+            .size_full()
+            .py_1()
+            .gap_1()
+            .child(self.render_toolbar(window, cx))
+            .child(Divider::horizontal())
+            .child(
+                div()
+                    .id("image-viewer-viewport")
+                    .relative()
+                    .flex_1()
+                    .min_h_0()
+                    .w_full()
+                    .overflow_scroll()
+                    .track_scroll(&self.scroll_handle)
+                    .child(
+                        canvas(
+                            move |bounds, _, cx| {
+                                // Only the size matters, as the origin moves when scrolling.
+                                let previous_bounds = viewport_bounds.replace(Some(bounds));
+                                if previous_bounds.map(|bounds| bounds.size) != Some(bounds.size) {
+                                    cx.notify(entity_id);
+                                }
+                            },
+                            |_, _, _, _| {},
                         )
-                        .child(
-                            img(image)
-                                .object_fit(ObjectFit::ScaleDown)
-                                .max_w_full()
-                                .max_h_full()
-                                .id("img"),
-                        ),
-                ),
-        )
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .size_full(),
+                    )
+                    .child(
+                        h_flex()
+                            .min_w_full()
+                            .min_h_full()
+                            .justify_center()
+                            .items_center()
+                            .map(|this| match &decoded {
+                                Some(decoded) => this.child(self.render_image(decoded, window, cx)),
+                                None => this.child(
+                                    img(self.display_image(cx))
+                                        .object_fit(ObjectFit::ScaleDown)
+                                        .max_w_full()
+                                        .max_h_full()
+                                        .id("img"),
+                                ),
+                            }),
+                    ),
+            )
+            .child(Divider::horizontal())
+            .child(self.render_status_bar(cx))
     }
 }
 
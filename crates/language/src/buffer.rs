@@ -25,7 +25,7 @@ use anyhow::{Context as _, Result};
 pub use clock::ReplicaId;
 use clock::{Global, Lamport};
 use collections::{HashMap, HashSet};
-use fs::MTime;
+use fs::{FileWindow, LoadedFileWindow, MTime};
 use futures::channel::oneshot;
 use gpui::{
     App, AppContext as _, Context, Entity, EventEmitter, HighlightStyle, SharedString, StyledText,
@@ -46,6 +46,7 @@ use std::{
     cmp::{self, Ordering, Reverse},
     collections::{BTreeMap, BTreeSet},
     future::Future,
+    io,
     iter::{self, Iterator, Peekable},
     mem,
     num::NonZeroU32,
@@ -124,6 +125,9 @@ pub struct Buffer {
     completion_triggers_timestamp: clock::Lamport,
     deferred_ops: OperationQueue<Operation>,
     capability: Capability,
+    /// The window of the file that the buffer holds, if it was opened in large-file mode, in
+    /// which it is never parsed.
+    large_file_window: Option<FileWindow>,
     has_conflict: bool,
     /// Memoize calls to has_changes_since(saved_version).
     /// The contents of a cell are (self.version, has_changes) at the time of a last call.
@@ -360,6 +364,9 @@ pub enum BufferEvent {
     DiagnosticsUpdated,
     /// The buffer gained or lost editing capabilities.
     CapabilityChanged,
+    /// The buffer's large-file window, which held the end of the file, is full and stopped
+    /// following the text appended to the file.
+    LargeFileWindowFull,
 }
 
 /// The file associated with a buffer.
@@ -446,6 +453,15 @@ pub trait LocalFile: File {
 
     /// Loads the file's contents from disk.
     fn load_bytes(&self, cx: &App) -> Task<Result<Vec<u8>>>;
+
+    /// Loads a window of at most `max_len` bytes of the file, starting with the first line that
+    /// begins at or after `start`.
+    fn load_window(&self, start: u64, max_len: u64, cx: &App) -> Task<Result<LoadedFileWindow>> {
+        let bytes = self.load_bytes(cx);
+        cx.background_spawn(async move {
+            fs::read_window(io::Cursor::new(bytes.await?), start, max_len)
+        })
+    }
 }
 
 /// The auto-indent behavior associated with an editing operation.
@@ -558,6 +574,39 @@ pub struct Diff {
     pub edits: Vec<(Range<usize>, Arc<str>)>,
 }
 
+/// A transaction from the undo history of a buffer, expressed as plain text edits so that it can
+/// be persisted and replayed into another buffer.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UndoHistoryTransaction {
+    /// The edits of the transaction, ordered by position, with ranges in the text that preceded
+    /// the transaction.
+    pub edits: Vec<UndoHistoryEdit>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UndoHistoryEdit {
+    pub old_range: Range<usize>,
+    pub old_text: String,
+    pub new_text: String,
+}
+
+impl UndoHistoryTransaction {
+    /// Returns the ranges of the edits in the text that followed the transaction.
+    fn new_ranges(&self) -> impl DoubleEndedIterator<Item = (Range<usize>, &UndoHistoryEdit)> {
+        let mut delta = 0_isize;
+        let ranges = self
+            .edits
+            .iter()
+            .map(|edit| {
+                let start = (edit.old_range.start as isize + delta) as usize;
+                delta += edit.new_text.len() as isize - edit.old_range.len() as isize;
+                (start..start + edit.new_text.len(), edit)
+            })
+            .collect::<Vec<_>>();
+        ranges.into_iter()
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct DiagnosticEndpoint {
     offset: usize,
@@ -1043,6 +1092,24 @@ impl Buffer {
         self
     }
 
+    /// Opens the buffer in large-file mode, holding the given window of its file. Its text is
+    /// never parsed, and reloading it only appends to it when the file on disk was appended to,
+    /// as logs are.
+    pub fn with_large_file_window(mut self, window: FileWindow) -> Self {
+        self.large_file_window = Some(window);
+        self
+    }
+
+    /// Whether the buffer was opened in large-file mode.
+    pub fn is_large_file(&self) -> bool {
+        self.large_file_window.is_some()
+    }
+
+    /// The window of the file that the buffer holds, if it was opened in large-file mode.
+    pub fn large_file_window(&self) -> Option<&FileWindow> {
+        self.large_file_window.as_ref()
+    }
+
     /// Returns the [`Capability`] of this buffer.
     pub fn capability(&self) -> Capability {
         self.capability
@@ -1072,6 +1139,7 @@ impl Buffer {
             branch_state: None,
             file,
             capability,
+            large_file_window: None,
             syntax_map,
             reparse: None,
             non_text_state_update_count: 0,
@@ -1448,26 +1516,45 @@ impl Buffer {
         let (tx, rx) = futures::channel::oneshot::channel();
         let prev_version = self.text.version();
         self.reload_task = Some(cx.spawn(async move |this, cx| {
-            let Some((new_mtime, new_text)) = this.update(cx, |this, cx| {
+            let Some((new_mtime, diff)) = this.update(cx, |this, cx| {
                 let file = this.file.as_ref()?.as_local()?;
+                let diff = if let Some(window) = this.large_file_window.clone() {
+                    this.large_file_diff(file, window, cx)
+                } else {
+                    let new_text = file.load(cx);
+                    cx.spawn(async move |this, cx| {
+                        let new_text = new_text.await?;
+                        let diff = this.update(cx, |this, cx| this.diff(new_text, cx))?.await;
+                        Ok((diff, None))
+                    })
+                };
 
-                Some((file.disk_state().mtime(), file.load(cx)))
+                Some((file.disk_state().mtime(), diff))
             })?
             else {
                 return Ok(());
             };
 
-            let new_text = new_text.await?;
-            let diff = this
-                .update(cx, |this, cx| this.diff(new_text.clone(), cx))?
-                .await;
+            let (diff, large_file_window) = diff.await?;
             this.update(cx, |this, cx| {
                 if this.version() == diff.base_version {
+                    let mut window_filled = false;
+                    if let Some(large_file_window) = large_file_window {
+                        window_filled = this
+                            .large_file_window
+                            .as_ref()
+                            .is_some_and(FileWindow::reaches_end)
+                            && !large_file_window.reaches_end();
+                        this.large_file_window = Some(large_file_window);
+                    }
                     this.finalize_last_transaction();
                     this.apply_diff(diff, cx);
                     tx.send(this.finalize_last_transaction().cloned()).ok();
                     this.has_conflict = false;
                     this.did_reload(this.version(), this.line_ending(), new_mtime, cx);
+                    if window_filled {
+                        cx.emit(BufferEvent::LargeFileWindowFull);
+                    }
                 } else {
                     if !diff.edits.is_empty()
                         || this
@@ -1645,7 +1732,7 @@ impl Buffer {
     /// for the same buffer, we only initiate a new parse if we are not already
     /// parsing in the background.
     pub fn reparse(&mut self, cx: &mut Context<Self>, may_block: bool) {
-        if self.reparse.is_some() {
+        if self.reparse.is_some() || self.large_file_window.is_some() {
             return;
         }
         let language = if let Some(language) = self.language.clone() {
@@ -2080,6 +2167,86 @@ impl Buffer {
             })
     }
 
+    /// Spawns a task that computes the diff to reload a buffer in large-file mode, along with
+    /// the window of the file that the buffer holds afterwards. When the window reached the end
+    /// of the file, the text appended to the file since is appended to the buffer, as long as the
+    /// window stays within its maximum size. When the file was truncated, or rewritten, before the
+    /// end of the window, the window is read again, and otherwise the buffer is left as it is.
+    fn large_file_diff(
+        &self,
+        file: &dyn LocalFile,
+        window: FileWindow,
+        cx: &Context<Self>,
+    ) -> Task<Result<(Diff, Option<FileWindow>)>> {
+        // A window that doesn't reach the end of the file only needs the file's new length.
+        let tail_len = if window.reaches_end() {
+            window.max_len
+        } else {
+            0
+        };
+        let tail = file.load_window(window.last_line_start, tail_len, cx);
+        let base_version = self.version();
+        let line_ending = self.line_ending();
+        let len = self.len();
+        let last_line_start = self.point_to_offset(Point::new(self.max_point().row, 0));
+        cx.spawn(async move |this, cx| {
+            let tail = tail.await?;
+            let is_unchanged = tail.window.range.start == window.last_line_start
+                && tail.window.file_len >= window.range.end;
+            // Buffers don't free the text they delete, so rather than dropping lines from its
+            // start, a full window is replaced with one at the end of the file by its editors.
+            let is_full = tail.window.range.end.saturating_sub(window.range.start) > window.max_len;
+            if is_unchanged && (!window.reaches_end() || is_full) {
+                let window = FileWindow {
+                    file_len: tail.window.file_len,
+                    ..window
+                };
+                let diff = Diff {
+                    base_version,
+                    line_ending,
+                    edits: Vec::new(),
+                };
+                return Ok((diff, Some(window)));
+            }
+            if is_unchanged {
+                let edits = if tail.window.range.end == window.range.end {
+                    Vec::new()
+                } else {
+                    vec![(last_line_start..len, Arc::from(tail.text.to_string()))]
+                };
+                let window = FileWindow {
+                    range: window.range.start..tail.window.range.end,
+                    ..tail.window
+                };
+                let diff = Diff {
+                    base_version,
+                    line_ending,
+                    edits,
+                };
+                return Ok((diff, Some(window)));
+            }
+
+            let start = if window.range.start < tail.window.file_len {
+                window.range.start
+            } else {
+                0
+            };
+            let loaded = this
+                .update(cx, |this, cx| {
+                    let file = this.file.as_ref()?.as_local()?;
+                    Some(file.load_window(start, window.max_len, cx))
+                })?
+                .context("large file is no longer local")?
+                .await?;
+            let diff = Diff {
+                base_version,
+                line_ending: loaded.line_ending,
+                edits: vec![(0..len, Arc::from(loaded.text.to_string()))],
+            };
+            Ok((diff, Some(loaded.window)))
+        })
+    }
+
     /// Spawns a background task that searches the buffer for any whitespace
     /// at the ends of a lines, and returns a `Diff` that removes that whitespace.
     pub fn remove_trailing_whitespace(&self, cx: &App) -> Task<Diff> {
@@ -2161,6 +2328,114 @@ impl Buffer {
         self.end_transaction(cx)
     }
 
+    /// Returns the most recent transactions of the undo history as text edits, from oldest to
+    /// newest, so that they can be replayed with [`Buffer::restore_undo_history`].
+    pub fn undo_history(
+        &self,
+        max_transactions: usize,
+        cx: &App,
+    ) -> Task<Vec<UndoHistoryTransaction>> {
+        let snapshot = self.text_snapshot();
+        let mut versions = self
+            .text
+            .undo_stack()
+            .rev()
+            .take(max_transactions)
+            .map(|transaction| transaction.start.clone())
+            .collect::<Vec<_>>();
+        versions.reverse();
+        versions.push(snapshot.version().clone());
+
+        cx.background_spawn(async move {
+            versions
+                .windows(2)
+                .filter_map(|versions| {
+                    let edits = snapshot
+                        .edits_between(&versions[0], &versions[1])
+                        .into_iter()
+                        .map(|(old_range, old_text, new_text)| UndoHistoryEdit {
+                            old_range,
+                            old_text,
+                            new_text,
+                        })
+                        .collect::<Vec<_>>();
+                    (!edits.is_empty()).then_some(UndoHistoryTransaction { edits })
+                })
+                .collect()
+        })
+    }
+
+    /// Rebuilds the undo history from transactions returned by [`Buffer::undo_history`]. The
+    /// transactions must lead to the buffer's current text, which is left unchanged, as is
+    /// whether the buffer has unsaved edits.
+    ///
+    /// The history is rebuilt in the underlying text buffer only, so no edits are emitted. This
+    /// must therefore not be used on buffers that are shared with collaborators.
+    pub fn restore_undo_history(
+        &mut self,
+        transactions: &[UndoHistoryTransaction],
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let mut base_text = self.as_rope().clone();
+        for transaction in transactions.iter().rev() {
+            let edits_are_ordered = transaction.edits.iter().all(|edit| {
+                edit.old_range.start <= edit.old_range.end
+                    && edit.old_range.len() == edit.old_text.len()
+            }) && transaction
+                .edits
+                .windows(2)
+                .all(|edits| edits[0].old_range.end <= edits[1].old_range.start);
+            anyhow::ensure!(edits_are_ordered, "invalid undo history edits");
+            for (new_range, edit) in transaction.new_ranges().rev() {
+                let is_valid = new_range.end <= base_text.len()
+                    && base_text.is_char_boundary(new_range.start)
+                    && base_text.is_char_boundary(new_range.end)
+                    && base_text
+                        .chunks_in_range(new_range.clone())
+                        .collect::<String>()
+                        == edit.new_text;
+                anyhow::ensure!(is_valid, "undo history does not match the buffer's text");
+                base_text.replace(new_range, &edit.old_text);
+            }
+        }
+        if transactions.is_empty() {
+            return Ok(());
+        }
+
+        let was_dirty = self.is_dirty();
+        self.text.finalize_last_transaction();
+        self.text.start_transaction();
+        for transaction in transactions.iter().rev() {
+            self.text.edit(
+                transaction
+                    .new_ranges()
+                    .map(|(new_range, edit)| (new_range, edit.old_text.as_str())),
+            );
+        }
+        if let Some((transaction_id, _)) = self.text.end_transaction() {
+            self.text.forget_transaction(transaction_id);
+        }
+        for transaction in transactions {
+            self.text.start_transaction();
+            self.text.edit(
+                transaction
+                    .edits
+                    .iter()
+                    .map(|edit| (edit.old_range.clone(), edit.new_text.as_str())),
+            );
+            self.text.end_transaction();
+            self.text.finalize_last_transaction();
+        }
+
+        if !was_dirty {
+            self.saved_version = self.version();
+            self.has_unsaved_edits
+                .set((self.saved_version.clone(), false));
+        }
+        cx.notify();
+        Ok(())
+    }
+
     pub fn has_unsaved_edits(&self) -> bool {
         let (last_version, has_unsaved_edits) = self.has_unsaved_edits.take();
 
@@ -4260,6 +4535,16 @@ impl BufferSnapshot {
             .filter_map(|(range, obj)| (obj == TextObject::InsideFunction).then_some(range))
     }
 
+    /// For each grammar in the language, returns the captures of the provided
+    /// [`tree_sitter::Query`] in the given range, ordered by position.
+    pub fn captures(
+        &self,
+        range: Range<usize>,
+        query: fn(&Grammar) -> Option<&tree_sitter::Query>,
+    ) -> SyntaxMapCaptures<'_> {
+        self.syntax.captures(range, self, query)
+    }
+
     /// For each grammar in the language, runs the provided
     /// [`tree_sitter::Query`] against the given range.
     pub fn matches(
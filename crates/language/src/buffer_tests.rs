@@ -532,6 +532,68 @@ async fn test_apply_diff(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_undo_history(cx: &mut TestAppContext) {
+    let buffer = cx.new(|cx| {
+        let mut buffer = Buffer::local("one two three\n", cx);
+        buffer.set_group_interval(Duration::ZERO);
+        buffer.edit([(4..7, "TWO")], None, cx);
+        buffer.edit([(0..0, "zero "), (14..14, "four\n")], None, cx);
+        buffer.edit([(0..5, "")], None, cx);
+        buffer.undo(cx);
+        buffer
+    });
+
+    let history = buffer
+        .update(cx, |buffer, cx| buffer.undo_history(10, cx))
+        .await;
+    assert_eq!(history.len(), 2);
+    let truncated_history = buffer
+        .update(cx, |buffer, cx| buffer.undo_history(1, cx))
+        .await;
+    assert_eq!(truncated_history[..], history[1..]);
+
+    let text = buffer.read_with(cx, |buffer, _| buffer.text());
+    let reopened_buffer = cx.new(|cx| Buffer::local(text.clone(), cx));
+    let events = Arc::new(Mutex::new(Vec::new()));
+    cx.update(|cx| {
+        let events = events.clone();
+        cx.subscribe(&reopened_buffer, move |_, event, _| {
+            events.lock().push(event.clone())
+        })
+        .detach();
+    });
+    reopened_buffer.update(cx, |buffer, cx| {
+        buffer.restore_undo_history(&history, cx).unwrap();
+    });
+    // Restoring the history doesn't edit the buffer from the point of view of its observers.
+    assert_eq!(*events.lock(), Vec::<BufferEvent>::new());
+
+    reopened_buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "zero one TWO three\nfour\n");
+        assert!(!buffer.is_dirty());
+
+        buffer.undo(cx);
+        assert_eq!(buffer.text(), "one TWO three\n");
+        buffer.undo(cx);
+        assert_eq!(buffer.text(), "one two three\n");
+        buffer.undo(cx);
+        assert_eq!(buffer.text(), "one two three\n");
+
+        buffer.redo(cx);
+        buffer.redo(cx);
+        assert_eq!(buffer.text(), "zero one TWO three\nfour\n");
+    });
+
+    // A history that doesn't lead to the buffer's text is rejected.
+    let other_buffer = cx.new(|cx| Buffer::local("something else\n", cx));
+    other_buffer.update(cx, |buffer, cx| {
+        assert!(buffer.restore_undo_history(&history, cx).is_err());
+        assert_eq!(buffer.text(), "something else\n");
+        assert!(buffer.peek_undo_stack().is_none());
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_normalize_whitespace(cx: &mut gpui::TestAppContext) {
     let text = [
@@ -87,7 +87,8 @@ pub use language_registry::{
 pub use lsp::{LanguageServerId, LanguageServerName};
 pub use outline::*;
 pub use syntax_map::{
-    OwnedSyntaxLayer, SyntaxLayer, SyntaxMapMatches, ToTreeSitterPoint, TreeSitterOptions,
+    OwnedSyntaxLayer, SyntaxLayer, SyntaxMapCapture, SyntaxMapCaptures, SyntaxMapMatches,
+    ToTreeSitterPoint, TreeSitterOptions,
 };
 pub use text::{AnchorRangeExt, LineEnding};
 pub use tree_sitter::{Node, Parser, Tree, TreeCursor};
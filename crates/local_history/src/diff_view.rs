@@ -0,0 +1,411 @@
+//! LocalHistoryDiffView shows the differences between a version of a file from the local history and the file's current content.
+
+use std::{
+    any::{Any, TypeId},
+    pin::pin,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Result;
+use buffer_diff::{BufferDiff, BufferDiffSnapshot};
+use editor::{Editor, EditorEvent, MultiBuffer};
+use futures::{FutureExt, select_biased};
+use gpui::{
+    AnyElement, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, IntoElement, Render, Task, Window,
+};
+use language::Buffer;
+use project::Project;
+use ui::{Tooltip, prelude::*};
+use util::paths::PathExt;
+use workspace::{
+    Item, ItemHandle as _, ItemNavHistory, ToolbarItemLocation, Workspace,
+    item::{BreadcrumbText, ItemEvent, SaveOptions, TabContentParams},
+    searchable::SearchableItemHandle,
+};
+
+use crate::{RestoreVersion, Version, history_picker::version_description};
+
+const RECALCULATE_DIFF_DEBOUNCE: Duration = Duration::from_millis(250);
+
+pub struct LocalHistoryDiffView {
+    diff_editor: Entity<Editor>,
+    buffer: Entity<Buffer>,
+    version_buffer: Entity<Buffer>,
+    version_description: SharedString,
+    title: SharedString,
+    path: Option<SharedString>,
+    buffer_changes_tx: watch::Sender<()>,
+    _recalculate_diff_task: Task<Result<()>>,
+}
+
+impl LocalHistoryDiffView {
+    /// Opens a diff of the file's current content against the content of one of its versions.
+    pub fn open(
+        workspace: &Workspace,
+        buffer: Entity<Buffer>,
+        version: &Version,
+        version_text: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let version_buffer = cx.new(|cx| {
+            let mut version_buffer = Buffer::local(version_text, cx);
+            version_buffer.set_language(buffer.read(cx).language().cloned(), cx);
+            version_buffer
+        });
+        let diff = cx.new(|cx| BufferDiff::new(&buffer.read(cx).text_snapshot(), cx));
+        let version_description = version_description(version);
+        let workspace = workspace.weak_handle();
+
+        window.spawn(cx, async move |cx| {
+            let project = workspace.update(cx, |workspace, _| workspace.project().clone())?;
+
+            update_diff(&diff, &buffer, &version_buffer, cx).await?;
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let diff_view = cx.new(|cx| {
+                    LocalHistoryDiffView::new(
+                        buffer,
+                        version_buffer,
+                        version_description,
+                        diff,
+                        project,
+                        window,
+                        cx,
+                    )
+                });
+
+                let pane = workspace.active_pane();
+                pane.update(cx, |pane, cx| {
+                    pane.add_item(Box::new(diff_view.clone()), true, true, None, window, cx);
+                });
+
+                diff_view
+            })
+        })
+    }
+
+    fn new(
+        buffer: Entity<Buffer>,
+        version_buffer: Entity<Buffer>,
+        version_description: SharedString,
+        diff: Entity<BufferDiff>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::singleton(buffer.clone(), cx);
+            multibuffer.add_diff(diff.clone(), cx);
+            multibuffer
+        });
+        let diff_editor = cx.new(|cx| {
+            let mut editor = Editor::for_multibuffer(multibuffer, Some(project), window, cx);
+            editor.start_temporary_diff_override();
+            editor.disable_diagnostics(cx);
+            editor.set_expand_all_diff_hunks(cx);
+            editor.set_render_diff_hunk_controls(
+                Arc::new(|_, _, _, _, _, _, _, _| gpui::Empty.into_any_element()),
+                cx,
+            );
+            editor
+        });
+
+        let (buffer_changes_tx, mut buffer_changes_rx) = watch::channel(());
+
+        cx.subscribe(&buffer, move |this, _, event, _| match event {
+            language::BufferEvent::Edited
+            | language::BufferEvent::LanguageChanged
+            | language::BufferEvent::Reparsed => {
+                this.buffer_changes_tx.send(()).ok();
+            }
+            _ => {}
+        })
+        .detach();
+
+        let file = buffer.read(cx).file();
+        let file_name = file
+            .map(|file| file.file_name(cx).to_string())
+            .unwrap_or_else(|| "untitled".to_string());
+        let path = file.map(|file| {
+            let path = file.full_path(cx).compact().to_string_lossy().into_owned();
+            SharedString::from(format!("{path} @ {version_description} ↔ Current"))
+        });
+
+        Self {
+            diff_editor,
+            title: format!("{file_name} @ {version_description} ↔ Current").into(),
+            path,
+            version_description,
+            buffer: buffer.clone(),
+            version_buffer: version_buffer.clone(),
+            buffer_changes_tx,
+            _recalculate_diff_task: cx.spawn(async move |_, cx| {
+                while buffer_changes_rx.recv().await.is_ok() {
+                    loop {
+                        let mut timer = cx
+                            .background_executor()
+                            .timer(RECALCULATE_DIFF_DEBOUNCE)
+                            .fuse();
+                        let mut recv = pin!(buffer_changes_rx.recv().fuse());
+                        select_biased! {
+                            _ = timer => break,
+                            _ = recv => continue,
+                        }
+                    }
+
+                    update_diff(&diff, &buffer, &version_buffer, cx).await?;
+                }
+                Ok(())
+            }),
+        }
+    }
+
+    fn restore(&mut self, _: &RestoreVersion, _: &mut Window, cx: &mut Context<Self>) {
+        let version_text = self.version_buffer.read(cx).text();
+        restore_version(self.buffer.clone(), version_text, cx);
+    }
+}
+
+/// Replaces the content of the buffer with the content of a version, as a single undoable edit.
+/// The buffer is left unsaved.
+pub(crate) fn restore_version(buffer: Entity<Buffer>, version_text: String, cx: &mut App) {
+    let diff = buffer.read(cx).diff(version_text, cx);
+    cx.spawn(async move |cx| {
+        let mut diff = diff.await;
+        buffer.update(cx, |buffer, cx| {
+            // Versions are stored with normalized line endings, so keep the ones the file uses.
+            diff.line_ending = buffer.line_ending();
+            buffer.finalize_last_transaction();
+            buffer.apply_diff(diff, cx);
+            buffer.finalize_last_transaction();
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+async fn update_diff(
+    diff: &Entity<BufferDiff>,
+    buffer: &Entity<Buffer>,
+    version_buffer: &Entity<Buffer>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    let buffer_snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+    let version_buffer_snapshot = version_buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+    let version_text = version_buffer_snapshot.text();
+
+    let diff_snapshot = cx
+        .update(|cx| {
+            BufferDiffSnapshot::new_with_base_buffer(
+                buffer_snapshot.text.clone(),
+                Some(Arc::new(version_text)),
+                version_buffer_snapshot,
+                cx,
+            )
+        })?
+        .await;
+
+    diff.update(cx, |diff, cx| {
+        diff.set_snapshot(diff_snapshot, &buffer_snapshot.text, cx);
+    })?;
+    Ok(())
+}
+
+impl EventEmitter<EditorEvent> for LocalHistoryDiffView {}
+
+impl Focusable for LocalHistoryDiffView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.diff_editor.focus_handle(cx)
+    }
+}
+
+impl Item for LocalHistoryDiffView {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::HistoryRerun).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _: &App) -> SharedString {
+        self.title.clone()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        self.path.clone()
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Local History Diff View Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.diff_editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.diff_editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.diff_editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.diff_editor.for_each_project_item(cx, f)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.diff_editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.diff_editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn breadcrumb_location(&self, _: &App) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        self.diff_editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.diff_editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.diff_editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.diff_editor
+            .update(cx, |editor, cx| editor.save(options, project, window, cx))
+    }
+}
+
+impl Render for LocalHistoryDiffView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .key_context("LocalHistoryDiffView")
+            .on_action(cx.listener(Self::restore))
+            .child(
+                h_flex()
+                    .w_full()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Label::new(format!(
+                            "Changes since the version from {}",
+                            self.version_description
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    )
+                    .child(
+                        Button::new("restore-version", "Restore This Version")
+                            .label_size(LabelSize::Small)
+                            .tooltip(Tooltip::for_action_title(
+                                "Replace the file's content with this version",
+                                &RestoreVersion,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.restore(&RestoreVersion, window, cx)
+                            })),
+                    ),
+            )
+            .child(self.diff_editor.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use language::LineEnding;
+
+    #[gpui::test]
+    async fn test_restore_version_keeps_line_endings(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("one\r\ntwo\r\nthree\r\n", cx));
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.line_ending()),
+            LineEnding::Windows
+        );
+
+        cx.update(|cx| restore_version(buffer.clone(), "one\n2\nthree\n".to_string(), cx));
+        cx.run_until_parked();
+        buffer.read_with(cx, |buffer, _| {
+            assert_eq!(buffer.text(), "one\n2\nthree\n");
+            assert_eq!(buffer.line_ending(), LineEnding::Windows);
+        });
+
+        // Restoring is undone as a single edit.
+        buffer.update(cx, |buffer, cx| buffer.undo(cx));
+        buffer.read_with(cx, |buffer, _| {
+            assert_eq!(buffer.text(), "one\ntwo\nthree\n");
+        });
+    }
+}
@@ -0,0 +1,321 @@
+use std::{path::PathBuf, sync::Arc};
+
+use editor::Editor;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{DismissEvent, Entity, EventEmitter, Focusable, Render, Subscription, Task, WeakEntity};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use time::{OffsetDateTime, UtcOffset};
+use time_format::TimestampFormat;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use workspace::{ModalView, Workspace};
+
+use crate::{
+    LocalHistoryDiffView, ShowFileHistory, Version, VersionSource, buffer_abs_path,
+    diff_view::restore_version, local_history_store,
+};
+
+/// Describes when a version was recorded, e.g. "Today at 3:00 PM".
+pub(crate) fn version_description(version: &Version) -> SharedString {
+    time_format::format_localized_timestamp(
+        version.recorded_at(),
+        OffsetDateTime::now_utc(),
+        UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC),
+        TimestampFormat::EnhancedAbsolute,
+    )
+    .into()
+}
+
+fn source_description(source: VersionSource) -> &'static str {
+    match source {
+        VersionSource::Save => "Saved",
+        VersionSource::External => "Changed on disk",
+    }
+}
+
+/// A timeline of the versions of the active file, from newest to oldest.
+pub struct FileHistoryPicker {
+    picker: Entity<Picker<FileHistoryPickerDelegate>>,
+    _subscription: Subscription,
+}
+
+impl FileHistoryPicker {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        _: &ShowFileHistory,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(buffer) = workspace
+            .active_item_as::<Editor>(cx)
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+        else {
+            return;
+        };
+        let Some(path) = buffer_abs_path(&buffer, cx) else {
+            return;
+        };
+
+        let store = local_history_store(cx);
+        cx.spawn_in(window, async move |workspace, cx| {
+            let versions = cx
+                .background_spawn({
+                    let path = path.clone();
+                    async move { store.versions(&path).await }
+                })
+                .await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                let workspace_handle = workspace.weak_handle();
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    Self::new(workspace_handle, buffer, path, versions, window, cx)
+                });
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        buffer: Entity<Buffer>,
+        path: PathBuf,
+        versions: Vec<Version>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let candidates = versions
+            .iter()
+            .enumerate()
+            .map(|(id, version)| StringMatchCandidate::new(id, &version_description(version)))
+            .collect();
+        let picker = cx.new(|cx| {
+            Picker::uniform_list(
+                FileHistoryPickerDelegate {
+                    workspace,
+                    buffer,
+                    path,
+                    versions,
+                    candidates,
+                    matches: Vec::new(),
+                    selected_index: 0,
+                },
+                window,
+                cx,
+            )
+            .modal(true)
+        });
+        Self {
+            _subscription: cx.subscribe(&picker, |_, _, _, cx| {
+                cx.emit(DismissEvent);
+            }),
+            picker,
+        }
+    }
+}
+
+impl Render for FileHistoryPicker {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("FileHistoryPicker")
+            .track_focus(&self.focus_handle(cx))
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl EventEmitter<DismissEvent> for FileHistoryPicker {}
+
+impl Focusable for FileHistoryPicker {
+    fn focus_handle(&self, cx: &App) -> gpui::FocusHandle {
+        self.picker.read(cx).focus_handle(cx)
+    }
+}
+
+impl ModalView for FileHistoryPicker {}
+
+pub struct FileHistoryPickerDelegate {
+    workspace: WeakEntity<Workspace>,
+    buffer: Entity<Buffer>,
+    path: PathBuf,
+    versions: Vec<Version>,
+    candidates: Arc<[StringMatchCandidate]>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for FileHistoryPickerDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Compare with a version, or restore it with secondary confirm".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        if self.versions.is_empty() {
+            Some("No versions of this file have been recorded yet".into())
+        } else {
+            Some("No matches".into())
+        }
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let candidates = self.candidates.clone();
+        cx.spawn(async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        score: 0.,
+                        positions: Vec::new(),
+                        string: candidate.string.clone(),
+                    })
+                    .collect()
+            } else {
+                let mut matches = fuzzy::match_strings(
+                    &candidates[..],
+                    &query,
+                    true,
+                    true,
+                    1000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await;
+                // Keep the timeline in order, rather than sorting by score.
+                matches.sort_unstable_by_key(|hit| hit.candidate_id);
+                matches
+            };
+
+            this.update(cx, |this, _| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                if delegate.matches.is_empty() {
+                    delegate.selected_index = 0;
+                } else {
+                    delegate.selected_index =
+                        delegate.selected_index.min(delegate.matches.len() - 1);
+                }
+            })
+            .ok();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(version) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|hit| self.versions.get(hit.candidate_id))
+            .cloned()
+        else {
+            return;
+        };
+        let store = local_history_store(cx);
+        let buffer = self.buffer.clone();
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            let version_text = cx
+                .background_spawn({
+                    let version = version.clone();
+                    async move { store.load_version(&version).await }
+                })
+                .await?;
+            if secondary {
+                cx.update(|_, cx| restore_version(buffer, version_text, cx))?;
+            } else {
+                workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        LocalHistoryDiffView::open(
+                            workspace,
+                            buffer,
+                            &version,
+                            version_text,
+                            window,
+                            cx,
+                        )
+                    })?
+                    .await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let version = self.versions.get(hit.candidate_id)?;
+        let is_latest = hit.candidate_id == 0;
+        let file_name = self
+            .path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let subtitle = if is_latest {
+            format!(
+                "{} · {file_name} · Latest",
+                source_description(version.source)
+            )
+        } else {
+            format!("{} · {file_name}", source_description(version.source))
+        };
+
+        Some(
+            ListItem::new(SharedString::from(format!("version-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(
+                    Icon::new(match version.source {
+                        VersionSource::Save => IconName::Check,
+                        VersionSource::External => IconName::File,
+                    })
+                    .color(Color::Muted),
+                )
+                .child(
+                    v_flex()
+                        .items_start()
+                        .child(HighlightedLabel::new(
+                            hit.string.clone(),
+                            hit.positions.clone(),
+                        ))
+                        .child(
+                            Label::new(subtitle)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
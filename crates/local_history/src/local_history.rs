@@ -0,0 +1,187 @@
+mod diff_view;
+mod history_picker;
+mod local_history_settings;
+mod store;
+
+use std::{path::PathBuf, sync::Arc};
+
+use fs::Fs;
+use gpui::{App, AppContext as _, Context, Entity, Global, actions};
+use language::{Buffer, BufferEvent, LineEnding};
+use project::{
+    PathChange, Project, ProjectPath, UpdatedEntriesSet, WorktreeId, buffer_store::BufferStoreEvent,
+};
+use settings::Settings as _;
+use time::OffsetDateTime;
+use util::ResultExt as _;
+use workspace::Workspace;
+
+pub use diff_view::LocalHistoryDiffView;
+pub use history_picker::FileHistoryPicker;
+pub use local_history_settings::LocalHistorySettings;
+pub use store::{LocalHistoryStore, Retention, Version, VersionSource};
+
+actions!(
+    local_history,
+    [
+        /// Shows the versions of the active file recorded in the local history.
+        ShowFileHistory,
+        /// Replaces the content of the file with the version shown in the local history diff.
+        RestoreVersion,
+    ]
+);
+
+struct GlobalLocalHistoryStore(Arc<LocalHistoryStore>);
+
+impl Global for GlobalLocalHistoryStore {}
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut App) {
+    let store = Arc::new(LocalHistoryStore::new(
+        fs,
+        paths::local_history_dir().clone(),
+    ));
+    cx.set_global(GlobalLocalHistoryStore(store.clone()));
+
+    let retention = LocalHistorySettings::get_global(cx).retention;
+    cx.background_spawn(async move { store.prune(retention, OffsetDateTime::now_utc()).await })
+        .detach_and_log_err(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, _, cx| {
+        workspace.register_action(FileHistoryPicker::toggle);
+        record_project_changes(workspace, cx);
+    })
+    .detach();
+}
+
+pub(crate) fn local_history_store(cx: &App) -> Arc<LocalHistoryStore> {
+    cx.global::<GlobalLocalHistoryStore>().0.clone()
+}
+
+pub(crate) fn buffer_abs_path(buffer: &Entity<Buffer>, cx: &App) -> Option<PathBuf> {
+    project::File::from_dyn(buffer.read(cx).file())
+        .map(|file| file.worktree.read(cx).absolutize(&file.path))
+}
+
+/// Records a version of the project's files whenever they are saved, or changed on disk.
+fn record_project_changes(workspace: &Workspace, cx: &mut Context<Workspace>) {
+    let project = workspace.project().clone();
+    if !project.read(cx).is_local() {
+        return;
+    }
+
+    let buffer_store = project.read(cx).buffer_store().clone();
+    cx.subscribe(&buffer_store, |_, _, event, cx| {
+        if let BufferStoreEvent::BufferAdded(buffer) = event {
+            cx.subscribe(buffer, |_, buffer, event: &BufferEvent, cx| {
+                let source = match event {
+                    BufferEvent::Saved => VersionSource::Save,
+                    BufferEvent::Reloaded => VersionSource::External,
+                    _ => return,
+                };
+                if let Some(path) = buffer_abs_path(&buffer, cx) {
+                    record_version(path, buffer.read(cx).text(), source, cx);
+                }
+            })
+            .detach();
+        }
+    })
+    .detach();
+
+    cx.subscribe(&project, |_, project, event, cx| {
+        if let project::Event::WorktreeUpdatedEntries(worktree_id, changes) = event {
+            record_changed_files(&project, *worktree_id, changes, cx);
+        }
+    })
+    .detach();
+}
+
+fn record_version(path: PathBuf, content: String, source: VersionSource, cx: &App) {
+    let settings = *LocalHistorySettings::get_global(cx);
+    if !settings.enabled || content.len() as u64 > settings.max_file_size {
+        return;
+    }
+    let store = local_history_store(cx);
+    cx.background_spawn(async move {
+        store
+            .record(
+                &path,
+                &content,
+                source,
+                settings.retention,
+                OffsetDateTime::now_utc(),
+            )
+            .await
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Records the files that changed on disk while not open in a buffer. Open buffers are recorded
+/// when they reload instead.
+fn record_changed_files(
+    project: &Entity<Project>,
+    worktree_id: WorktreeId,
+    changes: &UpdatedEntriesSet,
+    cx: &App,
+) {
+    let settings = *LocalHistorySettings::get_global(cx);
+    if !settings.enabled {
+        return;
+    }
+    let project = project.read(cx);
+    let Some(worktree) = project.worktree_for_id(worktree_id, cx) else {
+        return;
+    };
+    let worktree = worktree.read(cx);
+    let paths = changes
+        .iter()
+        .filter(|(_, _, change)| {
+            matches!(
+                change,
+                PathChange::Added | PathChange::Updated | PathChange::AddedOrUpdated
+            )
+        })
+        .filter_map(|(path, entry_id, _)| {
+            let entry = worktree.entry_for_id(*entry_id)?;
+            if !entry.is_file() || entry.is_ignored || entry.size > settings.max_file_size {
+                return None;
+            }
+            let project_path = ProjectPath {
+                worktree_id,
+                path: path.clone(),
+            };
+            if project.get_open_buffer(&project_path, cx).is_some() {
+                return None;
+            }
+            Some(worktree.absolutize(path))
+        })
+        .collect::<Vec<_>>();
+    if paths.is_empty() {
+        return;
+    }
+
+    let store = local_history_store(cx);
+    cx.background_spawn(async move {
+        let now = OffsetDateTime::now_utc();
+        for path in paths {
+            let Some(content) = store.fs().load_bytes(&path).await.log_err() else {
+                continue;
+            };
+            // Only text files are recorded.
+            let Ok(mut content) = String::from_utf8(content) else {
+                continue;
+            };
+            LineEnding::normalize(&mut content);
+            store
+                .record(
+                    &path,
+                    &content,
+                    VersionSource::External,
+                    settings.retention,
+                    now,
+                )
+                .await
+                .log_err();
+        }
+    })
+    .detach();
+}
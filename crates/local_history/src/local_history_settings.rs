@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use settings::{RegisterSetting, Settings};
+
+use crate::Retention;
+
+#[derive(Clone, Copy, Debug, RegisterSetting)]
+pub struct LocalHistorySettings {
+    pub enabled: bool,
+    pub retention: Retention,
+    /// The size, in bytes, above which files are not recorded.
+    pub max_file_size: u64,
+}
+
+impl Settings for LocalHistorySettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let local_history = content.local_history.as_ref().unwrap();
+
+        Self {
+            enabled: local_history.enabled.unwrap(),
+            retention: Retention {
+                max_age: Duration::from_secs(
+                    u64::from(local_history.max_age_days.unwrap()) * 24 * 60 * 60,
+                ),
+                max_versions: local_history.max_versions_per_file.unwrap() as usize,
+            },
+            max_file_size: local_history.max_file_size_kb.unwrap() * 1024,
+        }
+    }
+}
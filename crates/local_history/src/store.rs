@@ -0,0 +1,364 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use fs::{Fs, RemoveOptions};
+use futures::{StreamExt as _, lock::Mutex};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use time::OffsetDateTime;
+use util::ResultExt as _;
+
+const OBJECTS_DIR: &str = "objects";
+const HISTORIES_DIR: &str = "histories";
+
+/// What caused a version of a file to be recorded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionSource {
+    /// The file was saved from the editor.
+    Save,
+    /// The file was changed on disk by another program.
+    External,
+}
+
+/// A recorded version of a file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Version {
+    /// When the version was recorded, in seconds since the Unix epoch.
+    pub timestamp: i64,
+    /// The hash of the version's content, which the content is stored under.
+    pub hash: String,
+    pub source: VersionSource,
+}
+
+impl Version {
+    pub fn recorded_at(&self) -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp(self.timestamp).unwrap_or(OffsetDateTime::UNIX_EPOCH)
+    }
+}
+
+/// The versions recorded for a file, from oldest to newest.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FileHistory {
+    path: PathBuf,
+    versions: Vec<Version>,
+}
+
+/// How many versions of each file are kept.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Retention {
+    pub max_age: Duration,
+    pub max_versions: usize,
+}
+
+impl Retention {
+    /// Drops the versions older than the maximum age, then the oldest versions beyond the maximum
+    /// count. `versions` must be ordered from oldest to newest.
+    fn apply(&self, versions: &mut Vec<Version>, now: OffsetDateTime) {
+        let oldest_timestamp = (now - self.max_age).unix_timestamp();
+        versions.retain(|version| version.timestamp >= oldest_timestamp);
+        let excess = versions.len().saturating_sub(self.max_versions);
+        versions.drain(..excess);
+    }
+}
+
+/// A content-addressed store of the versions of files.
+///
+/// The content of every version is stored once, under its hash, in the `objects` directory. Each
+/// file has an index of its versions in the `histories` directory, named after the hash of the
+/// file's path.
+pub struct LocalHistoryStore {
+    fs: Arc<dyn Fs>,
+    root: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl LocalHistoryStore {
+    pub fn new(fs: Arc<dyn Fs>, root: PathBuf) -> Self {
+        Self {
+            fs,
+            root,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn fs(&self) -> &Arc<dyn Fs> {
+        &self.fs
+    }
+
+    /// Records a new version of the file, unless its content is the same as the latest version.
+    /// Returns whether a version was recorded.
+    pub async fn record(
+        &self,
+        path: &Path,
+        content: &str,
+        source: VersionSource,
+        retention: Retention,
+        now: OffsetDateTime,
+    ) -> Result<bool> {
+        let _lock = self.lock.lock().await;
+        let history_path = self.history_path(path);
+        let mut history = self.load_history(&history_path).await?;
+
+        let hash = content_hash(content.as_bytes());
+        if history
+            .versions
+            .last()
+            .is_some_and(|version| version.hash == hash)
+        {
+            return Ok(false);
+        }
+
+        let object_path = self.object_path(&hash);
+        if !self.fs.is_file(&object_path).await {
+            self.write(object_path, content.to_string()).await?;
+        }
+        history.path = path.to_path_buf();
+        history.versions.push(Version {
+            timestamp: now.unix_timestamp(),
+            hash,
+            source,
+        });
+        retention.apply(&mut history.versions, now);
+        self.write(history_path, serde_json::to_string(&history)?)
+            .await?;
+        Ok(true)
+    }
+
+    /// Returns the versions recorded for the file, from newest to oldest.
+    pub async fn versions(&self, path: &Path) -> Result<Vec<Version>> {
+        let _lock = self.lock.lock().await;
+        let mut versions = self.load_history(&self.history_path(path)).await?.versions;
+        versions.reverse();
+        Ok(versions)
+    }
+
+    pub async fn load_version(&self, version: &Version) -> Result<String> {
+        let object_path = self.object_path(&version.hash);
+        self.fs
+            .load(&object_path)
+            .await
+            .with_context(|| format!("loading local history version {object_path:?}"))
+    }
+
+    /// Applies the retention to the history of every file, then removes the content that is no
+    /// longer referenced by any version.
+    pub async fn prune(&self, retention: Retention, now: OffsetDateTime) -> Result<()> {
+        let _lock = self.lock.lock().await;
+        let mut referenced_hashes = HashSet::default();
+        for history_path in self.read_dir(&self.root.join(HISTORIES_DIR)).await? {
+            let Some(mut history) = self.load_history(&history_path).await.log_err() else {
+                continue;
+            };
+            let version_count = history.versions.len();
+            retention.apply(&mut history.versions, now);
+            if history.versions.is_empty() {
+                self.fs
+                    .remove_file(&history_path, RemoveOptions::default())
+                    .await?;
+                continue;
+            }
+            if history.versions.len() != version_count {
+                self.write(history_path, serde_json::to_string(&history)?)
+                    .await?;
+            }
+            referenced_hashes.extend(history.versions.into_iter().map(|version| version.hash));
+        }
+
+        for shard_path in self.read_dir(&self.root.join(OBJECTS_DIR)).await? {
+            for object_path in self.read_dir(&shard_path).await? {
+                let is_referenced = object_path
+                    .file_name()
+                    .and_then(|file_name| file_name.to_str())
+                    .is_some_and(|hash| referenced_hashes.contains(hash));
+                if !is_referenced {
+                    self.fs
+                        .remove_file(&object_path, RemoveOptions::default())
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn history_path(&self, path: &Path) -> PathBuf {
+        let hash = content_hash(path.as_os_str().as_encoded_bytes());
+        self.root.join(HISTORIES_DIR).join(format!("{hash}.json"))
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.root
+            .join(OBJECTS_DIR)
+            .join(hash.get(..2).unwrap_or(hash))
+            .join(hash)
+    }
+
+    async fn load_history(&self, history_path: &Path) -> Result<FileHistory> {
+        if !self.fs.is_file(history_path).await {
+            return Ok(FileHistory::default());
+        }
+        let json = self.fs.load(history_path).await?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("parsing local history index {history_path:?}"))
+    }
+
+    async fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        if !self.fs.is_dir(path).await {
+            return Ok(Vec::new());
+        }
+        let mut entries = self.fs.read_dir(path).await?;
+        let mut paths = Vec::new();
+        while let Some(entry) = entries.next().await {
+            paths.push(entry?);
+        }
+        Ok(paths)
+    }
+
+    async fn write(&self, path: PathBuf, content: String) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            self.fs.create_dir(parent).await?;
+        }
+        self.fs.atomic_write(path, content).await
+    }
+}
+
+fn content_hash(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use util::path;
+
+    fn version(timestamp: i64, hash: &str) -> Version {
+        Version {
+            timestamp,
+            hash: hash.to_string(),
+            source: VersionSource::Save,
+        }
+    }
+
+    #[test]
+    fn test_retention() {
+        let now = OffsetDateTime::from_unix_timestamp(1_000_000).unwrap();
+        let retention = Retention {
+            max_age: Duration::from_secs(100),
+            max_versions: 2,
+        };
+
+        let mut versions = vec![
+            version(999_800, "a"),
+            version(999_950, "b"),
+            version(999_990, "c"),
+        ];
+        retention.apply(&mut versions, now);
+        assert_eq!(versions, [version(999_950, "b"), version(999_990, "c")]);
+
+        // Versions past the maximum age are dropped, even when there are fewer than the maximum count.
+        let mut versions = vec![version(999_800, "a"), version(999_990, "b")];
+        retention.apply(&mut versions, now);
+        assert_eq!(versions, [version(999_990, "b")]);
+    }
+
+    #[gpui::test]
+    async fn test_record_and_prune(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let store = LocalHistoryStore::new(fs.clone(), PathBuf::from(path!("/history")));
+        let file_path = Path::new(path!("/project/main.rs"));
+        let other_file_path = Path::new(path!("/project/lib.rs"));
+        let retention = Retention {
+            max_age: Duration::from_secs(60 * 60),
+            max_versions: 2,
+        };
+        let at = |seconds| OffsetDateTime::from_unix_timestamp(seconds).unwrap();
+
+        assert!(
+            store
+                .record(file_path, "one", VersionSource::Save, retention, at(100))
+                .await
+                .unwrap()
+        );
+        // Recording the same content as the latest version is a no-op.
+        assert!(
+            !store
+                .record(
+                    file_path,
+                    "one",
+                    VersionSource::External,
+                    retention,
+                    at(200)
+                )
+                .await
+                .unwrap()
+        );
+        assert!(
+            store
+                .record(
+                    file_path,
+                    "two",
+                    VersionSource::External,
+                    retention,
+                    at(300)
+                )
+                .await
+                .unwrap()
+        );
+        assert!(
+            store
+                .record(
+                    other_file_path,
+                    "one",
+                    VersionSource::Save,
+                    retention,
+                    at(300)
+                )
+                .await
+                .unwrap()
+        );
+
+        let versions = store.versions(file_path).await.unwrap();
+        assert_eq!(
+            versions
+                .iter()
+                .map(|version| (version.timestamp, version.source))
+                .collect::<Vec<_>>(),
+            [(300, VersionSource::External), (100, VersionSource::Save)]
+        );
+        assert_eq!(store.load_version(&versions[0]).await.unwrap(), "two");
+        assert_eq!(store.load_version(&versions[1]).await.unwrap(), "one");
+        // Identical content is stored once, even when it belongs to different files.
+        assert_eq!(
+            store.versions(other_file_path).await.unwrap()[0].hash,
+            versions[1].hash
+        );
+
+        // Recording beyond the maximum count drops the oldest version, but keeps its content
+        // until the store is pruned.
+        store
+            .record(file_path, "three", VersionSource::Save, retention, at(400))
+            .await
+            .unwrap();
+        assert_eq!(store.versions(file_path).await.unwrap().len(), 2);
+        assert!(fs.is_file(&store.object_path(&versions[1].hash)).await);
+
+        // Once the versions from the earlier recordings expire, their content is no longer referenced.
+        store.prune(retention, at(400 + 60 * 60)).await.unwrap();
+        assert!(store.versions(other_file_path).await.unwrap().is_empty());
+        let remaining_versions = store.versions(file_path).await.unwrap();
+        assert_eq!(remaining_versions.len(), 1);
+        assert_eq!(
+            store.load_version(&remaining_versions[0]).await.unwrap(),
+            "three"
+        );
+        assert!(!fs.is_file(&store.object_path(&versions[0].hash)).await);
+        assert!(!fs.is_file(&store.object_path(&versions[1].hash)).await);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::{markdown_math::MathNode, markdown_mermaid::MermaidDiagram};
 use gpui::{
     DefiniteLength, FontStyle, FontWeight, HighlightStyle, SharedString, StrikethroughStyle,
     UnderlineStyle, px,
@@ -14,6 +15,8 @@ pub enum ParsedMarkdownElement {
     Table(ParsedMarkdownTable),
     BlockQuote(ParsedMarkdownBlockQuote),
     CodeBlock(ParsedMarkdownCodeBlock),
+    /// A fenced `mermaid` code block that parsed as a supported diagram.
+    Mermaid(ParsedMarkdownMermaid),
     /// A paragraph of text and other inline elements.
     Paragraph(MarkdownParagraph),
     HorizontalRule(Range<usize>),
@@ -28,9 +31,13 @@ impl ParsedMarkdownElement {
             Self::Table(table) => table.source_range.clone(),
             Self::BlockQuote(block_quote) => block_quote.source_range.clone(),
             Self::CodeBlock(code_block) => code_block.source_range.clone(),
+            Self::Mermaid(mermaid) => mermaid.source_range.clone(),
             Self::Paragraph(text) => match text.get(0)? {
                 MarkdownParagraphChunk::Text(t) => t.source_range.clone(),
                 MarkdownParagraphChunk::Image(image) => image.source_range.clone(),
+                MarkdownParagraphChunk::InlineMath(math) | MarkdownParagraphChunk::Math(math) => {
+                    math.source_range.clone()
+                }
             },
             Self::HorizontalRule(range) => range.clone(),
             Self::Image(image) => image.source_range.clone(),
@@ -49,6 +56,10 @@ pub type MarkdownParagraph = Vec<MarkdownParagraphChunk>;
 pub enum MarkdownParagraphChunk {
     Text(ParsedMarkdownText),
     Image(Image),
+    /// Inline math (`$...$`), laid out between the surrounding runs of text.
+    InlineMath(ParsedMarkdownMath),
+    /// Display math (`$$...$$`), laid out on its own line.
+    Math(ParsedMarkdownMath),
 }
 
 #[derive(Debug)]
@@ -86,6 +97,22 @@ pub struct ParsedMarkdownCodeBlock {
     pub highlights: Option<Vec<(Range<usize>, HighlightId)>>,
 }
 
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownMermaid {
+    pub source_range: Range<usize>,
+    pub contents: SharedString,
+    pub diagram: MermaidDiagram,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownMath {
+    pub source_range: Range<usize>,
+    pub contents: SharedString,
+    pub expression: MathNode,
+}
+
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct ParsedMarkdownHeading {
@@ -173,6 +200,24 @@ pub struct ParsedMarkdownText {
     pub highlights: Vec<(Range<usize>, MarkdownHighlight)>,
     /// The regions of the Markdown document.
     pub regions: Vec<(Range<usize>, ParsedRegion)>,
+    /// Where each run of `contents` starts, paired with the source range the
+    /// run was parsed from.
+    pub source_runs: Vec<(usize, Range<usize>)>,
+}
+
+impl ParsedMarkdownText {
+    /// Maps an offset in `contents` back to an offset in the source document.
+    pub fn source_offset_for(&self, index: usize) -> usize {
+        let run_index = self
+            .source_runs
+            .partition_point(|(run_start, _)| *run_start <= index);
+        match run_index.checked_sub(1).map(|ix| &self.source_runs[ix]) {
+            Some((run_start, source_range)) => {
+                (source_range.start + index - run_start).min(source_range.end)
+            }
+            None => self.source_range.start,
+        }
+    }
 }
 
 /// A run of highlighted Markdown text.
@@ -0,0 +1,1438 @@
+//! Parsing and layout of LaTeX math for the Markdown preview.
+//!
+//! Math is laid out in the style of KaTeX (stacked fractions, raised and
+//! lowered scripts, radicals, stretched delimiters and matrices) and emitted
+//! as SVG, in display style for `$$...$$` blocks and in the more compact text
+//! style for inline `$...$` math.
+
+use std::fmt::Write as _;
+
+/// A parsed LaTeX math expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MathNode {
+    /// A variable or other symbol that is set in italics.
+    Identifier(String),
+    Number(String),
+    /// A binary operator or relation, which gets space on both sides.
+    Operator(String),
+    /// Upright text such as function names, punctuation and `\text{}`.
+    Text(String),
+    Row(Vec<MathNode>),
+    Fraction {
+        numerator: Box<MathNode>,
+        denominator: Box<MathNode>,
+    },
+    Root {
+        index: Option<Box<MathNode>>,
+        radicand: Box<MathNode>,
+    },
+    Scripts {
+        base: Box<MathNode>,
+        superscript: Option<Box<MathNode>>,
+        subscript: Option<Box<MathNode>>,
+    },
+    /// A large operator like `\sum` or `\int`.
+    LargeOperator(String),
+    Delimited {
+        open: String,
+        close: String,
+        body: Box<MathNode>,
+    },
+    Matrix {
+        open: String,
+        close: String,
+        rows: Vec<Vec<MathNode>>,
+        align_left: bool,
+    },
+    /// Horizontal space, in ems.
+    Space(f32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Terminator {
+    EndOfInput,
+    Brace,
+    Right(String),
+    Ampersand,
+    NewRow,
+    End,
+}
+
+/// How deeply groups and command arguments are parsed. Anything nested deeper
+/// is kept as raw text, so that pathological input can't overflow the stack.
+const MAX_NESTING_DEPTH: usize = 32;
+
+/// Parses a LaTeX math expression. Unknown commands are kept by name so that
+/// nothing in the source is silently dropped.
+pub fn parse_math(source: &str) -> MathNode {
+    let mut parser = MathParser {
+        chars: source.chars().collect(),
+        position: 0,
+        depth: 0,
+    };
+    let mut nodes = Vec::new();
+    loop {
+        let (row, terminator) = parser.parse_row();
+        nodes.extend(row);
+        if terminator == Terminator::EndOfInput {
+            break;
+        }
+    }
+    MathNode::Row(nodes)
+}
+
+struct MathParser {
+    chars: Vec<char>,
+    position: usize,
+    depth: usize,
+}
+
+impl MathParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let character = self.peek()?;
+        self.position += 1;
+        Some(character)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn parse_row(&mut self) -> (Vec<MathNode>, Terminator) {
+        if self.depth >= MAX_NESTING_DEPTH {
+            let (text, terminator) = self.raw_text_to_closing_brace();
+            return (vec![MathNode::Text(text)], terminator);
+        }
+        self.depth += 1;
+        let row = self.parse_row_nodes();
+        self.depth -= 1;
+        row
+    }
+
+    fn parse_row_nodes(&mut self) -> (Vec<MathNode>, Terminator) {
+        let mut nodes: Vec<MathNode> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let Some(character) = self.next() else {
+                return (nodes, Terminator::EndOfInput);
+            };
+            match character {
+                '}' => return (nodes, Terminator::Brace),
+                '&' => return (nodes, Terminator::Ampersand),
+                '^' | '_' => {
+                    let script = self.parse_argument();
+                    let base = nodes.pop().unwrap_or(MathNode::Text(String::new()));
+                    nodes.push(attach_script(base, script, character == '^'));
+                }
+                '\\' => match self.parse_command() {
+                    Ok(Some(node)) => nodes.push(node),
+                    Ok(None) => {}
+                    Err(terminator) => return (nodes, terminator),
+                },
+                character => nodes.push(self.parse_character(character)),
+            }
+        }
+    }
+
+    fn parse_character(&mut self, character: char) -> MathNode {
+        match character {
+            '{' => {
+                let (row, _) = self.parse_row();
+                MathNode::Row(row)
+            }
+            '0'..='9' | '.' => {
+                let mut number = character.to_string();
+                while let Some(next) = self
+                    .peek()
+                    .filter(|next| next.is_ascii_digit() || *next == '.')
+                {
+                    number.push(next);
+                    self.position += 1;
+                }
+                MathNode::Number(number)
+            }
+            '+' | '=' | '<' | '>' => MathNode::Operator(character.to_string()),
+            '-' => MathNode::Operator("−".to_string()),
+            '*' => MathNode::Operator("∗".to_string()),
+            '\'' => MathNode::Text("′".to_string()),
+            '~' => MathNode::Space(0.33),
+            character if character.is_alphabetic() => MathNode::Identifier(character.to_string()),
+            character => MathNode::Text(character.to_string()),
+        }
+    }
+
+    /// Parses a single argument: a braced group, a command or one character.
+    fn parse_argument(&mut self) -> MathNode {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return MathNode::Text(self.parse_raw_group());
+        }
+        self.depth += 1;
+        let argument = self.parse_argument_node();
+        self.depth -= 1;
+        argument
+    }
+
+    fn parse_argument_node(&mut self) -> MathNode {
+        self.skip_whitespace();
+        match self.next() {
+            Some('{') => {
+                let (row, _) = self.parse_row();
+                MathNode::Row(row)
+            }
+            Some('\\') => self
+                .parse_command()
+                .ok()
+                .flatten()
+                .unwrap_or(MathNode::Row(Vec::new())),
+            Some(character) => self.parse_character(character),
+            None => MathNode::Row(Vec::new()),
+        }
+    }
+
+    fn parse_raw_group(&mut self) -> String {
+        self.skip_whitespace();
+        if self.peek() != Some('{') {
+            return self.next().map(String::from).unwrap_or_default();
+        }
+        self.position += 1;
+        self.raw_text_to_closing_brace().0
+    }
+
+    /// Takes the text up to the brace closing the current group, which is consumed.
+    fn raw_text_to_closing_brace(&mut self) -> (String, Terminator) {
+        let mut depth = 0;
+        let mut text = String::new();
+        while let Some(character) = self.next() {
+            match character {
+                '{' => depth += 1,
+                '}' if depth == 0 => return (text, Terminator::Brace),
+                '}' => depth -= 1,
+                _ => {}
+            }
+            text.push(character);
+        }
+        (text, Terminator::EndOfInput)
+    }
+
+    fn parse_optional_argument(&mut self) -> Option<MathNode> {
+        self.skip_whitespace();
+        if self.peek() != Some('[') {
+            return None;
+        }
+        self.position += 1;
+        let mut nodes = Vec::new();
+        while let Some(character) = self.peek() {
+            if character == ']' {
+                self.position += 1;
+                break;
+            }
+            let node = self.parse_argument();
+            nodes.push(node);
+        }
+        Some(MathNode::Row(nodes))
+    }
+
+    fn parse_command_name(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(character) = self.peek().filter(char::is_ascii_alphabetic) {
+            name.push(character);
+            self.position += 1;
+        }
+        if name.is_empty()
+            && let Some(character) = self.next()
+        {
+            name.push(character);
+        }
+        name
+    }
+
+    fn parse_delimiter(&mut self) -> String {
+        self.skip_whitespace();
+        match self.next() {
+            Some('\\') => {
+                let name = self.parse_command_name();
+                match name.as_str() {
+                    "{" | "lbrace" => "{",
+                    "}" | "rbrace" => "}",
+                    "|" | "Vert" => "‖",
+                    "vert" | "lvert" | "rvert" => "|",
+                    "langle" => "⟨",
+                    "rangle" => "⟩",
+                    "lfloor" => "⌊",
+                    "rfloor" => "⌋",
+                    "lceil" => "⌈",
+                    "rceil" => "⌉",
+                    _ => "",
+                }
+                .to_string()
+            }
+            Some('.') | None => String::new(),
+            Some(character) => character.to_string(),
+        }
+    }
+
+    fn parse_command(&mut self) -> Result<Option<MathNode>, Terminator> {
+        let name = self.parse_command_name();
+        let node = match name.as_str() {
+            "\\" => return Err(Terminator::NewRow),
+            "right" => return Err(Terminator::Right(self.parse_delimiter())),
+            "end" => {
+                self.parse_raw_group();
+                return Err(Terminator::End);
+            }
+            "," => MathNode::Space(0.17),
+            ":" | ">" => MathNode::Space(0.22),
+            ";" => MathNode::Space(0.28),
+            "!" => MathNode::Space(-0.17),
+            " " => MathNode::Space(0.25),
+            "quad" => MathNode::Space(1.),
+            "qquad" => MathNode::Space(2.),
+            "{" | "}" | "|" | "%" | "$" | "#" | "_" => MathNode::Text(name.replace('|', "‖")),
+            "frac" | "dfrac" | "tfrac" | "cfrac" => MathNode::Fraction {
+                numerator: Box::new(self.parse_argument()),
+                denominator: Box::new(self.parse_argument()),
+            },
+            "binom" => MathNode::Matrix {
+                open: "(".to_string(),
+                close: ")".to_string(),
+                rows: vec![vec![self.parse_argument()], vec![self.parse_argument()]],
+                align_left: false,
+            },
+            "sqrt" => {
+                let index = self.parse_optional_argument().map(Box::new);
+                MathNode::Root {
+                    index,
+                    radicand: Box::new(self.parse_argument()),
+                }
+            }
+            "left" => {
+                let open = self.parse_delimiter();
+                let mut nodes = Vec::new();
+                let close = loop {
+                    let (row, terminator) = self.parse_row();
+                    nodes.extend(row);
+                    match terminator {
+                        Terminator::Right(close) => break close,
+                        Terminator::EndOfInput => break String::new(),
+                        _ => {}
+                    }
+                };
+                MathNode::Delimited {
+                    open,
+                    close,
+                    body: Box::new(MathNode::Row(nodes)),
+                }
+            }
+            "begin" => self.parse_environment(),
+            "text" | "textrm" | "textit" | "textbf" | "mbox" | "mathrm" | "operatorname" => {
+                MathNode::Text(self.parse_raw_group())
+            }
+            "mathbb" => {
+                let text = self.parse_raw_group();
+                MathNode::Text(text.chars().map(double_struck).collect())
+            }
+            "mathbf" | "mathit" | "mathsf" | "mathtt" | "mathcal" | "boldsymbol" | "bm" => {
+                self.parse_argument()
+            }
+            "displaystyle" | "textstyle" | "scriptstyle" | "limits" | "nolimits" | "bigl"
+            | "bigr" | "Bigl" | "Bigr" | "big" | "Big" | "biggl" | "biggr" => return Ok(None),
+            "sin" | "cos" | "tan" | "sec" | "csc" | "cot" | "sinh" | "cosh" | "tanh" | "arcsin"
+            | "arccos" | "arctan" | "log" | "ln" | "lg" | "exp" | "lim" | "liminf" | "limsup"
+            | "max" | "min" | "sup" | "inf" | "det" | "dim" | "ker" | "deg" | "arg" | "gcd"
+            | "Pr" | "mod" | "bmod" => MathNode::Text(name),
+            "sum" => MathNode::LargeOperator("∑".to_string()),
+            "prod" => MathNode::LargeOperator("∏".to_string()),
+            "coprod" => MathNode::LargeOperator("∐".to_string()),
+            "int" => MathNode::LargeOperator("∫".to_string()),
+            "iint" => MathNode::LargeOperator("∬".to_string()),
+            "iiint" => MathNode::LargeOperator("∭".to_string()),
+            "oint" => MathNode::LargeOperator("∮".to_string()),
+            "bigcup" => MathNode::LargeOperator("⋃".to_string()),
+            "bigcap" => MathNode::LargeOperator("⋂".to_string()),
+            "bigoplus" => MathNode::LargeOperator("⨁".to_string()),
+            "bigotimes" => MathNode::LargeOperator("⨂".to_string()),
+            name => match symbol(name) {
+                Some((symbol, SymbolKind::Identifier)) => MathNode::Identifier(symbol.to_string()),
+                Some((symbol, SymbolKind::Operator)) => MathNode::Operator(symbol.to_string()),
+                Some((symbol, SymbolKind::Ordinary)) => MathNode::Text(symbol.to_string()),
+                None => MathNode::Text(name.to_string()),
+            },
+        };
+        Ok(Some(node))
+    }
+
+    fn parse_environment(&mut self) -> MathNode {
+        let environment = self.parse_raw_group();
+        let (open, close, align_left) = match environment.trim_end_matches('*') {
+            "pmatrix" => ("(", ")", false),
+            "bmatrix" => ("[", "]", false),
+            "Bmatrix" => ("{", "}", false),
+            "vmatrix" => ("|", "|", false),
+            "Vmatrix" => ("‖", "‖", false),
+            "cases" => ("{", "", true),
+            "aligned" | "align" | "alignat" | "split" | "gathered" | "gather" | "eqnarray" => {
+                ("", "", true)
+            }
+            "array" => {
+                self.parse_raw_group();
+                ("", "", false)
+            }
+            _ => ("", "", false),
+        };
+
+        let mut rows = Vec::new();
+        let mut cells = Vec::new();
+        loop {
+            let (row, terminator) = self.parse_row();
+            cells.push(MathNode::Row(row));
+            match terminator {
+                Terminator::Ampersand => {}
+                Terminator::NewRow => rows.push(std::mem::take(&mut cells)),
+                _ => break,
+            }
+        }
+        if cells.iter().any(|cell| *cell != MathNode::Row(Vec::new())) {
+            rows.push(cells);
+        }
+
+        MathNode::Matrix {
+            open: open.to_string(),
+            close: close.to_string(),
+            rows,
+            align_left,
+        }
+    }
+}
+
+fn attach_script(base: MathNode, script: MathNode, is_superscript: bool) -> MathNode {
+    let (base, mut superscript, mut subscript) = match base {
+        MathNode::Scripts {
+            base,
+            superscript,
+            subscript,
+        } => (*base, superscript, subscript),
+        base => (base, None, None),
+    };
+    if is_superscript {
+        superscript = Some(Box::new(script));
+    } else {
+        subscript = Some(Box::new(script));
+    }
+    MathNode::Scripts {
+        base: Box::new(base),
+        superscript,
+        subscript,
+    }
+}
+
+enum SymbolKind {
+    Identifier,
+    Operator,
+    Ordinary,
+}
+
+fn symbol(name: &str) -> Option<(&'static str, SymbolKind)> {
+    use SymbolKind::*;
+    Some(match name {
+        "alpha" => ("α", Identifier),
+        "beta" => ("β", Identifier),
+        "gamma" => ("γ", Identifier),
+        "delta" => ("δ", Identifier),
+        "epsilon" => ("ϵ", Identifier),
+        "varepsilon" => ("ε", Identifier),
+        "zeta" => ("ζ", Identifier),
+        "eta" => ("η", Identifier),
+        "theta" => ("θ", Identifier),
+        "vartheta" => ("ϑ", Identifier),
+        "iota" => ("ι", Identifier),
+        "kappa" => ("κ", Identifier),
+        "lambda" => ("λ", Identifier),
+        "mu" => ("μ", Identifier),
+        "nu" => ("ν", Identifier),
+        "xi" => ("ξ", Identifier),
+        "pi" => ("π", Identifier),
+        "varpi" => ("ϖ", Identifier),
+        "rho" => ("ρ", Identifier),
+        "sigma" => ("σ", Identifier),
+        "tau" => ("τ", Identifier),
+        "upsilon" => ("υ", Identifier),
+        "phi" => ("ϕ", Identifier),
+        "varphi" => ("φ", Identifier),
+        "chi" => ("χ", Identifier),
+        "psi" => ("ψ", Identifier),
+        "omega" => ("ω", Identifier),
+        "Gamma" => ("Γ", Ordinary),
+        "Delta" => ("Δ", Ordinary),
+        "Theta" => ("Θ", Ordinary),
+        "Lambda" => ("Λ", Ordinary),
+        "Xi" => ("Ξ", Ordinary),
+        "Pi" => ("Π", Ordinary),
+        "Sigma" => ("Σ", Ordinary),
+        "Upsilon" => ("Υ", Ordinary),
+        "Phi" => ("Φ", Ordinary),
+        "Psi" => ("Ψ", Ordinary),
+        "Omega" => ("Ω", Ordinary),
+        "ell" => ("ℓ", Identifier),
+        "hbar" => ("ℏ", Identifier),
+        "imath" => ("ı", Identifier),
+        "jmath" => ("ȷ", Identifier),
+        "cdot" => ("⋅", Operator),
+        "times" => ("×", Operator),
+        "div" => ("÷", Operator),
+        "pm" => ("±", Operator),
+        "mp" => ("∓", Operator),
+        "ast" => ("∗", Operator),
+        "star" => ("⋆", Operator),
+        "circ" => ("∘", Operator),
+        "bullet" => ("∙", Operator),
+        "oplus" => ("⊕", Operator),
+        "otimes" => ("⊗", Operator),
+        "cup" => ("∪", Operator),
+        "cap" => ("∩", Operator),
+        "setminus" => ("∖", Operator),
+        "wedge" | "land" => ("∧", Operator),
+        "vee" | "lor" => ("∨", Operator),
+        "leq" | "le" => ("≤", Operator),
+        "geq" | "ge" => ("≥", Operator),
+        "neq" | "ne" => ("≠", Operator),
+        "ll" => ("≪", Operator),
+        "gg" => ("≫", Operator),
+        "approx" => ("≈", Operator),
+        "equiv" => ("≡", Operator),
+        "cong" => ("≅", Operator),
+        "sim" => ("∼", Operator),
+        "simeq" => ("≃", Operator),
+        "propto" => ("∝", Operator),
+        "in" => ("∈", Operator),
+        "notin" => ("∉", Operator),
+        "ni" => ("∋", Operator),
+        "subset" => ("⊂", Operator),
+        "supset" => ("⊃", Operator),
+        "subseteq" => ("⊆", Operator),
+        "supseteq" => ("⊇", Operator),
+        "mid" => ("∣", Operator),
+        "parallel" => ("∥", Operator),
+        "perp" => ("⊥", Operator),
+        "to" | "rightarrow" => ("→", Operator),
+        "gets" | "leftarrow" => ("←", Operator),
+        "Rightarrow" | "implies" => ("⇒", Operator),
+        "Leftarrow" => ("⇐", Operator),
+        "leftrightarrow" => ("↔", Operator),
+        "Leftrightarrow" | "iff" => ("⇔", Operator),
+        "mapsto" => ("↦", Operator),
+        "longrightarrow" => ("⟶", Operator),
+        "longmapsto" => ("⟼", Operator),
+        "infty" => ("∞", Ordinary),
+        "partial" => ("∂", Ordinary),
+        "nabla" => ("∇", Ordinary),
+        "forall" => ("∀", Ordinary),
+        "exists" => ("∃", Ordinary),
+        "nexists" => ("∄", Ordinary),
+        "emptyset" | "varnothing" => ("∅", Ordinary),
+        "neg" | "lnot" => ("¬", Ordinary),
+        "angle" => ("∠", Ordinary),
+        "triangle" => ("△", Ordinary),
+        "prime" => ("′", Ordinary),
+        "degree" => ("°", Ordinary),
+        "ldots" | "dots" => ("…", Ordinary),
+        "cdots" => ("⋯", Ordinary),
+        "vdots" => ("⋮", Ordinary),
+        "ddots" => ("⋱", Ordinary),
+        "langle" => ("⟨", Ordinary),
+        "rangle" => ("⟩", Ordinary),
+        "lfloor" => ("⌊", Ordinary),
+        "rfloor" => ("⌋", Ordinary),
+        "lceil" => ("⌈", Ordinary),
+        "rceil" => ("⌉", Ordinary),
+        "lbrace" => ("{", Ordinary),
+        "rbrace" => ("}", Ordinary),
+        "vert" => ("|", Ordinary),
+        "Vert" => ("‖", Ordinary),
+        _ => return None,
+    })
+}
+
+fn double_struck(character: char) -> char {
+    match character {
+        'C' => 'ℂ',
+        'H' => 'ℍ',
+        'N' => 'ℕ',
+        'P' => 'ℙ',
+        'Q' => 'ℚ',
+        'R' => 'ℝ',
+        'Z' => 'ℤ',
+        character => character,
+    }
+}
+
+impl MathNode {
+    /// Flattens the expression to a single line of Unicode text.
+    pub fn to_unicode(&self) -> String {
+        let mut text = String::new();
+        self.write_unicode(&mut text);
+        text.trim().to_string()
+    }
+
+    fn write_unicode(&self, text: &mut String) {
+        match self {
+            MathNode::Identifier(symbol) | MathNode::Number(symbol) | MathNode::Text(symbol) => {
+                text.push_str(symbol)
+            }
+            MathNode::Operator(symbol) => {
+                if symbol == "," {
+                    text.push_str(", ");
+                } else {
+                    let _ = write!(text, " {symbol} ");
+                }
+            }
+            MathNode::LargeOperator(symbol) => text.push_str(symbol),
+            MathNode::Row(nodes) => {
+                let mut previous = None;
+                for node in nodes {
+                    match node {
+                        MathNode::Operator(symbol) if is_unary(previous) => text.push_str(symbol),
+                        node => node.write_unicode(text),
+                    }
+                    previous = Some(node);
+                }
+            }
+            MathNode::Fraction {
+                numerator,
+                denominator,
+            } => {
+                let _ = write!(
+                    text,
+                    "{}/{}",
+                    grouped_unicode(numerator),
+                    grouped_unicode(denominator)
+                );
+            }
+            MathNode::Root { index, radicand } => {
+                match index.as_ref().map(|index| index.to_unicode()).as_deref() {
+                    Some("3") => text.push('∛'),
+                    Some("4") => text.push('∜'),
+                    _ => text.push('√'),
+                }
+                text.push_str(&grouped_unicode(radicand));
+            }
+            MathNode::Scripts {
+                base,
+                superscript,
+                subscript,
+            } => {
+                base.write_unicode(text);
+                if let Some(subscript) = subscript {
+                    text.push_str(&script_unicode(subscript, subscript_char, '_'));
+                }
+                if let Some(superscript) = superscript {
+                    text.push_str(&script_unicode(superscript, superscript_char, '^'));
+                }
+            }
+            MathNode::Delimited { open, close, body } => {
+                text.push_str(open);
+                body.write_unicode(text);
+                text.push_str(close);
+            }
+            MathNode::Matrix {
+                open, close, rows, ..
+            } => {
+                text.push_str(if open.is_empty() { "[" } else { open });
+                let rows = rows
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|cell| cell.to_unicode())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                    .collect::<Vec<_>>();
+                text.push_str(&rows.join("; "));
+                text.push_str(if close.is_empty() { "]" } else { close });
+            }
+            MathNode::Space(width) => {
+                if *width > 0. {
+                    text.push(' ');
+                }
+            }
+        }
+    }
+}
+
+/// An operator is unary (like the minus in `-x`) when nothing it could
+/// combine with precedes it.
+fn is_unary(previous: Option<&MathNode>) -> bool {
+    match previous {
+        None | Some(MathNode::Operator(_)) => true,
+        Some(MathNode::Text(text)) => matches!(text.as_str(), "(" | "[" | "{" | ","),
+        Some(_) => false,
+    }
+}
+
+fn grouped_unicode(node: &MathNode) -> String {
+    let text = node.to_unicode();
+    if text.chars().count() <= 1 || text.chars().all(char::is_alphanumeric) {
+        text
+    } else {
+        format!("({text})")
+    }
+}
+
+fn script_unicode(node: &MathNode, map: fn(char) -> Option<char>, marker: char) -> String {
+    let text = node.to_unicode();
+    match text.chars().map(map).collect::<Option<String>>() {
+        Some(mapped) if !text.is_empty() => mapped,
+        _ => format!("{marker}{}", grouped_unicode(node)),
+    }
+}
+
+fn superscript_char(character: char) -> Option<char> {
+    Some(match character {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        '+' => '⁺',
+        '−' | '-' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'n' => 'ⁿ',
+        'i' => 'ⁱ',
+        'T' => 'ᵀ',
+        '′' => '′',
+        _ => return None,
+    })
+}
+
+fn subscript_char(character: char) -> Option<char> {
+    Some(match character {
+        '0' => '₀',
+        '1' => '₁',
+        '2' => '₂',
+        '3' => '₃',
+        '4' => '₄',
+        '5' => '₅',
+        '6' => '₆',
+        '7' => '₇',
+        '8' => '₈',
+        '9' => '₉',
+        '+' => '₊',
+        '−' | '-' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'i' => 'ᵢ',
+        'j' => 'ⱼ',
+        'k' => 'ₖ',
+        'n' => 'ₙ',
+        'x' => 'ₓ',
+        _ => return None,
+    })
+}
+
+const GLYPH_ASCENT: f32 = 0.72;
+const GLYPH_DESCENT: f32 = 0.22;
+const AXIS_HEIGHT: f32 = 0.25;
+const RULE_THICKNESS: f32 = 0.05;
+const SCRIPT_SCALE: f32 = 0.7;
+/// The generic `serif` family often resolves to a font that isn't installed,
+/// so list common math and serif fonts explicitly.
+const MATH_FONT_FAMILIES: &str = "'Latin Modern Math', 'STIX Two Math', 'Cambria Math', 'Times New Roman', 'Liberation Serif', 'DejaVu Serif', serif";
+
+#[derive(Debug, Clone)]
+enum MathItem {
+    Glyph {
+        x: f32,
+        y: f32,
+        text: String,
+        size: f32,
+        italic: bool,
+    },
+    Line {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        thickness: f32,
+    },
+    Path {
+        data: String,
+        thickness: f32,
+    },
+}
+
+impl MathItem {
+    fn translated(self, dx: f32, dy: f32) -> Self {
+        match self {
+            MathItem::Glyph {
+                x,
+                y,
+                text,
+                size,
+                italic,
+            } => MathItem::Glyph {
+                x: x + dx,
+                y: y + dy,
+                text,
+                size,
+                italic,
+            },
+            MathItem::Line {
+                x1,
+                y1,
+                x2,
+                y2,
+                thickness,
+            } => MathItem::Line {
+                x1: x1 + dx,
+                y1: y1 + dy,
+                x2: x2 + dx,
+                y2: y2 + dy,
+                thickness,
+            },
+            MathItem::Path { data, thickness } => MathItem::Path {
+                data: translate_path(&data, dx, dy),
+                thickness,
+            },
+        }
+    }
+}
+
+fn translate_path(data: &str, dx: f32, dy: f32) -> String {
+    let mut result = String::new();
+    let mut is_x = true;
+    for token in data.split_whitespace() {
+        if !result.is_empty() {
+            result.push(' ');
+        }
+        match token.parse::<f32>() {
+            Ok(value) => {
+                let offset = if is_x { dx } else { dy };
+                let _ = write!(result, "{:.2}", value + offset);
+                is_x = !is_x;
+            }
+            Err(_) => {
+                result.push_str(token);
+                is_x = true;
+            }
+        }
+    }
+    result
+}
+
+/// A laid out box. Coordinates are relative to the left end of the baseline,
+/// with y growing downwards as in SVG.
+#[derive(Debug, Clone, Default)]
+pub struct MathBox {
+    pub width: f32,
+    pub ascent: f32,
+    pub descent: f32,
+    items: Vec<MathItem>,
+}
+
+impl MathBox {
+    fn glyph(text: &str, size: f32, italic: bool) -> Self {
+        Self {
+            width: text_width(text, size, italic),
+            ascent: GLYPH_ASCENT * size,
+            descent: GLYPH_DESCENT * size,
+            items: vec![MathItem::Glyph {
+                x: 0.,
+                y: 0.,
+                text: text.to_string(),
+                size,
+                italic,
+            }],
+        }
+    }
+
+    fn space(width: f32) -> Self {
+        Self {
+            width,
+            ..Default::default()
+        }
+    }
+
+    fn append(&mut self, other: MathBox, dx: f32, dy: f32) {
+        self.items
+            .extend(other.items.into_iter().map(|item| item.translated(dx, dy)));
+    }
+
+    fn append_row(&mut self, other: MathBox) {
+        let dx = self.width;
+        self.width += other.width;
+        self.ascent = self.ascent.max(other.ascent);
+        self.descent = self.descent.max(other.descent);
+        self.append(other, dx, 0.);
+    }
+
+    pub fn height(&self) -> f32 {
+        self.ascent + self.descent
+    }
+
+    /// Renders the box as a standalone SVG document.
+    pub fn to_svg(&self, color: &str) -> String {
+        let padding = 2.;
+        let width = self.width + padding * 2.;
+        let height = self.height() + padding * 2.;
+        let mut svg = String::new();
+        let _ = write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.2}" height="{height:.2}" viewBox="0 0 {width:.2} {height:.2}">"#
+        );
+        let _ = write!(
+            svg,
+            r#"<g transform="translate({padding:.2} {:.2})">"#,
+            padding + self.ascent
+        );
+        for item in &self.items {
+            match item {
+                MathItem::Glyph {
+                    x,
+                    y,
+                    text,
+                    size,
+                    italic,
+                } => {
+                    let style = if *italic { "italic" } else { "normal" };
+                    let _ = write!(
+                        svg,
+                        r#"<text x="{x:.2}" y="{y:.2}" font-family="{MATH_FONT_FAMILIES}" font-size="{size:.2}" font-style="{style}" fill="{color}">{}</text>"#,
+                        escape_xml(text)
+                    );
+                }
+                MathItem::Line {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    thickness,
+                } => {
+                    let _ = write!(
+                        svg,
+                        r#"<line x1="{x1:.2}" y1="{y1:.2}" x2="{x2:.2}" y2="{y2:.2}" stroke="{color}" stroke-width="{thickness:.2}"/>"#
+                    );
+                }
+                MathItem::Path { data, thickness } => {
+                    let _ = write!(
+                        svg,
+                        r#"<path d="{data}" fill="none" stroke="{color}" stroke-width="{thickness:.2}" stroke-linejoin="round"/>"#
+                    );
+                }
+            }
+        }
+        svg.push_str("</g></svg>");
+        svg
+    }
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// Estimates the advance width of `text`, since the layout runs without
+/// access to font metrics.
+pub(crate) fn text_width(text: &str, size: f32, italic: bool) -> f32 {
+    let ems: f32 = text
+        .chars()
+        .map(|character| match character {
+            'i' | 'j' | 'l' | 'I' | '.' | ',' | ':' | ';' | '!' | '\'' | '|' | '′' => 0.3,
+            'f' | 't' | 'r' | '(' | ')' | '[' | ']' | '{' | '}' | ' ' => 0.4,
+            'm' | 'w' | 'M' | 'W' => 0.8,
+            '∫' | '∮' => 0.45,
+            '∬' => 0.7,
+            '∭' => 0.95,
+            character if character.is_ascii_uppercase() => 0.68,
+            character if character.is_ascii_alphanumeric() => 0.52,
+            character if character.is_alphabetic() => 0.58,
+            _ => 0.78,
+        })
+        .sum();
+    ems * size + if italic { 0.05 * size } else { 0. }
+}
+
+/// Lays out an expression in display style at the given font size in pixels.
+pub fn layout_math(node: &MathNode, size: f32) -> MathBox {
+    layout_node(node, size, true)
+}
+
+/// Lays out an expression in text style, to sit within a line of text at the
+/// given font size in pixels.
+pub fn layout_inline_math(node: &MathNode, size: f32) -> MathBox {
+    layout_node(node, size, false)
+}
+
+fn layout_node(node: &MathNode, size: f32, display: bool) -> MathBox {
+    match node {
+        MathNode::Identifier(symbol) => MathBox::glyph(symbol, size, true),
+        MathNode::Number(symbol) | MathNode::Text(symbol) => MathBox::glyph(symbol, size, false),
+        MathNode::Operator(symbol) => {
+            let spacing = if display { 0.25 * size } else { 0.15 * size };
+            let mut operator = MathBox::space(spacing);
+            operator.append_row(MathBox::glyph(symbol, size, false));
+            operator.append_row(MathBox::space(spacing));
+            operator
+        }
+        MathNode::Space(ems) => MathBox::space(ems * size),
+        MathNode::Row(nodes) => {
+            let mut row = MathBox {
+                ascent: GLYPH_ASCENT * size,
+                descent: GLYPH_DESCENT * size,
+                ..Default::default()
+            };
+            let mut previous = None;
+            for node in nodes {
+                let child = match node {
+                    MathNode::Operator(symbol) if is_unary(previous) => {
+                        MathBox::glyph(symbol, size, false)
+                    }
+                    node => layout_node(node, size, display),
+                };
+                row.append_row(child);
+                previous = Some(node);
+            }
+            row
+        }
+        MathNode::Fraction {
+            numerator,
+            denominator,
+        } => layout_fraction(numerator, denominator, size, display),
+        MathNode::Root { index, radicand } => {
+            layout_root(index.as_deref(), radicand, size, display)
+        }
+        MathNode::Scripts {
+            base,
+            superscript,
+            subscript,
+        } => layout_scripts(
+            base,
+            superscript.as_deref(),
+            subscript.as_deref(),
+            size,
+            display,
+        ),
+        MathNode::LargeOperator(symbol) => {
+            let scale = if display { 1.6 } else { 1.2 };
+            let mut glyph = MathBox::glyph(symbol, size * scale, false);
+            let shift = (glyph.ascent - glyph.descent) / 2. - AXIS_HEIGHT * size;
+            glyph = shifted(glyph, shift);
+            let mut operator = MathBox::space(0.1 * size);
+            operator.append_row(glyph);
+            operator.append_row(MathBox::space(0.1 * size));
+            operator
+        }
+        MathNode::Delimited { open, close, body } => {
+            let body = layout_node(body, size, display);
+            let mut delimited = MathBox::default();
+            delimited.append_row(layout_delimiter(open, &body, size));
+            delimited.append_row(body.clone());
+            delimited.append_row(layout_delimiter(close, &body, size));
+            delimited
+        }
+        MathNode::Matrix {
+            open,
+            close,
+            rows,
+            align_left,
+        } => {
+            let grid = layout_matrix(rows, *align_left, size, display);
+            let mut matrix = MathBox::default();
+            matrix.append_row(layout_delimiter(open, &grid, size));
+            matrix.append_row(grid.clone());
+            matrix.append_row(layout_delimiter(close, &grid, size));
+            matrix
+        }
+    }
+}
+
+/// Moves a box up by `amount` pixels.
+fn shifted(content: MathBox, amount: f32) -> MathBox {
+    let mut result = MathBox {
+        width: content.width,
+        ascent: content.ascent + amount,
+        descent: content.descent - amount,
+        items: Vec::new(),
+    };
+    result.append(content, 0., -amount);
+    result
+}
+
+fn layout_fraction(
+    numerator: &MathNode,
+    denominator: &MathNode,
+    size: f32,
+    display: bool,
+) -> MathBox {
+    let inner_size = if display { size } else { size * 0.85 };
+    let numerator = layout_node(numerator, inner_size, false);
+    let denominator = layout_node(denominator, inner_size, false);
+    let padding = 0.12 * size;
+    let gap = 0.18 * size;
+    let width = numerator.width.max(denominator.width) + padding * 2.;
+    let axis = AXIS_HEIGHT * size;
+
+    let numerator_baseline = -axis - gap - numerator.descent;
+    let denominator_baseline = -axis + gap + denominator.ascent;
+    let mut fraction = MathBox {
+        width,
+        ascent: axis + gap + numerator.height(),
+        descent: gap + denominator.height() - axis,
+        items: vec![MathItem::Line {
+            x1: padding / 2.,
+            y1: -axis,
+            x2: width - padding / 2.,
+            y2: -axis,
+            thickness: RULE_THICKNESS * size,
+        }],
+    };
+    let numerator_x = (width - numerator.width) / 2.;
+    let denominator_x = (width - denominator.width) / 2.;
+    fraction.append(numerator, numerator_x, numerator_baseline);
+    fraction.append(denominator, denominator_x, denominator_baseline);
+    fraction
+}
+
+fn layout_root(index: Option<&MathNode>, radicand: &MathNode, size: f32, display: bool) -> MathBox {
+    let radicand = layout_node(radicand, size, display);
+    let gap = 0.15 * size;
+    let sign_width = 0.6 * size;
+    let top = -(radicand.ascent + gap);
+    let bottom = radicand.descent;
+    let thickness = RULE_THICKNESS * size;
+
+    let mut root = MathBox {
+        width: sign_width + radicand.width + 0.1 * size,
+        ascent: radicand.ascent + gap + thickness,
+        descent: radicand.descent,
+        items: vec![MathItem::Path {
+            data: format!(
+                "M {:.2} {:.2} L {:.2} {:.2} L {:.2} {:.2} L {:.2} {:.2} L {:.2} {:.2}",
+                0.05 * size,
+                -AXIS_HEIGHT * size,
+                0.2 * size,
+                -AXIS_HEIGHT * size - 0.08 * size,
+                0.35 * size,
+                bottom,
+                sign_width - 0.05 * size,
+                top,
+                sign_width + radicand.width + 0.1 * size,
+                top
+            ),
+            thickness,
+        }],
+    };
+    root.append(radicand, sign_width, 0.);
+    if let Some(index) = index {
+        let index = layout_node(index, size * 0.55, false);
+        let index_baseline = -AXIS_HEIGHT * size - 0.15 * size;
+        root.ascent = root.ascent.max(-index_baseline + index.ascent);
+        let index_x = (0.3 * size - index.width).max(0.);
+        root.append(index, index_x, index_baseline);
+    }
+    root
+}
+
+fn layout_scripts(
+    base: &MathNode,
+    superscript: Option<&MathNode>,
+    subscript: Option<&MathNode>,
+    size: f32,
+    display: bool,
+) -> MathBox {
+    let script_size = size * SCRIPT_SCALE;
+    let superscript = superscript.map(|node| layout_node(node, script_size, false));
+    let subscript = subscript.map(|node| layout_node(node, script_size, false));
+
+    if display
+        && let MathNode::LargeOperator(symbol) = base
+        && !matches!(symbol.as_str(), "∫" | "∬" | "∭" | "∮")
+    {
+        let operator = layout_node(base, size, display);
+        let gap = 0.12 * size;
+        let width = operator
+            .width
+            .max(superscript.as_ref().map_or(0., |script| script.width))
+            .max(subscript.as_ref().map_or(0., |script| script.width));
+        let mut result = MathBox {
+            width,
+            ascent: operator.ascent,
+            descent: operator.descent,
+            items: Vec::new(),
+        };
+        if let Some(superscript) = superscript {
+            let baseline = -operator.ascent - gap - superscript.descent;
+            result.ascent = -baseline + superscript.ascent;
+            result.append(
+                superscript.clone(),
+                (width - superscript.width) / 2.,
+                baseline,
+            );
+        }
+        if let Some(subscript) = subscript {
+            let baseline = operator.descent + gap + subscript.ascent;
+            result.descent = baseline + subscript.descent;
+            result.append(subscript.clone(), (width - subscript.width) / 2., baseline);
+        }
+        result.append(operator.clone(), (width - operator.width) / 2., 0.);
+        return result;
+    }
+
+    let mut result = layout_node(base, size, display);
+    let script_x = result.width + 0.05 * size;
+    let mut scripts_width: f32 = 0.;
+    if let Some(superscript) = superscript {
+        let shift = (0.42 * size).max(result.ascent - 0.3 * size);
+        result.ascent = result.ascent.max(shift + superscript.ascent);
+        scripts_width = scripts_width.max(superscript.width);
+        result.append(superscript, script_x, -shift);
+    }
+    if let Some(subscript) = subscript {
+        let shift = (0.22 * size).max(result.descent - 0.05 * size);
+        result.descent = result.descent.max(shift + subscript.descent);
+        scripts_width = scripts_width.max(subscript.width);
+        result.append(subscript, script_x, shift);
+    }
+    result.width = script_x + scripts_width;
+    result
+}
+
+fn layout_delimiter(delimiter: &str, content: &MathBox, size: f32) -> MathBox {
+    if delimiter.is_empty() {
+        return MathBox::space(0.1 * size);
+    }
+
+    let axis = AXIS_HEIGHT * size;
+    let half_height = (content.ascent - axis).max(content.descent + axis) + 0.1 * size;
+    if half_height * 2. <= (GLYPH_ASCENT + GLYPH_DESCENT) * size * 1.2 {
+        return MathBox::glyph(delimiter, size, false);
+    }
+
+    let width = 0.45 * size;
+    let top = -axis - half_height;
+    let bottom = -axis + half_height;
+    let middle = -axis;
+    let (left, right) = (0.1 * size, width - 0.1 * size);
+    let data = match delimiter {
+        "(" => format!("M {right:.2} {top:.2} Q {left:.2} {middle:.2} {right:.2} {bottom:.2}"),
+        ")" => format!("M {left:.2} {top:.2} Q {right:.2} {middle:.2} {left:.2} {bottom:.2}"),
+        "[" => format!(
+            "M {right:.2} {top:.2} L {left:.2} {top:.2} L {left:.2} {bottom:.2} L {right:.2} {bottom:.2}"
+        ),
+        "]" => format!(
+            "M {left:.2} {top:.2} L {right:.2} {top:.2} L {right:.2} {bottom:.2} L {left:.2} {bottom:.2}"
+        ),
+        "{" => {
+            let center = (left + right) / 2.;
+            format!(
+                "M {right:.2} {top:.2} Q {center:.2} {top:.2} {center:.2} {:.2} L {center:.2} {:.2} Q {center:.2} {middle:.2} {left:.2} {middle:.2} Q {center:.2} {middle:.2} {center:.2} {:.2} L {center:.2} {:.2} Q {center:.2} {bottom:.2} {right:.2} {bottom:.2}",
+                top + 0.15 * size,
+                middle - 0.15 * size,
+                middle + 0.15 * size,
+                bottom - 0.15 * size,
+            )
+        }
+        "}" => {
+            let center = (left + right) / 2.;
+            format!(
+                "M {left:.2} {top:.2} Q {center:.2} {top:.2} {center:.2} {:.2} L {center:.2} {:.2} Q {center:.2} {middle:.2} {right:.2} {middle:.2} Q {center:.2} {middle:.2} {center:.2} {:.2} L {center:.2} {:.2} Q {center:.2} {bottom:.2} {left:.2} {bottom:.2}",
+                top + 0.15 * size,
+                middle - 0.15 * size,
+                middle + 0.15 * size,
+                bottom - 0.15 * size,
+            )
+        }
+        "⟨" => format!("M {right:.2} {top:.2} L {left:.2} {middle:.2} L {right:.2} {bottom:.2}"),
+        "⟩" => format!("M {left:.2} {top:.2} L {right:.2} {middle:.2} L {left:.2} {bottom:.2}"),
+        "‖" => {
+            let first = width / 2. - 0.08 * size;
+            let second = width / 2. + 0.08 * size;
+            format!(
+                "M {first:.2} {top:.2} L {first:.2} {bottom:.2} M {second:.2} {top:.2} L {second:.2} {bottom:.2}"
+            )
+        }
+        _ => {
+            let center = width / 2.;
+            format!("M {center:.2} {top:.2} L {center:.2} {bottom:.2}")
+        }
+    };
+
+    MathBox {
+        width,
+        ascent: -top,
+        descent: bottom,
+        items: vec![MathItem::Path {
+            data,
+            thickness: RULE_THICKNESS * size * 1.2,
+        }],
+    }
+}
+
+fn layout_matrix(rows: &[Vec<MathNode>], align_left: bool, size: f32, display: bool) -> MathBox {
+    let cells: Vec<Vec<MathBox>> = rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| layout_node(cell, size, display))
+                .collect()
+        })
+        .collect();
+    let column_count = cells.iter().map(Vec::len).max().unwrap_or(0);
+    let mut column_widths = vec![0f32; column_count];
+    for row in &cells {
+        for (column, cell) in row.iter().enumerate() {
+            column_widths[column] = column_widths[column].max(cell.width);
+        }
+    }
+
+    let column_gap = if align_left { 0.5 * size } else { 1. * size };
+    let row_gap = 0.3 * size;
+    let row_metrics: Vec<(f32, f32)> = cells
+        .iter()
+        .map(|row| {
+            row.iter().fold(
+                (GLYPH_ASCENT * size, GLYPH_DESCENT * size),
+                |(ascent, descent), cell| (ascent.max(cell.ascent), descent.max(cell.descent)),
+            )
+        })
+        .collect();
+    let total_height = row_metrics
+        .iter()
+        .map(|(ascent, descent)| ascent + descent)
+        .sum::<f32>()
+        + row_gap * row_metrics.len().saturating_sub(1) as f32;
+    let width = column_widths.iter().sum::<f32>()
+        + column_gap * column_count.saturating_sub(1) as f32
+        + 0.2 * size;
+
+    let axis = AXIS_HEIGHT * size;
+    let top = -axis - total_height / 2.;
+    let mut matrix = MathBox {
+        width,
+        ascent: -top,
+        descent: top + total_height,
+        items: Vec::new(),
+    };
+    let mut y = top;
+    for (row, (ascent, descent)) in cells.into_iter().zip(row_metrics) {
+        let baseline = y + ascent;
+        let mut x = 0.1 * size;
+        for (column, cell) in row.into_iter().enumerate() {
+            let cell_x = if align_left {
+                x
+            } else {
+                x + (column_widths[column] - cell.width) / 2.
+            };
+            matrix.append(cell, cell_x, baseline);
+            x += column_widths[column] + column_gap;
+        }
+        y += ascent + descent + row_gap;
+    }
+    matrix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_math() {
+        assert_eq!(
+            parse_math(r"x^{2}_i"),
+            MathNode::Row(vec![MathNode::Scripts {
+                base: Box::new(MathNode::Identifier("x".into())),
+                superscript: Some(Box::new(MathNode::Row(vec![MathNode::Number("2".into())]))),
+                subscript: Some(Box::new(MathNode::Identifier("i".into()))),
+            }])
+        );
+        assert_eq!(
+            parse_math(r"\left( a \right]"),
+            MathNode::Row(vec![MathNode::Delimited {
+                open: "(".into(),
+                close: "]".into(),
+                body: Box::new(MathNode::Row(vec![MathNode::Identifier("a".into())])),
+            }])
+        );
+
+        let MathNode::Row(nodes) = parse_math(r"\begin{pmatrix} a & b \\ c & d \end{pmatrix}")
+        else {
+            panic!("expected a row");
+        };
+        let [MathNode::Matrix { open, rows, .. }] = nodes.as_slice() else {
+            panic!("expected a matrix, got {nodes:?}");
+        };
+        assert_eq!(open, "(");
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row.len() == 2));
+
+        // Input nested too deeply is kept as text instead of being parsed.
+        let nested = format!("{}x{}", "{".repeat(10_000), "}".repeat(10_000));
+        let mut node = parse_math(&nested);
+        let mut depth = 0;
+        while let MathNode::Row(mut nodes) = node {
+            assert_eq!(nodes.len(), 1);
+            node = nodes.pop().unwrap();
+            depth += 1;
+        }
+        assert_eq!(depth, MAX_NESTING_DEPTH + 1);
+        assert!(matches!(node, MathNode::Text(text) if text.contains('x')));
+        parse_math(&r"\frac".repeat(10_000));
+    }
+
+    #[test]
+    fn test_math_to_unicode() {
+        assert_eq!(
+            parse_math(r"x^2 + y_{1} = \alpha").to_unicode(),
+            "x² + y₁ = α"
+        );
+        assert_eq!(parse_math(r"\frac{a+b}{2}").to_unicode(), "(a + b)/2");
+        assert_eq!(
+            parse_math(r"\sqrt[3]{x} \leq \infty").to_unicode(),
+            "∛x ≤ ∞"
+        );
+        assert_eq!(parse_math(r"\mathbb{R}^n").to_unicode(), "ℝⁿ");
+    }
+
+    #[test]
+    fn test_layout_math() {
+        let symbol = layout_math(&parse_math("x"), 20.);
+        let fraction = layout_math(&parse_math(r"\frac{1}{x}"), 20.);
+        assert!(fraction.height() > symbol.height() * 1.5);
+        assert!(fraction.ascent > symbol.ascent);
+
+        let scripted = layout_math(&parse_math("x^2"), 20.);
+        assert!(scripted.width > symbol.width);
+        assert!(scripted.ascent > symbol.ascent);
+
+        let matrix = layout_math(&parse_math(r"\begin{bmatrix}1&0\\0&1\end{bmatrix}"), 20.);
+        assert!(matrix.height() > symbol.height() * 2.);
+
+        let svg = fraction.to_svg("#000000");
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("<line"));
+        assert!(svg.contains(">x</text>"));
+    }
+}
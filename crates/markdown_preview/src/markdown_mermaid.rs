@@ -0,0 +1,1747 @@
+//! Parsing and layout of mermaid diagrams for the Markdown preview.
+//!
+//! Flowcharts, sequence diagrams and class diagrams are supported. Diagrams
+//! are laid out here and emitted as SVG, which the renderer draws like any
+//! other image. Statements that aren't understood are skipped so that a
+//! partially supported diagram still renders.
+
+use std::fmt::Write as _;
+
+use crate::markdown_math::{escape_xml, text_width};
+
+const FONT_SIZE: f32 = 14.;
+const LINE_HEIGHT: f32 = 18.;
+const FONT_FAMILIES: &str =
+    "'Helvetica Neue', Helvetica, Arial, 'Liberation Sans', 'DejaVu Sans', sans-serif";
+
+/// Colors used when drawing a diagram, as SVG color strings.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MermaidPalette {
+    pub text: String,
+    pub line: String,
+    pub node_fill: String,
+    pub node_stroke: String,
+    pub note_fill: String,
+    pub background: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MermaidDiagram {
+    Flowchart(Flowchart),
+    Sequence(SequenceDiagram),
+    Class(ClassDiagram),
+}
+
+/// Parses a mermaid diagram, returning `None` for unsupported diagram types.
+pub fn parse_mermaid(source: &str) -> Option<MermaidDiagram> {
+    let mut lines = source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("%%"));
+    let header = lines.next()?;
+    let mut header_words = header.split_whitespace();
+    let diagram = match header_words.next()? {
+        "graph" | "flowchart" => MermaidDiagram::Flowchart(Flowchart::parse(
+            FlowDirection::parse(header_words.next().unwrap_or("TD")),
+            lines,
+        )),
+        "sequenceDiagram" => MermaidDiagram::Sequence(SequenceDiagram::parse(lines)),
+        "classDiagram" | "classDiagram-v2" => MermaidDiagram::Class(ClassDiagram::parse(lines)),
+        _ => return None,
+    };
+    Some(diagram)
+}
+
+impl MermaidDiagram {
+    pub fn to_svg(&self, palette: &MermaidPalette) -> String {
+        let mut canvas = Canvas::default();
+        match self {
+            MermaidDiagram::Flowchart(flowchart) => flowchart.draw(&mut canvas, palette),
+            MermaidDiagram::Sequence(sequence) => sequence.draw(&mut canvas, palette),
+            MermaidDiagram::Class(class) => class.draw(&mut canvas, palette),
+        }
+        canvas.finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlowDirection {
+    TopDown,
+    BottomUp,
+    LeftRight,
+    RightLeft,
+}
+
+impl FlowDirection {
+    fn parse(text: &str) -> Self {
+        match text {
+            "LR" => Self::LeftRight,
+            "RL" => Self::RightLeft,
+            "BT" => Self::BottomUp,
+            _ => Self::TopDown,
+        }
+    }
+
+    fn is_horizontal(self) -> bool {
+        matches!(self, Self::LeftRight | Self::RightLeft)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NodeShape {
+    Rectangle,
+    Rounded,
+    Stadium,
+    Circle,
+    Diamond,
+    Hexagon,
+    Cylinder,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowNode {
+    pub id: String,
+    pub label: String,
+    pub shape: NodeShape,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EdgeStyle {
+    Solid,
+    Dotted,
+    Thick,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowEdge {
+    pub from: usize,
+    pub to: usize,
+    pub label: Option<String>,
+    pub style: EdgeStyle,
+    pub arrow_start: bool,
+    pub arrow_end: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flowchart {
+    pub direction: FlowDirection,
+    pub nodes: Vec<FlowNode>,
+    pub edges: Vec<FlowEdge>,
+}
+
+struct EdgeToken {
+    label: Option<String>,
+    style: EdgeStyle,
+    arrow_start: bool,
+    arrow_end: bool,
+}
+
+/// A cursor over a single flowchart statement.
+struct StatementCursor {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl StatementCursor {
+    fn new(text: &str) -> Self {
+        Self {
+            chars: text.chars().collect(),
+            position: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.position + offset).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn starts_with(&self, prefix: &str) -> bool {
+        prefix
+            .chars()
+            .enumerate()
+            .all(|(offset, character)| self.peek_at(offset) == Some(character))
+    }
+
+    fn rest(&self) -> String {
+        self.chars[self.position..].iter().collect()
+    }
+
+    fn read_until(&mut self, closing: &str) -> String {
+        let mut text = String::new();
+        while self.peek().is_some() && !self.starts_with(closing) {
+            text.push(self.chars[self.position]);
+            self.position += 1;
+        }
+        self.position = (self.position + closing.chars().count()).min(self.chars.len());
+        text
+    }
+}
+
+impl Flowchart {
+    fn parse<'a>(direction: FlowDirection, lines: impl Iterator<Item = &'a str>) -> Self {
+        let mut flowchart = Flowchart {
+            direction,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+        for line in lines {
+            for statement in line.split(';').map(str::trim) {
+                let keyword = statement.split_whitespace().next().unwrap_or_default();
+                if statement.is_empty()
+                    || matches!(
+                        keyword,
+                        "classDef"
+                            | "class"
+                            | "style"
+                            | "linkStyle"
+                            | "click"
+                            | "subgraph"
+                            | "end"
+                            | "direction"
+                    )
+                {
+                    continue;
+                }
+                flowchart.parse_statement(statement);
+            }
+        }
+        flowchart
+    }
+
+    fn parse_statement(&mut self, statement: &str) {
+        let mut cursor = StatementCursor::new(statement);
+        let mut sources = self.parse_node_group(&mut cursor);
+        while !sources.is_empty() {
+            let Some(edge) = Self::parse_edge(&mut cursor) else {
+                break;
+            };
+            let targets = self.parse_node_group(&mut cursor);
+            for &from in &sources {
+                for &to in &targets {
+                    self.edges.push(FlowEdge {
+                        from,
+                        to,
+                        label: edge.label.clone(),
+                        style: edge.style,
+                        arrow_start: edge.arrow_start,
+                        arrow_end: edge.arrow_end,
+                    });
+                }
+            }
+            sources = targets;
+        }
+    }
+
+    fn parse_node_group(&mut self, cursor: &mut StatementCursor) -> Vec<usize> {
+        let mut nodes = Vec::new();
+        loop {
+            cursor.skip_whitespace();
+            let Some(node) = self.parse_node(cursor) else {
+                break;
+            };
+            nodes.push(node);
+            cursor.skip_whitespace();
+            if cursor.peek() == Some('&') {
+                cursor.position += 1;
+            } else {
+                break;
+            }
+        }
+        nodes
+    }
+
+    fn parse_node(&mut self, cursor: &mut StatementCursor) -> Option<usize> {
+        let mut id = String::new();
+        while let Some(character) = cursor
+            .peek()
+            .filter(|character| character.is_alphanumeric() || *character == '_')
+        {
+            id.push(character);
+            cursor.position += 1;
+        }
+        if id.is_empty() {
+            return None;
+        }
+
+        const SHAPES: &[(&str, &str, NodeShape)] = &[
+            ("(((", ")))", NodeShape::Circle),
+            ("((", "))", NodeShape::Circle),
+            ("([", "])", NodeShape::Stadium),
+            ("[(", ")]", NodeShape::Cylinder),
+            ("[[", "]]", NodeShape::Rectangle),
+            ("{{", "}}", NodeShape::Hexagon),
+            ("[/", "/]", NodeShape::Rectangle),
+            ("[\\", "\\]", NodeShape::Rectangle),
+            ("[/", "\\]", NodeShape::Rectangle),
+            ("[\\", "/]", NodeShape::Rectangle),
+            ("(", ")", NodeShape::Rounded),
+            ("[", "]", NodeShape::Rectangle),
+            ("{", "}", NodeShape::Diamond),
+            (">", "]", NodeShape::Rectangle),
+        ];
+        let shape = SHAPES.iter().find(|(open, _, _)| cursor.starts_with(open));
+        let label = shape.map(|(open, close, shape)| {
+            cursor.position += open.chars().count();
+            (clean_label(&cursor.read_until(close)), *shape)
+        });
+
+        let index = match self.nodes.iter().position(|node| node.id == id) {
+            Some(index) => index,
+            None => {
+                self.nodes.push(FlowNode {
+                    label: id.clone(),
+                    id,
+                    shape: NodeShape::Rectangle,
+                });
+                self.nodes.len() - 1
+            }
+        };
+        if let Some((label, shape)) = label {
+            self.nodes[index].label = label;
+            self.nodes[index].shape = shape;
+        }
+        Some(index)
+    }
+
+    fn parse_edge(cursor: &mut StatementCursor) -> Option<EdgeToken> {
+        cursor.skip_whitespace();
+        let is_edge_character = |character: char| matches!(character, '-' | '=' | '.' | '<' | '>');
+
+        let mut arrow_start = false;
+        if matches!(cursor.peek(), Some('x' | 'o'))
+            && cursor
+                .peek_at(1)
+                .is_some_and(|next| matches!(next, '-' | '='))
+        {
+            arrow_start = true;
+            cursor.position += 1;
+        }
+
+        let mut run = String::new();
+        while let Some(character) = cursor
+            .peek()
+            .filter(|character| is_edge_character(*character))
+        {
+            run.push(character);
+            cursor.position += 1;
+        }
+        if run.len() < 2 {
+            return None;
+        }
+        arrow_start |= run.starts_with('<');
+
+        let mut arrow_end = run.ends_with('>');
+        if !arrow_end
+            && matches!(cursor.peek(), Some('x' | 'o'))
+            && cursor.peek_at(1).is_none_or(char::is_whitespace)
+        {
+            arrow_end = true;
+            cursor.position += 1;
+        }
+
+        let mut label = None;
+        if !arrow_end && matches!(run.as_str(), "--" | "==" | "-." | "<--" | "<==" | "<-.") {
+            let rest = cursor.rest();
+            const CLOSINGS: &[&str] = &["-->", "---", "==>", "===", ".->", ".-", "--x", "--o"];
+            if let Some((offset, closing)) = CLOSINGS
+                .iter()
+                .filter_map(|closing| rest.find(closing).map(|offset| (offset, *closing)))
+                .min_by_key(|(offset, _)| *offset)
+            {
+                label = Some(clean_label(&rest[..offset]));
+                cursor.position += rest[..offset].chars().count() + closing.chars().count();
+                while cursor.peek().is_some_and(is_edge_character) {
+                    cursor.position += 1;
+                }
+                arrow_end = closing.ends_with(['>', 'x', 'o']);
+            }
+        }
+
+        cursor.skip_whitespace();
+        if cursor.peek() == Some('|') {
+            cursor.position += 1;
+            label = Some(clean_label(&cursor.read_until("|")));
+        }
+
+        let style = if run.contains('=') {
+            EdgeStyle::Thick
+        } else if run.contains('.') {
+            EdgeStyle::Dotted
+        } else {
+            EdgeStyle::Solid
+        };
+        Some(EdgeToken {
+            label: label.filter(|label| !label.is_empty()),
+            style,
+            arrow_start,
+            arrow_end,
+        })
+    }
+
+    fn node_size(node: &FlowNode) -> (f32, f32) {
+        let (text_width, text_height) = text_block_size(&node.label);
+        let width = (text_width + 32.).max(60.);
+        let height = (text_height + 20.).max(40.);
+        match node.shape {
+            NodeShape::Diamond => (width * 1.3 + 10., height * 1.4 + 10.),
+            NodeShape::Circle => {
+                let diameter = width.max(height);
+                (diameter, diameter)
+            }
+            NodeShape::Hexagon => (width + 20., height),
+            NodeShape::Cylinder => (width, height + 10.),
+            _ => (width, height),
+        }
+    }
+
+    fn draw(&self, canvas: &mut Canvas, palette: &MermaidPalette) {
+        let sizes: Vec<(f32, f32)> = self.nodes.iter().map(Self::node_size).collect();
+        let links: Vec<(usize, usize)> =
+            self.edges.iter().map(|edge| (edge.from, edge.to)).collect();
+        let centers = place_layers(&sizes, &links, self.direction, 50., 30.);
+
+        for edge in &self.edges {
+            let from = (
+                centers[edge.from],
+                sizes[edge.from],
+                self.nodes[edge.from].shape,
+            );
+            let to = (centers[edge.to], sizes[edge.to], self.nodes[edge.to].shape);
+            let (stroke_width, dash) = match edge.style {
+                EdgeStyle::Solid => (1.5, None),
+                EdgeStyle::Dotted => (1.5, Some("4 3")),
+                EdgeStyle::Thick => (3., None),
+            };
+            let midpoint = if edge.from == edge.to {
+                canvas.self_loop(from.0, from.1, &palette.line, stroke_width)
+            } else {
+                let start = clip_to_shape(from.0, from.1, from.2, to.0);
+                let end = clip_to_shape(to.0, to.1, to.2, from.0);
+                canvas.line(start, end, &palette.line, stroke_width, dash);
+                if edge.arrow_end {
+                    canvas.arrow_head(start, end, &palette.line, true);
+                }
+                if edge.arrow_start {
+                    canvas.arrow_head(end, start, &palette.line, true);
+                }
+                ((start.0 + end.0) / 2., (start.1 + end.1) / 2.)
+            };
+            if let Some(label) = &edge.label {
+                canvas.label_with_background(midpoint, label, palette);
+            }
+        }
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let (x, y) = centers[index];
+            let (width, height) = sizes[index];
+            canvas.shape(node.shape, (x, y), (width, height), palette);
+            canvas.text_block((x, y), &node.label, &palette.text, false);
+        }
+    }
+}
+
+fn clean_label(text: &str) -> String {
+    let text = text.trim();
+    let text = text
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+        .unwrap_or(text);
+    text.replace("<br/>", "\n")
+        .replace("<br />", "\n")
+        .replace("<br>", "\n")
+        .replace("&quot;", "\"")
+        .replace("#quot;", "\"")
+}
+
+fn text_block_size(text: &str) -> (f32, f32) {
+    let lines: Vec<&str> = text.lines().collect();
+    let width = lines
+        .iter()
+        .map(|line| text_width(line, FONT_SIZE, false))
+        .fold(0., f32::max);
+    (width, lines.len().max(1) as f32 * LINE_HEIGHT)
+}
+
+/// Assigns nodes to layers with a longest-path ranking and orders each layer
+/// by the average position of its predecessors, returning node centers.
+fn layer_nodes(node_count: usize, links: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let mut forward_links = Vec::new();
+    let mut state = vec![0u8; node_count];
+    let mut adjacency = vec![Vec::new(); node_count];
+    for &(from, to) in links {
+        if from != to {
+            adjacency[from].push(to);
+        }
+    }
+    fn visit(
+        node: usize,
+        adjacency: &[Vec<usize>],
+        state: &mut [u8],
+        forward_links: &mut Vec<(usize, usize)>,
+    ) {
+        state[node] = 1;
+        for &next in &adjacency[node] {
+            match state[next] {
+                0 => {
+                    forward_links.push((node, next));
+                    visit(next, adjacency, state, forward_links);
+                }
+                2 => forward_links.push((node, next)),
+                _ => {}
+            }
+        }
+        state[node] = 2;
+    }
+    for node in 0..node_count {
+        if state[node] == 0 {
+            visit(node, &adjacency, &mut state, &mut forward_links);
+        }
+    }
+
+    let mut ranks = vec![0usize; node_count];
+    for _ in 0..node_count {
+        let mut changed = false;
+        for &(from, to) in &forward_links {
+            if ranks[to] < ranks[from] + 1 {
+                ranks[to] = ranks[from] + 1;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let layer_count = ranks.iter().max().map_or(0, |rank| rank + 1);
+    let mut layers = vec![Vec::new(); layer_count];
+    for node in 0..node_count {
+        layers[ranks[node]].push(node);
+    }
+
+    for layer_index in 1..layers.len() {
+        let previous = &layers[layer_index - 1];
+        let position_in_previous = |node: usize| previous.iter().position(|other| *other == node);
+        let mut keyed: Vec<(f32, usize)> = layers[layer_index]
+            .iter()
+            .enumerate()
+            .map(|(index, &node)| {
+                let positions: Vec<usize> = forward_links
+                    .iter()
+                    .filter(|(_, to)| *to == node)
+                    .filter_map(|(from, _)| position_in_previous(*from))
+                    .collect();
+                let key = if positions.is_empty() {
+                    index as f32
+                } else {
+                    positions.iter().sum::<usize>() as f32 / positions.len() as f32
+                };
+                (key, node)
+            })
+            .collect();
+        keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+        layers[layer_index] = keyed.into_iter().map(|(_, node)| node).collect();
+    }
+    layers
+}
+
+fn place_layers(
+    sizes: &[(f32, f32)],
+    links: &[(usize, usize)],
+    direction: FlowDirection,
+    layer_gap: f32,
+    node_gap: f32,
+) -> Vec<(f32, f32)> {
+    let layers = layer_nodes(sizes.len(), links);
+    let horizontal = direction.is_horizontal();
+    let along = |(width, height): (f32, f32)| if horizontal { width } else { height };
+    let across = |(width, height): (f32, f32)| if horizontal { height } else { width };
+
+    let layer_extents: Vec<f32> = layers
+        .iter()
+        .map(|layer| {
+            layer
+                .iter()
+                .map(|node| along(sizes[*node]))
+                .fold(0., f32::max)
+        })
+        .collect();
+    let layer_lengths: Vec<f32> = layers
+        .iter()
+        .map(|layer| {
+            layer.iter().map(|node| across(sizes[*node])).sum::<f32>()
+                + node_gap * layer.len().saturating_sub(1) as f32
+        })
+        .collect();
+    let widest = layer_lengths.iter().copied().fold(0., f32::max);
+    let total_depth = layer_extents.iter().sum::<f32>()
+        + layer_gap * layer_extents.len().saturating_sub(1) as f32;
+
+    let mut centers = vec![(0., 0.); sizes.len()];
+    let mut depth = 0.;
+    for (layer_index, layer) in layers.iter().enumerate() {
+        let layer_center = depth + layer_extents[layer_index] / 2.;
+        let mut offset = (widest - layer_lengths[layer_index]) / 2.;
+        for &node in layer {
+            let node_center = offset + across(sizes[node]) / 2.;
+            let depth_center = match direction {
+                FlowDirection::BottomUp | FlowDirection::RightLeft => total_depth - layer_center,
+                _ => layer_center,
+            };
+            centers[node] = if horizontal {
+                (depth_center, node_center)
+            } else {
+                (node_center, depth_center)
+            };
+            offset += across(sizes[node]) + node_gap;
+        }
+        depth += layer_extents[layer_index] + layer_gap;
+    }
+    centers
+}
+
+/// Returns the point where the segment from `center` towards `target` leaves
+/// the node's outline.
+fn clip_to_shape(
+    center: (f32, f32),
+    size: (f32, f32),
+    shape: NodeShape,
+    target: (f32, f32),
+) -> (f32, f32) {
+    let dx = target.0 - center.0;
+    let dy = target.1 - center.1;
+    if dx == 0. && dy == 0. {
+        return center;
+    }
+    let half_width = size.0 / 2.;
+    let half_height = size.1 / 2.;
+    let scale = match shape {
+        NodeShape::Diamond => 1. / (dx.abs() / half_width + dy.abs() / half_height),
+        NodeShape::Circle => half_width / (dx * dx + dy * dy).sqrt(),
+        _ => (half_width / dx.abs()).min(half_height / dy.abs()),
+    };
+    (center.0 + dx * scale, center.1 + dy * scale)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Participant {
+    pub id: String,
+    pub label: String,
+    pub is_actor: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MessageArrow {
+    Filled,
+    Open,
+    Cross,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotePlacement {
+    LeftOf,
+    RightOf,
+    Over,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SequenceEvent {
+    Message {
+        from: usize,
+        to: usize,
+        text: String,
+        dashed: bool,
+        arrow: MessageArrow,
+    },
+    Note {
+        first: usize,
+        last: usize,
+        placement: NotePlacement,
+        text: String,
+    },
+    BlockStart {
+        label: String,
+    },
+    BlockDivider {
+        label: String,
+    },
+    BlockEnd,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequenceDiagram {
+    pub participants: Vec<Participant>,
+    pub events: Vec<SequenceEvent>,
+}
+
+impl SequenceDiagram {
+    fn parse<'a>(lines: impl Iterator<Item = &'a str>) -> Self {
+        let mut diagram = SequenceDiagram {
+            participants: Vec::new(),
+            events: Vec::new(),
+        };
+        for line in lines {
+            let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let rest = rest.trim();
+            match keyword {
+                "participant" | "actor" => {
+                    let (id, label) = match rest.split_once(" as ") {
+                        Some((id, label)) => (id.trim(), label.trim()),
+                        None => (rest, rest),
+                    };
+                    let index = diagram.participant(id);
+                    diagram.participants[index].label = label.to_string();
+                    diagram.participants[index].is_actor = keyword == "actor";
+                }
+                "loop" | "alt" | "opt" | "par" | "critical" | "break" | "rect" => {
+                    diagram.events.push(SequenceEvent::BlockStart {
+                        label: if keyword == "rect" {
+                            String::new()
+                        } else {
+                            format!("{keyword} {rest}").trim().to_string()
+                        },
+                    });
+                }
+                "else" | "and" | "option" => diagram.events.push(SequenceEvent::BlockDivider {
+                    label: rest.to_string(),
+                }),
+                "end" => diagram.events.push(SequenceEvent::BlockEnd),
+                "autonumber" | "activate" | "deactivate" | "title" | "box" | "create"
+                | "destroy" => {}
+                _ if keyword.eq_ignore_ascii_case("note") => diagram.parse_note(rest),
+                _ => diagram.parse_message(line),
+            }
+        }
+        diagram
+    }
+
+    fn participant(&mut self, id: &str) -> usize {
+        if let Some(index) = self
+            .participants
+            .iter()
+            .position(|participant| participant.id == id)
+        {
+            return index;
+        }
+        self.participants.push(Participant {
+            id: id.to_string(),
+            label: id.to_string(),
+            is_actor: false,
+        });
+        self.participants.len() - 1
+    }
+
+    fn parse_note(&mut self, rest: &str) {
+        let Some((target, text)) = rest.split_once(':') else {
+            return;
+        };
+        let (placement, participants) = if let Some(target) = target.strip_prefix("left of") {
+            (NotePlacement::LeftOf, target)
+        } else if let Some(target) = target.strip_prefix("right of") {
+            (NotePlacement::RightOf, target)
+        } else if let Some(target) = target.strip_prefix("over") {
+            (NotePlacement::Over, target)
+        } else {
+            return;
+        };
+        let mut indices = participants
+            .split(',')
+            .map(|id| self.participant(id.trim()))
+            .collect::<Vec<_>>();
+        indices.sort_unstable();
+        let (Some(&first), Some(&last)) = (indices.first(), indices.last()) else {
+            return;
+        };
+        self.events.push(SequenceEvent::Note {
+            first,
+            last,
+            placement,
+            text: clean_label(text),
+        });
+    }
+
+    fn parse_message(&mut self, line: &str) {
+        const ARROWS: &[(&str, bool, MessageArrow)] = &[
+            ("-->>", true, MessageArrow::Filled),
+            ("->>", false, MessageArrow::Filled),
+            ("--x", true, MessageArrow::Cross),
+            ("-x", false, MessageArrow::Cross),
+            ("--)", true, MessageArrow::Open),
+            ("-)", false, MessageArrow::Open),
+            ("-->", true, MessageArrow::None),
+            ("->", false, MessageArrow::None),
+        ];
+        let (statement, text) = line.split_once(':').unwrap_or((line, ""));
+        let Some((offset, (arrow_text, dashed, arrow))) = ARROWS
+            .iter()
+            .filter_map(|arrow| statement.find(arrow.0).map(|offset| (offset, arrow)))
+            .min_by_key(|(offset, arrow)| (*offset, usize::MAX - arrow.0.len()))
+        else {
+            return;
+        };
+        let from = statement[..offset].trim();
+        let to = statement[offset + arrow_text.len()..]
+            .trim()
+            .trim_start_matches(['+', '-'])
+            .trim();
+        if from.is_empty() || to.is_empty() {
+            return;
+        }
+        let from = self.participant(from);
+        let to = self.participant(to);
+        self.events.push(SequenceEvent::Message {
+            from,
+            to,
+            text: clean_label(text),
+            dashed: *dashed,
+            arrow: *arrow,
+        });
+    }
+
+    fn draw(&self, canvas: &mut Canvas, palette: &MermaidPalette) {
+        const BOX_HEIGHT: f32 = 40.;
+        const MESSAGE_HEIGHT: f32 = 40.;
+
+        let box_widths: Vec<f32> = self
+            .participants
+            .iter()
+            .map(|participant| (text_block_size(&participant.label).0 + 24.).max(100.))
+            .collect();
+        let mut gaps: Vec<f32> = box_widths
+            .windows(2)
+            .map(|pair| (pair[0] + pair[1]) / 2. + 40.)
+            .collect();
+        for event in &self.events {
+            if let SequenceEvent::Message { from, to, text, .. } = event
+                && from.abs_diff(*to) == 1
+            {
+                let gap = &mut gaps[(*from).min(*to)];
+                *gap = gap.max(text_block_size(text).0 + 40.);
+            }
+        }
+        let mut centers = Vec::with_capacity(self.participants.len());
+        let mut x = box_widths.first().map_or(0., |width| width / 2.);
+        for index in 0..self.participants.len() {
+            centers.push(x);
+            x += gaps.get(index).copied().unwrap_or(0.);
+        }
+        let left = 0.;
+        let right = centers.last().copied().unwrap_or(0.)
+            + box_widths.last().map_or(0., |width| width / 2.);
+
+        let mut y = BOX_HEIGHT + 20.;
+        let mut blocks: Vec<f32> = Vec::new();
+        for event in &self.events {
+            match event {
+                SequenceEvent::Message {
+                    from,
+                    to,
+                    text,
+                    dashed,
+                    arrow,
+                } => {
+                    let text_height = text_block_size(text).1;
+                    y += text_height;
+                    let dash = dashed.then_some("4 3");
+                    if from == to {
+                        let x = centers[*from];
+                        canvas.path(
+                            &format!(
+                                "M {x:.1} {y:.1} C {:.1} {y:.1} {:.1} {:.1} {x:.1} {:.1}",
+                                x + 50.,
+                                x + 50.,
+                                y + 24.,
+                                y + 24.
+                            ),
+                            &palette.line,
+                            1.5,
+                            dash,
+                        );
+                        canvas.text_block(
+                            (x + 40., y - text_height / 2.),
+                            text,
+                            &palette.text,
+                            false,
+                        );
+                        canvas.arrow_head((x + 10., y + 24.), (x, y + 24.), &palette.line, true);
+                        y += MESSAGE_HEIGHT;
+                    } else {
+                        let start = (centers[*from], y);
+                        let end = (centers[*to], y);
+                        canvas.text_block(
+                            ((start.0 + end.0) / 2., y - 4. - text_height / 2.),
+                            text,
+                            &palette.text,
+                            false,
+                        );
+                        canvas.line(start, end, &palette.line, 1.5, dash);
+                        match arrow {
+                            MessageArrow::Filled => {
+                                canvas.arrow_head(start, end, &palette.line, true)
+                            }
+                            MessageArrow::Open => {
+                                canvas.arrow_head(start, end, &palette.line, false)
+                            }
+                            MessageArrow::Cross => canvas.cross(end, &palette.line),
+                            MessageArrow::None => {}
+                        }
+                        y += MESSAGE_HEIGHT / 2.;
+                    }
+                }
+                SequenceEvent::Note {
+                    first,
+                    last,
+                    placement,
+                    text,
+                } => {
+                    let (text_width, text_height) = text_block_size(text);
+                    let width = text_width + 20.;
+                    let height = text_height + 12.;
+                    let center_x = match placement {
+                        NotePlacement::LeftOf => centers[*first] - width / 2. - 10.,
+                        NotePlacement::RightOf => centers[*last] + width / 2. + 10.,
+                        NotePlacement::Over => (centers[*first] + centers[*last]) / 2.,
+                    };
+                    let width = if *placement == NotePlacement::Over && first != last {
+                        width.max(centers[*last] - centers[*first] + 40.)
+                    } else {
+                        width
+                    };
+                    canvas.rect(
+                        (center_x - width / 2., y),
+                        (width, height),
+                        &palette.note_fill,
+                        &palette.node_stroke,
+                        0.,
+                    );
+                    canvas.text_block((center_x, y + height / 2.), text, &palette.text, false);
+                    y += height + 16.;
+                }
+                SequenceEvent::BlockStart { label } => {
+                    blocks.push(y);
+                    if !label.is_empty() {
+                        canvas.text((left + 8., y + 14.), label, &palette.text, "start", true);
+                    }
+                    y += 28.;
+                }
+                SequenceEvent::BlockDivider { label } => {
+                    let inset = blocks.len() as f32 * 6.;
+                    canvas.line(
+                        (left + inset - 10., y),
+                        (right - inset + 10., y),
+                        &palette.node_stroke,
+                        1.,
+                        Some("4 3"),
+                    );
+                    if !label.is_empty() {
+                        canvas.text(
+                            (left + 8., y + 14.),
+                            &format!("[{label}]"),
+                            &palette.text,
+                            "start",
+                            false,
+                        );
+                    }
+                    y += 28.;
+                }
+                SequenceEvent::BlockEnd => {
+                    if let Some(start) = blocks.pop() {
+                        let inset = blocks.len() as f32 * 6.;
+                        canvas.rect(
+                            (left + inset - 10., start),
+                            (right - left - inset * 2. + 20., y - start),
+                            "none",
+                            &palette.node_stroke,
+                            0.,
+                        );
+                        y += 12.;
+                    }
+                }
+            }
+        }
+        y += 10.;
+
+        for (index, participant) in self.participants.iter().enumerate() {
+            let x = centers[index];
+            canvas.line(
+                (x, BOX_HEIGHT),
+                (x, y),
+                &palette.node_stroke,
+                1.,
+                Some("3 3"),
+            );
+            for top in [0., y] {
+                canvas.rect(
+                    (x - box_widths[index] / 2., top),
+                    (box_widths[index], BOX_HEIGHT),
+                    &palette.node_fill,
+                    &palette.node_stroke,
+                    if participant.is_actor { 20. } else { 3. },
+                );
+                canvas.text_block(
+                    (x, top + BOX_HEIGHT / 2.),
+                    &participant.label,
+                    &palette.text,
+                    false,
+                );
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RelationMarker {
+    None,
+    Arrow,
+    Triangle,
+    FilledDiamond,
+    HollowDiamond,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassRelation {
+    pub from: usize,
+    pub to: usize,
+    pub from_marker: RelationMarker,
+    pub to_marker: RelationMarker,
+    pub dashed: bool,
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassBox {
+    pub name: String,
+    pub annotation: Option<String>,
+    pub attributes: Vec<String>,
+    pub methods: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassDiagram {
+    pub classes: Vec<ClassBox>,
+    pub relations: Vec<ClassRelation>,
+}
+
+impl ClassDiagram {
+    fn parse<'a>(lines: impl Iterator<Item = &'a str>) -> Self {
+        let mut diagram = ClassDiagram {
+            classes: Vec::new(),
+            relations: Vec::new(),
+        };
+        let mut open_class: Option<usize> = None;
+        for line in lines {
+            if let Some(class) = open_class {
+                if line.starts_with('}') {
+                    open_class = None;
+                } else if let Some(annotation) = parse_annotation(line) {
+                    diagram.classes[class].annotation = Some(annotation);
+                } else {
+                    diagram.add_member(class, line);
+                }
+                continue;
+            }
+
+            let keyword = line.split_whitespace().next().unwrap_or_default();
+            if matches!(
+                keyword,
+                "direction" | "note" | "link" | "click" | "cssClass" | "style"
+            ) {
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("class ") {
+                let rest = rest.trim();
+                let (name, opens_block) = match rest.strip_suffix('{') {
+                    Some(name) => (name.trim(), true),
+                    None => (rest, false),
+                };
+                let class = diagram.class(name);
+                if opens_block {
+                    open_class = Some(class);
+                }
+            } else if let Some(annotation) = parse_annotation(line) {
+                let name = line.rsplit(">>").next().unwrap_or_default().trim();
+                if !name.is_empty() {
+                    let class = diagram.class(name);
+                    diagram.classes[class].annotation = Some(annotation);
+                }
+            } else if !diagram.parse_relation(line)
+                && let Some((name, member)) = line.split_once(':')
+            {
+                let class = diagram.class(name.trim());
+                diagram.add_member(class, member.trim());
+            }
+        }
+        diagram
+    }
+
+    fn class(&mut self, name: &str) -> usize {
+        let name = expand_generics(name);
+        if let Some(index) = self.classes.iter().position(|class| class.name == name) {
+            return index;
+        }
+        self.classes.push(ClassBox {
+            name,
+            annotation: None,
+            attributes: Vec::new(),
+            methods: Vec::new(),
+        });
+        self.classes.len() - 1
+    }
+
+    fn add_member(&mut self, class: usize, member: &str) {
+        let member = expand_generics(member.trim());
+        if member.is_empty() {
+            return;
+        }
+        if member.contains('(') {
+            self.classes[class].methods.push(member);
+        } else {
+            self.classes[class].attributes.push(member);
+        }
+    }
+
+    fn parse_relation(&mut self, line: &str) -> bool {
+        use RelationMarker::*;
+        const RELATIONS: &[(&str, RelationMarker, RelationMarker, bool)] = &[
+            ("<|--", Triangle, None, false),
+            ("--|>", None, Triangle, false),
+            ("<|..", Triangle, None, true),
+            ("..|>", None, Triangle, true),
+            ("*--", FilledDiamond, None, false),
+            ("--*", None, FilledDiamond, false),
+            ("o--", HollowDiamond, None, false),
+            ("--o", None, HollowDiamond, false),
+            ("<--", Arrow, None, false),
+            ("-->", None, Arrow, false),
+            ("<..", Arrow, None, true),
+            ("..>", None, Arrow, true),
+            ("--", None, None, false),
+            ("..", None, None, true),
+        ];
+        let (statement, label) = match line.split_once(':') {
+            Some((statement, label)) => (statement, Some(label.trim().to_string())),
+            Option::None => (line, Option::None),
+        };
+        let Some((offset, (operator, from_marker, to_marker, dashed))) = RELATIONS
+            .iter()
+            .filter_map(|relation| statement.find(relation.0).map(|offset| (offset, relation)))
+            .min_by_key(|(offset, relation)| (*offset, usize::MAX - relation.0.len()))
+        else {
+            return false;
+        };
+        let strip_cardinality = |text: &str| -> String {
+            text.split('"')
+                .enumerate()
+                .filter(|(index, _)| index % 2 == 0)
+                .map(|(_, part)| part)
+                .collect::<String>()
+                .trim()
+                .to_string()
+        };
+        let from = strip_cardinality(&statement[..offset]);
+        let to = strip_cardinality(&statement[offset + operator.len()..]);
+        if from.is_empty() || to.is_empty() {
+            return false;
+        }
+        let from = self.class(&from);
+        let to = self.class(&to);
+        self.relations.push(ClassRelation {
+            from,
+            to,
+            from_marker: *from_marker,
+            to_marker: *to_marker,
+            dashed: *dashed,
+            label: label.filter(|label| !label.is_empty()),
+        });
+        true
+    }
+
+    fn class_size(class: &ClassBox) -> (f32, f32) {
+        let title_lines = 1 + class.annotation.is_some() as usize;
+        let width = class
+            .annotation
+            .iter()
+            .chain(std::iter::once(&class.name))
+            .chain(&class.attributes)
+            .chain(&class.methods)
+            .map(|line| text_width(line, FONT_SIZE, false))
+            .fold(0., f32::max)
+            + 24.;
+        let height = title_lines as f32 * LINE_HEIGHT
+            + 12.
+            + class.attributes.len().max(1) as f32 * LINE_HEIGHT
+            + 8.
+            + class.methods.len().max(1) as f32 * LINE_HEIGHT
+            + 8.;
+        (width.max(100.), height)
+    }
+
+    fn draw(&self, canvas: &mut Canvas, palette: &MermaidPalette) {
+        let sizes: Vec<(f32, f32)> = self.classes.iter().map(Self::class_size).collect();
+        let links: Vec<(usize, usize)> = self
+            .relations
+            .iter()
+            .map(|relation| {
+                let from_is_parent = matches!(
+                    relation.from_marker,
+                    RelationMarker::Triangle
+                        | RelationMarker::FilledDiamond
+                        | RelationMarker::HollowDiamond
+                ) || (relation.to_marker == RelationMarker::None
+                    && relation.from_marker == RelationMarker::None)
+                    || relation.to_marker == RelationMarker::Arrow;
+                if from_is_parent {
+                    (relation.from, relation.to)
+                } else {
+                    (relation.to, relation.from)
+                }
+            })
+            .collect();
+        let centers = place_layers(&sizes, &links, FlowDirection::TopDown, 60., 40.);
+
+        for relation in &self.relations {
+            let from = (centers[relation.from], sizes[relation.from]);
+            let to = (centers[relation.to], sizes[relation.to]);
+            if relation.from == relation.to {
+                let midpoint = canvas.self_loop(from.0, from.1, &palette.line, 1.5);
+                if let Some(label) = &relation.label {
+                    canvas.label_with_background(midpoint, label, palette);
+                }
+                continue;
+            }
+            let start = clip_to_shape(from.0, from.1, NodeShape::Rectangle, to.0);
+            let end = clip_to_shape(to.0, to.1, NodeShape::Rectangle, from.0);
+            canvas.line(
+                start,
+                end,
+                &palette.line,
+                1.5,
+                relation.dashed.then_some("4 3"),
+            );
+            canvas.relation_marker(end, start, relation.from_marker, palette);
+            canvas.relation_marker(start, end, relation.to_marker, palette);
+            if let Some(label) = &relation.label {
+                canvas.label_with_background(
+                    ((start.0 + end.0) / 2., (start.1 + end.1) / 2.),
+                    label,
+                    palette,
+                );
+            }
+        }
+
+        for (index, class) in self.classes.iter().enumerate() {
+            let (center_x, center_y) = centers[index];
+            let (width, height) = sizes[index];
+            let left = center_x - width / 2.;
+            let top = center_y - height / 2.;
+            canvas.rect(
+                (left, top),
+                (width, height),
+                &palette.node_fill,
+                &palette.node_stroke,
+                0.,
+            );
+
+            let mut y = top + 6.;
+            if let Some(annotation) = &class.annotation {
+                canvas.text(
+                    (center_x, y + LINE_HEIGHT * 0.7),
+                    &format!("«{annotation}»"),
+                    &palette.text,
+                    "middle",
+                    false,
+                );
+                y += LINE_HEIGHT;
+            }
+            canvas.text(
+                (center_x, y + LINE_HEIGHT * 0.7),
+                &class.name,
+                &palette.text,
+                "middle",
+                true,
+            );
+            y += LINE_HEIGHT + 6.;
+            canvas.line((left, y), (left + width, y), &palette.node_stroke, 1., None);
+            y += 4.;
+            for attribute in &class.attributes {
+                canvas.text(
+                    (left + 10., y + LINE_HEIGHT * 0.7),
+                    attribute,
+                    &palette.text,
+                    "start",
+                    false,
+                );
+                y += LINE_HEIGHT;
+            }
+            if class.attributes.is_empty() {
+                y += LINE_HEIGHT;
+            }
+            y += 4.;
+            canvas.line((left, y), (left + width, y), &palette.node_stroke, 1., None);
+            y += 4.;
+            for method in &class.methods {
+                canvas.text(
+                    (left + 10., y + LINE_HEIGHT * 0.7),
+                    method,
+                    &palette.text,
+                    "start",
+                    false,
+                );
+                y += LINE_HEIGHT;
+            }
+        }
+    }
+}
+
+/// Rewrites mermaid's `List~int~` generic syntax as `List<int>`.
+fn expand_generics(text: &str) -> String {
+    let mut opening = true;
+    text.chars()
+        .map(|character| {
+            if character != '~' {
+                return character;
+            }
+            opening = !opening;
+            if opening { '>' } else { '<' }
+        })
+        .collect()
+}
+
+fn parse_annotation(line: &str) -> Option<String> {
+    let rest = line.trim().strip_prefix("<<")?;
+    let (annotation, _) = rest.split_once(">>")?;
+    Some(annotation.trim().to_string())
+}
+
+/// Accumulates SVG elements while tracking the drawn bounds.
+#[derive(Default)]
+struct Canvas {
+    body: String,
+    min: (f32, f32),
+    max: (f32, f32),
+}
+
+impl Canvas {
+    fn include(&mut self, point: (f32, f32)) {
+        self.min = (self.min.0.min(point.0), self.min.1.min(point.1));
+        self.max = (self.max.0.max(point.0), self.max.1.max(point.1));
+    }
+
+    fn line(
+        &mut self,
+        start: (f32, f32),
+        end: (f32, f32),
+        color: &str,
+        width: f32,
+        dash: Option<&str>,
+    ) {
+        self.include(start);
+        self.include(end);
+        let dash = dash
+            .map(|dash| format!(r#" stroke-dasharray="{dash}""#))
+            .unwrap_or_default();
+        let _ = write!(
+            self.body,
+            r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{color}" stroke-width="{width}"{dash}/>"#,
+            start.0, start.1, end.0, end.1
+        );
+    }
+
+    fn path(&mut self, data: &str, color: &str, width: f32, dash: Option<&str>) {
+        let dash = dash
+            .map(|dash| format!(r#" stroke-dasharray="{dash}""#))
+            .unwrap_or_default();
+        let _ = write!(
+            self.body,
+            r#"<path d="{data}" fill="none" stroke="{color}" stroke-width="{width}"{dash}/>"#
+        );
+    }
+
+    fn polygon(&mut self, points: &[(f32, f32)], fill: &str, stroke: &str) {
+        for point in points {
+            self.include(*point);
+        }
+        let points = points
+            .iter()
+            .map(|(x, y)| format!("{x:.1},{y:.1}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = write!(
+            self.body,
+            r#"<polygon points="{points}" fill="{fill}" stroke="{stroke}" stroke-width="1.5"/>"#
+        );
+    }
+
+    fn rect(
+        &mut self,
+        origin: (f32, f32),
+        size: (f32, f32),
+        fill: &str,
+        stroke: &str,
+        radius: f32,
+    ) {
+        self.include(origin);
+        self.include((origin.0 + size.0, origin.1 + size.1));
+        let _ = write!(
+            self.body,
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="{radius:.1}" fill="{fill}" stroke="{stroke}" stroke-width="1.5"/>"#,
+            origin.0, origin.1, size.0, size.1
+        );
+    }
+
+    fn text(&mut self, position: (f32, f32), text: &str, color: &str, anchor: &str, bold: bool) {
+        let width = text_width(text, FONT_SIZE, false);
+        let left = match anchor {
+            "middle" => position.0 - width / 2.,
+            "end" => position.0 - width,
+            _ => position.0,
+        };
+        self.include((left, position.1 - FONT_SIZE));
+        self.include((left + width, position.1 + 4.));
+        let weight = if bold { "bold" } else { "normal" };
+        let _ = write!(
+            self.body,
+            r#"<text x="{:.1}" y="{:.1}" font-family="{FONT_FAMILIES}" font-size="{FONT_SIZE}" font-weight="{weight}" text-anchor="{anchor}" fill="{color}">{}</text>"#,
+            position.0,
+            position.1,
+            escape_xml(text)
+        );
+    }
+
+    /// Draws possibly multi-line text centered on `center`.
+    fn text_block(&mut self, center: (f32, f32), text: &str, color: &str, bold: bool) {
+        let lines: Vec<&str> = text.lines().collect();
+        let top = center.1 - lines.len() as f32 * LINE_HEIGHT / 2.;
+        for (index, line) in lines.iter().enumerate() {
+            let baseline = top + index as f32 * LINE_HEIGHT + LINE_HEIGHT * 0.72;
+            self.text((center.0, baseline), line, color, "middle", bold);
+        }
+    }
+
+    fn label_with_background(&mut self, center: (f32, f32), text: &str, palette: &MermaidPalette) {
+        let (width, height) = text_block_size(text);
+        self.rect(
+            (center.0 - width / 2. - 4., center.1 - height / 2. - 2.),
+            (width + 8., height + 4.),
+            &palette.background,
+            "none",
+            2.,
+        );
+        self.text_block(center, text, &palette.text, false);
+    }
+
+    fn shape(
+        &mut self,
+        shape: NodeShape,
+        center: (f32, f32),
+        size: (f32, f32),
+        palette: &MermaidPalette,
+    ) {
+        let (x, y) = center;
+        let (half_width, half_height) = (size.0 / 2., size.1 / 2.);
+        let fill = &palette.node_fill;
+        let stroke = &palette.node_stroke;
+        match shape {
+            NodeShape::Rectangle => {
+                self.rect((x - half_width, y - half_height), size, fill, stroke, 2.)
+            }
+            NodeShape::Rounded => {
+                self.rect((x - half_width, y - half_height), size, fill, stroke, 8.)
+            }
+            NodeShape::Stadium => self.rect(
+                (x - half_width, y - half_height),
+                size,
+                fill,
+                stroke,
+                half_height,
+            ),
+            NodeShape::Circle => self.rect(
+                (x - half_width, y - half_height),
+                size,
+                fill,
+                stroke,
+                half_width,
+            ),
+            NodeShape::Diamond => self.polygon(
+                &[
+                    (x, y - half_height),
+                    (x + half_width, y),
+                    (x, y + half_height),
+                    (x - half_width, y),
+                ],
+                fill,
+                stroke,
+            ),
+            NodeShape::Hexagon => self.polygon(
+                &[
+                    (x - half_width + 12., y - half_height),
+                    (x + half_width - 12., y - half_height),
+                    (x + half_width, y),
+                    (x + half_width - 12., y + half_height),
+                    (x - half_width + 12., y + half_height),
+                    (x - half_width, y),
+                ],
+                fill,
+                stroke,
+            ),
+            NodeShape::Cylinder => {
+                self.rect((x - half_width, y - half_height), size, fill, stroke, 0.);
+                let top = y - half_height + 6.;
+                self.path(
+                    &format!(
+                        "M {:.1} {top:.1} Q {x:.1} {:.1} {:.1} {top:.1}",
+                        x - half_width,
+                        top + 10.,
+                        x + half_width
+                    ),
+                    stroke,
+                    1.5,
+                    None,
+                );
+            }
+        }
+    }
+
+    /// Draws an arrow head at `end`, pointing away from `start`.
+    fn arrow_head(&mut self, start: (f32, f32), end: (f32, f32), color: &str, filled: bool) {
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        let length = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+        let (ux, uy) = (dx / length, dy / length);
+        let (size, spread) = (10., 5.);
+        let base = (end.0 - ux * size, end.1 - uy * size);
+        let left = (base.0 - uy * spread, base.1 + ux * spread);
+        let right = (base.0 + uy * spread, base.1 - ux * spread);
+        if filled {
+            self.polygon(&[end, left, right], color, color);
+        } else {
+            self.line(left, end, color, 1.5, None);
+            self.line(right, end, color, 1.5, None);
+        }
+    }
+
+    fn cross(&mut self, center: (f32, f32), color: &str) {
+        let (x, y) = center;
+        self.line((x - 5., y - 5.), (x + 5., y + 5.), color, 1.5, None);
+        self.line((x - 5., y + 5.), (x + 5., y - 5.), color, 1.5, None);
+    }
+
+    /// Draws a loop leaving and re-entering the top right corner of a node,
+    /// returning a point suitable for its label.
+    fn self_loop(
+        &mut self,
+        center: (f32, f32),
+        size: (f32, f32),
+        color: &str,
+        width: f32,
+    ) -> (f32, f32) {
+        let start = (center.0 + size.0 / 4., center.1 - size.1 / 2.);
+        let end = (center.0 + size.0 / 2., center.1 - size.1 / 4.);
+        let control = (center.0 + size.0 / 2. + 30., center.1 - size.1 / 2. - 30.);
+        self.include(control);
+        self.path(
+            &format!(
+                "M {:.1} {:.1} Q {:.1} {:.1} {:.1} {:.1}",
+                start.0, start.1, control.0, control.1, end.0, end.1
+            ),
+            color,
+            width,
+            None,
+        );
+        self.arrow_head(control, end, color, true);
+        (control.0 - 10., control.1 + 10.)
+    }
+
+    /// Draws a UML relationship marker at `end`, for a line arriving from `start`.
+    fn relation_marker(
+        &mut self,
+        start: (f32, f32),
+        end: (f32, f32),
+        marker: RelationMarker,
+        palette: &MermaidPalette,
+    ) {
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        let length = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+        let (ux, uy) = (dx / length, dy / length);
+        let point_at =
+            |back: f32, side: f32| (end.0 - ux * back - uy * side, end.1 - uy * back + ux * side);
+        match marker {
+            RelationMarker::None => {}
+            RelationMarker::Arrow => self.arrow_head(start, end, &palette.line, false),
+            RelationMarker::Triangle => self.polygon(
+                &[end, point_at(14., 8.), point_at(14., -8.)],
+                &palette.background,
+                &palette.line,
+            ),
+            RelationMarker::FilledDiamond | RelationMarker::HollowDiamond => {
+                let fill = if marker == RelationMarker::FilledDiamond {
+                    &palette.line
+                } else {
+                    &palette.background
+                };
+                self.polygon(
+                    &[end, point_at(9., 6.), point_at(18., 0.), point_at(9., -6.)],
+                    fill,
+                    &palette.line,
+                );
+            }
+        }
+    }
+
+    fn finish(self) -> String {
+        let padding = 8.;
+        let width = self.max.0 - self.min.0 + padding * 2.;
+        let height = self.max.1 - self.min.1 + padding * 2.;
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.1}" height="{height:.1}" viewBox="{:.1} {:.1} {width:.1} {height:.1}">{}</svg>"#,
+            self.min.0 - padding,
+            self.min.1 - padding,
+            self.body
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn palette() -> MermaidPalette {
+        MermaidPalette {
+            text: "#000000".into(),
+            line: "#333333".into(),
+            node_fill: "#eeeeee".into(),
+            node_stroke: "#999999".into(),
+            note_fill: "#ffffcc".into(),
+            background: "#ffffff".into(),
+        }
+    }
+
+    #[test]
+    fn test_parse_flowchart() {
+        let Some(MermaidDiagram::Flowchart(flowchart)) = parse_mermaid(
+            "graph LR\n    A[Start] -->|yes| B{Decide?}\n    B -- no --> C((End)) & A\n    C -.-> A",
+        ) else {
+            panic!("expected a flowchart");
+        };
+        assert_eq!(flowchart.direction, FlowDirection::LeftRight);
+        let labels: Vec<_> = flowchart
+            .nodes
+            .iter()
+            .map(|node| (node.label.as_str(), node.shape))
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                ("Start", NodeShape::Rectangle),
+                ("Decide?", NodeShape::Diamond),
+                ("End", NodeShape::Circle),
+            ]
+        );
+        let edges: Vec<_> = flowchart
+            .edges
+            .iter()
+            .map(|edge| (edge.from, edge.to, edge.label.as_deref(), edge.style))
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                (0, 1, Some("yes"), EdgeStyle::Solid),
+                (1, 2, Some("no"), EdgeStyle::Solid),
+                (1, 0, Some("no"), EdgeStyle::Solid),
+                (2, 0, None, EdgeStyle::Dotted),
+            ]
+        );
+        assert!(flowchart.edges.iter().all(|edge| edge.arrow_end));
+    }
+
+    #[test]
+    fn test_parse_sequence_diagram() {
+        let Some(MermaidDiagram::Sequence(sequence)) = parse_mermaid(
+            "sequenceDiagram\n    participant A as Alice\n    A->>+B: Hello\n    B-->>-A: Hi\n    Note over A,B: Done",
+        ) else {
+            panic!("expected a sequence diagram");
+        };
+        let participants: Vec<_> = sequence
+            .participants
+            .iter()
+            .map(|participant| participant.label.as_str())
+            .collect();
+        assert_eq!(participants, ["Alice", "B"]);
+        assert_eq!(
+            sequence.events,
+            [
+                SequenceEvent::Message {
+                    from: 0,
+                    to: 1,
+                    text: "Hello".into(),
+                    dashed: false,
+                    arrow: MessageArrow::Filled,
+                },
+                SequenceEvent::Message {
+                    from: 1,
+                    to: 0,
+                    text: "Hi".into(),
+                    dashed: true,
+                    arrow: MessageArrow::Filled,
+                },
+                SequenceEvent::Note {
+                    first: 0,
+                    last: 1,
+                    placement: NotePlacement::Over,
+                    text: "Done".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_class_diagram() {
+        let Some(MermaidDiagram::Class(class_diagram)) = parse_mermaid(
+            "classDiagram\n    class Animal {\n        +List~String~ tags\n        <<abstract>>\n        +String name\n        +eat() void\n    }\n    Animal <|-- Duck\n    Duck : +swim()\n    Duck \"1\" *-- \"many\" Egg : lays",
+        ) else {
+            panic!("expected a class diagram");
+        };
+        let animal = &class_diagram.classes[0];
+        assert_eq!(animal.annotation.as_deref(), Some("abstract"));
+        assert_eq!(animal.attributes, ["+List<String> tags", "+String name"]);
+        assert_eq!(animal.methods, ["+eat() void"]);
+        assert_eq!(class_diagram.classes[1].methods, ["+swim()"]);
+        assert_eq!(class_diagram.relations.len(), 2);
+        assert_eq!(
+            class_diagram.relations[0].from_marker,
+            RelationMarker::Triangle
+        );
+        assert_eq!(
+            class_diagram.relations[1].from_marker,
+            RelationMarker::FilledDiamond
+        );
+        assert_eq!(class_diagram.relations[1].label.as_deref(), Some("lays"));
+        assert_eq!(class_diagram.classes[2].name, "Egg");
+    }
+
+    #[test]
+    fn test_mermaid_to_svg() {
+        let flowchart = parse_mermaid("flowchart TD\n  A --> B\n  A --> C\n  B --> D\n  C --> D")
+            .expect("flowchart should parse");
+        let svg = flowchart.to_svg(&palette());
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<rect").count(), 4);
+        assert_eq!(svg.matches("<line").count(), 4);
+
+        assert!(parse_mermaid("pie title Pets\n \"Dogs\" : 386").is_none());
+    }
+
+    #[test]
+    fn test_layer_nodes() {
+        let layers = layer_nodes(4, &[(0, 1), (0, 2), (1, 3), (2, 3), (3, 0)]);
+        assert_eq!(layers, vec![vec![0], vec![1, 2], vec![3]]);
+    }
+}
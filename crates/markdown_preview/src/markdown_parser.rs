@@ -1,5 +1,7 @@
 use crate::{
     markdown_elements::*,
+    markdown_math::parse_math,
+    markdown_mermaid::parse_mermaid,
     markdown_minifier::{Minifier, MinifierOptions},
 };
 use async_recursion::async_recursion;
@@ -210,6 +212,17 @@ impl<'a> MarkdownParser<'a> {
                     self.cursor += 1;
 
                     let code_block = self.parse_code_block(language).await?;
+                    if code_block.language.as_deref() == Some("mermaid")
+                        && let Some(diagram) = parse_mermaid(&code_block.contents)
+                    {
+                        return Some(vec![ParsedMarkdownElement::Mermaid(
+                            ParsedMarkdownMermaid {
+                                source_range: code_block.source_range,
+                                contents: code_block.contents,
+                                diagram,
+                            },
+                        )]);
+                    }
                     Some(vec![ParsedMarkdownElement::CodeBlock(code_block)])
                 }
                 Tag::HtmlBlock => {
@@ -247,6 +260,7 @@ impl<'a> MarkdownParser<'a> {
         let mut image: Option<Image> = None;
         let mut regions: Vec<(Range<usize>, ParsedRegion)> = vec![];
         let mut highlights: Vec<(Range<usize>, MarkdownHighlight)> = vec![];
+        let mut source_runs: Vec<(usize, Range<usize>)> = vec![];
         let mut link_urls: Vec<String> = vec![];
         let mut link_ranges: Vec<Range<usize>> = vec![];
 
@@ -255,7 +269,8 @@ impl<'a> MarkdownParser<'a> {
                 break;
             }
 
-            let (current, _) = self.current().unwrap();
+            let (current, current_range) = self.current().unwrap();
+            let current_range = current_range.clone();
             let prev_len = text.len();
             match current {
                 Event::SoftBreak => {
@@ -275,6 +290,7 @@ impl<'a> MarkdownParser<'a> {
 
                 Event::Text(t) => {
                     text.push_str(t.as_ref());
+                    source_runs.push((prev_len, current_range));
                     let mut style = MarkdownHighlightStyle::default();
 
                     if bold_depth > 0 {
@@ -358,8 +374,34 @@ impl<'a> MarkdownParser<'a> {
                         }
                     }
                 }
+                Event::InlineMath(math) | Event::DisplayMath(math) => {
+                    if !text.is_empty() {
+                        markdown_text_like.push(MarkdownParagraphChunk::Text(ParsedMarkdownText {
+                            source_range: source_range.clone(),
+                            contents: mem::take(&mut text).into(),
+                            highlights: mem::take(&mut highlights),
+                            regions: mem::take(&mut regions),
+                            source_runs: mem::take(&mut source_runs),
+                        }));
+                    }
+                    let math = ParsedMarkdownMath {
+                        source_range: current_range,
+                        contents: math.to_string().into(),
+                        expression: parse_math(math),
+                    };
+                    markdown_text_like.push(if matches!(current, Event::InlineMath(_)) {
+                        MarkdownParagraphChunk::InlineMath(math)
+                    } else {
+                        MarkdownParagraphChunk::Math(math)
+                    });
+                }
                 Event::Code(t) => {
                     text.push_str(t.as_ref());
+                    let backticks = current_range.len().saturating_sub(t.len()) / 2;
+                    source_runs.push((
+                        prev_len,
+                        current_range.start + backticks..current_range.end - backticks,
+                    ));
                     let range = prev_len..text.len();
 
                     if link.is_some() {
@@ -396,6 +438,7 @@ impl<'a> MarkdownParser<'a> {
                                 contents: mem::take(&mut text).into(),
                                 highlights: mem::take(&mut highlights),
                                 regions: mem::take(&mut regions),
+                                source_runs: mem::take(&mut source_runs),
                             });
                             markdown_text_like.push(parsed_regions);
                         }
@@ -423,6 +466,7 @@ impl<'a> MarkdownParser<'a> {
                                 image.set_alt_text(std::mem::take(&mut text).into());
                                 mem::take(&mut highlights);
                                 mem::take(&mut regions);
+                                mem::take(&mut source_runs);
                             }
                             markdown_text_like.push(MarkdownParagraphChunk::Image(image));
                         }
@@ -448,6 +492,7 @@ impl<'a> MarkdownParser<'a> {
                 contents: text.into(),
                 highlights,
                 regions,
+                source_runs,
             }));
         }
         markdown_text_like
@@ -875,6 +920,7 @@ impl<'a> MarkdownParser<'a> {
                         regions: Vec::default(),
                         highlights: Vec::default(),
                         contents: contents.borrow().to_string().into(),
+                        source_runs: Vec::default(),
                     }),
                 ]));
             }
@@ -1015,6 +1061,7 @@ impl<'a> MarkdownParser<'a> {
                                 .map(|(_, region)| region),
                         ),
                         contents: contents.into(),
+                        source_runs: Vec::new(),
                     }));
                 }
             }
@@ -1544,6 +1591,7 @@ mod tests {
                     contents: "Some bostrikethroughld text".into(),
                     highlights: Vec::new(),
                     regions: Vec::new(),
+                    source_runs: Vec::new(),
                 }
             )])
         );
@@ -1862,6 +1910,7 @@ mod tests {
                     contents: " Lorem Ipsum ".into(),
                     highlights: Vec::new(),
                     regions: Vec::new(),
+                    source_runs: Vec::new(),
                 }),
                 MarkdownParagraphChunk::Image(Image {
                     source_range: 0..81,
@@ -2121,7 +2170,8 @@ mod tests {
                         source_range: 0..71,
                         contents: "Some text".into(),
                         highlights: Default::default(),
-                        regions: Default::default()
+                        regions: Default::default(),
+                        source_runs: Default::default(),
                     }),
                     MarkdownParagraphChunk::Image(Image {
                         source_range: 0..71,
@@ -2136,7 +2186,8 @@ mod tests {
                         source_range: 0..71,
                         contents: " some more text".into(),
                         highlights: Default::default(),
-                        regions: Default::default()
+                        regions: Default::default(),
+                        source_runs: Default::default(),
                     }),
                 ])]
             },
@@ -2311,7 +2362,8 @@ mod tests {
                         source_range: 0..280,
                         contents: "My Table".into(),
                         highlights: Default::default(),
-                        regions: Default::default()
+                        regions: Default::default(),
+                        source_runs: Default::default(),
                     })]),
                     vec![],
                     vec![
@@ -2474,7 +2526,8 @@ mod tests {
                             source_range: 0..96,
                             contents: "Heading".into(),
                             highlights: Vec::default(),
-                            regions: Vec::default()
+                            regions: Vec::default(),
+                            source_runs: Vec::default(),
                         })],
                     }),
                     ParsedMarkdownElement::Heading(ParsedMarkdownHeading {
@@ -2484,7 +2537,8 @@ mod tests {
                             source_range: 0..96,
                             contents: "Heading".into(),
                             highlights: Vec::default(),
-                            regions: Vec::default()
+                            regions: Vec::default(),
+                            source_runs: Vec::default(),
                         })],
                     }),
                     ParsedMarkdownElement::Heading(ParsedMarkdownHeading {
@@ -2494,7 +2548,8 @@ mod tests {
                             source_range: 0..96,
                             contents: "Heading".into(),
                             highlights: Vec::default(),
-                            regions: Vec::default()
+                            regions: Vec::default(),
+                            source_runs: Vec::default(),
                         })],
                     }),
                     ParsedMarkdownElement::Heading(ParsedMarkdownHeading {
@@ -2504,7 +2559,8 @@ mod tests {
                             source_range: 0..96,
                             contents: "Heading".into(),
                             highlights: Vec::default(),
-                            regions: Vec::default()
+                            regions: Vec::default(),
+                            source_runs: Vec::default(),
                         })],
                     }),
                     ParsedMarkdownElement::Heading(ParsedMarkdownHeading {
@@ -2514,7 +2570,8 @@ mod tests {
                             source_range: 0..96,
                             contents: "Heading".into(),
                             highlights: Vec::default(),
-                            regions: Vec::default()
+                            regions: Vec::default(),
+                            source_runs: Vec::default(),
                         })],
                     }),
                     ParsedMarkdownElement::Heading(ParsedMarkdownHeading {
@@ -2524,7 +2581,8 @@ mod tests {
                             source_range: 0..96,
                             contents: "Heading".into(),
                             highlights: Vec::default(),
-                            regions: Vec::default()
+                            regions: Vec::default(),
+                            source_runs: Vec::default(),
                         })],
                     }),
                 ],
@@ -3077,6 +3135,80 @@ fn main() {
         );
     }
 
+    #[gpui::test]
+    async fn test_text_source_offsets() {
+        let parsed = parse("Some **bold** and `code`").await;
+
+        let ParsedMarkdownElement::Paragraph(chunks) = &parsed.children[0] else {
+            panic!("expected a paragraph, got {:?}", parsed.children[0]);
+        };
+        let MarkdownParagraphChunk::Text(text) = &chunks[0] else {
+            panic!("expected text, got {:?}", chunks[0]);
+        };
+        assert_eq!(text.contents.as_ref(), "Some bold and code");
+        assert_eq!(text.source_offset_for(0), 0);
+        assert_eq!(text.source_offset_for(5), 7);
+        assert_eq!(text.source_offset_for(8), 10);
+        assert_eq!(text.source_offset_for(14), 19);
+        assert_eq!(text.source_offset_for(18), 23);
+    }
+
+    #[gpui::test]
+    async fn test_mermaid_block() {
+        let parsed = parse("```mermaid\ngraph TD\n    A --> B\n```\n").await;
+
+        let ParsedMarkdownElement::Mermaid(mermaid) = &parsed.children[0] else {
+            panic!("expected a mermaid diagram, got {:?}", parsed.children[0]);
+        };
+        assert_eq!(mermaid.source_range, 0..35);
+        assert_eq!(mermaid.contents.as_ref(), "graph TD\n    A --> B");
+        assert_eq!(mermaid.diagram, parse_mermaid("graph TD\nA --> B").unwrap());
+
+        let parsed = parse("```mermaid\npie\n    \"Dogs\" : 386\n```\n").await;
+        assert_eq!(
+            parsed.children,
+            vec![code_block(
+                Some("mermaid".to_string()),
+                "pie\n    \"Dogs\" : 386",
+                0..35,
+                None
+            )]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_math() {
+        let parsed = parse("Euler: $e^{i\\pi} = -1$\n\n$$\\frac{1}{2}$$").await;
+
+        let ParsedMarkdownElement::Paragraph(chunks) = &parsed.children[0] else {
+            panic!("expected a paragraph, got {:?}", parsed.children[0]);
+        };
+        let MarkdownParagraphChunk::Text(text) = &chunks[0] else {
+            panic!("expected text, got {:?}", chunks[0]);
+        };
+        assert_eq!(text.contents.as_ref(), "Euler: ");
+        assert_eq!(
+            chunks[1],
+            MarkdownParagraphChunk::InlineMath(ParsedMarkdownMath {
+                source_range: 7..22,
+                contents: "e^{i\\pi} = -1".into(),
+                expression: parse_math("e^{i\\pi} = -1"),
+            })
+        );
+        assert_eq!(chunks.len(), 2);
+
+        assert_eq!(
+            parsed.children[1],
+            ParsedMarkdownElement::Paragraph(vec![MarkdownParagraphChunk::Math(
+                ParsedMarkdownMath {
+                    source_range: 24..39,
+                    contents: "\\frac{1}{2}".into(),
+                    expression: parse_math("\\frac{1}{2}"),
+                }
+            )])
+        );
+    }
+
     fn h1(contents: MarkdownParagraph, source_range: Range<usize>) -> ParsedMarkdownElement {
         ParsedMarkdownElement::Heading(ParsedMarkdownHeading {
             source_range,
@@ -3111,6 +3243,7 @@ fn main() {
             regions: Vec::new(),
             source_range,
             contents: contents.to_string().into(),
+            source_runs: Vec::new(),
         })]
     }
 
@@ -2,6 +2,8 @@ use gpui::{App, actions};
 use workspace::Workspace;
 
 pub mod markdown_elements;
+pub mod markdown_math;
+pub mod markdown_mermaid;
 mod markdown_minifier;
 pub mod markdown_parser;
 pub mod markdown_preview_view;
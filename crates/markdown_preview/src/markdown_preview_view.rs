@@ -3,11 +3,12 @@ use std::time::Duration;
 use std::{ops::Range, path::PathBuf};
 
 use anyhow::Result;
-use editor::scroll::Autoscroll;
-use editor::{Editor, EditorEvent, MultiBufferOffset, SelectionEffects};
+use editor::display_map::{DisplayRow, DisplaySnapshot, ToDisplayPoint};
+use editor::scroll::{Autoscroll, ScrollOffset};
+use editor::{Bias, DisplayPoint, Editor, EditorEvent, MultiBufferOffset, SelectionEffects};
 use gpui::{
     App, ClickEvent, Context, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement,
-    IntoElement, IsZero, ListState, ParentElement, Render, RetainAllImageCache, Styled,
+    IntoElement, IsZero, ListOffset, ListState, ParentElement, Render, RetainAllImageCache, Styled,
     Subscription, Task, WeakEntity, Window, list,
 };
 use language::LanguageRegistry;
@@ -18,7 +19,7 @@ use workspace::item::{Item, ItemHandle};
 use workspace::{Pane, Workspace};
 
 use crate::markdown_elements::ParsedMarkdownElement;
-use crate::markdown_renderer::CheckboxClickedEvent;
+use crate::markdown_renderer::{CheckboxClickedEvent, SourceClickedEvent};
 use crate::{
     MovePageDown, MovePageUp, OpenFollowingPreview, OpenPreview, OpenPreviewToTheSide,
     markdown_elements::ParsedMarkdown,
@@ -39,6 +40,9 @@ pub struct MarkdownPreviewView {
     language_registry: Arc<LanguageRegistry>,
     parsing_markdown_task: Option<Task<Result<()>>>,
     mode: MarkdownPreviewMode,
+    /// The editor scroll position last set to match the preview, so that the
+    /// resulting scroll event isn't synced back to the preview.
+    synced_editor_scroll_top: Option<ScrollOffset>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -203,6 +207,17 @@ impl MarkdownPreviewView {
     ) -> Entity<Self> {
         cx.new(|cx| {
             let list_state = ListState::new(0, gpui::ListAlignment::Top, px(1000.));
+            let view = cx.weak_entity();
+            list_state.set_scroll_handler(move |_, window, cx| {
+                let view = view.clone();
+                // The list is still borrowed while its scroll handler runs.
+                window.defer(cx, move |window, cx| {
+                    view.update(cx, |view, cx| {
+                        view.sync_editor_scroll_to_preview(window, cx)
+                    })
+                    .ok();
+                });
+            });
 
             let mut this = Self {
                 selected_block: 0,
@@ -215,6 +230,7 @@ impl MarkdownPreviewView {
                 parsing_markdown_task: None,
                 image_cache: RetainAllImageCache::new(cx),
                 mode,
+                synced_editor_scroll_top: None,
             };
 
             this.set_editor(active_editor, window, cx);
@@ -288,6 +304,11 @@ impl MarkdownPreviewView {
                         this.list_state.scroll_to_reveal_item(this.selected_block);
                         cx.notify();
                     }
+                    EditorEvent::ScrollPositionChanged {
+                        autoscroll: false, ..
+                    } => {
+                        this.sync_preview_scroll_to_editor(editor, cx);
+                    }
                     _ => {}
                 };
             },
@@ -359,6 +380,7 @@ impl MarkdownPreviewView {
         window: &mut Window,
         cx: &mut Context<Self>,
         selection: Range<MultiBufferOffset>,
+        focus_editor: bool,
     ) {
         if let Some(state) = &self.active_editor {
             state.editor.update(cx, |editor, cx| {
@@ -368,11 +390,99 @@ impl MarkdownPreviewView {
                     cx,
                     |selections| selections.select_ranges(vec![selection]),
                 );
-                window.focus(&editor.focus_handle(cx));
+                if focus_editor {
+                    window.focus(&editor.focus_handle(cx));
+                }
             });
         }
     }
 
+    /// Scrolls the editor so that its first visible line matches the source of
+    /// the content at the top of the preview.
+    fn sync_editor_scroll_to_preview(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let (Some(state), Some(contents)) = (&self.active_editor, &self.contents) else {
+            return;
+        };
+        let scroll_top = self.list_state.logical_scroll_top();
+        let Some(source_range) = contents
+            .children
+            .get(scroll_top.item_ix)
+            .and_then(|block| block.source_range())
+        else {
+            return;
+        };
+        let next_block_start = contents.children[scroll_top.item_ix + 1..]
+            .iter()
+            .find_map(|block| block.source_range())
+            .map_or(source_range.end, |range| range.start);
+        let progress = self
+            .list_state
+            .bounds_for_item(scroll_top.item_ix)
+            .map(|bounds| bounds.size.height)
+            .filter(|height| !height.is_zero())
+            .map_or(0., |height| {
+                (scroll_top.offset_in_item / height).clamp(0., 1.)
+            });
+
+        let editor_scroll_top = state.editor.update(cx, |editor, cx| {
+            let snapshot = editor.display_snapshot(cx);
+            let start_row = display_row_for_offset(&snapshot, source_range.start);
+            let end_row = display_row_for_offset(&snapshot, next_block_start);
+            let mut scroll_position = editor.scroll_position(cx);
+            scroll_position.y = start_row + (end_row - start_row) * progress as ScrollOffset;
+            editor.set_scroll_position(scroll_position, window, cx);
+            editor.scroll_position(cx).y
+        });
+        self.synced_editor_scroll_top = Some(editor_scroll_top);
+    }
+
+    /// Scrolls the preview so that the content at its top matches the first
+    /// visible line of the editor.
+    fn sync_preview_scroll_to_editor(&mut self, editor: &Entity<Editor>, cx: &mut Context<Self>) {
+        let (scroll_top, snapshot) = editor.update(cx, |editor, cx| {
+            (editor.scroll_position(cx).y, editor.display_snapshot(cx))
+        });
+        if self.synced_editor_scroll_top.take() == Some(scroll_top) {
+            return;
+        }
+
+        let top_offset = DisplayPoint::new(DisplayRow(scroll_top.floor() as u32), 0)
+            .to_offset(&snapshot, Bias::Left);
+        let block_index = self.get_block_index_under_cursor(top_offset..top_offset);
+        let Some(contents) = &self.contents else {
+            return;
+        };
+        let Some(source_range) = contents
+            .children
+            .get(block_index)
+            .and_then(|block| block.source_range())
+        else {
+            return;
+        };
+        let next_block_start = contents.children[block_index + 1..]
+            .iter()
+            .find_map(|block| block.source_range())
+            .map_or(source_range.end, |range| range.start);
+
+        let start_row = display_row_for_offset(&snapshot, source_range.start);
+        let end_row = display_row_for_offset(&snapshot, next_block_start);
+        let progress = if end_row > start_row {
+            ((scroll_top - start_row) / (end_row - start_row)).clamp(0., 1.)
+        } else {
+            0.
+        };
+        let offset_in_item = self
+            .list_state
+            .bounds_for_item(block_index)
+            .map_or(px(0.), |bounds| bounds.size.height * progress as f32);
+
+        self.list_state.scroll_to(ListOffset {
+            item_ix: block_index,
+            offset_in_item,
+        });
+        cx.notify();
+    }
+
     /// The absolute path of the file that is currently being previewed.
     fn get_folder_for_active_editor(editor: &Editor, cx: &App) -> Option<PathBuf> {
         if let Some(file) = editor.file_at(MultiBufferOffset(0), cx) {
@@ -446,6 +556,11 @@ impl MarkdownPreviewView {
     }
 }
 
+fn display_row_for_offset(snapshot: &DisplaySnapshot, offset: usize) -> ScrollOffset {
+    let offset = MultiBufferOffset(offset).min(snapshot.buffer_snapshot().len());
+    offset.to_display_point(snapshot).row().0 as ScrollOffset
+}
+
 impl Focusable for MarkdownPreviewView {
     fn focus_handle(&self, _: &App) -> gpui::FocusHandle {
         self.focus_handle.clone()
@@ -542,6 +657,17 @@ impl Render for MarkdownPreviewView {
                                                 cx.notify();
                                             }
                                         },
+                                    ))
+                                    .with_source_clicked_callback(cx.listener(
+                                        move |this, event: &SourceClickedEvent, window, cx| {
+                                            let offset = MultiBufferOffset(event.source_offset());
+                                            this.move_cursor_to_block(
+                                                window,
+                                                cx,
+                                                offset..offset,
+                                                event.click_count() > 1,
+                                            );
+                                        },
                                     ));
 
                             let block = contents.children.get(ix).unwrap();
@@ -574,6 +700,7 @@ impl Render for MarkdownPreviewView {
                                                 cx,
                                                 MultiBufferOffset(source_range.start)
                                                     ..MultiBufferOffset(source_range.start),
+                                                true,
                                             );
                                         }
                                     },
@@ -1,15 +1,20 @@
-use crate::markdown_elements::{
-    HeadingLevel, Image, Link, MarkdownParagraph, MarkdownParagraphChunk, ParsedMarkdown,
-    ParsedMarkdownBlockQuote, ParsedMarkdownCodeBlock, ParsedMarkdownElement,
-    ParsedMarkdownHeading, ParsedMarkdownListItem, ParsedMarkdownListItemType, ParsedMarkdownTable,
-    ParsedMarkdownTableAlignment, ParsedMarkdownTableRow,
+use crate::{
+    markdown_elements::{
+        HeadingLevel, Image, Link, MarkdownParagraph, MarkdownParagraphChunk, ParsedMarkdown,
+        ParsedMarkdownBlockQuote, ParsedMarkdownCodeBlock, ParsedMarkdownElement,
+        ParsedMarkdownHeading, ParsedMarkdownListItem, ParsedMarkdownListItemType,
+        ParsedMarkdownMath, ParsedMarkdownMermaid, ParsedMarkdownTable,
+        ParsedMarkdownTableAlignment, ParsedMarkdownTableRow,
+    },
+    markdown_math::{layout_inline_math, layout_math, parse_math},
+    markdown_mermaid::{MermaidPalette, parse_mermaid},
 };
 use fs::normalize_path;
 use gpui::{
-    AbsoluteLength, AnyElement, App, AppContext as _, ClipboardItem, Context, Div, Element,
-    ElementId, Entity, HighlightStyle, Hsla, ImageSource, InteractiveText, IntoElement, Keystroke,
-    Modifiers, ParentElement, Render, Resource, SharedString, Styled, StyledText, TextStyle,
-    WeakEntity, Window, div, img, rems,
+    AbsoluteLength, AnyElement, App, AppContext as _, Asset, ClipboardItem, Context, Div, Element,
+    ElementId, Entity, HighlightStyle, Hsla, ImageCacheError, ImageSource, Img, InteractiveText,
+    IntoElement, Keystroke, Modifiers, ParentElement, Render, RenderImage, Resource, SharedString,
+    Styled, StyledText, TextStyle, WeakEntity, Window, div, img, rems,
 };
 use settings::Settings;
 use std::{
@@ -43,6 +48,23 @@ impl CheckboxClickedEvent {
 
 type CheckboxClickedCallback = Arc<Box<dyn Fn(&CheckboxClickedEvent, &mut Window, &mut App)>>;
 
+pub struct SourceClickedEvent {
+    pub source_offset: usize,
+    pub click_count: usize,
+}
+
+impl SourceClickedEvent {
+    pub fn source_offset(&self) -> usize {
+        self.source_offset
+    }
+
+    pub fn click_count(&self) -> usize {
+        self.click_count
+    }
+}
+
+type SourceClickedCallback = Arc<Box<dyn Fn(&SourceClickedEvent, &mut Window, &mut App)>>;
+
 #[derive(Clone)]
 pub struct RenderContext {
     workspace: Option<WeakEntity<Workspace>>,
@@ -62,6 +84,7 @@ pub struct RenderContext {
     syntax_theme: Arc<SyntaxTheme>,
     indent: usize,
     checkbox_clicked_callback: Option<CheckboxClickedCallback>,
+    source_clicked_callback: Option<SourceClickedCallback>,
     is_last_child: bool,
 }
 
@@ -97,6 +120,7 @@ impl RenderContext {
             code_block_background_color: theme.colors().surface_background,
             code_span_background_color: theme.colors().editor_document_highlight_read_background,
             checkbox_clicked_callback: None,
+            source_clicked_callback: None,
             is_last_child: false,
         }
     }
@@ -109,6 +133,16 @@ impl RenderContext {
         self
     }
 
+    /// Called when rendered text is clicked, with the source offset of the
+    /// clicked character.
+    pub fn with_source_clicked_callback(
+        mut self,
+        callback: impl Fn(&SourceClickedEvent, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.source_clicked_callback = Some(Arc::new(Box::new(callback)));
+        self
+    }
+
     fn next_id(&mut self, span: &Range<usize>) -> ElementId {
         let id = format!("markdown-{}-{}-{}", self.next_id, span.start, span.end);
         self.next_id += 1;
@@ -184,6 +218,7 @@ pub fn render_markdown_block(block: &ParsedMarkdownElement, cx: &mut RenderConte
         Table(table) => render_markdown_table(table, cx),
         BlockQuote(block_quote) => render_markdown_block_quote(block_quote, cx),
         CodeBlock(code_block) => render_markdown_code_block(code_block, cx),
+        Mermaid(mermaid) => render_markdown_mermaid(mermaid, cx),
         HorizontalRule(_) => render_markdown_rule(cx),
         Image(image) => render_markdown_image(image, cx),
     }
@@ -650,10 +685,19 @@ fn render_markdown_code_block(
 }
 
 fn render_markdown_paragraph(parsed: &MarkdownParagraph, cx: &mut RenderContext) -> AnyElement {
+    let has_inline_math = parsed
+        .iter()
+        .any(|chunk| matches!(chunk, MarkdownParagraphChunk::InlineMath(_)));
     cx.with_common_p(div())
         .children(render_markdown_text(parsed, cx))
         .flex()
-        .flex_col()
+        .map(|paragraph| {
+            if has_inline_math {
+                paragraph.flex_row().flex_wrap().items_center()
+            } else {
+                paragraph.flex_col()
+            }
+        })
         .into_any_element()
 }
 
@@ -708,48 +752,75 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
                     }
                 }
                 let workspace = workspace_clone.clone();
+                let styled_text = StyledText::new(parsed.contents.clone())
+                    .with_default_highlights(&text_style, highlights);
+                let text_layout = styled_text.layout().clone();
+                let source_click_id = cx.next_id(&parsed.source_range);
                 let element = div()
-                    .child(
-                        InteractiveText::new(
-                            element_id,
-                            StyledText::new(parsed.contents.clone())
-                                .with_default_highlights(&text_style, highlights),
-                        )
-                        .tooltip({
-                            let links = links.clone();
-                            let link_ranges = link_ranges.clone();
-                            move |idx, _, cx| {
-                                for (ix, range) in link_ranges.iter().enumerate() {
-                                    if range.contains(&idx) {
-                                        return Some(LinkPreview::new(&links[ix].to_string(), cx));
-                                    }
-                                }
-                                None
+                    .id(source_click_id)
+                    .when_some(cx.source_clicked_callback.clone(), |this, callback| {
+                        let parsed = parsed.clone();
+                        this.on_click(move |event, window, cx| {
+                            let index = text_layout
+                                .index_for_position(event.position())
+                                .unwrap_or_else(|closest_index| closest_index);
+                            let clicked_link = parsed.regions.iter().any(|(range, region)| {
+                                region.link.is_some() && range.contains(&index)
+                            });
+                            if clicked_link {
+                                return;
                             }
+                            callback(
+                                &SourceClickedEvent {
+                                    source_offset: parsed.source_offset_for(index),
+                                    click_count: event.click_count(),
+                                },
+                                window,
+                                cx,
+                            );
+                            cx.stop_propagation();
                         })
-                        .on_click(
-                            link_ranges,
-                            move |clicked_range_ix, window, cx| match &links[clicked_range_ix] {
-                                Link::Web { url } => cx.open_url(url),
-                                Link::Path { path, .. } => {
-                                    if let Some(workspace) = &workspace {
-                                        _ = workspace.update(cx, |workspace, cx| {
-                                            workspace
-                                                .open_abs_path(
-                                                    normalize_path(path.clone().as_path()),
-                                                    OpenOptions {
-                                                        visible: Some(OpenVisible::None),
-                                                        ..Default::default()
-                                                    },
-                                                    window,
-                                                    cx,
-                                                )
-                                                .detach();
-                                        });
+                    })
+                    .child(
+                        InteractiveText::new(element_id, styled_text)
+                            .tooltip({
+                                let links = links.clone();
+                                let link_ranges = link_ranges.clone();
+                                move |idx, _, cx| {
+                                    for (ix, range) in link_ranges.iter().enumerate() {
+                                        if range.contains(&idx) {
+                                            return Some(LinkPreview::new(
+                                                &links[ix].to_string(),
+                                                cx,
+                                            ));
+                                        }
                                     }
+                                    None
                                 }
-                            },
-                        ),
+                            })
+                            .on_click(
+                                link_ranges,
+                                move |clicked_range_ix, window, cx| match &links[clicked_range_ix] {
+                                    Link::Web { url } => cx.open_url(url),
+                                    Link::Path { path, .. } => {
+                                        if let Some(workspace) = &workspace {
+                                            _ = workspace.update(cx, |workspace, cx| {
+                                                workspace
+                                                    .open_abs_path(
+                                                        normalize_path(path.clone().as_path()),
+                                                        OpenOptions {
+                                                            visible: Some(OpenVisible::None),
+                                                            ..Default::default()
+                                                        },
+                                                        window,
+                                                        cx,
+                                                    )
+                                                    .detach();
+                                            });
+                                        }
+                                    }
+                                },
+                            ),
                     )
                     .into_any();
                 any_element.push(element);
@@ -758,12 +829,177 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
             MarkdownParagraphChunk::Image(image) => {
                 any_element.push(render_markdown_image(image, cx));
             }
+
+            MarkdownParagraphChunk::InlineMath(math) => {
+                any_element.push(render_markdown_inline_math(math, cx));
+            }
+
+            MarkdownParagraphChunk::Math(math) => {
+                any_element.push(render_markdown_math(math, cx));
+            }
         }
     }
 
     any_element
 }
 
+fn render_markdown_mermaid(parsed: &ParsedMarkdownMermaid, cx: &mut RenderContext) -> AnyElement {
+    let palette = MermaidPalette {
+        text: svg_color(cx.text_color),
+        line: svg_color(cx.text_muted_color),
+        node_fill: svg_color(cx.code_block_background_color),
+        node_stroke: svg_color(cx.border_color),
+        note_fill: svg_color(cx.title_bar_background_color),
+        background: svg_color(cx.panel_background_color),
+    };
+
+    let copy_source_button = IconButton::new("copy-mermaid", IconName::Copy)
+        .icon_size(IconSize::Small)
+        .on_click({
+            let contents = parsed.contents.clone();
+            move |_, _window, cx| {
+                cx.write_to_clipboard(ClipboardItem::new_string(contents.to_string()));
+            }
+        })
+        .tooltip(Tooltip::text("Copy diagram source"))
+        .visible_on_hover("markdown-block");
+
+    cx.with_common_p(div())
+        .flex()
+        .justify_center()
+        .child(
+            render_generated_svg(GeneratedSvg::Mermaid {
+                contents: parsed.contents.clone(),
+                palette,
+            })
+            .max_w_full(),
+        )
+        .child(
+            div()
+                .h_flex()
+                .absolute()
+                .right_1()
+                .top_1()
+                .child(copy_source_button),
+        )
+        .into_any()
+}
+
+fn render_markdown_math(parsed: &ParsedMarkdownMath, cx: &mut RenderContext) -> AnyElement {
+    let font_size = cx.buffer_text_style.font_size.to_pixels(cx.window_rem_size) * 1.2;
+
+    div()
+        .w_full()
+        .flex()
+        .justify_center()
+        .py(cx.scaled_rems(0.5))
+        .child(
+            render_generated_svg(GeneratedSvg::Math {
+                contents: parsed.contents.clone(),
+                font_size,
+                color: svg_color(cx.text_color),
+                inline: false,
+            })
+            .max_w_full(),
+        )
+        .into_any()
+}
+
+fn render_markdown_inline_math(parsed: &ParsedMarkdownMath, cx: &mut RenderContext) -> AnyElement {
+    let font_size = cx.text_style.font_size.to_pixels(cx.window_rem_size);
+
+    div()
+        .flex_none()
+        .px_0p5()
+        .child(render_generated_svg(GeneratedSvg::Math {
+            contents: parsed.contents.clone(),
+            font_size,
+            color: svg_color(cx.text_color),
+            inline: true,
+        }))
+        .into_any()
+}
+
+/// SVG generated by the preview itself, identified by its source and the
+/// theme it is drawn in, so that each diagram or expression is only laid out
+/// and rasterized once rather than on every frame.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum GeneratedSvg {
+    Mermaid {
+        contents: SharedString,
+        palette: MermaidPalette,
+    },
+    Math {
+        contents: SharedString,
+        font_size: Pixels,
+        color: String,
+        inline: bool,
+    },
+}
+
+impl GeneratedSvg {
+    fn to_svg(&self) -> Option<String> {
+        match self {
+            Self::Mermaid { contents, palette } => Some(parse_mermaid(contents)?.to_svg(palette)),
+            Self::Math {
+                contents,
+                font_size,
+                color,
+                inline,
+            } => {
+                let expression = parse_math(contents);
+                let font_size = f32::from(*font_size);
+                let layout = if *inline {
+                    layout_inline_math(&expression, font_size)
+                } else {
+                    layout_math(&expression, font_size)
+                };
+                Some(layout.to_svg(color))
+            }
+        }
+    }
+}
+
+/// Loads SVG markup generated by the preview itself, such as diagrams and
+/// math, into an image.
+enum GeneratedSvgLoader {}
+
+impl Asset for GeneratedSvgLoader {
+    type Source = GeneratedSvg;
+    type Output = Result<Arc<RenderImage>, ImageCacheError>;
+
+    fn load(
+        source: Self::Source,
+        cx: &mut App,
+    ) -> impl Future<Output = Self::Output> + Send + 'static {
+        let renderer = cx.svg_renderer();
+        async move {
+            let svg = source
+                .to_svg()
+                .ok_or_else(|| ImageCacheError::Asset("unsupported diagram".into()))?;
+            renderer
+                .render_single_frame(svg.as_bytes(), 1.0, true)
+                .map_err(|error| ImageCacheError::Asset(error.to_string().into()))
+        }
+    }
+}
+
+fn render_generated_svg(svg: GeneratedSvg) -> Img {
+    img(move |window: &mut Window, cx: &mut App| window.use_asset::<GeneratedSvgLoader>(&svg, cx))
+}
+
+fn svg_color(color: Hsla) -> String {
+    let color = color.to_rgb();
+    let channel = |value: f32| (value.clamp(0., 1.) * 255.).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}{:02x}",
+        channel(color.r),
+        channel(color.g),
+        channel(color.b),
+        channel(color.a)
+    )
+}
+
 fn render_markdown_rule(cx: &mut RenderContext) -> AnyElement {
     let rule = div().w_full().h(cx.scaled_rems(0.125)).bg(cx.border_color);
     div().py(cx.scaled_rems(0.5)).child(rule).into_any()
@@ -924,6 +1160,7 @@ mod tests {
             contents: SharedString::new(text),
             highlights: Default::default(),
             regions: Default::default(),
+            source_runs: Default::default(),
         })
     }
 
@@ -129,6 +129,7 @@ pub enum Event {
         transaction_id: TransactionId,
     },
     Reloaded,
+    LargeFileWindowFull,
     LanguageChanged(BufferId),
     Reparsed(BufferId),
     Saved,
@@ -2294,6 +2295,7 @@ impl MultiBuffer {
             BufferEvent::Saved => Event::Saved,
             BufferEvent::FileHandleChanged => Event::FileHandleChanged,
             BufferEvent::Reloaded => Event::Reloaded,
+            BufferEvent::LargeFileWindowFull => Event::LargeFileWindowFull,
             BufferEvent::LanguageChanged => Event::LanguageChanged(buffer_id),
             BufferEvent::Reparsed => Event::Reparsed(buffer_id),
             BufferEvent::DiagnosticsUpdated => Event::DiagnosticsUpdated,
@@ -0,0 +1,664 @@
+use std::cmp::Ordering;
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use collections::HashSet;
+
+use crate::document_tree::{DocumentTree, NodeKey, NodeKind};
+
+/// A query selecting nodes of a [`DocumentTree`].
+///
+/// Paths can be written as JSONPath (`$.items[*].metadata.name`, `$..image`,
+/// `$.items[?(@.kind == 'Service')]`) or as JMESPath (`items[*].metadata.name`,
+/// `items[?kind == 'Service']`). Both support fields, indices, slices, wildcards and filters
+/// comparing a field to a literal, but not functions, pipes or multi-select.
+/// Queries that aren't paths look for nodes whose key or value contains the query, ignoring case.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DocumentQuery {
+    Path(Vec<Segment>),
+    Text(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Segment {
+    Child(Selector),
+    /// Selects the matching children of the nodes and of all of their descendants, as in `$..name`.
+    Descendants(Selector),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Selector {
+    Names(Vec<String>),
+    Index(i64),
+    Slice {
+        start: Option<i64>,
+        end: Option<i64>,
+    },
+    Wildcard,
+    Filter(Filter),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Filter {
+    /// Matches nodes where the relative path resolves to a node.
+    Exists(Vec<NodeKey>),
+    Compare(Vec<NodeKey>, Comparison, Literal),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Literal {
+    String(String),
+    Number(f64),
+    Boolean(bool),
+    Null,
+}
+
+impl DocumentQuery {
+    pub fn parse(query: &str) -> Result<Self> {
+        let query = query.trim();
+        let is_path = query.starts_with(['$', '@', '['])
+            || query
+                .split_whitespace()
+                .next()
+                .is_some_and(|first_word| first_word.contains(['.', '[']))
+                && query.parse::<f64>().is_err();
+        if !is_path {
+            return Ok(Self::Text(query.to_lowercase()));
+        }
+        let mut parser = Parser {
+            source: query,
+            position: 0,
+        };
+        parser.parse_path().map(Self::Path)
+    }
+
+    /// The nodes matching the query, in the order they appear in.
+    pub fn matches(&self, tree: &DocumentTree) -> Vec<usize> {
+        match self {
+            Self::Text(text) => tree
+                .nodes
+                .iter()
+                .enumerate()
+                .filter(|(_, node)| {
+                    let key_matches = match &node.key {
+                        Some(NodeKey::Name(name)) => name.to_lowercase().contains(text),
+                        Some(NodeKey::Index(_)) | None => false,
+                    };
+                    key_matches
+                        || node
+                            .value
+                            .as_ref()
+                            .is_some_and(|value| value.to_lowercase().contains(text))
+                })
+                .map(|(index, _)| index)
+                .collect(),
+            Self::Path(segments) => {
+                let mut current = tree.roots.clone();
+                for segment in segments {
+                    let mut next = Vec::new();
+                    let mut seen = HashSet::default();
+                    for &index in &current {
+                        match segment {
+                            Segment::Child(selector) => {
+                                select(tree, index, selector, &mut next, &mut seen)
+                            }
+                            Segment::Descendants(selector) => {
+                                // Nodes are stored in document order, so a node's descendants
+                                // directly follow it.
+                                let end = subtree_end(tree, index);
+                                for descendant in index..end {
+                                    select(tree, descendant, selector, &mut next, &mut seen);
+                                }
+                            }
+                        }
+                    }
+                    next.sort_unstable();
+                    current = next;
+                }
+                current
+            }
+        }
+    }
+}
+
+fn subtree_end(tree: &DocumentTree, index: usize) -> usize {
+    let depth = tree.nodes[index].depth;
+    tree.nodes[index + 1..]
+        .iter()
+        .position(|node| node.depth <= depth)
+        .map_or(tree.nodes.len(), |position| index + 1 + position)
+}
+
+fn select(
+    tree: &DocumentTree,
+    index: usize,
+    selector: &Selector,
+    matches: &mut Vec<usize>,
+    seen: &mut HashSet<usize>,
+) {
+    let node = &tree.nodes[index];
+    let mut push = |child: usize| {
+        if seen.insert(child) {
+            matches.push(child);
+        }
+    };
+    match selector {
+        Selector::Names(names) => {
+            for name in names {
+                if let Some(child) = tree.child(index, &NodeKey::Name(name.clone())) {
+                    push(child);
+                }
+            }
+        }
+        Selector::Index(child_index) => {
+            if node.kind == NodeKind::Array
+                && let Some(child_index) = resolve_index(*child_index, node.children.len())
+            {
+                push(node.children[child_index]);
+            }
+        }
+        Selector::Slice { start, end } => {
+            if node.kind == NodeKind::Array {
+                let length = node.children.len();
+                let start = start.map_or(0, |start| clamp_index(start, length));
+                let end = end.map_or(length, |end| clamp_index(end, length));
+                for &child in node.children.get(start..end).unwrap_or_default() {
+                    push(child);
+                }
+            }
+        }
+        Selector::Wildcard => {
+            for &child in &node.children {
+                push(child);
+            }
+        }
+        Selector::Filter(filter) => {
+            for &child in &node.children {
+                if filter_matches(tree, child, filter) {
+                    push(child);
+                }
+            }
+        }
+    }
+}
+
+fn resolve_index(index: i64, length: usize) -> Option<usize> {
+    let index = if index < 0 {
+        length as i64 + index
+    } else {
+        index
+    };
+    (0..length as i64)
+        .contains(&index)
+        .then_some(index as usize)
+}
+
+fn clamp_index(index: i64, length: usize) -> usize {
+    let index = if index < 0 {
+        length as i64 + index
+    } else {
+        index
+    };
+    index.clamp(0, length as i64) as usize
+}
+
+fn resolve(tree: &DocumentTree, index: usize, path: &[NodeKey]) -> Option<usize> {
+    path.iter()
+        .try_fold(index, |index, key| tree.child(index, key))
+}
+
+fn filter_matches(tree: &DocumentTree, index: usize, filter: &Filter) -> bool {
+    match filter {
+        Filter::Exists(path) => resolve(tree, index, path).is_some(),
+        Filter::Compare(path, comparison, literal) => {
+            let ordering = resolve(tree, index, path).and_then(|index| {
+                let node = &tree.nodes[index];
+                let value = node.value.as_deref()?;
+                match (node.kind, literal) {
+                    (NodeKind::Number, Literal::Number(number)) => {
+                        value.parse::<f64>().ok()?.partial_cmp(number)
+                    }
+                    (NodeKind::String | NodeKind::Number, Literal::String(string)) => {
+                        Some(value.cmp(string.as_str()))
+                    }
+                    (NodeKind::Boolean, Literal::Boolean(boolean)) => {
+                        (value.eq_ignore_ascii_case("true") == *boolean).then_some(Ordering::Equal)
+                    }
+                    (NodeKind::Null, Literal::Null) => Some(Ordering::Equal),
+                    _ => None,
+                }
+            });
+            match comparison {
+                Comparison::Equal => ordering == Some(Ordering::Equal),
+                Comparison::NotEqual => ordering != Some(Ordering::Equal),
+                Comparison::Less => ordering == Some(Ordering::Less),
+                Comparison::LessOrEqual => ordering.is_some_and(|ordering| ordering.is_le()),
+                Comparison::Greater => ordering == Some(Ordering::Greater),
+                Comparison::GreaterOrEqual => ordering.is_some_and(|ordering| ordering.is_ge()),
+            }
+        }
+        Filter::And(left, right) => {
+            filter_matches(tree, index, left) && filter_matches(tree, index, right)
+        }
+        Filter::Or(left, right) => {
+            filter_matches(tree, index, left) || filter_matches(tree, index, right)
+        }
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn rest(&self) -> &str {
+        &self.source[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.position += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{token}`")))
+        }
+    }
+
+    fn error(&self, message: &str) -> anyhow::Error {
+        anyhow!("{message} at position {}", self.position + 1)
+    }
+
+    fn parse_path(&mut self) -> Result<Vec<Segment>> {
+        let mut segments = Vec::new();
+        if !self.eat("$") && !self.eat("@") {
+            // JMESPath expressions start with a field name, without a leading `.`.
+            if let Some(name) = self.parse_name()? {
+                segments.push(Segment::Child(name));
+            }
+        }
+        loop {
+            self.skip_whitespace();
+            if self.position == self.source.len() {
+                return Ok(segments);
+            }
+            if self.eat("..") {
+                let selector = if self.peek() == Some('[') {
+                    self.parse_bracket()?
+                } else {
+                    self.parse_name()?
+                        .ok_or_else(|| self.error("expected a name after `..`"))?
+                };
+                segments.push(Segment::Descendants(selector));
+            } else if self.eat(".") {
+                let selector = self
+                    .parse_name()?
+                    .ok_or_else(|| self.error("expected a name after `.`"))?;
+                segments.push(Segment::Child(selector));
+            } else if self.peek() == Some('[') {
+                segments.push(Segment::Child(self.parse_bracket()?));
+            } else {
+                bail!(self.error("unexpected character"));
+            }
+        }
+    }
+
+    /// Parses `name`, `*` or a JMESPath `"quoted name"`.
+    fn parse_name(&mut self) -> Result<Option<Selector>> {
+        self.skip_whitespace();
+        if self.eat("*") {
+            return Ok(Some(Selector::Wildcard));
+        }
+        if self.peek() == Some('"') {
+            return Ok(Some(Selector::Names(vec![self.parse_string()?])));
+        }
+        Ok(self
+            .parse_identifier()
+            .map(|name| Selector::Names(vec![name])))
+    }
+
+    fn parse_identifier(&mut self) -> Option<String> {
+        let rest = self.rest();
+        let length = rest
+            .find(|character: char| {
+                !(character.is_alphanumeric() || matches!(character, '_' | '-' | '$'))
+            })
+            .unwrap_or(rest.len());
+        if length == 0 {
+            return None;
+        }
+        let identifier = rest[..length].to_string();
+        self.position += length;
+        Some(identifier)
+    }
+
+    fn parse_bracket(&mut self) -> Result<Selector> {
+        self.expect("[")?;
+        // JMESPath's `[]` flattens arrays, which selects their items like `[*]`.
+        if self.eat("]") {
+            return Ok(Selector::Wildcard);
+        }
+        self.skip_whitespace();
+        let selector = if self.eat("*") {
+            Selector::Wildcard
+        } else if self.eat("?") {
+            let parenthesized = self.eat("(");
+            let filter = self.parse_or()?;
+            if parenthesized {
+                self.expect(")")?;
+            }
+            Selector::Filter(filter)
+        } else if matches!(self.peek(), Some('\'' | '"')) {
+            let mut names = vec![self.parse_string()?];
+            while self.eat(",") {
+                self.skip_whitespace();
+                names.push(self.parse_string()?);
+            }
+            Selector::Names(names)
+        } else {
+            let start = self.parse_integer()?;
+            if self.eat(":") {
+                let end = self.parse_integer()?;
+                Selector::Slice { start, end }
+            } else {
+                Selector::Index(start.ok_or_else(|| self.error("expected an index"))?)
+            }
+        };
+        self.expect("]")?;
+        Ok(selector)
+    }
+
+    fn parse_integer(&mut self) -> Result<Option<i64>> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let length = rest
+            .char_indices()
+            .find(|&(offset, character)| {
+                !(character.is_ascii_digit() || offset == 0 && character == '-')
+            })
+            .map_or(rest.len(), |(offset, _)| offset);
+        if length == 0 {
+            return Ok(None);
+        }
+        let integer = rest[..length]
+            .parse()
+            .with_context(|| self.error("invalid index"))?;
+        self.position += length;
+        Ok(Some(integer))
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        let quote = self.peek().ok_or_else(|| self.error("expected a string"))?;
+        let mut value = String::new();
+        let mut characters = self.rest().char_indices().skip(1);
+        while let Some((offset, character)) = characters.next() {
+            match character {
+                '\\' => match characters.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, escaped)) => value.push(escaped),
+                    None => break,
+                },
+                character if character == quote => {
+                    self.position += offset + character.len_utf8();
+                    return Ok(value);
+                }
+                character => value.push(character),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn parse_or(&mut self) -> Result<Filter> {
+        let mut filter = self.parse_and()?;
+        while self.eat("||") {
+            filter = Filter::Or(Box::new(filter), Box::new(self.parse_and()?));
+        }
+        Ok(filter)
+    }
+
+    fn parse_and(&mut self) -> Result<Filter> {
+        let mut filter = self.parse_condition()?;
+        while self.eat("&&") {
+            filter = Filter::And(Box::new(filter), Box::new(self.parse_condition()?));
+        }
+        Ok(filter)
+    }
+
+    fn parse_condition(&mut self) -> Result<Filter> {
+        if self.eat("(") {
+            let filter = self.parse_or()?;
+            self.expect(")")?;
+            return Ok(filter);
+        }
+        let path = self.parse_relative_path()?;
+        let comparison = [
+            ("==", Comparison::Equal),
+            ("!=", Comparison::NotEqual),
+            ("<=", Comparison::LessOrEqual),
+            (">=", Comparison::GreaterOrEqual),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+        ]
+        .into_iter()
+        .find(|(token, _)| self.eat(token));
+        match comparison {
+            Some((_, comparison)) => Ok(Filter::Compare(path, comparison, self.parse_literal()?)),
+            None => Ok(Filter::Exists(path)),
+        }
+    }
+
+    /// Parses the path of a filter, relative to the node being filtered: `@.spec.name` in
+    /// JSONPath, or `spec.name` in JMESPath.
+    fn parse_relative_path(&mut self) -> Result<Vec<NodeKey>> {
+        self.skip_whitespace();
+        let mut path = Vec::new();
+        let mut expects_name = !self.eat("@");
+        loop {
+            if expects_name {
+                self.skip_whitespace();
+                let name = if self.peek() == Some('"') {
+                    self.parse_string()?
+                } else {
+                    self.parse_identifier()
+                        .ok_or_else(|| self.error("expected a field name"))?
+                };
+                path.push(NodeKey::Name(name));
+            }
+            if self.rest().starts_with('.') {
+                self.position += 1;
+                expects_name = true;
+            } else if self.rest().starts_with('[') {
+                self.position += 1;
+                let key = if matches!(self.peek(), Some('\'' | '"')) {
+                    NodeKey::Name(self.parse_string()?)
+                } else {
+                    let index = self
+                        .parse_integer()?
+                        .filter(|index| *index >= 0)
+                        .ok_or_else(|| self.error("expected an index"))?;
+                    NodeKey::Index(index as usize)
+                };
+                self.expect("]")?;
+                path.push(key);
+                expects_name = false;
+            } else {
+                return Ok(path);
+            }
+        }
+    }
+
+    fn parse_literal(&mut self) -> Result<Literal> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('\'' | '"') => return Ok(Literal::String(self.parse_string()?)),
+            // JMESPath literals are JSON between backticks.
+            Some('`') => {
+                self.position += 1;
+                let end = self
+                    .rest()
+                    .find('`')
+                    .ok_or_else(|| self.error("unterminated literal"))?;
+                let json = &self.rest()[..end];
+                let literal = match serde_json::from_str(json.trim())
+                    .with_context(|| self.error("invalid JSON literal"))?
+                {
+                    serde_json::Value::String(string) => Literal::String(string),
+                    serde_json::Value::Number(number) => {
+                        Literal::Number(number.as_f64().unwrap_or_default())
+                    }
+                    serde_json::Value::Bool(boolean) => Literal::Boolean(boolean),
+                    serde_json::Value::Null => Literal::Null,
+                    _ => bail!(self.error("only scalars can be compared")),
+                };
+                self.position += end + 1;
+                return Ok(literal);
+            }
+            _ => {}
+        }
+        for (keyword, literal) in [
+            ("true", Literal::Boolean(true)),
+            ("false", Literal::Boolean(false)),
+            ("null", Literal::Null),
+        ] {
+            if self.eat(keyword) {
+                return Ok(literal);
+            }
+        }
+        let rest = self.rest();
+        let length = rest
+            .find(|character: char| {
+                !(character.is_ascii_digit() || matches!(character, '-' | '+' | '.' | 'e' | 'E'))
+            })
+            .unwrap_or(rest.len());
+        let number = rest[..length]
+            .parse()
+            .map_err(|_| self.error("expected a literal"))?;
+        self.position += length;
+        Ok(Literal::Number(number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(text: &str) -> DocumentTree {
+        let mut parser = language::Parser::new();
+        parser
+            .set_language(&tree_sitter_json::LANGUAGE.into())
+            .unwrap();
+        let syntax_tree = parser.parse(text, None).unwrap();
+        DocumentTree::new(syntax_tree.root_node(), text)
+    }
+
+    fn paths(tree: &DocumentTree, query: &str) -> Vec<String> {
+        DocumentQuery::parse(query)
+            .unwrap()
+            .matches(tree)
+            .into_iter()
+            .map(|index| tree.json_path(index))
+            .collect()
+    }
+
+    #[test]
+    fn test_queries() {
+        let tree = tree(
+            r#"{
+                "kind": "List",
+                "items": [
+                    {"kind": "Service", "metadata": {"name": "api"}, "port": 80},
+                    {"kind": "Deployment", "metadata": {"name": "web"}, "replicas": 3},
+                    {"kind": "Service", "metadata": {"name": "db", "image-name": "pg"}, "port": 5432}
+                ]
+            }"#,
+        );
+
+        assert_eq!(paths(&tree, "$.kind"), ["$.kind"]);
+        assert_eq!(
+            paths(&tree, "$.items[*].metadata.name"),
+            [
+                "$.items[0].metadata.name",
+                "$.items[1].metadata.name",
+                "$.items[2].metadata.name"
+            ]
+        );
+        assert_eq!(
+            paths(&tree, "items[].metadata.name"),
+            paths(&tree, "$.items[*].metadata.name")
+        );
+        assert_eq!(paths(&tree, "$.items[-1].port"), ["$.items[2].port"]);
+        assert_eq!(
+            paths(&tree, "items[0:2].kind"),
+            ["$.items[0].kind", "$.items[1].kind"]
+        );
+        assert_eq!(
+            paths(&tree, "$..kind"),
+            [
+                "$.kind",
+                "$.items[0].kind",
+                "$.items[1].kind",
+                "$.items[2].kind"
+            ]
+        );
+        assert_eq!(
+            paths(&tree, "$.items[?(@.kind == 'Service')].metadata.name"),
+            ["$.items[0].metadata.name", "$.items[2].metadata.name"]
+        );
+        assert_eq!(
+            paths(&tree, "items[?kind == `\"Service\"` && port > `100`].port"),
+            ["$.items[2].port"]
+        );
+        assert_eq!(
+            paths(&tree, "$.items[?(@.replicas || @.metadata['image-name'])]"),
+            ["$.items[1]", "$.items[2]"]
+        );
+        assert_eq!(
+            paths(&tree, "$..['image-name']"),
+            ["$.items[2].metadata['image-name']"]
+        );
+        assert_eq!(
+            paths(&tree, "items[2].metadata.\"image-name\""),
+            ["$.items[2].metadata['image-name']"]
+        );
+
+        assert_eq!(
+            paths(&tree, "DEPLOY"),
+            ["$.items[1].kind"],
+            "plain text looks for keys and values containing it"
+        );
+        assert_eq!(paths(&tree, "image"), ["$.items[2].metadata['image-name']"]);
+
+        assert!(DocumentQuery::parse("$.items[").is_err());
+        assert!(DocumentQuery::parse("$.items[?(@.port >)]").is_err());
+    }
+}
@@ -0,0 +1,539 @@
+use std::ops::Range;
+
+use language::Node;
+
+/// Documents nested deeper than this are only shown down to this depth, which keeps malformed or
+/// adversarial files from making the tree unusable.
+const MAX_DEPTH: usize = 256;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NodeKey {
+    Name(String),
+    Index(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    Object,
+    Array,
+    String,
+    Number,
+    Boolean,
+    Null,
+    /// A YAML alias, such as `*defaults`, which refers to an anchored node.
+    Alias,
+}
+
+impl NodeKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            NodeKind::Object => "object",
+            NodeKind::Array => "array",
+            NodeKind::String => "string",
+            NodeKind::Number => "number",
+            NodeKind::Boolean => "boolean",
+            NodeKind::Null => "null",
+            NodeKind::Alias => "alias",
+        }
+    }
+
+    pub fn is_container(self) -> bool {
+        matches!(self, NodeKind::Object | NodeKind::Array)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DocumentNode {
+    /// `None` for the root of a document.
+    pub key: Option<NodeKey>,
+    pub kind: NodeKind,
+    /// The value of a scalar, without its quotes.
+    pub value: Option<String>,
+    /// The byte range of the node in the buffer, including its key.
+    pub range: Range<usize>,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    pub depth: usize,
+}
+
+/// The structure of a JSON or YAML document, built from its syntax tree.
+///
+/// Nodes are stored in the order they appear in, so that a node's descendants directly follow it.
+#[derive(Clone, Debug, Default)]
+pub struct DocumentTree {
+    pub nodes: Vec<DocumentNode>,
+    /// The root node of each document. YAML files can contain several documents.
+    pub roots: Vec<usize>,
+}
+
+struct PendingNode<'a> {
+    node: Option<Node<'a>>,
+    key: Option<NodeKey>,
+    range: Range<usize>,
+    parent: Option<usize>,
+    depth: usize,
+}
+
+impl DocumentTree {
+    /// Builds the tree from the root of a `json` or `yaml` syntax tree. Other syntax trees produce
+    /// an empty tree.
+    pub fn new(root: Node, text: &str) -> Self {
+        let mut tree = Self::default();
+        let mut cursor = root.walk();
+        let documents: Vec<PendingNode> = match root.kind() {
+            "document" => root
+                .named_children(&mut cursor)
+                .filter(|child| !is_ignored(child))
+                .map(|child| PendingNode {
+                    range: child.byte_range(),
+                    node: Some(child),
+                    key: None,
+                    parent: None,
+                    depth: 0,
+                })
+                .collect(),
+            "stream" => root
+                .named_children(&mut cursor)
+                .filter(|document| document.kind() == "document")
+                .filter_map(|document| {
+                    let mut cursor = document.walk();
+                    let content = document
+                        .named_children(&mut cursor)
+                        .find(|child| !is_ignored(child))?;
+                    Some(PendingNode {
+                        range: content.byte_range(),
+                        node: Some(content),
+                        key: None,
+                        parent: None,
+                        depth: 0,
+                    })
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let mut stack = documents;
+        stack.reverse();
+        while let Some(pending) = stack.pop() {
+            let node = pending.node.and_then(yaml_content);
+            let (kind, value) = match node {
+                Some(node) => classify(node, text),
+                None => (NodeKind::Null, None),
+            };
+            let index = tree.nodes.len();
+            tree.nodes.push(DocumentNode {
+                key: pending.key,
+                kind,
+                value,
+                range: pending.range,
+                parent: pending.parent,
+                children: Vec::new(),
+                depth: pending.depth,
+            });
+            match pending.parent {
+                Some(parent) => tree.nodes[parent].children.push(index),
+                None => tree.roots.push(index),
+            }
+
+            if let Some(node) = node
+                && kind.is_container()
+                && pending.depth < MAX_DEPTH
+            {
+                let first_child = stack.len();
+                push_children(node, text, index, pending.depth + 1, &mut stack);
+                stack[first_child..].reverse();
+            }
+        }
+        tree
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The deepest node containing the given offset.
+    pub fn node_at(&self, offset: usize) -> Option<usize> {
+        let mut candidates = &self.roots;
+        let mut found = None;
+        loop {
+            let position =
+                candidates.partition_point(|&index| self.nodes[index].range.start <= offset);
+            let Some(&index) = position
+                .checked_sub(1)
+                .and_then(|position| candidates.get(position))
+                .filter(|&&index| offset <= self.nodes[index].range.end)
+            else {
+                return found;
+            };
+            found = Some(index);
+            candidates = &self.nodes[index].children;
+        }
+    }
+
+    pub fn ancestors(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(self.nodes[index].parent, |&index| self.nodes[index].parent)
+    }
+
+    /// The keys leading from the root of the node's document to the node.
+    pub fn keys(&self, index: usize) -> Vec<&NodeKey> {
+        let mut keys: Vec<&NodeKey> = std::iter::once(index)
+            .chain(self.ancestors(index))
+            .filter_map(|index| self.nodes[index].key.as_ref())
+            .collect();
+        keys.reverse();
+        keys
+    }
+
+    /// The child of a node with the given key.
+    pub fn child(&self, index: usize, key: &NodeKey) -> Option<usize> {
+        let node = &self.nodes[index];
+        match (node.kind, key) {
+            (NodeKind::Array, NodeKey::Index(child_index)) => {
+                node.children.get(*child_index).copied()
+            }
+            (NodeKind::Object, NodeKey::Name(_)) => node
+                .children
+                .iter()
+                .copied()
+                .find(|&child| self.nodes[child].key.as_ref() == Some(key)),
+            _ => None,
+        }
+    }
+
+    /// The node's path as JSONPath, such as `$.spec.containers[0]['image-name']`.
+    pub fn json_path(&self, index: usize) -> String {
+        let mut path = String::from("$");
+        for key in self.keys(index) {
+            match key {
+                NodeKey::Name(name) if is_identifier(name) => {
+                    path.push('.');
+                    path.push_str(name);
+                }
+                NodeKey::Name(name) => {
+                    path.push_str("['");
+                    path.push_str(&name.replace('\\', "\\\\").replace('\'', "\\'"));
+                    path.push_str("']");
+                }
+                NodeKey::Index(child_index) => path.push_str(&format!("[{child_index}]")),
+            }
+        }
+        path
+    }
+
+    /// The node's path as JMESPath, such as `spec.containers[0]."image-name"`.
+    pub fn jmes_path(&self, index: usize) -> String {
+        let mut path = String::new();
+        for key in self.keys(index) {
+            match key {
+                NodeKey::Name(name) => {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    if is_identifier(name) {
+                        path.push_str(name);
+                    } else {
+                        path.push_str(
+                            &serde_json::to_string(name).unwrap_or_else(|_| name.clone()),
+                        );
+                    }
+                }
+                NodeKey::Index(child_index) => path.push_str(&format!("[{child_index}]")),
+            }
+        }
+        if path.is_empty() {
+            path.push('@');
+        }
+        path
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut characters = name.chars();
+    characters
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && characters.all(|character| character.is_ascii_alphanumeric() || character == '_')
+}
+
+fn is_ignored(node: &Node) -> bool {
+    matches!(node.kind(), "comment" | "anchor" | "tag" | "ERROR")
+}
+
+/// Unwraps YAML's `block_node` and `flow_node` wrappers, skipping anchors and tags.
+fn yaml_content(node: Node) -> Option<Node> {
+    match node.kind() {
+        "block_node" | "flow_node" => {
+            let mut cursor = node.walk();
+            let content = node
+                .named_children(&mut cursor)
+                .find(|child| !is_ignored(child))?;
+            yaml_content(content)
+        }
+        _ => Some(node),
+    }
+}
+
+fn classify(node: Node, text: &str) -> (NodeKind, Option<String>) {
+    let source = &text[node.byte_range()];
+    match node.kind() {
+        "object" | "block_mapping" | "flow_mapping" | "flow_pair" => (NodeKind::Object, None),
+        "array" | "block_sequence" | "flow_sequence" => (NodeKind::Array, None),
+        "string" | "double_quote_scalar" => (NodeKind::String, Some(unquote_double(source))),
+        "single_quote_scalar" => (NodeKind::String, Some(unquote_single(source))),
+        "block_scalar" => (NodeKind::String, Some(block_scalar_value(source))),
+        "number" => (NodeKind::Number, Some(source.to_string())),
+        "true" | "false" => (NodeKind::Boolean, Some(source.to_string())),
+        "null" => (NodeKind::Null, Some(source.to_string())),
+        "alias" => (NodeKind::Alias, Some(source.to_string())),
+        "plain_scalar" => {
+            let kind = node
+                .named_child(0)
+                .map_or(NodeKind::String, |scalar| match scalar.kind() {
+                    "integer_scalar" | "float_scalar" => NodeKind::Number,
+                    "boolean_scalar" => NodeKind::Boolean,
+                    "null_scalar" => NodeKind::Null,
+                    _ => NodeKind::String,
+                });
+            (kind, Some(source.trim().to_string()))
+        }
+        _ => (NodeKind::String, Some(source.trim().to_string())),
+    }
+}
+
+fn push_children<'a>(
+    node: Node<'a>,
+    text: &str,
+    parent: usize,
+    depth: usize,
+    stack: &mut Vec<PendingNode<'a>>,
+) {
+    let mut cursor = node.walk();
+    let children: Vec<Node<'a>> = node
+        .named_children(&mut cursor)
+        .filter(|child| !is_ignored(child))
+        .collect();
+    match node.kind() {
+        "object" | "block_mapping" | "flow_mapping" => {
+            for pair in children {
+                let (key_node, value) = match pair.kind() {
+                    "pair" | "block_mapping_pair" | "flow_pair" => (
+                        pair.child_by_field_name("key"),
+                        pair.child_by_field_name("value"),
+                    ),
+                    // A flow mapping entry without a colon, like `{ key }`, has a null value.
+                    "flow_node" => (Some(pair), None),
+                    _ => continue,
+                };
+                let Some(key) = key_node.map(|key_node| key_text(key_node, text)) else {
+                    continue;
+                };
+                stack.push(PendingNode {
+                    node: value,
+                    key: Some(NodeKey::Name(key)),
+                    range: pair.byte_range(),
+                    parent: Some(parent),
+                    depth,
+                });
+            }
+        }
+        "flow_pair" => {
+            let key = node
+                .child_by_field_name("key")
+                .map(|key_node| key_text(key_node, text))
+                .unwrap_or_default();
+            stack.push(PendingNode {
+                node: node.child_by_field_name("value"),
+                key: Some(NodeKey::Name(key)),
+                range: node.byte_range(),
+                parent: Some(parent),
+                depth,
+            });
+        }
+        "array" | "block_sequence" | "flow_sequence" => {
+            for (index, item) in children.into_iter().enumerate() {
+                let value = if item.kind() == "block_sequence_item" {
+                    let mut cursor = item.walk();
+                    item.named_children(&mut cursor)
+                        .find(|child| !is_ignored(child))
+                } else {
+                    Some(item)
+                };
+                stack.push(PendingNode {
+                    node: value,
+                    key: Some(NodeKey::Index(index)),
+                    range: item.byte_range(),
+                    parent: Some(parent),
+                    depth,
+                });
+            }
+        }
+        _ => {}
+    }
+}
+
+fn key_text(key: Node, text: &str) -> String {
+    match yaml_content(key) {
+        Some(key) => match classify(key, text) {
+            (_, Some(value)) => value,
+            (_, None) => text[key.byte_range()].trim().to_string(),
+        },
+        None => String::new(),
+    }
+}
+
+fn unquote_double(source: &str) -> String {
+    serde_json::from_str::<String>(source).unwrap_or_else(|_| {
+        source
+            .strip_prefix('"')
+            .and_then(|source| source.strip_suffix('"'))
+            .unwrap_or(source)
+            .to_string()
+    })
+}
+
+fn unquote_single(source: &str) -> String {
+    source
+        .strip_prefix('\'')
+        .and_then(|source| source.strip_suffix('\''))
+        .unwrap_or(source)
+        .replace("''", "'")
+}
+
+/// The text of a `|` or `>` block scalar, without its header and indentation. Folded scalars are
+/// joined into one line.
+fn block_scalar_value(source: &str) -> String {
+    let is_folded = source.starts_with('>');
+    let mut lines = source.lines().skip(1).map(str::trim);
+    let separator = if is_folded { " " } else { "\n" };
+    let mut value = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        value.push_str(separator);
+        value.push_str(line);
+    }
+    value.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json_tree(text: &str) -> DocumentTree {
+        let mut parser = language::Parser::new();
+        parser
+            .set_language(&tree_sitter_json::LANGUAGE.into())
+            .unwrap();
+        let syntax_tree = parser.parse(text, None).unwrap();
+        DocumentTree::new(syntax_tree.root_node(), text)
+    }
+
+    fn yaml_tree(text: &str) -> DocumentTree {
+        let mut parser = language::Parser::new();
+        parser
+            .set_language(&tree_sitter_yaml::LANGUAGE.into())
+            .unwrap();
+        let syntax_tree = parser.parse(text, None).unwrap();
+        DocumentTree::new(syntax_tree.root_node(), text)
+    }
+
+    fn outline(tree: &DocumentTree) -> Vec<String> {
+        tree.nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| {
+                format!(
+                    "{}{} {}{}",
+                    "  ".repeat(node.depth),
+                    tree.json_path(index),
+                    node.kind.label(),
+                    node.value
+                        .as_ref()
+                        .map(|value| format!(" {value}"))
+                        .unwrap_or_default()
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_json_tree() {
+        let text = r#"{
+            // Comments are allowed in JSONC.
+            "name": "zed",
+            "tags": ["editor", 1.5, true, null],
+            "nested key": {"a\"b": {}}
+        }"#;
+        let tree = json_tree(text);
+        assert_eq!(
+            outline(&tree),
+            [
+                "$ object",
+                "  $.name string zed",
+                "  $.tags array",
+                "    $.tags[0] string editor",
+                "    $.tags[1] number 1.5",
+                "    $.tags[2] boolean true",
+                "    $.tags[3] null null",
+                "  $['nested key'] object",
+                "    $['nested key']['a\"b'] object",
+            ]
+        );
+        assert_eq!(tree.jmes_path(8), r#""nested key"."a\"b""#);
+        assert_eq!(tree.jmes_path(0), "@");
+
+        let offset = text.find("1.5").unwrap();
+        assert_eq!(tree.node_at(offset), Some(4));
+        assert_eq!(tree.node_at(text.find("\"tags\"").unwrap()), Some(2));
+        assert_eq!(tree.node_at(0), Some(0));
+        assert_eq!(tree.child(2, &NodeKey::Index(3)), Some(6));
+        assert_eq!(tree.child(0, &NodeKey::Name("tags".into())), Some(2));
+    }
+
+    #[test]
+    fn test_yaml_tree() {
+        let text = "\
+apiVersion: v1
+kind: Pod
+metadata:
+  name: &name web
+  labels: {app: web, tier}
+spec:
+  containers:
+    - name: *name
+      ports: [80, 443]
+      command: |
+        run
+        --fast
+    -
+  enabled: true
+---
+'quoted': \"a\\tb\"
+";
+        let tree = yaml_tree(text);
+        assert_eq!(
+            outline(&tree),
+            [
+                "$ object",
+                "  $.apiVersion string v1",
+                "  $.kind string Pod",
+                "  $.metadata object",
+                "    $.metadata.name string web",
+                "    $.metadata.labels object",
+                "      $.metadata.labels.app string web",
+                "      $.metadata.labels.tier null",
+                "  $.spec object",
+                "    $.spec.containers array",
+                "      $.spec.containers[0] object",
+                "        $.spec.containers[0].name alias *name",
+                "        $.spec.containers[0].ports array",
+                "          $.spec.containers[0].ports[0] number 80",
+                "          $.spec.containers[0].ports[1] number 443",
+                "        $.spec.containers[0].command string run\n--fast",
+                "      $.spec.containers[1] null",
+                "    $.spec.enabled boolean true",
+                "$ object",
+                "  $.quoted string a\tb",
+            ]
+        );
+        assert_eq!(tree.roots, [0, 18]);
+    }
+}
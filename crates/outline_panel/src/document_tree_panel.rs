@@ -0,0 +1,1037 @@
+use std::{ops::Range, sync::Arc, time::Duration};
+
+use collections::{HashMap, HashSet};
+use editor::{Editor, EditorEvent, SelectionEffects, scroll::Autoscroll};
+use gpui::{
+    Action, App, AsyncWindowContext, ClipboardItem, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, KeyContext, ListSizingBehavior, Pixels, Render, Subscription, Task,
+    UniformListScrollHandle, WeakEntity, Window, actions, px, uniform_list,
+};
+use language::{Buffer, BufferEvent, BufferSnapshot, ToOffset as _};
+use lsp::DiagnosticSeverity;
+use project::Fs;
+use settings::Settings as _;
+use ui::{ListItem, ListItemSpacing, Tab, Tooltip, prelude::*};
+use util::size::format_file_size;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+use crate::{
+    document_query::DocumentQuery,
+    document_tree::{DocumentTree, NodeKey, NodeKind},
+    outline_panel_settings::DocumentTreePanelSettings,
+};
+
+actions!(
+    document_tree_panel,
+    [
+        /// Toggles focus on the document tree panel.
+        ToggleFocus,
+        /// Copies the JSONPath of the selected node.
+        CopyJsonPath,
+        /// Copies the JMESPath of the selected node.
+        CopyJmesPath,
+        /// Expands the selected node.
+        ExpandSelectedNode,
+        /// Collapses the selected node, or selects its parent if it's already collapsed.
+        CollapseSelectedNode,
+    ]
+);
+
+const DOCUMENT_TREE_PANEL_KEY: &str = "DocumentTreePanel";
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(50);
+/// Nodes shallower than this are expanded until they're collapsed.
+const DEFAULT_EXPANDED_DEPTH: usize = 1;
+const MAX_VALUE_PREVIEW_CHARS: usize = 80;
+
+/// Shows the structure of the active JSON, JSONC or YAML file as a tree, along with the errors and
+/// warnings of the file's language server, which validates it against its schema.
+pub struct DocumentTreePanel {
+    workspace: WeakEntity<Workspace>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    query_editor: Entity<Editor>,
+    scroll_handle: UniformListScrollHandle,
+    active_document: Option<ActiveDocument>,
+    tree: Arc<DocumentTree>,
+    problems: Vec<DocumentProblem>,
+    /// The errors and warnings of each node, including those of its descendants.
+    problem_counts: Vec<ProblemCounts>,
+    query_matches: Option<QueryMatches>,
+    query_error: Option<SharedString>,
+    /// Nodes expanded or collapsed by the user, by their path, which stays the same across edits.
+    expanded_overrides: HashMap<String, bool>,
+    rows: Vec<usize>,
+    selected_node: Option<usize>,
+    show_problems: bool,
+    tree_update_task: Task<()>,
+    query_update_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct ActiveDocument {
+    editor: Entity<Editor>,
+    buffer: Entity<Buffer>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct DocumentProblem {
+    node: Option<usize>,
+    range: Range<usize>,
+    severity: DiagnosticSeverity,
+    message: String,
+}
+
+#[derive(Clone, Copy, Default)]
+struct ProblemCounts {
+    errors: usize,
+    warnings: usize,
+}
+
+struct QueryMatches {
+    nodes: HashSet<usize>,
+    ancestors: HashSet<usize>,
+}
+
+impl DocumentTreePanel {
+    pub async fn load(
+        workspace: WeakEntity<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<Entity<Self>> {
+        workspace.update_in(&mut cx, |workspace, window, cx| {
+            let fs = workspace.app_state().fs.clone();
+            let workspace_handle = workspace.weak_handle();
+            let workspace_entity = cx.entity();
+            let active_editor = workspace
+                .active_item(cx)
+                .and_then(|item| item.act_as::<Editor>(cx));
+            cx.new(|cx| {
+                let query_editor = cx.new(|cx| {
+                    let mut editor = Editor::single_line(window, cx);
+                    editor.set_placeholder_text(
+                        "Filter by text, JSONPath or JMESPath…",
+                        window,
+                        cx,
+                    );
+                    editor
+                });
+                let subscriptions = vec![
+                    cx.subscribe_in(&query_editor, window, |this, _, event, _, cx| {
+                        if let EditorEvent::BufferEdited = event {
+                            this.update_query_matches(Some(UPDATE_DEBOUNCE), cx);
+                        }
+                    }),
+                    cx.subscribe_in(
+                        &workspace_entity,
+                        window,
+                        |this, workspace, event, window, cx| {
+                            if let workspace::Event::ActiveItemChanged = event {
+                                let active_editor = workspace
+                                    .read(cx)
+                                    .active_item(cx)
+                                    .and_then(|item| item.act_as::<Editor>(cx));
+                                this.set_active_editor(active_editor, window, cx);
+                            }
+                        },
+                    ),
+                    cx.observe_global::<settings::SettingsStore>(|_, cx| cx.notify()),
+                ];
+                let mut this = Self {
+                    workspace: workspace_handle,
+                    fs,
+                    focus_handle: cx.focus_handle(),
+                    width: None,
+                    query_editor,
+                    scroll_handle: UniformListScrollHandle::new(),
+                    active_document: None,
+                    tree: Arc::default(),
+                    problems: Vec::new(),
+                    problem_counts: Vec::new(),
+                    query_matches: None,
+                    query_error: None,
+                    expanded_overrides: HashMap::default(),
+                    rows: Vec::new(),
+                    selected_node: None,
+                    show_problems: true,
+                    tree_update_task: Task::ready(()),
+                    query_update_task: Task::ready(()),
+                    _subscriptions: subscriptions,
+                };
+                this.set_active_editor(active_editor, window, cx);
+                this
+            })
+        })
+    }
+
+    fn set_active_editor(
+        &mut self,
+        editor: Option<Entity<Editor>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Focusing the panel makes no item active, which shouldn't clear the tree.
+        let Some(editor) = editor.filter(|editor| editor.read(cx).mode().is_full()) else {
+            return;
+        };
+        if self
+            .active_document
+            .as_ref()
+            .is_some_and(|document| document.editor == editor)
+        {
+            return;
+        }
+        let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+            self.active_document = None;
+            self.clear_tree(cx);
+            return;
+        };
+
+        let subscriptions = vec![
+            cx.subscribe(&buffer, |this, _, event, cx| match event {
+                BufferEvent::Reparsed
+                | BufferEvent::DiagnosticsUpdated
+                | BufferEvent::LanguageChanged => this.update_tree(Some(UPDATE_DEBOUNCE), cx),
+                _ => {}
+            }),
+            cx.subscribe_in(&editor, window, |this, _, event, _, cx| {
+                if let EditorEvent::SelectionsChanged { local: true } = event {
+                    this.select_node_at_cursor(cx);
+                }
+            }),
+        ];
+        self.active_document = Some(ActiveDocument {
+            editor,
+            buffer,
+            _subscriptions: subscriptions,
+        });
+        self.expanded_overrides.clear();
+        self.selected_node = None;
+        self.update_tree(None, cx);
+    }
+
+    fn clear_tree(&mut self, cx: &mut Context<Self>) {
+        self.tree_update_task = Task::ready(());
+        self.tree = Arc::default();
+        self.problems.clear();
+        self.problem_counts.clear();
+        self.query_matches = None;
+        self.selected_node = None;
+        self.rows.clear();
+        cx.notify();
+    }
+
+    fn update_tree(&mut self, debounce: Option<Duration>, cx: &mut Context<Self>) {
+        let Some(document) = &self.active_document else {
+            return;
+        };
+        let buffer = document.buffer.read(cx);
+        if !buffer
+            .language()
+            .is_some_and(|language| is_document_language(language.name().as_ref()))
+        {
+            self.clear_tree(cx);
+            return;
+        }
+        let snapshot = buffer.snapshot();
+        self.tree_update_task = cx.spawn(async move |this, cx| {
+            if let Some(debounce) = debounce {
+                cx.background_executor().timer(debounce).await;
+            }
+            let (tree, problems, problem_counts) = cx
+                .background_spawn(async move { build_document(&snapshot) })
+                .await;
+            this.update(cx, |this, cx| {
+                this.tree = Arc::new(tree);
+                this.problems = problems;
+                this.problem_counts = problem_counts;
+                this.selected_node = None;
+                this.update_query_matches(None, cx);
+                this.select_node_at_cursor(cx);
+            })
+            .ok();
+        });
+    }
+
+    fn update_query_matches(&mut self, debounce: Option<Duration>, cx: &mut Context<Self>) {
+        let query = self.query_editor.read(cx).text(cx);
+        if query.trim().is_empty() {
+            self.query_update_task = Task::ready(());
+            self.query_matches = None;
+            self.query_error = None;
+            self.update_rows(cx);
+            return;
+        }
+        let tree = self.tree.clone();
+        self.query_update_task = cx.spawn(async move |this, cx| {
+            if let Some(debounce) = debounce {
+                cx.background_executor().timer(debounce).await;
+            }
+            let result = cx
+                .background_spawn(async move {
+                    let query = DocumentQuery::parse(&query)?;
+                    let nodes: HashSet<usize> = query.matches(&tree).into_iter().collect();
+                    let ancestors = nodes
+                        .iter()
+                        .flat_map(|&node| tree.ancestors(node))
+                        .collect();
+                    anyhow::Ok(QueryMatches { nodes, ancestors })
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(query_matches) => {
+                        this.query_matches = Some(query_matches);
+                        this.query_error = None;
+                    }
+                    // Incomplete paths are common while typing, so the tree stays as it was.
+                    Err(error) => this.query_error = Some(error.to_string().into()),
+                }
+                this.update_rows(cx);
+            })
+            .ok();
+        });
+    }
+
+    fn is_expanded(&self, index: usize) -> bool {
+        let node = &self.tree.nodes[index];
+        if !node.kind.is_container() || node.children.is_empty() {
+            return false;
+        }
+        if let Some(query_matches) = &self.query_matches
+            && query_matches.ancestors.contains(&index)
+        {
+            return true;
+        }
+        self.expanded_overrides
+            .get(&self.expansion_key(index))
+            .copied()
+            .unwrap_or(self.query_matches.is_none() && node.depth < DEFAULT_EXPANDED_DEPTH)
+    }
+
+    fn expansion_key(&self, index: usize) -> String {
+        let root = self.tree.ancestors(index).last().unwrap_or(index);
+        let document = self
+            .tree
+            .roots
+            .iter()
+            .position(|&candidate| candidate == root)
+            .unwrap_or_default();
+        format!("{document}{}", self.tree.json_path(index))
+    }
+
+    /// Lists the visible nodes. While filtering, only the matching nodes and their ancestors are
+    /// shown, along with the descendants of matching nodes that get expanded.
+    fn update_rows(&mut self, cx: &mut Context<Self>) {
+        self.rows.clear();
+        let query_matches = self.query_matches.as_ref();
+        let mut stack: Vec<(usize, bool)> = self
+            .tree
+            .roots
+            .iter()
+            .rev()
+            .map(|&root| (root, query_matches.is_none()))
+            .collect();
+        while let Some((index, shows_all_children)) = stack.pop() {
+            if !shows_all_children
+                && query_matches.is_some_and(|query_matches| {
+                    !query_matches.nodes.contains(&index)
+                        && !query_matches.ancestors.contains(&index)
+                })
+            {
+                continue;
+            }
+            self.rows.push(index);
+            if self.is_expanded(index) {
+                let shows_all_children = shows_all_children
+                    || query_matches
+                        .is_some_and(|query_matches| query_matches.nodes.contains(&index));
+                stack.extend(
+                    self.tree.nodes[index]
+                        .children
+                        .iter()
+                        .rev()
+                        .map(|&child| (child, shows_all_children)),
+                );
+            }
+        }
+        cx.notify();
+    }
+
+    fn set_expanded(&mut self, index: usize, expanded: bool, cx: &mut Context<Self>) {
+        let key = self.expansion_key(index);
+        self.expanded_overrides.insert(key, expanded);
+        self.update_rows(cx);
+    }
+
+    fn toggle_expanded(&mut self, index: usize, cx: &mut Context<Self>) {
+        let expanded = self.is_expanded(index);
+        self.set_expanded(index, !expanded, cx);
+    }
+
+    fn expand_selected_node(
+        &mut self,
+        _: &ExpandSelectedNode,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(index) = self.selected_node {
+            self.set_expanded(index, true, cx);
+        }
+    }
+
+    fn collapse_selected_node(
+        &mut self,
+        _: &CollapseSelectedNode,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(index) = self.selected_node else {
+            return;
+        };
+        if self.is_expanded(index) {
+            self.set_expanded(index, false, cx);
+        } else if let Some(parent) = self.tree.nodes[index].parent {
+            self.select_node(parent, cx);
+        }
+    }
+
+    fn select_node(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.selected_node = Some(index);
+        if let Some(row) = self.rows.iter().position(|&row| row == index) {
+            self.scroll_handle
+                .scroll_to_item(row, gpui::ScrollStrategy::Center);
+        }
+        cx.notify();
+    }
+
+    /// Selects the deepest node containing the active editor's cursor, expanding its ancestors.
+    fn select_node_at_cursor(&mut self, cx: &mut Context<Self>) {
+        let Some(document) = &self.active_document else {
+            return;
+        };
+        let snapshot = document.buffer.read(cx).snapshot();
+        let cursor = document
+            .editor
+            .read(cx)
+            .selections
+            .newest_anchor()
+            .head()
+            .text_anchor
+            .to_offset(&snapshot);
+        let Some(index) = self.tree.node_at(cursor) else {
+            return;
+        };
+        if self.selected_node == Some(index) {
+            return;
+        }
+        let ancestors: Vec<usize> = self.tree.ancestors(index).collect();
+        for ancestor in ancestors {
+            if !self.is_expanded(ancestor) {
+                let key = self.expansion_key(ancestor);
+                self.expanded_overrides.insert(key, true);
+            }
+        }
+        self.update_rows(cx);
+        self.select_node(index, cx);
+    }
+
+    fn reveal_in_editor(
+        &mut self,
+        range: Range<usize>,
+        focus_editor: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(document) = &self.active_document else {
+            return;
+        };
+        let editor = document.editor.clone();
+        let snapshot = document.buffer.read(cx).snapshot();
+        let start = snapshot.anchor_before(range.start.min(snapshot.len()));
+        let end = snapshot.anchor_after(range.end.min(snapshot.len()));
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.activate_item(&editor, true, focus_editor, window, cx)
+            })
+            .ok();
+        editor.update(cx, |editor, cx| {
+            let multi_buffer_snapshot = editor.buffer().read(cx).snapshot(cx);
+            let (Some(start), Some(end)) = (
+                multi_buffer_snapshot.as_singleton_anchor(start),
+                multi_buffer_snapshot.as_singleton_anchor(end),
+            ) else {
+                return;
+            };
+            editor.change_selections(
+                SelectionEffects::scroll(Autoscroll::center()),
+                window,
+                cx,
+                |selections| selections.select_ranges([start..end]),
+            );
+        });
+        if !focus_editor {
+            window.focus(&self.focus_handle);
+        }
+    }
+
+    fn open_node(
+        &mut self,
+        index: usize,
+        focus_editor: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.select_node(index, cx);
+        let start = self.tree.nodes[index].range.start;
+        self.reveal_in_editor(start..start, focus_editor, window, cx);
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let next_row = match self
+            .selected_node
+            .and_then(|index| self.rows.iter().position(|&row| row == index))
+        {
+            Some(row) => row + 1,
+            None => 0,
+        };
+        if let Some(&index) = self.rows.get(next_row) {
+            self.select_node(index, cx);
+        }
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let previous_row = match self
+            .selected_node
+            .and_then(|index| self.rows.iter().position(|&row| row == index))
+        {
+            Some(row) => row.checked_sub(1),
+            None => self.rows.len().checked_sub(1),
+        };
+        if let Some(&index) = previous_row.and_then(|row| self.rows.get(row)) {
+            self.select_node(index, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.query_editor.focus_handle(cx).is_focused(window) {
+            if let Some(&index) = self.rows.first() {
+                self.open_node(index, false, window, cx);
+            }
+        } else if let Some(index) = self.selected_node {
+            self.open_node(index, true, window, cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.query_editor.read(cx).is_empty(cx) {
+            window.focus(&self.focus_handle);
+        } else {
+            self.query_editor
+                .update(cx, |editor, cx| editor.set_text("", window, cx));
+        }
+    }
+
+    fn copy_json_path(&mut self, _: &CopyJsonPath, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(index) = self.selected_node {
+            cx.write_to_clipboard(ClipboardItem::new_string(self.tree.json_path(index)));
+        }
+    }
+
+    fn copy_jmes_path(&mut self, _: &CopyJmesPath, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(index) = self.selected_node {
+            cx.write_to_clipboard(ClipboardItem::new_string(self.tree.jmes_path(index)));
+        }
+    }
+
+    fn dispatch_context(&self, window: &mut Window, cx: &mut Context<Self>) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("DocumentTreePanel");
+        dispatch_context.add("menu");
+        let identifier = if self.query_editor.focus_handle(cx).is_focused(window) {
+            "editing"
+        } else {
+            "not_editing"
+        };
+        dispatch_context.add(identifier);
+        dispatch_context
+    }
+
+    fn node_label(&self, index: usize) -> String {
+        let node = &self.tree.nodes[index];
+        match &node.key {
+            Some(NodeKey::Name(name)) => name.clone(),
+            Some(NodeKey::Index(child_index)) => format!("[{child_index}]"),
+            None if self.tree.roots.len() > 1 => {
+                let document = self
+                    .tree
+                    .roots
+                    .iter()
+                    .position(|&root| root == index)
+                    .unwrap_or_default();
+                format!("Document {}", document + 1)
+            }
+            None => "Document".to_string(),
+        }
+    }
+
+    fn render_row(&self, index: usize, window: &mut Window, cx: &mut Context<Self>) -> AnyElement {
+        let node = &self.tree.nodes[index];
+        let is_selected = self.selected_node == Some(index);
+        let is_match = self
+            .query_matches
+            .as_ref()
+            .is_some_and(|query_matches| query_matches.nodes.contains(&index));
+        let details = match node.kind {
+            NodeKind::Object | NodeKind::Array => {
+                let count = node.children.len();
+                let unit = match (node.kind, count) {
+                    (NodeKind::Object, 1) => "key",
+                    (NodeKind::Object, _) => "keys",
+                    (_, 1) => "item",
+                    _ => "items",
+                };
+                format!(
+                    "{count} {unit} · {}",
+                    format_file_size(node.range.len() as u64, true)
+                )
+            }
+            _ => node.value.as_deref().map(value_preview).unwrap_or_default(),
+        };
+        let counts = self.problem_counts.get(index).copied().unwrap_or_default();
+        let problem_indicator = (counts.errors > 0 || counts.warnings > 0).then(|| {
+            let (icon, color, count) = if counts.errors > 0 {
+                (IconName::XCircle, Color::Error, counts.errors)
+            } else {
+                (IconName::Warning, Color::Warning, counts.warnings)
+            };
+            h_flex()
+                .gap_0p5()
+                .child(Icon::new(icon).size(IconSize::XSmall).color(color))
+                .child(
+                    Label::new(count.to_string())
+                        .size(LabelSize::Small)
+                        .color(color),
+                )
+        });
+        let is_focused = self.focus_handle.contains_focused(window, cx);
+
+        ListItem::new(("document-tree-node", index))
+            .spacing(ListItemSpacing::Dense)
+            .indent_level(node.depth)
+            .indent_step_size(px(12.))
+            .toggle_state(is_selected)
+            .when(
+                node.kind.is_container() && !node.children.is_empty(),
+                |item| {
+                    item.toggle(self.is_expanded(index)).on_toggle(
+                        cx.listener(move |this, _, _, cx| this.toggle_expanded(index, cx)),
+                    )
+                },
+            )
+            .child(
+                h_flex()
+                    .gap_1p5()
+                    .overflow_hidden()
+                    .child(
+                        Label::new(self.node_label(index))
+                            .single_line()
+                            .when(is_match, |label| label.color(Color::Accent)),
+                    )
+                    .child(
+                        Label::new(node.kind.label())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(details)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .single_line()
+                            .truncate(),
+                    ),
+            )
+            .end_slot(problem_indicator)
+            .when(is_selected && is_focused, |item| item.focused(true))
+            .on_click(
+                cx.listener(move |this, event: &gpui::ClickEvent, window, cx| {
+                    this.open_node(index, event.click_count() > 1, window, cx);
+                }),
+            )
+            .into_any_element()
+    }
+
+    fn render_query_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let selected_path = self.selected_node.map(|index| self.tree.json_path(index));
+        h_flex()
+            .p_2()
+            .h(Tab::container_height(cx))
+            .gap_1p5()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Icon::new(IconName::MagnifyingGlass)
+                    .size(IconSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(div().flex_1().child(self.query_editor.clone()))
+            .when_some(selected_path, |bar, path| {
+                bar.child(
+                    IconButton::new("document-tree-copy-path", IconName::Copy)
+                        .icon_size(IconSize::Small)
+                        .tooltip(move |_, cx| {
+                            Tooltip::with_meta(
+                                "Copy JSONPath",
+                                Some(&CopyJsonPath),
+                                path.clone(),
+                                cx,
+                            )
+                        })
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.copy_json_path(&CopyJsonPath, window, cx)
+                        })),
+                )
+            })
+    }
+
+    fn render_problems(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let (errors, warnings) =
+            self.problems
+                .iter()
+                .fold((0, 0), |(errors, warnings), problem| {
+                    if problem.severity == DiagnosticSeverity::ERROR {
+                        (errors + 1, warnings)
+                    } else {
+                        (errors, warnings + 1)
+                    }
+                });
+        let mut groups: Vec<(Option<usize>, Vec<usize>)> = Vec::new();
+        for (problem_index, problem) in self.problems.iter().enumerate() {
+            match groups.last_mut() {
+                Some((node, problems)) if *node == problem.node => problems.push(problem_index),
+                _ => groups.push((problem.node, vec![problem_index])),
+            }
+        }
+
+        v_flex()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                ListItem::new("document-tree-problems")
+                    .spacing(ListItemSpacing::Dense)
+                    .toggle(self.show_problems)
+                    .on_toggle(cx.listener(|this, _, _, cx| {
+                        this.show_problems = !this.show_problems;
+                        cx.notify();
+                    }))
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.show_problems = !this.show_problems;
+                        cx.notify();
+                    }))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new("Problems").size(LabelSize::Small))
+                            .child(
+                                Label::new(format!("{errors} errors, {warnings} warnings"))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    ),
+            )
+            .when(self.show_problems, |panel| {
+                panel.child(
+                    v_flex()
+                        .id("document-tree-problem-list")
+                        .max_h_64()
+                        .overflow_y_scroll()
+                        .children(groups.into_iter().map(|(node, problem_indices)| {
+                            let path = node.map_or_else(
+                                || "Outside of the document".to_string(),
+                                |node| self.tree.json_path(node),
+                            );
+                            v_flex()
+                                .child(
+                                    ListItem::new((
+                                        "document-tree-problem-node",
+                                        problem_indices[0],
+                                    ))
+                                    .spacing(ListItemSpacing::Dense)
+                                    .child(
+                                        Label::new(path)
+                                            .size(LabelSize::Small)
+                                            .buffer_font(cx)
+                                            .single_line()
+                                            .truncate(),
+                                    )
+                                    .when_some(
+                                        node,
+                                        |item, node| {
+                                            item.on_click(cx.listener(
+                                                move |this, _, window, cx| {
+                                                    this.select_node_with_ancestors(node, cx);
+                                                    this.open_node(node, false, window, cx);
+                                                },
+                                            ))
+                                        },
+                                    ),
+                                )
+                                .children(
+                                    problem_indices.into_iter().map(|problem_index| {
+                                        self.render_problem(problem_index, cx)
+                                    }),
+                                )
+                        })),
+                )
+            })
+    }
+
+    fn render_problem(&self, problem_index: usize, cx: &mut Context<Self>) -> impl IntoElement {
+        let problem = &self.problems[problem_index];
+        let (icon, color) = if problem.severity == DiagnosticSeverity::ERROR {
+            (IconName::XCircle, Color::Error)
+        } else {
+            (IconName::Warning, Color::Warning)
+        };
+        let range = problem.range.clone();
+        ListItem::new(("document-tree-problem", problem_index))
+            .spacing(ListItemSpacing::Dense)
+            .indent_level(1)
+            .indent_step_size(px(12.))
+            .start_slot(Icon::new(icon).size(IconSize::XSmall).color(color))
+            .child(
+                Label::new(
+                    problem
+                        .message
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+                .size(LabelSize::Small)
+                .single_line()
+                .truncate(),
+            )
+            .tooltip(Tooltip::text(problem.message.clone()))
+            .on_click(
+                cx.listener(move |this, event: &gpui::ClickEvent, window, cx| {
+                    this.reveal_in_editor(range.clone(), event.click_count() > 1, window, cx);
+                }),
+            )
+    }
+
+    fn select_node_with_ancestors(&mut self, index: usize, cx: &mut Context<Self>) {
+        let ancestors: Vec<usize> = self.tree.ancestors(index).collect();
+        for ancestor in ancestors {
+            let key = self.expansion_key(ancestor);
+            self.expanded_overrides.insert(key, true);
+        }
+        self.update_rows(cx);
+    }
+
+    fn render_empty_state(&self, message: &'static str) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .p_4()
+            .items_center()
+            .justify_center()
+            .child(Label::new(message).color(Color::Muted))
+    }
+}
+
+fn is_document_language(name: &str) -> bool {
+    matches!(name, "JSON" | "JSONC" | "YAML")
+}
+
+fn build_document(
+    snapshot: &BufferSnapshot,
+) -> (DocumentTree, Vec<DocumentProblem>, Vec<ProblemCounts>) {
+    let text = snapshot.text();
+    let tree = snapshot
+        .syntax_layers()
+        .next()
+        .map(|layer| DocumentTree::new(layer.node(), &text))
+        .unwrap_or_default();
+
+    let mut problems: Vec<DocumentProblem> = snapshot
+        .diagnostics_in_range::<_, usize>(0..snapshot.len(), false)
+        .filter(|entry| {
+            entry.diagnostic.is_primary && entry.diagnostic.severity <= DiagnosticSeverity::WARNING
+        })
+        .map(|entry| DocumentProblem {
+            node: tree.node_at(entry.range.start),
+            range: entry.range.clone(),
+            severity: entry.diagnostic.severity,
+            message: entry.diagnostic.message.clone(),
+        })
+        .collect();
+    problems.sort_by_key(|problem| (problem.node, problem.range.start));
+
+    let mut problem_counts = vec![ProblemCounts::default(); tree.nodes.len()];
+    for problem in &problems {
+        let Some(node) = problem.node else {
+            continue;
+        };
+        for index in std::iter::once(node).chain(tree.ancestors(node)) {
+            let counts = &mut problem_counts[index];
+            if problem.severity == DiagnosticSeverity::ERROR {
+                counts.errors += 1;
+            } else {
+                counts.warnings += 1;
+            }
+        }
+    }
+    (tree, problems, problem_counts)
+}
+
+fn value_preview(value: &str) -> String {
+    let mut preview: String = value
+        .chars()
+        .take(MAX_VALUE_PREVIEW_CHARS)
+        .map(|character| {
+            if character.is_control() {
+                ' '
+            } else {
+                character
+            }
+        })
+        .collect();
+    if value.chars().nth(MAX_VALUE_PREVIEW_CHARS).is_some() {
+        preview.push('…');
+    }
+    preview
+}
+
+impl Render for DocumentTreePanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let row_count = self.rows.len();
+        let contents = if self.active_document.is_none() || self.tree.is_empty() {
+            self.render_empty_state("Open a JSON or YAML file to see its structure")
+                .into_any_element()
+        } else if row_count == 0 {
+            self.render_empty_state("No nodes match the query")
+                .into_any_element()
+        } else {
+            uniform_list(
+                "document-tree-rows",
+                row_count,
+                cx.processor(|this, range: Range<usize>, window, cx| {
+                    range
+                        .filter_map(|row| this.rows.get(row).copied())
+                        .map(|index| this.render_row(index, window, cx))
+                        .collect()
+                }),
+            )
+            .with_sizing_behavior(ListSizingBehavior::Infer)
+            .track_scroll(&self.scroll_handle)
+            .size_full()
+            .into_any_element()
+        };
+
+        v_flex()
+            .id("document-tree-panel")
+            .size_full()
+            .overflow_hidden()
+            .key_context(self.dispatch_context(window, cx))
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::expand_selected_node))
+            .on_action(cx.listener(Self::collapse_selected_node))
+            .on_action(cx.listener(Self::copy_json_path))
+            .on_action(cx.listener(Self::copy_jmes_path))
+            .child(self.render_query_bar(cx))
+            .when_some(self.query_error.clone(), |panel, error| {
+                panel.child(
+                    h_flex()
+                        .px_2()
+                        .py_1()
+                        .gap_1()
+                        .child(
+                            Icon::new(IconName::Warning)
+                                .size(IconSize::XSmall)
+                                .color(Color::Warning),
+                        )
+                        .child(Label::new(error).size(LabelSize::Small).color(Color::Muted)),
+                )
+            })
+            .child(v_flex().flex_1().min_h_0().child(contents))
+            .when(!self.problems.is_empty(), |panel| {
+                panel.child(self.render_problems(cx))
+            })
+    }
+}
+
+impl Focusable for DocumentTreePanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for DocumentTreePanel {}
+
+impl Panel for DocumentTreePanel {
+    fn persistent_name() -> &'static str {
+        "DocumentTreePanel"
+    }
+
+    fn panel_key() -> &'static str {
+        DOCUMENT_TREE_PANEL_KEY
+    }
+
+    fn position(&self, _window: &Window, cx: &App) -> DockPosition {
+        DocumentTreePanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(
+        &mut self,
+        position: DockPosition,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings.document_tree_panel.get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn size(&self, _window: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| DocumentTreePanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _window: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _window: &Window, cx: &App) -> Option<IconName> {
+        DocumentTreePanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::Json)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _cx: &App) -> Option<&'static str> {
+        Some("Document Tree Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        4
+    }
+}
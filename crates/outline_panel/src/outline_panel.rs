@@ -1,3 +1,6 @@
+mod document_query;
+mod document_tree;
+pub mod document_tree_panel;
 mod outline_panel_settings;
 
 use anyhow::Context as _;
@@ -38,6 +41,7 @@ use std::{
     u32,
 };
 
+pub use document_tree_panel::DocumentTreePanel;
 use outline_panel_settings::{DockSide, OutlinePanelSettings, ShowIndentGuides};
 use project::{File, Fs, GitEntry, GitTraversal, Project, ProjectItem};
 use search::{BufferSearchBar, ProjectSearchView};
@@ -657,6 +661,11 @@ pub fn init(cx: &mut App) {
         workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
             workspace.toggle_panel_focus::<OutlinePanel>(window, cx);
         });
+        workspace.register_action(
+            |workspace, _: &document_tree_panel::ToggleFocus, window, cx| {
+                workspace.toggle_panel_focus::<DocumentTreePanel>(window, cx);
+            },
+        );
     })
     .detach();
 }
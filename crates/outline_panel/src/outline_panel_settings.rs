@@ -3,6 +3,7 @@ use gpui::{App, Pixels};
 use settings::RegisterSetting;
 pub use settings::{DockSide, Settings, ShowIndentGuides};
 use ui::scrollbars::{ScrollbarVisibility, ShowScrollbar};
+use workspace::dock::DockPosition;
 
 #[derive(Debug, Clone, Copy, PartialEq, RegisterSetting)]
 pub struct OutlinePanelSettings {
@@ -64,3 +65,21 @@ impl Settings for OutlinePanelSettings {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, RegisterSetting)]
+pub struct DocumentTreePanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
+impl Settings for DocumentTreePanelSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let panel = content.document_tree_panel.as_ref().unwrap();
+        Self {
+            button: panel.button.unwrap(),
+            dock: panel.dock.unwrap().into(),
+            default_width: panel.default_width.map(gpui::px).unwrap(),
+        }
+    }
+}
@@ -197,6 +197,18 @@ pub fn database_dir() -> &'static PathBuf {
     DATABASE_DIR.get_or_init(|| data_dir().join("db"))
 }
 
+/// Returns the path to the directory where the local history of files is stored.
+pub fn local_history_dir() -> &'static PathBuf {
+    static LOCAL_HISTORY_DIR: OnceLock<PathBuf> = OnceLock::new();
+    LOCAL_HISTORY_DIR.get_or_init(|| data_dir().join("local_history"))
+}
+
+/// Returns the path to the directory where spell checking dictionaries are looked up.
+pub fn dictionaries_dir() -> &'static PathBuf {
+    static DICTIONARIES_DIR: OnceLock<PathBuf> = OnceLock::new();
+    DICTIONARIES_DIR.get_or_init(|| data_dir().join("dictionaries"))
+}
+
 /// Returns the path to the crashes directory, if it exists for the current platform.
 pub fn crashes_dir() -> &'static Option<PathBuf> {
     static CRASHES_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
@@ -455,6 +467,15 @@ pub fn local_debug_file_relative_path() -> &'static RelPath {
     *CACHED
 }
 
+/// Returns the relative path to a `words.txt` file within a project, listing the words the spell
+/// checker accepts in that project.
+/// .zed/words.txt
+pub fn local_spell_check_words_file_relative_path() -> &'static RelPath {
+    static CACHED: LazyLock<&'static RelPath> =
+        LazyLock::new(|| RelPath::unix(".zed/words.txt").unwrap());
+    *CACHED
+}
+
 /// Returns the relative path to a `.vscode/launch.json` file within a project.
 pub fn local_vscode_launch_file_relative_path() -> &'static RelPath {
     static CACHED: LazyLock<&'static RelPath> =
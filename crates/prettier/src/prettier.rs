@@ -3,13 +3,15 @@ use collections::{HashMap, HashSet};
 use fs::Fs;
 use gpui::{AsyncApp, Entity};
 use language::language_settings::PrettierSettings;
-use language::{Buffer, Diff, Language, language_settings::language_settings};
+use language::{
+    Anchor, Buffer, Diff, Language, ToOffsetUtf16, language_settings::language_settings,
+};
 use lsp::{LanguageServer, LanguageServerId};
 use node_runtime::NodeRuntime;
 use paths::default_prettier_dir;
 use serde::{Deserialize, Serialize};
 use std::{
-    ops::ControlFlow,
+    ops::{ControlFlow, Range},
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -344,6 +346,7 @@ impl Prettier {
         buffer: &Entity<Buffer>,
         buffer_path: Option<PathBuf>,
         ignore_dir: Option<PathBuf>,
+        range: Option<Range<Anchor>>,
         cx: &mut AsyncApp,
     ) -> anyhow::Result<Diff> {
         match self {
@@ -465,6 +468,11 @@ impl Prettier {
                             ignore_path,
                         );
 
+                        // Prettier measures ranges in UTF-16 code units, like JavaScript strings.
+                        let range = range.map(|range| {
+                            range.start.to_offset_utf16(buffer).0..range.end.to_offset_utf16(buffer).0
+                        });
+
                         anyhow::Ok(FormatParams {
                             text: buffer.text(),
                             options: FormatOptions {
@@ -473,6 +481,8 @@ impl Prettier {
                                 plugins,
                                 prettier_options,
                                 ignore_path,
+                                range_start: range.as_ref().map(|range| range.start),
+                                range_end: range.map(|range| range.end),
                             },
                         })
                 })?
@@ -650,6 +660,8 @@ struct FormatOptions {
     path: Option<PathBuf>,
     prettier_options: Option<HashMap<String, serde_json::Value>>,
     ignore_path: Option<PathBuf>,
+    range_start: Option<usize>,
+    range_end: Option<usize>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -0,0 +1,522 @@
+//! Module for managing bookmarks in a project.
+//!
+//! Bookmarks are kept per file. Once a file is opened, its bookmarks are anchored in the buffer so that
+//! they follow edits. Each bookmark also remembers the text of its line as of the last load or save, which
+//! is used to re-anchor it when the file was changed outside of the editor.
+use std::{path::Path, sync::Arc};
+
+use collections::BTreeMap;
+use gpui::{App, Context, Entity, EventEmitter, Subscription};
+use language::{Buffer, BufferEvent, DiskState};
+use text::{BufferSnapshot, Point};
+
+use crate::buffer_store::{BufferStore, BufferStoreEvent};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BookmarkId(usize);
+
+/// A bookmark, as displayed in bookmark lists and stored in the workspace database.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BookmarkEntry {
+    pub id: BookmarkId,
+    pub path: Arc<Path>,
+    pub row: u32,
+    pub label: Option<Arc<str>>,
+    pub list: Option<Arc<str>>,
+    pub line_text: Arc<str>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SerializedBookmark {
+    pub row: u32,
+    pub label: Option<Arc<str>>,
+    pub list: Option<Arc<str>>,
+    pub line_text: Arc<str>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum BookmarkPosition {
+    /// The file has not been opened yet.
+    Row(u32),
+    Anchor(text::Anchor),
+}
+
+#[derive(Clone, Debug)]
+struct Bookmark {
+    id: BookmarkId,
+    position: BookmarkPosition,
+    label: Option<Arc<str>>,
+    list: Option<Arc<str>>,
+    line_text: Arc<str>,
+}
+
+impl Bookmark {
+    fn row(&self, snapshot: Option<&BufferSnapshot>) -> u32 {
+        match self.position {
+            BookmarkPosition::Row(row) => row,
+            BookmarkPosition::Anchor(anchor) => {
+                snapshot.map_or(0, |snapshot| anchor.summary::<Point>(snapshot).row)
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct BookmarksInFile {
+    buffer: Option<(Entity<Buffer>, Subscription)>,
+    /// Sorted by row.
+    bookmarks: Vec<Bookmark>,
+}
+
+impl BookmarksInFile {
+    fn snapshot(&self, cx: &App) -> Option<BufferSnapshot> {
+        self.buffer
+            .as_ref()
+            .map(|(buffer, _)| buffer.read(cx).text_snapshot())
+    }
+}
+
+pub enum BookmarkStoreEvent {
+    BookmarksUpdated,
+}
+
+impl EventEmitter<BookmarkStoreEvent> for BookmarkStore {}
+
+pub struct BookmarkStore {
+    buffer_store: Entity<BufferStore>,
+    bookmarks: BTreeMap<Arc<Path>, BookmarksInFile>,
+    next_bookmark_id: usize,
+    _subscription: Subscription,
+}
+
+impl BookmarkStore {
+    pub fn new(buffer_store: Entity<BufferStore>, cx: &mut Context<Self>) -> Self {
+        let subscription = cx.subscribe(&buffer_store, |this, _, event, cx| {
+            if let BufferStoreEvent::BufferAdded(buffer) = event
+                && let Some(abs_path) = Self::abs_path_from_buffer(buffer, cx)
+                && this.bookmarks.contains_key(&abs_path)
+            {
+                this.attach_buffer(abs_path, buffer, cx);
+                cx.notify();
+            }
+        });
+        Self {
+            buffer_store,
+            bookmarks: BTreeMap::default(),
+            next_bookmark_id: 0,
+            _subscription: subscription,
+        }
+    }
+
+    pub fn abs_path_from_buffer(buffer: &Entity<Buffer>, cx: &App) -> Option<Arc<Path>> {
+        worktree::File::from_dyn(buffer.read(cx).file())
+            .map(|file| file.worktree.read(cx).absolutize(&file.path))
+            .map(Arc::<Path>::from)
+    }
+
+    fn next_id(&mut self) -> BookmarkId {
+        let id = BookmarkId(self.next_bookmark_id);
+        self.next_bookmark_id += 1;
+        id
+    }
+
+    /// Starts tracking the bookmarks of a file in its buffer, re-anchoring the ones that were not anchored
+    /// yet by their line's content.
+    fn attach_buffer(
+        &mut self,
+        abs_path: Arc<Path>,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) {
+        let file = self.bookmarks.entry(abs_path).or_default();
+        if file
+            .buffer
+            .as_ref()
+            .is_some_and(|(attached_buffer, _)| attached_buffer == buffer)
+        {
+            return;
+        }
+
+        let snapshot = buffer.read(cx).text_snapshot();
+        let text = snapshot.text();
+        let lines = text.split('\n').collect::<Vec<_>>();
+        for bookmark in &mut file.bookmarks {
+            let row = match bookmark.position {
+                BookmarkPosition::Row(row) => row,
+                BookmarkPosition::Anchor(anchor) => anchor.summary::<Point>(&snapshot).row,
+            };
+            let row = closest_matching_row(&lines, row, &bookmark.line_text)
+                .unwrap_or_else(|| row.min(snapshot.max_point().row));
+            bookmark.position = BookmarkPosition::Anchor(snapshot.anchor_after(Point::new(row, 0)));
+        }
+        sort_and_dedup(&mut file.bookmarks, Some(&snapshot));
+
+        let subscription = cx.subscribe(buffer, Self::on_buffer_event);
+        file.buffer = Some((buffer.clone(), subscription));
+    }
+
+    fn on_buffer_event(
+        &mut self,
+        buffer: Entity<Buffer>,
+        event: &BufferEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(old_path) = self
+            .bookmarks
+            .iter()
+            .find(|(_, file)| {
+                file.buffer
+                    .as_ref()
+                    .is_some_and(|(attached_buffer, _)| *attached_buffer == buffer)
+            })
+            .map(|(path, _)| path.clone())
+        else {
+            return;
+        };
+
+        match event {
+            BufferEvent::Edited => cx.notify(),
+            BufferEvent::Saved => {
+                let snapshot = buffer.read(cx).text_snapshot();
+                if let Some(file) = self.bookmarks.get_mut(&old_path) {
+                    for bookmark in &mut file.bookmarks {
+                        bookmark.line_text = line_text(&snapshot, bookmark.row(Some(&snapshot)));
+                    }
+                }
+                cx.emit(BookmarkStoreEvent::BookmarksUpdated);
+            }
+            BufferEvent::Reloaded => {
+                let snapshot = buffer.read(cx).text_snapshot();
+                let text = snapshot.text();
+                let lines = text.split('\n').collect::<Vec<_>>();
+                if let Some(file) = self.bookmarks.get_mut(&old_path) {
+                    for bookmark in &mut file.bookmarks {
+                        let row = bookmark.row(Some(&snapshot));
+                        match closest_matching_row(&lines, row, &bookmark.line_text) {
+                            Some(matching_row) if matching_row != row => {
+                                bookmark.position = BookmarkPosition::Anchor(
+                                    snapshot.anchor_after(Point::new(matching_row, 0)),
+                                );
+                            }
+                            Some(_) => {}
+                            None => bookmark.line_text = line_text(&snapshot, row),
+                        }
+                    }
+                    sort_and_dedup(&mut file.bookmarks, Some(&snapshot));
+                }
+                cx.emit(BookmarkStoreEvent::BookmarksUpdated);
+                cx.notify();
+            }
+            BufferEvent::FileHandleChanged => {
+                if buffer
+                    .read(cx)
+                    .file()
+                    .is_none_or(|file| file.disk_state() == DiskState::Deleted)
+                {
+                    self.bookmarks.remove(&old_path);
+                } else if let Some(new_path) = Self::abs_path_from_buffer(&buffer, cx)
+                    && new_path != old_path
+                    && let Some(file) = self.bookmarks.remove(&old_path)
+                {
+                    self.bookmarks.insert(new_path, file);
+                } else {
+                    return;
+                }
+                cx.emit(BookmarkStoreEvent::BookmarksUpdated);
+                cx.notify();
+            }
+            _ => {}
+        }
+    }
+
+    /// Adds a bookmark on the given row of the buffer, or removes the one that is already there.
+    pub fn toggle_bookmark(&mut self, buffer: &Entity<Buffer>, row: u32, cx: &mut Context<Self>) {
+        let Some(abs_path) = Self::abs_path_from_buffer(buffer, cx) else {
+            return;
+        };
+        let id = self.next_id();
+        self.attach_buffer(abs_path.clone(), buffer, cx);
+        let Some(file) = self.bookmarks.get_mut(&abs_path) else {
+            return;
+        };
+
+        let snapshot = buffer.read(cx).text_snapshot();
+        if let Some(ix) = file
+            .bookmarks
+            .iter()
+            .position(|bookmark| bookmark.row(Some(&snapshot)) == row)
+        {
+            file.bookmarks.remove(ix);
+            if file.bookmarks.is_empty() {
+                self.bookmarks.remove(&abs_path);
+            }
+        } else {
+            file.bookmarks.push(Bookmark {
+                id,
+                position: BookmarkPosition::Anchor(snapshot.anchor_after(Point::new(row, 0))),
+                label: None,
+                list: None,
+                line_text: line_text(&snapshot, row),
+            });
+            sort_and_dedup(&mut file.bookmarks, Some(&snapshot));
+        }
+
+        cx.emit(BookmarkStoreEvent::BookmarksUpdated);
+        cx.notify();
+    }
+
+    pub fn remove_bookmark(&mut self, id: BookmarkId, cx: &mut Context<Self>) {
+        let mut removed = false;
+        self.bookmarks.retain(|_, file| {
+            let len_before = file.bookmarks.len();
+            file.bookmarks.retain(|bookmark| bookmark.id != id);
+            removed |= len_before != file.bookmarks.len();
+            !file.bookmarks.is_empty()
+        });
+        if removed {
+            cx.emit(BookmarkStoreEvent::BookmarksUpdated);
+            cx.notify();
+        }
+    }
+
+    pub fn set_label(&mut self, id: BookmarkId, label: Option<Arc<str>>, cx: &mut Context<Self>) {
+        if let Some(bookmark) = self.bookmark_mut(id) {
+            bookmark.label = label.filter(|label| !label.trim().is_empty());
+            cx.emit(BookmarkStoreEvent::BookmarksUpdated);
+            cx.notify();
+        }
+    }
+
+    pub fn set_list(&mut self, id: BookmarkId, list: Option<Arc<str>>, cx: &mut Context<Self>) {
+        if let Some(bookmark) = self.bookmark_mut(id) {
+            bookmark.list = list.filter(|list| !list.trim().is_empty());
+            cx.emit(BookmarkStoreEvent::BookmarksUpdated);
+            cx.notify();
+        }
+    }
+
+    pub fn clear_bookmarks(&mut self, cx: &mut Context<Self>) {
+        self.bookmarks.clear();
+        cx.emit(BookmarkStoreEvent::BookmarksUpdated);
+        cx.notify();
+    }
+
+    fn bookmark_mut(&mut self, id: BookmarkId) -> Option<&mut Bookmark> {
+        self.bookmarks
+            .values_mut()
+            .flat_map(|file| file.bookmarks.iter_mut())
+            .find(|bookmark| bookmark.id == id)
+    }
+
+    /// Returns all bookmarks of the project, ordered by path and row.
+    pub fn bookmarks(&self, cx: &App) -> Vec<BookmarkEntry> {
+        self.bookmarks
+            .iter()
+            .flat_map(|(path, file)| {
+                let snapshot = file.snapshot(cx);
+                file.bookmarks
+                    .iter()
+                    .map(move |bookmark| BookmarkEntry {
+                        id: bookmark.id,
+                        path: path.clone(),
+                        row: bookmark.row(snapshot.as_ref()),
+                        label: bookmark.label.clone(),
+                        list: bookmark.list.clone(),
+                        line_text: bookmark.line_text.clone(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    pub fn bookmarks_for_buffer(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &App,
+    ) -> Vec<(BookmarkId, text::Anchor)> {
+        let Some(abs_path) = Self::abs_path_from_buffer(buffer, cx) else {
+            return Vec::new();
+        };
+        self.bookmarks
+            .get(&abs_path)
+            .filter(|file| {
+                file.buffer
+                    .as_ref()
+                    .is_some_and(|(attached_buffer, _)| attached_buffer == buffer)
+            })
+            .into_iter()
+            .flat_map(|file| &file.bookmarks)
+            .filter_map(|bookmark| match bookmark.position {
+                BookmarkPosition::Anchor(anchor) => Some((bookmark.id, anchor)),
+                BookmarkPosition::Row(_) => None,
+            })
+            .collect()
+    }
+
+    pub fn bookmark_at_row(
+        &self,
+        buffer: &Entity<Buffer>,
+        row: u32,
+        cx: &App,
+    ) -> Option<BookmarkId> {
+        let snapshot = buffer.read(cx).text_snapshot();
+        self.bookmarks_for_buffer(buffer, cx)
+            .into_iter()
+            .find(|(_, anchor)| anchor.summary::<Point>(&snapshot).row == row)
+            .map(|(id, _)| id)
+    }
+
+    /// Returns the buffer position of a bookmark, if its file is open.
+    pub fn bookmark_position(&self, id: BookmarkId) -> Option<(Entity<Buffer>, text::Anchor)> {
+        self.bookmarks.values().find_map(|file| {
+            let (buffer, _) = file.buffer.as_ref()?;
+            let bookmark = file.bookmarks.iter().find(|bookmark| bookmark.id == id)?;
+            match bookmark.position {
+                BookmarkPosition::Anchor(anchor) => Some((buffer.clone(), anchor)),
+                BookmarkPosition::Row(_) => None,
+            }
+        })
+    }
+
+    /// Returns the names of all bookmark lists, sorted alphabetically.
+    pub fn lists(&self) -> Vec<Arc<str>> {
+        let mut lists = self
+            .bookmarks
+            .values()
+            .flat_map(|file| &file.bookmarks)
+            .filter_map(|bookmark| bookmark.list.clone())
+            .collect::<Vec<_>>();
+        lists.sort();
+        lists.dedup();
+        lists
+    }
+
+    pub fn serialized_bookmarks(&self, cx: &App) -> BTreeMap<Arc<Path>, Vec<SerializedBookmark>> {
+        self.bookmarks
+            .iter()
+            .map(|(path, file)| {
+                let snapshot = file.snapshot(cx);
+                let bookmarks = file
+                    .bookmarks
+                    .iter()
+                    .map(|bookmark| SerializedBookmark {
+                        row: bookmark.row(snapshot.as_ref()),
+                        label: bookmark.label.clone(),
+                        list: bookmark.list.clone(),
+                        line_text: bookmark.line_text.clone(),
+                    })
+                    .collect();
+                (path.clone(), bookmarks)
+            })
+            .collect()
+    }
+
+    /// Restores bookmarks from the workspace database. Bookmarks of files that are not open yet get anchored
+    /// once their buffer is opened.
+    pub fn with_serialized_bookmarks(
+        &mut self,
+        bookmarks: BTreeMap<Arc<Path>, Vec<SerializedBookmark>>,
+        cx: &mut Context<Self>,
+    ) {
+        for (path, serialized_bookmarks) in bookmarks {
+            if serialized_bookmarks.is_empty() || self.bookmarks.contains_key(&path) {
+                continue;
+            }
+            let bookmarks = serialized_bookmarks
+                .into_iter()
+                .map(|bookmark| Bookmark {
+                    id: self.next_id(),
+                    position: BookmarkPosition::Row(bookmark.row),
+                    label: bookmark.label,
+                    list: bookmark.list,
+                    line_text: bookmark.line_text,
+                })
+                .collect();
+            self.bookmarks.insert(
+                path,
+                BookmarksInFile {
+                    buffer: None,
+                    bookmarks,
+                },
+            );
+        }
+
+        let open_buffers = self
+            .buffer_store
+            .read(cx)
+            .buffers()
+            .filter_map(|buffer| {
+                let abs_path = Self::abs_path_from_buffer(&buffer, cx)?;
+                let file = self.bookmarks.get(&abs_path)?;
+                file.buffer.is_none().then_some((abs_path, buffer))
+            })
+            .collect::<Vec<_>>();
+        for (abs_path, buffer) in open_buffers {
+            self.attach_buffer(abs_path, &buffer, cx);
+        }
+        cx.notify();
+    }
+}
+
+fn line_text(snapshot: &BufferSnapshot, row: u32) -> Arc<str> {
+    let row = row.min(snapshot.max_point().row);
+    snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .collect::<String>()
+        .into()
+}
+
+fn sort_and_dedup(bookmarks: &mut Vec<Bookmark>, snapshot: Option<&BufferSnapshot>) {
+    bookmarks.sort_by_key(|bookmark| bookmark.row(snapshot));
+    bookmarks.dedup_by_key(|bookmark| bookmark.row(snapshot));
+}
+
+/// Returns the row closest to `row` whose content matches `line_text`, ignoring surrounding whitespace.
+/// Blank lines are never matched, as they carry no information about where the bookmark moved to.
+fn closest_matching_row(lines: &[&str], row: u32, line_text: &str) -> Option<u32> {
+    let line_text = line_text.trim();
+    if line_text.is_empty() || lines.is_empty() {
+        return None;
+    }
+    let row = (row as usize).min(lines.len() - 1);
+    let matches = |ix: usize| lines[ix].trim() == line_text;
+    if matches(row) {
+        return Some(row as u32);
+    }
+    (1..lines.len())
+        .flat_map(|distance| [row.checked_sub(distance), Some(row + distance)])
+        .flatten()
+        .filter(|&ix| ix < lines.len())
+        .find(|&ix| matches(ix))
+        .map(|ix| ix as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::closest_matching_row;
+
+    #[test]
+    fn test_closest_matching_row() {
+        let lines = [
+            "fn a() {",
+            "    one();",
+            "}",
+            "",
+            "fn b() {",
+            "    one();",
+            "}",
+        ];
+
+        assert_eq!(closest_matching_row(&lines, 1, "    one();"), Some(1));
+        // Indentation changes are ignored.
+        assert_eq!(closest_matching_row(&lines, 4, "  fn b() {"), Some(4));
+        // The line moved: the closest match wins.
+        assert_eq!(closest_matching_row(&lines, 3, "    one();"), Some(1));
+        assert_eq!(closest_matching_row(&lines, 6, "one();"), Some(5));
+        // Rows past the end of the file are clamped before searching.
+        assert_eq!(closest_matching_row(&lines, 42, "fn b() {"), Some(4));
+        // The line was deleted, or was blank.
+        assert_eq!(closest_matching_row(&lines, 2, "two();"), None);
+        assert_eq!(closest_matching_row(&lines, 3, ""), None);
+    }
+}
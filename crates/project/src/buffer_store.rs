@@ -6,6 +6,7 @@ use crate::{
 use anyhow::{Context as _, Result, anyhow};
 use client::Client;
 use collections::{HashMap, HashSet, hash_map};
+use fs::LoadedFileWindow;
 use futures::{Future, FutureExt as _, channel::oneshot, future::Shared};
 use gpui::{
     App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity,
@@ -617,9 +618,27 @@ impl LocalBufferStore {
         worktree: Entity<Worktree>,
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<Entity<Buffer>>> {
-        let load_file = worktree.update(cx, |worktree, cx| worktree.load_file(path.as_ref(), cx));
+        let load_large_file = worktree.update(cx, |worktree, cx| {
+            worktree.load_large_file(path.as_ref(), cx)
+        });
         cx.spawn(async move |this, cx| {
             let path = path.clone();
+            let load_file = match load_large_file.await {
+                Ok(Some(loaded)) => {
+                    let buffer = build_large_file_buffer(loaded.file, loaded.window, cx)?;
+                    this.update(cx, |this, cx| this.add_opened_local_buffer(&buffer, cx))??;
+                    return Ok(buffer);
+                }
+                Ok(None) => {
+                    worktree.update(cx, |worktree, cx| worktree.load_file(path.as_ref(), cx))?
+                }
+                Err(error) => {
+                    return Err(error.context(format!(
+                        "Could not open path: {}",
+                        path.display(PathStyle::local())
+                    )));
+                }
+            };
             let buffer = match load_file.await.with_context(|| {
                 format!("Could not open path: {}", path.display(PathStyle::local()))
             }) {
@@ -653,26 +672,7 @@ impl LocalBufferStore {
                 })?,
                 Err(e) => return Err(e),
             };
-            this.update(cx, |this, cx| {
-                this.add_buffer(buffer.clone(), cx)?;
-                let buffer_id = buffer.read(cx).remote_id();
-                if let Some(file) = File::from_dyn(buffer.read(cx).file()) {
-                    this.path_to_buffer_id.insert(
-                        ProjectPath {
-                            worktree_id: file.worktree_id(cx),
-                            path: file.path.clone(),
-                        },
-                        buffer_id,
-                    );
-                    let this = this.as_local_mut().unwrap();
-                    if let Some(entry_id) = file.entry_id {
-                        this.local_buffer_ids_by_entry_id
-                            .insert(entry_id, buffer_id);
-                    }
-                }
-
-                anyhow::Ok(())
-            })??;
+            this.update(cx, |this, cx| this.add_opened_local_buffer(&buffer, cx))??;
 
             Ok(buffer)
         })
@@ -916,6 +916,55 @@ impl BufferStore {
         })
     }
 
+    /// Opens the window of a file opened in large-file mode that starts at `start`, in a new
+    /// buffer that takes the place of the given one for the file, so that the text of the
+    /// previous window can be released along with that buffer.
+    pub fn open_large_file_window(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        start: u64,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Buffer>>> {
+        let buffer = buffer.read(cx);
+        let (Some(window), Some(file)) =
+            (buffer.large_file_window(), File::from_dyn(buffer.file()))
+        else {
+            return Task::ready(Err(anyhow!("buffer was not opened in large-file mode")));
+        };
+        let file = Arc::new(file.clone());
+        let load_window = file.load_window(start, window.max_len, cx);
+        cx.spawn(async move |this, cx| {
+            let buffer = build_large_file_buffer(file, load_window.await?, cx)?;
+            this.update(cx, |this, cx| this.add_opened_local_buffer(&buffer, cx))??;
+            Ok(buffer)
+        })
+    }
+
+    /// Adds a buffer opened from a local file, indexing it by the file's path and entry.
+    fn add_opened_local_buffer(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        self.add_buffer(buffer.clone(), cx)?;
+        let buffer_id = buffer.read(cx).remote_id();
+        if let Some(file) = File::from_dyn(buffer.read(cx).file()) {
+            self.path_to_buffer_id.insert(
+                ProjectPath {
+                    worktree_id: file.worktree_id(cx),
+                    path: file.path.clone(),
+                },
+                buffer_id,
+            );
+            let this = self.as_local_mut().unwrap();
+            if let Some(entry_id) = file.entry_id {
+                this.local_buffer_ids_by_entry_id
+                    .insert(entry_id, buffer_id);
+            }
+        }
+        Ok(())
+    }
+
     fn add_buffer(&mut self, buffer_entity: Entity<Buffer>, cx: &mut Context<Self>) -> Result<()> {
         let buffer = buffer_entity.read(cx);
         let remote_id = buffer.remote_id();
@@ -1680,6 +1729,22 @@ impl OpenBuffer {
     }
 }
 
+/// Builds the read-only buffer holding a window of a file opened in large-file mode.
+fn build_large_file_buffer(
+    file: Arc<File>,
+    loaded: LoadedFileWindow,
+    cx: &mut AsyncApp,
+) -> Result<Entity<Buffer>> {
+    let reservation = cx.reserve_entity::<Buffer>()?;
+    let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
+    let text_buffer =
+        text::Buffer::new_normalized(ReplicaId::LOCAL, buffer_id, loaded.line_ending, loaded.text);
+    cx.insert_entity(reservation, |_| {
+        Buffer::build(text_buffer, Some(file), Capability::ReadOnly)
+            .with_large_file_window(loaded.window)
+    })
+}
+
 fn is_not_found_error(error: &anyhow::Error) -> bool {
     error
         .root_cause()
@@ -5,9 +5,10 @@ use super::dap_command::{
     self, Attach, ConfigurationDone, ContinueCommand, DataBreakpointInfoCommand, DisconnectCommand,
     EvaluateCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand,
     ModulesCommand, NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand,
-    ScopesCommand, SetDataBreakpointsCommand, SetExceptionBreakpoints, SetVariableValueCommand,
-    StackTraceCommand, StepBackCommand, StepCommand, StepInCommand, StepOutCommand,
-    TerminateCommand, TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
+    ReverseContinueCommand, ScopesCommand, SetDataBreakpointsCommand, SetExceptionBreakpoints,
+    SetExpressionCommand, SetVariableValueCommand, StackTraceCommand, StepBackCommand, StepCommand,
+    StepInCommand, StepOutCommand, TerminateCommand, TerminateThreadsCommand, ThreadsCommand,
+    VariablesCommand,
 };
 use super::dap_store::DapStore;
 use crate::debugger::breakpoint_store::BreakpointSessionState;
@@ -2296,6 +2297,25 @@ impl Session {
         .detach();
     }
 
+    pub fn reverse_continue(&mut self, thread_id: ThreadId, cx: &mut Context<Self>) {
+        self.select_historic_snapshot(None, cx);
+
+        let supports_single_thread_execution_requests =
+            self.capabilities.supports_single_thread_execution_requests;
+        self.active_snapshot
+            .thread_states
+            .continue_thread(thread_id);
+        self.request(
+            ReverseContinueCommand {
+                thread_id: thread_id.0,
+                single_thread: supports_single_thread_execution_requests,
+            },
+            Self::on_step_response::<ReverseContinueCommand>(thread_id),
+            cx,
+        )
+        .detach();
+    }
+
     pub fn adapter_client(&self) -> Option<Arc<DebugAdapterClient>> {
         match self.state {
             SessionState::Running(ref local) => Some(local.client.clone()),
@@ -2708,6 +2728,39 @@ impl Session {
         }
     }
 
+    pub fn set_expression(
+        &mut self,
+        stack_frame_id: u64,
+        expression: String,
+        value: String,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .capabilities
+            .supports_set_expression
+            .unwrap_or_default()
+        {
+            self.request(
+                SetExpressionCommand {
+                    expression,
+                    value,
+                    frame_id: Some(stack_frame_id),
+                },
+                move |this, response, cx| {
+                    let response = response.log_err()?;
+                    this.invalidate_command_type::<VariablesCommand>();
+                    this.invalidate_command_type::<ReadMemory>();
+                    this.memory.clear(cx.background_executor());
+                    this.refresh_watchers(stack_frame_id, cx);
+                    cx.emit(SessionEvent::Variables);
+                    Some(response)
+                },
+                cx,
+            )
+            .detach();
+        }
+    }
+
     pub fn evaluate(
         &mut self,
         expression: String,
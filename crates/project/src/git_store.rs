@@ -30,8 +30,8 @@ use git::{
     parse_git_remote_url,
     repository::{
         Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, FetchOptions,
-        GitRepository, GitRepositoryCheckpoint, PushOptions, Remote, RemoteCommandOutput, RepoPath,
-        ResetMode, UpstreamTrackingStatus, Worktree as GitWorktree,
+        FileRevision, GitRepository, GitRepositoryCheckpoint, PushOptions, Remote,
+        RemoteCommandOutput, RepoPath, ResetMode, UpstreamTrackingStatus, Worktree as GitWorktree,
     },
     stash::{GitStash, StashEntry},
     status::{
@@ -499,6 +499,7 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_git_diff);
         client.add_entity_request_handler(Self::handle_tree_diff);
         client.add_entity_request_handler(Self::handle_get_blob_content);
+        client.add_entity_request_handler(Self::handle_get_file_bytes);
         client.add_entity_request_handler(Self::handle_open_unstaged_diff);
         client.add_entity_request_handler(Self::handle_open_uncommitted_diff);
         client.add_entity_message_handler(Self::handle_update_diff_bases);
@@ -639,6 +640,9 @@ impl GitStore {
         cx: &mut Context<Self>,
     ) -> Task<Result<Entity<BufferDiff>>> {
         let buffer_id = buffer.read(cx).remote_id();
+        if buffer.read(cx).is_large_file() {
+            return Task::ready(Err(anyhow!("git diffs are disabled for large files")));
+        }
         if let Some(diff_state) = self.diffs.get(&buffer_id)
             && let Some(unstaged_diff) = diff_state
                 .read(cx)
@@ -740,6 +744,9 @@ impl GitStore {
         cx: &mut Context<Self>,
     ) -> Task<Result<Entity<BufferDiff>>> {
         let buffer_id = buffer.read(cx).remote_id();
+        if buffer.read(cx).is_large_file() {
+            return Task::ready(Err(anyhow!("git diffs are disabled for large files")));
+        }
 
         if let Some(diff_state) = self.diffs.get(&buffer_id)
             && let Some(uncommitted_diff) = diff_state
@@ -1022,6 +1029,9 @@ impl GitStore {
         cx: &mut Context<Self>,
     ) -> Task<Result<Option<Blame>>> {
         let buffer = buffer.read(cx);
+        if buffer.is_large_file() {
+            return Task::ready(Ok(None));
+        }
         let Some((repo, repo_path)) =
             self.repository_and_path_for_buffer_id(buffer.remote_id(), cx)
         else {
@@ -2631,6 +2641,29 @@ impl GitStore {
         Ok(proto::GetBlobContentResponse { content })
     }
 
+    async fn handle_get_file_bytes(
+        this: Entity<Self>,
+        request: TypedEnvelope<proto::GetFileBytes>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetFileBytesResponse> {
+        let repository_id = RepositoryId::from_proto(request.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath::from_proto(&request.payload.path)?;
+        let revision = proto_to_file_revision(
+            request
+                .payload
+                .revision
+                .as_ref()
+                .context("missing revision")?,
+        )?;
+        let content = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.load_file_bytes(revision, path)
+            })?
+            .await??;
+        Ok(proto::GetFileBytesResponse { content })
+    }
+
     async fn handle_open_unstaged_diff(
         this: Entity<Self>,
         request: TypedEnvelope<proto::OpenUnstagedDiff>,
@@ -5708,6 +5741,34 @@ impl Repository {
         cx.spawn(|_: &mut AsyncApp| async move { rx.await? })
     }
 
+    /// Loads the raw bytes of a file at the given revision, for contents that can't be represented
+    /// as text, such as images.
+    pub fn load_file_bytes(
+        &mut self,
+        revision: FileRevision,
+        path: RepoPath,
+    ) -> oneshot::Receiver<Result<Option<Vec<u8>>>> {
+        let id = self.id;
+        self.send_job(None, move |state, _| async move {
+            match state {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.load_file_bytes(revision, path).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { client, project_id }) => {
+                    let response = client
+                        .request(proto::GetFileBytes {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            revision: Some(file_revision_to_proto(&revision)),
+                            path: path.to_proto(),
+                        })
+                        .await?;
+                    Ok(response.content)
+                }
+            }
+        })
+    }
+
     fn paths_changed(
         &mut self,
         paths: Vec<RepoPath>,
@@ -6098,6 +6159,47 @@ fn proto_to_commit_details(proto: &proto::GitCommitDetails) -> CommitDetails {
     }
 }
 
+fn file_revision_to_proto(revision: &FileRevision) -> proto::FileRevision {
+    let (kind, commit, base_ref) = match revision {
+        FileRevision::Index => (proto::file_revision::Kind::Index, None, None),
+        FileRevision::Head => (proto::file_revision::Kind::Head, None, None),
+        FileRevision::Commit(oid) => (
+            proto::file_revision::Kind::Commit,
+            Some(oid.to_string()),
+            None,
+        ),
+        FileRevision::ParentOf(oid) => (
+            proto::file_revision::Kind::ParentOf,
+            Some(oid.to_string()),
+            None,
+        ),
+        FileRevision::MergeBase(base_ref) => (
+            proto::file_revision::Kind::MergeBase,
+            None,
+            Some(base_ref.to_string()),
+        ),
+    };
+    proto::FileRevision {
+        kind: kind.into(),
+        commit,
+        base_ref,
+    }
+}
+
+fn proto_to_file_revision(proto: &proto::FileRevision) -> Result<FileRevision> {
+    let commit =
+        || -> Result<Oid> { Oid::from_str(proto.commit.as_deref().context("missing commit")?) };
+    Ok(match proto.kind() {
+        proto::file_revision::Kind::Index => FileRevision::Index,
+        proto::file_revision::Kind::Head => FileRevision::Head,
+        proto::file_revision::Kind::Commit => FileRevision::Commit(commit()?),
+        proto::file_revision::Kind::ParentOf => FileRevision::ParentOf(commit()?),
+        proto::file_revision::Kind::MergeBase => {
+            FileRevision::MergeBase(proto.base_ref.clone().context("missing base ref")?.into())
+        }
+    })
+}
+
 async fn compute_snapshot(
     id: RepositoryId,
     work_directory_abs_path: Arc<Path>,
@@ -97,6 +97,11 @@ impl BranchDiff {
         &self.diff_base
     }
 
+    /// How the given path differs between the merge base and HEAD, when diffing against a merge base.
+    pub fn tree_diff_status(&self, path: &RepoPath) -> Option<&TreeDiffStatus> {
+        self.tree_diff.as_ref()?.entries.get(path)
+    }
+
     pub async fn handle_status_updates(
         this: WeakEntity<Self>,
         mut recv: postage::watch::Receiver<()>,
@@ -108,7 +108,7 @@ pub struct ImageItem {
 }
 
 impl ImageItem {
-    fn compute_metadata_from_bytes(image_bytes: &[u8]) -> Result<ImageMetadata> {
+    pub fn compute_metadata_from_bytes(image_bytes: &[u8]) -> Result<ImageMetadata> {
         let image_format = image::guess_format(image_bytes)?;
 
         let mut image_reader = ImageReader::new(std::io::Cursor::new(image_bytes));
@@ -216,10 +216,12 @@ pub fn is_image_file(project: &Entity<Project>, path: &ProjectPath, cx: &App) ->
             .map(str::to_lowercase)
     });
 
-    match ext {
-        Some(ext) => Img::extensions().contains(&ext.as_str()) && !ext.contains("svg"),
-        None => false,
-    }
+    ext.is_some_and(|ext| is_image_extension(&ext))
+}
+
+/// Whether files with the given lowercase extension are opened as images rather than as text.
+pub fn is_image_extension(extension: &str) -> bool {
+    Img::extensions().contains(&extension) && !extension.contains("svg")
 }
 
 impl ProjectItem for ImageItem {
@@ -887,7 +889,7 @@ impl LocalImageStore {
     }
 }
 
-fn create_gpui_image(content: Vec<u8>) -> anyhow::Result<Arc<gpui::Image>> {
+pub fn create_gpui_image(content: Vec<u8>) -> anyhow::Result<Arc<gpui::Image>> {
     let format = image::guess_format(&content)?;
 
     Ok(Arc::new(gpui::Image::from_bytes(
@@ -17,6 +17,7 @@ pub mod rust_analyzer_ext;
 pub mod vue_language_server_ext;
 
 mod inlay_hint_cache;
+mod workspace_edit_preview;
 
 use self::inlay_hint_cache::BufferInlayHints;
 use crate::{
@@ -26,6 +27,7 @@ use crate::{
     ProjectTransaction, PulledDiagnostics, ResolveState, Symbol,
     buffer_store::{BufferStore, BufferStoreEvent},
     environment::ProjectEnvironment,
+    git_store::GitStore,
     lsp_command::{self, *},
     lsp_store::{
         self,
@@ -133,6 +135,7 @@ pub use language::Location;
 pub use lsp_store::inlay_hint_cache::{CacheInlayHints, InvalidationStrategy};
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
+pub use workspace_edit_preview::{WorkspaceEditOperation, WorkspaceEditPreview};
 pub use worktree::{
     Entry, EntryKind, FS_WATCH_LATENCY, File, LocalWorktree, PathChange, ProjectEntryId,
     UpdatedEntriesSet, UpdatedGitRepositoriesSet, Worktree, WorktreeId, WorktreeSettings,
@@ -269,6 +272,7 @@ pub struct LocalLspStore {
     supplementary_language_servers:
         HashMap<LanguageServerId, (LanguageServerName, Arc<LanguageServer>)>,
     prettier_store: Entity<PrettierStore>,
+    git_store: Entity<GitStore>,
     next_diagnostic_group_id: usize,
     diagnostics: HashMap<
         WorktreeId,
@@ -945,6 +949,7 @@ impl LocalLspStore {
                     let mut cx = cx.clone();
                     async move {
                         this.update(&mut cx, |this, cx| {
+                            this.invalidate_code_lens();
                             cx.emit(LspStoreEvent::RefreshCodeLens);
                             this.downstream_client.as_ref().map(|(client, project_id)| {
                                 client.send(proto::RefreshCodeLens {
@@ -1441,13 +1446,43 @@ impl LocalLspStore {
             })?
         }
 
+        let modified_ranges = if trigger == FormatTrigger::Save
+            && settings.format_on_save == FormatOnSave::ModifiedLines
+            && buffer.ranges.is_none()
+        {
+            let modified_ranges = Self::modified_ranges(&lsp_store, &buffer.handle, cx).await;
+            if modified_ranges.is_none() {
+                zlog::trace!(logger => "buffer is not in a git repository, formatting all lines");
+            }
+            modified_ranges
+        } else {
+            None
+        };
+        let format_ranges = buffer.ranges.as_ref().or(modified_ranges.as_ref());
+
         // handle whitespace formatting
         if settings.remove_trailing_whitespace_on_save {
             zlog::trace!(logger => "removing trailing whitespace");
-            let diff = buffer
+            let mut diff = buffer
                 .handle
                 .read_with(cx, |buffer, cx| buffer.remove_trailing_whitespace(cx))?
                 .await;
+            if let Some(modified_ranges) = modified_ranges.as_ref() {
+                let modified_ranges = buffer.handle.read_with(cx, |buffer, _| {
+                    let snapshot = buffer.text_snapshot();
+                    modified_ranges
+                        .iter()
+                        .map(|range| {
+                            range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot)
+                        })
+                        .collect::<Vec<_>>()
+                })?;
+                diff.edits.retain(|(edit_range, _)| {
+                    modified_ranges
+                        .iter()
+                        .any(|range| range.start <= edit_range.end && edit_range.start <= range.end)
+                });
+            }
             extend_formatting_transaction(buffer, formatting_transaction_id, cx, |buffer, cx| {
                 buffer.apply_diff(diff, cx);
             })?;
@@ -1467,7 +1502,9 @@ impl LocalLspStore {
             (trigger, &settings.format_on_save),
             (FormatTrigger::Save, &FormatOnSave::Off)
         );
-        if should_run_code_actions_on_format {
+        if should_run_code_actions_on_format && modified_ranges.is_some() {
+            zlog::trace!(logger => "formatting modified lines only, skipping code actions on format");
+        } else if should_run_code_actions_on_format {
             let have_code_actions_to_run_on_format = settings
                 .code_actions_on_format
                 .values()
@@ -1488,7 +1525,8 @@ impl LocalLspStore {
 
         let formatters = match (trigger, &settings.format_on_save) {
             (FormatTrigger::Save, FormatOnSave::Off) => &[],
-            (FormatTrigger::Manual, _) | (FormatTrigger::Save, FormatOnSave::On) => {
+            (FormatTrigger::Manual, _)
+            | (FormatTrigger::Save, FormatOnSave::On | FormatOnSave::ModifiedLines) => {
                 settings.formatter.as_ref()
             }
         };
@@ -1520,28 +1558,46 @@ impl LocalLspStore {
                     let prettier = lsp_store.read_with(cx, |lsp_store, _cx| {
                         lsp_store.prettier_store().unwrap().downgrade()
                     })?;
-                    let diff = prettier_store::format_with_prettier(&prettier, &buffer.handle, cx)
+                    // Prettier formats a single range at a time, so each modified range is
+                    // formatted separately, after the edits made for the previous ones.
+                    let ranges = match modified_ranges.as_ref() {
+                        Some(ranges) => ranges.iter().cloned().map(Some).collect(),
+                        None => vec![None],
+                    };
+                    for range in ranges {
+                        let diff = prettier_store::format_with_prettier(
+                            &prettier,
+                            &buffer.handle,
+                            range,
+                            cx,
+                        )
                         .await
                         .transpose()?;
-                    let Some(diff) = diff else {
-                        zlog::trace!(logger => "No changes");
-                        continue;
-                    };
+                        let Some(diff) = diff else {
+                            zlog::trace!(logger => "No changes");
+                            continue;
+                        };
 
-                    extend_formatting_transaction(
-                        buffer,
-                        formatting_transaction_id,
-                        cx,
-                        |buffer, cx| {
-                            buffer.apply_diff(diff, cx);
-                        },
-                    )?;
+                        extend_formatting_transaction(
+                            buffer,
+                            formatting_transaction_id,
+                            cx,
+                            |buffer, cx| {
+                                buffer.apply_diff(diff, cx);
+                            },
+                        )?;
+                    }
                 }
                 Formatter::External { command, arguments } => {
                     let logger = zlog::scoped!(logger => "command");
                     zlog::trace!(logger => "formatting");
                     let _timer = zlog::time!(logger => "Formatting buffer via external command");
 
+                    if modified_ranges.is_some() {
+                        zlog::warn!(logger => "External commands can't format modified lines only. Skipping");
+                        continue;
+                    }
+
                     let diff = Self::format_via_external_command(
                         buffer,
                         command.as_ref(),
@@ -1606,8 +1662,22 @@ impl LocalLspStore {
                         language_server.name()
                     );
 
-                    let edits = if let Some(ranges) = buffer.ranges.as_ref() {
+                    let edits = if let Some(ranges) = format_ranges {
                         zlog::trace!(logger => "formatting ranges");
+                        if modified_ranges.is_some()
+                            && language_server
+                                .capabilities()
+                                .document_range_formatting_provider
+                                .as_ref()
+                                .is_none_or(|provider| provider == &OneOf::Left(false))
+                        {
+                            zlog::warn!(
+                                logger =>
+                                "Language server '{:?}' can't format modified lines only. Skipping",
+                                language_server.name()
+                            );
+                            continue;
+                        }
                         Self::format_ranges_via_lsp(
                             &lsp_store,
                             &buffer.handle,
@@ -1651,6 +1721,11 @@ impl LocalLspStore {
                     zlog::trace!(logger => "formatting");
                     let _timer = zlog::time!(logger => "Formatting buffer using code actions");
 
+                    if modified_ranges.is_some() {
+                        zlog::warn!(logger => "Code actions can't format modified lines only. Skipping");
+                        continue;
+                    }
+
                     let Some(buffer_path_abs) = buffer.abs_path.as_ref() else {
                         zlog::warn!(logger => "Cannot format buffer that is not backed by a file on disk using code actions. Skipping");
                         continue;
@@ -2007,6 +2082,33 @@ impl LocalLspStore {
         Ok(())
     }
 
+    /// Returns the ranges of the buffer's lines changed since the last commit, or `None` when the
+    /// buffer isn't in a git repository.
+    async fn modified_ranges(
+        lsp_store: &WeakEntity<LspStore>,
+        buffer: &Entity<Buffer>,
+        cx: &mut AsyncApp,
+    ) -> Option<Vec<Range<Anchor>>> {
+        let diff = lsp_store
+            .update(cx, |lsp_store, cx| {
+                let git_store = lsp_store.as_local()?.git_store.clone();
+                Some(git_store.update(cx, |git_store, cx| {
+                    git_store.open_uncommitted_diff(buffer.clone(), cx)
+                }))
+            })
+            .ok()??
+            .await
+            .ok()?;
+        diff.read_with(cx, |diff, cx| {
+            let snapshot = buffer.read(cx).text_snapshot();
+            diff.hunks(&snapshot, cx)
+                .filter(|hunk| !hunk.range.is_empty())
+                .map(|hunk| hunk.buffer_range)
+                .collect()
+        })
+        .ok()
+    }
+
     pub async fn format_ranges_via_lsp(
         this: &WeakEntity<LspStore>,
         buffer_handle: &Entity<Buffer>,
@@ -2470,6 +2572,9 @@ impl LocalLspStore {
     ) {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id();
+        if buffer.is_large_file() {
+            return;
+        }
 
         let Some(file) = File::from_dyn(buffer.file()) else {
             return;
@@ -2998,28 +3103,8 @@ impl LocalLspStore {
     ) -> Result<ProjectTransaction> {
         let fs = this.read_with(cx, |this, _| this.as_local().unwrap().fs.clone())?;
 
-        let mut operations = Vec::new();
-        if let Some(document_changes) = edit.document_changes {
-            match document_changes {
-                lsp::DocumentChanges::Edits(edits) => {
-                    operations.extend(edits.into_iter().map(lsp::DocumentChangeOperation::Edit))
-                }
-                lsp::DocumentChanges::Operations(ops) => operations = ops,
-            }
-        } else if let Some(changes) = edit.changes {
-            operations.extend(changes.into_iter().map(|(uri, edits)| {
-                lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
-                    text_document: lsp::OptionalVersionedTextDocumentIdentifier {
-                        uri,
-                        version: None,
-                    },
-                    edits: edits.into_iter().map(Edit::Plain).collect(),
-                })
-            }));
-        }
-
         let mut project_transaction = ProjectTransaction::default();
-        for operation in operations {
+        for operation in workspace_edit_operations(edit) {
             match operation {
                 lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Create(op)) => {
                     let abs_path = op
@@ -3598,6 +3683,28 @@ impl LocalLspStore {
     }
 }
 
+/// Lists the changes of a workspace edit in the order they should be applied, whether the server
+/// sent them as `documentChanges` or as `changes`.
+fn workspace_edit_operations(edit: lsp::WorkspaceEdit) -> Vec<lsp::DocumentChangeOperation> {
+    let mut operations = Vec::new();
+    if let Some(document_changes) = edit.document_changes {
+        match document_changes {
+            lsp::DocumentChanges::Edits(edits) => {
+                operations.extend(edits.into_iter().map(lsp::DocumentChangeOperation::Edit))
+            }
+            lsp::DocumentChanges::Operations(ops) => operations = ops,
+        }
+    } else if let Some(changes) = edit.changes {
+        operations.extend(changes.into_iter().map(|(uri, edits)| {
+            lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                text_document: lsp::OptionalVersionedTextDocumentIdentifier { uri, version: None },
+                edits: edits.into_iter().map(Edit::Plain).collect(),
+            })
+        }));
+    }
+    operations
+}
+
 fn notify_server_capabilities_updated(server: &LanguageServer, cx: &mut Context<LspStore>) {
     if let Some(capabilities) = serde_json::to_string(&server.capabilities()).ok() {
         cx.emit(LspStoreEvent::LanguageServerUpdate {
@@ -3739,6 +3846,17 @@ struct CodeLensData {
     update: Option<(Global, CodeLensTask)>,
 }
 
+/// How a code lens runs when it's clicked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodeLensCommand {
+    /// The lens is applied like a code action, executing its command on the language server.
+    Server,
+    /// A client-side command that shows the references of the symbol.
+    References,
+    /// A client-side command that runs, tests or debugs the code.
+    Runnable,
+}
+
 #[derive(Debug)]
 pub enum LspStoreEvent {
     LanguageServerAdded(LanguageServerId, LanguageServerName, Option<WorktreeId>),
@@ -3908,6 +4026,7 @@ impl LspStore {
         buffer_store: Entity<BufferStore>,
         worktree_store: Entity<WorktreeStore>,
         prettier_store: Entity<PrettierStore>,
+        git_store: Entity<GitStore>,
         toolchain_store: Entity<LocalToolchainStore>,
         environment: Entity<ProjectEnvironment>,
         manifest_tree: Entity<ManifestTree>,
@@ -3950,6 +4069,7 @@ impl LspStore {
                 buffers_being_formatted: Default::default(),
                 buffer_snapshots: Default::default(),
                 prettier_store,
+                git_store,
                 environment,
                 http_client,
                 fs,
@@ -6059,6 +6179,87 @@ impl LspStore {
         }
     }
 
+    /// Drops the cached code lens of all buffers, so that the next query fetches them anew.
+    fn invalidate_code_lens(&mut self) {
+        for lsp_data in self.lsp_data.values_mut() {
+            lsp_data.code_lens = None;
+        }
+    }
+
+    /// How the code lens runs when it's clicked, or `None` if it can't be run.
+    ///
+    /// Servers also return lenses with client-side commands, like rust-analyzer's
+    /// `rust-analyzer.runSingle`, which are mapped to the editor's own features when possible.
+    pub fn code_lens_command(&self, action: &CodeAction) -> Option<CodeLensCommand> {
+        let LspAction::CodeLens(lens) = &action.lsp_action else {
+            return Some(CodeLensCommand::Server);
+        };
+        let Some(command) = &lens.command else {
+            return Some(CodeLensCommand::Server);
+        };
+        let executable_by_server = self
+            .lsp_server_capabilities
+            .get(&action.server_id)
+            .and_then(|capabilities| capabilities.execute_command_provider.as_ref())
+            .is_some_and(|options| options.commands.contains(&command.command));
+        if executable_by_server {
+            return Some(CodeLensCommand::Server);
+        }
+        let command = command.command.to_lowercase();
+        if command.contains("reference") {
+            Some(CodeLensCommand::References)
+        } else if ["run", "debug", "test"]
+            .iter()
+            .any(|keyword| command.contains(keyword))
+        {
+            Some(CodeLensCommand::Runnable)
+        } else {
+            None
+        }
+    }
+
+    /// Resolves the command of a code lens, updating the cached lenses of the buffer.
+    ///
+    /// Lenses are only resolved for local projects.
+    pub fn resolve_code_lens(
+        &self,
+        buffer: &Entity<Buffer>,
+        mut action: CodeAction,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<CodeAction>> {
+        if action.resolved || !matches!(action.lsp_action, LspAction::CodeLens(_)) {
+            return Task::ready(Ok(action));
+        }
+        let Some(lang_server) = buffer.update(cx, |buffer, cx| {
+            self.language_server_for_local_buffer(buffer, action.server_id, cx)
+                .map(|(_, server)| server.clone())
+        }) else {
+            return Task::ready(Ok(action));
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        cx.spawn(async move |lsp_store, cx| {
+            let unresolved_action = action.clone();
+            LocalLspStore::try_resolve_code_action(&lang_server, &mut action)
+                .await
+                .context("resolving a code lens")?;
+            lsp_store.update(cx, |lsp_store, _| {
+                if let Some(cached_lens) = lsp_store
+                    .current_lsp_data(buffer_id)
+                    .and_then(|lsp_data| lsp_data.code_lens.as_mut())
+                    .and_then(|code_lens| code_lens.lens.get_mut(&action.server_id))
+                    .and_then(|lens| {
+                        lens.iter_mut().find(|cached_action| {
+                            cached_action.lsp_action == unresolved_action.lsp_action
+                        })
+                    })
+                {
+                    *cached_lens = action.clone();
+                }
+            })?;
+            Ok(action)
+        })
+    }
+
     #[inline(never)]
     pub fn completions(
         &self,
@@ -9636,6 +9837,82 @@ impl LspStore {
         is_dir: bool,
         cx: AsyncApp,
     ) -> Task<ProjectTransaction> {
+        let edits = Self::will_rename_entry_edits(
+            this.clone(),
+            worktree_id,
+            old_path,
+            new_path,
+            is_dir,
+            &cx,
+        );
+        cx.spawn(async move |cx| {
+            let mut merged_transaction = ProjectTransaction::default();
+            for (language_server, edit) in edits.await {
+                let Some(this) = this.upgrade() else {
+                    break;
+                };
+                if let Ok(transaction) = LocalLspStore::deserialize_workspace_edit(
+                    this,
+                    edit,
+                    false,
+                    language_server,
+                    cx,
+                )
+                .await
+                {
+                    for (buffer, buffer_transaction) in transaction.0 {
+                        merged_transaction.0.insert(buffer, buffer_transaction);
+                    }
+                }
+            }
+            merged_transaction
+        })
+    }
+
+    /// Like [`Self::will_rename_entry`], but returns the edits of the language servers for review
+    /// instead of applying them.
+    pub(super) fn preview_will_rename_entry(
+        this: WeakEntity<Self>,
+        worktree_id: WorktreeId,
+        old_path: &Path,
+        new_path: &Path,
+        is_dir: bool,
+        cx: AsyncApp,
+    ) -> Task<WorkspaceEditPreview> {
+        let edits = Self::will_rename_entry_edits(
+            this.clone(),
+            worktree_id,
+            old_path,
+            new_path,
+            is_dir,
+            &cx,
+        );
+        cx.spawn(async move |cx| {
+            let mut merged_preview = WorkspaceEditPreview::default();
+            for (language_server, edit) in edits.await {
+                let Some(this) = this.upgrade() else {
+                    break;
+                };
+                if let Some(preview) =
+                    LocalLspStore::preview_workspace_edit(this, edit, language_server, cx)
+                        .await
+                        .log_err()
+                {
+                    merged_preview.extend(preview);
+                }
+            }
+            merged_preview
+        })
+    }
+
+    fn will_rename_entry_edits(
+        this: WeakEntity<Self>,
+        worktree_id: WorktreeId,
+        old_path: &Path,
+        new_path: &Path,
+        is_dir: bool,
+        cx: &AsyncApp,
+    ) -> Task<Vec<(Arc<LanguageServer>, lsp::WorkspaceEdit)>> {
         let old_uri = lsp::Uri::from_file_path(old_path)
             .ok()
             .map(|uri| uri.to_string());
@@ -9657,11 +9934,11 @@ impl LspStore {
                     };
 
                     if filter.should_send_will_rename(&old_uri, is_dir) {
-                        let apply_edit = cx.spawn({
+                        let request_edit = cx.spawn({
                             let old_uri = old_uri.clone();
                             let new_uri = new_uri.clone();
                             let language_server = language_server.clone();
-                            async move |this, cx| {
+                            async move |_, _| {
                                 let edit = language_server
                                     .request::<WillRenameFiles>(RenameFilesParams {
                                         files: vec![FileRename { old_uri, new_uri }],
@@ -9671,37 +9948,25 @@ impl LspStore {
                                     .context("will rename files")
                                     .log_err()
                                     .flatten()?;
-
-                                let transaction = LocalLspStore::deserialize_workspace_edit(
-                                    this.upgrade()?,
-                                    edit,
-                                    false,
-                                    language_server.clone(),
-                                    cx,
-                                )
-                                .await
-                                .ok()?;
-                                Some(transaction)
+                                Some((language_server, edit))
                             }
                         });
-                        tasks.push(apply_edit);
+                        tasks.push(request_edit);
                     }
                 }
                 Some(())
             })
             .ok()
             .flatten();
-            let mut merged_transaction = ProjectTransaction::default();
+            let mut edits = Vec::new();
             for task in tasks {
                 // Await on tasks sequentially so that the order of application of edits is deterministic
                 // (at least with regards to the order of registration of language servers)
-                if let Some(transaction) = task.await {
-                    for (buffer, buffer_transaction) in transaction.0 {
-                        merged_transaction.0.insert(buffer, buffer_transaction);
-                    }
+                if let Some(edit) = task.await {
+                    edits.push(edit);
                 }
             }
-            merged_transaction
+            edits
         })
     }
 
@@ -10207,7 +10472,8 @@ impl LspStore {
         _: TypedEnvelope<proto::RefreshCodeLens>,
         mut cx: AsyncApp,
     ) -> Result<proto::Ack> {
-        this.update(&mut cx, |_, cx| {
+        this.update(&mut cx, |this, cx| {
+            this.invalidate_code_lens();
             cx.emit(LspStoreEvent::RefreshCodeLens);
         })?;
         Ok(proto::Ack {})
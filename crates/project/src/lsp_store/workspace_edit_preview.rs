@@ -0,0 +1,426 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result, anyhow};
+use gpui::{AsyncApp, Context, Entity, Task};
+use language::{Anchor, Buffer, PointUtf16, Transaction};
+use lsp::{Edit, LanguageServer, LanguageServerId, TextEdit};
+use snippet::Snippet;
+
+use crate::{
+    File, ProjectTransaction,
+    lsp_command::{LspCommand as _, LspParamsOrResponse, PerformRename},
+    lsp_store::{LocalLspStore, LspStore, workspace_edit_operations},
+};
+
+/// The changes of a `WorkspaceEdit` from a language server, resolved against the project's
+/// buffers but not applied yet, so that they can be reviewed and filtered first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WorkspaceEditPreview {
+    pub operations: Vec<WorkspaceEditOperation>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum WorkspaceEditOperation {
+    Edit {
+        buffer: Entity<Buffer>,
+        edits: Vec<(Range<Anchor>, Arc<str>)>,
+    },
+    /// Edits to a file created by an earlier operation, which can only be resolved once it exists.
+    EditCreatedFile {
+        abs_path: PathBuf,
+        edits: Vec<TextEdit>,
+        language_server_id: LanguageServerId,
+    },
+    CreateFile {
+        abs_path: PathBuf,
+        overwrite: bool,
+        ignore_if_exists: bool,
+    },
+    RenameFile {
+        old_abs_path: PathBuf,
+        new_abs_path: PathBuf,
+        overwrite: bool,
+        ignore_if_exists: bool,
+    },
+    DeleteFile {
+        abs_path: PathBuf,
+        recursive: bool,
+        ignore_if_not_exists: bool,
+    },
+}
+
+impl WorkspaceEditPreview {
+    pub fn is_empty(&self) -> bool {
+        self.operations.iter().all(|operation| match operation {
+            WorkspaceEditOperation::Edit { edits, .. } => edits.is_empty(),
+            WorkspaceEditOperation::EditCreatedFile { edits, .. } => edits.is_empty(),
+            _ => false,
+        })
+    }
+
+    pub fn extend(&mut self, other: WorkspaceEditPreview) {
+        self.operations.extend(other.operations);
+    }
+}
+
+fn abs_path_from_uri(uri: &lsp::Uri) -> Result<PathBuf> {
+    uri.to_file_path()
+        .map_err(|()| anyhow!("can't convert URI to path"))
+}
+
+/// Returns the path that `abs_path` has before the given renames, which map new paths to old
+/// ones, are applied.
+fn path_before_renames(abs_path: &Path, renames: &[(PathBuf, PathBuf)]) -> PathBuf {
+    let mut abs_path = abs_path.to_path_buf();
+    for (new_abs_path, old_abs_path) in renames.iter().rev() {
+        if let Ok(suffix) = abs_path.strip_prefix(new_abs_path) {
+            abs_path = if suffix.as_os_str().is_empty() {
+                old_abs_path.clone()
+            } else {
+                old_abs_path.join(suffix)
+            };
+        }
+    }
+    abs_path
+}
+
+fn apply_edits_in_transaction(
+    buffer: &Entity<Buffer>,
+    edits: Vec<(Range<Anchor>, Arc<str>)>,
+    cx: &mut AsyncApp,
+) -> Result<Option<Transaction>> {
+    buffer.update(cx, |buffer, cx| {
+        buffer.finalize_last_transaction();
+        buffer.start_transaction();
+        for (range, text) in edits {
+            buffer.edit([(range, text)], None, cx);
+        }
+        let transaction_id = buffer.end_transaction(cx)?;
+        buffer.finalize_last_transaction();
+        buffer.get_transaction(transaction_id).cloned()
+    })
+}
+
+impl LocalLspStore {
+    /// Opens the buffers that `edit` changes and converts its text edits to anchors, without
+    /// applying anything.
+    ///
+    /// Edits to files renamed by earlier operations are resolved against the buffers of the files
+    /// before they are renamed, which follow the renames once applied. Edits to files created by
+    /// earlier operations are kept as they are, to be resolved when the preview is applied.
+    pub(crate) async fn preview_workspace_edit(
+        this: Entity<LspStore>,
+        edit: lsp::WorkspaceEdit,
+        language_server: Arc<LanguageServer>,
+        cx: &mut AsyncApp,
+    ) -> Result<WorkspaceEditPreview> {
+        let mut preview = WorkspaceEditPreview::default();
+        let mut renames = Vec::new();
+        let mut created_abs_paths = Vec::new();
+        for operation in workspace_edit_operations(edit) {
+            let operation = match operation {
+                lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Create(op)) => {
+                    let abs_path = abs_path_from_uri(&op.uri)?;
+                    created_abs_paths.push(path_before_renames(&abs_path, &renames));
+                    WorkspaceEditOperation::CreateFile {
+                        abs_path,
+                        overwrite: op
+                            .options
+                            .as_ref()
+                            .and_then(|options| options.overwrite)
+                            .unwrap_or(false),
+                        ignore_if_exists: op
+                            .options
+                            .as_ref()
+                            .and_then(|options| options.ignore_if_exists)
+                            .unwrap_or(false),
+                    }
+                }
+                lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Rename(op)) => {
+                    let old_abs_path = abs_path_from_uri(&op.old_uri)?;
+                    let new_abs_path = abs_path_from_uri(&op.new_uri)?;
+                    renames.push((new_abs_path.clone(), old_abs_path.clone()));
+                    WorkspaceEditOperation::RenameFile {
+                        old_abs_path,
+                        new_abs_path,
+                        overwrite: op
+                            .options
+                            .as_ref()
+                            .and_then(|options| options.overwrite)
+                            .unwrap_or(false),
+                        ignore_if_exists: op
+                            .options
+                            .as_ref()
+                            .and_then(|options| options.ignore_if_exists)
+                            .unwrap_or(false),
+                    }
+                }
+                lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Delete(op)) => {
+                    WorkspaceEditOperation::DeleteFile {
+                        abs_path: abs_path_from_uri(&op.uri)?,
+                        recursive: op
+                            .options
+                            .as_ref()
+                            .and_then(|options| options.recursive)
+                            .unwrap_or(false),
+                        ignore_if_not_exists: op
+                            .options
+                            .as_ref()
+                            .and_then(|options| options.ignore_if_not_exists)
+                            .unwrap_or(false),
+                    }
+                }
+                lsp::DocumentChangeOperation::Edit(op) => {
+                    let abs_path = abs_path_from_uri(&op.text_document.uri)?;
+                    let abs_path_before_renames = path_before_renames(&abs_path, &renames);
+                    let mut lsp_edits = Vec::new();
+                    for edit in op.edits {
+                        let edit = match edit {
+                            Edit::Plain(edit) => edit,
+                            Edit::Annotated(edit) => edit.text_edit,
+                            Edit::Snippet(edit) => {
+                                let Ok(snippet) = Snippet::parse(&edit.snippet.value) else {
+                                    continue;
+                                };
+                                TextEdit {
+                                    range: edit.range,
+                                    new_text: snippet.text,
+                                }
+                            }
+                        };
+                        if !lsp_edits.contains(&edit) {
+                            lsp_edits.push(edit);
+                        }
+                    }
+
+                    if created_abs_paths.iter().any(|created_abs_path| {
+                        abs_path_before_renames.starts_with(created_abs_path)
+                    }) {
+                        preview
+                            .operations
+                            .push(WorkspaceEditOperation::EditCreatedFile {
+                                abs_path,
+                                edits: lsp_edits,
+                                language_server_id: language_server.server_id(),
+                            });
+                        continue;
+                    }
+
+                    // The buffer of a renamed file is opened at its current path, so that edits
+                    // made to it after the rename is applied still reach the file.
+                    let (uri, version) = if abs_path_before_renames == abs_path {
+                        (op.text_document.uri, op.text_document.version)
+                    } else {
+                        let uri = lsp::Uri::from_file_path(&abs_path_before_renames)
+                            .map_err(|()| anyhow!("invalid path {abs_path_before_renames:?}"))?;
+                        (uri, None)
+                    };
+                    let buffer = this
+                        .update(cx, |this, cx| {
+                            this.open_local_buffer_via_lsp(
+                                uri.clone(),
+                                language_server.server_id(),
+                                cx,
+                            )
+                        })?
+                        .await
+                        .with_context(|| format!("opening {uri} to preview its edits"))?;
+
+                    let edits = this
+                        .update(cx, |this, cx| {
+                            let local = this
+                                .as_local_mut()
+                                .context("previewing a workspace edit in a remote project")?;
+                            anyhow::Ok(local.edits_from_lsp(
+                                &buffer,
+                                lsp_edits,
+                                language_server.server_id(),
+                                version,
+                                cx,
+                            ))
+                        })??
+                        .await?;
+                    WorkspaceEditOperation::Edit { buffer, edits }
+                }
+            };
+            preview.operations.push(operation);
+        }
+        Ok(preview)
+    }
+}
+
+impl LspStore {
+    /// Asks the first language server that supports renames for the edits renaming the symbol at
+    /// `position`, so they can be reviewed before being applied.
+    pub fn preview_rename(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: PointUtf16,
+        new_name: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<WorkspaceEditPreview>> {
+        let Some(local) = self.as_local() else {
+            return Task::ready(Err(anyhow!(
+                "rename previews are only supported in local projects"
+            )));
+        };
+        let request = PerformRename {
+            position,
+            new_name,
+            push_to_history: false,
+        };
+        let Some(language_server) = buffer.update(cx, |buffer, cx| {
+            local
+                .language_servers_for_buffer(buffer, cx)
+                .find(|(_, server)| {
+                    request.check_capabilities(server.adapter_server_capabilities())
+                })
+                .map(|(_, server)| server.clone())
+        }) else {
+            return Task::ready(Ok(WorkspaceEditPreview::default()));
+        };
+        let Some(file) = File::from_dyn(buffer.read(cx).file()).and_then(File::as_local) else {
+            return Task::ready(Ok(WorkspaceEditPreview::default()));
+        };
+        let lsp_params = match request.to_lsp_params_or_response(
+            &file.abs_path(cx),
+            buffer.read(cx),
+            &language_server,
+            cx,
+        ) {
+            Ok(LspParamsOrResponse::Params(lsp_params)) => lsp_params,
+            Ok(LspParamsOrResponse::Response(_)) => {
+                return Task::ready(Ok(WorkspaceEditPreview::default()));
+            }
+            Err(error) => return Task::ready(Err(error)),
+        };
+        cx.spawn(async move |this, cx| {
+            let edit = language_server
+                .request::<lsp::request::Rename>(lsp_params)
+                .await
+                .into_response()
+                .with_context(|| format!("rename via {}", language_server.name()))?;
+            let Some(edit) = edit else {
+                return Ok(WorkspaceEditPreview::default());
+            };
+            let this = this.upgrade().context("no lsp store")?;
+            LocalLspStore::preview_workspace_edit(this, edit, language_server, cx).await
+        })
+    }
+
+    /// Applies the operations of a preview in order, with the edits of each buffer in a single
+    /// transaction that is pushed to its history.
+    pub fn apply_workspace_edit_preview(
+        &self,
+        preview: WorkspaceEditPreview,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        let Some(local) = self.as_local() else {
+            return Task::ready(Err(anyhow!(
+                "workspace edit previews can only be applied in local projects"
+            )));
+        };
+        let fs = local.fs.clone();
+        cx.spawn(async move |this, cx| {
+            let mut project_transaction = ProjectTransaction::default();
+            for operation in preview.operations {
+                match operation {
+                    WorkspaceEditOperation::Edit { buffer, edits } => {
+                        if let Some(transaction) = apply_edits_in_transaction(&buffer, edits, cx)? {
+                            project_transaction.0.insert(buffer, transaction);
+                        }
+                    }
+                    WorkspaceEditOperation::EditCreatedFile {
+                        abs_path,
+                        edits,
+                        language_server_id,
+                    } => {
+                        let uri = lsp::Uri::from_file_path(&abs_path)
+                            .map_err(|()| anyhow!("invalid path {abs_path:?}"))?;
+                        let buffer = this
+                            .update(cx, |this, cx| {
+                                this.open_local_buffer_via_lsp(uri, language_server_id, cx)
+                            })?
+                            .await
+                            .with_context(|| format!("opening {abs_path:?} to apply its edits"))?;
+                        let edits = this
+                            .update(cx, |this, cx| {
+                                let local = this
+                                    .as_local_mut()
+                                    .context("applying a workspace edit in a remote project")?;
+                                anyhow::Ok(local.edits_from_lsp(
+                                    &buffer,
+                                    edits,
+                                    language_server_id,
+                                    None,
+                                    cx,
+                                ))
+                            })??
+                            .await?;
+                        if let Some(transaction) = apply_edits_in_transaction(&buffer, edits, cx)? {
+                            project_transaction.0.insert(buffer, transaction);
+                        }
+                    }
+                    WorkspaceEditOperation::CreateFile {
+                        abs_path,
+                        overwrite,
+                        ignore_if_exists,
+                    } => {
+                        if let Some(parent_path) = abs_path.parent() {
+                            fs.create_dir(parent_path).await?;
+                        }
+                        if abs_path.ends_with("/") {
+                            fs.create_dir(&abs_path).await?;
+                        } else {
+                            fs.create_file(
+                                &abs_path,
+                                fs::CreateOptions {
+                                    overwrite,
+                                    ignore_if_exists,
+                                },
+                            )
+                            .await?;
+                        }
+                    }
+                    WorkspaceEditOperation::RenameFile {
+                        old_abs_path,
+                        new_abs_path,
+                        overwrite,
+                        ignore_if_exists,
+                    } => {
+                        fs.rename(
+                            &old_abs_path,
+                            &new_abs_path,
+                            fs::RenameOptions {
+                                overwrite,
+                                ignore_if_exists,
+                                create_parents: true,
+                            },
+                        )
+                        .await?;
+                    }
+                    WorkspaceEditOperation::DeleteFile {
+                        abs_path,
+                        recursive,
+                        ignore_if_not_exists,
+                    } => {
+                        let options = fs::RemoveOptions {
+                            recursive,
+                            ignore_if_not_exists,
+                        };
+                        if abs_path.ends_with("/") {
+                            fs.remove_dir(&abs_path, options).await?;
+                        } else {
+                            fs.remove_file(&abs_path, options).await?;
+                        }
+                    }
+                }
+            }
+            Ok(project_transaction)
+        })
+    }
+}
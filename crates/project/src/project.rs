@@ -1,4 +1,5 @@
 pub mod agent_server_store;
+pub mod bookmark_store;
 pub mod buffer_store;
 mod color_extractor;
 pub mod connection_manager;
@@ -49,6 +50,7 @@ pub use manifest_tree::ManifestTree;
 pub use project_search::Search;
 
 use anyhow::{Context as _, Result, anyhow};
+use bookmark_store::BookmarkStore;
 use buffer_store::{BufferStore, BufferStoreEvent};
 use client::{Client, Collaborator, PendingEntitySubscription, TypedEnvelope, UserStore, proto};
 use clock::ReplicaId;
@@ -148,9 +150,10 @@ pub use task_inventory::{
 
 pub use buffer_store::ProjectTransaction;
 pub use lsp_store::{
-    DiagnosticSummary, InvalidationStrategy, LanguageServerLogType, LanguageServerProgress,
-    LanguageServerPromptRequest, LanguageServerStatus, LanguageServerToQuery, LspStore,
-    LspStoreEvent, ProgressToken, SERVER_PROGRESS_THROTTLE_TIMEOUT,
+    CodeLensCommand, DiagnosticSummary, InvalidationStrategy, LanguageServerLogType,
+    LanguageServerProgress, LanguageServerPromptRequest, LanguageServerStatus,
+    LanguageServerToQuery, LspStore, LspStoreEvent, ProgressToken,
+    SERVER_PROGRESS_THROTTLE_TIMEOUT, WorkspaceEditOperation, WorkspaceEditPreview,
 };
 pub use toolchain_store::{ToolchainStore, Toolchains};
 const MAX_PROJECT_SEARCH_HISTORY_SIZE: usize = 500;
@@ -188,6 +191,7 @@ pub struct Project {
     agent_server_store: Entity<AgentServerStore>,
 
     breakpoint_store: Entity<BreakpointStore>,
+    bookmark_store: Entity<BookmarkStore>,
     collab_client: Arc<client::Client>,
     join_project_response_message_id: u32,
     task_store: Entity<TaskStore>,
@@ -348,6 +352,12 @@ pub enum Event {
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
     ExpandedAllForEntry(WorktreeId, ProjectEntryId),
     EntryRenamed(ProjectTransaction, ProjectPath, PathBuf),
+    /// Language server edits that should be reviewed before being applied, as requested by the
+    /// `preview_renames` setting.
+    WorkspaceEditPreviewRequested {
+        title: SharedString,
+        preview: WorkspaceEditPreview,
+    },
     AgentLocationChanged,
 }
 
@@ -1097,6 +1107,7 @@ impl Project {
 
             let breakpoint_store =
                 cx.new(|_| BreakpointStore::local(worktree_store.clone(), buffer_store.clone()));
+            let bookmark_store = cx.new(|cx| BookmarkStore::new(buffer_store.clone(), cx));
 
             let dap_store = cx.new(|cx| {
                 DapStore::new_local(
@@ -1148,11 +1159,22 @@ impl Project {
             cx.subscribe(&settings_observer, Self::on_settings_observer_event)
                 .detach();
 
+            let git_store = cx.new(|cx| {
+                GitStore::local(
+                    &worktree_store,
+                    buffer_store.clone(),
+                    environment.clone(),
+                    fs.clone(),
+                    cx,
+                )
+            });
+
             let lsp_store = cx.new(|cx| {
                 LspStore::new_local(
                     buffer_store.clone(),
                     worktree_store.clone(),
                     prettier_store.clone(),
+                    git_store.clone(),
                     toolchain_store
                         .read(cx)
                         .as_local_store()
@@ -1167,16 +1189,6 @@ impl Project {
                 )
             });
 
-            let git_store = cx.new(|cx| {
-                GitStore::local(
-                    &worktree_store,
-                    buffer_store.clone(),
-                    environment.clone(),
-                    fs.clone(),
-                    cx,
-                )
-            });
-
             let agent_server_store = cx.new(|cx| {
                 AgentServerStore::local(
                     node.clone(),
@@ -1212,6 +1224,7 @@ impl Project {
                 fs,
                 remote_client: None,
                 breakpoint_store,
+                bookmark_store,
                 dap_store,
                 agent_server_store,
 
@@ -1335,6 +1348,7 @@ impl Project {
 
             let breakpoint_store =
                 cx.new(|_| BreakpointStore::remote(REMOTE_SERVER_PROJECT_ID, remote_proto.clone()));
+            let bookmark_store = cx.new(|cx| BookmarkStore::new(buffer_store.clone(), cx));
 
             let dap_store = cx.new(|cx| {
                 DapStore::new_remote(
@@ -1373,6 +1387,7 @@ impl Project {
                 lsp_store,
                 context_server_store,
                 breakpoint_store,
+                bookmark_store,
                 dap_store,
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
@@ -1537,6 +1552,7 @@ impl Project {
             cx.new(|cx| ProjectEnvironment::new(None, worktree_store.downgrade(), None, true, cx))?;
         let breakpoint_store =
             cx.new(|_| BreakpointStore::remote(remote_id, client.clone().into()))?;
+        let bookmark_store = cx.new(|cx| BookmarkStore::new(buffer_store.clone(), cx))?;
         let dap_store = cx.new(|cx| {
             DapStore::new_collab(
                 remote_id,
@@ -1660,6 +1676,7 @@ impl Project {
                     replica_id,
                 },
                 breakpoint_store,
+                bookmark_store,
                 dap_store: dap_store.clone(),
                 git_store: git_store.clone(),
                 agent_server_store,
@@ -1869,6 +1886,11 @@ impl Project {
         self.breakpoint_store.clone()
     }
 
+    #[inline]
+    pub fn bookmark_store(&self) -> Entity<BookmarkStore> {
+        self.bookmark_store.clone()
+    }
+
     pub fn active_debug_session(&self, cx: &App) -> Option<(Entity<Session>, ActiveStackFrame)> {
         let active_position = self.breakpoint_store.read(cx).active_position()?;
         let session = self
@@ -2220,6 +2242,9 @@ impl Project {
         let is_root_entry = self.entry_is_worktree_root(entry_id, cx);
 
         let lsp_store = self.lsp_store().downgrade();
+        let preview_renames = ProjectSettings::get_global(cx)
+            .global_lsp_settings
+            .preview_renames;
         cx.spawn(async move |project, cx| {
             let (old_abs_path, new_abs_path) = {
                 let root_path = worktree.read_with(cx, |this, _| this.abs_path())?;
@@ -2233,15 +2258,29 @@ impl Project {
                 };
                 (root_path.join(old_path.as_std_path()), new_abs_path)
             };
-            let transaction = LspStore::will_rename_entry(
-                lsp_store.clone(),
-                worktree_id,
-                &old_abs_path,
-                &new_abs_path,
-                is_dir,
-                cx.clone(),
-            )
-            .await;
+            let (transaction, preview) = if preview_renames {
+                let preview = LspStore::preview_will_rename_entry(
+                    lsp_store.clone(),
+                    worktree_id,
+                    &old_abs_path,
+                    &new_abs_path,
+                    is_dir,
+                    cx.clone(),
+                )
+                .await;
+                (ProjectTransaction::default(), Some(preview))
+            } else {
+                let transaction = LspStore::will_rename_entry(
+                    lsp_store.clone(),
+                    worktree_id,
+                    &old_abs_path,
+                    &new_abs_path,
+                    is_dir,
+                    cx.clone(),
+                )
+                .await;
+                (transaction, None)
+            };
 
             let entry = worktree_store
                 .update(cx, |worktree_store, cx| {
@@ -2256,6 +2295,17 @@ impl Project {
                         new_path.clone(),
                         new_abs_path.clone(),
                     ));
+                    if let Some(preview) = preview.filter(|preview| !preview.is_empty()) {
+                        cx.emit(Event::WorkspaceEditPreviewRequested {
+                            title: format!(
+                                "Rename {} to {}",
+                                old_path.display(PathStyle::local()),
+                                new_path.path.display(PathStyle::local())
+                            )
+                            .into(),
+                            preview,
+                        });
+                    }
                 })
                 .ok();
 
@@ -3636,6 +3686,19 @@ impl Project {
         })
     }
 
+    /// Opens the window starting at byte `start` of a file opened in large-file mode, replacing
+    /// the given buffer as the project's buffer for that file.
+    pub fn open_large_file_window(
+        &self,
+        buffer: &Entity<Buffer>,
+        start: u64,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Buffer>>> {
+        self.buffer_store.update(cx, |buffer_store, cx| {
+            buffer_store.open_large_file_window(buffer, start, cx)
+        })
+    }
+
     pub fn reload_images(
         &self,
         images: HashSet<Entity<ImageItem>>,
@@ -3900,27 +3963,44 @@ impl Project {
         let code_lens_actions = self
             .lsp_store
             .update(cx, |lsp_store, cx| lsp_store.code_lens_actions(buffer, cx));
+        let lsp_store = self.lsp_store.downgrade();
 
-        cx.background_spawn(async move {
+        cx.spawn(async move |_, cx| {
             let mut code_lens_actions = code_lens_actions
                 .await
                 .map_err(|e| anyhow!("code lens fetch failed: {e:#}"))?;
             if let Some(code_lens_actions) = &mut code_lens_actions {
-                code_lens_actions.retain(|code_lens_action| {
-                    range
-                        .start
-                        .cmp(&code_lens_action.range.start, &snapshot)
-                        .is_ge()
-                        && range
-                            .end
-                            .cmp(&code_lens_action.range.end, &snapshot)
-                            .is_le()
-                });
+                lsp_store.read_with(cx, |lsp_store, _| {
+                    code_lens_actions.retain(|code_lens_action| {
+                        range
+                            .start
+                            .cmp(&code_lens_action.range.start, &snapshot)
+                            .is_ge()
+                            && range
+                                .end
+                                .cmp(&code_lens_action.range.end, &snapshot)
+                                .is_le()
+                            && lsp_store.code_lens_command(code_lens_action)
+                                == Some(CodeLensCommand::Server)
+                    });
+                })?;
             }
             Ok(code_lens_actions)
         })
     }
 
+    /// Resolves the command of a code lens, so that its title can be displayed.
+    pub fn resolve_code_lens(
+        &self,
+        buffer: &Entity<Buffer>,
+        action: CodeAction,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<CodeAction>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.resolve_code_lens(buffer, action, cx)
+        })
+    }
+
     pub fn apply_code_action(
         &self,
         buffer_handle: Entity<Buffer>,
@@ -3981,6 +4061,30 @@ impl Project {
         )
     }
 
+    /// Computes the edits of a rename without applying them, so they can be reviewed first.
+    pub fn preview_rename<T: ToPointUtf16>(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: T,
+        new_name: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<WorkspaceEditPreview>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.preview_rename(buffer, position, new_name, cx)
+        })
+    }
+
+    pub fn apply_workspace_edit_preview(
+        &mut self,
+        preview: WorkspaceEditPreview,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.apply_workspace_edit_preview(preview, cx)
+        })
+    }
+
     pub fn on_type_format<T: ToPointUtf16>(
         &mut self,
         buffer: Entity<Buffer>,
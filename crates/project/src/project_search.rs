@@ -318,6 +318,7 @@ impl Search {
                     Some(
                         Self::grab_buffer_snapshots(
                             grab_buffer_snapshot_rx,
+                            query.is_structural(),
                             find_all_matches_tx,
                             sorted_matches_tx,
                             cx.clone(),
@@ -480,6 +481,7 @@ impl Search {
 
     async fn grab_buffer_snapshots(
         rx: Receiver<Entity<Buffer>>,
+        wait_for_parsing: bool,
         find_all_matches_tx: Sender<(
             Entity<Buffer>,
             BufferSnapshot,
@@ -490,6 +492,12 @@ impl Search {
     ) {
         _ = maybe!(async move {
             while let Ok(buffer) = rx.recv().await {
+                // Structural queries match against syntax trees, which newly opened buffers don't have yet.
+                if wait_for_parsing {
+                    buffer
+                        .read_with(&mut cx, |buffer, _| buffer.parsing_idle())?
+                        .await;
+                }
                 let snapshot = buffer.read_with(&mut cx, |this, _| this.snapshot())?;
                 let (tx, rx) = oneshot::channel();
                 find_all_matches_tx.send((buffer, snapshot, tx)).await?;
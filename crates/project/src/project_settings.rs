@@ -112,6 +112,10 @@ pub struct GlobalLspSettings {
     ///
     /// Default: `true`
     pub button: bool,
+    /// Whether to review the edits that language servers make for a rename before applying them.
+    ///
+    /// Default: `false`
+    pub preview_renames: bool,
 }
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -543,6 +547,12 @@ impl Settings for ProjectSettings {
                     .unwrap()
                     .button
                     .unwrap(),
+                preview_renames: content
+                    .global_lsp_settings
+                    .as_ref()
+                    .unwrap()
+                    .preview_renames
+                    .unwrap(),
             },
             dap: project
                 .dap
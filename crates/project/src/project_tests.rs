@@ -4239,6 +4239,175 @@ async fn test_file_changes_multiple_times_on_disk(cx: &mut gpui::TestAppContext)
     });
 }
 
+#[gpui::test]
+async fn test_large_file_mode(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.project.worktree.large_file_threshold_mb = Some(1);
+            });
+        });
+    });
+
+    let line = "2024-01-01T00:00:00Z INFO request handled\r\n";
+    let lines_per_window = 1024 * 1024 / line.len();
+    let mut large_text = line.repeat(2 * lines_per_window + 10);
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "large.log": large_text,
+            "small.log": line,
+            "binary.dat": "\0".repeat(2 * 1024 * 1024),
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let worktree = project.read_with(cx, |project, cx| project.worktrees(cx).next().unwrap());
+    let small_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/small.log"), cx)
+        })
+        .await
+        .unwrap();
+    small_buffer.read_with(cx, |buffer, _| {
+        assert!(!buffer.is_large_file());
+        assert!(!buffer.read_only());
+    });
+
+    let binary = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/binary.dat"), cx)
+        })
+        .await;
+    assert!(binary.is_err());
+
+    // Only the first window of the file is loaded.
+    let window_len = (lines_per_window * line.len()) as u64;
+    let window_text = line.replace("\r\n", "\n").repeat(lines_per_window);
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/large.log"), cx)
+        })
+        .await
+        .unwrap();
+    buffer.read_with(cx, |buffer, _| {
+        assert!(buffer.is_large_file());
+        assert!(buffer.read_only());
+        assert_eq!(buffer.line_ending(), LineEnding::Windows);
+        assert_eq!(buffer.text(), window_text);
+        let window = buffer.large_file_window().unwrap();
+        assert_eq!(window.range, 0..window_len);
+        assert!(!window.reaches_end());
+    });
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_large_file_window(&buffer, window_len, cx)
+        })
+        .await
+        .unwrap();
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), window_text);
+        assert_eq!(
+            buffer.large_file_window().unwrap().range,
+            window_len..2 * window_len
+        );
+    });
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_large_file_window(&buffer, 2 * window_len, cx)
+        })
+        .await
+        .unwrap();
+    let mut expected_text = line.replace("\r\n", "\n").repeat(10);
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), expected_text);
+        assert!(buffer.large_file_window().unwrap().reaches_end());
+    });
+
+    // Text appended to the file is appended to the last window.
+    large_text.push_str("2024-01-01T00:00:01Z WARN request failed\r\n");
+    expected_text.push_str("2024-01-01T00:00:01Z WARN request failed\n");
+    fs.save(
+        path!("/dir/large.log").as_ref(),
+        &large_text.replace("\r\n", "\n").as_str().into(),
+        LineEnding::Windows,
+    )
+    .await
+    .unwrap();
+    worktree.next_event(cx).await;
+    cx.executor().run_until_parked();
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), expected_text);
+        assert!(!buffer.is_dirty());
+        assert!(!buffer.has_conflict());
+    });
+
+    // Once the last window would grow past its maximum size, it stops following the file and
+    // asks to be replaced with a window at the new end.
+    let events = Arc::new(Mutex::new(Vec::new()));
+    buffer.update(cx, |_, cx| {
+        cx.subscribe(&buffer, {
+            let events = events.clone();
+            move |_, _, event, _| events.lock().push(event.clone())
+        })
+        .detach();
+    });
+    large_text.push_str(&line.repeat(lines_per_window));
+    fs.save(
+        path!("/dir/large.log").as_ref(),
+        &large_text.replace("\r\n", "\n").as_str().into(),
+        LineEnding::Windows,
+    )
+    .await
+    .unwrap();
+    worktree.next_event(cx).await;
+    cx.executor().run_until_parked();
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), expected_text);
+        assert!(!buffer.large_file_window().unwrap().reaches_end());
+    });
+    assert!(events.lock().contains(&BufferEvent::LargeFileWindowFull));
+
+    // The previous window ends where the current one starts.
+    let previous_start = buffer.read_with(cx, |buffer, _| {
+        let window = buffer.large_file_window().unwrap();
+        window.range.start - window.max_len
+    });
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_large_file_window(&buffer, previous_start, cx)
+        })
+        .await
+        .unwrap();
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), window_text);
+        assert_eq!(
+            buffer.large_file_window().unwrap().range,
+            window_len..2 * window_len
+        );
+    });
+
+    // When the file is truncated before the window, the window is reloaded from the start.
+    fs.save(
+        path!("/dir/large.log").as_ref(),
+        &"rotated\n".into(),
+        LineEnding::Windows,
+    )
+    .await
+    .unwrap();
+    worktree.next_event(cx).await;
+    cx.executor().run_until_parked();
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "rotated\n");
+        assert_eq!(buffer.large_file_window().unwrap().range, 0..9);
+        assert!(!buffer.is_dirty());
+    });
+}
+
 #[gpui::test(iterations = 30)]
 async fn test_edit_buffer_while_it_reloads(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4992,6 +5161,77 @@ async fn test_buffer_file_changes_on_disk(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_bookmarks_reanchor_by_line_content(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "the-file": "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\n",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let bookmark_store = project.read_with(cx, |project, _| project.bookmark_store());
+    let bookmark_rows = |cx: &mut gpui::TestAppContext| {
+        bookmark_store.read_with(cx, |bookmark_store, cx| {
+            bookmark_store
+                .bookmarks(cx)
+                .into_iter()
+                .map(|bookmark| (bookmark.row, bookmark.line_text.to_string()))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    // The file was edited since the bookmark was saved, so it gets anchored by its line's content once
+    // the file is opened.
+    let abs_path: Arc<Path> = Path::new(path!("/dir/the-file")).into();
+    bookmark_store.update(cx, |bookmark_store, cx| {
+        bookmark_store.with_serialized_bookmarks(
+            [(
+                abs_path,
+                vec![bookmark_store::SerializedBookmark {
+                    row: 0,
+                    label: None,
+                    list: None,
+                    line_text: "fn c() {}".into(),
+                }],
+            )]
+            .into_iter()
+            .collect(),
+            cx,
+        )
+    });
+    assert_eq!(bookmark_rows(cx), [(0, "fn c() {}".to_string())]);
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/the-file"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(bookmark_rows(cx), [(2, "fn c() {}".to_string())]);
+
+    // Bookmarks follow their line when the file is changed outside of the editor.
+    fs.save(
+        path!("/dir/the-file").as_ref(),
+        &"fn d() {}\nfn c() {}\nfn b() {}\nfn a() {}\n".into(),
+        LineEnding::Unix,
+    )
+    .await
+    .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(bookmark_rows(cx), [(1, "fn c() {}".to_string())]);
+
+    bookmark_store.update(cx, |bookmark_store, cx| {
+        bookmark_store.toggle_bookmark(&buffer, 1, cx)
+    });
+    assert!(bookmark_rows(cx).is_empty());
+}
+
 #[gpui::test]
 async fn test_buffer_line_endings(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -5590,6 +5830,267 @@ async fn test_rename(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_preview_rename(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "const ONE: usize = 1;",
+            "two.rs": "const TWO: usize = one::ONE + one::ONE;"
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                rename_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/one.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+
+    let response = project.update(cx, |project, cx| {
+        project.preview_rename(buffer.clone(), 7, "THREE".to_string(), cx)
+    });
+    fake_server
+        .set_request_handler::<lsp::request::Rename, _, _>(|_, _| async move {
+            Ok(Some(lsp::WorkspaceEdit {
+                document_changes: Some(lsp::DocumentChanges::Operations(vec![
+                    lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                        text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                            uri: lsp::Uri::from_file_path(path!("/dir/one.rs")).unwrap(),
+                            version: None,
+                        },
+                        edits: vec![lsp::OneOf::Left(lsp::TextEdit::new(
+                            lsp::Range::new(lsp::Position::new(0, 6), lsp::Position::new(0, 9)),
+                            "THREE".to_string(),
+                        ))],
+                    }),
+                    lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                        text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                            uri: lsp::Uri::from_file_path(path!("/dir/two.rs")).unwrap(),
+                            version: None,
+                        },
+                        edits: vec![
+                            lsp::OneOf::Left(lsp::TextEdit::new(
+                                lsp::Range::new(
+                                    lsp::Position::new(0, 24),
+                                    lsp::Position::new(0, 27),
+                                ),
+                                "THREE".to_string(),
+                            )),
+                            lsp::OneOf::Left(lsp::TextEdit::new(
+                                lsp::Range::new(
+                                    lsp::Position::new(0, 35),
+                                    lsp::Position::new(0, 38),
+                                ),
+                                "THREE".to_string(),
+                            )),
+                        ],
+                    }),
+                    lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Rename(lsp::RenameFile {
+                        old_uri: lsp::Uri::from_file_path(path!("/dir/one.rs")).unwrap(),
+                        new_uri: lsp::Uri::from_file_path(path!("/dir/three.rs")).unwrap(),
+                        options: None,
+                        annotation_id: None,
+                    })),
+                ])),
+                ..Default::default()
+            }))
+        })
+        .next()
+        .await
+        .unwrap();
+    let mut preview = response.await.unwrap();
+    assert_eq!(preview.operations.len(), 3);
+    assert_eq!(
+        buffer.read_with(cx, |buffer, _| buffer.text()),
+        "const ONE: usize = 1;",
+        "previewing a rename should not edit any buffer"
+    );
+    assert!(fs.is_file(path!("/dir/one.rs").as_ref()).await);
+
+    // Opt out of the second occurrence in `two.rs` and of renaming the file.
+    preview.operations.pop();
+    let WorkspaceEditOperation::Edit {
+        buffer: two_buffer,
+        edits,
+    } = &mut preview.operations[1]
+    else {
+        panic!("expected the edits of two.rs");
+    };
+    edits.pop();
+    let two_buffer = two_buffer.clone();
+
+    let transaction = project
+        .update(cx, |project, cx| {
+            project.apply_workspace_edit_preview(preview, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(transaction.0.len(), 2);
+    assert_eq!(
+        buffer.read_with(cx, |buffer, _| buffer.text()),
+        "const THREE: usize = 1;"
+    );
+    assert_eq!(
+        two_buffer.read_with(cx, |buffer, _| buffer.text()),
+        "const TWO: usize = one::THREE + one::ONE;"
+    );
+    assert!(fs.is_file(path!("/dir/one.rs").as_ref()).await);
+
+    two_buffer.update(cx, |buffer, cx| buffer.undo(cx));
+    assert_eq!(
+        two_buffer.read_with(cx, |buffer, _| buffer.text()),
+        "const TWO: usize = one::ONE + one::ONE;"
+    );
+}
+
+#[gpui::test]
+async fn test_preview_workspace_edit_after_file_operations(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "const ONE: usize = 1;",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                rename_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/one.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+
+    let response = project.update(cx, |project, cx| {
+        project.preview_rename(buffer.clone(), 7, "THREE".to_string(), cx)
+    });
+    fake_server
+        .set_request_handler::<lsp::request::Rename, _, _>(|_, _| async move {
+            Ok(Some(lsp::WorkspaceEdit {
+                document_changes: Some(lsp::DocumentChanges::Operations(vec![
+                    lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Rename(lsp::RenameFile {
+                        old_uri: lsp::Uri::from_file_path(path!("/dir/one.rs")).unwrap(),
+                        new_uri: lsp::Uri::from_file_path(path!("/dir/three.rs")).unwrap(),
+                        options: None,
+                        annotation_id: None,
+                    })),
+                    lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                        text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                            uri: lsp::Uri::from_file_path(path!("/dir/three.rs")).unwrap(),
+                            version: None,
+                        },
+                        edits: vec![lsp::OneOf::Left(lsp::TextEdit::new(
+                            lsp::Range::new(lsp::Position::new(0, 6), lsp::Position::new(0, 9)),
+                            "THREE".to_string(),
+                        ))],
+                    }),
+                    lsp::DocumentChangeOperation::Op(lsp::ResourceOp::Create(lsp::CreateFile {
+                        uri: lsp::Uri::from_file_path(path!("/dir/four.rs")).unwrap(),
+                        options: None,
+                        annotation_id: None,
+                    })),
+                    lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                        text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                            uri: lsp::Uri::from_file_path(path!("/dir/four.rs")).unwrap(),
+                            version: None,
+                        },
+                        edits: vec![lsp::OneOf::Left(lsp::TextEdit::new(
+                            lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 0)),
+                            "use three::THREE;".to_string(),
+                        ))],
+                    }),
+                ])),
+                ..Default::default()
+            }))
+        })
+        .next()
+        .await
+        .unwrap();
+    let preview = response.await.unwrap();
+    assert_eq!(preview.operations.len(), 4);
+    assert!(
+        matches!(
+            &preview.operations[1],
+            WorkspaceEditOperation::Edit { buffer: edited_buffer, .. } if *edited_buffer == buffer
+        ),
+        "edits to a renamed file should apply to the buffer of the file before the rename"
+    );
+    assert!(matches!(
+        &preview.operations[3],
+        WorkspaceEditOperation::EditCreatedFile { .. }
+    ));
+    assert!(!fs.is_file(path!("/dir/four.rs").as_ref()).await);
+
+    let transaction = project
+        .update(cx, |project, cx| {
+            project.apply_workspace_edit_preview(preview, cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(transaction.0.len(), 2);
+    assert!(fs.is_file(path!("/dir/three.rs").as_ref()).await);
+    assert!(!fs.is_file(path!("/dir/one.rs").as_ref()).await);
+    buffer.read_with(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "const THREE: usize = 1;");
+        assert_eq!(
+            buffer.file().unwrap().full_path(cx),
+            Path::new("dir/three.rs")
+        );
+    });
+    let four_buffer = transaction
+        .0
+        .keys()
+        .find(|transaction_buffer| **transaction_buffer != buffer)
+        .unwrap()
+        .clone();
+    assert_eq!(
+        four_buffer.read_with(cx, |buffer, _| buffer.text()),
+        "use three::THREE;"
+    );
+}
+
 #[gpui::test]
 async fn test_search(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -6464,6 +6965,59 @@ async fn test_search_with_unicode(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "fn main() {\n    foo(1, 2);\n    foo(3);\n}\n",
+            "two.rs": "fn two() -> i32 {\n    foo(bar(1), /* b */ 2)\n}\n",
+            "three.rs": "// foo(1, 2)\n",
+            "four.txt": "foo(1, 2)\n",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+
+    let query = SearchQuery::structural(
+        "foo($A, $B)",
+        false,
+        Default::default(),
+        Default::default(),
+        false,
+        None,
+    )
+    .unwrap()
+    .with_replacement("bar($B, $A)".to_string());
+    assert!(query.is_structural());
+    assert!(
+        SearchQuery::from_proto(query.to_proto(), PathStyle::local())
+            .unwrap()
+            .is_structural()
+    );
+    assert_eq!(
+        search(&project, query.clone(), cx).await.unwrap(),
+        HashMap::from_iter([
+            (path!("dir/one.rs").to_string(), vec![16..25]),
+            (path!("dir/two.rs").to_string(), vec![22..44]),
+        ])
+    );
+
+    let rust = rust_lang();
+    assert_eq!(
+        query
+            .replacement_for("foo(bar(1), /* b */ 2)", Some(&rust))
+            .as_deref(),
+        Some("bar(2, bar(1))")
+    );
+    assert_eq!(query.replacement_for("foo(1, 2)", None), None);
+}
+
 #[gpui::test]
 async fn test_create_entry(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -0,0 +1,475 @@
+use std::{cmp::Reverse, fmt, ops::Range, sync::Arc};
+
+use anyhow::Result;
+use collections::{HashMap, HashSet};
+use language::{BufferSnapshot, Grammar, GrammarId, Language, Node, Tree, with_parser};
+use parking_lot::Mutex;
+
+const METAVARIABLE_PREFIX: &str = "zed_metavariable_";
+const SEQUENCE_METAVARIABLE_PREFIX: &str = "zed_metavariables_";
+
+/// Code that a pattern is wrapped in when it doesn't parse on its own, e.g. because the grammar
+/// only accepts statements inside of a function body. The first one that parses without errors
+/// is used.
+const PATTERN_CONTEXTS: &[(&str, &str)] = &[
+    ("", ""),
+    ("", ";"),
+    ("fn zed_pattern() {\n", "\n}"),
+    ("function zed_pattern() {\n", "\n}"),
+    ("func zed_pattern() {\n", "\n}"),
+    ("void zed_pattern() {\n", "\n}"),
+    ("class ZedPattern { void zed_pattern() {\n", "\n} }"),
+    ("def zed_pattern():\n    ", "\n"),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Metavariable {
+    name: String,
+    /// Whether this is a `$$$NAME` metavariable, which matches any number of sibling nodes.
+    is_sequence: bool,
+}
+
+impl Metavariable {
+    /// `$_` and `$$$_` match anything without recording what they matched.
+    fn is_anonymous(&self) -> bool {
+        self.name == "_"
+    }
+}
+
+/// A pattern parsed with the grammar of one language.
+struct CompiledPattern {
+    tree: Tree,
+    source: String,
+    context: (&'static str, &'static str),
+    root_range: Range<usize>,
+}
+
+impl CompiledPattern {
+    fn root(&self) -> Option<Node<'_>> {
+        node_for_range(&self.tree, self.root_range.clone())
+    }
+}
+
+/// A snippet of code with metavariables, matched against syntax trees instead of text.
+///
+/// `$NAME` matches any single node and `$$$NAME` matches any number of sibling nodes. A
+/// metavariable that appears more than once must match the same text everywhere.
+pub struct StructuralPattern {
+    /// The pattern, with each metavariable replaced by an identifier so that it can be parsed.
+    text: String,
+    metavariables: HashMap<String, Metavariable>,
+    /// Words that every match contains, used to skip files before parsing them.
+    required_words: Vec<String>,
+    compiled_patterns: Mutex<HashMap<GrammarId, Option<Arc<CompiledPattern>>>>,
+}
+
+impl fmt::Debug for StructuralPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StructuralPattern")
+            .field("text", &self.text)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StructuralPattern {
+    pub fn new(query: &str) -> Result<Self> {
+        let (text, metavariables) = substitute_metavariables(query.trim());
+        anyhow::ensure!(!text.is_empty(), "structural search pattern is empty");
+        anyhow::ensure!(
+            !metavariables.contains_key(&text),
+            "structural search pattern must contain code besides metavariables"
+        );
+        let mut seen_words = HashSet::default();
+        let required_words = text
+            .split(|character: char| !(character.is_alphanumeric() || character == '_'))
+            .filter(|word| {
+                !word.is_empty()
+                    && !word.starts_with(METAVARIABLE_PREFIX)
+                    && !word.starts_with(SEQUENCE_METAVARIABLE_PREFIX)
+            })
+            .filter(|word| seen_words.insert(*word))
+            .map(ToOwned::to_owned)
+            .collect();
+        Ok(Self {
+            text,
+            metavariables,
+            required_words,
+            compiled_patterns: Mutex::default(),
+        })
+    }
+
+    /// Whether `text` could contain a match, without parsing it.
+    pub fn may_match(&self, text: &str) -> bool {
+        self.required_words.iter().all(|word| text.contains(word))
+    }
+
+    /// Returns the ranges of the outermost nodes in `range` that match this pattern, across every
+    /// syntax layer of the buffer whose grammar can parse the pattern.
+    pub fn search(&self, buffer: &BufferSnapshot, range: Range<usize>) -> Vec<Range<usize>> {
+        let candidate_text = |range: Range<usize>| buffer.text_for_range(range).collect::<String>();
+        let mut matches = Vec::new();
+        for layer in buffer.syntax_layers() {
+            let Some(pattern) = self.compile(layer.language) else {
+                continue;
+            };
+            let Some(pattern_root) = pattern.root() else {
+                continue;
+            };
+            let matcher = Matcher {
+                metavariables: &self.metavariables,
+                pattern_source: &pattern.source,
+                candidate_text: &candidate_text,
+            };
+            let mut cursor = layer.node().walk();
+            'traversal: loop {
+                let node = cursor.node();
+                let is_contained = range.start <= node.start_byte() && node.end_byte() <= range.end;
+                let overlaps = node.start_byte() < range.end && range.start < node.end_byte();
+                if is_contained
+                    && node.kind_id() == pattern_root.kind_id()
+                    && matcher.matches(pattern_root, node).is_some()
+                {
+                    matches.push(node.byte_range());
+                } else if overlaps && cursor.goto_first_child() {
+                    continue;
+                }
+                while !cursor.goto_next_sibling() {
+                    if !cursor.goto_parent() {
+                        break 'traversal;
+                    }
+                }
+            }
+        }
+        matches.sort_by_key(|range| (range.start, Reverse(range.end)));
+        matches.dedup();
+        matches
+    }
+
+    /// Matches `text`, which is expected to be a match of this pattern, once more to fill the
+    /// metavariables in `template` with the code they matched.
+    pub fn replacement_for(
+        &self,
+        text: &str,
+        template: &str,
+        language: &Arc<Language>,
+    ) -> Option<String> {
+        let pattern = self.compile(language)?;
+        let pattern_root = pattern.root()?;
+        let (tree, source, range) = parse_in_context(language.grammar()?, text, pattern.context)?;
+        let candidate = node_for_range(&tree, range)?;
+        let candidate_text = |range: Range<usize>| source[range].to_string();
+        let matcher = Matcher {
+            metavariables: &self.metavariables,
+            pattern_source: &pattern.source,
+            candidate_text: &candidate_text,
+        };
+        let bindings = matcher.matches(pattern_root, candidate)?;
+        Some(render_template(template, &bindings))
+    }
+
+    fn compile(&self, language: &Language) -> Option<Arc<CompiledPattern>> {
+        let grammar = language.grammar()?;
+        self.compiled_patterns
+            .lock()
+            .entry(grammar.id())
+            .or_insert_with(|| self.compile_for_grammar(grammar).map(Arc::new))
+            .clone()
+    }
+
+    fn compile_for_grammar(&self, grammar: &Grammar) -> Option<CompiledPattern> {
+        PATTERN_CONTEXTS.iter().find_map(|&context| {
+            let (tree, source, root_range) = parse_in_context(grammar, &self.text, context)?;
+            if tree.root_node().has_error() {
+                return None;
+            }
+            let pattern = CompiledPattern {
+                tree,
+                source,
+                context,
+                root_range,
+            };
+            let is_valid = pattern.root().is_some_and(|root| {
+                !self
+                    .metavariables
+                    .contains_key(&pattern.source[root.byte_range()])
+            });
+            is_valid.then_some(pattern)
+        })
+    }
+}
+
+type Bindings = HashMap<String, String>;
+
+struct Matcher<'a> {
+    metavariables: &'a HashMap<String, Metavariable>,
+    pattern_source: &'a str,
+    candidate_text: &'a dyn Fn(Range<usize>) -> String,
+}
+
+impl Matcher<'_> {
+    fn matches(&self, pattern: Node, candidate: Node) -> Option<Bindings> {
+        let mut bindings = Bindings::default();
+        self.match_node(pattern, candidate, &mut bindings)
+            .then_some(bindings)
+    }
+
+    fn metavariable(&self, pattern: Node) -> Option<&Metavariable> {
+        self.metavariables
+            .get(&self.pattern_source[pattern.byte_range()])
+    }
+
+    fn match_node(&self, pattern: Node, candidate: Node, bindings: &mut Bindings) -> bool {
+        if let Some(metavariable) = self.metavariable(pattern) {
+            let text = (self.candidate_text)(candidate.byte_range());
+            return bind(metavariable, text, bindings);
+        }
+        if pattern.kind_id() != candidate.kind_id() {
+            return false;
+        }
+        let pattern_children = significant_children(pattern);
+        let candidate_children = significant_children(candidate);
+        if pattern_children.is_empty() {
+            return candidate_children.is_empty()
+                && self.pattern_source[pattern.byte_range()]
+                    == (self.candidate_text)(candidate.byte_range());
+        }
+        self.match_siblings(&pattern_children, &candidate_children, bindings)
+    }
+
+    fn match_siblings(
+        &self,
+        patterns: &[Node],
+        candidates: &[Node],
+        bindings: &mut Bindings,
+    ) -> bool {
+        let Some((pattern, remaining_patterns)) = patterns.split_first() else {
+            return candidates.is_empty();
+        };
+        if let Some(metavariable) = self
+            .metavariable(*pattern)
+            .filter(|metavariable| metavariable.is_sequence)
+        {
+            for count in 0..=candidates.len() {
+                let text = match candidates[..count] {
+                    [] => String::new(),
+                    [first, .., last] => (self.candidate_text)(first.start_byte()..last.end_byte()),
+                    [single] => (self.candidate_text)(single.byte_range()),
+                };
+                let mut attempt = bindings.clone();
+                if bind(metavariable, text, &mut attempt)
+                    && self.match_siblings(remaining_patterns, &candidates[count..], &mut attempt)
+                {
+                    *bindings = attempt;
+                    return true;
+                }
+            }
+            return false;
+        }
+        let Some((candidate, remaining_candidates)) = candidates.split_first() else {
+            return false;
+        };
+        let mut attempt = bindings.clone();
+        if self.match_node(*pattern, *candidate, &mut attempt)
+            && self.match_siblings(remaining_patterns, remaining_candidates, &mut attempt)
+        {
+            *bindings = attempt;
+            return true;
+        }
+        false
+    }
+}
+
+fn bind(metavariable: &Metavariable, text: String, bindings: &mut Bindings) -> bool {
+    if metavariable.is_anonymous() {
+        return true;
+    }
+    match bindings.get(&metavariable.name) {
+        Some(bound_text) => *bound_text == text,
+        None => {
+            bindings.insert(metavariable.name.clone(), text);
+            true
+        }
+    }
+}
+
+/// Children that take part in matching, leaving out comments and nodes inserted by error
+/// recovery.
+fn significant_children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|child| !child.is_extra() && !child.is_missing())
+        .collect()
+}
+
+fn parse_in_context(
+    grammar: &Grammar,
+    text: &str,
+    (prefix, suffix): (&str, &str),
+) -> Option<(Tree, String, Range<usize>)> {
+    let source = format!("{prefix}{text}{suffix}");
+    let tree = with_parser(|parser| {
+        parser.set_language(&grammar.ts_language).ok()?;
+        parser.parse(&source, None)
+    })?;
+    let range = prefix.len()..prefix.len() + text.len();
+    Some((tree, source, range))
+}
+
+/// Returns the innermost node spanning exactly `range`.
+fn node_for_range(tree: &Tree, range: Range<usize>) -> Option<Node<'_>> {
+    let node = tree
+        .root_node()
+        .descendant_for_byte_range(range.start, range.end)?;
+    (node.byte_range() == range).then_some(node)
+}
+
+enum TemplatePart<'a> {
+    Text(&'a str),
+    Metavariable { name: &'a str, is_sequence: bool },
+}
+
+/// Splits `text` into metavariables and the text between them. `$` signs that aren't followed
+/// by an uppercase name, such as in `$foo` or `$$A`, are kept as text.
+fn template_parts(text: &str) -> Vec<TemplatePart<'_>> {
+    let mut parts = Vec::new();
+    let mut remaining = text;
+    while let Some(dollar_ix) = remaining.find('$') {
+        parts.push(TemplatePart::Text(&remaining[..dollar_ix]));
+        remaining = &remaining[dollar_ix..];
+        let dollar_count = remaining.bytes().take_while(|byte| *byte == b'$').count();
+        let name_len = remaining[dollar_count..]
+            .bytes()
+            .take_while(|byte| byte.is_ascii_alphanumeric() || *byte == b'_')
+            .count();
+        let name = &remaining[dollar_count..dollar_count + name_len];
+        let is_metavariable_name = name
+            .starts_with(|character: char| character.is_ascii_uppercase() || character == '_')
+            && !name.bytes().any(|byte| byte.is_ascii_lowercase());
+        if is_metavariable_name && (dollar_count == 1 || dollar_count == 3) {
+            parts.push(TemplatePart::Metavariable {
+                name,
+                is_sequence: dollar_count == 3,
+            });
+        } else {
+            parts.push(TemplatePart::Text(&remaining[..dollar_count + name_len]));
+        }
+        remaining = &remaining[dollar_count + name_len..];
+    }
+    parts.push(TemplatePart::Text(remaining));
+    parts
+}
+
+/// Replaces every metavariable with an identifier, so that the pattern can be parsed as code.
+fn substitute_metavariables(query: &str) -> (String, HashMap<String, Metavariable>) {
+    let mut text = String::with_capacity(query.len());
+    let mut metavariables = HashMap::default();
+    for part in template_parts(query) {
+        match part {
+            TemplatePart::Text(plain_text) => text.push_str(plain_text),
+            TemplatePart::Metavariable { name, is_sequence } => {
+                let prefix = if is_sequence {
+                    SEQUENCE_METAVARIABLE_PREFIX
+                } else {
+                    METAVARIABLE_PREFIX
+                };
+                let placeholder = format!("{prefix}{name}");
+                text.push_str(&placeholder);
+                metavariables.insert(
+                    placeholder,
+                    Metavariable {
+                        name: name.to_string(),
+                        is_sequence,
+                    },
+                );
+            }
+        }
+    }
+    (text, metavariables)
+}
+
+/// Fills the metavariables in a replacement template, leaving unknown ones as they are written.
+fn render_template(template: &str, bindings: &Bindings) -> String {
+    let mut text = String::with_capacity(template.len());
+    for part in template_parts(template) {
+        match part {
+            TemplatePart::Text(plain_text) => text.push_str(plain_text),
+            TemplatePart::Metavariable { name, is_sequence } => match bindings.get(name) {
+                Some(bound_text) => text.push_str(bound_text),
+                None => {
+                    text.push_str(if is_sequence { "$$$" } else { "$" });
+                    text.push_str(name);
+                }
+            },
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use language::rust_lang;
+
+    #[test]
+    fn test_substitute_metavariables() {
+        let (text, metavariables) = substitute_metavariables("foo($A, $$$REST, $_, $lower, $$B)");
+        assert_eq!(
+            text,
+            "foo(zed_metavariable_A, zed_metavariables_REST, zed_metavariable__, $lower, $$B)"
+        );
+        assert_eq!(metavariables.len(), 3);
+        assert_eq!(
+            metavariables.get("zed_metavariables_REST"),
+            Some(&Metavariable {
+                name: "REST".to_string(),
+                is_sequence: true,
+            })
+        );
+        assert!(StructuralPattern::new("$A").is_err());
+        assert!(StructuralPattern::new("  ").is_err());
+    }
+
+    #[test]
+    fn test_structural_replacement() {
+        let language = rust_lang();
+        let pattern = StructuralPattern::new("foo($A, $B)").unwrap();
+        assert!(pattern.may_match("fn main() { foo(1, 2); }"));
+        assert!(!pattern.may_match("fn main() { bar(1, 2); }"));
+        assert_eq!(
+            pattern
+                .replacement_for("foo(x + 1, /* second */ y)", "bar($B, $A)", &language)
+                .as_deref(),
+            Some("bar(y, x + 1)")
+        );
+        assert_eq!(
+            pattern.replacement_for("foo(x)", "bar($B, $A)", &language),
+            None
+        );
+
+        let pattern = StructuralPattern::new("$A.push($$$ITEMS)").unwrap();
+        assert_eq!(
+            pattern
+                .replacement_for("items.push(1, 2)", "$A.extend([$$$ITEMS])", &language)
+                .as_deref(),
+            Some("items.extend([1, 2])")
+        );
+        assert_eq!(
+            pattern
+                .replacement_for("items.push()", "$A.extend([$$$ITEMS]) $UNKNOWN", &language)
+                .as_deref(),
+            Some("items.extend([]) $UNKNOWN")
+        );
+
+        let pattern = StructuralPattern::new("$A == $A").unwrap();
+        assert_eq!(
+            pattern
+                .replacement_for("a.len() == a.len()", "true", &language)
+                .as_deref(),
+            Some("true")
+        );
+        assert_eq!(
+            pattern.replacement_for("a.len() == b.len()", "true", &language),
+            None
+        );
+    }
+}
@@ -185,6 +185,7 @@ impl HeadlessProject {
                 buffer_store.clone(),
                 worktree_store.clone(),
                 prettier_store.clone(),
+                git_store.clone(),
                 toolchain_store
                     .read(cx)
                     .as_local_store()
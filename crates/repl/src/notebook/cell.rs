@@ -4,11 +4,13 @@ use std::sync::Arc;
 use editor::{Editor, EditorMode, MultiBuffer};
 use futures::future::Shared;
 use gpui::{
-    App, Entity, Hsla, RetainAllImageCache, Task, TextStyleRefinement, image_cache, prelude::*,
+    App, Entity, EventEmitter, Hsla, RetainAllImageCache, Task, TextStyleRefinement, image_cache,
+    prelude::*,
 };
 use language::{Buffer, Language, LanguageRegistry};
 use markdown_preview::{markdown_parser::parse_markdown, markdown_renderer::render_markdown_block};
 use nbformat::v4::{CellId, CellMetadata, CellType};
+use runtimelib::{ExecutionState, JupyterMessage, JupyterMessageContent, MimeBundle, Stdio};
 use settings::Settings as _;
 use theme::ThemeSettings;
 use ui::{IconButtonShape, prelude::*};
@@ -85,6 +87,43 @@ pub enum Cell {
     Raw(Entity<RawCell>),
 }
 
+/// Splits cell source into the line-per-entry form used by `.ipynb` files,
+/// where every line but the last keeps its trailing newline.
+pub(crate) fn source_lines(source: &str) -> Vec<String> {
+    source.split_inclusive('\n').map(str::to_string).collect()
+}
+
+fn convert_output(output: &nbformat::v4::Output, window: &mut Window, cx: &mut App) -> Output {
+    match output {
+        nbformat::v4::Output::Stream { text, .. } => Output::Stream {
+            content: cx.new(|cx| TerminalOutput::from(&text.0, window, cx)),
+        },
+        nbformat::v4::Output::DisplayData(display_data) => Output::new(
+            &display_data.data,
+            display_data
+                .transient
+                .as_ref()
+                .and_then(|transient| transient.display_id.clone()),
+            window,
+            cx,
+        ),
+        nbformat::v4::Output::ExecuteResult(execute_result) => Output::new(
+            &execute_result.data,
+            execute_result
+                .transient
+                .as_ref()
+                .and_then(|transient| transient.display_id.clone()),
+            window,
+            cx,
+        ),
+        nbformat::v4::Output::Error(error) => Output::ErrorOutput(ErrorView {
+            ename: error.ename.clone(),
+            evalue: error.evalue.clone(),
+            traceback: cx.new(|cx| TerminalOutput::from(&error.traceback.join("\n"), window, cx)),
+        }),
+    }
+}
+
 fn convert_outputs(
     outputs: &Vec<nbformat::v4::Output>,
     window: &mut Window,
@@ -92,27 +131,53 @@ fn convert_outputs(
 ) -> Vec<Output> {
     outputs
         .iter()
-        .map(|output| match output {
-            nbformat::v4::Output::Stream { text, .. } => Output::Stream {
-                content: cx.new(|cx| TerminalOutput::from(&text.0, window, cx)),
-            },
-            nbformat::v4::Output::DisplayData(display_data) => {
-                Output::new(&display_data.data, None, window, cx)
-            }
-            nbformat::v4::Output::ExecuteResult(execute_result) => {
-                Output::new(&execute_result.data, None, window, cx)
-            }
-            nbformat::v4::Output::Error(error) => Output::ErrorOutput(ErrorView {
-                ename: error.ename.clone(),
-                evalue: error.evalue.clone(),
-                traceback: cx
-                    .new(|cx| TerminalOutput::from(&error.traceback.join("\n"), window, cx)),
-            }),
-        })
+        .map(|output| convert_output(output, window, cx))
         .collect()
 }
 
 impl Cell {
+    pub fn id(&self, cx: &App) -> CellId {
+        match self {
+            Cell::Code(cell) => cell.read(cx).id().clone(),
+            Cell::Markdown(cell) => cell.read(cx).id().clone(),
+            Cell::Raw(cell) => cell.read(cx).id().clone(),
+        }
+    }
+
+    /// Converts the cell back into its `.ipynb` representation, keeping the
+    /// cell ID and metadata it was loaded with.
+    pub fn to_nbformat(&self, cx: &App) -> nbformat::v4::Cell {
+        match self {
+            Cell::Code(cell) => {
+                let cell = cell.read(cx);
+                nbformat::v4::Cell::Code {
+                    id: cell.id.clone(),
+                    metadata: cell.metadata.clone(),
+                    execution_count: cell.execution_count,
+                    source: source_lines(&cell.editor.read(cx).text(cx)),
+                    outputs: cell.raw_outputs.clone(),
+                }
+            }
+            Cell::Markdown(cell) => {
+                let cell = cell.read(cx);
+                nbformat::v4::Cell::Markdown {
+                    id: cell.id.clone(),
+                    metadata: cell.metadata.clone(),
+                    source: source_lines(&cell.source),
+                    attachments: cell.attachments.clone(),
+                }
+            }
+            Cell::Raw(cell) => {
+                let cell = cell.read(cx);
+                nbformat::v4::Cell::Raw {
+                    id: cell.id.clone(),
+                    metadata: cell.metadata.clone(),
+                    source: source_lines(&cell.source),
+                }
+            }
+        }
+    }
+
     pub fn load(
         cell: &nbformat::v4::Cell,
         languages: &Arc<LanguageRegistry>,
@@ -125,7 +190,7 @@ impl Cell {
                 id,
                 metadata,
                 source,
-                ..
+                attachments,
             } => {
                 let source = source.join("");
 
@@ -155,6 +220,7 @@ impl Cell {
                         id: id.clone(),
                         metadata: metadata.clone(),
                         source: source.clone(),
+                        attachments: attachments.clone(),
                         parsed_markdown: None,
                         selected: false,
                         cell_position: None,
@@ -172,7 +238,7 @@ impl Cell {
             } => Cell::Code(cx.new(|cx| {
                 let text = source.join("");
 
-                let buffer = cx.new(|cx| Buffer::local(text.clone(), cx));
+                let buffer = cx.new(|cx| Buffer::local(text, cx));
                 let multi_buffer = cx.new(|cx| MultiBuffer::singleton(buffer.clone(), cx));
 
                 let editor_view = cx.new(|cx| {
@@ -197,7 +263,6 @@ impl Cell {
                         ..Default::default()
                     };
 
-                    editor.set_text(text, window, cx);
                     editor.set_show_gutter(false, cx);
                     editor.set_text_style_refinement(refinement);
 
@@ -221,6 +286,10 @@ impl Cell {
                     source: source.join(""),
                     editor: editor_view,
                     outputs: convert_outputs(outputs, window, cx),
+                    raw_outputs: outputs.clone(),
+                    clear_on_next_output: false,
+                    executing: false,
+                    kernel_error: None,
                     selected: false,
                     language_task,
                     cell_position: None,
@@ -337,6 +406,7 @@ pub struct MarkdownCell {
     metadata: CellMetadata,
     image_cache: Entity<RetainAllImageCache>,
     source: String,
+    attachments: Option<serde_json::Value>,
     parsed_markdown: Option<markdown_preview::markdown_elements::ParsedMarkdown>,
     markdown_parsing_task: Task<()>,
     selected: bool,
@@ -435,21 +505,189 @@ pub struct CodeCell {
     source: String,
     editor: Entity<editor::Editor>,
     outputs: Vec<Output>,
+    /// The outputs as they are written to the `.ipynb` file, kept in step with `outputs`.
+    raw_outputs: Vec<nbformat::v4::Output>,
+    clear_on_next_output: bool,
+    executing: bool,
+    kernel_error: Option<SharedString>,
     selected: bool,
     cell_position: Option<CellPosition>,
     language_task: Task<()>,
 }
 
+pub enum CodeCellEvent {
+    Run,
+    OutputsChanged,
+}
+
+impl EventEmitter<CodeCellEvent> for CodeCell {}
+
 impl CodeCell {
+    pub fn editor(&self) -> &Entity<Editor> {
+        &self.editor
+    }
+
     pub fn is_dirty(&self, cx: &App) -> bool {
         self.editor.read(cx).buffer().read(cx).is_dirty(cx)
     }
+
     pub fn has_outputs(&self) -> bool {
         !self.outputs.is_empty()
     }
 
+    pub fn is_executing(&self) -> bool {
+        self.executing
+    }
+
     pub fn clear_outputs(&mut self) {
         self.outputs.clear();
+        self.raw_outputs.clear();
+        self.clear_on_next_output = false;
+    }
+
+    /// Prepares the cell for a new execution, dropping the outputs of the previous run.
+    pub fn start_execution(&mut self, cx: &mut Context<Self>) {
+        self.clear_outputs();
+        self.executing = true;
+        self.kernel_error = None;
+        cx.emit(CodeCellEvent::OutputsChanged);
+        cx.notify();
+    }
+
+    pub fn kernel_errored(&mut self, error_message: &str, cx: &mut Context<Self>) {
+        self.executing = false;
+        self.kernel_error = Some(error_message.to_string().into());
+        cx.notify();
+    }
+
+    /// Applies a kernel message sent in reply to one of this cell's executions.
+    pub fn handle_message(
+        &mut self,
+        message: &JupyterMessage,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let output = match &message.content {
+            JupyterMessageContent::ExecuteInput(input) => {
+                self.execution_count = Some(input.execution_count.0 as i32);
+                cx.emit(CodeCellEvent::OutputsChanged);
+                cx.notify();
+                return;
+            }
+            JupyterMessageContent::ExecuteReply(reply) => {
+                self.execution_count = Some(reply.execution_count.0 as i32);
+                self.executing = false;
+                cx.emit(CodeCellEvent::OutputsChanged);
+                cx.notify();
+                return;
+            }
+            JupyterMessageContent::Status(status) => {
+                self.executing = matches!(status.execution_state, ExecutionState::Busy);
+                cx.notify();
+                return;
+            }
+            JupyterMessageContent::ClearOutput(options) => {
+                if options.wait {
+                    self.clear_on_next_output = true;
+                } else {
+                    self.clear_outputs();
+                    cx.emit(CodeCellEvent::OutputsChanged);
+                    cx.notify();
+                }
+                return;
+            }
+            JupyterMessageContent::StreamContent(stream) => {
+                let name = match stream.name {
+                    Stdio::Stdout => "stdout",
+                    Stdio::Stderr => "stderr",
+                };
+                nbformat::v4::Output::Stream {
+                    name: name.to_string(),
+                    text: nbformat::v4::MultilineString(stream.text.clone()),
+                }
+            }
+            JupyterMessageContent::DisplayData(display_data) => {
+                nbformat::v4::Output::DisplayData(display_data.clone())
+            }
+            JupyterMessageContent::ExecuteResult(execute_result) => {
+                nbformat::v4::Output::ExecuteResult(execute_result.clone())
+            }
+            JupyterMessageContent::ErrorOutput(error) => nbformat::v4::Output::Error(error.clone()),
+            _ => return,
+        };
+
+        if self.clear_on_next_output {
+            self.clear_outputs();
+        }
+
+        if !self.append_to_stream(&output, cx) {
+            self.outputs.push(convert_output(&output, window, cx));
+            self.raw_outputs.push(output);
+        }
+
+        cx.emit(CodeCellEvent::OutputsChanged);
+        cx.notify();
+    }
+
+    /// Merges consecutive writes to the same stream into a single output, the
+    /// way Jupyter stores them.
+    fn append_to_stream(&mut self, output: &nbformat::v4::Output, cx: &mut Context<Self>) -> bool {
+        let nbformat::v4::Output::Stream { name, text } = output else {
+            return false;
+        };
+        let (
+            Some(nbformat::v4::Output::Stream {
+                name: last_name,
+                text: last_text,
+            }),
+            Some(Output::Stream { content }),
+        ) = (self.raw_outputs.last_mut(), self.outputs.last())
+        else {
+            return false;
+        };
+        if last_name != name {
+            return false;
+        }
+
+        last_text.0.push_str(&text.0);
+        content.update(cx, |content, cx| {
+            content.append_text(&text.0, cx);
+            cx.notify();
+        });
+        true
+    }
+
+    pub fn update_display_data(
+        &mut self,
+        data: &MimeBundle,
+        display_id: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let mut any = false;
+
+        for (output, raw_output) in self.outputs.iter_mut().zip(self.raw_outputs.iter_mut()) {
+            if output.display_id().as_deref() != Some(display_id) {
+                continue;
+            }
+
+            *output = Output::new(data, Some(display_id.to_owned()), window, cx);
+            match raw_output {
+                nbformat::v4::Output::DisplayData(display_data) => {
+                    display_data.data = data.clone();
+                }
+                nbformat::v4::Output::ExecuteResult(execute_result) => {
+                    execute_result.data = data.clone();
+                }
+                _ => {}
+            }
+            any = true;
+        }
+
+        if any {
+            cx.emit(CodeCellEvent::OutputsChanged);
+            cx.notify();
+        }
     }
 
     fn output_control(&self) -> Option<CellControlType> {
@@ -526,8 +764,8 @@ impl RenderableCell for CodeCell {
             CellControl::new("rerun-cell", CellControlType::RerunCell)
         } else {
             CellControl::new("run-cell", CellControlType::RunCell)
-                .on_click(cx.listener(move |this, _, window, cx| this.run(window, cx)))
-        };
+        }
+        .on_click(cx.listener(move |this, _, window, cx| this.run(window, cx)));
 
         Some(cell_control)
     }
@@ -552,8 +790,8 @@ impl RenderableCell for CodeCell {
 }
 
 impl RunnableCell for CodeCell {
-    fn run(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Running code cell: {}", self.id);
+    fn run(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(CodeCellEvent::Run);
     }
 
     fn execution_count(&self) -> Option<i32> {
@@ -621,45 +859,64 @@ impl Render for CodeCell {
                                 .border_1()
                                 // .border_color(cx.theme().colors().border)
                                 // .bg(cx.theme().colors().editor_background)
-                                .child(div().w_full().children(self.outputs.iter().map(
-                                    |output| {
-                                        let content = match output {
-                                            Output::Plain { content, .. } => {
-                                                Some(content.clone().into_any_element())
-                                            }
-                                            Output::Markdown { content, .. } => {
-                                                Some(content.clone().into_any_element())
-                                            }
-                                            Output::Stream { content, .. } => {
-                                                Some(content.clone().into_any_element())
-                                            }
-                                            Output::Image { content, .. } => {
-                                                Some(content.clone().into_any_element())
-                                            }
-                                            Output::Message(message) => Some(
-                                                div().child(message.clone()).into_any_element(),
-                                            ),
-                                            Output::Table { content, .. } => {
-                                                Some(content.clone().into_any_element())
-                                            }
-                                            Output::ErrorOutput(error_view) => {
-                                                error_view.render(window, cx)
-                                            }
-                                            Output::ClearOutputWaitMarker => None,
-                                        };
-
-                                        div()
-                                            // .w_full()
-                                            // .mt_3()
-                                            // .p_3()
-                                            // .rounded_sm()
-                                            // .bg(cx.theme().colors().editor_background)
-                                            // .border(px(1.))
-                                            // .border_color(cx.theme().colors().border)
-                                            // .shadow_xs()
-                                            .children(content)
-                                    },
-                                ))),
+                                .child(
+                                    div()
+                                        .w_full()
+                                        .children(self.outputs.iter().map(|output| {
+                                            let content = match output {
+                                                Output::Plain { content, .. } => {
+                                                    Some(content.clone().into_any_element())
+                                                }
+                                                Output::Markdown { content, .. } => {
+                                                    Some(content.clone().into_any_element())
+                                                }
+                                                Output::Stream { content, .. } => {
+                                                    Some(content.clone().into_any_element())
+                                                }
+                                                Output::Image { content, .. } => {
+                                                    Some(content.clone().into_any_element())
+                                                }
+                                                Output::Message(message) => Some(
+                                                    div().child(message.clone()).into_any_element(),
+                                                ),
+                                                Output::Table { content, .. } => {
+                                                    Some(content.clone().into_any_element())
+                                                }
+                                                Output::Html { content, .. } => {
+                                                    Some(content.clone().into_any_element())
+                                                }
+                                                Output::Svg { content, .. } => {
+                                                    Some(content.clone().into_any_element())
+                                                }
+                                                Output::Latex { content, .. } => {
+                                                    Some(content.clone().into_any_element())
+                                                }
+                                                Output::Json { content, .. } => {
+                                                    Some(content.clone().into_any_element())
+                                                }
+                                                Output::ErrorOutput(error_view) => {
+                                                    error_view.render(window, cx)
+                                                }
+                                                Output::ClearOutputWaitMarker => None,
+                                            };
+
+                                            div()
+                                                // .w_full()
+                                                // .mt_3()
+                                                // .p_3()
+                                                // .rounded_sm()
+                                                // .bg(cx.theme().colors().editor_background)
+                                                // .border(px(1.))
+                                                // .border_color(cx.theme().colors().border)
+                                                // .shadow_xs()
+                                                .children(content)
+                                        }))
+                                        .children(
+                                            self.kernel_error
+                                                .clone()
+                                                .map(|error| Label::new(error).color(Color::Error)),
+                                        ),
+                                ),
                         ),
                     ),
             )
@@ -1,26 +1,36 @@
 #![allow(unused, dead_code)]
+use std::any::Any;
 use std::future::Future;
+use std::ops::Range;
 use std::{path::PathBuf, sync::Arc};
 
 use anyhow::{Context as _, Result};
 use client::proto::ViewId;
 use collections::HashMap;
-use feature_flags::{FeatureFlagAppExt as _, NotebookFeatureFlag};
+use editor::Anchor;
 use futures::FutureExt;
 use futures::future::Shared;
 use gpui::{
     AnyElement, App, Entity, EventEmitter, FocusHandle, Focusable, ListScrollEvent, ListState,
-    Point, Task, actions, list, prelude::*,
+    Point, Subscription, Task, actions, list, prelude::*,
 };
 use language::{Language, LanguageRegistry};
+use project::search::SearchQuery;
 use project::{Project, ProjectEntryId, ProjectPath};
+use serde::Serialize as _;
 use ui::{Tooltip, prelude::*};
+use util::ResultExt as _;
+use uuid::Uuid;
 use workspace::item::{ItemEvent, SaveOptions, TabContentParams};
-use workspace::searchable::SearchableItemHandle;
-use workspace::{Item, ItemHandle, Pane, ProjectItem, ToolbarItemLocation};
+use workspace::searchable::{
+    Direction, SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle,
+};
+use workspace::{Item, ItemHandle, ItemNavHistory, Pane, ProjectItem, ToolbarItemLocation};
 use workspace::{ToolbarItemEvent, ToolbarItemView};
 
-use super::{Cell, CellPosition, RenderableCell};
+use super::{Cell, CellPosition, CodeCell, CodeCellEvent, RenderableCell};
+use crate::repl_store::ReplStore;
+use crate::{Kernel, Session};
 
 use nbformat::v4::CellId;
 use nbformat::v4::Metadata as NotebookMetadata;
@@ -42,6 +52,8 @@ actions!(
         AddMarkdownBlock,
         /// Adds a new code cell.
         AddCodeBlock,
+        /// Deletes the current cell.
+        DeleteCell,
     ]
 );
 
@@ -54,21 +66,21 @@ pub(crate) const CODE_BLOCK_INSET: f32 = MEDIUM_SPACING_SIZE;
 pub(crate) const CONTROL_SIZE: f32 = 20.0;
 
 pub fn init(cx: &mut App) {
-    if cx.has_flag::<NotebookFeatureFlag>() || std::env::var("LOCAL_NOTEBOOK_DEV").is_ok() {
-        workspace::register_project_item::<NotebookEditor>(cx);
-    }
+    workspace::register_project_item::<NotebookEditor>(cx);
+}
 
-    cx.observe_flag::<NotebookFeatureFlag, _>({
-        move |is_enabled, cx| {
-            if is_enabled {
-                workspace::register_project_item::<NotebookEditor>(cx);
-            } else {
-                // todo: there is no way to unregister a project item, so if the feature flag
-                // gets turned off they need to restart Zed.
-            }
-        }
-    })
-    .detach();
+/// A structural change to the notebook's list of cells. Applying an operation
+/// yields its inverse, which is what the undo and redo stacks hold.
+enum CellOperation {
+    Insert { index: usize, cell: Cell },
+    Remove { index: usize },
+    Move { from: usize, to: usize },
+}
+
+#[derive(Clone)]
+pub struct NotebookSearchMatch {
+    cell_id: CellId,
+    range: Range<Anchor>,
 }
 
 pub struct NotebookEditor {
@@ -77,13 +89,27 @@ pub struct NotebookEditor {
 
     focus_handle: FocusHandle,
     notebook_item: Entity<NotebookItem>,
+    notebook_language: Shared<Task<Option<Arc<Language>>>>,
+    language: Option<Arc<Language>>,
 
     remote_id: Option<ViewId>,
     cell_list: ListState,
+    nav_history: Option<ItemNavHistory>,
 
     selected_cell_index: usize,
     cell_order: Vec<CellId>,
     cell_map: HashMap<CellId, Cell>,
+    cell_subscriptions: HashMap<CellId, Vec<Subscription>>,
+
+    undo_stack: Vec<CellOperation>,
+    redo_stack: Vec<CellOperation>,
+    /// Whether cells were added, removed, moved or re-run since the notebook was last saved.
+    has_unsaved_changes: bool,
+    has_conflict: bool,
+
+    session: Option<Entity<Session>>,
+    reload_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl NotebookEditor {
@@ -96,49 +122,143 @@ impl NotebookEditor {
         let focus_handle = cx.focus_handle();
 
         let languages = project.read(cx).languages().clone();
-        let language_name = notebook_item.read(cx).language_name();
 
         let notebook_language = notebook_item.read(cx).notebook_language();
         let notebook_language = cx
             .spawn_in(window, async move |_, _| notebook_language.await)
             .shared();
 
-        let mut cell_order = vec![]; // Vec<CellId>
-        let mut cell_map = HashMap::default(); // HashMap<CellId, Cell>
-
-        for (index, cell) in notebook_item
-            .read(cx)
-            .notebook
-            .clone()
-            .cells
-            .iter()
-            .enumerate()
-        {
-            let cell_id = cell.id();
-            cell_order.push(cell_id.clone());
-            cell_map.insert(
-                cell_id.clone(),
-                Cell::load(cell, &languages, notebook_language.clone(), window, cx),
-            );
-        }
-
-        let notebook_handle = cx.entity().downgrade();
-        let cell_count = cell_order.len();
+        let language_task = notebook_language.clone();
+        cx.spawn(async move |this, cx| {
+            let language = language_task.await;
+            this.update(cx, |this, _| this.language = language).ok();
+        })
+        .detach();
 
-        let this = cx.entity();
-        let cell_list = ListState::new(cell_count, gpui::ListAlignment::Top, px(1000.));
+        let subscriptions = vec![cx.subscribe_in(&project, window, Self::on_project_event)];
 
-        Self {
+        let mut this = Self {
             project,
-            languages: languages.clone(),
+            languages,
             focus_handle,
             notebook_item,
+            notebook_language,
+            language: None,
             remote_id: None,
-            cell_list,
+            cell_list: ListState::new(0, gpui::ListAlignment::Top, px(1000.)),
+            nav_history: None,
             selected_cell_index: 0,
-            cell_order: cell_order.clone(),
-            cell_map: cell_map.clone(),
+            cell_order: Vec::new(),
+            cell_map: HashMap::default(),
+            cell_subscriptions: HashMap::default(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            has_unsaved_changes: false,
+            has_conflict: false,
+            session: None,
+            reload_task: None,
+            _subscriptions: subscriptions,
+        };
+        this.load_cells(window, cx);
+        this
+    }
+
+    fn load_cells(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.cell_order.clear();
+        self.cell_map.clear();
+        self.cell_subscriptions.clear();
+
+        let cells = self.notebook_item.read(cx).notebook.cells.clone();
+        for cell in &cells {
+            let cell = Cell::load(
+                cell,
+                &self.languages,
+                self.notebook_language.clone(),
+                window,
+                cx,
+            );
+            let cell_id = cell.id(cx);
+            self.subscribe_to_cell(&cell_id, &cell, window, cx);
+            self.cell_order.push(cell_id.clone());
+            self.cell_map.insert(cell_id, cell);
+        }
+
+        self.cell_list.reset(self.cell_order.len());
+        self.selected_cell_index = self
+            .selected_cell_index
+            .min(self.cell_order.len().saturating_sub(1));
+    }
+
+    fn subscribe_to_cell(
+        &mut self,
+        cell_id: &CellId,
+        cell: &Cell,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Cell::Code(code_cell) = cell else {
+            return;
+        };
+
+        let mut subscriptions = vec![cx.subscribe_in(code_cell, window, {
+            let cell_id = cell_id.clone();
+            move |this, code_cell, event, window, cx| match event {
+                CodeCellEvent::Run => this.run_cell(code_cell.clone(), window, cx),
+                CodeCellEvent::OutputsChanged => {
+                    this.has_unsaved_changes = true;
+                    cx.emit(ItemEvent::UpdateTab);
+                    cx.notify();
+                }
+            }
+        })];
+
+        let editor = code_cell.read(cx).editor().clone();
+        subscriptions.push(cx.subscribe(&editor, |_, _, event: &SearchEvent, cx| {
+            cx.emit(event.clone())
+        }));
+        if let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() {
+            subscriptions.push(
+                cx.subscribe(&buffer, |_, _, event: &language::BufferEvent, cx| {
+                    if let language::BufferEvent::DirtyChanged | language::BufferEvent::Saved =
+                        event
+                    {
+                        cx.emit(ItemEvent::UpdateTab);
+                    }
+                }),
+            );
         }
+
+        self.cell_subscriptions
+            .insert(cell_id.clone(), subscriptions);
+    }
+
+    fn code_cells(&self) -> Vec<(CellId, Entity<CodeCell>)> {
+        self.cell_order
+            .iter()
+            .filter_map(|cell_id| match self.cell_map.get(cell_id) {
+                Some(Cell::Code(code_cell)) => Some((cell_id.clone(), code_cell.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn selected_code_cell(&self) -> Option<Entity<CodeCell>> {
+        let cell_id = self.cell_order.get(self.selected_cell_index)?;
+        match self.cell_map.get(cell_id)? {
+            Cell::Code(code_cell) => Some(code_cell.clone()),
+            _ => None,
+        }
+    }
+
+    fn to_notebook(&self, cx: &App) -> nbformat::v4::Notebook {
+        let mut notebook = self.notebook_item.read(cx).notebook.clone();
+        notebook.cells = self
+            .cell_order
+            .iter()
+            .filter_map(|cell_id| self.cell_map.get(cell_id))
+            .map(|cell| cell.to_nbformat(cx))
+            .collect();
+        notebook
     }
 
     fn has_outputs(&self, window: &mut Window, cx: &mut Context<Self>) -> bool {
@@ -159,10 +279,170 @@ impl NotebookEditor {
                 });
             }
         }
+        self.has_unsaved_changes = true;
+        cx.emit(ItemEvent::UpdateTab);
+        cx.notify();
     }
 
     fn run_cells(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Cells would all run here, if that was implemented!");
+        for (_, code_cell) in self.code_cells() {
+            self.run_cell(code_cell, window, cx);
+        }
+    }
+
+    fn run_cell(
+        &mut self,
+        code_cell: Entity<CodeCell>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let code = code_cell.read(cx).editor().read(cx).text(cx);
+        if code.trim().is_empty() {
+            return;
+        }
+
+        match self.ensure_session(window, cx) {
+            Ok(session) => session.update(cx, |session, cx| {
+                session.execute_cell(code, &code_cell, cx);
+            }),
+            Err(error) => code_cell.update(cx, |cell, cx| {
+                cell.kernel_errored(&error.to_string(), cx);
+            }),
+        }
+    }
+
+    pub fn session(&self) -> Option<&Entity<Session>> {
+        self.session.as_ref()
+    }
+
+    /// Returns the kernel session for this notebook, starting one for the
+    /// notebook's kernelspec if none is running.
+    fn ensure_session(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<Entity<Session>> {
+        if let Some(session) = &self.session
+            && !matches!(
+                session.read(cx).kernel,
+                Kernel::Shutdown | Kernel::ErroredLaunch(_)
+            )
+        {
+            return Ok(session.clone());
+        }
+
+        let store = ReplStore::global(cx);
+        anyhow::ensure!(store.read(cx).is_enabled(), "The REPL is disabled");
+
+        let notebook_item = self.notebook_item.read(cx);
+        let worktree_id = notebook_item.project_path.worktree_id;
+        let kernel_name = notebook_item
+            .notebook
+            .metadata
+            .kernelspec
+            .as_ref()
+            .map(|kernelspec| kernelspec.name.clone());
+        let working_directory = notebook_item.path.parent().map(|path| path.to_path_buf());
+
+        let kernel_specification = kernel_name
+            .and_then(|kernel_name| {
+                store
+                    .read(cx)
+                    .kernel_specifications_for_worktree(worktree_id)
+                    .find(|specification| specification.name().as_ref() == kernel_name)
+                    .cloned()
+            })
+            .or_else(|| {
+                store
+                    .read(cx)
+                    .active_kernelspec(worktree_id, self.language.clone(), cx)
+            })
+            .context("No kernel found for this notebook")?;
+
+        let fs = store.read(cx).fs().clone();
+        let session = cx.new(|cx| {
+            Session::for_notebook(working_directory, fs, kernel_specification, window, cx)
+        });
+        self.session = Some(session.clone());
+        Ok(session)
+    }
+
+    fn perform_cell_operation(
+        &mut self,
+        operation: CellOperation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(inverse) = self.apply_cell_operation(operation, window, cx) {
+            self.undo_stack.push(inverse);
+            self.redo_stack.clear();
+        }
+    }
+
+    fn undo(&mut self, _: &editor::actions::Undo, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(operation) = self.undo_stack.pop()
+            && let Some(inverse) = self.apply_cell_operation(operation, window, cx)
+        {
+            self.redo_stack.push(inverse);
+        }
+    }
+
+    fn redo(&mut self, _: &editor::actions::Redo, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(operation) = self.redo_stack.pop()
+            && let Some(inverse) = self.apply_cell_operation(operation, window, cx)
+        {
+            self.undo_stack.push(inverse);
+        }
+    }
+
+    fn apply_cell_operation(
+        &mut self,
+        operation: CellOperation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<CellOperation> {
+        let cell_count = self.cell_order.len();
+        let inverse = match operation {
+            CellOperation::Insert { index, cell } => {
+                let index = index.min(cell_count);
+                let cell_id = cell.id(cx);
+                self.subscribe_to_cell(&cell_id, &cell, window, cx);
+                self.cell_order.insert(index, cell_id.clone());
+                self.cell_map.insert(cell_id, cell);
+                self.cell_list.splice(index..index, 1);
+                self.selected_cell_index = index;
+                CellOperation::Remove { index }
+            }
+            CellOperation::Remove { index } => {
+                if index >= cell_count {
+                    return None;
+                }
+                let cell_id = self.cell_order.remove(index);
+                self.cell_subscriptions.remove(&cell_id);
+                let cell = self.cell_map.remove(&cell_id)?;
+                self.cell_list.splice(index..index + 1, 0);
+                self.selected_cell_index = index.min(self.cell_order.len().saturating_sub(1));
+                CellOperation::Insert { index, cell }
+            }
+            CellOperation::Move { from, to } => {
+                if from >= cell_count || to >= cell_count || from == to {
+                    return None;
+                }
+                let cell_id = self.cell_order.remove(from);
+                self.cell_order.insert(to, cell_id);
+                self.cell_list
+                    .splice(from.min(to)..from.max(to) + 1, from.abs_diff(to) + 1);
+                self.selected_cell_index = to;
+                CellOperation::Move { from: to, to: from }
+            }
+        };
+
+        self.has_unsaved_changes = true;
+        self.jump_to_cell(self.selected_cell_index, window, cx);
+        cx.emit(ItemEvent::UpdateTab);
+        cx.emit(SearchEvent::MatchesInvalidated);
+        cx.notify();
+        Some(inverse)
     }
 
     fn open_notebook(&mut self, _: &OpenNotebook, _window: &mut Window, _cx: &mut Context<Self>) {
@@ -170,19 +450,197 @@ impl NotebookEditor {
     }
 
     fn move_cell_up(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Move cell up triggered");
+        let index = self.selected_cell_index;
+        if index > 0 {
+            self.perform_cell_operation(
+                CellOperation::Move {
+                    from: index,
+                    to: index - 1,
+                },
+                window,
+                cx,
+            );
+        }
     }
 
     fn move_cell_down(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Move cell down triggered");
+        let index = self.selected_cell_index;
+        self.perform_cell_operation(
+            CellOperation::Move {
+                from: index,
+                to: index + 1,
+            },
+            window,
+            cx,
+        );
     }
 
     fn add_markdown_block(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Add markdown block triggered");
+        self.add_cell(nbformat::v4::CellType::Markdown, window, cx)
+            .log_err();
     }
 
     fn add_code_block(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Add code block triggered");
+        self.add_cell(nbformat::v4::CellType::Code, window, cx)
+            .log_err();
+    }
+
+    fn add_cell(
+        &mut self,
+        cell_type: nbformat::v4::CellType,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let id = CellId::from(Uuid::new_v4());
+        let metadata = serde_json::from_value(serde_json::json!({}))?;
+        let cell = match cell_type {
+            nbformat::v4::CellType::Code => nbformat::v4::Cell::Code {
+                id,
+                metadata,
+                execution_count: None,
+                source: Vec::new(),
+                outputs: Vec::new(),
+            },
+            nbformat::v4::CellType::Markdown => nbformat::v4::Cell::Markdown {
+                id,
+                metadata,
+                source: Vec::new(),
+                attachments: None,
+            },
+            nbformat::v4::CellType::Raw => nbformat::v4::Cell::Raw {
+                id,
+                metadata,
+                source: Vec::new(),
+            },
+        };
+        let cell = Cell::load(
+            &cell,
+            &self.languages,
+            self.notebook_language.clone(),
+            window,
+            cx,
+        );
+
+        let index = if self.cell_order.is_empty() {
+            0
+        } else {
+            self.selected_cell_index + 1
+        };
+        self.perform_cell_operation(CellOperation::Insert { index, cell }, window, cx);
+        Ok(())
+    }
+
+    fn delete_cell(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let index = self.selected_cell_index;
+        self.perform_cell_operation(CellOperation::Remove { index }, window, cx);
+    }
+
+    fn write_notebook(
+        &mut self,
+        abs_path: PathBuf,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let notebook = self.to_notebook(cx);
+        let fs = project.read(cx).fs().clone();
+        let saved_buffers = self
+            .code_cells()
+            .into_iter()
+            .filter_map(|(_, code_cell)| {
+                let buffer = code_cell
+                    .read(cx)
+                    .editor()
+                    .read(cx)
+                    .buffer()
+                    .read(cx)
+                    .as_singleton()?;
+                let version = buffer.read(cx).version();
+                Some((buffer, version))
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let contents = serialize_notebook(&notebook)?;
+            fs.atomic_write(abs_path.clone(), contents.clone()).await?;
+
+            this.update(cx, |this, cx| {
+                for (buffer, version) in saved_buffers {
+                    buffer.update(cx, |buffer, cx| buffer.did_save(version, None, cx));
+                }
+                this.notebook_item.update(cx, |item, _| {
+                    item.path = abs_path;
+                    item.notebook = notebook;
+                    item.contents = contents;
+                });
+                this.has_unsaved_changes = false;
+                this.has_conflict = false;
+                cx.emit(ItemEvent::UpdateTab);
+                cx.notify();
+            })
+        })
+    }
+
+    fn load_contents(
+        &mut self,
+        contents: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let notebook = parse_notebook(&contents)?;
+        self.notebook_item.update(cx, |item, _| {
+            item.notebook = notebook;
+            item.contents = contents;
+        });
+        self.load_cells(window, cx);
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.has_unsaved_changes = false;
+        self.has_conflict = false;
+        cx.emit(ItemEvent::UpdateTab);
+        cx.emit(SearchEvent::MatchesInvalidated);
+        cx.notify();
+        Ok(())
+    }
+
+    fn on_project_event(
+        &mut self,
+        _: &Entity<Project>,
+        event: &project::Event,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let project::Event::WorktreeUpdatedEntries(worktree_id, changes) = event else {
+            return;
+        };
+        let project_path = self.notebook_item.read(cx).project_path.clone();
+        if *worktree_id != project_path.worktree_id
+            || !changes
+                .iter()
+                .any(|(path, _, _)| *path == project_path.path)
+        {
+            return;
+        }
+
+        let fs = self.project.read(cx).fs().clone();
+        let abs_path = self.notebook_item.read(cx).path.clone();
+        self.reload_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let Some(contents) = fs.load(&abs_path).await.log_err() else {
+                return;
+            };
+            this.update_in(cx, |this, window, cx| {
+                if this.notebook_item.read(cx).contents == contents {
+                    return;
+                }
+                if this.is_dirty(cx) {
+                    this.has_conflict = true;
+                    cx.emit(ItemEvent::UpdateTab);
+                } else {
+                    this.load_contents(contents, window, cx).log_err();
+                }
+            })
+            .ok();
+        }));
     }
 
     fn cell_count(&self) -> usize {
@@ -200,13 +658,18 @@ impl NotebookEditor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        // let previous_index = self.selected_cell_index;
+        let previous_cell = self.cell_order.get(self.selected_cell_index).cloned();
         self.selected_cell_index = index;
         let current_index = self.selected_cell_index;
 
         // in the future we may have some `on_cell_change` event that we want to fire here
 
         if jump_to_index {
+            if let Some(nav_history) = self.nav_history.as_mut()
+                && previous_cell.is_some()
+            {
+                nav_history.push(previous_cell, cx);
+            }
             self.jump_to_cell(current_index, window, cx);
         }
     }
@@ -495,6 +958,9 @@ impl Render for NotebookEditor {
             .on_action(
                 cx.listener(|this, &AddCodeBlock, window, cx| this.add_code_block(window, cx)),
             )
+            .on_action(cx.listener(|this, &DeleteCell, window, cx| this.delete_cell(window, cx)))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::redo))
             .on_action(cx.listener(Self::select_next))
             .on_action(cx.listener(Self::select_previous))
             .on_action(cx.listener(Self::select_first))
@@ -543,8 +1009,9 @@ pub struct NotebookItem {
     languages: Arc<LanguageRegistry>,
     // Raw notebook data
     notebook: nbformat::v4::Notebook,
-    // Store our version of the notebook in memory (cell_order, cell_map)
-    id: ProjectEntryId,
+    /// The file contents the notebook was last loaded from or saved as.
+    contents: String,
+    id: Option<ProjectEntryId>,
 }
 
 impl project::ProjectItem for NotebookItem {
@@ -564,24 +1031,8 @@ impl project::ProjectItem for NotebookItem {
                     .read_with(cx, |project, cx| project.absolute_path(&path, cx))?
                     .with_context(|| format!("finding the absolute path of {path:?}"))?;
 
-                // todo: watch for changes to the file
-                let file_content = fs.load(abs_path.as_path()).await?;
-                let notebook = nbformat::parse_notebook(&file_content);
-
-                let notebook = match notebook {
-                    Ok(nbformat::Notebook::V4(notebook)) => notebook,
-                    // 4.1 - 4.4 are converted to 4.5
-                    Ok(nbformat::Notebook::Legacy(legacy_notebook)) => {
-                        // TODO: Decide if we want to mutate the notebook by including Cell IDs
-                        // and any other conversions
-
-                        nbformat::upgrade_legacy_notebook(legacy_notebook)?
-                    }
-                    // Bad notebooks and notebooks v4.0 and below are not supported
-                    Err(e) => {
-                        anyhow::bail!("Failed to parse notebook: {:?}", e);
-                    }
-                };
+                let contents = fs.load(abs_path.as_path()).await?;
+                let notebook = parse_notebook(&contents)?;
 
                 let id = project
                     .update(cx, |project, cx| {
@@ -594,7 +1045,8 @@ impl project::ProjectItem for NotebookItem {
                     project_path: path,
                     languages,
                     notebook,
-                    id,
+                    contents,
+                    id: Some(id),
                 })
             }))
         } else {
@@ -603,7 +1055,7 @@ impl project::ProjectItem for NotebookItem {
     }
 
     fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
-        Some(self.id)
+        self.id
     }
 
     fn project_path(&self, _: &App) -> Option<ProjectPath> {
@@ -644,7 +1096,35 @@ impl NotebookItem {
     }
 }
 
-impl EventEmitter<()> for NotebookEditor {}
+fn parse_notebook(contents: &str) -> Result<nbformat::v4::Notebook> {
+    match nbformat::parse_notebook(contents) {
+        Ok(nbformat::Notebook::V4(notebook)) => Ok(notebook),
+        // 4.1 - 4.4 are converted to 4.5
+        Ok(nbformat::Notebook::Legacy(legacy_notebook)) => {
+            Ok(nbformat::upgrade_legacy_notebook(legacy_notebook)?)
+        }
+        // Bad notebooks and notebooks v4.0 and below are not supported
+        Err(e) => {
+            anyhow::bail!("Failed to parse notebook: {:?}", e);
+        }
+    }
+}
+
+/// Serializes a notebook the way Jupyter writes `.ipynb` files: one-space
+/// indentation and a trailing newline, so saving an unchanged notebook
+/// doesn't produce a diff.
+fn serialize_notebook(notebook: &nbformat::v4::Notebook) -> Result<String> {
+    let mut bytes = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b" ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut bytes, formatter);
+    notebook.serialize(&mut serializer)?;
+    let mut contents = String::from_utf8(bytes)?;
+    contents.push('\n');
+    Ok(contents)
+}
+
+impl EventEmitter<ItemEvent> for NotebookEditor {}
+impl EventEmitter<SearchEvent> for NotebookEditor {}
 
 // pub struct NotebookControls {
 //     pane_focused: bool,
@@ -692,7 +1172,11 @@ impl EventEmitter<()> for NotebookEditor {}
 // }
 
 impl Item for NotebookEditor {
-    type Event = ();
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
 
     fn can_split(&self) -> bool {
         true
@@ -755,64 +1239,317 @@ impl Item for NotebookEditor {
         None
     }
 
-    // TODO
-    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
-        None
+    fn as_searchable(
+        &self,
+        handle: &Entity<Self>,
+        _: &App,
+    ) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(handle.clone()))
     }
 
     fn set_nav_history(
         &mut self,
-        _: workspace::ItemNavHistory,
+        nav_history: ItemNavHistory,
         _window: &mut Window,
         _: &mut Context<Self>,
     ) {
-        // TODO
+        self.nav_history = Some(nav_history);
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Ok(cell_id) = data.downcast::<CellId>() else {
+            return false;
+        };
+        let Some(index) = self.cell_order.iter().position(|id| *id == *cell_id) else {
+            return false;
+        };
+        self.selected_cell_index = index;
+        self.jump_to_cell(index, window, cx);
+        cx.notify();
+        true
     }
 
-    // TODO
     fn can_save(&self, _cx: &App) -> bool {
-        false
+        true
     }
-    // TODO
+
+    fn can_save_as(&self, _cx: &App) -> bool {
+        true
+    }
+
     fn save(
         &mut self,
         _options: SaveOptions,
-        _project: Entity<Project>,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        unimplemented!("save() must be implemented if can_save() returns true")
+        let abs_path = self.notebook_item.read(cx).path.clone();
+        self.write_notebook(abs_path, project, window, cx)
     }
 
-    // TODO
     fn save_as(
         &mut self,
-        _project: Entity<Project>,
-        _path: ProjectPath,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
+        project: Entity<Project>,
+        path: ProjectPath,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        unimplemented!("save_as() must be implemented if can_save() returns true")
+        let Some(abs_path) = project.read(cx).absolute_path(&path, cx) else {
+            return Task::ready(Err(anyhow::anyhow!("Failed to resolve path {path:?}")));
+        };
+
+        let write = self.write_notebook(abs_path, project.clone(), window, cx);
+        cx.spawn(async move |this, cx| {
+            write.await?;
+            this.update(cx, |this, cx| {
+                let id = project
+                    .read(cx)
+                    .entry_for_path(&path, cx)
+                    .map(|entry| entry.id);
+                this.notebook_item.update(cx, |item, _| {
+                    item.project_path = path;
+                    item.id = id;
+                });
+                cx.emit(ItemEvent::UpdateTab);
+            })
+        })
     }
-    // TODO
+
     fn reload(
         &mut self,
-        _project: Entity<Project>,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        unimplemented!("reload() must be implemented if can_save() returns true")
+        let fs = project.read(cx).fs().clone();
+        let abs_path = self.notebook_item.read(cx).path.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let contents = fs.load(&abs_path).await?;
+            this.update_in(cx, |this, window, cx| {
+                this.load_contents(contents, window, cx)
+            })?
+        })
+    }
+
+    fn has_conflict(&self, _: &App) -> bool {
+        self.has_conflict
     }
 
     fn is_dirty(&self, cx: &App) -> bool {
-        self.cell_map.values().any(|cell| {
-            if let Cell::Code(code_cell) = cell {
-                code_cell.read(cx).is_dirty(cx)
-            } else {
-                false
+        self.has_unsaved_changes
+            || self.cell_map.values().any(|cell| {
+                if let Cell::Code(code_cell) = cell {
+                    code_cell.read(cx).is_dirty(cx)
+                } else {
+                    false
+                }
+            })
+    }
+}
+
+impl SearchableItem for NotebookEditor {
+    type Match = NotebookSearchMatch;
+
+    fn supported_options(&self) -> SearchOptions {
+        SearchOptions {
+            case: true,
+            word: true,
+            regex: true,
+            replacement: true,
+            selection: false,
+            find_in_results: false,
+        }
+    }
+
+    fn clear_matches(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        for (_, code_cell) in self.code_cells() {
+            let editor = code_cell.read(cx).editor().clone();
+            editor.update(cx, |editor, cx| editor.clear_matches(window, cx));
+        }
+    }
+
+    fn update_matches(
+        &mut self,
+        matches: &[NotebookSearchMatch],
+        active_match_index: Option<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for (cell_id, code_cell) in self.code_cells() {
+            let cell_matches = matches_in_cell(&cell_id, matches);
+            let active_match_index = active_match_index
+                .filter(|index| {
+                    matches
+                        .get(*index)
+                        .is_some_and(|search_match| search_match.cell_id == cell_id)
+                })
+                .map(|index| index_in_cell(&cell_id, matches, index));
+
+            let editor = code_cell.read(cx).editor().clone();
+            editor.update(cx, |editor, cx| {
+                if cell_matches.is_empty() {
+                    editor.clear_matches(window, cx);
+                } else {
+                    editor.update_matches(&cell_matches, active_match_index, window, cx);
+                }
+            });
+        }
+    }
+
+    fn query_suggestion(&mut self, window: &mut Window, cx: &mut Context<Self>) -> String {
+        let Some(code_cell) = self.selected_code_cell() else {
+            return String::new();
+        };
+        let editor = code_cell.read(cx).editor().clone();
+        editor.update(cx, |editor, cx| editor.query_suggestion(window, cx))
+    }
+
+    fn activate_match(
+        &mut self,
+        index: usize,
+        matches: &[NotebookSearchMatch],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(search_match) = matches.get(index) else {
+            return;
+        };
+        let Some(cell_index) = self
+            .cell_order
+            .iter()
+            .position(|cell_id| *cell_id == search_match.cell_id)
+        else {
+            return;
+        };
+        let Some(Cell::Code(code_cell)) = self.cell_map.get(&search_match.cell_id).cloned() else {
+            return;
+        };
+
+        self.set_selected_index(cell_index, true, window, cx);
+        let cell_matches = matches_in_cell(&search_match.cell_id, matches);
+        let index = index_in_cell(&search_match.cell_id, matches, index);
+        let editor = code_cell.read(cx).editor().clone();
+        editor.update(cx, |editor, cx| {
+            editor.activate_match(index, &cell_matches, window, cx)
+        });
+        cx.notify();
+    }
+
+    fn select_matches(
+        &mut self,
+        matches: &[NotebookSearchMatch],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for (cell_id, code_cell) in self.code_cells() {
+            let cell_matches = matches_in_cell(&cell_id, matches);
+            if !cell_matches.is_empty() {
+                let editor = code_cell.read(cx).editor().clone();
+                editor.update(cx, |editor, cx| {
+                    editor.select_matches(&cell_matches, window, cx)
+                });
+            }
+        }
+    }
+
+    fn replace(
+        &mut self,
+        search_match: &NotebookSearchMatch,
+        query: &SearchQuery,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(Cell::Code(code_cell)) = self.cell_map.get(&search_match.cell_id) {
+            let editor = code_cell.read(cx).editor().clone();
+            editor.update(cx, |editor, cx| {
+                editor.replace(&search_match.range, query, window, cx)
+            });
+        }
+    }
+
+    fn find_matches(
+        &mut self,
+        query: Arc<SearchQuery>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Vec<NotebookSearchMatch>> {
+        let searches = self
+            .code_cells()
+            .into_iter()
+            .map(|(cell_id, code_cell)| {
+                let editor = code_cell.read(cx).editor().clone();
+                let search = editor.update(cx, |editor, cx| {
+                    editor.find_matches(query.clone(), window, cx)
+                });
+                (cell_id, search)
+            })
+            .collect::<Vec<_>>();
+
+        cx.background_spawn(async move {
+            let mut matches = Vec::new();
+            for (cell_id, search) in searches {
+                matches.extend(search.await.into_iter().map(|range| NotebookSearchMatch {
+                    cell_id: cell_id.clone(),
+                    range,
+                }));
             }
+            matches
         })
     }
+
+    fn active_match_index(
+        &mut self,
+        direction: Direction,
+        matches: &[NotebookSearchMatch],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<usize> {
+        let selected_cell_id = self.cell_order.get(self.selected_cell_index)?.clone();
+
+        if let Some(code_cell) = self.selected_code_cell() {
+            let cell_matches = matches_in_cell(&selected_cell_id, matches);
+            let editor = code_cell.read(cx).editor().clone();
+            let index_in_cell = editor.update(cx, |editor, cx| {
+                editor.active_match_index(direction, &cell_matches, window, cx)
+            });
+            if let Some(index_in_cell) = index_in_cell {
+                return matches
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, search_match)| search_match.cell_id == selected_cell_id)
+                    .nth(index_in_cell)
+                    .map(|(index, _)| index);
+            }
+        }
+
+        matches.iter().position(|search_match| {
+            self.cell_order
+                .iter()
+                .position(|cell_id| *cell_id == search_match.cell_id)
+                .is_some_and(|cell_index| cell_index >= self.selected_cell_index)
+        })
+    }
+}
+
+fn matches_in_cell(cell_id: &CellId, matches: &[NotebookSearchMatch]) -> Vec<Range<Anchor>> {
+    matches
+        .iter()
+        .filter(|search_match| search_match.cell_id == *cell_id)
+        .map(|search_match| search_match.range.clone())
+        .collect()
+}
+
+fn index_in_cell(cell_id: &CellId, matches: &[NotebookSearchMatch], index: usize) -> usize {
+    matches[..index]
+        .iter()
+        .filter(|search_match| search_match.cell_id == *cell_id)
+        .count()
 }
 
 // TODO: Implement this to allow us to persist to the database, etc:
@@ -834,3 +1571,92 @@ impl ProjectItem for NotebookEditor {
         Self::new(project, item, window, cx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notebook::source_lines;
+
+    #[test]
+    fn test_notebook_round_trip() {
+        let contents = r##"{
+ "cells": [
+  {
+   "cell_type": "markdown",
+   "id": "intro",
+   "metadata": {
+    "tags": [
+     "header"
+    ]
+   },
+   "source": [
+    "# Title\n",
+    "Some text"
+   ]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": 3,
+   "id": "compute",
+   "metadata": {
+    "collapsed": false
+   },
+   "outputs": [
+    {
+     "name": "stdout",
+     "output_type": "stream",
+     "text": [
+      "2\n"
+     ]
+    }
+   ],
+   "source": [
+    "print(1 + 1)"
+   ]
+  }
+ ],
+ "metadata": {
+  "kernelspec": {
+   "display_name": "Python 3",
+   "language": "python",
+   "name": "python3"
+  }
+ },
+ "nbformat": 4,
+ "nbformat_minor": 5
+}
+"##;
+
+        let notebook = parse_notebook(contents).unwrap();
+        let serialized = serialize_notebook(&notebook).unwrap();
+        let reparsed = parse_notebook(&serialized).unwrap();
+
+        assert!(serialized.ends_with("}\n"));
+        assert_eq!(
+            serde_json::to_value(&notebook).unwrap(),
+            serde_json::to_value(&reparsed).unwrap()
+        );
+
+        let cell_ids = reparsed
+            .cells
+            .iter()
+            .map(|cell| cell.id().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(cell_ids, ["intro", "compute"]);
+        assert_eq!(
+            reparsed
+                .metadata
+                .kernelspec
+                .map(|kernelspec| kernelspec.name),
+            Some("python3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_source_lines() {
+        assert_eq!(source_lines(""), Vec::<String>::new());
+        assert_eq!(source_lines("a = 1"), ["a = 1"]);
+        assert_eq!(source_lines("a = 1\nb = 2\n"), ["a = 1\n", "b = 2\n"]);
+        assert_eq!(source_lines("a = 1\n\nb = 2"), ["a = 1\n", "\n", "b = 2"]);
+    }
+}
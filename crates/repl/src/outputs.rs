@@ -14,7 +14,10 @@
 //! The module supports several output types, including:
 //! - Plain text
 //! - Markdown
-//! - Images (PNG and JPEG)
+//! - HTML (a safe subset, rendered as Markdown)
+//! - Images (PNG, JPEG and SVG)
+//! - LaTeX math
+//! - JSON trees
 //! - Tables
 //! - Error messages
 //!
@@ -42,18 +45,30 @@ use ui::{
     Window, div, h_flex, prelude::*, v_flex,
 };
 
+mod html;
+use html::HtmlView;
+
 mod image;
 use image::ImageView;
 
+mod json;
+use json::JsonView;
+
+mod latex;
+use latex::LatexView;
+
 mod markdown;
 use markdown::MarkdownView;
 
-mod table;
+pub(crate) mod table;
 use table::TableView;
 
 pub mod plain;
 use plain::TerminalOutput;
 
+mod svg;
+use svg::SvgView;
+
 pub(crate) mod user_error;
 use user_error::ErrorView;
 use workspace::Workspace;
@@ -61,16 +76,30 @@ use workspace::Workspace;
 /// When deciding what to render from a collection of mediatypes, we need to rank them in order of importance
 fn rank_mime_type(mimetype: &MimeType) -> usize {
     match mimetype {
-        MimeType::DataTable(_) => 6,
-        MimeType::Png(_) => 4,
-        MimeType::Jpeg(_) => 3,
-        MimeType::Markdown(_) => 2,
+        MimeType::DataTable(_) => 9,
+        MimeType::Svg(_) => 8,
+        MimeType::Png(_) => 7,
+        MimeType::Jpeg(_) => 6,
+        // HTML is only rendered as far as it converts to Markdown, so images are preferred.
+        MimeType::Html(_) => 5,
+        MimeType::Markdown(_) => 4,
+        MimeType::Latex(_) => 3,
+        MimeType::Json(_) => 2,
         MimeType::Plain(_) => 1,
         // All other media types are not supported in Zed at this time
         _ => 0,
     }
 }
 
+/// Ranks media types for outputs whose HTML converts to no Markdown at all, such as interactive
+/// widgets made only of scripts and styles.
+fn rank_mime_type_without_html(mimetype: &MimeType) -> usize {
+    match mimetype {
+        MimeType::Html(_) => 0,
+        mimetype => rank_mime_type(mimetype),
+    }
+}
+
 pub(crate) trait OutputContent {
     fn clipboard_content(&self, window: &Window, cx: &App) -> Option<ClipboardItem>;
     fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
@@ -124,6 +153,22 @@ pub enum Output {
         content: Entity<MarkdownView>,
         display_id: Option<String>,
     },
+    Html {
+        content: Entity<HtmlView>,
+        display_id: Option<String>,
+    },
+    Svg {
+        content: Entity<SvgView>,
+        display_id: Option<String>,
+    },
+    Latex {
+        content: Entity<LatexView>,
+        display_id: Option<String>,
+    },
+    Json {
+        content: Entity<JsonView>,
+        display_id: Option<String>,
+    },
     ClearOutputWaitMarker,
 }
 
@@ -213,6 +258,10 @@ impl Output {
             Self::Image { content, .. } => Some(content.clone().into_any_element()),
             Self::Message(message) => Some(div().child(message.clone()).into_any_element()),
             Self::Table { content, .. } => Some(content.clone().into_any_element()),
+            Self::Html { content, .. } => Some(content.clone().into_any_element()),
+            Self::Svg { content, .. } => Some(content.clone().into_any_element()),
+            Self::Latex { content, .. } => Some(content.clone().into_any_element()),
+            Self::Json { content, .. } => Some(content.clone().into_any_element()),
             Self::ErrorOutput(error_view) => error_view.render(window, cx),
             Self::ClearOutputWaitMarker => None,
         };
@@ -323,6 +372,18 @@ impl Output {
                 Self::Table { content, .. } => {
                     Self::render_output_controls(content.clone(), workspace, window, cx)
                 }
+                Self::Html { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace, window, cx)
+                }
+                Self::Svg { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace, window, cx)
+                }
+                Self::Latex { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace, window, cx)
+                }
+                Self::Json { content, .. } => {
+                    Self::render_output_controls(content.clone(), workspace, window, cx)
+                }
                 Self::ClearOutputWaitMarker => None,
             })
     }
@@ -336,6 +397,10 @@ impl Output {
             Output::Message(_) => None,
             Output::Table { display_id, .. } => display_id.clone(),
             Output::Markdown { display_id, .. } => display_id.clone(),
+            Output::Html { display_id, .. } => display_id.clone(),
+            Output::Svg { display_id, .. } => display_id.clone(),
+            Output::Latex { display_id, .. } => display_id.clone(),
+            Output::Json { display_id, .. } => display_id.clone(),
             Output::ClearOutputWaitMarker => None,
         }
     }
@@ -346,7 +411,21 @@ impl Output {
         window: &mut Window,
         cx: &mut App,
     ) -> Self {
-        match data.richest(rank_mime_type) {
+        let mut mime_type = data.richest(rank_mime_type);
+        let mut html_markdown = None;
+        if let Some(MimeType::Html(html)) = mime_type {
+            match html::html_to_markdown(html) {
+                Ok(markdown) if markdown.trim().is_empty() => {
+                    if let Some(fallback) = data.richest(rank_mime_type_without_html) {
+                        mime_type = Some(fallback);
+                    } else {
+                        html_markdown = Some(Ok(markdown));
+                    }
+                }
+                markdown => html_markdown = Some(markdown),
+            }
+        }
+        match mime_type {
             Some(MimeType::Plain(text)) => Output::Plain {
                 content: cx.new(|cx| TerminalOutput::from(text, window, cx)),
                 display_id,
@@ -369,6 +448,27 @@ impl Output {
                 content: cx.new(|cx| TableView::new(data, window, cx)),
                 display_id,
             },
+            Some(MimeType::Html(html)) => {
+                match html_markdown.unwrap_or_else(|| html::html_to_markdown(html)) {
+                    Ok(markdown) => Output::Html {
+                        content: cx.new(|cx| HtmlView::new(html.clone(), markdown, cx)),
+                        display_id,
+                    },
+                    Err(error) => Output::Message(format!("Failed to render HTML: {}", error)),
+                }
+            }
+            Some(MimeType::Svg(svg)) => Output::Svg {
+                content: cx.new(|cx| SvgView::from(svg.clone(), window, cx)),
+                display_id,
+            },
+            Some(MimeType::Latex(text)) => Output::Latex {
+                content: cx.new(|_| LatexView::from(text)),
+                display_id,
+            },
+            Some(MimeType::Json(value)) => Output::Json {
+                content: cx.new(|_| JsonView::from(value.clone())),
+                display_id,
+            },
             // Any other media types are not supported
             _ => Output::Message("Unsupported media type".to_string()),
         }
@@ -0,0 +1,71 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use anyhow::Result;
+use gpui::{App, ClipboardItem, Entity, Window};
+use html_to_markdown::{TagHandler, convert_html_to_markdown, markdown};
+use language::Buffer;
+use ui::{IntoElement, div, prelude::*};
+
+use crate::outputs::OutputContent;
+use crate::outputs::markdown::MarkdownView;
+
+/// HtmlView renders `text/html` outputs (e.g. pandas DataFrames) by
+/// converting a safe subset of HTML to Markdown. Scripts, styles and any
+/// tags outside of that subset are dropped rather than executed.
+pub struct HtmlView {
+    raw_html: String,
+    markdown: Entity<MarkdownView>,
+}
+
+impl HtmlView {
+    pub fn new(raw_html: String, markdown: String, cx: &mut Context<Self>) -> Self {
+        Self {
+            raw_html,
+            markdown: cx.new(|cx| MarkdownView::from(markdown, cx)),
+        }
+    }
+}
+
+pub(crate) fn html_to_markdown(html: &str) -> Result<String> {
+    let mut handlers: Vec<TagHandler> = vec![
+        Rc::new(RefCell::new(markdown::WebpageChromeRemover)),
+        Rc::new(RefCell::new(markdown::ParagraphHandler)),
+        Rc::new(RefCell::new(markdown::HeadingHandler)),
+        Rc::new(RefCell::new(markdown::ListHandler)),
+        Rc::new(RefCell::new(markdown::TableHandler::new())),
+        Rc::new(RefCell::new(markdown::StyledTextHandler)),
+        Rc::new(RefCell::new(markdown::CodeHandler)),
+    ];
+    convert_html_to_markdown(html.as_bytes(), &mut handlers)
+}
+
+impl Render for HtmlView {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div().child(self.markdown.clone())
+    }
+}
+
+impl OutputContent for HtmlView {
+    fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
+        Some(ClipboardItem::new_string(self.raw_html.clone()))
+    }
+
+    fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn has_buffer_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn buffer_content(&mut self, _: &mut Window, cx: &mut App) -> Option<Entity<Buffer>> {
+        let buffer = cx.new(|cx| {
+            let mut buffer = Buffer::local(self.raw_html.clone(), cx)
+                .with_language(language::PLAIN_TEXT.clone(), cx);
+            buffer.set_capability(language::Capability::ReadOnly, cx);
+            buffer
+        });
+        Some(buffer)
+    }
+}
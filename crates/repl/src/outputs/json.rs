@@ -0,0 +1,195 @@
+use collections::HashSet;
+use gpui::{App, ClipboardItem, Entity, Window};
+use language::Buffer;
+use serde_json::Value;
+use ui::{Disclosure, IntoElement, Label, div, h_flex, prelude::*, v_flex};
+
+use crate::outputs::OutputContent;
+
+/// JsonView renders `application/json` outputs as a tree whose objects and
+/// arrays can be expanded and collapsed.
+pub struct JsonView {
+    value: Value,
+    expanded_paths: HashSet<String>,
+}
+
+struct JsonRow {
+    depth: usize,
+    path: String,
+    key: Option<String>,
+    summary: String,
+    is_container: bool,
+    is_expanded: bool,
+}
+
+impl JsonView {
+    pub fn from(value: Value) -> Self {
+        let mut expanded_paths = HashSet::default();
+        expanded_paths.insert(String::new());
+        Self {
+            value,
+            expanded_paths,
+        }
+    }
+
+    fn toggle(&mut self, path: &str, cx: &mut Context<Self>) {
+        if !self.expanded_paths.remove(path) {
+            self.expanded_paths.insert(path.to_string());
+        }
+        cx.notify();
+    }
+
+    fn visible_rows(&self) -> Vec<JsonRow> {
+        let mut rows = Vec::new();
+        self.push_rows(&self.value, None, String::new(), 0, &mut rows);
+        rows
+    }
+
+    fn push_rows(
+        &self,
+        value: &Value,
+        key: Option<String>,
+        path: String,
+        depth: usize,
+        rows: &mut Vec<JsonRow>,
+    ) {
+        let is_expanded = self.expanded_paths.contains(&path);
+        let children: Vec<(String, &Value)> = match value {
+            Value::Object(map) => map
+                .iter()
+                .map(|(key, value)| (key.clone(), value))
+                .collect(),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(index, value)| (index.to_string(), value))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        rows.push(JsonRow {
+            depth,
+            path: path.clone(),
+            key,
+            summary: summarize(value, is_expanded),
+            is_container: matches!(value, Value::Object(_) | Value::Array(_)),
+            is_expanded,
+        });
+
+        if is_expanded {
+            for (child_key, child) in children {
+                let child_path = format!("{path}/{child_key}");
+                self.push_rows(child, Some(child_key), child_path, depth + 1, rows);
+            }
+        }
+    }
+
+    fn pretty_text(&self) -> String {
+        serde_json::to_string_pretty(&self.value).unwrap_or_else(|_| self.value.to_string())
+    }
+}
+
+fn summarize(value: &Value, is_expanded: bool) -> String {
+    match value {
+        Value::Object(map) if is_expanded => format!("{} keys", map.len()),
+        Value::Object(map) => format!("{{…}} {} keys", map.len()),
+        Value::Array(items) if is_expanded => format!("{} items", items.len()),
+        Value::Array(items) => format!("[…] {} items", items.len()),
+        value => value.to_string(),
+    }
+}
+
+impl Render for JsonView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().py_1().children(
+            self.visible_rows()
+                .into_iter()
+                .enumerate()
+                .map(|(index, row)| {
+                    let toggle = row.is_container.then(|| {
+                        let path = row.path.clone();
+                        Disclosure::new(("json-node", index), row.is_expanded)
+                            .on_click(cx.listener(move |this, _, _, cx| this.toggle(&path, cx)))
+                    });
+
+                    h_flex()
+                        .pl(px(16. * row.depth as f32))
+                        .gap_1()
+                        .child(div().w_4().children(toggle))
+                        .children(row.key.map(|key| {
+                            Label::new(format!("{key}:"))
+                                .buffer_font(cx)
+                                .color(Color::Accent)
+                        }))
+                        .child(
+                            Label::new(row.summary)
+                                .buffer_font(cx)
+                                .color(if row.is_container {
+                                    Color::Muted
+                                } else {
+                                    Color::Default
+                                }),
+                        )
+                }),
+        )
+    }
+}
+
+impl OutputContent for JsonView {
+    fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
+        Some(ClipboardItem::new_string(self.pretty_text()))
+    }
+
+    fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn has_buffer_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+
+    fn buffer_content(&mut self, _: &mut Window, cx: &mut App) -> Option<Entity<Buffer>> {
+        let buffer = cx.new(|cx| {
+            let mut buffer = Buffer::local(self.pretty_text(), cx)
+                .with_language(language::PLAIN_TEXT.clone(), cx);
+            buffer.set_capability(language::Capability::ReadOnly, cx);
+            buffer
+        });
+        Some(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_rows_follow_expanded_paths() {
+        let mut view = JsonView::from(json!({"a": 1, "b": {"c": [true, null]}}));
+
+        let rows = view.visible_rows();
+        let summaries: Vec<_> = rows.iter().map(|row| row.summary.as_str()).collect();
+        assert_eq!(summaries, ["2 keys", "1", "{…} 1 keys"]);
+
+        view.expanded_paths.insert("/b".to_string());
+        view.expanded_paths.insert("/b/c".to_string());
+        let rows = view.visible_rows();
+        let paths: Vec<_> = rows
+            .iter()
+            .map(|row| (row.depth, row.path.as_str()))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                (0, ""),
+                (1, "/a"),
+                (1, "/b"),
+                (2, "/b/c"),
+                (3, "/b/c/0"),
+                (3, "/b/c/1")
+            ]
+        );
+        assert_eq!(rows[5].summary, "null");
+    }
+}
@@ -0,0 +1,343 @@
+use gpui::{App, ClipboardItem, Window};
+use settings::Settings as _;
+use theme::ThemeSettings;
+use ui::{IntoElement, div, prelude::*};
+
+use crate::outputs::OutputContent;
+
+/// LatexView renders `text/latex` outputs (e.g. from sympy) by typesetting
+/// the math with Unicode symbols, superscripts and subscripts.
+pub struct LatexView {
+    raw_text: String,
+    typeset: SharedString,
+}
+
+impl LatexView {
+    pub fn from(text: &str) -> Self {
+        Self {
+            raw_text: text.to_string(),
+            typeset: typeset_latex(text).into(),
+        }
+    }
+}
+
+impl Render for LatexView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let buffer_font = ThemeSettings::get_global(cx).buffer_font.clone();
+
+        div()
+            .py_1()
+            .font(buffer_font)
+            .italic()
+            .child(self.typeset.clone())
+    }
+}
+
+impl OutputContent for LatexView {
+    fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
+        Some(ClipboardItem::new_string(self.raw_text.clone()))
+    }
+
+    fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+}
+
+/// Converts a LaTeX math expression into a single line of Unicode text.
+///
+/// This covers what kernels commonly emit for symbolic math: greek letters,
+/// operators, fractions, roots, superscripts and subscripts. Commands that
+/// are not recognized are rendered by name so no information is lost.
+pub(crate) fn typeset_latex(source: &str) -> String {
+    let source = strip_math_delimiters(source.trim());
+    let mut output = String::new();
+    let mut chars = source.chars().peekable();
+    typeset_into(&mut chars, &mut output, None);
+    collapse_whitespace(&output)
+}
+
+fn strip_math_delimiters(source: &str) -> &str {
+    for (open, close) in [("$$", "$$"), ("\\[", "\\]"), ("\\(", "\\)"), ("$", "$")] {
+        if let Some(inner) = source
+            .strip_prefix(open)
+            .and_then(|rest| rest.strip_suffix(close))
+        {
+            return inner.trim();
+        }
+    }
+    source
+}
+
+fn typeset_into(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+    output: &mut String,
+    closing: Option<char>,
+) {
+    while let Some(character) = chars.next() {
+        match character {
+            _ if Some(character) == closing => return,
+            '{' => typeset_into(chars, output, Some('}')),
+            '\\' => typeset_command(chars, output),
+            '^' => {
+                let argument = read_argument(chars);
+                output.push_str(&script(&argument, superscript_char, "^"));
+            }
+            '_' => {
+                let argument = read_argument(chars);
+                output.push_str(&script(&argument, subscript_char, "_"));
+            }
+            '&' => output.push(' '),
+            '~' => output.push(' '),
+            character => output.push(character),
+        }
+    }
+}
+
+fn typeset_command(chars: &mut std::iter::Peekable<std::str::Chars>, output: &mut String) {
+    let mut name = String::new();
+    while let Some(&character) = chars.peek() {
+        if character.is_ascii_alphabetic() {
+            name.push(character);
+            chars.next();
+        } else {
+            break;
+        }
+    }
+
+    if name.is_empty() {
+        match chars.next() {
+            Some(',' | ';' | ':' | ' ' | '!') => output.push(' '),
+            Some('\\') => output.push(' '),
+            Some(character) => output.push(character),
+            None => {}
+        }
+        return;
+    }
+
+    match name.as_str() {
+        "frac" | "dfrac" | "tfrac" => {
+            let numerator = read_argument(chars);
+            let denominator = read_argument(chars);
+            output.push_str(&format!(
+                "{}/{}",
+                group_if_compound(&numerator),
+                group_if_compound(&denominator)
+            ));
+        }
+        "sqrt" => {
+            let argument = read_argument(chars);
+            output.push('√');
+            output.push_str(&group_if_compound(&argument));
+        }
+        "mathrm" | "text" | "textrm" | "operatorname" | "mathit" | "mathbf" | "mathsf"
+        | "mathtt" | "boldsymbol" | "mbox" => {
+            output.push_str(&read_argument(chars));
+        }
+        "displaystyle" | "textstyle" | "left" | "right" | "bigl" | "bigr" | "Bigl" | "Bigr"
+        | "big" | "Big" | "limits" | "nolimits" => {}
+        "begin" | "end" => {
+            read_argument(chars);
+        }
+        "quad" | "qquad" => output.push_str("  "),
+        name => match symbol(name) {
+            Some(symbol) => output.push_str(symbol),
+            None => output.push_str(name),
+        },
+    }
+}
+
+/// Reads a single argument for a command or script: either a braced group or
+/// the next token.
+fn read_argument(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    while chars.peek().is_some_and(|character| *character == ' ') {
+        chars.next();
+    }
+    let mut argument = String::new();
+    match chars.next() {
+        Some('{') => typeset_into(chars, &mut argument, Some('}')),
+        Some('\\') => typeset_command(chars, &mut argument),
+        Some(character) => argument.push(character),
+        None => {}
+    }
+    argument
+}
+
+fn script(argument: &str, map: fn(char) -> Option<char>, marker: &str) -> String {
+    let mapped: Option<String> = argument.chars().map(map).collect();
+    match mapped {
+        Some(mapped) if !argument.is_empty() => mapped,
+        _ => format!("{marker}{}", group_if_compound(argument)),
+    }
+}
+
+fn group_if_compound(text: &str) -> String {
+    let text = text.trim();
+    if text.chars().count() <= 1 || text.chars().all(|character| character.is_alphanumeric()) {
+        text.to_string()
+    } else {
+        format!("({text})")
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn superscript_char(character: char) -> Option<char> {
+    Some(match character {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        '+' => '⁺',
+        '-' | '−' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'n' => 'ⁿ',
+        'i' => 'ⁱ',
+        'T' => 'ᵀ',
+        _ => return None,
+    })
+}
+
+fn subscript_char(character: char) -> Option<char> {
+    Some(match character {
+        '0' => '₀',
+        '1' => '₁',
+        '2' => '₂',
+        '3' => '₃',
+        '4' => '₄',
+        '5' => '₅',
+        '6' => '₆',
+        '7' => '₇',
+        '8' => '₈',
+        '9' => '₉',
+        '+' => '₊',
+        '-' | '−' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'i' => 'ᵢ',
+        'j' => 'ⱼ',
+        'k' => 'ₖ',
+        'n' => 'ₙ',
+        'x' => 'ₓ',
+        _ => return None,
+    })
+}
+
+fn symbol(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" | "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" | "vartheta" => "θ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "pi" => "π",
+        "rho" => "ρ",
+        "sigma" => "σ",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" | "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        "cdot" => "·",
+        "times" => "×",
+        "div" => "÷",
+        "pm" => "±",
+        "mp" => "∓",
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "neq" | "ne" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "propto" => "∝",
+        "infty" => "∞",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "sum" => "∑",
+        "prod" => "∏",
+        "int" => "∫",
+        "iint" => "∬",
+        "oint" => "∮",
+        "in" => "∈",
+        "notin" => "∉",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "cup" => "∪",
+        "cap" => "∩",
+        "emptyset" => "∅",
+        "forall" => "∀",
+        "exists" => "∃",
+        "neg" | "lnot" => "¬",
+        "wedge" | "land" => "∧",
+        "vee" | "lor" => "∨",
+        "to" | "rightarrow" => "→",
+        "leftarrow" => "←",
+        "Rightarrow" => "⇒",
+        "Leftarrow" => "⇐",
+        "leftrightarrow" => "↔",
+        "Leftrightarrow" | "iff" => "⇔",
+        "mapsto" => "↦",
+        "ldots" | "dots" | "cdots" => "…",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "hbar" => "ℏ",
+        "ell" => "ℓ",
+        "circ" => "∘",
+        "degree" => "°",
+        "prime" => "′",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typeset_latex() {
+        assert_eq!(typeset_latex(r"$\displaystyle x^{2} + 1$"), "x² + 1");
+        assert_eq!(typeset_latex(r"$$\alpha_{1} \leq \beta$$"), "α₁ ≤ β");
+        assert_eq!(typeset_latex(r"\frac{x + 1}{2}"), "(x + 1)/2");
+        assert_eq!(typeset_latex(r"\sqrt{2} \cdot \pi"), "√2 · π");
+        assert_eq!(typeset_latex(r"e^{i \pi}"), "e^(i π)");
+        assert_eq!(typeset_latex(r"\left(x + y\right)^{n}"), "(x + y)ⁿ");
+        assert_eq!(typeset_latex(r"\mathrm{d}x"), "dx");
+        assert_eq!(typeset_latex(r"\sin x + \unknown y"), "sin x + unknown y");
+    }
+}
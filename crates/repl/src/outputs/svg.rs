@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use gpui::{App, ClipboardItem, Image, ImageFormat, RenderImage, Task, Window, img};
+use ui::{IntoElement, div, prelude::*};
+
+use crate::outputs::OutputContent;
+
+/// SvgView renders `image/svg+xml` outputs (e.g. plots) by rasterizing them
+/// in the background with the same renderer used for SVG previews.
+pub struct SvgView {
+    raw_svg: String,
+    image: Option<Result<Arc<RenderImage>, SharedString>>,
+    _render_task: Task<()>,
+}
+
+impl SvgView {
+    pub fn from(svg: String, window: &Window, cx: &mut Context<Self>) -> Self {
+        let scale_factor = window.scale_factor();
+        let renderer = cx.svg_renderer();
+        let bytes = svg.clone().into_bytes();
+        let rendered =
+            cx.background_spawn(
+                async move { renderer.render_single_frame(&bytes, scale_factor, true) },
+            );
+        let render_task = cx.spawn(async move |this, cx| {
+            let result = rendered.await;
+            this.update(cx, |this, cx| {
+                this.image = Some(result.map_err(|error| error.to_string().into()));
+                cx.notify();
+            })
+            .ok();
+        });
+
+        Self {
+            raw_svg: svg,
+            image: None,
+            _render_task: render_task,
+        }
+    }
+}
+
+impl Render for SvgView {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        match self.image.clone() {
+            Some(Ok(image)) => img(image).max_w_full().into_any_element(),
+            Some(Err(error)) => div()
+                .py_1()
+                .child(format!("Failed to render SVG: {error}"))
+                .into_any_element(),
+            None => div().into_any_element(),
+        }
+    }
+}
+
+impl OutputContent for SvgView {
+    fn clipboard_content(&self, _window: &Window, _cx: &App) -> Option<ClipboardItem> {
+        Some(ClipboardItem::new_image(&Image::from_bytes(
+            ImageFormat::Svg,
+            self.raw_svg.clone().into_bytes(),
+        )))
+    }
+
+    fn has_clipboard_content(&self, _window: &Window, _cx: &App) -> bool {
+        true
+    }
+}
@@ -59,6 +59,7 @@ pub fn assign_kernelspec(
                             store.remove_session(shutdown_event.entity_id());
                         });
                     }
+                    SessionEvent::ExecutionFinished => {}
                 }
             })
             .detach();
@@ -134,6 +135,7 @@ pub fn run(
                                 store.remove_session(shutdown_event.entity_id());
                             });
                         }
+                        SessionEvent::ExecutionFinished => {}
                     }
                 })
                 .detach();
@@ -1,5 +1,6 @@
 use crate::components::KernelListItem;
 use crate::kernels::RemoteRunningKernel;
+use crate::notebook::CodeCell;
 use crate::setup_editor_session_actions;
 use crate::{
     KernelStatus,
@@ -18,6 +19,7 @@ use editor::{
     scroll::Autoscroll,
 };
 use futures::FutureExt as _;
+use futures::channel::oneshot;
 use gpui::{
     Context, Entity, EventEmitter, Render, Subscription, Task, WeakEntity, Window, div, prelude::*,
 };
@@ -25,9 +27,9 @@ use language::Point;
 use project::Fs;
 use runtimelib::{
     ExecuteRequest, ExecutionState, InterruptRequest, JupyterMessage, JupyterMessageContent,
-    ShutdownRequest,
+    ShutdownRequest, Stdio,
 };
-use std::{env::temp_dir, ops::Range, sync::Arc, time::Duration};
+use std::{env::temp_dir, ops::Range, path::PathBuf, sync::Arc, time::Duration};
 use theme::ActiveTheme;
 use ui::{IconButtonShape, Tooltip, prelude::*};
 use util::ResultExt as _;
@@ -37,10 +39,20 @@ pub struct Session {
     editor: WeakEntity<Editor>,
     pub kernel: Kernel,
     blocks: HashMap<String, EditorBlock>,
+    /// Notebook cells awaiting replies, keyed by the id of their execute request.
+    cell_executions: HashMap<String, WeakEntity<CodeCell>>,
+    introspections: HashMap<String, PendingIntrospection>,
+    working_directory: Option<PathBuf>,
     pub kernel_specification: KernelSpecification,
     _buffer_subscription: Subscription,
 }
 
+struct PendingIntrospection {
+    stdout: String,
+    error: Option<String>,
+    done: oneshot::Sender<anyhow::Result<String>>,
+}
+
 struct EditorBlock {
     code_range: Range<Anchor>,
     invalidation_anchor: Anchor,
@@ -220,6 +232,9 @@ impl Session {
             editor,
             kernel: Kernel::StartingKernel(Task::ready(()).shared()),
             blocks: HashMap::default(),
+            cell_executions: HashMap::default(),
+            introspections: HashMap::default(),
+            working_directory: None,
             kernel_specification,
             _buffer_subscription: subscription,
         };
@@ -228,13 +243,41 @@ impl Session {
         session
     }
 
+    /// Creates a session that isn't attached to an editor, for running the
+    /// cells of a notebook via [`Session::execute_cell`].
+    pub fn for_notebook(
+        working_directory: Option<PathBuf>,
+        fs: Arc<dyn Fs>,
+        kernel_specification: KernelSpecification,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut session = Self {
+            fs,
+            editor: WeakEntity::new_invalid(),
+            kernel: Kernel::StartingKernel(Task::ready(()).shared()),
+            blocks: HashMap::default(),
+            cell_executions: HashMap::default(),
+            introspections: HashMap::default(),
+            working_directory,
+            kernel_specification,
+            _buffer_subscription: Subscription::new(|| {}),
+        };
+
+        session.start_kernel(window, cx);
+        session
+    }
+
     fn start_kernel(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let kernel_language = self.kernel_specification.language();
-        let entity_id = self.editor.entity_id();
+        let editor = self.editor.upgrade();
+        let entity_id = editor
+            .as_ref()
+            .map_or_else(|| cx.entity_id(), |editor| editor.entity_id());
         let working_directory = self
-            .editor
-            .upgrade()
-            .and_then(|editor| editor.read(cx).working_directory(cx))
+            .working_directory
+            .clone()
+            .or_else(|| editor.and_then(|editor| editor.read(cx).working_directory(cx)))
             .unwrap_or_else(temp_dir);
 
         telemetry::event!(
@@ -309,6 +352,15 @@ impl Session {
                 cx.notify();
             });
         });
+
+        for cell in self.cell_executions.values() {
+            cell.update(cx, |cell, cx| {
+                if cell.is_executing() {
+                    cell.kernel_errored(&error_message, cx);
+                }
+            })
+            .ok();
+        }
     }
 
     fn on_buffer_event(
@@ -361,6 +413,40 @@ impl Session {
             .ok();
 
         self.blocks.clear();
+        self.cell_executions.clear();
+        self.introspections.clear();
+    }
+
+    /// Runs `code` on the kernel without adding it to the execution history,
+    /// resolving with everything the code printed to stdout.
+    pub fn introspect(
+        &mut self,
+        code: String,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<String>> {
+        let Kernel::RunningKernel(_) = &self.kernel else {
+            return Task::ready(Err(anyhow::anyhow!("The kernel is not running")));
+        };
+
+        let message: JupyterMessage = ExecuteRequest {
+            code,
+            store_history: false,
+            ..ExecuteRequest::default()
+        }
+        .into();
+
+        let (done, result) = oneshot::channel();
+        self.introspections.insert(
+            message.header.msg_id.clone(),
+            PendingIntrospection {
+                stdout: String::new(),
+                error: None,
+                done,
+            },
+        );
+        self.send(message, cx).ok();
+
+        cx.background_spawn(async move { result.await? })
     }
 
     pub fn execute(
@@ -487,16 +573,64 @@ impl Session {
         }
     }
 
+    pub fn execute_cell(&mut self, code: String, cell: &Entity<CodeCell>, cx: &mut Context<Self>) {
+        let message: JupyterMessage = ExecuteRequest {
+            code,
+            ..ExecuteRequest::default()
+        }
+        .into();
+
+        self.cell_executions
+            .retain(|_, executing_cell| executing_cell != &cell.downgrade());
+        self.cell_executions
+            .insert(message.header.msg_id.clone(), cell.downgrade());
+        cell.update(cx, |cell, cx| cell.start_execution(cx));
+
+        match &self.kernel {
+            Kernel::RunningKernel(_) => {
+                self.send(message, cx).ok();
+            }
+            Kernel::StartingKernel(task) => {
+                let task = task.clone();
+
+                cx.spawn(async move |this, cx| {
+                    task.await;
+                    this.update(cx, |session, cx| {
+                        session.send(message, cx).ok();
+                    })
+                    .ok();
+                })
+                .detach();
+            }
+            Kernel::ErroredLaunch(error) => {
+                let error = error.clone();
+                cell.update(cx, |cell, cx| cell.kernel_errored(&error, cx));
+            }
+            _ => {}
+        }
+    }
+
     pub fn route(&mut self, message: &JupyterMessage, window: &mut Window, cx: &mut Context<Self>) {
         let parent_message_id = match message.parent_header.as_ref() {
             Some(header) => &header.msg_id,
             None => return,
         };
 
+        if self.introspections.contains_key(parent_message_id) {
+            self.route_introspection(parent_message_id, message);
+        }
+
         match &message.content {
             JupyterMessageContent::Status(status) => {
                 self.kernel.set_execution_state(&status.execution_state);
 
+                if let ExecutionState::Idle = status.execution_state
+                    && (self.blocks.contains_key(parent_message_id)
+                        || self.cell_executions.contains_key(parent_message_id))
+                {
+                    cx.emit(SessionEvent::ExecutionFinished);
+                }
+
                 telemetry::event!(
                     "Kernel Status Changed",
                     kernel_language = self.kernel_specification.language(),
@@ -522,6 +656,12 @@ impl Session {
                         execution_view.update_display_data(&update.data, &display_id, window, cx);
                     });
                 });
+                for cell in self.cell_executions.values() {
+                    cell.update(cx, |cell, cx| {
+                        cell.update_display_data(&update.data, &display_id, window, cx);
+                    })
+                    .ok();
+                }
                 return;
             }
             _ => {}
@@ -529,6 +669,38 @@ impl Session {
 
         if let Some(block) = self.blocks.get_mut(parent_message_id) {
             block.handle_message(message, window, cx);
+        } else if let Some(cell) = self.cell_executions.get(parent_message_id) {
+            cell.update(cx, |cell, cx| cell.handle_message(message, window, cx))
+                .ok();
+        }
+    }
+
+    fn route_introspection(&mut self, parent_message_id: &str, message: &JupyterMessage) {
+        match &message.content {
+            JupyterMessageContent::StreamContent(stream) => {
+                if let (Stdio::Stdout, Some(introspection)) =
+                    (&stream.name, self.introspections.get_mut(parent_message_id))
+                {
+                    introspection.stdout.push_str(&stream.text);
+                }
+            }
+            JupyterMessageContent::ErrorOutput(error) => {
+                if let Some(introspection) = self.introspections.get_mut(parent_message_id) {
+                    introspection.error = Some(format!("{}: {}", error.ename, error.evalue));
+                }
+            }
+            JupyterMessageContent::Status(status) => {
+                if let ExecutionState::Idle = status.execution_state
+                    && let Some(introspection) = self.introspections.remove(parent_message_id)
+                {
+                    let result = match introspection.error {
+                        Some(error) => Err(anyhow::anyhow!(error)),
+                        None => Ok(introspection.stdout),
+                    };
+                    introspection.done.send(result).ok();
+                }
+            }
+            _ => {}
         }
     }
 
@@ -641,6 +813,8 @@ impl Session {
 
 pub enum SessionEvent {
     Shutdown(WeakEntity<Editor>),
+    /// An execution from an editor or notebook cell has completed.
+    ExecutionFinished,
 }
 
 impl EventEmitter<SessionEvent> for Session {}
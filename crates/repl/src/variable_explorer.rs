@@ -0,0 +1,526 @@
+use editor::Editor;
+use gpui::{
+    App, Entity, EventEmitter, FocusHandle, Focusable, Subscription, Task, WeakEntity, actions,
+    prelude::*,
+};
+use runtimelib::media::datatable::TabularDataResource;
+use serde::Deserialize;
+use ui::{Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::item::{ItemEvent, WeakItemHandle};
+use workspace::{ItemHandle, Workspace, item::Item};
+
+use crate::Session;
+use crate::notebook::NotebookEditor;
+use crate::outputs::table::TableView;
+use crate::repl_store::ReplStore;
+use crate::session::SessionEvent;
+
+actions!(
+    repl,
+    [
+        /// Opens the variable explorer for the REPL session of the active editor.
+        Variables,
+        /// Refreshes the variables listed in the variable explorer.
+        RefreshVariables
+    ]
+);
+
+/// Prints the user-defined variables of an IPython kernel as a JSON array.
+const PYTHON_LIST_VARIABLES: &str = r#"
+def __zed_list_variables():
+    import json, types
+    ipython = get_ipython()
+    hidden = getattr(ipython, "user_ns_hidden", {})
+    variables = []
+    for name, value in list(ipython.user_ns.items()):
+        if name.startswith("_") or name in hidden:
+            continue
+        if isinstance(value, (types.ModuleType, types.FunctionType, types.BuiltinFunctionType, type)):
+            continue
+        type_name = type(value).__name__
+        module = type(value).__module__.split(".")[0]
+        shape = getattr(value, "shape", None)
+        if isinstance(shape, tuple):
+            shape = " × ".join(str(dimension) for dimension in shape)
+        elif hasattr(value, "__len__") and not isinstance(value, str):
+            try:
+                shape = str(len(value))
+            except Exception:
+                shape = None
+        else:
+            shape = None
+        try:
+            preview = " ".join(repr(value).split())
+        except Exception:
+            preview = ""
+        tabular = (module == "pandas" and type_name in ("DataFrame", "Series")) or (
+            module == "numpy" and type_name == "ndarray" and 1 <= getattr(value, "ndim", 0) <= 2
+        )
+        variables.append({
+            "name": name,
+            "type": type_name,
+            "shape": shape,
+            "preview": preview[:200],
+            "tabular": tabular,
+        })
+    print(json.dumps(variables))
+__zed_list_variables()
+del __zed_list_variables
+"#;
+
+/// Prints a variable as a Table Schema data resource, using pandas.
+const PYTHON_VARIABLE_TABLE: &str = r#"
+def __zed_variable_table(value):
+    import pandas
+    if not isinstance(value, (pandas.DataFrame, pandas.Series)):
+        value = pandas.DataFrame(value)
+    print(value.head(1000).to_json(orient="table", default_handler=str))
+__zed_variable_table({name})
+del __zed_variable_table
+"#;
+
+fn list_variables_code(language: &str) -> Option<String> {
+    match language.to_lowercase().as_str() {
+        "python" => Some(PYTHON_LIST_VARIABLES.to_string()),
+        _ => None,
+    }
+}
+
+fn variable_table_code(language: &str, name: &str) -> Option<String> {
+    let is_identifier = name
+        .chars()
+        .all(|character| character.is_alphanumeric() || character == '_');
+    if !is_identifier {
+        return None;
+    }
+    match language.to_lowercase().as_str() {
+        "python" => Some(PYTHON_VARIABLE_TABLE.replace("{name}", name)),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+struct KernelVariable {
+    name: String,
+    #[serde(rename = "type")]
+    type_name: String,
+    shape: Option<String>,
+    preview: String,
+    #[serde(default)]
+    tabular: bool,
+}
+
+fn parse_variables(output: &str) -> anyhow::Result<Vec<KernelVariable>> {
+    let line = output
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or_default();
+    Ok(serde_json::from_str(line)?)
+}
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(
+        |workspace: &mut Workspace, _window, _cx: &mut Context<Workspace>| {
+            workspace.register_action(|workspace, _: &Variables, window, cx| {
+                let existing = workspace
+                    .active_pane()
+                    .read(cx)
+                    .items()
+                    .find_map(|item| item.downcast::<VariableExplorer>());
+
+                if let Some(existing) = existing {
+                    workspace.activate_item(&existing, true, true, window, cx);
+                } else {
+                    let variable_explorer = VariableExplorer::new(workspace, window, cx);
+                    workspace.add_item_to_active_pane(
+                        Box::new(variable_explorer),
+                        None,
+                        true,
+                        window,
+                        cx,
+                    )
+                }
+            });
+        },
+    )
+    .detach();
+}
+
+/// Returns the REPL session attached to an editor or notebook, if any.
+fn session_for_item(item: &dyn ItemHandle, cx: &App) -> Option<Entity<Session>> {
+    if let Some(notebook) = item.downcast::<NotebookEditor>() {
+        return notebook.read(cx).session().cloned();
+    }
+    let editor = item.act_as::<Editor>(cx)?;
+    ReplStore::global(cx)
+        .read(cx)
+        .get_session(editor.entity_id())
+        .cloned()
+}
+
+/// Lists the variables defined in the kernel of a REPL session.
+pub struct VariableExplorer {
+    focus_handle: FocusHandle,
+    source_item: Option<Box<dyn WeakItemHandle>>,
+    session: Option<WeakEntity<Session>>,
+    variables: Vec<KernelVariable>,
+    loading: bool,
+    error: Option<SharedString>,
+    table: Option<(SharedString, Entity<TableView>)>,
+    refresh_task: Task<()>,
+    table_task: Task<()>,
+    _session_subscription: Option<Subscription>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl VariableExplorer {
+    pub fn new(
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let source_item = workspace.active_item(cx).map(|item| item.downgrade_item());
+        let workspace_handle = cx.entity();
+
+        cx.new(|cx| {
+            let focus_handle = cx.focus_handle();
+            let subscriptions = vec![
+                cx.subscribe_in(&workspace_handle, window, Self::on_workspace_event),
+                cx.on_focus_in(&focus_handle, window, |this, _window, cx| this.refresh(cx)),
+            ];
+
+            let mut this = Self {
+                focus_handle,
+                source_item,
+                session: None,
+                variables: Vec::new(),
+                loading: false,
+                error: None,
+                table: None,
+                refresh_task: Task::ready(()),
+                table_task: Task::ready(()),
+                _session_subscription: None,
+                _subscriptions: subscriptions,
+            };
+            this.refresh(cx);
+            this
+        })
+    }
+
+    fn on_workspace_event(
+        &mut self,
+        workspace: &Entity<Workspace>,
+        event: &workspace::Event,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let workspace::Event::ActiveItemChanged = event else {
+            return;
+        };
+        let Some(item) = workspace.read(cx).active_item(cx) else {
+            return;
+        };
+        if item.item_id() == cx.entity_id() || session_for_item(item.as_ref(), cx).is_none() {
+            return;
+        }
+
+        self.source_item = Some(item.downgrade_item());
+        self.refresh(cx);
+    }
+
+    fn update_session(&mut self, cx: &mut Context<Self>) -> Option<Entity<Session>> {
+        let session = self
+            .source_item
+            .as_ref()
+            .and_then(|item| item.upgrade())
+            .and_then(|item| session_for_item(item.as_ref(), cx));
+
+        let current = self.session.as_ref().and_then(|session| session.upgrade());
+        if current != session {
+            self.session = session.as_ref().map(|session| session.downgrade());
+            self.variables.clear();
+            self.table = None;
+            self._session_subscription = session.as_ref().map(|session| {
+                cx.subscribe(session, |this, _, event, cx| {
+                    if let SessionEvent::ExecutionFinished = event {
+                        this.refresh(cx);
+                    }
+                })
+            });
+        }
+        session
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let Some(session) = self.update_session(cx) else {
+            self.error = None;
+            cx.notify();
+            return;
+        };
+
+        let language = session.read(cx).kernel_specification.language();
+        let Some(code) = list_variables_code(&language) else {
+            self.error = Some(
+                format!("Inspecting variables isn't supported for {language} kernels yet").into(),
+            );
+            cx.notify();
+            return;
+        };
+
+        let introspection = session.update(cx, |session, cx| session.introspect(code, cx));
+        self.loading = true;
+        cx.notify();
+
+        self.refresh_task = cx.spawn(async move |this, cx| {
+            let variables = introspection
+                .await
+                .and_then(|output| parse_variables(&output));
+            this.update(cx, |this, cx| {
+                this.loading = false;
+                match variables {
+                    Ok(variables) => {
+                        this.variables = variables;
+                        this.error = None;
+                    }
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn open_table(&mut self, name: String, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(session) = self.session.as_ref().and_then(|session| session.upgrade()) else {
+            return;
+        };
+        let language = session.read(cx).kernel_specification.language();
+        let Some(code) = variable_table_code(&language, &name) else {
+            return;
+        };
+
+        let introspection = session.update(cx, |session, cx| session.introspect(code, cx));
+        self.table_task = cx.spawn_in(window, async move |this, cx| {
+            let table = introspection
+                .await
+                .and_then(|output| Ok(serde_json::from_str::<TabularDataResource>(output.trim())?));
+            this.update_in(cx, |this, window, cx| {
+                match table {
+                    Ok(table) => {
+                        let table = cx.new(|cx| TableView::new(&table, window, cx));
+                        this.table = Some((name.into(), table));
+                    }
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .log_err();
+        });
+    }
+
+    fn render_variable(
+        &self,
+        index: usize,
+        variable: &KernelVariable,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let details = match &variable.shape {
+            Some(shape) => format!("{} ({shape})", variable.type_name),
+            None => variable.type_name.clone(),
+        };
+
+        h_flex()
+            .id(("variable", index))
+            .w_full()
+            .gap_4()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                div()
+                    .w(rems(12.))
+                    .flex_none()
+                    .child(Label::new(variable.name.clone()).buffer_font(cx).truncate()),
+            )
+            .child(
+                div()
+                    .w(rems(12.))
+                    .flex_none()
+                    .child(Label::new(details).color(Color::Muted).truncate()),
+            )
+            .child(
+                div().flex_1().min_w_0().child(
+                    Label::new(variable.preview.clone())
+                        .buffer_font(cx)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .truncate(),
+                ),
+            )
+            .when(variable.tabular, |row| {
+                let name = variable.name.clone();
+                row.child(
+                    IconButton::new(("open-variable-table", index), IconName::Eye)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Open as Table"))
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.open_table(name.clone(), window, cx)
+                        })),
+                )
+            })
+    }
+}
+
+impl EventEmitter<ItemEvent> for VariableExplorer {}
+
+impl Focusable for VariableExplorer {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for VariableExplorer {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Variables".into()
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(workspace::item::ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for VariableExplorer {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let session = self.session.as_ref().and_then(|session| session.upgrade());
+
+        let header = h_flex()
+            .w_full()
+            .justify_between()
+            .child(Label::new("Variables").size(LabelSize::Large))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .when(self.loading, |this| {
+                        this.child(Label::new("Refreshing…").color(Color::Muted))
+                    })
+                    .child(
+                        IconButton::new("refresh-variables", IconName::RotateCw)
+                            .tooltip(move |_window, cx| {
+                                Tooltip::for_action("Refresh Variables", &RefreshVariables, cx)
+                            })
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(Box::new(RefreshVariables), cx)
+                            }),
+                    ),
+            );
+
+        let body = if session.is_none() {
+            v_flex()
+                .child(Label::new(
+                    "Run code in a REPL session or notebook to inspect its variables.",
+                ))
+                .into_any_element()
+        } else if self.variables.is_empty() && self.error.is_none() && !self.loading {
+            Label::new("No variables defined")
+                .color(Color::Muted)
+                .into_any_element()
+        } else {
+            v_flex()
+                .w_full()
+                .children(
+                    self.variables
+                        .iter()
+                        .enumerate()
+                        .map(|(index, variable)| self.render_variable(index, variable, cx)),
+                )
+                .into_any_element()
+        };
+
+        v_flex()
+            .id("variable-explorer")
+            .key_context("VariableExplorer")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(|this, _: &RefreshVariables, _window, cx| this.refresh(cx)))
+            .p_4()
+            .gap_2()
+            .size_full()
+            .overflow_y_scroll()
+            .child(header)
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| Label::new(error).color(Color::Error)),
+            )
+            .child(body)
+            .when_some(self.table.clone(), |this, (name, table)| {
+                this.child(
+                    v_flex()
+                        .pt_4()
+                        .gap_2()
+                        .child(
+                            h_flex()
+                                .justify_between()
+                                .child(Label::new(name).buffer_font(cx))
+                                .child(
+                                    IconButton::new("close-variable-table", IconName::Close)
+                                        .icon_size(IconSize::Small)
+                                        .tooltip(Tooltip::text("Close Table"))
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.table = None;
+                                            cx.notify();
+                                        })),
+                                ),
+                        )
+                        .child(table),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_variables() {
+        let output = "warming up\n[{\"name\": \"df\", \"type\": \"DataFrame\", \"shape\": \"3 × 2\", \"preview\": \"a b\", \"tabular\": true}, {\"name\": \"x\", \"type\": \"int\", \"shape\": null, \"preview\": \"1\", \"tabular\": false}]\n";
+        let variables = parse_variables(output).unwrap();
+        assert_eq!(
+            variables,
+            vec![
+                KernelVariable {
+                    name: "df".into(),
+                    type_name: "DataFrame".into(),
+                    shape: Some("3 × 2".into()),
+                    preview: "a b".into(),
+                    tabular: true,
+                },
+                KernelVariable {
+                    name: "x".into(),
+                    type_name: "int".into(),
+                    shape: None,
+                    preview: "1".into(),
+                    tabular: false,
+                },
+            ]
+        );
+        assert!(parse_variables("").is_err());
+    }
+
+    #[test]
+    fn test_variable_table_code_rejects_expressions() {
+        assert!(variable_table_code("python", "df").is_some());
+        assert!(variable_table_code("Python", "data_2").is_some());
+        assert!(variable_table_code("python", "__import__('os')").is_none());
+        assert!(variable_table_code("r", "df").is_none());
+    }
+}
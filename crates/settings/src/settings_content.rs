@@ -81,11 +81,17 @@ pub struct SettingsContent {
     /// Default: VSCode
     pub base_keymap: Option<BaseKeymapContent>,
 
+    /// Configuration for the bookmarks panel.
+    pub bookmarks_panel: Option<PanelSettingsContent>,
+
     /// Configuration for the collab panel visual settings.
     pub collaboration_panel: Option<PanelSettingsContent>,
 
     pub debugger: Option<DebuggerSettingsContent>,
 
+    /// Configuration for the document tree panel.
+    pub document_tree_panel: Option<PanelSettingsContent>,
+
     /// Configuration for Diagnostics-related features.
     pub diagnostics: Option<DiagnosticsSettingsContent>,
 
@@ -115,6 +121,9 @@ pub struct SettingsContent {
 
     pub line_indicator_format: Option<LineIndicatorFormat>,
 
+    /// Configuration for the local history of files.
+    pub local_history: Option<LocalHistorySettingsContent>,
+
     pub language_models: Option<AllLanguageModelSettingsContent>,
 
     pub outline_panel: Option<OutlinePanelSettingsContent>,
@@ -137,6 +146,10 @@ pub struct SettingsContent {
 
     /// Configuration for session-related features
     pub session: Option<SessionSettingsContent>,
+
+    /// Configuration for spell checking.
+    pub spell_check: Option<SpellCheckSettingsContent>,
+
     /// Control what info is collected by Zed.
     pub telemetry: Option<TelemetrySettingsContent>,
 
@@ -747,6 +760,51 @@ pub enum HourFormat {
     Hour24,
 }
 
+/// Settings for the local history of files, which keeps the versions of files as they are saved
+/// or changed on disk.
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct LocalHistorySettingsContent {
+    /// Whether to record the versions of files as they are saved or changed on disk.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// How many days to keep versions for.
+    ///
+    /// Default: 7
+    pub max_age_days: Option<u32>,
+    /// How many versions to keep for each file, dropping the oldest ones first.
+    ///
+    /// Default: 100
+    pub max_versions_per_file: Option<u32>,
+    /// Files larger than this size (in kilobytes) are not recorded.
+    ///
+    /// Default: 1024
+    pub max_file_size_kb: Option<u64>,
+}
+
+/// Configuration for spell checking comments and strings in code, and prose files such as
+/// Markdown documents and commit messages.
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct SpellCheckSettingsContent {
+    /// Whether to report misspelled words as diagnostics.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// The names of the Hunspell dictionaries to check words against. Each name refers to a pair
+    /// of `.aff` and `.dic` files in the `dictionaries` folder of Zed's data directory, such as
+    /// `en_US.aff` and `en_US.dic`. Words known to any of the dictionaries are accepted.
+    ///
+    /// Default: ["en_US"]
+    pub dictionaries: Option<Vec<String>>,
+    /// Additional words to accept in every project. Words specific to a project can be listed,
+    /// one per line, in its `.zed/words.txt` file.
+    ///
+    /// Default: []
+    pub words: Option<Vec<String>>,
+}
+
 #[with_fallible_options]
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
 pub struct OutlinePanelSettingsContent {
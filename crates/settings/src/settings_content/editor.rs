@@ -202,6 +202,10 @@ pub struct EditorSettingsContent {
     ///
     /// Default: [`DocumentColorsRenderMode::Inlay`]
     pub lsp_document_colors: Option<DocumentColorsRenderMode>,
+    /// Where to show LSP `textDocument/codeLens` lenses.
+    ///
+    /// Default: [`CodeLensDisplayMode::Menu`]
+    pub code_lens: Option<CodeLensDisplayMode>,
     /// When to show the scrollbar in the completion menu.
     /// This setting can take four values:
     ///
@@ -215,6 +219,8 @@ pub struct EditorSettingsContent {
     /// 4. Never show the scrollbar:
     ///    "never" (default)
     pub completion_menu_scrollbar: Option<ShowScrollbar>,
+    /// Settings for keeping the undo history of files across restarts.
+    pub persistent_undo: Option<PersistentUndoContent>,
 }
 
 #[derive(
@@ -401,6 +407,32 @@ pub struct GutterContent {
     pub folds: Option<bool>,
 }
 
+/// Where to show LSP `textDocument/codeLens` lenses.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeLensDisplayMode {
+    /// Draw lenses as clickable lines above the code they refer to.
+    Inline,
+    /// List lenses in the code actions menu.
+    #[default]
+    Menu,
+    /// Do not query and show code lenses.
+    Off,
+}
+
 /// How to render LSP `textDocument/documentColor` colors in the editor.
 #[derive(
     Copy,
@@ -800,6 +832,25 @@ pub struct DragAndDropSelectionContent {
     pub delay: Option<DelayMs>,
 }
 
+/// Settings for keeping the undo history of files across restarts.
+#[with_fallible_options]
+#[derive(Clone, Default, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq)]
+pub struct PersistentUndoContent {
+    /// Whether to save the undo history of files when they are saved, and restore it when they
+    /// are opened again without having changed on disk.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// How many of the most recent transactions to keep in the saved undo history of a file.
+    ///
+    /// Default: 1000
+    pub max_transactions: Option<u32>,
+    /// The undo history of files larger than this size (in kilobytes) is not saved.
+    ///
+    /// Default: 1024
+    pub max_file_size_kb: Option<u64>,
+}
+
 /// When to show the minimap in the editor.
 ///
 /// Default: never
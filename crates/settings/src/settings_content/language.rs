@@ -700,7 +700,6 @@ pub struct PrettierSettingsContent {
     pub options: Option<HashMap<String, serde_json::Value>>,
 }
 
-/// TODO: this should just be a bool
 /// Controls the behavior of formatting files when they are saved.
 #[derive(
     Debug,
@@ -715,12 +714,19 @@ pub struct PrettierSettingsContent {
     strum::VariantArray,
     strum::VariantNames,
 )]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 pub enum FormatOnSave {
     /// Files should be formatted on save.
     On,
     /// Files should not be formatted on save.
     Off,
+    /// Only the lines changed since the last git commit should be formatted on save.
+    ///
+    /// Language servers format these lines through range formatting, and Prettier through its
+    /// `rangeStart` and `rangeEnd` options. Formatters that can't format ranges, like external
+    /// commands and code actions, including `code_actions_on_format`, are skipped. Files outside
+    /// of a git repository are formatted entirely.
+    ModifiedLines,
 }
 
 /// Controls which formatters should be used when formatting code.
@@ -97,6 +97,12 @@ pub struct WorktreeSettingsContent {
     /// Treat the files matching these globs as hidden files. You can hide hidden files in the project panel.
     /// Default: ["**/.*"]
     pub hidden_files: Option<Vec<String>>,
+
+    /// The size, in megabytes, from which files are opened in large-file mode: read-only,
+    /// without syntax highlighting, language servers or git diffs.
+    ///
+    /// Default: 256
+    pub large_file_threshold_mb: Option<u64>,
 }
 
 #[with_fallible_options]
@@ -164,6 +170,11 @@ pub struct GlobalLspSettingsContent {
     ///
     /// Default: `true`
     pub button: Option<bool>,
+    /// Whether to review the edits that language servers make for a rename, including the
+    /// ones for renaming files from the project panel, before applying them.
+    ///
+    /// Default: `false`
+    pub preview_renames: Option<bool>,
 }
 
 #[with_fallible_options]
@@ -176,9 +176,11 @@ impl VsCodeSettings {
             audio: None,
             auto_update: None,
             base_keymap: Some(BaseKeymapContent::VSCode),
+            bookmarks_panel: None,
             calls: None,
             collaboration_panel: None,
             debugger: None,
+            document_tree_panel: None,
             diagnostics: None,
             disable_ai: None,
             editor: self.editor_settings_content(),
@@ -192,6 +194,7 @@ impl VsCodeSettings {
             journal: None,
             language_models: None,
             line_indicator_format: None,
+            local_history: self.local_history_settings_content(),
             log: None,
             message_editor: None,
             node: self.node_binary_settings(),
@@ -205,6 +208,7 @@ impl VsCodeSettings {
             repl: None,
             server_url: None,
             session: None,
+            spell_check: None,
             status_bar: self.status_bar_settings_content(),
             tab_bar: self.tab_bar_settings_content(),
             tabs: self.item_settings_content(),
@@ -231,6 +235,13 @@ impl VsCodeSettings {
         EditorSettingsContent {
             auto_signature_help: self.read_bool("editor.parameterHints.enabled"),
             autoscroll_on_clicks: None,
+            code_lens: self.read_bool("editor.codeLens").map(|enabled| {
+                if enabled {
+                    CodeLensDisplayMode::Inline
+                } else {
+                    CodeLensDisplayMode::Off
+                }
+            }),
             cursor_blink: self.read_enum("editor.cursorBlinking", |s| match s {
                 "blink" | "phase" | "expand" | "smooth" => Some(true),
                 "solid" => Some(false),
@@ -274,6 +285,7 @@ impl VsCodeSettings {
                 "alt" => Some(MultiCursorModifier::Alt),
                 _ => None,
             }),
+            persistent_undo: None,
             redact_private_values: None,
             relative_line_numbers: self.read_enum("editor.lineNumbers", |s| match s {
                 "relative" => Some(RelativeLineNumbers::Enabled),
@@ -430,10 +442,14 @@ impl VsCodeSettings {
             ensure_final_newline_on_save: self.read_bool("files.insertFinalNewline"),
             extend_comment_on_newline: None,
             format_on_save: self.read_bool("editor.guides.formatOnSave").map(|b| {
-                if b {
-                    FormatOnSave::On
-                } else {
+                if !b {
                     FormatOnSave::Off
+                } else if let Some("modifications" | "modificationsIfAvailable") =
+                    self.read_str("editor.formatOnSaveMode")
+                {
+                    FormatOnSave::ModifiedLines
+                } else {
+                    FormatOnSave::On
                 }
             }),
             formatter: None,
@@ -644,6 +660,15 @@ impl VsCodeSettings {
         })
     }
 
+    fn local_history_settings_content(&self) -> Option<LocalHistorySettingsContent> {
+        skip_default(LocalHistorySettingsContent {
+            enabled: self.read_bool("workbench.localHistory.enabled"),
+            max_age_days: None,
+            max_versions_per_file: self.read_u32("workbench.localHistory.maxFileEntries"),
+            max_file_size_kb: self.read_u64("workbench.localHistory.maxFileSize"),
+        })
+    }
+
     fn status_bar_settings_content(&self) -> Option<StatusBarSettingsContent> {
         skip_default(StatusBarSettingsContent {
             show: self.read_bool("workbench.statusBar.visible"),
@@ -901,6 +926,7 @@ impl VsCodeSettings {
                 .filter(|r| !r.is_empty()),
             private_files: None,
             hidden_files: None,
+            large_file_threshold_mb: None,
         }
     }
 }
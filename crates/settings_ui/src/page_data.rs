@@ -7019,6 +7019,19 @@ fn language_settings_data() -> Vec<SettingsPageItem> {
             }),
             metadata: None,
             files: USER,
+        }));
+        items.push(SettingsPageItem::SettingItem(SettingItem {
+            title: "Code Lens",
+            description: "Where to show LSP code lenses, like reference counts and test runners.",
+            field: Box::new(SettingField {
+                json_path: Some("code_lens"),
+                pick: |settings_content| settings_content.editor.code_lens.as_ref(),
+                write: |settings_content, value| {
+                    settings_content.editor.code_lens = value;
+                },
+            }),
+            metadata: None,
+            files: USER,
         }))
     }
     items.extend([
@@ -1,6 +1,7 @@
 use anyhow::{Context as _, Result};
+use regex::Regex;
 use smallvec::SmallVec;
-use std::{collections::BTreeMap, ops::Range};
+use std::{cell::RefCell, collections::BTreeMap, ops::Range};
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Snippet {
@@ -12,14 +13,156 @@ pub struct Snippet {
 pub struct TabStop {
     pub ranges: SmallVec<[Range<isize>; 2]>,
     pub choices: Option<Vec<String>>,
+    /// Mirrors of this tabstop whose text is derived from the tabstop's text through a
+    /// transform, like `${1/(.*)/${1:/upcase}/}`.
+    pub transformed_mirrors: Vec<(Range<isize>, Transform)>,
+}
+
+/// A regular expression substitution applied to the value of a tabstop or variable,
+/// written as `/regex/format/flags`.
+#[derive(Clone, Debug)]
+pub struct Transform {
+    regex: Regex,
+    format: Vec<FormatItem>,
+    global: bool,
+}
+
+impl PartialEq for Transform {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str()
+            && self.format == other.format
+            && self.global == other.global
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum FormatItem {
+    Text(String),
+    Group(usize),
+    CaseModifier(usize, CaseModifier),
+    Conditional {
+        group: usize,
+        if_matched: Option<String>,
+        otherwise: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CaseModifier {
+    Upcase,
+    Downcase,
+    Capitalize,
+    Camelcase,
+    Pascalcase,
+}
+
+/// The variables defined by the LSP and VS Code snippet syntax. Any other variable is turned into
+/// a placeholder named after it.
+const KNOWN_VARIABLES: &[&str] = &[
+    "BLOCK_COMMENT_END",
+    "BLOCK_COMMENT_START",
+    "CLIPBOARD",
+    "CURRENT_DATE",
+    "CURRENT_DAY_NAME",
+    "CURRENT_DAY_NAME_SHORT",
+    "CURRENT_HOUR",
+    "CURRENT_MINUTE",
+    "CURRENT_MONTH",
+    "CURRENT_MONTH_NAME",
+    "CURRENT_MONTH_NAME_SHORT",
+    "CURRENT_SECOND",
+    "CURRENT_SECONDS_UNIX",
+    "CURRENT_TIMEZONE_OFFSET",
+    "CURRENT_YEAR",
+    "CURRENT_YEAR_SHORT",
+    "CURSOR_INDEX",
+    "CURSOR_NUMBER",
+    "LINE_COMMENT",
+    "RANDOM",
+    "RANDOM_HEX",
+    "RELATIVE_FILEPATH",
+    "SELECTION",
+    "TM_CURRENT_LINE",
+    "TM_CURRENT_WORD",
+    "TM_DIRECTORY",
+    "TM_FILENAME",
+    "TM_FILENAME_BASE",
+    "TM_FILEPATH",
+    "TM_LINE_INDEX",
+    "TM_LINE_NUMBER",
+    "TM_SELECTED_TEXT",
+    "UUID",
+    "WORKSPACE_FOLDER",
+    "WORKSPACE_NAME",
+];
+
+struct ParseContext<'a> {
+    resolve_variable: &'a dyn Fn(&str) -> Option<String>,
+    /// The text of each tabstop's placeholder, used to fill in transformed mirrors.
+    placeholders: BTreeMap<usize, String>,
+    /// The index of the first tabstop given to an unknown variable, after the snippet's own
+    /// tabstops. `None` while the placeholders are collected.
+    first_unknown_variable_tabstop: Option<usize>,
+    unknown_variable_tabstops: RefCell<BTreeMap<String, usize>>,
+}
+
+impl ParseContext<'_> {
+    /// The tabstop of an unknown variable, shared by all of its occurrences.
+    fn unknown_variable_tabstop(&self, name: &str) -> Option<usize> {
+        let first_tabstop = self.first_unknown_variable_tabstop?;
+        let mut tabstops = self.unknown_variable_tabstops.borrow_mut();
+        let next_tabstop = first_tabstop + tabstops.len();
+        Some(*tabstops.entry(name.to_string()).or_insert(next_tabstop))
+    }
 }
 
 impl Snippet {
     pub fn parse(source: &str) -> Result<Self> {
+        Self::parse_with_variables(source, &|_| None)
+    }
+
+    /// Parses a snippet, expanding variables like `$TM_FILENAME` with `resolve_variable`.
+    ///
+    /// Variables that resolve to `None` or an empty string are replaced by their default
+    /// (`${NAME:default}`), or removed when there is no default. Unknown variables become
+    /// placeholders holding their name or default, which are visited after the snippet's own
+    /// tabstops.
+    pub fn parse_with_variables(
+        source: &str,
+        resolve_variable: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        let mut context = ParseContext {
+            resolve_variable,
+            placeholders: BTreeMap::new(),
+            first_unknown_variable_tabstop: None,
+            unknown_variable_tabstops: RefCell::default(),
+        };
+
+        // Transformed mirrors can appear before the placeholder they mirror, so the
+        // placeholders are collected in a first pass.
+        let mut text = String::with_capacity(source.len());
+        let mut tabstops = BTreeMap::new();
+        parse_snippet(source, false, &context, &mut text, &mut tabstops)
+            .context("failed to parse snippet")?;
+        context.placeholders = tabstops
+            .iter()
+            .filter_map(|(index, tabstop)| {
+                let range = tabstop.ranges.iter().find(|range| !range.is_empty())?;
+                Some((
+                    *index,
+                    text[range.start as usize..range.end as usize].to_string(),
+                ))
+            })
+            .collect();
+        context.first_unknown_variable_tabstop =
+            Some(tabstops.keys().max().map_or(1, |index| index + 1));
+
         let mut text = String::with_capacity(source.len());
         let mut tabstops = BTreeMap::new();
-        parse_snippet(source, false, &mut text, &mut tabstops)
+        parse_snippet(source, false, &context, &mut text, &mut tabstops)
             .context("failed to parse snippet")?;
+        // A tabstop that only appears transformed has nothing to edit.
+        tabstops.retain(|_, tabstop| !tabstop.ranges.is_empty());
 
         let len = text.len() as isize;
         let final_tabstop = tabstops.remove(&0);
@@ -30,7 +173,7 @@ impl Snippet {
         } else {
             let end_tabstop = TabStop {
                 ranges: [len..len].into_iter().collect(),
-                choices: None,
+                ..Default::default()
             };
 
             if !tabstops.last().is_some_and(|t| *t == end_tabstop) {
@@ -42,9 +185,100 @@ impl Snippet {
     }
 }
 
+impl Transform {
+    /// Replaces the first match of the regex in `input` (or every match, with the `g` flag)
+    /// with the format string.
+    pub fn apply(&self, input: &str) -> String {
+        let mut result = String::with_capacity(input.len());
+        let mut last_match_end = 0;
+        for captures in self.regex.captures_iter(input) {
+            let Some(whole_match) = captures.get(0) else {
+                continue;
+            };
+            result.push_str(&input[last_match_end..whole_match.start()]);
+            for item in &self.format {
+                item.format(&captures, &mut result);
+            }
+            last_match_end = whole_match.end();
+            if !self.global {
+                break;
+            }
+        }
+        result.push_str(&input[last_match_end..]);
+        result
+    }
+}
+
+impl FormatItem {
+    fn format(&self, captures: &regex::Captures, result: &mut String) {
+        let group_text = |group: usize| captures.get(group).map(|group| group.as_str());
+        match self {
+            FormatItem::Text(text) => result.push_str(text),
+            FormatItem::Group(group) => result.push_str(group_text(*group).unwrap_or_default()),
+            FormatItem::CaseModifier(group, modifier) => {
+                result.push_str(&modifier.apply(group_text(*group).unwrap_or_default()))
+            }
+            FormatItem::Conditional {
+                group,
+                if_matched,
+                otherwise,
+            } => match group_text(*group).filter(|text| !text.is_empty()) {
+                Some(text) => result.push_str(if_matched.as_deref().unwrap_or(text)),
+                None => result.push_str(otherwise),
+            },
+        }
+    }
+}
+
+impl CaseModifier {
+    fn parse(name: &str) -> Result<Self> {
+        Ok(match name {
+            "upcase" => CaseModifier::Upcase,
+            "downcase" => CaseModifier::Downcase,
+            "capitalize" => CaseModifier::Capitalize,
+            "camelcase" => CaseModifier::Camelcase,
+            "pascalcase" => CaseModifier::Pascalcase,
+            _ => anyhow::bail!("unknown format modifier `{name}`"),
+        })
+    }
+
+    fn apply(self, text: &str) -> String {
+        match self {
+            CaseModifier::Upcase => text.to_uppercase(),
+            CaseModifier::Downcase => text.to_lowercase(),
+            CaseModifier::Capitalize => capitalize(text),
+            CaseModifier::Camelcase | CaseModifier::Pascalcase => text
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .enumerate()
+                .map(|(index, word)| {
+                    if index == 0 && self == CaseModifier::Camelcase {
+                        let mut chars = word.chars();
+                        chars
+                            .next()
+                            .map(|first| first.to_lowercase().chain(chars).collect())
+                            .unwrap_or_default()
+                    } else {
+                        capitalize(word)
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
 fn parse_snippet<'a>(
     mut source: &'a str,
     nested: bool,
+    context: &ParseContext,
     text: &mut String,
     tabstops: &mut BTreeMap<usize, TabStop>,
 ) -> Result<&'a str> {
@@ -52,7 +286,7 @@ fn parse_snippet<'a>(
         match source.chars().next() {
             None => return Ok(""),
             Some('$') => {
-                source = parse_tabstop(&source[1..], text, tabstops)?;
+                source = parse_tabstop(&source[1..], context, text, tabstops)?;
             }
             Some('\\') => {
                 // As specified in the LSP spec (`Grammar` section),
@@ -91,24 +325,49 @@ fn parse_snippet<'a>(
 
 fn parse_tabstop<'a>(
     mut source: &'a str,
+    context: &ParseContext,
     text: &mut String,
     tabstops: &mut BTreeMap<usize, TabStop>,
 ) -> Result<&'a str> {
+    let braced = source.starts_with('{');
+    if braced {
+        source = &source[1..];
+    }
+    if source.starts_with(|c: char| c == '_' || c.is_ascii_alphabetic()) {
+        return parse_variable(source, braced, context, text, tabstops);
+    }
+
     let tabstop_start = text.len();
     let tabstop_index;
     let mut choices = None;
 
-    if source.starts_with('{') {
-        let (index, rest) = parse_int(&source[1..])?;
+    if braced {
+        let (index, rest) = parse_int(source)?;
         tabstop_index = index;
         source = rest;
 
+        if let Some(transform_source) = source.strip_prefix('/') {
+            let (transform, rest) = parse_transform(transform_source)?;
+            let placeholder = context
+                .placeholders
+                .get(&tabstop_index)
+                .map(String::as_str)
+                .unwrap_or_default();
+            text.push_str(&transform.apply(placeholder));
+            tabstops
+                .entry(tabstop_index)
+                .or_default()
+                .transformed_mirrors
+                .push((tabstop_start as isize..text.len() as isize, transform));
+            return Ok(rest);
+        }
+
         if source.starts_with("|") {
             (source, choices) = parse_choices(&source[1..], text)?;
         }
 
         if source.starts_with(':') {
-            source = parse_snippet(&source[1..], true, text, tabstops)?;
+            source = parse_snippet(&source[1..], true, context, text, tabstops)?;
         }
 
         if source.starts_with('}') {
@@ -122,17 +381,269 @@ fn parse_tabstop<'a>(
         source = rest;
     }
 
-    tabstops
-        .entry(tabstop_index)
-        .or_insert_with(|| TabStop {
-            ranges: Default::default(),
-            choices,
-        })
+    let tabstop = tabstops.entry(tabstop_index).or_default();
+    if tabstop.choices.is_none() {
+        tabstop.choices = choices;
+    }
+    tabstop
         .ranges
         .push(tabstop_start as isize..text.len() as isize);
     Ok(source)
 }
 
+fn parse_variable<'a>(
+    mut source: &'a str,
+    braced: bool,
+    context: &ParseContext,
+    text: &mut String,
+    tabstops: &mut BTreeMap<usize, TabStop>,
+) -> Result<&'a str> {
+    let name_len = source
+        .find(|c: char| c != '_' && !c.is_ascii_alphanumeric())
+        .unwrap_or(source.len());
+    let (name, rest) = source.split_at(name_len);
+    source = rest;
+    let value = (context.resolve_variable)(name).filter(|value| !value.is_empty());
+
+    if braced && let Some(transform_source) = source.strip_prefix('/') {
+        let (transform, rest) = parse_transform(transform_source)?;
+        text.push_str(&transform.apply(value.as_deref().unwrap_or_default()));
+        return Ok(rest);
+    }
+
+    let variable_start = text.len();
+    let is_unknown = value.is_none() && !KNOWN_VARIABLES.contains(&name);
+    if braced && source.starts_with(':') {
+        match &value {
+            Some(value) => {
+                // The default may contain tabstops, which are dropped along with it.
+                let mut default_text = String::new();
+                let mut default_tabstops = BTreeMap::new();
+                source = parse_snippet(
+                    &source[1..],
+                    true,
+                    context,
+                    &mut default_text,
+                    &mut default_tabstops,
+                )?;
+                text.push_str(value);
+            }
+            None => {
+                source = parse_snippet(&source[1..], true, context, text, tabstops)?;
+            }
+        }
+    } else if let Some(value) = &value {
+        text.push_str(value);
+    } else if is_unknown {
+        text.push_str(name);
+    }
+    if braced {
+        source = source
+            .strip_prefix('}')
+            .context("expected a closing brace")?;
+    }
+
+    if is_unknown && let Some(tabstop_index) = context.unknown_variable_tabstop(name) {
+        tabstops
+            .entry(tabstop_index)
+            .or_default()
+            .ranges
+            .push(variable_start as isize..text.len() as isize);
+    }
+    Ok(source)
+}
+
+/// Parses the `regex/format/flags}` part of a transform, after the opening slash.
+fn parse_transform(source: &str) -> Result<(Transform, &str)> {
+    let (pattern, source) = parse_transform_regex(source)?;
+    let (format, source) = parse_format(source, FormatEnd::Slash)?;
+    let flags_end = source.find('}').context("expected a closing brace")?;
+    let (flags, source) = source.split_at(flags_end);
+
+    let mut inline_flags = String::new();
+    for flag in ['i', 'm', 's'] {
+        if flags.contains(flag) {
+            inline_flags.push(flag);
+        }
+    }
+    let pattern = if inline_flags.is_empty() {
+        pattern
+    } else {
+        format!("(?{inline_flags}){pattern}")
+    };
+
+    Ok((
+        Transform {
+            regex: Regex::new(&pattern).context("invalid transform regex")?,
+            format,
+            global: flags.contains('g'),
+        },
+        &source[1..],
+    ))
+}
+
+fn parse_transform_regex(mut source: &str) -> Result<(String, &str)> {
+    let mut pattern = String::new();
+    loop {
+        match source.chars().next() {
+            None => anyhow::bail!("expected a closing slash after transform regex"),
+            Some('/') => return Ok((pattern, &source[1..])),
+            Some('\\') => {
+                source = &source[1..];
+                match source.chars().next() {
+                    Some('/') => pattern.push('/'),
+                    Some(c) => {
+                        pattern.push('\\');
+                        pattern.push(c);
+                    }
+                    None => anyhow::bail!("expected a closing slash after transform regex"),
+                }
+                source = &source[source.chars().next().map_or(0, char::len_utf8)..];
+            }
+            Some(c) => {
+                pattern.push(c);
+                source = &source[c.len_utf8()..];
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FormatEnd {
+    Slash,
+    Brace,
+    ColonOrBrace,
+}
+
+impl FormatEnd {
+    fn matches(self, c: char) -> bool {
+        match self {
+            FormatEnd::Slash => c == '/',
+            FormatEnd::Brace => c == '}',
+            FormatEnd::ColonOrBrace => c == ':' || c == '}',
+        }
+    }
+}
+
+/// Parses a transform format string up to (and excluding) its terminator.
+fn parse_format(mut source: &str, end: FormatEnd) -> Result<(Vec<FormatItem>, &str)> {
+    let mut items = Vec::new();
+    let mut literal = String::new();
+    loop {
+        let Some(c) = source.chars().next() else {
+            anyhow::bail!("unterminated transform format");
+        };
+        if end.matches(c) {
+            if end == FormatEnd::Slash {
+                source = &source[1..];
+            }
+            break;
+        }
+        match c {
+            '\\' => {
+                source = &source[1..];
+                match source.chars().next() {
+                    Some(escaped @ ('$' | '\\' | '/' | '}' | ':')) => {
+                        literal.push(escaped);
+                        source = &source[1..];
+                    }
+                    _ => literal.push('\\'),
+                }
+            }
+            '$' if end == FormatEnd::Slash => {
+                let (item, rest) = parse_format_group(&source[1..])?;
+                match item {
+                    Some(item) => {
+                        if !literal.is_empty() {
+                            items.push(FormatItem::Text(std::mem::take(&mut literal)));
+                        }
+                        items.push(item);
+                    }
+                    None => literal.push('$'),
+                }
+                source = rest;
+            }
+            c => {
+                literal.push(c);
+                source = &source[c.len_utf8()..];
+            }
+        }
+    }
+    if !literal.is_empty() {
+        items.push(FormatItem::Text(literal));
+    }
+    Ok((items, source))
+}
+
+/// Parses a format reference to a capture group, after the `$`. Returns `None` if the `$`
+/// doesn't start a reference and should be kept literally.
+fn parse_format_group(source: &str) -> Result<(Option<FormatItem>, &str)> {
+    if !source.starts_with('{') {
+        return Ok(match parse_int(source) {
+            Ok((group, rest)) => (Some(FormatItem::Group(group)), rest),
+            Err(_) => (None, source),
+        });
+    }
+
+    let (group, mut source) = parse_int(&source[1..])?;
+    let item = if let Some(rest) = source.strip_prefix(":/") {
+        let name_end = rest.find('}').context("expected a closing brace")?;
+        let modifier = CaseModifier::parse(&rest[..name_end])?;
+        source = &rest[name_end..];
+        FormatItem::CaseModifier(group, modifier)
+    } else if let Some(rest) = source.strip_prefix(":+") {
+        let (if_matched, rest) = parse_format_text(rest, FormatEnd::Brace)?;
+        source = rest;
+        FormatItem::Conditional {
+            group,
+            if_matched: Some(if_matched),
+            otherwise: String::new(),
+        }
+    } else if let Some(rest) = source.strip_prefix(":?") {
+        let (if_matched, rest) = parse_format_text(rest, FormatEnd::ColonOrBrace)?;
+        let rest = rest
+            .strip_prefix(':')
+            .context("expected `:` in conditional format")?;
+        let (otherwise, rest) = parse_format_text(rest, FormatEnd::Brace)?;
+        source = rest;
+        FormatItem::Conditional {
+            group,
+            if_matched: Some(if_matched),
+            otherwise,
+        }
+    } else if let Some(rest) = source
+        .strip_prefix(":-")
+        .or_else(|| source.strip_prefix(':'))
+    {
+        let (otherwise, rest) = parse_format_text(rest, FormatEnd::Brace)?;
+        source = rest;
+        FormatItem::Conditional {
+            group,
+            if_matched: None,
+            otherwise,
+        }
+    } else {
+        FormatItem::Group(group)
+    };
+
+    let source = source
+        .strip_prefix('}')
+        .context("expected a closing brace")?;
+    Ok((Some(item), source))
+}
+
+fn parse_format_text(source: &str, end: FormatEnd) -> Result<(String, &str)> {
+    let (items, rest) = parse_format(source, end)?;
+    let text = items
+        .into_iter()
+        .map(|item| match item {
+            FormatItem::Text(text) => text,
+            _ => String::new(),
+        })
+        .collect();
+    Ok((text, rest))
+}
+
 fn parse_int(source: &str) -> Result<(usize, &str)> {
     let len = source
         .find(|c: char| !c.is_ascii_digit())
@@ -324,6 +835,98 @@ mod tests {
         assert_eq!(tabstops(&snippet), &[vec![4..4], vec![7..7]]);
     }
 
+    #[test]
+    fn test_snippet_with_variables() {
+        let resolve_variable = |name: &str| match name {
+            "TM_FILENAME" => Some("main.rs".to_string()),
+            "TM_SELECTED_TEXT" => Some(String::new()),
+            _ => None,
+        };
+
+        let snippet =
+            Snippet::parse_with_variables("// $TM_FILENAME ${TM_FILENAME}$1", &resolve_variable)
+                .unwrap();
+        assert_eq!(snippet.text, "// main.rs main.rs");
+        assert_eq!(tabstops(&snippet), &[vec![18..18]]);
+
+        // Empty variables fall back to their default, or are removed.
+        let snippet = Snippet::parse_with_variables(
+            "${TM_SELECTED_TEXT:${1:value}}$TM_DIRECTORY",
+            &resolve_variable,
+        )
+        .unwrap();
+        assert_eq!(snippet.text, "value");
+        assert_eq!(tabstops(&snippet), &[vec![0..5], vec![5..5]]);
+
+        // Unknown variables become placeholders after the snippet's own tabstops, with one
+        // tabstop per variable name.
+        let snippet = Snippet::parse_with_variables(
+            "$UNKNOWN ${1:one} ${OTHER:default} ${UNKNOWN}$0",
+            &resolve_variable,
+        )
+        .unwrap();
+        assert_eq!(snippet.text, "UNKNOWN one default UNKNOWN");
+        assert_eq!(
+            tabstops(&snippet),
+            &[vec![8..11], vec![0..7, 20..27], vec![12..19], vec![27..27]]
+        );
+
+        // The default of a resolved variable is dropped, including its tabstops.
+        let snippet =
+            Snippet::parse_with_variables("${TM_FILENAME:${1:value}}", &resolve_variable).unwrap();
+        assert_eq!(snippet.text, "main.rs");
+        assert_eq!(tabstops(&snippet), &[vec![7..7]]);
+
+        let snippet =
+            Snippet::parse_with_variables("${TM_FILENAME/(.*)\\..+$/$1/}", &resolve_variable)
+                .unwrap();
+        assert_eq!(snippet.text, "main");
+    }
+
+    #[test]
+    fn test_snippet_with_transformed_mirrors() {
+        let snippet = Snippet::parse("${1:foo_bar} ${1/(.*)/${1:/upcase}/}").unwrap();
+        assert_eq!(snippet.text, "foo_bar FOO_BAR");
+        assert_eq!(tabstops(&snippet), &[vec![0..7], vec![15..15]]);
+        let (range, transform) = &snippet.tabstops[0].transformed_mirrors[0];
+        assert_eq!(*range, 8..15);
+        assert_eq!(transform.apply("baz"), "BAZ");
+
+        // Mirrors may precede the placeholder they mirror.
+        let snippet = Snippet::parse("${1/_/-/g}: ${1:a_b_c}").unwrap();
+        assert_eq!(snippet.text, "a-b-c: a_b_c");
+        assert_eq!(tabstops(&snippet), &[vec![7..12], vec![12..12]]);
+    }
+
+    #[test]
+    fn test_transform_format() {
+        let transform = |source: &str| parse_transform(source).unwrap().0;
+
+        assert_eq!(transform("o/0/}").apply("foo"), "f0o");
+        assert_eq!(transform("o/0/g}").apply("foo"), "f00");
+        assert_eq!(transform("O/0/gi}").apply("foo"), "f00");
+        assert_eq!(transform("x/y/}").apply("foo"), "foo");
+        assert_eq!(
+            transform("(\\w+)/${1:/capitalize}/g}").apply("foo bar"),
+            "Foo Bar"
+        );
+        assert_eq!(
+            transform(".*/${0:/pascalcase}/}").apply("foo_bar-baz"),
+            "FooBarBaz"
+        );
+        assert_eq!(
+            transform(".*/${0:/camelcase}/}").apply("foo_bar-baz"),
+            "fooBarBaz"
+        );
+        assert_eq!(transform("(a)?b/${1:+yes}/}").apply("ab"), "yes");
+        assert_eq!(transform("(a)?b/${1:+yes}/}").apply("b"), "");
+        assert_eq!(transform("(a)?b/${1:?yes:no}/}").apply("b"), "no");
+        assert_eq!(transform("(a)?b/${1:-none}/}").apply("ab"), "a");
+        assert_eq!(transform("(a)?b/${1:none}/}").apply("b"), "none");
+        assert_eq!(transform("b/\\$1\\/\\}/}").apply("b"), "$1/}");
+        assert!(parse_transform("b/${1:/shout}/}").is_err());
+    }
+
     fn tabstops(snippet: &Snippet) -> Vec<Vec<Range<isize>>> {
         snippet.tabstops.iter().map(|t| t.ranges.to_vec()).collect()
     }
@@ -0,0 +1,210 @@
+use std::{ops::Range, path::PathBuf, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use editor::{CodeActionProvider, ExcerptId};
+use fs::Fs;
+use gpui::{App, AppContext as _, Entity, Task, WeakEntity, Window};
+use language::{Anchor, Buffer, LanguageServerId};
+use project::{CodeAction, LspAction, Project, ProjectTransaction};
+use serde::{Deserialize, Serialize};
+use settings::update_settings_file;
+
+use crate::{DIAGNOSTIC_SOURCE, load_dictionaries, project_words_path};
+
+const SPELL_CHECK_CODE_ACTION_PROVIDER_ID: &str = "spell_check";
+
+/// The number of replacements offered for a misspelled word.
+const MAX_SUGGESTIONS: usize = 5;
+
+/// The fix applied by a spell check code action, stored in the action's data.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum SpellCheckFix {
+    Replace { replacement: String },
+    AddToProjectWords { word: String },
+    AddToUserWords { word: String },
+}
+
+/// Offers replacements for the misspelled words reported by the spell checker, and to add them
+/// to the project's or the user's words.
+pub(crate) struct SpellCheckCodeActionProvider {
+    project: WeakEntity<Project>,
+}
+
+impl SpellCheckCodeActionProvider {
+    pub(crate) fn new(project: WeakEntity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl CodeActionProvider for SpellCheckCodeActionProvider {
+    fn id(&self) -> Arc<str> {
+        SPELL_CHECK_CODE_ACTION_PROVIDER_ID.into()
+    }
+
+    fn code_actions(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<Anchor>,
+        _: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Vec<CodeAction>>> {
+        let Some(project) = self.project.upgrade() else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let misspellings = snapshot
+            .diagnostics_in_range::<_, usize>(range, false)
+            .filter(|entry| entry.diagnostic.source.as_deref() == Some(DIAGNOSTIC_SOURCE))
+            .map(|entry| {
+                let word = snapshot
+                    .text_for_range(entry.range.clone())
+                    .collect::<String>();
+                let range = snapshot.anchor_before(entry.range.start)
+                    ..snapshot.anchor_after(entry.range.end);
+                (word, range)
+            })
+            .collect::<Vec<_>>();
+        if misspellings.is_empty() {
+            return Task::ready(Ok(Vec::new()));
+        }
+        let has_project_words = project_words_path(buffer, cx).is_some();
+        let dictionaries = load_dictionaries(project.read(cx).fs().clone(), cx);
+
+        cx.background_spawn(async move {
+            let dictionaries = dictionaries.await;
+            let mut actions = Vec::new();
+            for (word, range) in misspellings {
+                let mut suggestions = Vec::<String>::new();
+                for dictionary in &dictionaries {
+                    for suggestion in dictionary.suggest(&word, MAX_SUGGESTIONS) {
+                        if suggestions.len() < MAX_SUGGESTIONS && !suggestions.contains(&suggestion)
+                        {
+                            suggestions.push(suggestion);
+                        }
+                    }
+                }
+
+                let mut fixes = suggestions
+                    .into_iter()
+                    .map(|replacement| {
+                        (
+                            format!("Change to \"{replacement}\""),
+                            SpellCheckFix::Replace { replacement },
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                if has_project_words {
+                    fixes.push((
+                        format!("Add \"{word}\" to project words"),
+                        SpellCheckFix::AddToProjectWords { word: word.clone() },
+                    ));
+                }
+                fixes.push((
+                    format!("Add \"{word}\" to user words"),
+                    SpellCheckFix::AddToUserWords { word },
+                ));
+
+                for (title, fix) in fixes {
+                    actions.push(CodeAction {
+                        server_id: LanguageServerId(0),
+                        range: range.clone(),
+                        lsp_action: LspAction::Action(Box::new(lsp::CodeAction {
+                            title,
+                            kind: Some(lsp::CodeActionKind::QUICKFIX),
+                            data: Some(serde_json::to_value(fix)?),
+                            ..lsp::CodeAction::default()
+                        })),
+                        resolved: true,
+                    });
+                }
+            }
+            Ok(actions)
+        })
+    }
+
+    fn apply_code_action(
+        &self,
+        buffer: Entity<Buffer>,
+        action: CodeAction,
+        _: ExcerptId,
+        push_to_history: bool,
+        _: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<ProjectTransaction>> {
+        let Some(project) = self.project.upgrade() else {
+            return Task::ready(Ok(ProjectTransaction::default()));
+        };
+        let LspAction::Action(lsp_action) = action.lsp_action else {
+            return Task::ready(Ok(ProjectTransaction::default()));
+        };
+        let fix = match lsp_action
+            .data
+            .context("missing spell check fix")
+            .and_then(|data| Ok(serde_json::from_value::<SpellCheckFix>(data)?))
+        {
+            Ok(fix) => fix,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let fs = project.read(cx).fs().clone();
+
+        match fix {
+            SpellCheckFix::Replace { replacement } => {
+                let transaction = buffer.update(cx, |buffer, cx| {
+                    buffer.finalize_last_transaction();
+                    buffer.start_transaction();
+                    buffer.edit([(action.range, replacement)], None, cx);
+                    let transaction_id = buffer.end_transaction(cx)?;
+                    let transaction = buffer.finalize_last_transaction()?.clone();
+                    if !push_to_history {
+                        buffer.forget_transaction(transaction_id);
+                    }
+                    Some(transaction)
+                });
+                let mut project_transaction = ProjectTransaction::default();
+                if let Some(transaction) = transaction {
+                    project_transaction.0.insert(buffer, transaction);
+                }
+                Task::ready(Ok(project_transaction))
+            }
+            SpellCheckFix::AddToProjectWords { word } => {
+                let Some(words_path) = project_words_path(&buffer, cx) else {
+                    return Task::ready(Ok(ProjectTransaction::default()));
+                };
+                cx.background_spawn(async move {
+                    add_project_word(fs, words_path, word).await?;
+                    Ok(ProjectTransaction::default())
+                })
+            }
+            SpellCheckFix::AddToUserWords { word } => {
+                update_settings_file(fs, cx, move |settings, _| {
+                    let words = settings
+                        .spell_check
+                        .get_or_insert_default()
+                        .words
+                        .get_or_insert_default();
+                    if !words.contains(&word) {
+                        words.push(word);
+                    }
+                });
+                Task::ready(Ok(ProjectTransaction::default()))
+            }
+        }
+    }
+}
+
+async fn add_project_word(fs: Arc<dyn Fs>, words_path: PathBuf, word: String) -> Result<()> {
+    let mut words = fs.load(&words_path).await.unwrap_or_default();
+    if words.lines().any(|line| line.trim() == word) {
+        return Ok(());
+    }
+    if !words.is_empty() && !words.ends_with('\n') {
+        words.push('\n');
+    }
+    words.push_str(&word);
+    words.push('\n');
+    if let Some(directory) = words_path.parent() {
+        fs.create_dir(directory).await?;
+    }
+    fs.atomic_write(words_path, words).await
+}
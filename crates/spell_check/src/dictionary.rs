@@ -0,0 +1,553 @@
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+
+/// A flag attached to the words of a dictionary, selecting the affixes that apply to them.
+type Flag = u32;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum FlagFormat {
+    /// Each character is a flag.
+    #[default]
+    Char,
+    /// Each pair of characters is a flag.
+    Long,
+    /// Flags are comma-separated numbers.
+    Numeric,
+}
+
+impl FlagFormat {
+    fn parse(self, flags: &str) -> Vec<Flag> {
+        match self {
+            FlagFormat::Char => flags.chars().map(Flag::from).collect(),
+            FlagFormat::Long => {
+                let characters = flags.chars().collect::<Vec<_>>();
+                characters
+                    .chunks(2)
+                    .map(|pair| {
+                        pair.iter()
+                            .fold(0, |flag, character| flag << 16 | Flag::from(*character))
+                    })
+                    .collect()
+            }
+            FlagFormat::Numeric => flags
+                .split(',')
+                .filter_map(|flag| flag.trim().parse().ok())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum CharacterClass {
+    Any,
+    Character(char),
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+impl CharacterClass {
+    fn matches(&self, character: char) -> bool {
+        match self {
+            CharacterClass::Any => true,
+            CharacterClass::Character(expected) => *expected == character,
+            CharacterClass::OneOf(characters) => characters.contains(&character),
+            CharacterClass::NoneOf(characters) => !characters.contains(&character),
+        }
+    }
+}
+
+/// The condition a stem must satisfy for an affix to apply, such as `[^aeiou]y`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Condition(Vec<CharacterClass>);
+
+impl Condition {
+    fn parse(condition: &str) -> Self {
+        if condition == "." {
+            return Self::default();
+        }
+        let mut classes = Vec::new();
+        let mut characters = condition.chars();
+        while let Some(character) = characters.next() {
+            let class = match character {
+                '.' => CharacterClass::Any,
+                '[' => {
+                    let mut members = Vec::new();
+                    let mut negated = false;
+                    for member in characters.by_ref() {
+                        match member {
+                            ']' => break,
+                            '^' if members.is_empty() && !negated => negated = true,
+                            member => members.push(member),
+                        }
+                    }
+                    if negated {
+                        CharacterClass::NoneOf(members)
+                    } else {
+                        CharacterClass::OneOf(members)
+                    }
+                }
+                character => CharacterClass::Character(character),
+            };
+            classes.push(class);
+        }
+        Self(classes)
+    }
+
+    fn matches_start(&self, stem: &str) -> bool {
+        let mut characters = stem.chars();
+        self.0.iter().all(|class| {
+            characters
+                .next()
+                .is_some_and(|character| class.matches(character))
+        })
+    }
+
+    fn matches_end(&self, stem: &str) -> bool {
+        let mut characters = stem.chars().rev();
+        self.0.iter().rev().all(|class| {
+            characters
+                .next()
+                .is_some_and(|character| class.matches(character))
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum AffixKind {
+    Prefix,
+    Suffix,
+}
+
+#[derive(Clone, Debug)]
+struct Affix {
+    flag: Flag,
+    cross_product: bool,
+    strip: String,
+    condition: Condition,
+}
+
+/// A spelling dictionary in the Hunspell format, made of an affix file (`.aff`) describing how
+/// words can be inflected, and a dictionary file (`.dic`) listing the words.
+///
+/// Prefixes and suffixes are supported, but compound words are not.
+#[derive(Debug, Default)]
+pub struct Dictionary {
+    words: HashMap<String, Vec<Vec<Flag>>>,
+    prefixes: HashMap<String, Vec<Affix>>,
+    suffixes: HashMap<String, Vec<Affix>>,
+    forbidden_flag: Option<Flag>,
+    need_affix_flag: Option<Flag>,
+    no_suggest_flag: Option<Flag>,
+    try_characters: Vec<char>,
+    replacements: Vec<(String, String)>,
+}
+
+impl Dictionary {
+    /// Parses a dictionary from the content of its affix and dictionary files, decoded with the
+    /// encoding declared by the affix file.
+    pub fn new(affix_file: &[u8], dictionary_file: &[u8]) -> Result<Self> {
+        let affix_file = decode(affix_file, declared_encoding(affix_file))?;
+        let mut dictionary = Self::default();
+        let mut flag_format = FlagFormat::default();
+        let mut flag_aliases = Vec::new();
+        let mut has_flag_alias_count = false;
+        let mut cross_products = HashMap::default();
+
+        for line in affix_file.lines() {
+            let mut fields = line.split_whitespace();
+            let Some(keyword) = fields.next() else {
+                continue;
+            };
+            let fields = fields.collect::<Vec<_>>();
+            match (keyword, fields.as_slice()) {
+                ("FLAG", [format, ..]) => {
+                    flag_format = match *format {
+                        "long" => FlagFormat::Long,
+                        "num" => FlagFormat::Numeric,
+                        _ => FlagFormat::Char,
+                    }
+                }
+                // The first line of a table holds its number of entries.
+                ("AF", [_]) if !has_flag_alias_count => has_flag_alias_count = true,
+                ("AF", [flags, ..]) => flag_aliases.push(flag_format.parse(flags)),
+                ("TRY", [characters, ..]) => {
+                    dictionary.try_characters = characters.chars().collect()
+                }
+                ("FORBIDDENWORD", [flag, ..]) => {
+                    dictionary.forbidden_flag = flag_format.parse(flag).first().copied()
+                }
+                ("NEEDAFFIX", [flag, ..]) => {
+                    dictionary.need_affix_flag = flag_format.parse(flag).first().copied()
+                }
+                ("NOSUGGEST", [flag, ..]) => {
+                    dictionary.no_suggest_flag = flag_format.parse(flag).first().copied()
+                }
+                ("REP", [from, to, ..]) => dictionary
+                    .replacements
+                    .push((from.replace('_', " "), to.replace('_', " "))),
+                ("PFX" | "SFX", [flag, cross_product, count]) if count.parse::<usize>().is_ok() => {
+                    let kind = affix_kind(keyword);
+                    let flag = flag_format.parse(flag).first().copied().unwrap_or_default();
+                    cross_products.insert((kind, flag), *cross_product == "Y");
+                }
+                ("PFX" | "SFX", [flag, strip, add, rest @ ..]) => {
+                    let kind = affix_kind(keyword);
+                    let flag = flag_format.parse(flag).first().copied().unwrap_or_default();
+                    let strip = if *strip == "0" { "" } else { strip };
+                    // Continuation flags on the added text are not supported.
+                    let add = add.split('/').next().unwrap_or_default();
+                    let add = if add == "0" { "" } else { add };
+                    let affixes = match kind {
+                        AffixKind::Prefix => &mut dictionary.prefixes,
+                        AffixKind::Suffix => &mut dictionary.suffixes,
+                    };
+                    affixes.entry(add.to_string()).or_default().push(Affix {
+                        flag,
+                        cross_product: cross_products
+                            .get(&(kind, flag))
+                            .copied()
+                            .unwrap_or_default(),
+                        strip: strip.to_string(),
+                        condition: Condition::parse(rest.first().copied().unwrap_or(".")),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let dictionary_file = decode(dictionary_file, declared_encoding(affix_file.as_bytes()))?;
+        let mut lines = dictionary_file.lines();
+        // The first line holds the approximate number of words.
+        lines.next().context("empty dictionary file")?;
+        for line in lines {
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (word, flags) = match entry.split_once('/') {
+                Some((word, flags)) => {
+                    let flags = if flag_aliases.is_empty() {
+                        flag_format.parse(flags)
+                    } else {
+                        flags
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|alias| flag_aliases.get(alias.checked_sub(1)?))
+                            .cloned()
+                            .unwrap_or_default()
+                    };
+                    (word, flags)
+                }
+                None => (entry, Vec::new()),
+            };
+            dictionary
+                .words
+                .entry(word.to_string())
+                .or_default()
+                .push(flags);
+        }
+
+        Ok(dictionary)
+    }
+
+    /// Returns whether the word is spelled correctly. Capitalized words are also accepted when
+    /// their lowercase form is in the dictionary, as at the start of a sentence.
+    pub fn check(&self, word: &str) -> bool {
+        let word = word.replace('’', "'");
+        if self.check_exact(&word) {
+            return true;
+        }
+        let is_capitalized = word.chars().next().is_some_and(char::is_uppercase)
+            && !word.chars().skip(1).any(char::is_uppercase);
+        is_capitalized && self.check_exact(&lowercase_first(&word))
+    }
+
+    /// Returns up to `limit` correctly spelled words close to the given word, best first.
+    pub fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        let characters = word.chars().collect::<Vec<_>>();
+        let try_characters = if self.try_characters.is_empty() {
+            ('a'..='z').collect()
+        } else {
+            self.try_characters.clone()
+        };
+
+        let mut candidates = Vec::new();
+        for (from, to) in &self.replacements {
+            for (index, _) in word.match_indices(from.as_str()) {
+                let mut candidate = word.to_string();
+                candidate.replace_range(index..index + from.len(), to);
+                candidates.push(candidate);
+            }
+        }
+        for index in 0..characters.len().saturating_sub(1) {
+            let mut swapped = characters.clone();
+            swapped.swap(index, index + 1);
+            candidates.push(swapped.into_iter().collect());
+        }
+        for index in 0..characters.len() {
+            let mut removed = characters.clone();
+            removed.remove(index);
+            candidates.push(removed.into_iter().collect());
+        }
+        for index in 0..characters.len() {
+            for character in &try_characters {
+                let mut replaced = characters.clone();
+                replaced[index] = *character;
+                candidates.push(replaced.into_iter().collect());
+            }
+        }
+        for index in 0..=characters.len() {
+            for character in &try_characters {
+                let mut inserted = characters.clone();
+                inserted.insert(index, *character);
+                candidates.push(inserted.into_iter().collect());
+            }
+        }
+        for index in 1..characters.len() {
+            let first = characters[..index].iter().collect::<String>();
+            let second = characters[index..].iter().collect::<String>();
+            if self.check(&first) && self.check(&second) {
+                candidates.push(format!("{first} {second}"));
+            }
+        }
+
+        let mut seen = HashSet::default();
+        let mut suggestions = Vec::new();
+        for candidate in candidates {
+            if suggestions.len() == limit {
+                break;
+            }
+            let candidate = if capitalized {
+                capitalize(&candidate)
+            } else {
+                candidate
+            };
+            if candidate == word || !seen.insert(candidate.clone()) {
+                continue;
+            }
+            let is_suggestible = candidate
+                .split(' ')
+                .all(|part| self.check(part) && !self.is_not_suggested(part));
+            if is_suggestible {
+                suggestions.push(candidate);
+            }
+        }
+        suggestions
+    }
+
+    fn check_exact(&self, word: &str) -> bool {
+        if let Some(homonyms) = self.words.get(word) {
+            if self.has_flag(homonyms, self.forbidden_flag) {
+                return false;
+            }
+            let need_affix = self.need_affix_flag;
+            if homonyms
+                .iter()
+                .any(|flags| need_affix.is_none_or(|flag| !flags.contains(&flag)))
+            {
+                return true;
+            }
+        }
+
+        for (stem, suffix) in self.strip_affixes(AffixKind::Suffix, word) {
+            if self.has_stem_with_flags(&stem, &[suffix.flag]) {
+                return true;
+            }
+            if suffix.cross_product {
+                for (stem, prefix) in self.strip_affixes(AffixKind::Prefix, &stem) {
+                    if prefix.cross_product
+                        && self.has_stem_with_flags(&stem, &[prefix.flag, suffix.flag])
+                    {
+                        return true;
+                    }
+                }
+            }
+        }
+        self.strip_affixes(AffixKind::Prefix, word)
+            .any(|(stem, prefix)| self.has_stem_with_flags(&stem, &[prefix.flag]))
+    }
+
+    /// Returns the stems the word can be derived from with an affix of the given kind, along
+    /// with the affix.
+    fn strip_affixes<'a>(
+        &'a self,
+        kind: AffixKind,
+        word: &'a str,
+    ) -> impl Iterator<Item = (String, &'a Affix)> + 'a {
+        let affixes = match kind {
+            AffixKind::Prefix => &self.prefixes,
+            AffixKind::Suffix => &self.suffixes,
+        };
+        word.char_indices()
+            .map(|(index, _)| index)
+            .chain([word.len()])
+            .flat_map(move |index| {
+                let (added, rest) = match kind {
+                    AffixKind::Prefix => (&word[..index], &word[index..]),
+                    AffixKind::Suffix => (&word[index..], &word[..index]),
+                };
+                affixes
+                    .get(added)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(move |affix| {
+                        let (stem, condition_matches) = match kind {
+                            AffixKind::Prefix => {
+                                let stem = format!("{}{rest}", affix.strip);
+                                let matches = affix.condition.matches_start(&stem);
+                                (stem, matches)
+                            }
+                            AffixKind::Suffix => {
+                                let stem = format!("{rest}{}", affix.strip);
+                                let matches = affix.condition.matches_end(&stem);
+                                (stem, matches)
+                            }
+                        };
+                        (!stem.is_empty() && condition_matches).then_some((stem, affix))
+                    })
+            })
+    }
+
+    fn has_stem_with_flags(&self, stem: &str, required_flags: &[Flag]) -> bool {
+        self.words.get(stem).is_some_and(|homonyms| {
+            homonyms.iter().any(|flags| {
+                required_flags.iter().all(|flag| flags.contains(flag))
+                    && self
+                        .forbidden_flag
+                        .is_none_or(|flag| !flags.contains(&flag))
+            })
+        })
+    }
+
+    fn has_flag(&self, homonyms: &[Vec<Flag>], flag: Option<Flag>) -> bool {
+        flag.is_some_and(|flag| homonyms.iter().any(|flags| flags.contains(&flag)))
+    }
+
+    fn is_not_suggested(&self, word: &str) -> bool {
+        self.words
+            .get(word)
+            .is_some_and(|homonyms| self.has_flag(homonyms, self.no_suggest_flag))
+    }
+}
+
+fn affix_kind(keyword: &str) -> AffixKind {
+    if keyword == "PFX" {
+        AffixKind::Prefix
+    } else {
+        AffixKind::Suffix
+    }
+}
+
+fn declared_encoding(affix_file: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(affix_file)
+        .lines()
+        .find_map(|line| {
+            let encoding = line.trim().strip_prefix("SET")?;
+            Some(encoding.trim().to_uppercase())
+        })
+}
+
+fn decode(content: &[u8], encoding: Option<String>) -> Result<String> {
+    match encoding.as_deref() {
+        // Latin-1 maps every byte to the code point of the same value.
+        Some("ISO8859-1" | "ISO-8859-1") => Ok(content.iter().map(|byte| *byte as char).collect()),
+        _ => String::from_utf8(content.to_vec()).context("dictionary is not valid UTF-8"),
+    }
+}
+
+fn lowercase_first(word: &str) -> String {
+    let mut characters = word.chars();
+    match characters.next() {
+        Some(first) => first.to_lowercase().chain(characters).collect(),
+        None => String::new(),
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut characters = word.chars();
+    match characters.next() {
+        Some(first) => first.to_uppercase().chain(characters).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFFIX_FILE: &str = "SET UTF-8
+TRY esianrtolcdugmphbyfvkwz
+
+NOSUGGEST !
+
+REP 1
+REP f ph
+
+PFX U Y 1
+PFX U   0     un         .
+
+SFX S Y 4
+SFX S   y     ies        [^aeiou]y
+SFX S   0     s          [aeiou]y
+SFX S   0     es         [sxzh]
+SFX S   0     s          [^sxzhy]
+
+SFX D N 2
+SFX D   0     d          e
+SFX D   0     ed         [^ey]
+";
+
+    const DICTIONARY_FILE: &str = "6
+do/U
+fly/S
+box/S
+phone/SDU
+Paris
+damn/!
+";
+
+    fn dictionary() -> Dictionary {
+        Dictionary::new(AFFIX_FILE.as_bytes(), DICTIONARY_FILE.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_check() {
+        let dictionary = dictionary();
+        for word in [
+            "do", "undo", "fly", "flies", "boxes", "phones", "phoned", "unphone", "unphones",
+            "Paris", "Flies",
+        ] {
+            assert!(dictionary.check(word), "{word} should be spelled correctly");
+        }
+        for word in [
+            "flys", "boxs", "phoneed", "unfly", "paris", "undos", "FLIES", "fli", "", "unphoned",
+        ] {
+            assert!(!dictionary.check(word), "{word} should be misspelled");
+        }
+    }
+
+    #[test]
+    fn test_suggest() {
+        let dictionary = dictionary();
+        assert_eq!(dictionary.suggest("fone", 5), vec!["phone"]);
+        assert_eq!(dictionary.suggest("flise", 5), vec!["flies"]);
+        assert_eq!(dictionary.suggest("Boxse", 5), vec!["Boxes"]);
+        assert_eq!(dictionary.suggest("dofly", 5), vec!["do fly"]);
+        assert_eq!(dictionary.suggest("dam", 5), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_flag_formats() {
+        let affix_file = "FLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\n";
+        let dictionary = Dictionary::new(affix_file.as_bytes(), b"1\ncat/AaBb\n").unwrap();
+        assert!(dictionary.check("cats"));
+
+        let affix_file = "FLAG num\nAF 1\nAF 7,12\nSFX 12 Y 1\nSFX 12 0 s .\n";
+        let dictionary = Dictionary::new(affix_file.as_bytes(), b"1\ncat/1\n").unwrap();
+        assert!(dictionary.check("cats"));
+
+        let affix_file = b"SET ISO8859-1\nSFX A Y 1\nSFX A 0 s .\n";
+        let dictionary = Dictionary::new(affix_file, b"1\ncaf\xe9/A\n").unwrap();
+        assert!(dictionary.check("cafés"));
+    }
+}
@@ -0,0 +1,208 @@
+use std::{ops::Range, sync::Arc};
+
+use collections::HashSet;
+use language::{BufferSnapshot, Language};
+
+use crate::{Dictionary, words};
+
+/// Languages whose documents are checked in full, rather than only in their comments and
+/// strings.
+const PROSE_LANGUAGES: &[&str] = &["Markdown", "Markdown-Inline", "Git Commit", "Plain Text"];
+
+/// Highlight captures within prose that aren't checked, such as code spans and links.
+const UNCHECKED_PROSE_CAPTURES: &[&str] = &[
+    "constant",
+    "link_uri",
+    "markup.link.url",
+    "punctuation.embedded",
+    "string.special",
+    "text.literal",
+];
+
+/// Highlight captures within code that are checked.
+const CHECKED_CODE_CAPTURES: &[&str] = &["comment", "string"];
+
+/// Highlight captures within code that aren't checked, despite being strings.
+const UNCHECKED_CODE_CAPTURES: &[&str] = &["string.escape", "string.regex", "string.special"];
+
+/// Returns the ranges of the misspelled words in the buffer: in comments and strings for code,
+/// and everywhere but in code spans and links for prose. Words known to any of the dictionaries
+/// or listed in `known_words`, which must be lowercase, are accepted.
+pub fn misspellings(
+    snapshot: &BufferSnapshot,
+    dictionaries: &[Arc<Dictionary>],
+    known_words: &HashSet<String>,
+) -> Vec<Range<usize>> {
+    let mut misspellings = Vec::new();
+    for range in ranges_to_check(snapshot) {
+        let text = snapshot.text_for_range(range.clone()).collect::<String>();
+        for word in words(&text) {
+            let word_text = &text[word.clone()];
+            let is_known = known_words.contains(&word_text.to_lowercase())
+                || dictionaries
+                    .iter()
+                    .any(|dictionary| dictionary.check(word_text));
+            if !is_known {
+                misspellings.push(range.start + word.start..range.start + word.end);
+            }
+        }
+    }
+    misspellings
+}
+
+fn is_prose(language: &Language) -> bool {
+    PROSE_LANGUAGES.contains(&language.name().as_ref())
+}
+
+/// Returns whether the capture name is one of the given names, or a more specific form of one,
+/// such as `comment.doc` for `comment`.
+fn capture_matches(name: &str, names: &[&str]) -> bool {
+    names.iter().any(|prefix| {
+        name.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    })
+}
+
+fn ranges_to_check(snapshot: &BufferSnapshot) -> Vec<Range<usize>> {
+    let mut prose_ranges = Vec::new();
+    let mut unchecked_ranges = Vec::new();
+    let mut code_ranges = Vec::new();
+    let is_prose_buffer = snapshot
+        .language()
+        .is_none_or(|language| is_prose(language));
+    if is_prose_buffer {
+        prose_ranges.push(0..snapshot.len());
+        for layer in snapshot.syntax_layers() {
+            if !is_prose(layer.language) {
+                unchecked_ranges.push(layer.node().byte_range());
+            }
+        }
+    }
+
+    let captures = snapshot.captures(0..snapshot.len(), |grammar| {
+        grammar
+            .highlights_config
+            .as_ref()
+            .map(|config| &config.query)
+    });
+    let grammars = captures.grammars().to_vec();
+    for capture in captures {
+        let Some(name) = grammars[capture.grammar_index]
+            .highlights_config
+            .as_ref()
+            .and_then(|config| config.query.capture_names().get(capture.index as usize))
+        else {
+            continue;
+        };
+        if capture_matches(name, CHECKED_CODE_CAPTURES)
+            && !capture_matches(name, UNCHECKED_CODE_CAPTURES)
+        {
+            code_ranges.push(capture.node.byte_range());
+        } else if is_prose_buffer && capture_matches(name, UNCHECKED_PROSE_CAPTURES) {
+            unchecked_ranges.push(capture.node.byte_range());
+        }
+    }
+
+    let mut ranges = subtract_ranges(prose_ranges, unchecked_ranges);
+    ranges.extend(code_ranges);
+    merge_ranges(ranges)
+}
+
+/// Sorts the ranges, merging those that overlap.
+fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_unstable_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+fn subtract_ranges(ranges: Vec<Range<usize>>, removed: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    let removed = merge_ranges(removed);
+    let mut result = Vec::new();
+    for range in merge_ranges(ranges) {
+        let mut start = range.start;
+        for removed in &removed {
+            if removed.end <= start || removed.start >= range.end {
+                continue;
+            }
+            if removed.start > start {
+                result.push(start..removed.start);
+            }
+            start = removed.end;
+        }
+        if start < range.end {
+            result.push(start..range.end);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use language::{Buffer, rust_lang};
+
+    fn misspelled_words(
+        snapshot: &BufferSnapshot,
+        dictionary: &Arc<Dictionary>,
+        known_words: &[&str],
+    ) -> Vec<String> {
+        let known_words = known_words.iter().map(|word| word.to_string()).collect();
+        misspellings(snapshot, &[dictionary.clone()], &known_words)
+            .into_iter()
+            .map(|range| snapshot.text_for_range(range).collect())
+            .collect()
+    }
+
+    #[gpui::test]
+    async fn test_misspellings(cx: &mut TestAppContext) {
+        let dictionary = Arc::new(
+            Dictionary::new(
+                b"SFX S Y 1\nSFX S 0 s .\n",
+                b"6\nreturn/S\nnumber/S\nitem/S\nparse\nword/S\ntext\n",
+            )
+            .unwrap(),
+        );
+
+        let code = r#"
+            // Returns the numbr of items.
+            fn parse_itmes() -> &'static str {
+                "parse numbers and wrods"
+            }
+        "#;
+        let buffer = cx.new(|cx| Buffer::local(code, cx).with_language(rust_lang(), cx));
+        cx.run_until_parked();
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        assert_eq!(
+            misspelled_words(&snapshot, &dictionary, &[]),
+            ["numbr", "wrods"]
+        );
+        assert_eq!(
+            misspelled_words(&snapshot, &dictionary, &["wrods"]),
+            ["numbr"]
+        );
+
+        let prose = "Plain text wrods are checked, parse_itmes too.";
+        let buffer = cx.new(|cx| Buffer::local(prose, cx));
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        assert_eq!(
+            misspelled_words(&snapshot, &dictionary, &["plain", "checked"]),
+            ["wrods", "itmes"]
+        );
+    }
+
+    #[test]
+    fn test_subtract_ranges() {
+        assert_eq!(
+            subtract_ranges(vec![0..20], vec![15..18, 2..5, 4..8]),
+            vec![0..2, 8..15, 18..20]
+        );
+        assert_eq!(subtract_ranges(vec![0..10], vec![0..10]), vec![]);
+    }
+}
@@ -0,0 +1,359 @@
+mod code_actions;
+mod dictionary;
+mod misspellings;
+mod spell_check_settings;
+mod words;
+
+use std::{borrow::Cow, ops::Range, path::PathBuf, rc::Rc, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use collections::{HashMap, HashSet};
+use editor::Editor;
+use fs::Fs;
+use futures::{
+    FutureExt as _,
+    future::{Shared, join_all},
+};
+use gpui::{App, AppContext as _, Context, Entity, Global, Subscription, Task, WeakEntity};
+use language::{
+    Buffer, BufferEvent, BufferId, BufferSnapshot, DiagnosticSourceKind, LanguageServerId,
+    ToPointUtf16 as _, point_to_lsp,
+};
+use project::{Project, buffer_store::BufferStoreEvent, lsp_store::DocumentDiagnosticsUpdate};
+use settings::{Settings as _, SettingsStore};
+use util::ResultExt as _;
+use workspace::Workspace;
+
+use crate::code_actions::SpellCheckCodeActionProvider;
+
+pub use dictionary::Dictionary;
+pub use misspellings::misspellings;
+pub use spell_check_settings::SpellCheckSettings;
+pub use words::words;
+
+/// The source of the diagnostics reporting misspelled words.
+pub const DIAGNOSTIC_SOURCE: &str = "spell-check";
+
+const CHECK_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Buffers larger than this are not checked.
+const MAX_CHECKED_BUFFER_LEN: usize = 4 * 1024 * 1024;
+
+pub fn init(cx: &mut App) {
+    cx.set_global(LoadedDictionaries::default());
+
+    cx.observe_new(|workspace: &mut Workspace, _, cx| {
+        let project = workspace.project().clone();
+        if !project.read(cx).is_local() {
+            return;
+        }
+        let spell_checker = cx.new(|cx| ProjectSpellChecker::new(&project, cx));
+        let buffer_store = project.read(cx).buffer_store().clone();
+        cx.subscribe(&buffer_store, move |_, _, event, cx| {
+            spell_checker.update(cx, |spell_checker, cx| {
+                spell_checker.handle_buffer_store_event(event, cx)
+            });
+        })
+        .detach();
+    })
+    .detach();
+
+    cx.observe_new(|editor: &mut Editor, window, cx| {
+        let (Some(window), Some(project)) = (window, editor.project()) else {
+            return;
+        };
+        let provider = SpellCheckCodeActionProvider::new(project.downgrade());
+        editor.add_code_action_provider(Rc::new(provider), window, cx);
+    })
+    .detach();
+}
+
+#[derive(Clone)]
+enum LoadedDictionary {
+    Loaded(Arc<Dictionary>),
+    /// The dictionary files don't exist, but may be added later.
+    Missing,
+    Invalid,
+}
+
+#[derive(Default)]
+struct LoadedDictionaries(HashMap<String, Shared<Task<LoadedDictionary>>>);
+
+impl Global for LoadedDictionaries {}
+
+/// Loads the dictionaries enabled in the settings, skipping those that can't be loaded.
+pub(crate) fn load_dictionaries(
+    fs: Arc<dyn Fs>,
+    cx: &mut App,
+) -> impl Future<Output = Vec<Arc<Dictionary>>> + use<> {
+    let names = SpellCheckSettings::get_global(cx).dictionaries.clone();
+    let mut tasks = Vec::new();
+    for name in names {
+        let loaded_dictionaries = &mut cx.global_mut::<LoadedDictionaries>().0;
+        let needs_loading = loaded_dictionaries
+            .get(&name)
+            .is_none_or(|task| matches!(task.peek(), Some(LoadedDictionary::Missing)));
+        if needs_loading {
+            let task = cx
+                .background_spawn(load_dictionary(fs.clone(), name.clone()))
+                .shared();
+            cx.global_mut::<LoadedDictionaries>()
+                .0
+                .insert(name.clone(), task);
+        }
+        tasks.extend(cx.global::<LoadedDictionaries>().0.get(&name).cloned());
+    }
+
+    async move {
+        join_all(tasks)
+            .await
+            .into_iter()
+            .filter_map(|dictionary| match dictionary {
+                LoadedDictionary::Loaded(dictionary) => Some(dictionary),
+                LoadedDictionary::Missing | LoadedDictionary::Invalid => None,
+            })
+            .collect()
+    }
+}
+
+async fn load_dictionary(fs: Arc<dyn Fs>, name: String) -> LoadedDictionary {
+    let directory = paths::dictionaries_dir();
+    let affix_path = directory.join(format!("{name}.aff"));
+    let dictionary_path = directory.join(format!("{name}.dic"));
+    if !fs.is_file(&affix_path).await || !fs.is_file(&dictionary_path).await {
+        return LoadedDictionary::Missing;
+    }
+
+    let dictionary = async {
+        let affix_file = fs.load_bytes(&affix_path).await?;
+        let dictionary_file = fs.load_bytes(&dictionary_path).await?;
+        Dictionary::new(&affix_file, &dictionary_file)
+    }
+    .await
+    .with_context(|| format!("loading the {name} dictionary"));
+    match dictionary.log_err() {
+        Some(dictionary) => LoadedDictionary::Loaded(Arc::new(dictionary)),
+        None => LoadedDictionary::Invalid,
+    }
+}
+
+/// Returns the path of the file listing the words accepted in the buffer's project.
+pub(crate) fn project_words_path(buffer: &Entity<Buffer>, cx: &App) -> Option<PathBuf> {
+    project::File::from_dyn(buffer.read(cx).file()).map(|file| {
+        file.worktree
+            .read(cx)
+            .absolutize(paths::local_spell_check_words_file_relative_path())
+    })
+}
+
+/// Reports the misspelled words of a project's open buffers as diagnostics, keeping them up to
+/// date as the buffers change.
+struct ProjectSpellChecker {
+    project: WeakEntity<Project>,
+    /// Identifies the diagnostics of the spell checker, as if it were a language server.
+    server_id: LanguageServerId,
+    settings: SpellCheckSettings,
+    pending_checks: HashMap<BufferId, Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ProjectSpellChecker {
+    fn new(project: &Entity<Project>, cx: &mut Context<Self>) -> Self {
+        let subscriptions = vec![
+            cx.subscribe(project, |this, _, event, cx| {
+                if let project::Event::WorktreeUpdatedEntries(_, changes) = event
+                    && changes.iter().any(|(path, _, _)| {
+                        path.as_ref() == paths::local_spell_check_words_file_relative_path()
+                    })
+                {
+                    this.check_all_buffers(cx);
+                }
+            }),
+            cx.observe_global::<SettingsStore>(|this, cx| {
+                let settings = SpellCheckSettings::get_global(cx);
+                if *settings != this.settings {
+                    this.settings = settings.clone();
+                    this.check_all_buffers(cx);
+                }
+            }),
+        ];
+
+        let mut this = Self {
+            project: project.downgrade(),
+            server_id: project.read(cx).languages().next_language_server_id(),
+            settings: SpellCheckSettings::get_global(cx).clone(),
+            pending_checks: HashMap::default(),
+            _subscriptions: subscriptions,
+        };
+        let buffers = project
+            .read(cx)
+            .buffer_store()
+            .read(cx)
+            .buffers()
+            .collect::<Vec<_>>();
+        for buffer in buffers {
+            this.watch_buffer(&buffer, cx);
+        }
+        this
+    }
+
+    fn handle_buffer_store_event(&mut self, event: &BufferStoreEvent, cx: &mut Context<Self>) {
+        match event {
+            BufferStoreEvent::BufferAdded(buffer) => self.watch_buffer(buffer, cx),
+            BufferStoreEvent::BufferDropped(buffer_id) => {
+                self.pending_checks.remove(buffer_id);
+            }
+            _ => {}
+        }
+    }
+
+    fn watch_buffer(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        cx.subscribe(buffer, |this, buffer, event, cx| match event {
+            BufferEvent::Edited
+            | BufferEvent::Reparsed
+            | BufferEvent::LanguageChanged
+            | BufferEvent::FileHandleChanged => this.check_buffer(buffer, true, cx),
+            _ => {}
+        })
+        .detach();
+        self.check_buffer(buffer.clone(), true, cx);
+    }
+
+    fn check_all_buffers(&mut self, cx: &mut Context<Self>) {
+        let Some(project) = self.project.upgrade() else {
+            return;
+        };
+        let buffers = project
+            .read(cx)
+            .buffer_store()
+            .read(cx)
+            .buffers()
+            .collect::<Vec<_>>();
+        for buffer in buffers {
+            self.check_buffer(buffer, false, cx);
+        }
+    }
+
+    fn check_buffer(&mut self, buffer: Entity<Buffer>, debounce: bool, cx: &mut Context<Self>) {
+        let Some(project) = self.project.upgrade() else {
+            return;
+        };
+        let Some(words_path) = project_words_path(&buffer, cx) else {
+            return;
+        };
+        let fs = project.read(cx).fs().clone();
+        let settings = self.settings.clone();
+        let buffer_id = buffer.read(cx).remote_id();
+        let check = cx.spawn(async move |this, cx| {
+            if debounce {
+                cx.background_executor().timer(CHECK_DEBOUNCE).await;
+            }
+            let Ok((snapshot, dictionaries)) = cx.update(|cx| {
+                (
+                    buffer.read(cx).snapshot(),
+                    load_dictionaries(fs.clone(), cx),
+                )
+            }) else {
+                return;
+            };
+
+            let mut misspellings = Vec::new();
+            if settings.enabled && snapshot.len() <= MAX_CHECKED_BUFFER_LEN {
+                let dictionaries = dictionaries.await;
+                if !dictionaries.is_empty() {
+                    let mut known_words = settings
+                        .words
+                        .iter()
+                        .map(|word| word.to_lowercase())
+                        .collect::<HashSet<_>>();
+                    if let Ok(project_words) = fs.load(&words_path).await {
+                        known_words.extend(
+                            project_words
+                                .lines()
+                                .map(|word| word.trim().to_lowercase())
+                                .filter(|word| !word.is_empty()),
+                        );
+                    }
+                    misspellings = cx
+                        .background_spawn({
+                            let snapshot = snapshot.clone();
+                            async move {
+                                misspellings::misspellings(&snapshot, &dictionaries, &known_words)
+                            }
+                        })
+                        .await;
+                }
+            }
+
+            this.update(cx, |this, cx| {
+                this.publish_diagnostics(&buffer, &snapshot, misspellings, cx)
+            })
+            .ok();
+        });
+        self.pending_checks.insert(buffer_id, check);
+    }
+
+    fn publish_diagnostics(
+        &self,
+        buffer: &Entity<Buffer>,
+        snapshot: &BufferSnapshot,
+        misspellings: Vec<Range<usize>>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(project) = self.project.upgrade() else {
+            return;
+        };
+        // The buffer changed since it was checked, and will be checked again.
+        if buffer.read(cx).version() != *snapshot.version() {
+            return;
+        }
+        let Some(abs_path) = project::File::from_dyn(buffer.read(cx).file())
+            .map(|file| file.worktree.read(cx).absolutize(&file.path))
+        else {
+            return;
+        };
+        let Some(uri) = lsp::Uri::from_file_path(&abs_path).ok() else {
+            return;
+        };
+
+        let diagnostics = misspellings
+            .into_iter()
+            .map(|range| {
+                let word = snapshot.text_for_range(range.clone()).collect::<String>();
+                lsp::Diagnostic {
+                    range: lsp::Range {
+                        start: point_to_lsp(range.start.to_point_utf16(snapshot)),
+                        end: point_to_lsp(range.end.to_point_utf16(snapshot)),
+                    },
+                    severity: Some(lsp::DiagnosticSeverity::INFORMATION),
+                    source: Some(DIAGNOSTIC_SOURCE.to_string()),
+                    message: format!("Unknown word \"{word}\""),
+                    ..lsp::Diagnostic::default()
+                }
+            })
+            .collect();
+        let server_id = self.server_id;
+        project
+            .read(cx)
+            .lsp_store()
+            .update(cx, |lsp_store, cx| {
+                lsp_store.merge_lsp_diagnostics(
+                    DiagnosticSourceKind::Other,
+                    vec![DocumentDiagnosticsUpdate {
+                        diagnostics: lsp::PublishDiagnosticsParams {
+                            uri,
+                            diagnostics,
+                            version: None,
+                        },
+                        result_id: None,
+                        registration_id: None,
+                        server_id,
+                        disk_based_sources: Cow::Borrowed(&[]),
+                    }],
+                    |_, _, _| false,
+                    cx,
+                )
+            })
+            .log_err();
+    }
+}
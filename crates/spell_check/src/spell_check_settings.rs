@@ -0,0 +1,20 @@
+use settings::{RegisterSetting, Settings};
+
+#[derive(Clone, Debug, PartialEq, Eq, RegisterSetting)]
+pub struct SpellCheckSettings {
+    pub enabled: bool,
+    pub dictionaries: Vec<String>,
+    pub words: Vec<String>,
+}
+
+impl Settings for SpellCheckSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let spell_check = content.spell_check.as_ref().unwrap();
+
+        Self {
+            enabled: spell_check.enabled.unwrap(),
+            dictionaries: spell_check.dictionaries.clone().unwrap(),
+            words: spell_check.words.clone().unwrap(),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::ops::Range;
+
+/// Words shorter than this are not checked, as they are mostly abbreviations in code.
+const MIN_WORD_LEN: usize = 4;
+
+/// Returns the ranges of the words to check in the given text.
+///
+/// Identifiers are split into the words they are made of, so that `parseHttpRequest` and
+/// `parse_http_request` are both checked as "parse", "http" and "request". Acronyms, words
+/// containing digits, URLs and email addresses are skipped.
+pub fn words(text: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    for (chunk_start, chunk) in whitespace_separated_chunks(text) {
+        if chunk.contains("://") || chunk.contains('@') || chunk.starts_with("www.") {
+            continue;
+        }
+        for token in tokens(chunk) {
+            let token_text = &chunk[token.clone()];
+            if token_text
+                .chars()
+                .any(|character| character.is_ascii_digit())
+            {
+                continue;
+            }
+            for word in split_identifier(token_text) {
+                let word_text = &token_text[word.clone()];
+                let is_acronym = !word_text.chars().any(char::is_lowercase);
+                if word_text.chars().count() >= MIN_WORD_LEN && !is_acronym {
+                    let start = chunk_start + token.start + word.start;
+                    words.push(start..start + word.len());
+                }
+            }
+        }
+    }
+    words
+}
+
+fn whitespace_separated_chunks(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split_whitespace()
+        .map(move |chunk| (chunk.as_ptr() as usize - text.as_ptr() as usize, chunk))
+}
+
+fn is_word_character(character: char) -> bool {
+    character.is_alphanumeric()
+}
+
+fn is_apostrophe(character: char) -> bool {
+    character == '\'' || character == '’'
+}
+
+/// Splits a chunk of text into runs of alphanumeric characters, keeping apostrophes that join
+/// two runs, as in "don't".
+fn tokens(chunk: &str) -> Vec<Range<usize>> {
+    let mut tokens = Vec::new();
+    let mut token_start = None;
+    let mut characters = chunk.char_indices().peekable();
+    while let Some((index, character)) = characters.next() {
+        let continues_token = is_word_character(character)
+            || token_start.is_some()
+                && is_apostrophe(character)
+                && characters
+                    .peek()
+                    .is_some_and(|(_, next)| next.is_alphabetic());
+        match (continues_token, token_start) {
+            (true, None) => token_start = Some(index),
+            (false, Some(start)) => {
+                tokens.push(start..index);
+                token_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = token_start {
+        tokens.push(start..chunk.len());
+    }
+    tokens
+}
+
+/// Splits an identifier at case changes, as in `camelCase` and `HTTPServer`.
+fn split_identifier(identifier: &str) -> Vec<Range<usize>> {
+    let characters = identifier.char_indices().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut word_start = 0;
+    for (position, (index, character)) in characters.iter().enumerate().skip(1) {
+        let previous = characters[position - 1].1;
+        let next = characters.get(position + 1).map(|(_, next)| *next);
+        let is_boundary = character.is_uppercase()
+            && (previous.is_lowercase()
+                || previous.is_uppercase() && next.is_some_and(char::is_lowercase));
+        if is_boundary {
+            words.push(word_start..*index);
+            word_start = *index;
+        }
+    }
+    words.push(word_start..identifier.len());
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word_texts(text: &str) -> Vec<&str> {
+        words(text).into_iter().map(|range| &text[range]).collect()
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(
+            word_texts("Parses the HTTP request, doesn't retry."),
+            ["Parses", "request", "doesn't", "retry"]
+        );
+        assert_eq!(
+            word_texts("parseHttpRequest parse_http_request HTTPServer"),
+            [
+                "parse", "Http", "Request", "parse", "http", "request", "Server"
+            ]
+        );
+        assert_eq!(
+            word_texts("utf8 x86_64 see https://example.com/path or user@example.com"),
+            Vec::<&str>::new()
+        );
+        assert_eq!(
+            word_texts("  «héllo»\twörld’s  'quoted'"),
+            ["héllo", "wörld’s", "quoted"]
+        );
+    }
+}
@@ -321,6 +321,7 @@ impl Display for TerminalError {
 // https://github.com/alacritty/alacritty/blob/cb3a79dbf6472740daca8440d5166c1d4af5029e/extra/man/alacritty.5.scd?plain=1#L207-L213
 const DEFAULT_SCROLL_HISTORY_LINES: usize = 10_000;
 pub const MAX_SCROLL_HISTORY_LINES: usize = 100_000;
+const HISTORY_MARK_ROWS: usize = 8;
 
 pub struct TerminalBuilder {
     terminal: Terminal,
@@ -394,6 +395,8 @@ impl TerminalBuilder {
             },
             child_exited: None,
             event_loop_task: Task::ready(Ok(())),
+            #[cfg(any(test, feature = "test-support"))]
+            display_only_input: Vec::new(),
         };
 
         Ok(TerminalBuilder {
@@ -619,6 +622,8 @@ impl TerminalBuilder {
                 },
                 child_exited: None,
                 event_loop_task: Task::ready(Ok(())),
+                #[cfg(any(test, feature = "test-support"))]
+                display_only_input: Vec::new(),
             };
 
             if !activation_script.is_empty() && no_task {
@@ -769,6 +774,27 @@ pub struct TerminalContent {
     pub scrolled_to_bottom: bool,
 }
 
+/// The rows that ended a terminal's scrollback history when output was read from it, used to
+/// find how far that output has scrolled since. See [`Terminal::output_lines_since`].
+#[derive(Clone, Debug, Default)]
+pub struct HistoryMark {
+    history_size: usize,
+    rows: Vec<String>,
+}
+
+/// Output read by [`Terminal::output_lines_since`].
+#[derive(Debug)]
+pub struct OutputLines {
+    /// How many rows scrolled into the history since the mark was taken, or `None` if the marked
+    /// rows can't be found anymore, e.g. because the terminal was cleared. The lines then start
+    /// at the top of the history instead.
+    pub scrolled_rows: Option<usize>,
+    /// Logical lines paired with the grid line they start on.
+    pub lines: Vec<(i32, String)>,
+    /// Marks the end of the history as it is now, for the next read.
+    pub mark: HistoryMark,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct HoveredWord {
     pub word: String,
@@ -839,6 +865,9 @@ pub struct Terminal {
     activation_script: Vec<String>,
     child_exited: Option<ExitStatus>,
     event_loop_task: Task<Result<(), anyhow::Error>>,
+    /// Input written to a display-only terminal, which has no PTY to receive it.
+    #[cfg(any(test, feature = "test-support"))]
+    display_only_input: Vec<u8>,
 }
 
 struct CopyTemplate {
@@ -1413,9 +1442,20 @@ impl Terminal {
             .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
         self.events.push_back(InternalEvent::SetSelection(None));
 
+        let input = input.into();
+        #[cfg(any(test, feature = "test-support"))]
+        if let TerminalType::DisplayOnly = self.terminal_type {
+            self.display_only_input.extend_from_slice(&input);
+        }
         self.write_to_pty(input);
     }
 
+    /// Returns and clears the input written to a display-only terminal so far.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn take_display_only_input(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.display_only_input)
+    }
+
     pub fn toggle_vi_mode(&mut self) {
         self.events.push_back(InternalEvent::ToggleViMode);
     }
@@ -1607,6 +1647,65 @@ impl Terminal {
         term.bounds_to_string(start, end)
     }
 
+    /// Returns the logical lines from the one that started on grid line `start` when `mark` was
+    /// taken to the bottom of the screen, joining rows that were soft-wrapped. Only those rows are
+    /// read, so output can be followed without rebuilding the whole scrollback on every change.
+    pub fn output_lines_since(&self, mark: &HistoryMark, start: i32) -> OutputLines {
+        let terminal = self.term.lock_unfair();
+        let grid = terminal.grid();
+        let topmost_line = grid.topmost_line().0;
+        let bottommost_line = grid.bottommost_line().0;
+
+        let scrolled_rows = scrolled_rows_since(grid, mark)
+            .filter(|&scrolled_rows| start - scrolled_rows as i32 >= topmost_line);
+        let mut lines = Vec::new();
+        let mut current_line =
+            scrolled_rows.map_or(topmost_line, |scrolled_rows| start - scrolled_rows as i32);
+        while current_line <= bottommost_line {
+            let logical_line_end = self.find_logical_line_end(grid, current_line, bottommost_line);
+            let logical_line = self.construct_logical_line(grid, current_line, logical_line_end);
+            lines.push((current_line, logical_line.trim_end().to_string()));
+            current_line = logical_line_end + 1;
+        }
+
+        while lines.last().is_some_and(|(_, line)| line.is_empty()) {
+            lines.pop();
+        }
+
+        let history_size = grid.history_size();
+        let mark_rows = history_size.min(HISTORY_MARK_ROWS) as i32;
+        let mark = HistoryMark {
+            history_size,
+            rows: (-mark_rows..0)
+                .map(|line| row_to_string(&grid[Line(line)]))
+                .collect(),
+        };
+        OutputLines {
+            scrolled_rows,
+            lines,
+            mark,
+        }
+    }
+
+    /// Scrolls the logical line starting at the given grid line into view and selects it.
+    pub fn reveal_line(&mut self, line: i32) {
+        let (start, end) = {
+            let terminal = self.term.lock_unfair();
+            let grid = terminal.grid();
+            let bottommost_line = grid.bottommost_line().0;
+            let line = line.clamp(grid.topmost_line().0, bottommost_line);
+            let end_line = self.find_logical_line_end(grid, line, bottommost_line);
+            (
+                AlacPoint::new(Line(line), Column(0)),
+                AlacPoint::new(Line(end_line), grid.last_column()),
+            )
+        };
+
+        self.set_selection(Some((make_selection(&(start..=end)), end)));
+        self.events
+            .push_back(InternalEvent::ScrollToAlacPoint(start));
+    }
+
     pub fn last_n_non_empty_lines(&self, n: usize) -> Vec<String> {
         let term = self.term.clone();
         let terminal = term.lock_unfair();
@@ -1645,6 +1744,18 @@ impl Terminal {
         line_start
     }
 
+    fn find_logical_line_end(&self, grid: &Grid<Cell>, current: i32, bottommost: i32) -> i32 {
+        let mut line_end = current;
+        while line_end < bottommost {
+            let last_cell = &grid[Line(line_end)][Column(grid.columns() - 1)];
+            if !last_cell.flags.contains(Flags::WRAPLINE) {
+                break;
+            }
+            line_end += 1;
+        }
+        line_end
+    }
+
     fn construct_logical_line(&self, grid: &Grid<Cell>, start: i32, end: i32) -> String {
         let mut logical_line = String::new();
         for row in start..=end {
@@ -2191,6 +2302,22 @@ impl Terminal {
     }
 }
 
+/// Returns how many rows scrolled into the history since `mark` was taken, by finding the rows
+/// that ended the history back then. Until the history is full, they moved up exactly as much as
+/// it grew, so that offset is tried first.
+fn scrolled_rows_since(grid: &Grid<Cell>, mark: &HistoryMark) -> Option<usize> {
+    let history_size = grid.history_size();
+    let fewest = history_size.checked_sub(mark.history_size)?;
+    let most = history_size.checked_sub(mark.rows.len())?;
+    (fewest..=most).find(|&scrolled_rows| {
+        let end = -(scrolled_rows as i32);
+        let start = end - mark.rows.len() as i32;
+        (start..end)
+            .zip(&mark.rows)
+            .all(|(line, row)| row_to_string(&grid[Line(line)]) == *row)
+    })
+}
+
 // Helper function to convert a grid row to a string
 pub fn row_to_string(row: &Row<Cell>) -> String {
     row[..Column(row.len())]
@@ -2816,6 +2943,55 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_output_lines_since(cx: &mut TestAppContext) {
+        let terminal = cx.new(|cx| {
+            TerminalBuilder::new_display_only(CursorShape::default(), AlternateScroll::On, None, 0)
+                .unwrap()
+                .subscribe(cx)
+        });
+        fn texts(output: &OutputLines) -> Vec<&str> {
+            output.lines.iter().map(|(_, line)| line.as_str()).collect()
+        }
+
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output(b"first  \nsecond\n\nthird", cx);
+        });
+        let output = terminal.update(cx, |terminal, _| {
+            terminal.output_lines_since(&HistoryMark::default(), 0)
+        });
+        assert_eq!(output.scrolled_rows, Some(0));
+        assert_eq!(texts(&output), ["first", "second", "", "third"]);
+        assert_eq!(output.lines[3].0, 3);
+
+        // The screen holds six rows, so the first two lines scroll into the history.
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output(b"\nfourth\nfifth\nsixth\nseventh", cx);
+        });
+        let output = terminal.update(cx, |terminal, _| {
+            terminal.output_lines_since(&output.mark, 2)
+        });
+        assert_eq!(output.scrolled_rows, Some(2));
+        assert_eq!(
+            texts(&output),
+            ["", "third", "fourth", "fifth", "sixth", "seventh"]
+        );
+        assert_eq!(output.lines[0].0, 0);
+
+        // Clearing the history loses the marked rows, so the whole terminal is read again.
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output(b"\x1b[3J", cx);
+        });
+        let output = terminal.update(cx, |terminal, _| {
+            terminal.output_lines_since(&output.mark, 0)
+        });
+        assert_eq!(output.scrolled_rows, None);
+        assert_eq!(
+            texts(&output),
+            ["", "third", "fourth", "fifth", "sixth", "seventh"]
+        );
+    }
+
     #[gpui::test]
     async fn test_write_output_preserves_bare_cr(cx: &mut TestAppContext) {
         let terminal = cx.new(|cx| {
@@ -0,0 +1,431 @@
+use std::{ops::Range, time::Duration};
+
+use editor::{Addon, Editor, MultiBuffer, SelectionEffects, scroll::Autoscroll};
+use gpui::{App, AppContext as _, Context, Entity, KeyContext, Subscription, Task, WeakEntity};
+use language::{Buffer, Capability, Point};
+use terminal::{Event, HistoryMark, Terminal};
+use ui::Window;
+use workspace::Workspace;
+
+use crate::{RevealInTerminal, TerminalView, terminal_panel::TerminalPanel};
+
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Keeps a read-only buffer in sync with the output of a terminal.
+///
+/// The buffer holds everything captured so far. Lines that have scrolled into the terminal's
+/// history are settled and never touched again, while the trailing `snapshot` lines mirror the
+/// screen, which the running program may still rewrite.
+struct TerminalOutputAddon {
+    terminal: WeakEntity<Terminal>,
+    terminal_view: WeakEntity<TerminalView>,
+    buffer: Entity<Buffer>,
+    /// For each settled buffer row, the grid line it started on plus `scrolled_rows` at the time,
+    /// or `None` if the terminal was cleared since.
+    settled_lines: Vec<Option<i64>>,
+    /// How many rows have scrolled into the terminal's history since the buffer was opened.
+    scrolled_rows: i64,
+    history_mark: HistoryMark,
+    snapshot: Vec<(i32, String)>,
+    pending_refresh: Option<Task<()>>,
+    _subscription: Subscription,
+}
+
+impl Addon for TerminalOutputAddon {
+    fn extend_key_context(&self, key_context: &mut KeyContext, _: &App) {
+        key_context.add("TerminalOutput");
+    }
+
+    fn to_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn to_any_mut(&mut self) -> Option<&mut dyn std::any::Any> {
+        Some(self)
+    }
+}
+
+pub(crate) fn open_output_in_buffer(
+    terminal_view: &TerminalView,
+    language_name: Option<&str>,
+    window: &mut Window,
+    cx: &mut Context<TerminalView>,
+) {
+    let Some(workspace) = terminal_view.workspace.upgrade() else {
+        return;
+    };
+    let terminal = terminal_view.terminal.clone();
+    let terminal_view = cx.entity().downgrade();
+    let title = terminal.read(cx).title(true);
+    let languages = workspace.read(cx).project().read(cx).languages().clone();
+
+    let buffer = cx.new(|cx| {
+        let mut buffer = Buffer::local("", cx);
+        buffer.set_capability(Capability::ReadOnly, cx);
+        buffer.set_language_registry(languages.clone());
+        if let Some(language_name) = language_name {
+            let language = languages.language_for_name(language_name);
+            cx.spawn(async move |buffer, cx| {
+                let language = language.await?;
+                buffer.update(cx, |buffer, cx| buffer.set_language(Some(language), cx))
+            })
+            .detach_and_log_err(cx);
+        }
+        buffer
+    });
+    let multi_buffer = cx.new(|cx| {
+        MultiBuffer::singleton(buffer.clone(), cx).with_title(format!("Output of {title}"))
+    });
+    let editor = cx.new(|cx| {
+        let mut editor = Editor::for_multibuffer(multi_buffer, None, window, cx);
+        editor.set_read_only(true);
+
+        let subscription = cx.subscribe_in(
+            &terminal,
+            window,
+            |editor: &mut Editor, _, event: &Event, window, cx| {
+                if matches!(event, Event::Wakeup) {
+                    schedule_refresh(editor, window, cx);
+                }
+            },
+        );
+        editor.register_addon(TerminalOutputAddon {
+            terminal: terminal.downgrade(),
+            terminal_view,
+            buffer,
+            settled_lines: Vec::new(),
+            scrolled_rows: 0,
+            history_mark: HistoryMark::default(),
+            snapshot: Vec::new(),
+            pending_refresh: None,
+            _subscription: subscription,
+        });
+        refresh(&mut editor, window, cx);
+        editor
+    });
+
+    workspace.update(cx, |workspace, cx| {
+        workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+    });
+}
+
+fn schedule_refresh(editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>) {
+    let Some(addon) = editor.addon_mut::<TerminalOutputAddon>() else {
+        return;
+    };
+    if addon.pending_refresh.is_some() {
+        return;
+    }
+    addon.pending_refresh = Some(cx.spawn_in(window, async move |editor, cx| {
+        cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+        editor
+            .update_in(cx, |editor, window, cx| refresh(editor, window, cx))
+            .ok();
+    }));
+}
+
+fn refresh(editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>) {
+    let cursor_row = editor
+        .selections
+        .newest::<Point>(&editor.display_snapshot(cx))
+        .head()
+        .row;
+    let Some(addon) = editor.addon_mut::<TerminalOutputAddon>() else {
+        return;
+    };
+    addon.pending_refresh = None;
+    let Some(terminal) = addon.terminal.upgrade() else {
+        return;
+    };
+
+    let start = addon.snapshot.first().map_or(0, |(line, _)| *line);
+    let output = terminal
+        .read(cx)
+        .output_lines_since(&addon.history_mark, start);
+    let snapshot_start_row = addon.settled_lines.len() as u32;
+    let edits = match output.scrolled_rows {
+        Some(scrolled_rows) => {
+            addon.scrolled_rows += scrolled_rows as i64;
+            snapshot_edits(&addon.snapshot, &output.lines, snapshot_start_row)
+        }
+        None => {
+            // The captured output is gone from the terminal, e.g. because it was cleared, so
+            // keep it as it is and start over after it.
+            let kept_rows = addon.settled_lines.len() + addon.snapshot.len();
+            addon.settled_lines.fill(None);
+            addon.settled_lines.resize(kept_rows, None);
+            snapshot_edits(&[], &output.lines, kept_rows as u32)
+        }
+    };
+
+    let mut lines = output.lines;
+    let snapshot = lines.split_off(settled_line_count(&lines));
+    let scrolled_rows = addon.scrolled_rows;
+    addon.settled_lines.extend(
+        lines
+            .iter()
+            .map(|(line, _)| Some(i64::from(*line) + scrolled_rows)),
+    );
+    addon.snapshot = snapshot;
+    addon.history_mark = output.mark;
+
+    let following = addon.buffer.update(cx, |buffer, cx| {
+        let following = cursor_row >= buffer.max_point().row;
+        if !edits.is_empty() {
+            buffer.finalize_last_transaction();
+            buffer.start_transaction();
+            buffer.edit(edits, None, cx);
+            if let Some(transaction_id) = buffer.end_transaction(cx) {
+                buffer.forget_transaction(transaction_id);
+            }
+        }
+        following
+    });
+
+    if following {
+        let end = editor.buffer().read(cx).snapshot(cx).max_point();
+        editor.change_selections(
+            SelectionEffects::scroll(Autoscroll::fit()),
+            window,
+            cx,
+            |selections| selections.select_ranges([end..end]),
+        );
+    }
+}
+
+/// Returns how many leading lines lie entirely in the terminal's history, above the screen.
+fn settled_line_count(lines: &[(i32, String)]) -> usize {
+    lines.iter().rposition(|(line, _)| *line <= 0).unwrap_or(0)
+}
+
+/// Returns the edits that turn the buffer rows starting at `start_row`, which hold the `previous`
+/// lines, into the `current` lines. Unchanged lines are left alone and new output is appended, so
+/// a refresh only touches what the terminal actually changed.
+fn snapshot_edits(
+    previous: &[(i32, String)],
+    current: &[(i32, String)],
+    start_row: u32,
+) -> Vec<(Range<Point>, String)> {
+    let mut edits = previous
+        .iter()
+        .zip(current)
+        .enumerate()
+        .filter(|(_, ((_, previous_line), (_, current_line)))| previous_line != current_line)
+        .map(|(index, ((_, previous_line), (_, current_line)))| {
+            let row = start_row + index as u32;
+            (
+                Point::new(row, 0)..Point::new(row, previous_line.len() as u32),
+                current_line.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let end = Point::new(start_row + previous.len() as u32, 0);
+    if current.len() > previous.len() {
+        let appended = current[previous.len()..]
+            .iter()
+            .map(|(_, line)| format!("{line}\n"))
+            .collect::<String>();
+        edits.push((end..end, appended));
+    } else if current.len() < previous.len() {
+        let start = Point::new(start_row + current.len() as u32, 0);
+        edits.push((start..end, String::new()));
+    }
+    edits
+}
+
+pub(crate) fn reveal_in_terminal(
+    workspace: &mut Workspace,
+    _: &RevealInTerminal,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let target = editor.update(cx, |editor, cx| {
+        refresh(editor, window, cx);
+        let cursor_row = editor
+            .selections
+            .newest::<Point>(&editor.display_snapshot(cx))
+            .head()
+            .row;
+        let addon = editor.addon::<TerminalOutputAddon>()?;
+        let row = cursor_row as usize;
+        let line = match addon.settled_lines.get(row) {
+            Some(settled_line) => i32::try_from((*settled_line)? - addon.scrolled_rows).ok()?,
+            None => addon.snapshot.get(row - addon.settled_lines.len())?.0,
+        };
+        Some((addon.terminal_view.upgrade()?, line))
+    });
+    let Some((terminal_view, line)) = target else {
+        return;
+    };
+
+    terminal_view.update(cx, |terminal_view, cx| {
+        terminal_view
+            .terminal
+            .update(cx, |terminal, _| terminal.reveal_line(line));
+        cx.notify();
+    });
+
+    if workspace.activate_item(&terminal_view, true, true, window, cx) {
+        return;
+    }
+    let Some(terminal_panel) = workspace.panel::<TerminalPanel>(cx) else {
+        return;
+    };
+    let pane_and_index = terminal_panel
+        .read(cx)
+        .center
+        .panes()
+        .into_iter()
+        .find_map(|pane| {
+            pane.read(cx)
+                .index_for_item(&terminal_view)
+                .map(|index| (pane.clone(), index))
+        });
+    if let Some((pane, index)) = pane_and_index {
+        workspace.focus_panel::<TerminalPanel>(window, cx);
+        pane.update(cx, |pane, cx| {
+            pane.activate_item(index, true, true, window, cx);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use editor::Editor;
+    use gpui::{AppContext as _, TestAppContext};
+    use language::Point;
+    use terminal::{
+        TerminalBuilder,
+        terminal_settings::{AlternateScroll, CursorShape},
+    };
+
+    use super::{REFRESH_DEBOUNCE, open_output_in_buffer, settled_line_count, snapshot_edits};
+    use crate::{TerminalView, tests::init_test};
+
+    fn lines(lines: &[&str]) -> Vec<(i32, String)> {
+        lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| (index as i32, line.to_string()))
+            .collect()
+    }
+
+    #[gpui::test]
+    async fn test_output_buffer_follows_terminal(cx: &mut TestAppContext) {
+        let (project, workspace) = init_test(cx).await;
+        cx.update(editor::init);
+        let window = cx.windows()[0];
+
+        let terminal = cx.new(|cx| {
+            TerminalBuilder::new_display_only(CursorShape::default(), AlternateScroll::On, None, 0)
+                .unwrap()
+                .subscribe(cx)
+        });
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output(b"first\nsecond", cx);
+        });
+        window
+            .update(cx, |_, window, cx| {
+                let terminal_view = cx.new(|cx| {
+                    TerminalView::new(
+                        terminal.clone(),
+                        workspace.downgrade(),
+                        None,
+                        project.downgrade(),
+                        window,
+                        cx,
+                    )
+                });
+                terminal_view.update(cx, |terminal_view, cx| {
+                    open_output_in_buffer(terminal_view, None, window, cx);
+                });
+            })
+            .unwrap();
+        let editor = workspace.read_with(cx, |workspace, cx| {
+            workspace.active_item_as::<Editor>(cx).unwrap()
+        });
+        let cursor = |cx: &mut TestAppContext| {
+            editor.update(cx, |editor, cx| {
+                editor
+                    .selections
+                    .newest::<Point>(&editor.display_snapshot(cx))
+                    .head()
+            })
+        };
+        assert_eq!(
+            editor.read_with(cx, |editor, cx| editor.text(cx)),
+            "first\nsecond\n"
+        );
+        assert_eq!(cursor(cx), Point::new(2, 0));
+
+        // The screen holds six rows, so the first line scrolls into the history.
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output(b"\nthird\nfourth\nfifth\nsixth\nseventh", cx);
+        });
+        cx.executor().advance_clock(REFRESH_DEBOUNCE);
+        cx.run_until_parked();
+        assert_eq!(
+            editor.read_with(cx, |editor, cx| editor.text(cx)),
+            "first\nsecond\nthird\nfourth\nfifth\nsixth\nseventh\n"
+        );
+        assert_eq!(cursor(cx), Point::new(7, 0));
+
+        // Lines still on the screen follow the program rewriting them.
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output(b"\r\x1b[2Kdone", cx);
+        });
+        cx.executor().advance_clock(REFRESH_DEBOUNCE);
+        cx.run_until_parked();
+        assert_eq!(
+            editor.read_with(cx, |editor, cx| editor.text(cx)),
+            "first\nsecond\nthird\nfourth\nfifth\nsixth\ndone\n"
+        );
+    }
+
+    #[test]
+    fn test_settled_line_count() {
+        // Nothing has scrolled into the history yet.
+        assert_eq!(settled_line_count(&lines(&["a", "b"])), 0);
+
+        let history = vec![
+            (-3, "a".to_string()),
+            (-2, "wrapped".to_string()),
+            (0, "b".to_string()),
+            (1, "c".to_string()),
+        ];
+        assert_eq!(settled_line_count(&history), 2);
+
+        // A line wrapping from the history onto the screen can still be rewritten.
+        let wrapping = vec![(-2, "a".to_string()), (-1, "wrapped".to_string())];
+        assert_eq!(settled_line_count(&wrapping), 1);
+
+        assert_eq!(settled_line_count(&[]), 0);
+    }
+
+    #[test]
+    fn test_snapshot_edits() {
+        // Output appended to the screen is inserted after the existing lines.
+        assert_eq!(
+            snapshot_edits(&lines(&["a", "b"]), &lines(&["a", "b", "c", "d"]), 3),
+            vec![(Point::new(5, 0)..Point::new(5, 0), "c\nd\n".to_string())]
+        );
+
+        // Only rewritten lines are replaced.
+        assert_eq!(
+            snapshot_edits(&lines(&["a", "bb", "c"]), &lines(&["a", "x", "c"]), 0),
+            vec![(Point::new(1, 0)..Point::new(1, 2), "x".to_string())]
+        );
+
+        // Lines that disappeared from the end of the screen are removed.
+        assert_eq!(
+            snapshot_edits(&lines(&["a", "b", "c"]), &lines(&["a"]), 0),
+            vec![(Point::new(1, 0)..Point::new(3, 0), String::new())]
+        );
+
+        assert_eq!(snapshot_edits(&lines(&["a"]), &lines(&["a"]), 0), vec![]);
+    }
+}
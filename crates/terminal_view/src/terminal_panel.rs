@@ -1,7 +1,7 @@
 use std::{cmp, ops::ControlFlow, path::PathBuf, process::ExitStatus, sync::Arc, time::Duration};
 
 use crate::{
-    TerminalView, default_working_directory,
+    TerminalView, ToggleBroadcastInput, default_working_directory,
     persistence::{
         SerializedItems, SerializedTerminalPanel, deserialize_terminal_panel, serialize_pane_group,
     },
@@ -201,6 +201,35 @@ impl TerminalPanel {
                                 }
                             }),
                     )
+                    .when_some(
+                        pane.active_item()
+                            .and_then(|item| item.downcast::<TerminalView>()),
+                        |this, terminal_view| {
+                            let broadcast_input = terminal_view.read(cx).broadcast_input();
+                            this.child(
+                                IconButton::new("terminal-broadcast-input", IconName::Keyboard)
+                                    .icon_size(IconSize::Small)
+                                    .toggle_state(broadcast_input)
+                                    .selected_icon_color(Color::Warning)
+                                    .on_click(move |_, _, cx| {
+                                        terminal_view.update(cx, |terminal_view, cx| {
+                                            terminal_view.set_broadcast_input(!broadcast_input, cx);
+                                        });
+                                    })
+                                    .tooltip(move |_window, cx| {
+                                        Tooltip::for_action(
+                                            if broadcast_input {
+                                                "Stop Broadcasting Input"
+                                            } else {
+                                                "Broadcast Input"
+                                            },
+                                            &ToggleBroadcastInput,
+                                            cx,
+                                        )
+                                    }),
+                            )
+                        },
+                    )
                     .child({
                         let zoomed = pane.is_zoomed();
                         IconButton::new("toggle_zoom", IconName::Maximize)
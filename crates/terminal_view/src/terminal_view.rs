@@ -1,5 +1,6 @@
 mod persistence;
 pub mod terminal_element;
+mod terminal_output_buffer;
 pub mod terminal_panel;
 mod terminal_path_like_target;
 pub mod terminal_scrollbar;
@@ -12,6 +13,7 @@ use gpui::{
     KeyContext, KeyDownEvent, Keystroke, MouseButton, MouseDownEvent, Pixels, Render,
     ScrollWheelEvent, Styled, Subscription, Task, WeakEntity, actions, anchored, deferred, div,
 };
+use itertools::Itertools as _;
 use persistence::TERMINAL_DB;
 use project::{Project, search::SearchQuery};
 use schemars::JsonSchema;
@@ -81,11 +83,26 @@ pub struct SendText(String);
 #[action(namespace = terminal)]
 pub struct SendKeystroke(String);
 
+/// Opens the terminal output in a read-only buffer that follows new output.
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Action)]
+#[action(namespace = terminal)]
+#[serde(deny_unknown_fields)]
+pub struct OpenOutputInBuffer {
+    /// The name of the language to highlight the output as, e.g. "JSON". Plain text by default.
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
 actions!(
     terminal,
     [
         /// Reruns the last executed task in the terminal.
-        RerunTask
+        RerunTask,
+        /// Toggles mirroring input typed into this terminal to every other terminal
+        /// that also has broadcast input enabled.
+        ToggleBroadcastInput,
+        /// Reveals the line under the cursor of a terminal output buffer in its terminal.
+        RevealInTerminal
     ]
 );
 
@@ -97,6 +114,7 @@ pub fn init(cx: &mut App) {
 
     cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
         workspace.register_action(TerminalView::deploy);
+        workspace.register_action(terminal_output_buffer::reveal_in_terminal);
     })
     .detach();
     SlashCommandRegistry::global(cx).register_command(TerminalSlashCommand, true);
@@ -135,6 +153,7 @@ pub struct TerminalView {
     scroll_top: Pixels,
     scroll_handle: TerminalScrollHandle,
     ime_state: Option<ImeState>,
+    broadcast_input: bool,
     _subscriptions: Vec<Subscription>,
     _terminal_subscriptions: Vec<Subscription>,
 }
@@ -271,6 +290,7 @@ impl TerminalView {
             scroll_handle,
             cwd_serialized: false,
             ime_state: None,
+            broadcast_input: false,
             _subscriptions,
             _terminal_subscriptions: terminal_subscriptions,
         }
@@ -356,6 +376,75 @@ impl TerminalView {
             self.terminal.update(cx, |term, _| {
                 term.input(text.to_string().into_bytes());
             });
+            self.broadcast(cx, |term, _| {
+                term.input(text.to_string().into_bytes());
+            });
+        }
+    }
+
+    pub fn broadcast_input(&self) -> bool {
+        self.broadcast_input
+    }
+
+    pub fn set_broadcast_input(&mut self, broadcast_input: bool, cx: &mut Context<Self>) {
+        self.broadcast_input = broadcast_input;
+        cx.emit(ItemEvent::UpdateTab);
+        cx.notify();
+    }
+
+    fn open_output_in_buffer(
+        &mut self,
+        action: &OpenOutputInBuffer,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        terminal_output_buffer::open_output_in_buffer(self, action.language.as_deref(), window, cx);
+    }
+
+    fn toggle_broadcast_input(
+        &mut self,
+        _: &ToggleBroadcastInput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_broadcast_input(!self.broadcast_input, cx);
+    }
+
+    /// Returns the other terminals in the workspace, including the terminal panel,
+    /// that currently receive broadcast input.
+    fn broadcast_peers(&self, cx: &Context<Self>) -> Vec<Entity<TerminalView>> {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return Vec::new();
+        };
+        let workspace = workspace.read(cx);
+        let mut panes = workspace.panes().to_vec();
+        if let Some(terminal_panel) = workspace.panel::<TerminalPanel>(cx) {
+            panes.extend(terminal_panel.read(cx).center.panes().into_iter().cloned());
+        }
+
+        let this_id = cx.entity_id();
+        panes
+            .iter()
+            .flat_map(|pane| pane.read(cx).items_of_type::<TerminalView>())
+            .filter(|terminal_view| {
+                terminal_view.entity_id() != this_id && terminal_view.read(cx).broadcast_input
+            })
+            .unique_by(|terminal_view| terminal_view.entity_id())
+            .collect()
+    }
+
+    /// Applies the same input to every broadcast peer, if this terminal is broadcasting.
+    fn broadcast(
+        &self,
+        cx: &mut Context<Self>,
+        mut apply: impl FnMut(&mut Terminal, &mut Context<Terminal>),
+    ) {
+        if !self.broadcast_input {
+            return;
+        }
+        for peer in self.broadcast_peers(cx) {
+            let terminal = peer.read(cx).terminal.clone();
+            terminal.update(cx, |term, cx| apply(term, cx));
         }
     }
 
@@ -387,6 +476,8 @@ impl TerminalView {
             .upgrade()
             .and_then(|workspace| workspace.read(cx).panel::<TerminalPanel>(cx))
             .is_some_and(|terminal_panel| terminal_panel.read(cx).assistant_enabled());
+        let broadcast_input = self.broadcast_input;
+        let terminal_view = cx.entity().downgrade();
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
             menu.context(self.focus_handle.clone())
                 .action("New Terminal", Box::new(NewTerminal))
@@ -395,6 +486,24 @@ impl TerminalView {
                 .action("Paste", Box::new(Paste))
                 .action("Select All", Box::new(SelectAll))
                 .action("Clear", Box::new(Clear))
+                .action(
+                    "Open Output in Buffer",
+                    Box::new(OpenOutputInBuffer::default()),
+                )
+                .separator()
+                .toggleable_entry(
+                    "Broadcast Input",
+                    broadcast_input,
+                    IconPosition::Start,
+                    Some(Box::new(ToggleBroadcastInput)),
+                    move |_, cx| {
+                        terminal_view
+                            .update(cx, |terminal_view, cx| {
+                                terminal_view.set_broadcast_input(!broadcast_input, cx)
+                            })
+                            .ok();
+                    },
+                )
                 .when(assistant_enabled, |menu| {
                     menu.separator()
                         .action("Inline Assist", Box::new(InlineAssist::default()))
@@ -690,6 +799,7 @@ impl TerminalView {
         if let Some(clipboard_string) = cx.read_from_clipboard().and_then(|item| item.text()) {
             self.terminal
                 .update(cx, |terminal, _cx| terminal.paste(&clipboard_string));
+            self.broadcast(cx, |terminal, _| terminal.paste(&clipboard_string));
         }
     }
 
@@ -698,6 +808,9 @@ impl TerminalView {
         self.terminal.update(cx, |term, _| {
             term.input(text.0.to_string().into_bytes());
         });
+        self.broadcast(cx, |term, _| {
+            term.input(text.0.to_string().into_bytes());
+        });
     }
 
     fn send_keystroke(&mut self, text: &SendKeystroke, _: &mut Window, cx: &mut Context<Self>) {
@@ -711,6 +824,9 @@ impl TerminalView {
                 }
                 processed
             });
+            self.broadcast(cx, |term, cx| {
+                term.try_keystroke(&keystroke, TerminalSettings::get_global(cx).option_as_meta);
+            });
         }
     }
 
@@ -994,15 +1110,21 @@ impl TerminalView {
         self.clear_bell(cx);
         self.pause_cursor_blinking(window, cx);
 
-        self.terminal.update(cx, |term, cx| {
-            let handled = term.try_keystroke(
+        let handled = self.terminal.update(cx, |term, cx| {
+            term.try_keystroke(
                 &event.keystroke,
                 TerminalSettings::get_global(cx).option_as_meta,
-            );
-            if handled {
-                cx.stop_propagation();
-            }
+            )
         });
+        if handled {
+            cx.stop_propagation();
+            self.broadcast(cx, |term, cx| {
+                term.try_keystroke(
+                    &event.keystroke,
+                    TerminalSettings::get_global(cx).option_as_meta,
+                );
+            });
+        }
     }
 
     fn focus_in(&mut self, window: &mut Window, cx: &mut Context<Self>) {
@@ -1077,6 +1199,8 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
             .on_action(cx.listener(TerminalView::rerun_task))
+            .on_action(cx.listener(TerminalView::toggle_broadcast_input))
+            .on_action(cx.listener(TerminalView::open_output_in_buffer))
             .on_key_down(cx.listener(Self::key_down))
             .on_mouse_down(
                 MouseButton::Right,
@@ -1098,6 +1222,10 @@ impl Render for TerminalView {
                     .id("terminal-view-container")
                     .size_full()
                     .bg(cx.theme().colors().editor_background)
+                    .when(self.broadcast_input, |div| {
+                        div.border_2()
+                            .border_color(cx.theme().status().warning_border)
+                    })
                     .child(TerminalElement::new(
                         terminal_handle,
                         terminal_view_handle,
@@ -1210,6 +1338,13 @@ impl Item for TerminalView {
                     }),
             )
             .child(Label::new(title).color(params.text_color()))
+            .when(self.broadcast_input, |this| {
+                this.child(
+                    Icon::new(IconName::Keyboard)
+                        .size(IconSize::Small)
+                        .color(Color::Warning),
+                )
+            })
             .into_any()
     }
 
@@ -1599,6 +1734,7 @@ mod tests {
     use gpui::TestAppContext;
     use project::{Entry, Project, ProjectPath, Worktree};
     use std::path::Path;
+    use terminal::{TerminalBuilder, terminal_settings::AlternateScroll};
     use util::rel_path::RelPath;
     use workspace::AppState;
 
@@ -1708,6 +1844,83 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_broadcast_input(cx: &mut TestAppContext) {
+        let (project, workspace) = init_test(cx).await;
+        cx.update(editor::init);
+        let window = cx.windows()[0];
+
+        let terminals = window
+            .update(cx, |_, window, cx| {
+                (0..3)
+                    .map(|_| {
+                        let terminal = cx.new(|cx| {
+                            TerminalBuilder::new_display_only(
+                                CursorShape::default(),
+                                AlternateScroll::On,
+                                None,
+                                0,
+                            )
+                            .unwrap()
+                            .subscribe(cx)
+                        });
+                        let terminal_view = cx.new(|cx| {
+                            TerminalView::new(
+                                terminal,
+                                workspace.downgrade(),
+                                None,
+                                project.downgrade(),
+                                window,
+                                cx,
+                            )
+                        });
+                        workspace.update(cx, |workspace, cx| {
+                            workspace.add_item_to_active_pane(
+                                Box::new(terminal_view.clone()),
+                                None,
+                                true,
+                                window,
+                                cx,
+                            );
+                        });
+                        terminal_view
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap();
+        let [source, peer, bystander] = terminals.as_slice() else {
+            panic!("expected three terminals");
+        };
+
+        for terminal_view in [source, peer] {
+            terminal_view.update(cx, |terminal_view, cx| {
+                terminal_view.set_broadcast_input(true, cx);
+            });
+        }
+        source.update(cx, |terminal_view, cx| {
+            terminal_view.commit_text("ls\r", cx)
+        });
+
+        fn take_input(terminal_view: &Entity<TerminalView>, cx: &mut TestAppContext) -> Vec<u8> {
+            terminal_view.update(cx, |terminal_view, cx| {
+                terminal_view
+                    .terminal
+                    .update(cx, |terminal, _| terminal.take_display_only_input())
+            })
+        }
+        assert_eq!(take_input(source, cx), b"ls\r");
+        assert_eq!(take_input(peer, cx), b"ls\r");
+        assert_eq!(take_input(bystander, cx), b"");
+
+        source.update(cx, |terminal_view, cx| {
+            terminal_view.set_broadcast_input(false, cx);
+            terminal_view.commit_text("pwd\r", cx);
+        });
+        assert_eq!(take_input(source, cx), b"pwd\r");
+        assert_eq!(take_input(peer, cx), b"");
+        assert_eq!(take_input(bystander, cx), b"");
+    }
+
     /// Creates a worktree with 1 file: /root.txt
     pub async fn init_test(cx: &mut TestAppContext) -> (Entity<Project>, Entity<Workspace>) {
         let params = cx.update(AppState::test);
@@ -115,6 +115,19 @@ fn test_random_edits(mut rng: StdRng) {
             old_buffer.text()
         );
 
+        for (from, to) in [
+            (old_buffer.version(), buffer.version()),
+            (buffer.version(), old_buffer.version()),
+        ] {
+            let mut text = buffer.rope_for_version(from).to_string();
+            let expected_text = buffer.rope_for_version(to).to_string();
+            for (range, old_text, new_text) in buffer.edits_between(from, to).into_iter().rev() {
+                assert_eq!(text[range.clone()], old_text);
+                text.replace_range(range, &new_text);
+            }
+            assert_eq!(text, expected_text);
+        }
+
         for _ in 0..5 {
             let end_ix =
                 old_buffer.clip_offset(rng.random_range(0..=old_buffer.len()), Bias::Right);
@@ -1389,6 +1389,14 @@ impl Buffer {
         self.history.redo_stack.last()
     }
 
+    /// Returns the transactions that can be undone, from oldest to newest.
+    pub fn undo_stack(&self) -> impl DoubleEndedIterator<Item = &Transaction> {
+        self.history
+            .undo_stack
+            .iter()
+            .map(|entry| &entry.transaction)
+    }
+
     pub fn start_transaction(&mut self) -> Option<TransactionId> {
         self.start_transaction_at(Instant::now())
     }
@@ -1946,6 +1954,68 @@ impl BufferSnapshot {
         rope
     }
 
+    /// Returns the edits that turn the text at version `from` into the text at version `to`,
+    /// ordered by position. Each edit consists of a range in the text at `from`, the text that
+    /// was removed from that range and the text that was inserted in its place.
+    pub fn edits_between(
+        &self,
+        from: &clock::Global,
+        to: &clock::Global,
+    ) -> Vec<(Range<usize>, String, String)> {
+        let mut edits = Vec::new();
+        let mut pending_edit: Option<(Range<usize>, String, String)> = None;
+        let mut old_offset = 0;
+        let mut visible_offset = 0;
+
+        let mut cursor = self
+            .fragments
+            .filter::<_, FragmentTextSummary>(&None, move |summary| {
+                !from.observed_all(&summary.max_version) || !to.observed_all(&summary.max_version)
+            });
+        cursor.next();
+
+        while let Some(fragment) = cursor.item() {
+            let unchanged_len = cursor.start().visible - visible_offset;
+            if unchanged_len > 0 {
+                edits.extend(pending_edit.take());
+                old_offset += unchanged_len;
+            }
+            visible_offset = cursor.end().visible;
+
+            let was_visible = fragment.was_visible(from, &self.undo_map);
+            let is_visible = fragment.was_visible(to, &self.undo_map);
+            if was_visible == is_visible {
+                if was_visible {
+                    edits.extend(pending_edit.take());
+                    old_offset += fragment.len;
+                }
+            } else {
+                let text = if fragment.visible {
+                    self.visible_text
+                        .chunks_in_range(cursor.start().visible..cursor.end().visible)
+                        .collect::<String>()
+                } else {
+                    self.deleted_text
+                        .chunks_in_range(cursor.start().deleted..cursor.end().deleted)
+                        .collect::<String>()
+                };
+                let (range, old_text, new_text) = pending_edit
+                    .get_or_insert_with(|| (old_offset..old_offset, String::new(), String::new()));
+                if was_visible {
+                    old_offset += fragment.len;
+                    range.end = old_offset;
+                    old_text.push_str(&text);
+                } else {
+                    new_text.push_str(&text);
+                }
+            }
+
+            cursor.next();
+        }
+        edits.extend(pending_edit);
+        edits
+    }
+
     pub fn remote_id(&self) -> BufferId {
         self.remote_id
     }
@@ -7,7 +7,9 @@ use ::ignore::gitignore::{Gitignore, GitignoreBuilder};
 use anyhow::{Context as _, Result, anyhow};
 use clock::ReplicaId;
 use collections::{HashMap, HashSet, VecDeque};
-use fs::{Fs, MTime, PathEvent, RemoveOptions, Watcher, copy_recursive, read_dir_items};
+use fs::{
+    Fs, LoadedFileWindow, MTime, PathEvent, RemoveOptions, Watcher, copy_recursive, read_dir_items,
+};
 use futures::{
     FutureExt as _, Stream, StreamExt,
     channel::{
@@ -107,6 +109,12 @@ pub struct LoadedBinaryFile {
     pub content: Vec<u8>,
 }
 
+/// A file opened in large-file mode, of which only the first window is read.
+pub struct LoadedLargeFile {
+    pub file: Arc<File>,
+    pub window: LoadedFileWindow,
+}
+
 impl fmt::Debug for LoadedBinaryFile {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("LoadedBinaryFile")
@@ -711,6 +719,21 @@ impl Worktree {
         }
     }
 
+    /// Loads the file in large-file mode if its size reaches the `large_file_threshold_mb`
+    /// setting, returning `None` otherwise.
+    pub fn load_large_file(
+        &self,
+        path: &RelPath,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<Option<LoadedLargeFile>>> {
+        match self {
+            Worktree::Local(this) => this.load_large_file(path, cx),
+            Worktree::Remote(_) => {
+                Task::ready(Err(anyhow!("remote worktrees can't yet load files")))
+            }
+        }
+    }
+
     pub fn load_binary_file(
         &self,
         path: &RelPath,
@@ -1365,6 +1388,53 @@ impl LocalWorktree {
         })
     }
 
+    fn load_large_file(
+        &self,
+        path: &RelPath,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<Option<LoadedLargeFile>>> {
+        let path: Arc<RelPath> = Arc::from(path);
+        let abs_path = self.absolutize(&path);
+        let fs = self.fs.clone();
+        let large_file_threshold = self.settings.large_file_threshold;
+        let is_private = self.is_path_private(&path);
+
+        cx.spawn(async move |worktree, cx| {
+            let Some(metadata) = fs.metadata(&abs_path).await? else {
+                return Ok(None);
+            };
+            if metadata.is_dir || metadata.len < large_file_threshold {
+                return Ok(None);
+            }
+
+            let entry = worktree
+                .update(cx, |worktree, cx| {
+                    worktree
+                        .as_local()
+                        .map(|worktree| worktree.refresh_entry(path.clone(), None, cx))
+                })?
+                .context("worktree is not local")?;
+            let window = fs.load_window(&abs_path, 0, large_file_threshold).await?;
+
+            let worktree = worktree.upgrade().context("worktree was dropped")?;
+            let file = match entry.await? {
+                Some(entry) => File::for_entry(entry, worktree),
+                None => Arc::new(File {
+                    entry_id: None,
+                    worktree,
+                    path,
+                    disk_state: DiskState::Present {
+                        mtime: metadata.mtime,
+                    },
+                    is_local: true,
+                    is_private,
+                }),
+            };
+
+            Ok(Some(LoadedLargeFile { file, window }))
+        })
+    }
+
     /// Find the lowest path in the worktree's datastructures that is an ancestor
     fn lowest_ancestor(&self, path: &RelPath) -> Arc<RelPath> {
         let mut lowest_ancestor = None;
@@ -3162,6 +3232,13 @@ impl language::LocalFile for File {
         let fs = worktree.fs.clone();
         cx.background_spawn(async move { fs.load_bytes(&abs_path).await })
     }
+
+    fn load_window(&self, start: u64, max_len: u64, cx: &App) -> Task<Result<LoadedFileWindow>> {
+        let worktree = self.worktree.read(cx).as_local().unwrap();
+        let abs_path = worktree.absolutize(&self.path);
+        let fs = worktree.fs.clone();
+        cx.background_spawn(async move { fs.load_window(&abs_path, start, max_len).await })
+    }
 }
 
 impl File {
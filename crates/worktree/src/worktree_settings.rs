@@ -20,6 +20,8 @@ pub struct WorktreeSettings {
     pub parent_dir_scan_inclusions: PathMatcher,
     pub private_files: PathMatcher,
     pub hidden_files: PathMatcher,
+    /// The size, in bytes, from which files are opened in large-file mode.
+    pub large_file_threshold: u64,
 }
 
 impl WorktreeSettings {
@@ -84,6 +86,10 @@ impl Settings for WorktreeSettings {
             hidden_files: path_matchers(hidden_files, "hidden_files")
                 .log_err()
                 .unwrap_or_default(),
+            large_file_threshold: worktree
+                .large_file_threshold_mb
+                .unwrap()
+                .saturating_mul(1024 * 1024),
         }
     }
 }
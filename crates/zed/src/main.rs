@@ -603,6 +603,8 @@ pub fn main() {
 
         editor::init(cx);
         image_viewer::init(cx);
+        hex_editor::init(cx);
+        csv_view::init(cx);
         repl::notebook::init(cx);
         diagnostics::init(cx);
 
@@ -617,8 +619,11 @@ pub fn main() {
         project_symbols::init(cx);
         project_panel::init(cx);
         outline_panel::init(cx);
+        bookmarks::init(cx);
+        local_history::init(app_state.fs.clone(), cx);
         tasks_ui::init(cx);
         snippets_ui::init(cx);
+        spell_check::init(cx);
         channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
         search::init(cx);
         vim::init(cx);
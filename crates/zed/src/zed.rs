@@ -15,6 +15,7 @@ use anyhow::Context as _;
 pub use app_menus::*;
 use assets::Assets;
 use audio::{AudioSettings, REPLAY_DURATION};
+use bookmarks::BookmarksPanel;
 use breadcrumbs::Breadcrumbs;
 use client::zed_urls;
 use collections::VecDeque;
@@ -45,7 +46,7 @@ use migrator::migrate_keymap;
 use onboarding::DOCS_URL;
 use onboarding::multibuffer_hint::MultibufferHint;
 pub use open_listener::*;
-use outline_panel::OutlinePanel;
+use outline_panel::{DocumentTreePanel, OutlinePanel};
 use paths::{
     local_debug_file_relative_path, local_settings_file_relative_path,
     local_tasks_file_relative_path,
@@ -663,6 +664,8 @@ fn initialize_panels(
     cx.spawn_in(window, async move |workspace_handle, cx| {
         let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
         let outline_panel = OutlinePanel::load(workspace_handle.clone(), cx.clone());
+        let bookmarks_panel = BookmarksPanel::load(workspace_handle.clone(), cx.clone());
+        let document_tree_panel = DocumentTreePanel::load(workspace_handle.clone(), cx.clone());
         let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
         let git_panel = GitPanel::load(workspace_handle.clone(), cx.clone());
         let channels_panel =
@@ -691,6 +694,8 @@ fn initialize_panels(
         futures::join!(
             add_panel_when_ready(project_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(outline_panel, workspace_handle.clone(), cx.clone()),
+            add_panel_when_ready(bookmarks_panel, workspace_handle.clone(), cx.clone()),
+            add_panel_when_ready(document_tree_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(terminal_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(git_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(channels_panel, workspace_handle.clone(), cx.clone()),
@@ -4738,6 +4743,8 @@ mod tests {
                 "auto_update",
                 "branch_picker",
                 "bedrock",
+                "bookmarks",
+                "bookmarks_panel",
                 "branches",
                 "buffer_search",
                 "channel_modal",
@@ -4749,10 +4756,12 @@ mod tests {
                 "console",
                 "context_server",
                 "copilot",
+                "csv_view",
                 "debug_panel",
                 "debugger",
                 "dev",
                 "diagnostics",
+                "document_tree_panel",
                 "edit_prediction",
                 "editor",
                 "feedback",
@@ -4761,12 +4770,15 @@ mod tests {
                 "git_onboarding",
                 "git_panel",
                 "go_to_line",
+                "hex_editor",
                 "icon_theme_selector",
+                "image_viewer",
                 "journal",
                 "keymap_editor",
                 "keystroke_input",
                 "language_selector",
                 "line_ending_selector",
+                "local_history",
                 "lsp_tool",
                 "markdown",
                 "menu",
@@ -4936,6 +4948,7 @@ mod tests {
             git_ui::init(cx);
             project_panel::init(cx);
             outline_panel::init(cx);
+            bookmarks::init(cx);
             terminal_view::init(cx);
             copilot::copilot_chat::init(
                 app_state.fs.clone(),
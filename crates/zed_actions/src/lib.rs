@@ -203,6 +203,30 @@ pub mod workspace {
     );
 }
 
+pub mod hex_editor {
+    use gpui::actions;
+
+    actions!(
+        hex_editor,
+        [
+            /// Reopens the active file in the hex editor.
+            OpenInHexEditor
+        ]
+    );
+}
+
+pub mod csv_view {
+    use gpui::actions;
+
+    actions!(
+        csv_view,
+        [
+            /// Reopens the active file as a table.
+            OpenAsTable
+        ]
+    );
+}
+
 pub mod git {
     use gpui::actions;
 